@@ -314,6 +314,12 @@ pub type SignedExtra = (
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>
 );
+/// The tip paid through the `ChargeTransactionPayment` extension of `extra`.
+///
+/// When you reorder [`SignedExtra`], you **MUST** update the tuple index here!
+fn signed_extra_tip(extra: &SignedExtra) -> Balance {
+	extra.6.tip()
+}
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
@@ -442,6 +448,51 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl frame_system_rpc_runtime_api::DryRunApi<
+		Block,
+		frame_system::EventRecord<Event, Hash>,
+		pallet_transaction_payment::FeeDetails<Balance>,
+	> for Runtime {
+		fn dry_run(
+			extrinsic: <Block as BlockT>::Extrinsic,
+			overrides: frame_system_rpc_runtime_api::StorageOverrides,
+		) -> frame_system_rpc_runtime_api::DryRunEffects<
+			frame_system::EventRecord<Event, Hash>,
+			pallet_transaction_payment::FeeDetails<Balance>,
+		> {
+			use codec::Encode;
+			use frame_support::{storage::unhashed, weights::GetDispatchInfo};
+
+			for (key, value) in overrides {
+				match value {
+					Some(value) => unhashed::put_raw(&key, &value),
+					None => unhashed::kill(&key),
+				}
+			}
+			let len = extrinsic.encoded_size() as u32;
+			let dispatch_info = extrinsic.get_dispatch_info();
+			let tip = extrinsic.signature.as_ref()
+				.map_or(0, |(_, _, extra)| signed_extra_tip(extra));
+			let first_event = System::event_count() as usize;
+			let result = Executive::apply_extrinsic(extrinsic);
+			let events = System::events().into_iter().skip(first_event).collect::<Vec<_>>();
+			let actual_info = events.iter()
+				.find_map(|record| match record.event {
+					Event::frame_system(frame_system::Event::ExtrinsicSuccess(ref info)) |
+					Event::frame_system(frame_system::Event::ExtrinsicFailed(_, ref info)) =>
+						Some(info.clone()),
+					_ => None,
+				})
+				.unwrap_or(dispatch_info);
+			frame_system_rpc_runtime_api::DryRunEffects {
+				result,
+				events,
+				fee_details: TransactionPayment::compute_fee_details(len, &actual_info, tip),
+				actual_weight: actual_info.weight,
+			}
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,
@@ -73,12 +73,24 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export a binary snapshot of the state of a finalized block.
+	ExportSnapshot(sc_cli::ExportSnapshotCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Start an empty database from a state snapshot.
+	ImportSnapshot(sc_cli::ImportSnapshotCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
+
+	/// Switch the state pruning mode of the database.
+	MigratePruning(sc_cli::MigratePruningCmd),
+
+	/// Compile a runtime ahead of an upgrade.
+	PrecompileWasm(sc_cli::PrecompileWasmCmd),
 }
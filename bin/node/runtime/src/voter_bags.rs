@@ -0,0 +1,233 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Voter bag thresholds for the node runtime.
+//!
+//! The thresholds form a geometric series of 200 bags, starting at the existential weight and
+//! ending at `u64::MAX`.
+
+/// Existential weight for this runtime.
+#[allow(unused)]
+pub const EXISTENTIAL_WEIGHT: u64 = 100_000_000_000_000;
+
+/// Constant ratio between bags for this runtime.
+#[allow(unused)]
+pub const CONSTANT_RATIO: f64 = 1.0628253590743408;
+
+/// Upper thresholds delimiting the bag list.
+pub const THRESHOLDS: [u64; 200] = [
+	100_000_000_000_000,
+	106_282_535_907_434,
+	112_959_774_389_150,
+	120_056_512_776_105,
+	127_599_106_300_477,
+	135_615_565_971_369,
+	144_135_662_599_590,
+	153_191_037_357_827,
+	162_815_319_286_803,
+	173_044_250_183_800,
+	183_915_817_337_347,
+	195_470_394_601_017,
+	207_750_892_330_229,
+	220_802_916_738_890,
+	234_674_939_267_673,
+	249_418_476_592_914,
+	265_088_281_944_639,
+	281_742_548_444_211,
+	299_443_125_216_738,
+	318_255_747_080_822,
+	338_250_278_668_647,
+	359_500_973_883_001,
+	382_086_751_654_776,
+	406_091_489_025_036,
+	431_604_332_640_068,
+	458_720_029_816_222,
+	487_539_280_404_018,
+	518_169_110_758_246,
+	550_723_271_202_865,
+	585_322_658_466_781,
+	622_095_764_659_304,
+	661_179_154_452_651,
+	702_717_972_243_608,
+	746_866_481_177_805,
+	793_788_636_038_390,
+	843_658_692_126_633,
+	896_661_852_395_678,
+	952_994_955_240_700,
+	1_012_867_205_499_732,
+	1_076_500_951_379_877,
+	1_144_132_510_194_187,
+	1_216_013_045_975_764,
+	1_292_409_502_228_274,
+	1_373_605_593_276_856,
+	1_459_902_857_900_998,
+	1_551_621_779_162_284,
+	1_649_102_974_585_722,
+	1_752_708_461_114_634,
+	1_862_822_999_536_796,
+	1_979_855_523_374_636,
+	2_104_240_657_545_964,
+	2_236_440_332_435_116,
+	2_376_945_499_368_690,
+	2_526_277_953_866_666,
+	2_684_992_273_439_930,
+	2_853_677_877_130_624,
+	3_032_961_214_443_858,
+	3_223_508_091_799_842,
+	3_426_026_145_146_210,
+	3_641_267_467_913_100,
+	3_870_031_404_070_456,
+	4_113_167_516_660_158,
+	4_371_578_742_827_247,
+	4_646_224_747_067_124,
+	4_938_125_485_141_704,
+	5_248_364_991_899_885,
+	5_578_095_407_069_195,
+	5_928_541_253_969_249,
+	6_301_003_987_036_910,
+	6_696_866_825_051_357,
+	7_117_599_888_008_249,
+	7_564_765_656_719_855,
+	8_040_024_775_416_522,
+	8_545_142_218_898_662,
+	9_081_993_847_142_280,
+	9_652_573_371_699_948,
+	10_258_999_759_768_418,
+	10_903_525_103_419_446,
+	11_588_542_983_217_862,
+	12_316_597_357_286_958,
+	13_090_392_008_832_588,
+	13_912_800_587_211_378,
+	14_786_877_279_832_632,
+	15_715_868_154_526_330,
+	16_703_223_214_499_444,
+	17_752_609_210_649_238,
+	18_867_923_258_814_728,
+	20_053_307_312_536_872,
+	21_313_163_545_075_104,
+	22_652_170_697_804_600,
+	24_075_301_455_707_436,
+	25_587_840_914_485_256,
+	27_195_406_207_874_904,
+	28_903_967_368_057_204,
+	30_719_869_496_628_428,
+	32_649_856_328_471_000,
+	34_701_095_276_032_832,
+	36_881_204_047_022_504,
+	39_198_278_934_370_728,
+	41_660_924_883_518_736,
+	44_278_287_448_694_944,
+	47_060_086_756_856_080,
+	50_016_653_605_425_192,
+	53_158_967_827_882_952,
+	56_498_699_069_691_032,
+	60_048_250_125_977_496,
+	63_820_803_001_927_864,
+	67_830_367_866_936_752,
+	72_091_835_084_321_680,
+	76_621_030_509_822_352,
+	81_434_774_264_247_968,
+	86_550_943_198_537_232,
+	91_988_537_283_208_208,
+	97_767_750_168_749_152,
+	103_910_044_178_991_264,
+	110_438_230_015_967_008,
+	117_376_551_472_254_784,
+	124_750_775_465_407_040,
+	132_588_287_728_823_712,
+	140_918_194_514_439_088,
+	149_771_430_684_916_544,
+	159_180_874_596_774_176,
+	169_181_470_201_084_128,
+	179_810_356_815_192_128,
+	191_107_007_047_391_936,
+	203_113_373_386_766_912,
+	215_874_044_002_591_200,
+	229_436_408_331_884_032,
+	243_850_833_070_061_728,
+	259_170_849_218_265_504,
+	275_453_350_882_004_896,
+	292_758_806_559_397_248,
+	311_151_483_703_666_880,
+	330_699_687_393_863_680,
+	351_476_014_000_155_456,
+	373_557_620_785_733_248,
+	397_026_512_446_553_408,
+	421_969_845_653_041_408,
+	448_480_252_724_737_920,
+	476_656_185_639_920_704,
+	506_602_281_657_754_368,
+	538_429_751_910_783_168,
+	572_256_794_410_886_400,
+	608_209_033_002_481_536,
+	646_419_983_893_120_000,
+	687_031_551_494_034_944,
+	730_194_555_412_049_152,
+	776_069_290_549_939_840,
+	824_826_122_395_308_800,
+	876_646_119_708_690_304,
+	931_721_726_960_516_352,
+	990_257_479_014_175_744,
+	1_052_470_760_709_292_928,
+	1_118_592_614_166_098_944,
+	1_188_868_596_808_989_696,
+	1_263_559_693_295_722_240,
+	1_342_943_284_738_889_984,
+	1_427_314_178_819_085_568,
+	1_516_985_704_615_292_672,
+	1_612_290_876_218_390_272,
+	1_713_583_629_449_094_144,
+	1_821_240_136_273_145_600,
+	1_935_660_201_795_107_328,
+	2_057_268_749_018_796_032,
+	2_186_517_396_888_321_792,
+	2_323_886_137_470_123_520,
+	2_469_885_118_504_566_784,
+	2_625_056_537_946_987_008,
+	2_789_976_657_533_952_512,
+	2_965_257_942_852_552_192,
+	3_151_551_337_860_304_896,
+	3_349_548_682_302_597_632,
+	3_559_985_281_005_243_392,
+	3_783_642_634_583_766_016,
+	4_021_351_341_710_476_288,
+	4_273_994_183_717_519_360,
+	4_542_509_402_991_216_640,
+	4_827_894_187_332_709_376,
+	5_131_208_373_224_809_472,
+	5_453_578_381_757_922_304,
+	5_796_201_401_831_926_784,
+	6_160_349_836_169_214_976,
+	6_547_376_026_650_102_784,
+	6_958_717_276_519_126_016,
+	7_395_901_188_113_259_520,
+	7_860_551_335_934_819_328,
+	8_354_393_296_137_213_952,
+	8_879_261_054_815_300_608,
+	9_437_103_818_898_882_560,
+	10_029_993_254_943_037_440,
+	10_660_131_182_698_049_536,
+	11_329_857_752_030_631_936,
+	12_041_660_133_563_160_576,
+	12_798_181_755_305_441_280,
+	13_602_232_119_581_184_000,
+	14_456_797_236_706_404_352,
+	15_365_050_714_167_422_976,
+	16_330_365_542_480_447_488,
+	17_356_326_621_502_023_680,
+	18_446_744_073_709_551_615,
+];
@@ -70,4 +70,39 @@ pub trait ProofProvider<Block: BlockT> {
 		storage_key: Option<&PrefixedStorageKey>,
 		key: &StorageKey,
 	) -> sp_blockchain::Result<ChangesProof<Block::Header>>;
+
+	/// Given a `BlockId` iterate over all storage values starting at `start_key` exclusively,
+	/// building proofs until size limit is reached. Returns the combined proof and the number
+	/// of collected keys.
+	///
+	/// When `child_info` is given, the given child trie is iterated instead of the top trie.
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)>;
+
+	/// Given a `BlockId` iterate over all storage values starting at `start_key` exclusively,
+	/// until size limit is reached. Returns the collected key-value pairs and a flag indicating
+	/// if the end of the storage was reached.
+	fn storage_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)>;
+
+	/// Verify a range proof generated by `read_proof_collection` against the given state root.
+	/// Returns the proven key-value pairs and a flag indicating if the end of the storage was
+	/// reached.
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)>;
 }
@@ -0,0 +1,67 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{DatabaseParams, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use sc_service::{chain_ops::migrate_state_pruning, Configuration};
+use sp_runtime::traits::Block as BlockT;
+use structopt::StructOpt;
+
+/// The `migrate-pruning` command used to switch the state pruning mode of an existing database.
+///
+/// Supported are switching from `archive` to a number of blocks to keep and changing that
+/// number. The state that is no longer kept is removed in the background once the node is
+/// started with the new `--pruning` value, which continues after a restart.
+#[derive(Debug, StructOpt)]
+pub struct MigratePruningCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl MigratePruningCmd {
+	/// Run the migrate-pruning command
+	pub fn run<B: BlockT>(&self, config: Configuration) -> error::Result<()> {
+		migrate_state_pruning::<B>(&config)?;
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for MigratePruningCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -18,8 +18,12 @@
 mod build_spec_cmd;
 mod check_block_cmd;
 mod export_blocks_cmd;
+mod export_snapshot_cmd;
 mod export_state_cmd;
 mod import_blocks_cmd;
+mod import_snapshot_cmd;
+mod migrate_pruning_cmd;
+mod precompile_wasm_cmd;
 mod purge_chain_cmd;
 mod sign;
 mod verify;
@@ -38,8 +42,12 @@ pub use self::{
 	build_spec_cmd::BuildSpecCmd,
 	check_block_cmd::CheckBlockCmd,
 	export_blocks_cmd::ExportBlocksCmd,
+	export_snapshot_cmd::ExportSnapshotCmd,
 	export_state_cmd::ExportStateCmd,
 	import_blocks_cmd::ImportBlocksCmd,
+	import_snapshot_cmd::ImportSnapshotCmd,
+	migrate_pruning_cmd::MigratePruningCmd,
+	precompile_wasm_cmd::PrecompileWasmCmd,
 	purge_chain_cmd::PurgeChainCmd,
 	sign::SignCmd,
 	generate::GenerateCmd,
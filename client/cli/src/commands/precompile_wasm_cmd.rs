@@ -0,0 +1,56 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::SharedParams;
+use crate::CliConfiguration;
+use sc_service::{chain_ops::precompile_wasm, Configuration};
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// The `precompile-wasm` command used to compile a runtime ahead of an upgrade.
+///
+/// The compiled runtime is stored in the base path of the node, which loads it instead of
+/// compiling the runtime again when it is enacted.
+#[derive(Debug, StructOpt)]
+pub struct PrecompileWasmCmd {
+	/// Path to the runtime wasm blob, which may be compressed.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl PrecompileWasmCmd {
+	/// Run the precompile-wasm command
+	pub fn run(&self, config: Configuration) -> error::Result<()> {
+		let code = fs::read(&self.input)?;
+		precompile_wasm(&config, &code)?;
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for PrecompileWasmCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
@@ -30,7 +30,8 @@ use sc_client_api::execution_extensions::ExecutionStrategies;
 use sc_service::config::{
 	BasePath, Configuration, DatabaseConfig, ExtTransport, KeystoreConfig, NetworkConfiguration,
 	NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcMethods,
-	TaskExecutor, TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
+	TaskExecutor, TelemetryEndpoints, TransactionPoolOptions, TransactionPoolPersistence,
+	WasmExecutionMethod,
 };
 use sc_service::{ChainSpec, TracingReceiver, KeepBlocks, TransactionStorageMode};
 use sc_tracing::logging::LoggerBuilder;
@@ -150,6 +151,16 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(Default::default())
 	}
 
+	/// Get the persistence options of the transaction pool.
+	///
+	/// By default persistence is disabled.
+	fn transaction_pool_persistence(
+		&self,
+		_config_dir: &PathBuf,
+	) -> Result<Option<TransactionPoolPersistence>> {
+		Ok(None)
+	}
+
 	/// Get the network configuration
 	///
 	/// By default this is retrieved from `NetworkParams` if it is available otherwise it creates
@@ -489,6 +500,8 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		let is_validator = role.is_authority();
 		let (keystore_remote, keystore) = self.keystore_config(&config_dir)?;
 		let telemetry_endpoints = self.telemetry_endpoints(&chain_spec)?;
+		let mut transaction_pool = self.transaction_pool()?;
+		transaction_pool.persistence = self.transaction_pool_persistence(&config_dir)?;
 
 		let unsafe_pruning = self
 			.import_params()
@@ -499,7 +512,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			impl_name: C::impl_name(),
 			impl_version: C::impl_version(),
 			task_executor,
-			transaction_pool: self.transaction_pool()?,
+			transaction_pool,
 			network: self.network_config(
 				&chain_spec,
 				is_dev,
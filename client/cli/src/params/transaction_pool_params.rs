@@ -16,9 +16,13 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use sc_service::config::TransactionPoolOptions;
+use sc_service::config::{TransactionPoolOptions, TransactionPoolPersistence};
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 
+/// Name of the file in the chain's config directory that pending transactions are persisted to.
+const PERSISTENCE_FILE: &str = "txpool";
+
 /// Parameters used to create the pool configuration.
 #[derive(Debug, StructOpt)]
 pub struct TransactionPoolParams {
@@ -29,6 +33,30 @@ pub struct TransactionPoolParams {
 	/// Maximum number of kilobytes of all transactions stored in the pool.
 	#[structopt(long = "pool-kbytes", value_name = "COUNT", default_value = "20480")]
 	pub pool_kbytes: usize,
+
+	/// Maximum number of signed transactions of a single sender in the pool.
+	///
+	/// The sender is the account that signed transactions encode at the start of the tags they
+	/// provide, see `--pool-sender-key-len`. Unlimited if not given.
+	#[structopt(long = "pool-max-per-sender", value_name = "COUNT")]
+	pub pool_max_per_sender: Option<usize>,
+
+	/// Size in bytes of the account id of the runtime.
+	///
+	/// Used to tell the sender of a transaction for `--pool-max-per-sender`.
+	#[structopt(long = "pool-sender-key-len", value_name = "BYTES", default_value = "32")]
+	pub pool_sender_key_len: usize,
+
+	/// Persist pending transactions across restarts.
+	///
+	/// The transactions are written to the config directory of the chain periodically and when
+	/// the node shuts down. On startup they are revalidated and the valid ones are imported.
+	#[structopt(long = "pool-persist")]
+	pub pool_persist: bool,
+
+	/// Number of seconds between two writes of the pending transactions.
+	#[structopt(long = "pool-persist-interval", value_name = "SECONDS", default_value = "60")]
+	pub pool_persist_interval: u64,
 }
 
 impl TransactionPoolParams {
@@ -45,6 +73,21 @@ impl TransactionPoolParams {
 		opts.future.count = self.pool_limit / factor;
 		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
 
+		opts.max_per_sender = self.pool_max_per_sender;
+		opts.sender_key_len = self.pool_sender_key_len;
+
 		opts
 	}
+
+	/// Get the persistence options of the pool if persistence is enabled.
+	pub fn persistence(&self, config_dir: &PathBuf) -> Option<TransactionPoolPersistence> {
+		if !self.pool_persist {
+			return None;
+		}
+
+		Some(TransactionPoolPersistence {
+			path: config_dir.join(PERSISTENCE_FILE),
+			interval: Duration::from_secs(self.pool_persist_interval),
+		})
+	}
 }
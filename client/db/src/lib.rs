@@ -37,6 +37,7 @@ pub mod bench;
 mod children;
 mod cache;
 mod changes_tries_storage;
+mod pruning_migration;
 mod storage_cache;
 #[cfg(any(feature = "with-kvdb-rocksdb", test))]
 mod upgrade;
@@ -90,6 +91,7 @@ use crate::stats::StateUsageStats;
 // Re-export the Database trait so that one can pass an implementation of it.
 pub use sp_database::Database;
 pub use sc_state_db::PruningMode;
+pub use pruning_migration::migrate_state_pruning;
 
 #[cfg(any(feature = "with-kvdb-rocksdb", test))]
 pub use bench::BenchmarkingState;
@@ -369,6 +371,8 @@ pub(crate) mod columns {
 	pub const CACHE: u32 = 10;
 	/// Transactions
 	pub const TRANSACTION: u32 = 11;
+	/// Marks the state nodes in use while the state left from a previous pruning mode is removed.
+	pub const STATE_MARKS: u32 = 12;
 }
 
 struct PendingBlock<Block: BlockT> {
@@ -694,6 +698,7 @@ pub struct BlockImportOperation<Block: BlockT> {
 	finalized_blocks: Vec<(BlockId<Block>, Option<Justification>)>,
 	set_head: Option<BlockId<Block>>,
 	commit_state: bool,
+	reset_storage: bool,
 	index_ops: Vec<IndexOperation>,
 }
 
@@ -792,6 +797,7 @@ impl<Block: BlockT> sc_client_api::backend::BlockImportOperation<Block> for Bloc
 		self.db_updates = transaction;
 		self.changes_trie_config_update = Some(changes_trie_config);
 		self.commit_state = true;
+		self.reset_storage = true;
 		Ok(root)
 	}
 
@@ -1190,9 +1196,15 @@ impl<Block: BlockT> Backend<Block> {
 					implies existence of blocks with all numbers before it; qed")
 			};
 
+			if !self.storage.state_db.have_block(&hash) {
+				trace!(target: "db", "Skipping canonicalization of block #{} ({:?}) without state", new_canonical, hash);
+				return Ok(())
+			}
+
 			trace!(target: "db", "Canonicalize block #{} ({:?})", new_canonical, hash);
 			let commit = self.storage.state_db.canonicalize_block(&hash)
 				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+			self.mark_inserted_state(transaction, &commit);
 			apply_state_commit(transaction, commit);
 		}
 		Ok(())
@@ -1236,7 +1248,27 @@ impl<Block: BlockT> Backend<Block> {
 			// blocks are keyed by number + hash.
 			let lookup_key = utils::number_and_hash_to_lookup_key(number, hash)?;
 
-			let (enacted, retracted) = if pending_block.leaf_state.is_best() {
+			// The whole state of a block was imported without its ancestors, e.g. from a
+			// snapshot. The block becomes the new base of the chain.
+			let is_detached = operation.reset_storage && !number.is_zero() &&
+				self.blockchain.header_metadata(parent_hash).is_err();
+			if is_detached && !pending_block.leaf_state.is_final() {
+				return Err(sp_blockchain::Error::Backend(
+					"Blocks without known ancestors must be imported as finalized".into(),
+				));
+			}
+
+			let (enacted, retracted) = if is_detached {
+				// There is no route from the current best block.
+				transaction.set_from_vec(columns::META, meta_keys::BEST_BLOCK, lookup_key.clone());
+				utils::insert_number_to_key_mapping(
+					&mut transaction,
+					columns::KEY_LOOKUP,
+					number,
+					hash,
+				)?;
+				(Default::default(), Default::default())
+			} else if pending_block.leaf_state.is_best() {
 				self.set_head_with_transaction(&mut transaction, parent_hash, (number, hash))?
 			} else {
 				(Default::default(), Default::default())
@@ -1324,13 +1356,34 @@ impl<Block: BlockT> Backend<Block> {
 				}
 				self.state_usage.tally_writes(ops, bytes);
 				let number_u64 = number.saturated_into::<u64>();
-				let commit = self.storage.state_db.insert_block(
-					&hash,
-					number_u64,
-					&pending_block.header.parent_hash(),
-					changeset,
-				).map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
-				apply_state_commit(&mut transaction, commit);
+				if operation.reset_storage && !number.is_zero() {
+					// The whole state of an existing finalized block was imported, e.g. by
+					// state sync. The block is canonical already.
+					let commit = self.storage.state_db.import_canonical(
+						&hash,
+						number_u64,
+						changeset,
+					).map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+					self.mark_inserted_state(&mut transaction, &commit);
+					apply_state_commit(&mut transaction, commit);
+				} else {
+					let commit = self.storage.state_db.insert_block(
+						&hash,
+						number_u64,
+						&pending_block.header.parent_hash(),
+						changeset,
+					).map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+					self.mark_inserted_state(&mut transaction, &commit);
+					apply_state_commit(&mut transaction, commit);
+
+					if !number.is_zero() && number <= self.blockchain.meta.read().finalized_number {
+						// Re-importing the state of a block which was finalized without state.
+						let commit = self.storage.state_db.canonicalize_block(&hash)
+							.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+						self.mark_inserted_state(&mut transaction, &commit);
+						apply_state_commit(&mut transaction, commit);
+					}
+				}
 
 				// Check if need to finalize. Genesis is always finalized instantly.
 				let finalized = number_u64 == 0 || pending_block.leaf_state.is_final();
@@ -1362,7 +1415,9 @@ impl<Block: BlockT> Backend<Block> {
 
 			if finalized {
 				// TODO: ensure best chain contains this block.
-				self.ensure_sequential_finalization(header, Some(last_finalized_hash))?;
+				if !is_detached {
+					self.ensure_sequential_finalization(header, Some(last_finalized_hash))?;
+				}
 				self.note_finalized(
 					&mut transaction,
 					true,
@@ -1493,9 +1548,14 @@ impl<Block: BlockT> Backend<Block> {
 			let lookup_key = utils::number_and_hash_to_lookup_key(f_num, f_hash.clone())?;
 			transaction.set_from_vec(columns::META, meta_keys::FINALIZED_BLOCK, lookup_key);
 
-			let commit = self.storage.state_db.canonicalize_block(&f_hash)
-				.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
-			apply_state_commit(transaction, commit);
+			// Blocks imported without state, e.g. headers downloaded by state sync, are not
+			// known to the state database.
+			if self.storage.state_db.have_block(&f_hash) {
+				let commit = self.storage.state_db.canonicalize_block(&f_hash)
+					.map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+				self.mark_inserted_state(transaction, &commit);
+				apply_state_commit(transaction, commit);
+			}
 
 			if !f_num.is_zero() {
 				let new_changes_trie_cache_ops = self.changes_tries_storage.finalize(
@@ -1725,6 +1785,7 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 			finalized_blocks: Vec::new(),
 			set_head: None,
 			commit_state: false,
+			reset_storage: false,
 			index_ops: Default::default(),
 		})
 	}
@@ -2306,6 +2367,49 @@ pub(crate) mod tests {
 		}
 	}
 
+	#[test]
+	fn import_state_without_ancestors() {
+		let backend = Backend::<Block>::new_test(2, 0);
+		insert_header(&backend, 0, Default::default(), None, Default::default());
+
+		let storage = vec![(vec![1, 3, 5], vec![2, 4, 6])];
+		let import = |leaf_state| {
+			let mut op = backend.begin_operation().unwrap();
+			backend.begin_state_operation(&mut op, BlockId::Hash(Default::default())).unwrap();
+			let mut header = Header {
+				number: 10,
+				parent_hash: H256::from([1; 32]),
+				state_root: Default::default(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			header.state_root = op.old_state.storage_root(storage
+				.iter()
+				.map(|(x, y)| (&x[..], Some(&y[..])))
+			).0.into();
+			let hash = header.hash();
+			op.reset_storage(Storage {
+				top: storage.clone().into_iter().collect(),
+				children_default: Default::default(),
+			}).unwrap();
+			op.set_block_data(header, None, None, leaf_state).unwrap();
+			backend.commit_operation(op).map(|_| hash)
+		};
+
+		assert!(import(NewBlockState::Best).is_err());
+		let hash = import(NewBlockState::Final).unwrap();
+
+		let info = backend.blockchain().info();
+		assert_eq!((info.best_number, info.best_hash), (10, hash));
+		assert_eq!((info.finalized_number, info.finalized_hash), (10, hash));
+		let state = backend.state_at(BlockId::Number(10)).unwrap();
+		assert_eq!(state.storage(&[1, 3, 5]).unwrap(), Some(vec![2, 4, 6]));
+
+		// The chain continues from the imported block.
+		let child = insert_header(&backend, 11, hash, None, Default::default());
+		assert_eq!(backend.blockchain().info().best_hash, child);
+	}
+
 	#[test]
 	fn delete_only_when_negative_rc() {
 		sp_tracing::try_init_simple();
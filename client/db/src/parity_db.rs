@@ -44,10 +44,48 @@ pub fn open<H: Clone + AsRef<[u8]>>(path: &std::path::Path, db_type: DatabaseTyp
 		state_col.preimage = true;
 		state_col.uniform = true;
 	}
+	add_missing_columns(&config)?;
 	let db = parity_db::Db::open(&config)?;
 	Ok(std::sync::Arc::new(DbAdapter(db)))
 }
 
+/// Add the columns that were introduced after the database was created.
+///
+/// parity-db only checks the columns that are listed in its metadata file and creates the files
+/// of a column when it is first written to, so a column is added by listing it there. The
+/// `STATE_MARKS` column is the only one that was added since parity-db is supported.
+fn add_missing_columns(config: &parity_db::Options) -> parity_db::Result<()> {
+	let metadata_path = config.path.join("metadata");
+	if !metadata_path.exists() {
+		return Ok(());
+	}
+	let metadata = std::fs::read_to_string(&metadata_path)?;
+	let num_columns = metadata.lines().filter(|line| line.starts_with("col")).count();
+	if num_columns == NUM_COLUMNS as usize {
+		return Ok(());
+	}
+	if num_columns != columns::STATE_MARKS as usize {
+		return Err(parity_db::Error::InvalidConfiguration(format!(
+			"Unexpected number of columns: {}, expected {}", num_columns, NUM_COLUMNS,
+		)));
+	}
+
+	// Checks the existing columns against `config`.
+	let salt = config.load_and_validate_metadata()?
+		.ok_or_else(|| parity_db::Error::Corruption("Missing salt".into()))?;
+	let upgraded_path = config.path.join("metadata.upgrade");
+	config.write_metadata(&upgraded_path, &salt)?;
+	let upgraded = std::fs::read_to_string(&upgraded_path)?;
+	let added = upgraded.lines()
+		.filter(|line| line.starts_with(&format!("col{}=", columns::STATE_MARKS)));
+	let mut metadata = metadata.lines().chain(added).collect::<Vec<_>>().join("\n");
+	metadata.push('\n');
+	std::fs::write(&upgraded_path, metadata)?;
+	std::fs::rename(&upgraded_path, &metadata_path)?;
+	log::info!(target: "db", "Added the state marks column to the parity-db database");
+	Ok(())
+}
+
 impl<H: Clone + AsRef<[u8]>> Database<H> for DbAdapter {
 	fn commit(&self, transaction: Transaction<H>) -> Result<(), DatabaseError> {
 		handle_err(self.0.commit(transaction.0.into_iter().map(|change|
@@ -0,0 +1,401 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Switching the state pruning mode of an existing database.
+//!
+//! [`migrate_state_pruning`] updates the pruning metadata, which is quick. The state that was
+//! kept by the previous mode is removed afterwards by [`Backend::prune_migrated_state`] while
+//! the node is running, in two phases:
+//!
+//! 1. The nodes of the main and child state tries of all blocks that are kept are marked in the
+//!    `STATE_MARKS` column. The blocks are pinned while their state is visited, so it is not
+//!    pruned, but block import continues. Nodes that are written while the migration is running
+//!    are marked as part of the same commit.
+//! 2. The state tries of the older canonical blocks and of the discarded forks that branch off
+//!    them are visited from the newest to the oldest block. Their nodes that are not marked are
+//!    removed in batches. The marks are checked again while holding the import lock right before
+//!    the nodes are removed, since a node may have been written again in the meantime.
+//!
+//! The marks are kept on disk instead of in memory. They are removed when the database is opened
+//! after the migration finished.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::time::Duration;
+
+use codec::{Decode, Encode};
+use log::info;
+use sc_client_api::blockchain::Backend as _;
+use sc_state_db::{CommitSet, StateDb};
+use sp_blockchain::{HeaderBackend, HeaderMetadata, Result as ClientResult};
+use sp_core::storage::{well_known_keys, ChildInfo};
+use sp_database::Transaction;
+use sp_runtime::traits::{Block as BlockT, HashFor, SaturatedConversion, Zero};
+use sp_state_machine::backend::Backend as StateBackend;
+use sp_trie::prefixed_key;
+
+use crate::{
+	apply_state_commit, columns, Backend, BlockchainDb, DatabaseSettings, DatabaseSettingsSrc,
+	DbHash, DbState, StateMetaDb,
+	utils::{meta_keys, open_database, DatabaseType},
+};
+
+/// Number of blocks which state is removed at once.
+const BATCH_BLOCKS: u64 = 64;
+
+/// Number of marks that are written at once.
+const BATCH_MARKS: usize = 16 * 1024;
+
+/// Pause between two batches, which lets block import proceed.
+const BATCH_PAUSE: Duration = Duration::from_millis(100);
+
+/// Progress of marking the nodes in use, stored under [`meta_keys::STATE_MARKING`].
+#[derive(Encode, Decode)]
+struct Marking {
+	/// Incremented whenever the marking starts over. An interrupted attempt may have marked a
+	/// node without its children, so only the marks of the current attempt can be skipped.
+	generation: u32,
+	/// All nodes in use are marked.
+	complete: bool,
+}
+
+/// Switch the state pruning mode of the database to the one in `settings`.
+///
+/// Switching from an archive mode to a constrained mode and changing the constraints is
+/// supported. Switching from a constrained mode to an archive mode is not, since the state
+/// of pruned blocks can't be restored. The state of blocks that are no longer kept is removed
+/// by [`Backend::prune_migrated_state`] once the database is opened with the new mode.
+///
+/// parity-db databases are not supported, since the marks can't be iterated to remove them
+/// after the migration.
+pub fn migrate_state_pruning<Block: BlockT>(settings: &DatabaseSettings) -> ClientResult<()> {
+	if let DatabaseSettingsSrc::ParityDb { .. } = settings.source {
+		return Err(sp_blockchain::Error::Backend(
+			"Switching the state pruning mode of a parity-db database is not supported".into(),
+		));
+	}
+	let db = open_database::<Block>(settings, DatabaseType::Full)?;
+	let blockchain = BlockchainDb::<Block>::new(db.clone(), settings.transaction_storage.clone())?;
+	let (finalized_hash, finalized_number) = {
+		let meta = blockchain.meta.read();
+		(meta.finalized_hash, meta.finalized_number)
+	};
+
+	// Unfinalized blocks that descend from the last finalized block.
+	let mut non_canonical = HashMap::new();
+	for leaf in blockchain.leaves()? {
+		let mut route = Vec::new();
+		let mut header = blockchain.header_metadata(leaf)?;
+		while header.number > finalized_number && !non_canonical.contains_key(&header.hash) {
+			let parent = header.parent;
+			route.push(header);
+			header = blockchain.header_metadata(parent)?;
+		}
+		if header.hash == finalized_hash || non_canonical.contains_key(&header.hash) {
+			non_canonical.extend(route.into_iter().map(|header| {
+				(header.hash, (header.number.saturated_into::<u64>(), header.parent))
+			}));
+		}
+	}
+	let mut non_canonical = non_canonical.into_iter()
+		.map(|(hash, (number, parent))| (hash, number, parent))
+		.collect::<Vec<_>>();
+	non_canonical.sort_by_key(|(_, number, _)| *number);
+
+	let commit = StateDb::<Block::Hash, Vec<u8>>::migrate(
+		&settings.state_pruning,
+		!settings.source.supports_ref_counting(),
+		&StateMetaDb(&*db),
+		(finalized_hash, finalized_number.saturated_into::<u64>()),
+		&non_canonical,
+	).map_err(|e: sc_state_db::Error<io::Error>| sp_blockchain::Error::from_state_db(e))?;
+
+	let mut transaction = Transaction::new();
+	apply_state_commit(&mut transaction, commit);
+	db.commit(transaction)?;
+	Ok(())
+}
+
+impl<Block: BlockT> Backend<Block> {
+	/// Remove the state that was kept by the previous pruning mode of the database.
+	///
+	/// Does nothing unless the pruning mode was switched with [`migrate_state_pruning`].
+	/// This can be interrupted at any time and continues where it stopped when called again.
+	/// Blocks can be imported in the meantime.
+	pub fn prune_migrated_state(&self) -> ClientResult<()> {
+		if self.storage.state_db.migration().is_none() {
+			return Ok(());
+		}
+
+		let marking = self.storage.db.get(columns::META, meta_keys::STATE_MARKING)
+			.map(|marking| Marking::decode(&mut marking.as_slice()))
+			.transpose()
+			.map_err(|e| sp_blockchain::Error::Backend(format!("Invalid state marking: {}", e)))?;
+		match marking {
+			Some(Marking { complete: true, .. }) => (),
+			marking => {
+				let generation = marking.map_or(0, |marking| marking.generation) + 1;
+				info!(target: "db", "Marking the state in use before removing the old state");
+				self.write_marking(&Marking { generation, complete: false })?;
+				self.mark_live_state(generation)?;
+				self.write_marking(&Marking { generation, complete: true })?;
+			},
+		}
+
+		while let Some(next) = self.prune_migrated_batch()? {
+			info!(target: "db", "Removing state left from the previous pruning mode, at #{}", next);
+			std::thread::sleep(BATCH_PAUSE);
+		}
+		Ok(())
+	}
+
+	/// Mark the nodes inserted by `commit` as being in use, if the state left from a previous
+	/// pruning mode is being removed.
+	pub(crate) fn mark_inserted_state(
+		&self,
+		transaction: &mut Transaction<DbHash>,
+		commit: &CommitSet<Vec<u8>>,
+	) {
+		if self.storage.state_db.migration().is_some() {
+			for (key, _) in &commit.data.inserted {
+				transaction.set(columns::STATE_MARKS, key, &[]);
+			}
+		}
+	}
+
+	fn write_marking(&self, marking: &Marking) -> ClientResult<()> {
+		let mut transaction = Transaction::new();
+		transaction.set_from_vec(columns::META, meta_keys::STATE_MARKING, marking.encode());
+		self.storage.db.commit(transaction)?;
+		Ok(())
+	}
+
+	/// Mark the nodes of the state of all blocks that are kept.
+	fn mark_live_state(&self, generation: u32) -> ClientResult<()> {
+		let blocks = self.pin_live_blocks()?;
+		let result = blocks.iter().try_for_each(|(_, root)| self.mark_state(root, generation));
+		for (hash, _) in &blocks {
+			self.storage.state_db.unpin(hash);
+		}
+		result
+	}
+
+	/// Pin all blocks which state is kept and return their hashes and state roots.
+	fn pin_live_blocks(&self) -> ClientResult<Vec<(Block::Hash, Block::Hash)>> {
+		let _import_lock = self.import_lock.write();
+		let mut blocks = Vec::new();
+		let mut visited = HashSet::new();
+		let result = self.blockchain.leaves().and_then(|leaves| {
+			for leaf in leaves {
+				let mut hash = leaf;
+				while visited.insert(hash) {
+					let header = self.blockchain.header_metadata(hash)?;
+					// Blocks which state is pruned can't be pinned.
+					if self.storage.state_db.pin(&hash).is_err() {
+						break;
+					}
+					blocks.push((hash, header.state_root));
+					if header.number.is_zero() {
+						break;
+					}
+					hash = header.parent;
+				}
+			}
+			Ok(())
+		});
+		if let Err(e) = result {
+			for (hash, _) in &blocks {
+				self.storage.state_db.unpin(hash);
+			}
+			return Err(e);
+		}
+		Ok(blocks)
+	}
+
+	/// Mark the nodes of the main and child state tries with the given root.
+	fn mark_state(&self, root: &Block::Hash, generation: u32) -> ClientResult<()> {
+		let mark = generation.encode();
+		let mut pending = HashSet::new();
+		let mut result = Ok(());
+		let tries = std::iter::once((Vec::new(), *root)).chain(self.child_tries(root));
+		for (keyspace, root) in tries {
+			self.visit_state_nodes(&root, &keyspace, |key| {
+				if result.is_err() || pending.contains(key) {
+					return false;
+				}
+				// The children of nodes marked by this attempt are marked already or pending.
+				if self.storage.db.get(columns::STATE_MARKS, key).map_or(false, |m| m == mark) {
+					return false;
+				}
+				pending.insert(key.to_vec());
+				if pending.len() >= BATCH_MARKS {
+					result = self.write_marks(&mut pending, &mark);
+				}
+				true
+			})?;
+			if result.is_err() {
+				return result;
+			}
+		}
+		self.write_marks(&mut pending, &mark)
+	}
+
+	fn write_marks(&self, pending: &mut HashSet<Vec<u8>>, mark: &[u8]) -> ClientResult<()> {
+		let mut transaction = Transaction::new();
+		for key in pending.drain() {
+			transaction.set(columns::STATE_MARKS, &key, mark);
+		}
+		self.storage.db.commit(transaction)?;
+		Ok(())
+	}
+
+	/// Remove the state of the next batch of blocks.
+	///
+	/// Returns the number of the oldest block which state is still to be removed.
+	fn prune_migrated_batch(&self) -> ClientResult<Option<u64>> {
+		let first_kept = match self.storage.state_db.migration() {
+			Some(first_kept) => first_kept,
+			None => return Ok(None),
+		};
+
+		let next = first_kept.saturating_sub(BATCH_BLOCKS);
+		let mut roots = Vec::new();
+		for number in (next..first_kept).rev() {
+			let hash = match self.blockchain.hash(number.saturated_into())? {
+				Some(hash) => hash,
+				None => continue,
+			};
+			roots.push(self.blockchain.header_metadata(hash)?.state_root);
+			let canonical_child = self.blockchain.hash((number + 1).saturated_into())?;
+			roots.extend(self.fork_state_roots(hash, canonical_child)?);
+		}
+
+		let mut deleted = HashSet::new();
+		for root in roots {
+			let tries = std::iter::once((Vec::new(), root)).chain(self.child_tries(&root));
+			for (keyspace, root) in tries {
+				self.visit_state_nodes(&root, &keyspace, |key| {
+					!self.storage.db.contains(columns::STATE_MARKS, key) &&
+						deleted.insert(key.to_vec())
+				})?;
+			}
+		}
+
+		let _import_lock = self.import_lock.write();
+		// Nodes that were written again since they were visited are in use.
+		let deleted = deleted.into_iter()
+			.filter(|key| !self.storage.db.contains(columns::STATE_MARKS, key))
+			.collect();
+		let commit = self.storage.state_db.prune_migrated(next, deleted);
+		let mut transaction = Transaction::new();
+		apply_state_commit(&mut transaction, commit);
+		if next == 0 {
+			// The marks are removed when the database is opened again.
+			transaction.remove(columns::META, meta_keys::STATE_MARKING);
+		}
+		self.storage.db.commit(transaction)?;
+
+		if next == 0 {
+			info!(target: "db", "Removed all state left from the previous pruning mode");
+			return Ok(None);
+		}
+		Ok(Some(next))
+	}
+
+	/// Returns the state roots of the blocks on the forks that branch off the canonical block
+	/// `hash`, whose canonical child is `canonical_child`.
+	///
+	/// These forks were discarded when the canonical child was finalized.
+	fn fork_state_roots(
+		&self,
+		hash: Block::Hash,
+		canonical_child: Option<Block::Hash>,
+	) -> ClientResult<Vec<Block::Hash>> {
+		let mut forks = self.blockchain.children(hash)?;
+		forks.retain(|child| Some(*child) != canonical_child);
+		let mut roots = Vec::new();
+		while let Some(fork) = forks.pop() {
+			roots.push(self.blockchain.header_metadata(fork)?.state_root);
+			forks.extend(self.blockchain.children(fork)?);
+		}
+		Ok(roots)
+	}
+
+	/// Returns the keyspaces and roots of the child tries of the state with the given root.
+	fn child_tries(&self, root: &Block::Hash) -> Vec<(Vec<u8>, Block::Hash)> {
+		let state = DbState::<Block>::new(self.storage.clone(), *root);
+		let prefix = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
+		let mut tries = Vec::new();
+		state.for_key_values_with_prefix(prefix, |key, value| {
+			if let Ok(child_root) = Block::Hash::decode(&mut &value[..]) {
+				let child_info = ChildInfo::new_default(&key[prefix.len()..]);
+				tries.push((child_info.keyspace().to_vec(), child_root));
+			}
+		});
+		tries
+	}
+
+	/// Call `f` for the database keys of all nodes of the state trie with the given root.
+	///
+	/// The nodes of child tries are stored under their `keyspace`, which is empty for the main
+	/// trie. The children of a node are only visited when `f` returns `true`.
+	fn visit_state_nodes<F: FnMut(&[u8]) -> bool>(
+		&self,
+		root: &Block::Hash,
+		keyspace: &[u8],
+		mut f: F,
+	) -> ClientResult<()> {
+		let storage = &*self.storage;
+		let keyspaced = |prefix: hash_db::Prefix| {
+			let mut keyspaced = keyspace.to_vec();
+			keyspaced.extend_from_slice(prefix.0);
+			(keyspaced, prefix.1)
+		};
+		sp_trie::visit_nodes::<sp_trie::Layout<HashFor<Block>>, _, _>(
+			root,
+			|hash, prefix| {
+				let (prefix, last) = keyspaced(prefix);
+				sp_state_machine::Storage::get(storage, hash, (&prefix[..], last)).ok().flatten()
+			},
+			|hash, prefix| if storage.prefix_keys {
+				let (prefix, last) = keyspaced(prefix);
+				f(&prefixed_key::<HashFor<Block>>(hash, (&prefix[..], last)))
+			} else {
+				f(hash.as_ref())
+			},
+		).map_err(|e| sp_blockchain::Error::Backend(format!("Error reading state: {:?}", e)))
+	}
+}
+
+/// Remove the marks left by a finished removal of the state kept by a previous pruning mode.
+#[cfg(any(feature = "with-kvdb-rocksdb", test))]
+pub(crate) fn clear_state_marks(db: &kvdb_rocksdb::Database) -> io::Result<()> {
+	if db.get(columns::META, meta_keys::STATE_MARKING)?.is_some() {
+		return Ok(());
+	}
+	let mut transaction = db.transaction();
+	let mut count = 0;
+	for (key, _) in db.iter(columns::STATE_MARKS) {
+		transaction.delete(columns::STATE_MARKS, &key);
+		count += 1;
+		if count % BATCH_MARKS == 0 {
+			db.write(std::mem::replace(&mut transaction, db.transaction()))?;
+		}
+	}
+	db.write(transaction)
+}
@@ -31,11 +31,12 @@ use codec::{Decode, Encode};
 const VERSION_FILE_NAME: &'static str = "db_version";
 
 /// Current db version.
-const CURRENT_VERSION: u32 = 3;
+const CURRENT_VERSION: u32 = 4;
 
 /// Number of columns in v1.
 const V1_NUM_COLUMNS: u32 = 11;
 const V2_NUM_COLUMNS: u32 = 12;
+const V3_NUM_COLUMNS: u32 = 12;
 
 /// Upgrade database to current version.
 pub fn upgrade_db<Block: BlockT>(db_path: &Path, db_type: DatabaseType) -> sp_blockchain::Result<()> {
@@ -46,9 +47,14 @@ pub fn upgrade_db<Block: BlockT>(db_path: &Path, db_type: DatabaseType) -> sp_bl
 			0 => Err(sp_blockchain::Error::Backend(format!("Unsupported database version: {}", db_version)))?,
 			1 => {
 				migrate_1_to_2::<Block>(db_path, db_type)?;
-				migrate_2_to_3::<Block>(db_path, db_type)?
+				migrate_2_to_3::<Block>(db_path, db_type)?;
+				migrate_3_to_4::<Block>(db_path, db_type)?
 			},
-			2 => migrate_2_to_3::<Block>(db_path, db_type)?,
+			2 => {
+				migrate_2_to_3::<Block>(db_path, db_type)?;
+				migrate_3_to_4::<Block>(db_path, db_type)?
+			},
+			3 => migrate_3_to_4::<Block>(db_path, db_type)?,
 			CURRENT_VERSION => (),
 			_ => Err(sp_blockchain::Error::Backend(format!("Future database version: {}", db_version)))?,
 		}
@@ -98,6 +104,17 @@ fn migrate_2_to_3<Block: BlockT>(db_path: &Path, _db_type: DatabaseType) -> sp_b
 	Ok(())
 }
 
+/// Migration from version3 to version4:
+/// 1) the number of columns has changed from 12 to 13;
+/// 2) the column marking the state nodes in use while switching the pruning mode is added;
+fn migrate_3_to_4<Block: BlockT>(db_path: &Path, _db_type: DatabaseType) -> sp_blockchain::Result<()> {
+	let db_path = db_path.to_str()
+		.ok_or_else(|| sp_blockchain::Error::Backend("Invalid database path".into()))?;
+	let db_cfg = DatabaseConfig::with_columns(V3_NUM_COLUMNS);
+	let db = Database::open(&db_cfg, db_path).map_err(db_err)?;
+	db.add_column().map_err(db_err)
+}
+
 /// Reads current database version from the file at given path.
 /// If the file does not exist returns 0.
 fn current_version(path: &Path) -> sp_blockchain::Result<u32> {
@@ -151,14 +168,21 @@ mod tests {
 	}
 
 	fn open_database(db_path: &Path) -> sp_blockchain::Result<()> {
+		let source = DatabaseSettingsSrc::RocksDb { path: db_path.to_owned(), cache_size: 128 };
+		open_database_from(source).map(|_| ())
+	}
+
+	fn open_database_from(
+		source: DatabaseSettingsSrc,
+	) -> sp_blockchain::Result<std::sync::Arc<dyn sp_database::Database<crate::DbHash>>> {
 		crate::utils::open_database::<Block>(&DatabaseSettings {
 			state_cache_size: 0,
 			state_cache_child_ratio: None,
 			state_pruning: PruningMode::ArchiveAll,
-			source: DatabaseSettingsSrc::RocksDb { path: db_path.to_owned(), cache_size: 128 },
+			source,
 			keep_blocks: KeepBlocks::All,
 			transaction_storage: TransactionStorageMode::BlockBody,
-		}, DatabaseType::Full).map(|_| ())
+		}, DatabaseType::Full)
 	}
 
 	#[test]
@@ -177,13 +201,38 @@ mod tests {
 	}
 
 	#[test]
-	fn upgrade_to_3_works() {
-		for version_from_file in &[None, Some(1), Some(2)] {
+	fn upgrade_to_4_works() {
+		for version_from_file in &[None, Some(1), Some(2), Some(3)] {
 			let db_dir = tempfile::TempDir::new().unwrap();
 			let db_path = db_dir.path();
 			create_db(db_path, *version_from_file);
 			open_database(db_path).unwrap();
 			assert_eq!(current_version(db_path).unwrap(), CURRENT_VERSION);
 		}
+
+		#[cfg(feature = "with-parity-db")]
+		{
+			let db_dir = tempfile::TempDir::new().unwrap();
+			let db_path = db_dir.path();
+			let mut config = parity_db::Options::with_columns(db_path, V3_NUM_COLUMNS as u8);
+			let state_col = &mut config.columns[columns::STATE as usize];
+			state_col.ref_counted = true;
+			state_col.preimage = true;
+			state_col.uniform = true;
+			drop(parity_db::Db::open(&config).unwrap());
+
+			let source = || DatabaseSettingsSrc::ParityDb { path: db_path.to_owned() };
+			let db = open_database_from(source()).unwrap();
+			let mut transaction = sp_database::Transaction::new();
+			transaction.set(columns::STATE_MARKS, b"mark", b"");
+			db.commit(transaction).unwrap();
+			drop(db);
+
+			let metadata = fs::read_to_string(db_path.join("metadata")).unwrap();
+			let num_columns = metadata.lines().filter(|line| line.starts_with("col")).count();
+			assert_eq!(num_columns, crate::utils::NUM_COLUMNS as usize);
+			let db = open_database_from(source()).unwrap();
+			assert!(db.contains(columns::STATE_MARKS, b"mark"));
+		}
 	}
 }
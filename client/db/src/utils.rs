@@ -37,7 +37,7 @@ use crate::{DatabaseSettings, DatabaseSettingsSrc, Database, DbHash};
 /// Number of columns in the db. Must be the same for both full && light dbs.
 /// Otherwise RocksDb will fail to open database && check its type.
 #[cfg(any(feature = "with-kvdb-rocksdb", feature = "with-parity-db", feature = "test-helpers", test))]
-pub const NUM_COLUMNS: u32 = 12;
+pub const NUM_COLUMNS: u32 = 13;
 /// Meta column. The set of keys in the column is shared by full && light storages.
 pub const COLUMN_META: u32 = 0;
 
@@ -59,6 +59,9 @@ pub mod meta_keys {
 	pub const LEAF_PREFIX: &[u8; 4] = b"leaf";
 	/// Children prefix list key.
 	pub const CHILDREN_PREFIX: &[u8; 8] = b"children";
+	/// Progress of marking the state nodes in use while the state left from a previous pruning
+	/// mode is removed.
+	pub const STATE_MARKING: &[u8; 7] = b"marking";
 }
 
 /// Database metadata.
@@ -269,6 +272,10 @@ pub fn open_database<Block: BlockT>(
 
 			let db = kvdb_rocksdb::Database::open(&db_config, &path)
 				.map_err(|err| sp_blockchain::Error::Backend(format!("{}", err)))?;
+			if db_type == DatabaseType::Full {
+				crate::pruning_migration::clear_state_marks(&db)
+					.map_err(|err| sp_blockchain::Error::Backend(format!("{}", err)))?;
+			}
 			sp_database::as_database(db)
 		},
 		#[cfg(not(any(feature = "with-kvdb-rocksdb", test)))]
@@ -782,3 +782,47 @@ fn panic_in_spawned_instance_panics_on_joining_its_result(wasm_method: WasmExecu
 	dbg!(&error_result);
 	assert!(format!("{}", error_result).contains("Spawned task"));
 }
+
+#[cfg(feature = "wasmtime")]
+#[test]
+fn precompiled_runtime_is_loaded_from_cache_path() {
+	let cache_dir = tempfile::tempdir().unwrap();
+	let artifacts = || std::fs::read_dir(cache_dir.path()).unwrap()
+		.map(|entry| entry.unwrap().path())
+		.filter(|path| path.extension().map_or(false, |extension| extension == "cwasm"))
+		.collect::<Vec<_>>();
+	for i in 0..10 {
+		std::fs::write(cache_dir.path().join(format!("old-{}.cwasm", i)), b"old").unwrap();
+	}
+
+	let code = wasm_binary_unwrap();
+	let artifact = crate::precompile_wasm_runtime(code, cache_dir.path()).unwrap();
+	assert!(artifact.starts_with(cache_dir.path()));
+	// Only the 8 most recently written artifacts are kept.
+	assert_eq!(artifacts().len(), 8);
+	assert!(artifacts().contains(&artifact));
+
+	let call = || {
+		let executor = crate::WasmExecutor::new(
+			WasmExecutionMethod::Compiled,
+			Some(1024),
+			HostFunctions::host_functions(),
+			8,
+			Some(cache_dir.path().to_owned()),
+		);
+		executor.call_in_wasm(
+			code,
+			Some(blake2_256(code).to_vec()),
+			"test_empty_return",
+			&[],
+			&mut TestExternalities::default().ext(),
+			sp_core::traits::MissingHostFunctions::Allow,
+		).unwrap()
+	};
+	assert_eq!(call(), Vec::<u8>::new());
+
+	// A corrupted artifact is detected and replaced.
+	std::fs::write(&artifact, b"corrupted").unwrap();
+	assert_eq!(call(), Vec::<u8>::new());
+	assert_ne!(std::fs::read(&artifact).unwrap(), b"corrupted".to_vec());
+}
@@ -45,7 +45,7 @@ pub use codec::Codec;
 pub use sp_core::traits::{Externalities, CallInWasm};
 #[doc(hidden)]
 pub use sp_wasm_interface;
-pub use wasm_runtime::WasmExecutionMethod;
+pub use wasm_runtime::{WasmExecutionMethod, precompile_wasm_runtime};
 
 pub use sc_executor_common::{error, sandbox};
 
@@ -273,6 +273,18 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 		fallback_method: WasmExecutionMethod,
 		default_heap_pages: Option<u64>,
 		max_runtime_instances: usize,
+	) -> Self {
+		Self::new_with_cache_path(fallback_method, default_heap_pages, max_runtime_instances, None)
+	}
+
+	/// Create new instance that stores compiled runtimes in `cache_path`.
+	///
+	/// See [`NativeExecutor::new`] for the other parameters.
+	pub fn new_with_cache_path(
+		fallback_method: WasmExecutionMethod,
+		default_heap_pages: Option<u64>,
+		max_runtime_instances: usize,
+		cache_path: Option<PathBuf>,
 	) -> Self {
 		let extended =  D::ExtendHostFunctions::host_functions();
 		let mut host_functions = sp_io::SubstrateHostFunctions::host_functions()
@@ -293,7 +305,7 @@ impl<D: NativeExecutionDispatch> NativeExecutor<D> {
 			default_heap_pages,
 			host_functions,
 			max_runtime_instances,
-			None,
+			cache_path,
 		);
 
 		NativeExecutor {
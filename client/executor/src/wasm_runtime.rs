@@ -313,10 +313,7 @@ pub fn create_wasm_runtime_with_code(
 					heap_pages: heap_pages as u32,
 					allow_missing_func_imports,
 					cache_path: cache_path.map(ToOwned::to_owned),
-					semantics: sc_executor_wasmtime::Semantics {
-						fast_instance_reuse: true,
-						stack_depth_metering: false,
-					},
+					semantics: wasmtime_semantics(),
 				},
 				host_functions,
 			).map(|runtime| -> Arc<dyn WasmModule> { Arc::new(runtime) })
@@ -324,6 +321,37 @@ pub fn create_wasm_runtime_with_code(
 	}
 }
 
+/// The semantics of the runtimes that are compiled with wasmtime.
+#[cfg(feature = "wasmtime")]
+fn wasmtime_semantics() -> sc_executor_wasmtime::Semantics {
+	sc_executor_wasmtime::Semantics {
+		fast_instance_reuse: true,
+		stack_depth_metering: false,
+	}
+}
+
+/// Compile the runtime `code` with wasmtime and store the artifact in `cache_path`.
+///
+/// An executor using [`WasmExecutionMethod::Compiled`] with the same `cache_path` loads the
+/// runtime from the artifact instead of compiling it. Returns the path of the artifact.
+pub fn precompile_wasm_runtime(code: &[u8], cache_path: &Path) -> Result<PathBuf, WasmError> {
+	#[cfg(feature = "wasmtime")]
+	{
+		use sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT;
+
+		let code = sp_maybe_compressed_blob::decompress(code, CODE_BLOB_BOMB_LIMIT)
+			.map_err(|e| WasmError::Other(format!("Decompression error: {:?}", e)))?;
+		let blob = sc_executor_common::runtime_blob::RuntimeBlob::new(&code)?;
+		sc_executor_wasmtime::precompile_runtime(blob, &wasmtime_semantics(), cache_path)
+	}
+
+	#[cfg(not(feature = "wasmtime"))]
+	{
+		let _ = (code, cache_path);
+		Err(WasmError::Other("the node is built without wasmtime support".into()))
+	}
+}
+
 fn decode_version(version: &[u8]) -> Result<RuntimeVersion, WasmError> {
 	let v: RuntimeVersion = sp_api::OldRuntimeVersion::decode(&mut &version[..])
 		.map_err(|_|
@@ -0,0 +1,180 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Storing compiled runtimes on disk.
+//!
+//! Compiling a large runtime takes seconds, so the compiled artifact is written to a directory
+//! and loaded from there the next time the same runtime is needed, e.g. after a restart. The
+//! file name is derived from the hash of the code, the version of wasmtime, the configuration
+//! of the engine and the semantics the code was instrumented with. Every file starts with the
+//! hash of the artifact, which is checked before the artifact is loaded. Only the most
+//! recently written artifacts are kept, so the directory doesn't grow with every runtime upgrade
+//! or wasmtime update.
+
+use std::{fs, io, path::{Path, PathBuf}};
+
+use codec::Encode;
+use sc_executor_common::error::WasmError;
+use sp_core::{hashing::{blake2_64, blake2_256}, hexdisplay::HexDisplay};
+use wasmtime::{Engine, Module};
+
+use crate::runtime::{Semantics, OPT_LEVEL};
+
+/// The version of wasmtime that compiles the artifacts.
+///
+/// Must be updated together with the `wasmtime` requirement in `Cargo.toml`. Artifacts of other
+/// patch releases, which the requirement allows, are rejected by `Module::deserialize` and
+/// compiled again.
+const WASMTIME_VERSION: &str = "0.24";
+
+/// Length of the checksum at the start of an artifact file.
+const CHECKSUM_LEN: usize = 32;
+
+/// Number of artifacts that are kept in the directory.
+const MAX_ARTIFACTS: usize = 8;
+
+/// Extension of the artifact files.
+const EXTENSION: &str = "cwasm";
+
+/// Returns the path of the artifact of `code` instrumented with `semantics` in `dir`.
+pub fn artifact_path(dir: &Path, code: &[u8], semantics: &Semantics) -> PathBuf {
+	let config = (
+		WASMTIME_VERSION,
+		format!("{:?}", OPT_LEVEL),
+		semantics.fast_instance_reuse,
+		semantics.stack_depth_metering,
+	).encode();
+	dir.join(format!(
+		"{}-{}.{}",
+		HexDisplay::from(&blake2_256(code)),
+		HexDisplay::from(&blake2_64(&config)),
+		EXTENSION,
+	))
+}
+
+/// Load the module of `code` from its artifact in `dir` or compile it.
+///
+/// A freshly compiled module is written to `dir`. Failing to read or write the artifact is not
+/// an error, the module is compiled in this case.
+pub fn load_or_compile(
+	engine: &Engine,
+	dir: &Path,
+	code: &[u8],
+	semantics: &Semantics,
+) -> Result<Module, WasmError> {
+	let path = artifact_path(dir, code, semantics);
+	match load(&path) {
+		Ok(Some(artifact)) => match Module::deserialize(engine, &artifact) {
+			Ok(module) => return Ok(module),
+			Err(e) => log::warn!(
+				target: "wasm-runtime",
+				"Cannot deserialize compiled runtime {}: {}",
+				path.display(),
+				e,
+			),
+		},
+		Ok(None) => {},
+		Err(e) => log::warn!(
+			target: "wasm-runtime",
+			"Cannot read compiled runtime {}: {}",
+			path.display(),
+			e,
+		),
+	}
+
+	let module = Module::new(engine, code)
+		.map_err(|e| WasmError::Other(format!("cannot create module: {}", e)))?;
+	match module.serialize() {
+		Ok(artifact) => match store(&path, &artifact) {
+			Ok(()) => if let Err(e) = prune(dir) {
+				log::warn!(
+					target: "wasm-runtime",
+					"Cannot remove old compiled runtimes in {}: {}",
+					dir.display(),
+					e,
+				);
+			},
+			Err(e) => log::warn!(
+				target: "wasm-runtime",
+				"Cannot write compiled runtime {}: {}",
+				path.display(),
+				e,
+			),
+		},
+		Err(e) => log::warn!(target: "wasm-runtime", "Cannot serialize module: {}", e),
+	}
+	Ok(module)
+}
+
+/// Read the artifact at `path`.
+///
+/// Returns `None` if there is no artifact or if it doesn't match its checksum.
+fn load(path: &Path) -> io::Result<Option<Vec<u8>>> {
+	let mut data = match fs::read(path) {
+		Ok(data) => data,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+		Err(e) => return Err(e),
+	};
+
+	if data.len() < CHECKSUM_LEN || blake2_256(&data[CHECKSUM_LEN..])[..] != data[..CHECKSUM_LEN] {
+		log::warn!(
+			target: "wasm-runtime",
+			"Compiled runtime {} is corrupted, compiling it again",
+			path.display(),
+		);
+		return Ok(None);
+	}
+	Ok(Some(data.split_off(CHECKSUM_LEN)))
+}
+
+/// Write `artifact` to `path` preceded by its checksum.
+///
+/// The file is replaced atomically.
+fn store(path: &Path, artifact: &[u8]) -> io::Result<()> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	let mut data = Vec::with_capacity(CHECKSUM_LEN + artifact.len());
+	data.extend_from_slice(&blake2_256(artifact));
+	data.extend_from_slice(artifact);
+
+	let tmp_path = path.with_extension("tmp");
+	fs::write(&tmp_path, data)?;
+	fs::rename(&tmp_path, path)
+}
+
+/// Remove all but the [`MAX_ARTIFACTS`] most recently written artifacts in `dir`.
+fn prune(dir: &Path) -> io::Result<()> {
+	let mut artifacts = Vec::new();
+	for entry in fs::read_dir(dir)? {
+		let path = entry?.path();
+		if path.extension().map_or(false, |extension| extension == EXTENSION) {
+			artifacts.push((fs::metadata(&path)?.modified()?, path));
+		}
+	}
+	if artifacts.len() <= MAX_ARTIFACTS {
+		return Ok(());
+	}
+
+	artifacts.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+	for (_, path) in artifacts.into_iter().skip(MAX_ARTIFACTS) {
+		log::debug!(target: "wasm-runtime", "Removing old compiled runtime {}", path.display());
+		fs::remove_file(path)?;
+	}
+	Ok(())
+}
@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 ///! Defines a `WasmRuntime` that uses the Wasmtime JIT to execute.
+mod artifacts;
 mod host;
 mod imports;
 mod instance_wrapper;
@@ -24,4 +25,6 @@ mod runtime;
 mod state_holder;
 mod util;
 
-pub use runtime::{create_runtime, prepare_runtime_artifact, CodeSupplyMode, Config, Semantics};
+pub use runtime::{
+	create_runtime, precompile_runtime, prepare_runtime_artifact, CodeSupplyMode, Config, Semantics,
+};
@@ -18,6 +18,7 @@
 
 //! Defines the compiled Wasm runtime that uses Wasmtime internally.
 
+use crate::artifacts;
 use crate::host::HostState;
 use crate::imports::{Imports, resolve_imports};
 use crate::instance_wrapper::{InstanceWrapper, EntryPoint};
@@ -175,47 +176,12 @@ impl WasmInstance for WasmtimeInstance {
 	}
 }
 
-/// Prepare a directory structure and a config file to enable wasmtime caching.
-///
-/// In case of an error the caching will not be enabled.
-fn setup_wasmtime_caching(
-	cache_path: &Path,
-	config: &mut wasmtime::Config,
-) -> std::result::Result<(), String> {
-	use std::fs;
-
-	let wasmtime_cache_root = cache_path.join("wasmtime");
-	fs::create_dir_all(&wasmtime_cache_root)
-		.map_err(|err| format!("cannot create the dirs to cache: {:?}", err))?;
-
-	// Canonicalize the path after creating the directories.
-	let wasmtime_cache_root = wasmtime_cache_root
-		.canonicalize()
-		.map_err(|err| format!("failed to canonicalize the path: {:?}", err))?;
-
-	// Write the cache config file
-	let cache_config_path = wasmtime_cache_root.join("cache-config.toml");
-	let config_content = format!(
-		"\
-[cache]
-enabled = true
-directory = \"{cache_dir}\"
-",
-		cache_dir = wasmtime_cache_root.display()
-	);
-	fs::write(&cache_config_path, config_content)
-		.map_err(|err| format!("cannot write the cache config: {:?}", err))?;
-
-	config
-		.cache_config_load(cache_config_path)
-		.map_err(|err| format!("failed to parse the config: {:?}", err))?;
-
-	Ok(())
-}
+/// The optimization level the runtimes are compiled with.
+pub(crate) const OPT_LEVEL: wasmtime::OptLevel = wasmtime::OptLevel::SpeedAndSize;
 
 fn common_config() -> wasmtime::Config {
 	let mut config = wasmtime::Config::new();
-	config.cranelift_opt_level(wasmtime::OptLevel::SpeedAndSize);
+	config.cranelift_opt_level(OPT_LEVEL);
 	config
 }
 
@@ -265,7 +231,10 @@ pub struct Config {
 	/// will be resolved using stubs. These stubs will trap upon a call.
 	pub allow_missing_func_imports: bool,
 
-	/// A directory in which wasmtime can store its compiled artifacts cache.
+	/// A directory in which the compiled artifacts are stored.
+	///
+	/// If set, a runtime that was compiled before is loaded from there instead of being
+	/// compiled again. See [`precompile_runtime`].
 	pub cache_path: Option<PathBuf>,
 
 	/// Tuning of various semantics of the wasmtime executor.
@@ -303,17 +272,7 @@ pub fn create_runtime(
 	host_functions: Vec<&'static dyn Function>,
 ) -> std::result::Result<WasmtimeRuntime, WasmError> {
 	// Create the engine, store and finally the module from the given code.
-	let mut wasmtime_config = common_config();
-	if let Some(ref cache_path) = config.cache_path {
-		if let Err(reason) = setup_wasmtime_caching(cache_path, &mut wasmtime_config) {
-			log::warn!(
-				"failed to setup wasmtime cache. Performance may degrade significantly: {}.",
-				reason,
-			);
-		}
-	}
-
-	let engine = Engine::new(&wasmtime_config);
+	let engine = Engine::new(&common_config());
 
 	let (module, snapshot_data) = match code_supply_mode {
 		CodeSupplyMode::Verbatim { mut blob } => {
@@ -327,16 +286,14 @@ pub fn create_runtime(
 
 				let mutable_globals = ExposedMutableGlobalsSet::collect(&blob);
 
-				let module = wasmtime::Module::new(&engine, &blob.serialize())
-					.map_err(|e| WasmError::Other(format!("cannot create module: {}", e)))?;
+				let module = compile(&engine, &blob, &config)?;
 
 				(module, Some(InstanceSnapshotData {
 					data_segments_snapshot,
 					mutable_globals,
 				}))
 			} else {
-				let module = wasmtime::Module::new(&engine, &blob.serialize())
-					.map_err(|e| WasmError::Other(format!("cannot create module: {}", e)))?;
+				let module = compile(&engine, &blob, &config)?;
 				(module, None)
 			}
 		}
@@ -357,6 +314,21 @@ pub fn create_runtime(
 	})
 }
 
+/// Compile the instrumented `blob`, using the artifact in the cache directory if there is one.
+fn compile(
+	engine: &Engine,
+	blob: &RuntimeBlob,
+	config: &Config,
+) -> std::result::Result<wasmtime::Module, WasmError> {
+	let code = blob.serialize();
+	match config.cache_path {
+		Some(ref cache_path) =>
+			artifacts::load_or_compile(engine, cache_path, &code, &config.semantics),
+		None => wasmtime::Module::new(engine, &code)
+			.map_err(|e| WasmError::Other(format!("cannot create module: {}", e))),
+	}
+}
+
 fn instrument(blob: &mut RuntimeBlob, semantics: &Semantics) {
 	if semantics.fast_instance_reuse {
 		blob.expose_mutable_globals();
@@ -383,6 +355,24 @@ pub fn prepare_runtime_artifact(
 		.map_err(|e| WasmError::Other(format!("cannot serialize module: {}", e)))
 }
 
+/// Compiles the given [`RuntimeBlob`] and stores the artifact in `cache_path`.
+///
+/// A runtime that is later created with the same `semantics` and `cache_path` by
+/// [`create_runtime`] is loaded from the artifact instead of being compiled. This allows to
+/// compile a runtime ahead of an upgrade. Returns the path of the artifact.
+pub fn precompile_runtime(
+	mut blob: RuntimeBlob,
+	semantics: &Semantics,
+	cache_path: &Path,
+) -> std::result::Result<PathBuf, WasmError> {
+	instrument(&mut blob, semantics);
+
+	let code = blob.serialize();
+	let engine = Engine::new(&common_config());
+	artifacts::load_or_compile(&engine, cache_path, &code, semantics)?;
+	Ok(artifacts::artifact_path(cache_path, &code, semantics))
+}
+
 fn perform_call(
 	data: &[u8],
 	instance_wrapper: Rc<InstanceWrapper>,
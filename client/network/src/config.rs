@@ -115,6 +115,14 @@ pub struct Params<B: BlockT, H: ExHashT> {
 	/// incoming requests.
 	pub block_request_protocol_config: RequestResponseConfig,
 
+	/// Request response configuration for the state request protocol.
+	///
+	/// Can be constructed either via [`crate::state_request_handler::generate_protocol_config`]
+	/// allowing outgoing but not incoming requests, or constructed via
+	/// [`crate::state_request_handler::StateRequestHandler::new`] allowing both outgoing and
+	/// incoming requests.
+	pub state_request_protocol_config: RequestResponseConfig,
+
 	/// Request response configuration for the light client request protocol.
 	///
 	/// Can be constructed either via
@@ -368,6 +376,31 @@ impl From<multiaddr::Error> for ParseErr {
 	}
 }
 
+/// Sync operation mode.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SyncMode {
+	/// Full block download and verification.
+	Full,
+	/// Download blocks without executing them and download the latest finalized state.
+	Fast {
+		/// Skip state proof download and verification.
+		skip_proofs: bool,
+	},
+}
+
+impl SyncMode {
+	/// Returns if `self` is [`Self::Fast`].
+	pub fn is_fast(&self) -> bool {
+		matches!(self, Self::Fast { .. })
+	}
+}
+
+impl Default for SyncMode {
+	fn default() -> Self {
+		Self::Full
+	}
+}
+
 /// Network service configuration.
 #[derive(Clone, Debug)]
 pub struct NetworkConfiguration {
@@ -395,6 +428,8 @@ pub struct NetworkConfiguration {
 	pub transport: TransportConfig,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// Initial syncing mode.
+	pub sync_mode: SyncMode,
 
 	/// True if Kademlia random discovery should be enabled.
 	///
@@ -457,6 +492,7 @@ impl NetworkConfiguration {
 				wasm_external_transport: None,
 			},
 			max_parallel_downloads: 5,
+			sync_mode: SyncMode::Full,
 			enable_dht_random_walk: true,
 			allow_non_globals_in_dht: false,
 			kademlia_disjoint_query_paths: false,
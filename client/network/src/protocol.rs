@@ -188,6 +188,8 @@ struct Peer<B: BlockT> {
 		message::BlockRequest<B>,
 		oneshot::Receiver<Result<Vec<u8>, RequestFailure>>,
 	)>,
+	/// Current state request, if any. Started by emitting [`CustomMessageOutcome::StateRequest`].
+	state_request: Option<oneshot::Receiver<Result<Vec<u8>, RequestFailure>>>,
 	/// Holds a set of blocks known to this peer.
 	known_blocks: LruHashSet<B::Hash>,
 }
@@ -268,6 +270,7 @@ impl<B: BlockT> Protocol<B> {
 			&info,
 			block_announce_validator,
 			config.max_parallel_downloads,
+			network_config.sync_mode,
 		);
 
 		let boot_node_ids = {
@@ -540,6 +543,27 @@ impl<B: BlockT> Protocol<B> {
 		self.peerset_handle.report_peer(who, reputation)
 	}
 
+	/// Must be called in response to a [`CustomMessageOutcome::StateRequest`] being emitted.
+	/// Must contain the same `PeerId` that has been emitted.
+	pub fn on_state_response(
+		&mut self,
+		peer_id: PeerId,
+		response: crate::schema::v1::StateResponse,
+	) -> CustomMessageOutcome<B> {
+		match self.sync.on_state_data(&peer_id, response) {
+			Ok(sync::OnStateData::Import(origin, block)) =>
+				CustomMessageOutcome::BlockImport(origin, vec![block]),
+			Ok(sync::OnStateData::Request(peer, req)) => {
+				prepare_state_request::<B>(&mut self.peers, peer, req)
+			}
+			Err(sync::BadPeer(id, repu)) => {
+				self.behaviour.disconnect_peer(&id, HARDCODED_PEERSETS_SYNC);
+				self.peerset_handle.report_peer(id, repu);
+				CustomMessageOutcome::None
+			}
+		}
+	}
+
 	/// Must be called in response to a [`CustomMessageOutcome::BlockRequest`] being emitted.
 	/// Must contain the same `PeerId` and request that have been emitted.
 	pub fn on_block_response(
@@ -725,6 +749,7 @@ impl<B: BlockT> Protocol<B> {
 				best_number: status.best_number
 			},
 			block_request: None,
+			state_request: None,
 			known_blocks: LruHashSet::new(NonZeroUsize::new(MAX_KNOWN_BLOCKS)
 				.expect("Constant is nonzero")),
 		};
@@ -1139,6 +1164,24 @@ fn prepare_block_request<B: BlockT>(
 	}
 }
 
+fn prepare_state_request<B: BlockT>(
+	peers: &mut HashMap<PeerId, Peer<B>>,
+	who: PeerId,
+	request: crate::schema::v1::StateRequest,
+) -> CustomMessageOutcome<B> {
+	let (tx, rx) = oneshot::channel();
+
+	if let Some(ref mut peer) = peers.get_mut(&who) {
+		peer.state_request = Some(rx);
+	}
+
+	CustomMessageOutcome::StateRequest {
+		target: who,
+		request: request,
+		pending_response: tx,
+	}
+}
+
 /// Outcome of an incoming custom message.
 #[derive(Debug)]
 #[must_use]
@@ -1168,6 +1211,12 @@ pub enum CustomMessageOutcome<B: BlockT> {
 		request: crate::schema::v1::BlockRequest,
 		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
 	},
+	/// A new storage request must be emitted.
+	StateRequest {
+		target: PeerId,
+		request: crate::schema::v1::StateRequest,
+		pending_response: oneshot::Sender<Result<Vec<u8>, RequestFailure>>,
+	},
 	/// Peer has a reported a new head of chain.
 	PeerNewBest(PeerId, NumberFor<B>),
 	/// Now connected to a new peer for syncing purposes.
@@ -1297,10 +1346,82 @@ impl<B: BlockT> NetworkBehaviour for Protocol<B> {
 				}
 			}
 		}
+		let mut finished_state_requests = Vec::new();
+		for (id, peer) in self.peers.iter_mut() {
+			if let Peer { state_request: Some(pending_response), .. } = peer {
+				match pending_response.poll_unpin(cx) {
+					Poll::Ready(Ok(Ok(resp))) => {
+						peer.state_request.take();
+
+						let protobuf_response = match crate::schema::v1::StateResponse::decode(&resp[..]) {
+							Ok(proto) => proto,
+							Err(e) => {
+								debug!(target: "sync", "Failed to decode state response from peer {:?}: {:?}.", id, e);
+								self.peerset_handle.report_peer(id.clone(), rep::BAD_MESSAGE);
+								self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+								continue;
+							}
+						};
+
+						finished_state_requests.push((id.clone(), protobuf_response));
+					},
+					Poll::Ready(Ok(Err(e))) => {
+						peer.state_request.take();
+						debug!(target: "sync", "State request to peer {:?} failed: {:?}.", id, e);
+
+						match e {
+							RequestFailure::Network(OutboundFailure::Timeout) => {
+								self.peerset_handle.report_peer(id.clone(), rep::TIMEOUT);
+								self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+							}
+							RequestFailure::Network(OutboundFailure::UnsupportedProtocols) => {
+								self.peerset_handle.report_peer(id.clone(), rep::BAD_PROTOCOL);
+								self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+							}
+							RequestFailure::Network(OutboundFailure::DialFailure) => {
+								self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+							}
+							RequestFailure::Refused => {
+								self.peerset_handle.report_peer(id.clone(), rep::REFUSED);
+								self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+							}
+							RequestFailure::Network(OutboundFailure::ConnectionClosed)
+							| RequestFailure::NotConnected => {
+								self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+							},
+							RequestFailure::UnknownProtocol => {
+								debug_assert!(false, "State request protocol should always be known.");
+							}
+							RequestFailure::Obsolete => {
+								debug_assert!(
+									false,
+									"Can not receive `RequestFailure::Obsolete` after dropping the \
+									 response receiver.",
+								);
+							}
+						}
+					},
+					Poll::Ready(Err(oneshot::Canceled)) => {
+						peer.state_request.take();
+						trace!(
+							target: "sync",
+							"State request to peer {:?} failed due to oneshot being canceled.",
+							id,
+						);
+						self.behaviour.disconnect_peer(id, HARDCODED_PEERSETS_SYNC);
+					},
+					Poll::Pending => {},
+				}
+			}
+		}
 		for (id, req, protobuf_response) in finished_block_requests {
 			let ev = self.on_block_response(id, req, protobuf_response);
 			self.pending_messages.push_back(ev);
 		}
+		for (id, protobuf_response) in finished_state_requests {
+			let ev = self.on_state_response(id, protobuf_response);
+			self.pending_messages.push_back(ev);
+		}
 
 		while let Poll::Ready(Some(())) = self.tick_timeout.poll_next_unpin(cx) {
 			self.tick();
@@ -1314,6 +1435,10 @@ impl<B: BlockT> NetworkBehaviour for Protocol<B> {
 			let event = prepare_block_request(&mut self.peers, id, request);
 			self.pending_messages.push_back(event);
 		}
+		if let Some((id, request)) = self.sync.state_request() {
+			let event = prepare_state_request(&mut self.peers, id, request);
+			self.pending_messages.push_back(event);
+		}
 
 		// Check if there is any block announcement validation finished.
 		while let Poll::Ready(result) = self.sync.poll_block_announce_validation(cx) {
@@ -39,6 +39,8 @@ use sp_consensus::{BlockOrigin, BlockStatus,
 use crate::protocol::message::{
 	self, BlockAnnounce, BlockAttributes, BlockRequest, BlockResponse, Roles,
 };
+use crate::config::SyncMode;
+use crate::schema::v1::{StateRequest, StateResponse};
 use either::Either;
 use extra_requests::ExtraRequests;
 use libp2p::PeerId;
@@ -59,6 +61,9 @@ use futures::{task::Poll, Future, stream::FuturesUnordered, FutureExt, StreamExt
 
 mod blocks;
 mod extra_requests;
+mod state;
+
+use state::{StateSync, ImportResult as StateImportResult};
 
 /// Maximum blocks to request in a single packet.
 const MAX_BLOCKS_TO_REQUEST: usize = 128;
@@ -91,6 +96,10 @@ const MAX_CONCURRENT_BLOCK_ANNOUNCE_VALIDATIONS_PER_PEER: usize = 4;
 /// so far behind.
 const MAJOR_SYNC_BLOCKS: u8 = 5;
 
+/// Number of blocks we may be behind the best block of our peers when starting a state sync at
+/// a finalized block.
+const STATE_SYNC_FINALITY_THRESHOLD: u32 = 8;
+
 mod rep {
 	use sc_peerset::ReputationChange as Rep;
 	/// Reputation change when a peer sent us a message that led to a
@@ -209,6 +218,13 @@ pub struct ChainSync<B: BlockT> {
 	>,
 	/// Stats per peer about the number of concurrent block announce validations.
 	block_announce_validation_per_peer_stats: HashMap<PeerId, usize>,
+	/// Sync operation mode.
+	mode: SyncMode,
+	/// State sync in progress, if any.
+	state_sync: Option<StateSync<B>>,
+	/// Highest block with state while the blocks imported without state are re-imported after a
+	/// state sync. Blocks above it are downloaded again with their bodies.
+	imported_state: Option<(B::Hash, NumberFor<B>)>,
 }
 
 /// All the data we have about a Peer that we are trying to sync with
@@ -281,6 +297,8 @@ pub enum PeerSyncState<B: BlockT> {
 	DownloadingStale(B::Hash),
 	/// Downloading justification for given block hash.
 	DownloadingJustification(B::Hash),
+	/// Downloading state chunks.
+	DownloadingState,
 }
 
 impl<B: BlockT> PeerSyncState<B> {
@@ -344,6 +362,15 @@ impl<B: BlockT> OnBlockData<B> {
 	}
 }
 
+/// Result of [`ChainSync::on_state_data`].
+#[derive(Debug)]
+pub enum OnStateData<B: BlockT> {
+	/// The block and state that should be imported.
+	Import(BlockOrigin, IncomingBlock<B>),
+	/// A new state request needs to be made to the given peer.
+	Request(PeerId, StateRequest)
+}
+
 /// Result of [`ChainSync::poll_block_announce_validation`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PollBlockAnnounceValidation<H> {
@@ -447,10 +474,12 @@ impl<B: BlockT> ChainSync<B> {
 		info: &BlockchainInfo<B>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		max_parallel_downloads: u32,
+		mode: SyncMode,
 	) -> Self {
 		let mut required_block_attributes = BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION;
 
-		if role.is_full() {
+		// In fast sync mode the bodies are only downloaded once the state is imported.
+		if role.is_full() && !mode.is_fast() {
 			required_block_attributes |= BlockAttributes::BODY
 		}
 
@@ -471,6 +500,9 @@ impl<B: BlockT> ChainSync<B> {
 			downloaded_blocks: 0,
 			block_announce_validation: Default::default(),
 			block_announce_validation_per_peer_stats: Default::default(),
+			mode,
+			state_sync: None,
+			imported_state: None,
 		}
 	}
 
@@ -605,9 +637,16 @@ impl<B: BlockT> ChainSync<B> {
 					best_hash,
 					best_number,
 				);
+				// Blocks above the block with the imported state are known, but they still have to
+				// be downloaded again.
+				let common_number = if self.imported_state.is_some() {
+					std::cmp::min(self.best_queued_number, best_number)
+				} else {
+					best_number
+				};
 				self.peers.insert(who.clone(), PeerSync {
 					peer_id: who.clone(),
-					common_number: best_number,
+					common_number,
 					best_hash,
 					best_number,
 					state: PeerSyncState::Available,
@@ -829,6 +868,8 @@ impl<B: BlockT> ChainSync<B> {
 							peer.state = PeerSyncState::Available;
 							validate_blocks::<B>(&blocks, who, Some(request))?;
 							self.blocks.insert(start_block, blocks, who.clone());
+							let imported_state = self.imported_state.is_some();
+							let client = &self.client;
 							self.blocks
 								.drain(self.best_queued_number + One::one())
 								.into_iter()
@@ -836,6 +877,11 @@ impl<B: BlockT> ChainSync<B> {
 									let justifications = block_data.block.justifications.or(
 										legacy_justification_mapping(block_data.block.justification)
 									);
+									// Blocks imported without state before the state sync
+									// completed are imported again, this time with their state.
+									let import_existing = imported_state && client
+										.block_status(&BlockId::Hash(block_data.block.hash))
+										.map_or(false, |s| s == BlockStatus::InChainPruned);
 									IncomingBlock {
 										hash: block_data.block.hash,
 										header: block_data.block.header,
@@ -843,7 +889,8 @@ impl<B: BlockT> ChainSync<B> {
 										justifications,
 										origin: block_data.origin,
 										allow_missing_state: true,
-										import_existing: false,
+										import_existing,
+										state: None,
 									}
 								}).collect()
 						}
@@ -866,6 +913,7 @@ impl<B: BlockT> ChainSync<B> {
 									origin: Some(who.clone()),
 									allow_missing_state: true,
 									import_existing: false,
+									state: None,
 								}
 							}).collect()
 						}
@@ -961,7 +1009,8 @@ impl<B: BlockT> ChainSync<B> {
 						}
 
 						| PeerSyncState::Available
-						| PeerSyncState::DownloadingJustification(..) => Vec::new()
+						| PeerSyncState::DownloadingJustification(..)
+						| PeerSyncState::DownloadingState => Vec::new()
 					}
 				} else {
 					// When request.is_none() this is a block announcement. Just accept blocks.
@@ -978,6 +1027,7 @@ impl<B: BlockT> ChainSync<B> {
 							origin: Some(who.clone()),
 							allow_missing_state: true,
 							import_existing: false,
+							state: None,
 						}
 					}).collect()
 				}
@@ -989,6 +1039,82 @@ impl<B: BlockT> ChainSync<B> {
 		Ok(self.validate_and_queue_blocks(new_blocks))
 	}
 
+	/// Get a state request, if any.
+	pub fn state_request(&mut self) -> Option<(PeerId, StateRequest)> {
+		if self.peers.iter().any(|(_, peer)| peer.state == PeerSyncState::DownloadingState) {
+			// Only one pending state request is allowed.
+			return None
+		}
+		if let Some(sync) = &self.state_sync {
+			if sync.is_complete() {
+				return None
+			}
+			for (id, peer) in self.peers.iter_mut() {
+				if peer.state.is_available() && peer.common_number >= sync.target_block_num() {
+					trace!(target: "sync", "New StateRequest for {}", id);
+					peer.state = PeerSyncState::DownloadingState;
+					let request = sync.next_request();
+					return Some((id.clone(), request))
+				}
+			}
+		}
+		None
+	}
+
+	/// Handle a response from the remote to a state request that we made.
+	///
+	/// Returns next request if any.
+	pub fn on_state_data(
+		&mut self,
+		who: &PeerId,
+		response: StateResponse,
+	) -> Result<OnStateData<B>, BadPeer> {
+		if let Some(peer) = self.peers.get_mut(who) {
+			if let PeerSyncState::DownloadingState = peer.state {
+				peer.state = PeerSyncState::Available;
+			}
+		}
+
+		let import_result = if let Some(sync) = &mut self.state_sync {
+			debug!(
+				target: "sync",
+				"Importing state data from {} with {} keys, {} proof bytes.",
+				who,
+				response.entries.len(),
+				response.proof.len(),
+			);
+			sync.import(response)
+		} else {
+			debug!(target: "sync", "Ignored obsolete state response from {}", who);
+			return Err(BadPeer(who.clone(), rep::NOT_REQUESTED));
+		};
+
+		match import_result {
+			StateImportResult::Import(hash, header, state) => {
+				let origin = BlockOrigin::NetworkInitialSync;
+				let block = IncomingBlock {
+					hash,
+					header: Some(header),
+					body: None,
+					justifications: None,
+					origin: None,
+					allow_missing_state: true,
+					import_existing: true,
+					state: Some(state),
+				};
+				debug!(target: "sync", "State sync is complete. Import is queued");
+				Ok(OnStateData::Import(origin, block))
+			}
+			StateImportResult::Continue(request) => {
+				Ok(OnStateData::Request(who.clone(), request))
+			}
+			StateImportResult::BadResponse => {
+				debug!(target: "sync", "Bad state data received from {}", who);
+				Err(BadPeer(who.clone(), rep::BAD_BLOCK))
+			}
+		}
+	}
+
 	fn validate_and_queue_blocks(
 		&mut self,
 		mut new_blocks: Vec<IncomingBlock<B>>,
@@ -1103,6 +1229,47 @@ impl<B: BlockT> ChainSync<B> {
 				has_error = true;
 			}
 
+			if let Some(sync) = &self.state_sync {
+				if sync.is_complete() && sync.target() == hash {
+					match &result {
+						Ok(BlockImportResult::ImportedKnown(number, _))
+						| Ok(BlockImportResult::ImportedUnknown(number, _, _)) => {
+							info!(
+								target: "sync",
+								"State sync is complete ({} KiB), restarting block sync.",
+								sync.progress() / 1024,
+							);
+							let number = *number;
+							self.state_sync = None;
+							self.imported_state = Some((hash, number));
+							self.mode = SyncMode::Full;
+							if self.role.is_full() {
+								self.required_block_attributes |= BlockAttributes::BODY;
+							}
+							output.extend(self.restart());
+						},
+						Err(e) => {
+							debug!(target: "sync", "Error importing state: {:?}", e);
+							self.state_sync = None;
+						},
+					}
+					continue;
+				}
+			}
+
+			if let Some((_, imported_number)) = self.imported_state {
+				if let Ok(BlockImportResult::ImportedUnknown(number, _, _)) = &result {
+					if *number > imported_number {
+						if *number >= self.client.info().best_number {
+							// All blocks imported without state were imported again.
+							self.imported_state = None;
+						} else {
+							self.imported_state = Some((hash, *number));
+						}
+					}
+				}
+			}
+
 			match result {
 				Ok(BlockImportResult::ImportedKnown(number, who)) => {
 					if let Some(peer) = who.and_then(|p| self.peers.get_mut(&p)) {
@@ -1209,6 +1376,29 @@ impl<B: BlockT> ChainSync<B> {
 			is_descendent_of(&**client, base, block)
 		});
 
+		if let SyncMode::Fast { skip_proofs } = self.mode {
+			if self.state_sync.is_none()
+				&& !self.peers.is_empty()
+				&& self.queue_blocks.is_empty()
+			{
+				// Finalized a recent block.
+				let mut heads: Vec<_> = self.peers.iter().map(|(_, peer)| peer.best_number).collect();
+				heads.sort();
+				let median = heads[heads.len() / 2];
+				if number + STATE_SYNC_FINALITY_THRESHOLD.saturated_into() >= median {
+					if let Ok(Some(header)) = self.client.header(BlockId::hash(hash.clone())) {
+						debug!(
+							target: "sync",
+							"Starting state sync for #{} ({})",
+							number,
+							hash,
+						);
+						self.state_sync = Some(StateSync::new(self.client.clone(), header, skip_proofs));
+					}
+				}
+			}
+		}
+
 		if let Err(err) = r {
 			warn!(
 				target: "sync",
@@ -1584,6 +1774,7 @@ impl<B: BlockT> ChainSync<B> {
 					origin: block_data.origin,
 					allow_missing_state: true,
 					import_existing: false,
+					state: None,
 				}
 			}).collect();
 		if !blocks.is_empty() {
@@ -1601,8 +1792,11 @@ impl<B: BlockT> ChainSync<B> {
 	) -> impl Iterator<Item = Result<(PeerId, BlockRequest<B>), BadPeer>> + 'a {
 		self.blocks.clear();
 		let info = self.client.info();
-		self.best_queued_hash = info.best_hash;
-		self.best_queued_number = info.best_number;
+		// Blocks above the block with the imported state have to be downloaded again.
+		let (best_hash, best_number) = self.imported_state
+			.unwrap_or((info.best_hash, info.best_number));
+		self.best_queued_hash = best_hash;
+		self.best_queued_number = best_number;
 		self.pending_requests.set_all();
 		debug!(target:"sync", "Restarted with {} ({})", self.best_queued_number, self.best_queued_hash);
 		let old_peers = std::mem::take(&mut self.peers);
@@ -1613,7 +1807,12 @@ impl<B: BlockT> ChainSync<B> {
 			match p.state {
 				PeerSyncState::DownloadingJustification(_) => {
 					// We make sure our commmon number is at least something we have.
-					p.common_number = info.best_number;
+					p.common_number = best_number;
+					self.peers.insert(id, p);
+					return None;
+				}
+				// A pending state request is answered independently of the block download.
+				PeerSyncState::DownloadingState => {
 					self.peers.insert(id, p);
 					return None;
 				}
@@ -1993,6 +2192,7 @@ mod test {
 			&info,
 			block_announce_validator,
 			1,
+			SyncMode::Full,
 		);
 
 		let (a1_hash, a1_number) = {
@@ -2064,6 +2264,7 @@ mod test {
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			1,
+			SyncMode::Full,
 		);
 
 		let peer_id1 = PeerId::random();
@@ -2239,6 +2440,7 @@ mod test {
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			5,
+			SyncMode::Full,
 		);
 
 		let peer_id1 = PeerId::random();
@@ -2353,6 +2555,7 @@ mod test {
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			5,
+			SyncMode::Full,
 		);
 
 		let peer_id1 = PeerId::random();
@@ -2475,6 +2678,7 @@ mod test {
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			5,
+			SyncMode::Full,
 		);
 
 		let finalized_block = blocks[MAX_BLOCKS_TO_LOOK_BACKWARDS as usize * 2 - 1].clone();
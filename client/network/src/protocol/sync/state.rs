@@ -0,0 +1,228 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Download of the state of a single block, in chunks, from the network.
+
+use std::sync::Arc;
+use codec::{Encode, Decode};
+use log::debug;
+use sp_consensus::ImportedState;
+use sp_core::storage::{ChildInfo, ChildType, PrefixedStorageKey, Storage, StorageChild};
+use sp_runtime::traits::{Block as BlockT, Header, NumberFor};
+use sc_client_api::StorageProof;
+use crate::chain::Client;
+use crate::schema::v1::{StateRequest, StateResponse};
+
+/// State sync support.
+pub struct StateSync<B: BlockT> {
+	target_block: B::Hash,
+	target_header: B::Header,
+	target_root: B::Hash,
+	/// The trie that is currently downloaded, `None` for the top trie.
+	current_child: Option<ChildInfo>,
+	/// Last key received for the current trie.
+	last_key: Vec<u8>,
+	/// Child tries discovered in the top trie that remain to be downloaded, with their roots.
+	pending_children: Vec<(ChildInfo, B::Hash)>,
+	/// Downloaded state.
+	state: Storage,
+	complete: bool,
+	client: Arc<dyn Client<B>>,
+	imported_bytes: u64,
+	skip_proof: bool,
+}
+
+/// Import state chunk result.
+pub enum ImportResult<B: BlockT> {
+	/// State is complete and ready for import.
+	Import(B::Hash, B::Header, ImportedState<B>),
+	/// Continue downloading.
+	Continue(StateRequest),
+	/// Bad state chunk.
+	BadResponse,
+}
+
+impl<B: BlockT> StateSync<B> {
+	/// Create a new instance.
+	pub fn new(client: Arc<dyn Client<B>>, target: B::Header, skip_proof: bool) -> Self {
+		StateSync {
+			client,
+			target_block: target.hash(),
+			target_root: target.state_root().clone(),
+			target_header: target,
+			current_child: None,
+			last_key: Vec::new(),
+			pending_children: Vec::new(),
+			state: Default::default(),
+			complete: false,
+			imported_bytes: 0,
+			skip_proof,
+		}
+	}
+
+	/// Validate and import a state response.
+	pub fn import(&mut self, response: StateResponse) -> ImportResult<B> {
+		if response.entries.is_empty() && response.proof.is_empty() && !response.complete {
+			debug!(target: "sync", "Bad state response");
+			return ImportResult::BadResponse;
+		}
+		if !self.skip_proof && response.proof.is_empty() {
+			debug!(target: "sync", "Missing proof");
+			return ImportResult::BadResponse;
+		}
+
+		let (values, complete) = if !self.skip_proof {
+			debug!(target: "sync", "Importing state from {} trie nodes", response.proof.len());
+			let proof = match StorageProof::decode(&mut response.proof.as_ref()) {
+				Ok(proof) => proof,
+				Err(e) => {
+					debug!(target: "sync", "Error decoding proof: {:?}", e);
+					return ImportResult::BadResponse;
+				}
+			};
+			let root = match &self.current_child {
+				None => self.target_root,
+				Some(child) => match self.pending_children.iter()
+					.find(|(info, _)| info == child)
+					.map(|(_, root)| *root)
+				{
+					Some(root) => root,
+					None => return ImportResult::BadResponse,
+				},
+			};
+			match self.client.verify_range_proof(
+				root,
+				proof,
+				self.current_child.as_ref(),
+				&self.last_key,
+			) {
+				Err(e) => {
+					debug!(target: "sync", "StateResponse failed proof verification: {:?}", e);
+					return ImportResult::BadResponse;
+				},
+				Ok(values) => values,
+			}
+		} else {
+			debug!(target: "sync", "Importing state from {} entries", response.entries.len());
+			let values = response.entries.into_iter().map(|e| (e.key, e.value)).collect();
+			(values, response.complete)
+		};
+
+		if values.is_empty() && !complete {
+			debug!(target: "sync", "Empty incomplete state response");
+			return ImportResult::BadResponse;
+		}
+
+		if let Some((last_key, _)) = values.last() {
+			self.last_key = last_key.clone();
+		}
+		for (key, value) in values {
+			self.imported_bytes += (key.len() + value.len()) as u64;
+			match &self.current_child {
+				None => {
+					if let Some(child_info) = Self::default_child_info(&key) {
+						let root = match Decode::decode(&mut value.as_ref()) {
+							Ok(root) => root,
+							Err(e) => {
+								debug!(target: "sync", "Error decoding child trie root: {:?}", e);
+								return ImportResult::BadResponse;
+							}
+						};
+						self.pending_children.push((child_info, root));
+					} else {
+						self.state.top.insert(key, value);
+					}
+				},
+				Some(child_info) => {
+					self.state.children_default
+						.entry(child_info.storage_key().to_vec())
+						.or_insert_with(|| StorageChild {
+							data: Default::default(),
+							child_info: child_info.clone(),
+						})
+						.data
+						.insert(key, value);
+				},
+			}
+		}
+
+		if complete {
+			// Move on to the next trie.
+			if let Some(child_info) = self.current_child.take() {
+				self.pending_children.retain(|(info, _)| info != &child_info);
+			}
+			self.current_child = self.pending_children.first().map(|(info, _)| info.clone());
+			self.last_key.clear();
+			self.complete = self.current_child.is_none();
+		}
+
+		if self.complete {
+			ImportResult::Import(
+				self.target_block,
+				self.target_header.clone(),
+				ImportedState {
+					block: self.target_block,
+					state: std::mem::take(&mut self.state),
+				},
+			)
+		} else {
+			ImportResult::Continue(self.next_request())
+		}
+	}
+
+	/// Produce next state request.
+	pub fn next_request(&self) -> StateRequest {
+		StateRequest {
+			block: self.target_block.encode(),
+			start: self.last_key.clone(),
+			child_trie_key: self.current_child.as_ref()
+				.map(|child| child.storage_key().to_vec())
+				.unwrap_or_default(),
+			no_proof: self.skip_proof,
+		}
+	}
+
+	/// Check if the state is complete.
+	pub fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// Returns target block number.
+	pub fn target_block_num(&self) -> NumberFor<B> {
+		*self.target_header.number()
+	}
+
+	/// Returns target block hash.
+	pub fn target(&self) -> B::Hash {
+		self.target_block
+	}
+
+	/// Returns state sync estimated progress, as a number of bytes downloaded so far.
+	pub fn progress(&self) -> u64 {
+		self.imported_bytes
+	}
+
+	/// Returns the child info of the default child trie referenced by the given top trie key.
+	fn default_child_info(key: &[u8]) -> Option<ChildInfo> {
+		let prefixed_key = PrefixedStorageKey::new(key.to_vec());
+		match ChildType::from_prefixed_key(&prefixed_key) {
+			Some((ChildType::ParentKeyId, storage_key)) => Some(ChildInfo::new_default(storage_key)),
+			None => None,
+		}
+	}
+}
@@ -102,6 +102,8 @@ const UNSUPPORTED_KEY_TYPE: i64 = POOL_INVALID_TX + 7;
 /// The transaction was not included to the pool since it is unactionable,
 /// it is not propagable and the local node does not author blocks.
 const POOL_UNACTIONABLE: i64 = POOL_INVALID_TX + 8;
+/// The sender already has too many transactions in the pool.
+const POOL_TOO_MANY_FROM_SENDER: i64 = POOL_INVALID_TX + 9;
 
 impl From<Error> for rpc::Error {
 	fn from(e: Error) -> Self {
@@ -169,6 +171,13 @@ impl From<Error> for rpc::Error {
 					 the local node does not author blocks".into(),
 				),
 			},
+			Error::Pool(PoolError::TooManyFromSender) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(POOL_TOO_MANY_FROM_SENDER),
+				message: "Too Many From Sender".into(),
+				data: Some(
+					"The sender already has the maximal number of transactions in the pool".into(),
+				),
+			},
 			Error::UnsupportedKeyType => rpc::Error {
 				code: rpc::ErrorCode::ServerError(UNSUPPORTED_KEY_TYPE),
 				message: "Unknown key type crypto" .into(),
@@ -18,9 +18,11 @@
 
 //! Substrate state API.
 
+use jsonrpc_core::Result as RpcResult;
 use jsonrpc_derive::rpc;
-use sp_core::storage::{StorageKey, PrefixedStorageKey, StorageData};
-use crate::state::error::FutureResult;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use sp_core::storage::{StorageKey, PrefixedStorageKey, StorageData, StorageChangeSet};
+use crate::state::{error::FutureResult, ReadProof};
 
 pub use self::gen_client::Client as ChildStateClient;
 
@@ -68,4 +70,45 @@ pub trait ChildStateApi<Hash> {
 		key: StorageKey,
 		hash: Option<Hash>
 	) -> FutureResult<Option<u64>>;
+
+	/// Returns proof of storage for child key entries at a specific block's state.
+	///
+	/// The proof contains the root of the child trie in the main trie as well as the
+	/// requested `keys` in the child trie.
+	#[rpc(name = "childstate_getReadProof")]
+	fn read_child_proof(
+		&self,
+		child_storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+		hash: Option<Hash>,
+	) -> FutureResult<ReadProof<Hash>>;
+
+	/// New child storage subscription.
+	///
+	/// Notifies about changes of the given `keys` in the child trie identified by
+	/// `child_storage_key`. All keys of the child trie are watched when `keys` is `None`.
+	#[pubsub(
+		subscription = "childstate_storage",
+		subscribe,
+		name = "childstate_subscribeStorage"
+	)]
+	fn subscribe_storage(
+		&self,
+		metadata: Self::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Hash>>,
+		child_storage_key: PrefixedStorageKey,
+		keys: Option<Vec<StorageKey>>,
+	);
+
+	/// Unsubscribe from child storage subscription.
+	#[pubsub(
+		subscription = "childstate_storage",
+		unsubscribe,
+		name = "childstate_unsubscribeStorage"
+	)]
+	fn unsubscribe_storage(
+		&self,
+		metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
 }
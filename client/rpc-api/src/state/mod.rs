@@ -136,4 +136,29 @@ pub trait StateApi<Hash> {
 	fn unsubscribe_storage(
 		&self, metadata: Option<Self::Metadata>, id: SubscriptionId
 	) -> RpcResult<bool>;
+
+	/// Re-execute the given block and return the spans and events of its execution.
+	///
+	/// The block is executed on top of the state of its parent. Only unsafe to call.
+	///
+	/// ## Arguments
+	///
+	/// - `block`: hash of the block to trace.
+	/// - `targets`: comma separated list of targets to record spans and events for, e.g.
+	///   `pallet,frame,state`. A target may be suffixed with a level, e.g. `pallet=debug`.
+	///   Defaults to `pallet,frame,state`. An empty string records all targets.
+	/// - `storage_keys`: comma separated list of hex encoded storage key prefixes. Only the
+	///   storage accesses of keys that start with one of these prefixes are returned.
+	///   Defaults to returning all storage accesses.
+	///
+	/// Spans of the runtime are only reported if the runtime was compiled with the
+	/// `with-tracing` feature. Every span and event reports the index of the extrinsic
+	/// that was executing while it was recorded.
+	#[rpc(name = "state_traceBlock")]
+	fn trace_block(
+		&self,
+		block: Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::BlockTrace>;
 }
@@ -48,6 +48,15 @@ impl SubscriptionTaskExecutor {
 	pub fn new(spawn: impl SpawnNamed + 'static) -> Self {
 		Self(Arc::new(spawn))
 	}
+
+	/// Spawn a task that may block, e.g. re-executing a block.
+	pub fn spawn_blocking(
+		&self,
+		name: &'static str,
+		future: futures::future::BoxFuture<'static, ()>,
+	) {
+		self.0.spawn_blocking(name, future)
+	}
 }
 
 impl Executor<Box<dyn Future<Item = (), Error = ()> + Send>> for SubscriptionTaskExecutor {
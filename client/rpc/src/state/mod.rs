@@ -37,10 +37,13 @@ use sp_runtime::traits::Block as BlockT;
 use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
 
 use self::error::{Error, FutureResult};
+use crate::SubscriptionTaskExecutor;
 
 pub use sc_rpc_api::state::*;
 pub use sc_rpc_api::child_state::*;
-use sc_client_api::{ExecutorProvider, StorageProvider, BlockchainEvents, Backend, ProofProvider};
+use sc_client_api::{
+	ExecutorProvider, StorageProvider, BlockchainEvents, Backend, BlockBackend, ProofProvider,
+};
 use sp_blockchain::{HeaderMetadata, HeaderBackend};
 
 const STORAGE_KEYS_PAGED_MAX_COUNT: u32 = 1000;
@@ -165,12 +168,21 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 		_meta: Option<crate::Metadata>,
 		id: SubscriptionId,
 	) -> RpcResult<bool>;
+
+	/// Re-execute the given block and return the spans and events of its execution.
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::BlockTrace>;
 }
 
 /// Create new state API that works on full node.
 pub fn new_full<BE, Block: BlockT, Client>(
 	client: Arc<Client>,
 	subscriptions: SubscriptionManager,
+	executor: SubscriptionTaskExecutor,
 	deny_unsafe: DenyUnsafe,
 ) -> (State<Block, Client>, ChildState<Block, Client>)
 	where
@@ -178,13 +190,16 @@ pub fn new_full<BE, Block: BlockT, Client>(
 		BE: Backend<Block> + 'static,
 		Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block>
 			+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
-			+ CallApiAt<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+			+ CallApiAt<Block> + ProvideRuntimeApi<Block> + BlockBackend<Block>
+			+ Send + Sync + 'static,
 		Client::Api: Metadata<Block>,
 {
-	let child_backend = Box::new(
-		self::state_full::FullState::new(client.clone(), subscriptions.clone())
-	);
-	let backend = Box::new(self::state_full::FullState::new(client, subscriptions));
+	let child_backend = Box::new(self::state_full::FullState::new(
+		client.clone(),
+		subscriptions.clone(),
+		executor.clone(),
+	));
+	let backend = Box::new(self::state_full::FullState::new(client, subscriptions, executor));
 	(State { backend, deny_unsafe }, ChildState { backend: child_backend })
 }
 
@@ -346,6 +361,19 @@ impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
 	) -> RpcResult<bool> {
 		self.backend.unsubscribe_runtime_version(meta, id)
 	}
+
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::BlockTrace> {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return Box::new(result(Err(err.into())))
+		}
+
+		self.backend.trace_block(block, targets, storage_keys)
+	}
 }
 
 /// Child state backend API.
@@ -389,6 +417,29 @@ pub trait ChildStateBackend<Block: BlockT, Client>: Send + Sync + 'static
 		Box::new(self.storage(block, storage_key, key)
 			.map(|x| x.map(|x| x.0.len() as u64)))
 	}
+	/// Returns proof of storage for child key entries at a specific block's state.
+	fn read_child_proof(
+		&self,
+		block: Option<Block::Hash>,
+		storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+	) -> FutureResult<ReadProof<Block::Hash>>;
+
+	/// New child storage subscription.
+	fn subscribe_storage(
+		&self,
+		_meta: crate::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		storage_key: PrefixedStorageKey,
+		keys: Option<Vec<StorageKey>>,
+	);
+
+	/// Unsubscribe from child storage subscription.
+	fn unsubscribe_storage(
+		&self,
+		_meta: Option<crate::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool>;
 }
 
 /// Child state API with subscriptions support.
@@ -438,6 +489,32 @@ impl<Block, Client> ChildStateApi<Block::Hash> for ChildState<Block, Client>
 	) -> FutureResult<Option<u64>> {
 		self.backend.storage_size(block, storage_key, key)
 	}
+	fn read_child_proof(
+		&self,
+		child_storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+		block: Option<Block::Hash>,
+	) -> FutureResult<ReadProof<Block::Hash>> {
+		self.backend.read_child_proof(block, child_storage_key, keys)
+	}
+
+	fn subscribe_storage(
+		&self,
+		meta: Self::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		storage_key: PrefixedStorageKey,
+		keys: Option<Vec<StorageKey>>,
+	) {
+		self.backend.subscribe_storage(meta, subscriber, storage_key, keys);
+	}
+
+	fn unsubscribe_storage(
+		&self,
+		meta: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		self.backend.unsubscribe_storage(meta, id)
+	}
 }
 
 fn client_err(err: sp_blockchain::Error) -> Error {
@@ -21,7 +21,10 @@
 use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::ops::Range;
-use futures::{future, StreamExt as _, TryStreamExt as _};
+use futures::{
+	channel::oneshot, future, FutureExt as _, StreamExt as _, TryFutureExt as _,
+	TryStreamExt as _,
+};
 use log::warn;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId, manager::SubscriptionManager};
 use rpc::{Result as RpcResult, futures::{stream, Future, Sink, Stream, future::result}};
@@ -42,8 +45,11 @@ use sp_runtime::{
 use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
 
 use super::{StateBackend, ChildStateBackend, error::{FutureResult, Error, Result}, client_err};
+use crate::SubscriptionTaskExecutor;
 use std::marker::PhantomData;
-use sc_client_api::{CallExecutor, StorageProvider, ExecutorProvider, ProofProvider};
+use sc_client_api::{
+	BlockBackend, CallExecutor, StorageProvider, ExecutorProvider, ProofProvider,
+};
 
 /// Ranges to query in state_queryStorage.
 struct QueryStorageRange<Block: BlockT> {
@@ -63,6 +69,7 @@ struct QueryStorageRange<Block: BlockT> {
 pub struct FullState<BE, Block: BlockT, Client> {
 	client: Arc<Client>,
 	subscriptions: SubscriptionManager,
+	executor: SubscriptionTaskExecutor,
 	_phantom: PhantomData<(BE, Block)>
 }
 
@@ -74,8 +81,12 @@ impl<BE, Block: BlockT, Client> FullState<BE, Block, Client>
 		Block: BlockT + 'static,
 {
 	/// Create new state API backend for full nodes.
-	pub fn new(client: Arc<Client>, subscriptions: SubscriptionManager) -> Self {
-		Self { client, subscriptions, _phantom: PhantomData }
+	pub fn new(
+		client: Arc<Client>,
+		subscriptions: SubscriptionManager,
+		executor: SubscriptionTaskExecutor,
+	) -> Self {
+		Self { client, subscriptions, executor, _phantom: PhantomData }
 	}
 
 	/// Returns given block hash or best block hash if None is passed.
@@ -223,7 +234,7 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 	BE: Backend<Block> + 'static,
 	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
-		+ CallApiAt<Block> + ProvideRuntimeApi<Block>
+		+ CallApiAt<Block> + ProvideRuntimeApi<Block> + BlockBackend<Block>
 		+ Send + Sync + 'static,
 	Client::Api: Metadata<Block>,
 {
@@ -527,12 +538,40 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 	) -> RpcResult<bool> {
 		Ok(self.subscriptions.cancel(id))
 	}
+
+	fn trace_block(
+		&self,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::BlockTrace> {
+		let block_executor = sc_tracing::block::BlockExecutor::new(
+			self.client.clone(),
+			block,
+			targets,
+			storage_keys,
+		);
+		// Re-executing the block takes long, so it must not block the RPC server.
+		let (tx, rx) = oneshot::channel();
+		self.executor.spawn_blocking("substrate-rpc-trace-block", Box::pin(async move {
+			let _ = tx.send(block_executor.trace_block());
+		}));
+		Box::new(rx
+			.map(|res| match res {
+				Ok(trace) => trace.map_err(|e| Error::Client(Box::new(e))),
+				Err(e) => Err(Error::Client(Box::new(e))),
+			})
+			.boxed()
+			.compat()
+		)
+	}
 }
 
 impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block, Client> where
 	Block: BlockT + 'static,
 	BE: Backend<Block> + 'static,
-	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + HeaderBackend<Block>
+	Client: ExecutorProvider<Block> + StorageProvider<Block, BE> + ProofProvider<Block>
+		+ HeaderBackend<Block>
 		+ HeaderMetadata<Block, Error = sp_blockchain::Error> + BlockchainEvents<Block>
 		+ CallApiAt<Block> + ProvideRuntimeApi<Block>
 		+ Send + Sync + 'static,
@@ -603,6 +642,106 @@ impl<BE, Block, Client> ChildStateBackend<Block, Client> for FullState<BE, Block
 				})
 				.map_err(client_err)))
 	}
+	fn read_child_proof(
+		&self,
+		block: Option<Block::Hash>,
+		storage_key: PrefixedStorageKey,
+		keys: Vec<StorageKey>,
+	) -> FutureResult<ReadProof<Block::Hash>> {
+		Box::new(result(
+			self.block_or_best(block)
+				.and_then(|block| {
+					let child_info = match ChildType::from_prefixed_key(&storage_key) {
+						Some((ChildType::ParentKeyId, storage_key)) => ChildInfo::new_default(storage_key),
+						None => return Err(sp_blockchain::Error::InvalidChildStorageKey),
+					};
+					self.client
+						.read_child_proof(
+							&BlockId::Hash(block),
+							&child_info,
+							&mut keys.iter().map(|key| key.0.as_ref()),
+						)
+						.map(|proof| proof.iter_nodes().map(|node| node.into()).collect())
+						.map(|proof| ReadProof { at: block, proof })
+				})
+				.map_err(client_err),
+		))
+	}
+
+	fn subscribe_storage(
+		&self,
+		_meta: crate::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		storage_key: PrefixedStorageKey,
+		keys: Option<Vec<StorageKey>>,
+	) {
+		let child_key = match ChildType::from_prefixed_key(&storage_key) {
+			Some((ChildType::ParentKeyId, storage_key)) => StorageKey(storage_key.to_vec()),
+			None => {
+				let err = client_err(sp_blockchain::Error::InvalidChildStorageKey);
+				let _ = subscriber.reject(err.into());
+				return;
+			},
+		};
+		// Only listen for the changes of the child trie: no top-level keys are watched.
+		let child_filter = [(child_key.clone(), keys.clone())];
+		let stream = match self.client.storage_changes_notification_stream(
+			Some(&[][..]),
+			Some(&child_filter[..]),
+		) {
+			Ok(stream) => stream,
+			Err(err) => {
+				let _ = subscriber.reject(client_err(err).into());
+				return;
+			},
+		};
+
+		// initial values
+		let initial = stream::iter_result(keys
+			.map(|keys| {
+				let block = self.client.info().best_hash;
+				let changes = keys
+					.into_iter()
+					.map(|key| ChildStateBackend::storage(
+						self,
+						Some(block.clone()),
+						storage_key.clone(),
+						key.clone(),
+					)
+						.map(|val| (key.clone(), val))
+						.wait()
+						.unwrap_or_else(|_| (key, None))
+					)
+					.collect();
+				vec![Ok(Ok(StorageChangeSet { block, changes }))]
+			}).unwrap_or_default());
+
+		self.subscriptions.add(subscriber, |sink| {
+			let stream = stream
+				.map(move |(block, changes)| Ok::<_, ()>(Ok(StorageChangeSet {
+					block,
+					changes: changes.iter()
+						.filter_map(|(o_sk, k, v)| if o_sk == Some(&child_key) {
+							Some((k.clone(), v.cloned()))
+						} else { None }).collect(),
+				})))
+				.compat();
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(initial.chain(stream))
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_storage(
+		&self,
+		_meta: Option<crate::Metadata>,
+		id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
 }
 
 /// Splits passed range into two subranges where:
@@ -297,6 +297,15 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
 	}
 
+	fn trace_block(
+		&self,
+		_block: Block::Hash,
+		_targets: Option<String>,
+		_storage_keys: Option<String>,
+	) -> FutureResult<sp_rpc::tracing::BlockTrace> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
 	fn subscribe_storage(
 		&self,
 		_meta: crate::Metadata,
@@ -533,6 +542,32 @@ impl<Block, F, Client> ChildStateBackend<Block, Client> for LightState<Block, F,
 			)
 		)
 	}
+	fn read_child_proof(
+		&self,
+		_block: Option<Block::Hash>,
+		_storage_key: PrefixedStorageKey,
+		_keys: Vec<StorageKey>,
+	) -> FutureResult<ReadProof<Block::Hash>> {
+		Box::new(result(Err(client_err(ClientError::NotAvailableOnLightClient))))
+	}
+
+	fn subscribe_storage(
+		&self,
+		_meta: crate::Metadata,
+		subscriber: Subscriber<StorageChangeSet<Block::Hash>>,
+		_storage_key: PrefixedStorageKey,
+		_keys: Option<Vec<StorageKey>>,
+	) {
+		let _ = subscriber.reject(client_err(ClientError::NotAvailableOnLightClient).into());
+	}
+
+	fn unsubscribe_storage(
+		&self,
+		_meta: Option<crate::Metadata>,
+		_id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(false)
+	}
 }
 
 /// Resolve header by hash.
@@ -34,7 +34,7 @@ use substrate_test_runtime_client::{
 };
 use sc_rpc_api::DenyUnsafe;
 use sp_runtime::generic::BlockId;
-use crate::testing::TaskExecutor;
+use crate::{testing::TaskExecutor, SubscriptionTaskExecutor};
 use futures::{executor, compat::Future01CompatExt};
 
 const STORAGE_KEY: &[u8] = b"child";
@@ -62,6 +62,7 @@ fn should_return_storage() {
 	let (client, child) = new_full(
 		Arc::new(client),
 		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
 		DenyUnsafe::No,
 	);
 	let key = StorageKey(KEY.to_vec());
@@ -104,6 +105,7 @@ fn should_return_child_storage() {
 	let (_client, child) = new_full(
 		client,
 		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
 		DenyUnsafe::No,
 	);
 	let child_key = prefixed_storage_key();
@@ -136,6 +138,56 @@ fn should_return_child_storage() {
 	);
 }
 
+#[test]
+fn should_return_child_read_proof() {
+	use sp_runtime::traits::{BlakeTwo256, Header as _};
+	use sp_state_machine::{StorageProof, read_child_proof_check, read_child_root_proof_check};
+
+	let child_info = ChildInfo::new_default(STORAGE_KEY);
+	let client = Arc::new(substrate_test_runtime_client::TestClientBuilder::new()
+		.add_child_storage(&child_info, "key", vec![42_u8])
+		.build());
+	let genesis_hash = client.genesis_hash();
+	let state_root = *client.header(&BlockId::Hash(genesis_hash)).unwrap().unwrap().state_root();
+	let (_client, child) = new_full(
+		client,
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
+		DenyUnsafe::No,
+	);
+	let key = StorageKey(b"key".to_vec());
+
+	let read_proof = child.read_child_proof(
+		prefixed_storage_key(),
+		vec![key.clone()],
+		Some(genesis_hash).into(),
+	).wait().unwrap();
+	assert_eq!(read_proof.at, genesis_hash);
+
+	let proof = StorageProof::new(read_proof.proof.into_iter().map(|node| node.0).collect());
+	assert!(read_child_root_proof_check::<BlakeTwo256>(
+		state_root,
+		proof.clone(),
+		&child_info,
+	).unwrap().is_some());
+	let values = read_child_proof_check::<BlakeTwo256, _>(
+		state_root,
+		proof,
+		&child_info,
+		&[key.0.clone()],
+	).unwrap();
+	assert_eq!(values.get(&key.0), Some(&Some(vec![42_u8])));
+
+	assert_matches!(
+		child.read_child_proof(
+			PrefixedStorageKey::new(b"not_a_child_trie".to_vec()),
+			vec![key],
+			None,
+		).wait(),
+		Err(Error::Client(_))
+	);
+}
+
 #[test]
 fn should_call_contract() {
 	let client = Arc::new(substrate_test_runtime_client::new());
@@ -143,6 +195,7 @@ fn should_call_contract() {
 	let (client, _child) = new_full(
 		client,
 		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
 		DenyUnsafe::No,
 	);
 
@@ -161,6 +214,7 @@ fn should_notify_about_storage_changes() {
 		let (api, _child) = new_full(
 			client.clone(),
 			SubscriptionManager::new(Arc::new(TaskExecutor)),
+			SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
 			DenyUnsafe::No,
 		);
 
@@ -199,6 +253,7 @@ fn should_send_initial_storage_changes_and_notifications() {
 		let (api, _child) = new_full(
 			client.clone(),
 			SubscriptionManager::new(Arc::new(TaskExecutor)),
+			SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
 			DenyUnsafe::No,
 		);
 
@@ -235,12 +290,98 @@ fn should_send_initial_storage_changes_and_notifications() {
 	assert_eq!(executor::block_on(next.into_future().compat()).unwrap().0, None);
 }
 
+#[test]
+fn should_send_initial_child_storage_and_filter_notifications() {
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+
+	{
+		let child_info = ChildInfo::new_default(STORAGE_KEY);
+		let mut client = Arc::new(substrate_test_runtime_client::TestClientBuilder::new()
+			.add_child_storage(&child_info, "key", vec![42_u8])
+			.build());
+		let (_api, child) = new_full(
+			client.clone(),
+			SubscriptionManager::new(Arc::new(TaskExecutor)),
+			SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
+			DenyUnsafe::No,
+		);
+
+		child.subscribe_storage(
+			Default::default(),
+			subscriber,
+			prefixed_storage_key(),
+			Some(vec![StorageKey(b"key".to_vec())]),
+		);
+
+		// assert id assigned
+		assert!(matches!(
+			executor::block_on(id.compat()),
+			Ok(Ok(SubscriptionId::String(_)))
+		));
+
+		// neither the transfer nor the writes to other keys and child tries are watched
+		let mut builder = client.new_block(Default::default()).unwrap();
+		builder.push_transfer(runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 42,
+			nonce: 0,
+		}).unwrap();
+		builder.push_child_storage_change(STORAGE_KEY.to_vec(), b"key2".to_vec(), Some(vec![1]))
+			.unwrap();
+		builder.push_child_storage_change(b"other".to_vec(), b"key".to_vec(), Some(vec![2]))
+			.unwrap();
+		let block = builder.build().unwrap().block;
+		executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+
+		// the watched key is changed
+		let mut builder = client.new_block(Default::default()).unwrap();
+		builder.push_child_storage_change(STORAGE_KEY.to_vec(), b"key".to_vec(), Some(vec![43]))
+			.unwrap();
+		let block = builder.build().unwrap().block;
+		executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+	}
+
+	// assert initial values sent to transport
+	let (notification, next) = executor::block_on(transport.into_future().compat()).unwrap();
+	assert!(notification.unwrap().contains("0x2a"));
+	// assert only the change of the watched key is sent
+	let (notification, next) = executor::block_on(next.into_future().compat()).unwrap();
+	let notification = notification.unwrap();
+	assert!(notification.contains("0x2b"));
+	assert!(!notification.contains("0x6b657932"));
+	// no more notifications on this channel
+	assert_eq!(executor::block_on(next.into_future().compat()).unwrap().0, None);
+}
+
+#[test]
+fn should_reject_invalid_child_storage_subscription() {
+	let (subscriber, id, _transport) = Subscriber::new_test("test");
+	let client = Arc::new(substrate_test_runtime_client::new());
+	let (_api, child) = new_full(
+		client,
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
+		DenyUnsafe::No,
+	);
+
+	child.subscribe_storage(
+		Default::default(),
+		subscriber,
+		PrefixedStorageKey::new(b"not_a_child_trie".to_vec()),
+		None,
+	);
+
+	assert!(matches!(executor::block_on(id.compat()), Ok(Err(_))));
+}
+
 #[test]
 fn should_query_storage() {
 	fn run_tests(mut client: Arc<TestClient>, has_changes_trie_config: bool) {
 		let (api, _child) = new_full(
 			client.clone(),
 			SubscriptionManager::new(Arc::new(TaskExecutor)),
+			SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
 			DenyUnsafe::No,
 		);
 
@@ -456,12 +597,55 @@ fn should_split_ranges() {
 }
 
 
+#[test]
+fn should_trace_block() {
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_transfer(runtime::Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Ferdie.into(),
+		amount: 42,
+		nonce: 0,
+	}).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = block.header.hash();
+	executor::block_on(client.import(BlockOrigin::Own, block)).unwrap();
+
+	let (unsafe_api, _child) = new_full(
+		client.clone(),
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
+		DenyUnsafe::Yes,
+	);
+	assert_matches!(
+		unsafe_api.trace_block(block_hash, None, None).wait(),
+		Err(Error::UnsafeRpcCalled(_))
+	);
+
+	let (api, _child) = new_full(
+		client.clone(),
+		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
+		DenyUnsafe::No,
+	);
+	let trace = api.trace_block(block_hash, Some("state".into()), None).wait().unwrap();
+	assert_eq!(trace.block_hash, format!("{:?}", block_hash));
+	assert!(!trace.events.is_empty());
+	assert!(trace.events.iter().all(|event| event.target == "state"));
+
+	assert_matches!(
+		api.trace_block(H256::repeat_byte(0xaa), None, None).wait(),
+		Err(Error::Client(_))
+	);
+}
+
 #[test]
 fn should_return_runtime_version() {
 	let client = Arc::new(substrate_test_runtime_client::new());
 	let (api, _child) = new_full(
 		client.clone(),
 		SubscriptionManager::new(Arc::new(TaskExecutor)),
+		SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
 		DenyUnsafe::No,
 	);
 
@@ -489,6 +673,7 @@ fn should_notify_on_runtime_version_initially() {
 		let (api, _child) = new_full(
 			client.clone(),
 			SubscriptionManager::new(Arc::new(TaskExecutor)),
+			SubscriptionTaskExecutor::new(sp_core::testing::TaskExecutor::new()),
 			DenyUnsafe::No,
 		);
 
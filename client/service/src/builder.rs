@@ -43,6 +43,7 @@ use log::info;
 use sc_network::config::{Role, OnDemand};
 use sc_network::NetworkService;
 use sc_network::block_request_handler::{self, BlockRequestHandler};
+use sc_network::state_request_handler::{self, StateRequestHandler};
 use sc_network::light_client_requests::{self, handler::LightClientRequestHandler};
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{
@@ -314,10 +315,11 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 		TaskManager::new(config.task_executor.clone(), registry)?
 	};
 
-	let executor = NativeExecutor::<TExecDisp>::new(
+	let executor = NativeExecutor::<TExecDisp>::new_with_cache_path(
 		config.wasm_method,
 		config.default_heap_pages,
 		config.max_runtime_instances,
+		config.wasm_artifacts_path(),
 	);
 
 	let chain_spec = &config.chain_spec;
@@ -343,6 +345,14 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 
 		let backend = new_db_backend(db_config)?;
 
+		// Removes the state left from a previous pruning mode, if there is any.
+		let migration_backend = backend.clone();
+		task_manager.spawn_handle().spawn_blocking("state-db-migration", async move {
+			if let Err(e) = migration_backend.prune_migrated_state() {
+				log::warn!("Failed to remove the state left from the previous pruning mode: {}", e);
+			}
+		});
+
 		let extensions = sc_client_api::execution_extensions::ExecutionExtensions::new(
 			config.execution_strategies.clone(),
 			Some(keystore_container.sync_keystore()),
@@ -391,10 +401,11 @@ pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 		TaskManager::new(config.task_executor.clone(), registry)?
 	};
 
-	let executor = NativeExecutor::<TExecDisp>::new(
+	let executor = NativeExecutor::<TExecDisp>::new_with_cache_path(
 		config.wasm_method,
 		config.default_heap_pages,
 		config.max_runtime_instances,
+		config.wasm_artifacts_path(),
 	);
 
 	let db_storage = {
@@ -791,6 +802,7 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 		let (state, child_state) = sc_rpc::state::new_full(
 			client.clone(),
 			subscriptions.clone(),
+			task_executor.clone(),
 			deny_unsafe,
 		);
 		(chain, state, child_state)
@@ -900,6 +912,23 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		}
 	};
 
+	let state_request_protocol_config = {
+		if matches!(config.role, Role::Light) {
+			// Allow outgoing requests but deny incoming requests.
+			state_request_handler::generate_protocol_config(&protocol_id)
+		} else {
+			// Allow both outgoing and incoming requests.
+			let (handler, protocol_config) = StateRequestHandler::new(
+				&protocol_id,
+				client.clone(),
+				config.network.default_peers_set.in_peers as usize
+					+ config.network.default_peers_set.out_peers as usize,
+			);
+			spawn_handle.spawn("state_request_handler", handler.run());
+			protocol_config
+		}
+	};
+
 	let light_client_request_protocol_config = {
 		if matches!(config.role, Role::Light) {
 			// Allow outgoing requests but deny incoming requests.
@@ -938,6 +967,7 @@ pub fn build_network<TBl, TExPool, TImpQu, TCl>(
 		block_announce_validator,
 		metrics_registry: config.prometheus_config.as_ref().map(|config| config.registry.clone()),
 		block_request_protocol_config,
+		state_request_protocol_config,
 		light_client_request_protocol_config,
 	};
 
@@ -0,0 +1,38 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{config::Configuration, error::Error};
+use log::info;
+use sp_runtime::traits::Block as BlockT;
+
+/// Switch the state pruning mode of the database to the one in `config`.
+///
+/// The state that is no longer kept is removed in the background once the node is started.
+pub fn migrate_state_pruning<B: BlockT>(config: &Configuration) -> Result<(), Error> {
+	let db_settings = sc_client_db::DatabaseSettings {
+		state_cache_size: config.state_cache_size,
+		state_cache_child_ratio: config.state_cache_child_ratio.map(|v| (v, 100)),
+		state_pruning: config.state_pruning.clone(),
+		source: config.database.clone(),
+		keep_blocks: config.keep_blocks.clone(),
+		transaction_storage: config.transaction_storage.clone(),
+	};
+	sc_client_db::migrate_state_pruning::<B>(&db_settings)?;
+	info!("Switched the state pruning mode to {:?}", config.state_pruning);
+	Ok(())
+}
@@ -0,0 +1,34 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{config::Configuration, error::Error};
+use log::info;
+use std::path::PathBuf;
+
+/// Compile the runtime `code` and store the artifact in the directory of the node.
+///
+/// The node loads the runtime from the artifact instead of compiling it when it needs the
+/// runtime, e.g. after a runtime upgrade. Returns the path of the artifact.
+pub fn precompile_wasm(config: &Configuration, code: &[u8]) -> Result<PathBuf, Error> {
+	let cache_path = config.wasm_artifacts_path()
+		.ok_or_else(|| Error::Other("A base path is required to store compiled runtimes".into()))?;
+	let path = sc_executor::precompile_wasm_runtime(code, &cache_path)
+		.map_err(|e| Error::Other(format!("Failed to compile the runtime: {:?}", e)))?;
+	info!("Stored the compiled runtime at {}", path.display());
+	Ok(path)
+}
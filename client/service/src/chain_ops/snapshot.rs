@@ -0,0 +1,212 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Binary snapshots of the state at a finalized block.
+//!
+//! A snapshot starts with [`SNAPSHOT_MAGIC`] and the format version, followed by the SCALE
+//! encoded [`SnapshotHeader`] and the [`SnapshotEntry`]s with the key-value pairs of the state,
+//! which are written and read one at a time. Importing it into an empty database makes the
+//! block the finalized base of the chain, without any of its ancestors.
+//!
+//! Auxiliary data of consensus engines is not part of a snapshot. Engines that need data about
+//! the blocks before the first one they import, like the BABE epoch changes or the GRANDPA
+//! authority set, can't continue from a snapshot, so nodes using them must not import one.
+
+use crate::error::Error;
+use codec::{Decode, Encode, IoReader as CodecIoReader};
+use log::info;
+use sc_client_api::{BlockBackend, StorageProvider, UsageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::{
+	BlockImport, BlockImportParams, BlockOrigin, ForkChoiceStrategy, ImportResult, ImportedState,
+};
+use sp_core::storage::{well_known_keys, ChildInfo, Storage, StorageChild, StorageKey};
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
+use sp_runtime::Justifications;
+use std::{collections::HashMap, io::{Read, Write}, sync::Arc};
+
+/// Bytes every snapshot starts with.
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"snapshot";
+
+/// Version of the snapshot format written by [`export_snapshot`].
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The block of a snapshot, which is followed by its [`SnapshotEntry`]s.
+#[derive(Encode, Decode)]
+pub struct SnapshotHeader<B: BlockT> {
+	/// Hash of the genesis block of the chain.
+	pub genesis_hash: B::Hash,
+	/// Header of the block.
+	pub header: B::Header,
+	/// Justifications of the block.
+	pub justifications: Option<Justifications>,
+}
+
+/// An entry of the state in a snapshot.
+#[derive(Encode, Decode)]
+pub enum SnapshotEntry {
+	/// A key-value pair of the current trie, which is the main trie at first.
+	Pair(Vec<u8>, Vec<u8>),
+	/// The following pairs belong to the default child trie with this unprefixed storage key.
+	ChildTrie(Vec<u8>),
+	/// The last entry of a snapshot.
+	End,
+}
+
+/// Write a snapshot of the state at the given finalized `block` to `output`.
+///
+/// If `block` is `None`, the last finalized block is used. Returns the hash of the block.
+pub fn export_snapshot<B, BA, C>(
+	client: Arc<C>,
+	block: Option<BlockId<B>>,
+	mut output: impl Write,
+) -> Result<B::Hash, Error>
+where
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+	C: UsageProvider<B> + StorageProvider<B, BA> + BlockBackend<B> + HeaderBackend<B>,
+{
+	let info = client.usage_info().chain;
+	let block = block.unwrap_or_else(|| BlockId::Hash(info.finalized_hash));
+	let header = client.header(block)?
+		.ok_or_else(|| Error::Other(format!("Unknown block {}", block)))?;
+	let hash = header.hash();
+	let number = *header.number();
+	if number > info.finalized_number || client.hash(number)? != Some(hash) {
+		return Err(Error::Other(format!("Block #{} ({}) is not finalized", number, hash)));
+	}
+
+	info!("Exporting state snapshot of #{} ({})", number, hash);
+	let block = BlockId::Hash(hash);
+	let justifications = client.justifications(&block)?;
+	output.write_all(&SNAPSHOT_MAGIC)?;
+	output.write_all(&SNAPSHOT_VERSION.encode())?;
+	let snapshot_header = SnapshotHeader::<B> {
+		genesis_hash: info.genesis_hash,
+		header,
+		justifications,
+	};
+	output.write_all(&snapshot_header.encode())?;
+
+	let mut child_tries = Vec::new();
+	for key in client.storage_keys_iter(&block, None, None)? {
+		// The pairs of the child tries are written after the main trie instead of their roots.
+		let prefix = well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX;
+		if key.0.starts_with(prefix) {
+			child_tries.push(ChildInfo::new_default(&key.0[prefix.len()..]));
+			continue;
+		}
+		if let Some(value) = client.storage(&block, &key)? {
+			output.write_all(&SnapshotEntry::Pair(key.0, value.0).encode())?;
+		}
+	}
+	for child_info in child_tries {
+		output.write_all(&SnapshotEntry::ChildTrie(child_info.storage_key().to_vec()).encode())?;
+		let empty_key = StorageKey(Vec::new());
+		for key in client.child_storage_keys(&block, &child_info, &empty_key)? {
+			if let Some(value) = client.child_storage(&block, &child_info, &key)? {
+				output.write_all(&SnapshotEntry::Pair(key.0, value.0).encode())?;
+			}
+		}
+	}
+	output.write_all(&SnapshotEntry::End.encode())?;
+	output.flush()?;
+	Ok(hash)
+}
+
+/// Read a snapshot from `input` and import it into an empty database.
+///
+/// The state root is checked against the header of the block, which becomes the finalized and
+/// best block. Returns the hash of the block. Auxiliary data of consensus engines is not
+/// restored, see the [module documentation](self).
+pub async fn import_snapshot<B, C>(
+	mut client: Arc<C>,
+	mut input: impl Read,
+) -> Result<B::Hash, Error>
+where
+	B: BlockT,
+	C: UsageProvider<B> + Send + Sync,
+	for<'r> &'r C: BlockImport<B, Error = sp_consensus::Error>,
+{
+	let mut magic = [0u8; 8];
+	input.read_exact(&mut magic)?;
+	if magic != SNAPSHOT_MAGIC {
+		return Err(Error::Other("Input is not a state snapshot".into()));
+	}
+	let mut version = [0u8; 4];
+	input.read_exact(&mut version)?;
+	let version = u32::from_le_bytes(version);
+	if version != SNAPSHOT_VERSION {
+		return Err(Error::Other(format!("Unsupported snapshot version {}", version)));
+	}
+	let decode_error = |e: codec::Error| Error::Other(format!("Error decoding snapshot: {}", e));
+	let mut input = CodecIoReader(input);
+	let SnapshotHeader::<B> { genesis_hash, header, justifications } =
+		Decode::decode(&mut input).map_err(decode_error)?;
+
+	let info = client.usage_info().chain;
+	if genesis_hash != info.genesis_hash {
+		return Err(Error::Other("The snapshot is of a different chain".into()));
+	}
+	if !info.best_number.is_zero() {
+		return Err(Error::Other("Snapshots can only be imported into an empty database".into()));
+	}
+
+	let hash = header.hash();
+	let number = *header.number();
+	info!("Importing state snapshot of #{} ({})", number, hash);
+
+	let mut state = Storage::default();
+	let mut child_trie = None;
+	loop {
+		match SnapshotEntry::decode(&mut input).map_err(decode_error)? {
+			SnapshotEntry::Pair(key, value) => {
+				let data = match &mut child_trie {
+					Some((_, StorageChild { data, .. })) => data,
+					None => &mut state.top,
+				};
+				data.insert(key, value);
+			},
+			SnapshotEntry::ChildTrie(storage_key) => {
+				let child_info = ChildInfo::new_default(&storage_key);
+				let child = StorageChild { data: Default::default(), child_info };
+				if let Some((storage_key, child)) = child_trie.replace((storage_key, child)) {
+					state.children_default.insert(storage_key, child);
+				}
+			},
+			SnapshotEntry::End => break,
+		}
+	}
+	if let Some((storage_key, child)) = child_trie {
+		state.children_default.insert(storage_key, child);
+	}
+
+	let mut import = BlockImportParams::new(BlockOrigin::File, header);
+	import.justifications = justifications;
+	import.finalized = true;
+	import.fork_choice = Some(ForkChoiceStrategy::Custom(true));
+	import.state = Some(ImportedState { block: hash, state });
+
+	match client.import_block(import, HashMap::new()).await? {
+		ImportResult::Imported(_) => Ok(hash),
+		result => Err(Error::Other(
+			format!("Error importing snapshot of #{} ({}): {:?}", number, hash, result),
+		)),
+	}
+}
@@ -50,13 +50,14 @@ use sp_runtime::{
 };
 use sp_state_machine::{
 	DBValue, Backend as StateBackend, ChangesTrieAnchorBlockId,
-	prove_read, prove_child_read, ChangesTrieRootsStorage, ChangesTrieStorage,
+	prove_read, prove_child_read, prove_range_read_with_size, read_range_proof_check,
+	ChangesTrieRootsStorage, ChangesTrieStorage,
 	ChangesTrieConfigurationRange, key_changes, key_changes_proof,
 };
 use sc_executor::RuntimeVersion;
 use sp_consensus::{
 	Error as ConsensusError, BlockStatus, BlockImportParams, BlockCheckParams,
-	ImportResult, BlockOrigin, ForkChoiceStrategy,
+	ImportResult, BlockOrigin, ForkChoiceStrategy, ImportedState,
 };
 use sp_blockchain::{
 	self as blockchain,
@@ -632,6 +633,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			fork_choice,
 			intermediates,
 			import_existing,
+			state,
 			..
 		} = import_block;
 
@@ -671,6 +673,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			auxiliary,
 			fork_choice,
 			import_existing,
+			state,
 		);
 
 		if let Ok(ImportResult::Imported(ref aux)) = result {
@@ -710,6 +713,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		aux: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 		fork_choice: ForkChoiceStrategy,
 		import_existing: bool,
+		state: Option<ImportedState<Block>>,
 	) -> sp_blockchain::Result<ImportResult> where
 		Self: ProvideRuntimeApi<Block>,
 		<Self as ProvideRuntimeApi<Block>>::Api: CoreApi<Block> +
@@ -728,9 +732,15 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 		let info = self.backend.blockchain().info();
 
 		// the block is lower than our last finalized block so it must revert
-		// finality, refusing import.
-		if *import_headers.post().number() <= info.finalized_number {
-			return Err(sp_blockchain::Error::NotInFinalizedChain);
+		// finality, refusing import. The only exception is re-importing a finalized
+		// block, e.g. to add the state of a block that was imported without it.
+		let number = *import_headers.post().number();
+		if number <= info.finalized_number {
+			let is_finalized = import_existing &&
+				self.backend.blockchain().hash(number)? == Some(hash);
+			if !is_finalized {
+				return Err(sp_blockchain::Error::NotInFinalizedChain);
+			}
 		}
 
 		// this is a fairly arbitrary choice of where to draw the line on making notifications,
@@ -741,6 +751,7 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			BlockOrigin::Genesis | BlockOrigin::NetworkInitialSync | BlockOrigin::File => false,
 		};
 
+		let is_state_imported = state.is_some();
 		let storage_changes = match storage_changes {
 			Some(storage_changes) => {
 				self.backend.begin_state_operation(&mut operation.op, BlockId::Hash(parent_hash))?;
@@ -782,7 +793,20 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 
 				Some((main_sc, child_sc))
 			},
-			None => None,
+			None => {
+				if let Some(state) = state {
+					// The state was downloaded as a whole, e.g. by state sync.
+					let state_root = operation.op.reset_storage(state.state)?;
+					if state_root != *import_headers.post().state_root() {
+						// State root mismatch when importing state. This should not happen in
+						// safe fast sync mode, but may happen in unsafe mode.
+						warn!("Error importing state: State root mismatch.");
+						return Err(Error::InvalidStateRoot);
+					}
+					operation.op.update_cache(new_cache);
+				}
+				None
+			},
 		};
 
 		let is_new_best = finalized || match fork_choice {
@@ -798,7 +822,11 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			NewBlockState::Normal
 		};
 
-		let tree_route = if is_new_best && info.best_hash != parent_hash {
+		// A block which state is imported as a whole may have no known ancestors, e.g. when it
+		// is imported from a snapshot. There is no route to such a block.
+		let is_detached = is_state_imported &&
+			self.backend.blockchain().header(BlockId::Hash(parent_hash))?.is_none();
+		let tree_route = if is_new_best && info.best_hash != parent_hash && !is_detached {
 			let route_from_best = sp_blockchain::tree_route(
 				self.backend.blockchain(),
 				info.best_hash,
@@ -859,6 +887,12 @@ impl<B, E, Block, RA> Client<B, E, Block, RA> where
 			<Self as ProvideRuntimeApi<Block>>::Api: CoreApi<Block> +
 				ApiExt<Block, StateBackend = B::State>,
 	{
+		if import_block.state.is_some() {
+			// The state is imported as a whole, there is nothing to execute.
+			import_block.storage_changes = None;
+			return Ok(None)
+		}
+
 		let parent_hash = import_block.header.parent_hash();
 		let at = BlockId::Hash(*parent_hash);
 		let enact_state = match self.block_status(&at)? {
@@ -1281,6 +1315,64 @@ impl<B, E, Block, RA> ProofProvider<Block> for Client<B, E, Block, RA> where
 			cht::size(),
 		)
 	}
+
+	fn read_proof_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(StorageProof, u32)> {
+		let state = self.state_at(id)?;
+		prove_range_read_with_size::<_, HashFor<Block>>(state, child_info, start_key, size_limit)
+			.map_err(Into::into)
+	}
+
+	fn storage_collection(
+		&self,
+		id: &BlockId<Block>,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+		size_limit: usize,
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)> {
+		let state = self.state_at(id)?;
+		let next_key = |key: &[u8]| match child_info {
+			Some(child_info) => state.next_child_storage_key(child_info, key),
+			None => state.next_storage_key(key),
+		}.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)));
+		let mut entries = Vec::new();
+		let mut total_size = 0;
+		let mut current_key = start_key.to_vec();
+		loop {
+			let key = match next_key(&current_key)? {
+				Some(key) => key,
+				None => return Ok((entries, true)),
+			};
+			let value = match child_info {
+				Some(child_info) => state.child_storage(child_info, &key),
+				None => state.storage(&key),
+			}.map_err(|e| sp_blockchain::Error::from_state(Box::new(e)))?;
+			if let Some(value) = value {
+				total_size += key.len() + value.len();
+				entries.push((key.clone(), value));
+			}
+			if total_size > size_limit {
+				return Ok((entries, false))
+			}
+			current_key = key;
+		}
+	}
+
+	fn verify_range_proof(
+		&self,
+		root: Block::Hash,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_key: &[u8],
+	) -> sp_blockchain::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool)> {
+		read_range_proof_check::<HashFor<Block>>(root, proof, child_info, start_key)
+			.map_err(Into::into)
+	}
 }
 
 
@@ -1777,7 +1869,8 @@ impl<B, E, Block, RA> sp_consensus::BlockImport<Block> for &Client<B, E, Block,
 		{
 			BlockStatus::InChainWithState | BlockStatus::Queued if !import_existing  => return Ok(ImportResult::AlreadyInChain),
 			BlockStatus::InChainWithState | BlockStatus::Queued => {},
-			BlockStatus::InChainPruned => return Ok(ImportResult::AlreadyInChain),
+			BlockStatus::InChainPruned if !import_existing => return Ok(ImportResult::AlreadyInChain),
+			BlockStatus::InChainPruned => {},
 			BlockStatus::Unknown => {},
 			BlockStatus::KnownBad => return Ok(ImportResult::KnownBad),
 		}
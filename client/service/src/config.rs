@@ -28,7 +28,9 @@ pub use sc_executor::WasmExecutionMethod;
 use sc_client_api::execution_extensions::ExecutionStrategies;
 
 use std::{io, future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc};
-pub use sc_transaction_pool::txpool::Options as TransactionPoolOptions;
+pub use sc_transaction_pool::txpool::{
+	Options as TransactionPoolOptions, PersistenceOptions as TransactionPoolPersistence,
+};
 use sc_chain_spec::ChainSpec;
 use sp_core::crypto::SecretString;
 pub use sc_telemetry::TelemetryEndpoints;
@@ -202,6 +204,15 @@ impl Configuration {
 		self.prometheus_config.as_ref().map(|config| &config.registry)
 	}
 
+	/// Returns the directory in which compiled runtimes are stored.
+	///
+	/// The path looks like `$base_path/chains/$chain_id/wasm-artifacts`. Returns `None` if there
+	/// is no base path.
+	pub fn wasm_artifacts_path(&self) -> Option<PathBuf> {
+		self.base_path.as_ref()
+			.map(|base_path| base_path.config_dir(self.chain_spec.id()).join("wasm-artifacts"))
+	}
+
 	/// Returns the network protocol id from the chain spec, or the default.
 	pub fn protocol_id(&self) -> sc_network::config::ProtocolId {
 		let protocol_id_full = match self.chain_spec.protocol_id() {
@@ -1928,3 +1928,74 @@ fn reorg_triggers_a_notification_even_for_sources_that_should_not_trigger_notifi
 	let tree_route = notification.tree_route.unwrap();
 	assert_eq!(tree_route.enacted()[0].hash, b1.hash());
 }
+
+#[test]
+fn state_snapshot_export_and_import_works() {
+	use sc_service::chain_ops::{
+		export_snapshot, import_snapshot, SnapshotEntry, SnapshotHeader, SNAPSHOT_MAGIC,
+	};
+
+	let mut client = substrate_test_runtime_client::new();
+
+	// G -> A1 -> A2
+	let mut a1 = client.new_block(Default::default()).unwrap();
+	a1.push_transfer(Transfer {
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Ferdie.into(),
+		amount: 42,
+		nonce: 0,
+	}).unwrap();
+	let a1 = a1.build().unwrap().block;
+	block_on(client.import(BlockOrigin::Own, a1.clone())).unwrap();
+
+	let justification = Justifications::from((TEST_ENGINE_ID, vec![1, 2, 3]));
+	let a2 = client.new_block_at(
+		&BlockId::Hash(a1.hash()),
+		Default::default(),
+		false,
+	).unwrap().build().unwrap().block;
+	block_on(client.import_justified(BlockOrigin::Own, a2.clone(), justification.clone())).unwrap();
+
+	let client = Arc::new(client);
+	let mut snapshot = Vec::new();
+	assert_eq!(export_snapshot(client.clone(), None, &mut snapshot).unwrap(), a2.hash());
+
+	// A snapshot with a state that doesn't match the header is rejected.
+	let mut entries = &snapshot[SNAPSHOT_MAGIC.len() + 4..];
+	SnapshotHeader::<Block>::decode(&mut entries).unwrap();
+	let mut corrupted = snapshot[..snapshot.len() - entries.len()].to_vec();
+	match SnapshotEntry::decode(&mut entries).unwrap() {
+		SnapshotEntry::Pair(key, mut value) => {
+			value.push(0);
+			SnapshotEntry::Pair(key, value).encode_to(&mut corrupted);
+		},
+		_ => panic!("The snapshot starts with the pairs of the main trie"),
+	}
+	corrupted.extend_from_slice(entries);
+
+	let target = Arc::new(substrate_test_runtime_client::new());
+	assert!(block_on(import_snapshot(target.clone(), &corrupted[..])).is_err());
+	assert_eq!(block_on(import_snapshot(target.clone(), &snapshot[..])).unwrap(), a2.hash());
+
+	let info = target.chain_info();
+	assert_eq!((info.best_hash, info.finalized_hash), (a2.hash(), a2.hash()));
+	assert_eq!(
+		target.justifications(&BlockId::Hash(a2.hash())).unwrap(),
+		Some(justification),
+	);
+	let ferdie = StorageKey(
+		blake2_256(&runtime::system::balance_of_key(AccountKeyring::Ferdie.into())).to_vec(),
+	);
+	let at = BlockId::Hash(a2.hash());
+	assert!(target.storage(&at, &ferdie).unwrap().is_some());
+	assert_eq!(target.storage(&at, &ferdie).unwrap(), client.storage(&at, &ferdie).unwrap());
+
+	// The chain continues from the imported block.
+	let a3 = target.new_block_at(
+		&BlockId::Hash(a2.hash()),
+		Default::default(),
+		false,
+	).unwrap().build().unwrap().block;
+	block_on(target.clone().import(BlockOrigin::Own, a3.clone())).unwrap();
+	assert_eq!(target.chain_info().best_hash, a3.hash());
+}
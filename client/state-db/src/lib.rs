@@ -38,6 +38,16 @@
 //! # Pruning.
 //! See `RefWindow` for pruning algorithm details. `StateDb` prunes on each canonicalization until
 //! pruning constraints are satisfied.
+//!
+//! # Switching the pruning mode
+//! The pruning mode is stored in the database and the database can't be opened with a different
+//! one. `StateDb::migrate` switches a database from one of the archive modes to a constrained
+//! mode. The pruning window then starts at the last canonical block. The state of older blocks
+//! is not tracked by the window, so it has to be pruned separately: The database is marked with
+//! the number of the first block that is kept and `StateDb::prune_migrated` removes the nodes of
+//! older blocks in batches, which makes this resumable. The nodes to remove have to be provided
+//! by the caller, since the state db knows nothing about tries. The caller also has to make sure
+//! that none of them is in use by the blocks that are kept or inserted in the meantime.
 
 mod noncanonical;
 mod pruning;
@@ -46,7 +56,7 @@ mod test;
 
 use std::fmt;
 use parking_lot::RwLock;
-use codec::Codec;
+use codec::{Codec, Decode, Encode};
 use std::collections::{HashMap, hash_map::Entry};
 use noncanonical::NonCanonicalOverlay;
 use pruning::RefWindow;
@@ -58,6 +68,7 @@ const PRUNING_MODE: &[u8] = b"mode";
 const PRUNING_MODE_ARCHIVE: &[u8] = b"archive";
 const PRUNING_MODE_ARCHIVE_CANON: &[u8] = b"archive_canonical";
 const PRUNING_MODE_CONSTRAINED: &[u8] = b"constrained";
+const PRUNING_MIGRATION: &[u8] = b"pruning_migration";
 
 /// Database value type.
 pub type DBValue = Vec<u8>;
@@ -97,6 +108,8 @@ pub enum Error<E: fmt::Debug> {
 	InvalidParent,
 	/// Invalid pruning mode specified. Contains expected mode.
 	InvalidPruningMode(String),
+	/// Switching from the pruning mode of the database is not supported. Contains the mode.
+	UnsupportedPruningModeSwitch(String),
 	/// Too many unfinalized sibling blocks inserted.
 	TooManySiblingBlocks,
 }
@@ -122,6 +135,8 @@ impl<E: fmt::Debug> fmt::Debug for Error<E> {
 			Error::InvalidBlockNumber => write!(f, "Trying to insert block with invalid number"),
 			Error::InvalidParent => write!(f, "Trying to insert block with unknown parent"),
 			Error::InvalidPruningMode(e) => write!(f, "Expected pruning mode: {}", e),
+			Error::UnsupportedPruningModeSwitch(e) =>
+				write!(f, "Switching from pruning mode {} is not supported", e),
 			Error::TooManySiblingBlocks => write!(f, "Too many sibling blocks inserted"),
 		}
 	}
@@ -209,6 +224,8 @@ struct StateDbSync<BlockHash: Hash, Key: Hash> {
 	non_canonical: NonCanonicalOverlay<BlockHash, Key>,
 	pruning: Option<RefWindow<BlockHash, Key>>,
 	pinned: HashMap<BlockHash, u32>,
+	/// The state of blocks before this one is left from a previous pruning mode.
+	migration: Option<u64>,
 }
 
 impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<BlockHash, Key> {
@@ -231,15 +248,73 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 			PruningMode::Constrained(_) => Some(RefWindow::new(db, ref_counting)?),
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
+		let migration = db.get_meta(&to_meta_key(PRUNING_MIGRATION, &()))
+			.map_err(Error::Db)?
+			.map(|buffer| u64::decode(&mut buffer.as_slice()))
+			.transpose()?;
 
 		Ok(StateDbSync {
 			mode,
 			non_canonical,
 			pruning,
 			pinned: Default::default(),
+			migration,
 		})
 	}
 
+	fn migrate<D: MetaDb>(
+		mode: &PruningMode,
+		ref_counting: bool,
+		db: &D,
+		canonical: (BlockHash, u64),
+		non_canonical: &[(BlockHash, u64, BlockHash)],
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
+		let mut commit = CommitSet::default();
+		let db_mode = match db.get_meta(&to_meta_key(PRUNING_MODE, &())).map_err(Error::Db)? {
+			Some(db_mode) => db_mode,
+			// Nothing was written yet.
+			None => return Ok(commit),
+		};
+		// The constraints are not stored, so switching between them needs no migration.
+		if db_mode.as_slice() == mode.id() {
+			return Ok(commit);
+		}
+		// Pruning the state that was kept by the previous mode requires to delete nodes
+		// without reference counting.
+		if mode.is_archive() || !ref_counting {
+			let db_mode = String::from_utf8_lossy(&db_mode).into();
+			return Err(Error::UnsupportedPruningModeSwitch(db_mode));
+		}
+
+		let mut overlay: NonCanonicalOverlay<BlockHash, Key> = NonCanonicalOverlay::new(db)?;
+		let (hash, number) = if db_mode.as_slice() == PRUNING_MODE_ARCHIVE {
+			// Blocks were written to the database directly, so the overlay has to start tracking
+			// the non-canonical ones. Their nodes are in the database already.
+			for (hash, number, parent_hash) in non_canonical {
+				let insert = overlay.insert::<D::Error>(
+					hash,
+					*number,
+					parent_hash,
+					ChangeSet::default(),
+				)?;
+				commit.meta.inserted.extend(insert.meta.inserted);
+			}
+			canonical
+		} else {
+			overlay.last_canonicalized_hash()
+				.zip(overlay.last_canonicalized_block_number())
+				.unwrap_or(canonical)
+		};
+
+		trace!(target: "state-db", "Switching to {:?} at #{} ({:?})", mode, number, hash);
+		pruning::start_window(&hash, number, &mut commit);
+		if number > 0 {
+			commit.meta.inserted.push((to_meta_key(PRUNING_MIGRATION, &()), number.encode()));
+		}
+		commit.meta.inserted.push((to_meta_key(PRUNING_MODE, &()), mode.id().into()));
+		Ok(commit)
+	}
+
 	fn check_meta<D: MetaDb>(mode: &PruningMode, db: &D) -> Result<(), Error<D::Error>> {
 		let db_mode = db.get_meta(&to_meta_key(PRUNING_MODE, &())).map_err(Error::Db)?;
 		trace!(target: "state-db",
@@ -308,6 +383,54 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDbSync<Block
 		Ok(commit)
 	}
 
+	fn import_canonical<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		mut changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		let mut commit = CommitSet::default();
+		match self.mode {
+			PruningMode::ArchiveAll => {
+				changeset.deleted.clear();
+				commit.data = changeset;
+			},
+			PruningMode::ArchiveCanonical | PruningMode::Constrained(_) => {
+				self.non_canonical.set_canonical(hash, number, &mut commit)?;
+				commit.data = changeset;
+				if self.mode == PruningMode::ArchiveCanonical {
+					commit.data.deleted.clear();
+				}
+				if let Some(ref mut pruning) = self.pruning {
+					pruning.note_canonical(&hash, &mut commit);
+				}
+				self.prune(&mut commit);
+			},
+		}
+		Ok(commit)
+	}
+
+	fn have_block(&self, hash: &BlockHash) -> bool {
+		self.non_canonical.have_block(hash)
+	}
+
+	fn prune_migrated(&mut self, next: u64, deleted: Vec<Key>) -> CommitSet<Key> {
+		let mut commit = CommitSet::default();
+		if self.migration.is_none() {
+			return commit;
+		}
+		commit.data.deleted = deleted;
+		let migration_key = to_meta_key(PRUNING_MIGRATION, &());
+		if next == 0 {
+			commit.meta.deleted.push(migration_key);
+			self.migration = None;
+		} else {
+			commit.meta.inserted.push((migration_key, next.encode()));
+			self.migration = Some(next);
+		}
+		commit
+	}
+
 	fn best_canonical(&self) -> Option<u64> {
 		return self.non_canonical.last_canonicalized_block_number()
 	}
@@ -463,6 +586,40 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 		})
 	}
 
+	/// Switch a database created with a different pruning mode to `mode`.
+	///
+	/// Switching from an archive mode to a constrained mode is supported, as well as changing the
+	/// constraints. Switching from [`PruningMode::ArchiveAll`] requires the last finalized block as
+	/// `canonical` and all blocks above it in `non_canonical` as `(hash, number, parent_hash)`,
+	/// ordered by number. The pruning window starts at the last canonical block and the state of
+	/// older blocks has to be removed with [`StateDb::prune_migrated`] afterwards.
+	///
+	/// Returns the changes to commit to the database, which can then be opened with `mode`.
+	pub fn migrate<D: MetaDb>(
+		mode: &PruningMode,
+		ref_counting: bool,
+		db: &D,
+		canonical: (BlockHash, u64),
+		non_canonical: &[(BlockHash, u64, BlockHash)],
+	) -> Result<CommitSet<Key>, Error<D::Error>> {
+		StateDbSync::<BlockHash, Key>::migrate(mode, ref_counting, db, canonical, non_canonical)
+	}
+
+	/// Returns the number of the first block which state was kept when switching the pruning mode.
+	///
+	/// The state of older blocks has not been completely removed yet if this is `Some`.
+	pub fn migration(&self) -> Option<u64> {
+		self.db.read().migration
+	}
+
+	/// Remove nodes of the state that was left from a previous pruning mode.
+	///
+	/// `deleted` are the nodes of the blocks from `next` up to the previous call that are not in
+	/// use anymore. The migration is finished when `next` is zero.
+	pub fn prune_migrated(&self, next: u64, deleted: Vec<Key>) -> CommitSet<Key> {
+		self.db.write().prune_migrated(next, deleted)
+	}
+
 	/// Add a new non-canonical block.
 	pub fn insert_block<E: fmt::Debug>(
 		&self,
@@ -482,6 +639,22 @@ impl<BlockHash: Hash + MallocSizeOf, Key: Hash + MallocSizeOf> StateDb<BlockHash
 		self.db.write().canonicalize_block(hash)
 	}
 
+	/// Import the state of a block which is canonical already, e.g. a finalized block which
+	/// state was downloaded by state sync. Expects no non-canonical blocks to be present.
+	pub fn import_canonical<E: fmt::Debug>(
+		&self,
+		hash: &BlockHash,
+		number: u64,
+		changeset: ChangeSet<Key>,
+	) -> Result<CommitSet<Key>, Error<E>> {
+		self.db.write().import_canonical(hash, number, changeset)
+	}
+
+	/// Check if the block is waiting to be canonicalized.
+	pub fn have_block(&self, hash: &BlockHash) -> bool {
+		self.db.read().have_block(hash)
+	}
+
 	/// Prevents pruning of specified block and its descendants.
 	pub fn pin(&self, hash: &BlockHash) -> Result<(), PinError> {
 		self.db.write().pin(hash)
@@ -608,6 +781,63 @@ mod tests {
 		(db, state_db)
 	}
 
+	#[test]
+	fn import_canonical_works() {
+		let mut db = make_db(&[]);
+		let state_db: StateDb<H256, H256> =
+			StateDb::new(PruningMode::keep_blocks(2), false, &db).unwrap();
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(0),
+					0,
+					&H256::from_low_u64_be(0),
+					make_changeset(&[1], &[]),
+				)
+				.unwrap(),
+		);
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(0)).unwrap());
+		state_db.apply_pending();
+
+		// Can't go back.
+		assert!(state_db.import_canonical::<io::Error>(
+			&H256::from_low_u64_be(10),
+			0,
+			make_changeset(&[10], &[]),
+		).is_err());
+
+		db.commit(
+			&state_db
+				.import_canonical::<io::Error>(
+					&H256::from_low_u64_be(10),
+					10,
+					make_changeset(&[10], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(10));
+		assert!(db.data_eq(&make_db(&[1, 10])));
+
+		// Regular blocks continue on top of the imported one.
+		db.commit(
+			&state_db
+				.insert_block::<io::Error>(
+					&H256::from_low_u64_be(11),
+					11,
+					&H256::from_low_u64_be(10),
+					make_changeset(&[11], &[]),
+				)
+				.unwrap(),
+		);
+		state_db.apply_pending();
+		assert!(state_db.have_block(&H256::from_low_u64_be(11)));
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(11)).unwrap());
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(11));
+		assert!(db.data_eq(&make_db(&[1, 10, 11])));
+	}
+
 	#[test]
 	fn full_archive_keeps_everything() {
 		let (db, sdb) = make_test_db(PruningMode::ArchiveAll);
@@ -674,4 +904,87 @@ mod tests {
 		let state_db: Result<StateDb<H256, H256>, _> = StateDb::new(new_mode, false, &db);
 		assert!(state_db.is_err());
 	}
+
+	#[test]
+	fn migrates_from_canonical_archive() {
+		let (mut db, _) = make_test_db(PruningMode::ArchiveCanonical);
+		let new_mode = PruningMode::keep_blocks(2);
+		let canonical = (H256::from_low_u64_be(0), 0);
+		db.commit(&StateDb::<H256, H256>::migrate(&new_mode, true, &db, canonical, &[]).unwrap());
+
+		let state_db: StateDb<H256, H256> = StateDb::new(new_mode.clone(), true, &db).unwrap();
+		assert_eq!(state_db.migration(), Some(3));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(21), 2));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(3), 3));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(4), 4));
+
+		let commit = state_db.prune_migrated(1, vec![H256::from_low_u64_be(921)]);
+		assert_eq!(commit.data.deleted, vec![H256::from_low_u64_be(921)]);
+		db.commit(&commit);
+		assert!(db.data_eq(&make_db(&[1, 21, 3, 91, 922, 93, 94])));
+
+		// the migration can be resumed
+		let state_db: StateDb<H256, H256> = StateDb::new(new_mode.clone(), true, &db).unwrap();
+		assert_eq!(state_db.migration(), Some(1));
+		db.commit(&state_db.prune_migrated(0, vec![H256::from_low_u64_be(1)]));
+		assert_eq!(state_db.migration(), None);
+		assert!(db.data_eq(&make_db(&[21, 3, 91, 922, 93, 94])));
+		let state_db: StateDb<H256, H256> = StateDb::new(new_mode, true, &db).unwrap();
+		assert_eq!(state_db.migration(), None);
+	}
+
+	#[test]
+	fn migrates_from_full_archive() {
+		let (mut db, _) = make_test_db(PruningMode::ArchiveAll);
+		let new_mode = PruningMode::keep_blocks(2);
+		let canonical = (H256::from_low_u64_be(21), 2);
+		let non_canonical = [
+			(H256::from_low_u64_be(3), 3, H256::from_low_u64_be(21)),
+			(H256::from_low_u64_be(4), 4, H256::from_low_u64_be(3)),
+		];
+		let commit = StateDb::<H256, H256>::migrate(&new_mode, true, &db, canonical, &non_canonical)
+			.unwrap();
+		db.commit(&commit);
+
+		let state_db: StateDb<H256, H256> = StateDb::new(new_mode, true, &db).unwrap();
+		assert_eq!(state_db.migration(), Some(2));
+		assert_eq!(state_db.best_canonical(), Some(2));
+		assert!(state_db.is_pruned(&H256::from_low_u64_be(1), 1));
+		assert!(!state_db.is_pruned(&H256::from_low_u64_be(21), 2));
+		assert!(state_db.have_block(&H256::from_low_u64_be(3)));
+		assert!(state_db.have_block(&H256::from_low_u64_be(4)));
+
+		db.commit(&state_db.canonicalize_block::<io::Error>(&H256::from_low_u64_be(3)).unwrap());
+		state_db.apply_pending();
+		assert_eq!(state_db.best_canonical(), Some(3));
+		assert!(db.data_eq(&make_db(&[1, 21, 22, 3, 4, 91, 921, 922, 93, 94])));
+	}
+
+	#[test]
+	fn rejects_unsupported_migrations() {
+		let (db, _) = make_test_db(PruningMode::keep_blocks(2));
+		let canonical = (H256::from_low_u64_be(0), 0);
+
+		// changing the constraints needs no migration
+		let commit = StateDb::<H256, H256>::migrate(
+			&PruningMode::keep_blocks(10),
+			true,
+			&db,
+			canonical,
+			&[],
+		).unwrap();
+		assert!(commit.meta.inserted.is_empty());
+		assert!(StateDb::<H256, H256>::new(PruningMode::keep_blocks(10), true, &db).is_ok());
+
+		assert!(
+			StateDb::<H256, H256>::migrate(&PruningMode::ArchiveAll, true, &db, canonical, &[])
+				.is_err()
+		);
+
+		let (db, _) = make_test_db(PruningMode::ArchiveAll);
+		assert!(
+			StateDb::<H256, H256>::migrate(&PruningMode::keep_blocks(2), false, &db, canonical, &[])
+				.is_err()
+		);
+	}
 }
@@ -40,6 +40,7 @@ pub struct NonCanonicalOverlay<BlockHash: Hash, Key: Hash> {
 	parents: HashMap<BlockHash, BlockHash>,
 	pending_canonicalizations: Vec<BlockHash>,
 	pending_insertions: Vec<BlockHash>,
+	pending_canonical_reset: Option<(BlockHash, u64)>,
 	values: HashMap<Key, (u32, DBValue)>, //ref counted
 	//would be deleted but kept around because block is pinned, ref counted.
 	pinned: HashMap<BlockHash, u32>,
@@ -201,6 +202,7 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 			parents,
 			pending_canonicalizations: Default::default(),
 			pending_insertions: Default::default(),
+			pending_canonical_reset: None,
 			pinned: Default::default(),
 			pinned_insertions: Default::default(),
 			values: values,
@@ -295,6 +297,9 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	}
 
 	pub fn last_canonicalized_block_number(&self) -> Option<u64> {
+		if let Some(&(_, n)) = self.pending_canonical_reset.as_ref() {
+			return Some(n);
+		}
 		match self.last_canonicalized.as_ref().map(|&(_, n)| n) {
 			Some(n) => Some(n + self.pending_canonicalizations.len() as u64),
 			None if !self.pending_canonicalizations.is_empty() => Some(self.pending_canonicalizations.len() as u64),
@@ -417,6 +422,28 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 			&& !self.pending_canonicalizations.contains(hash)
 	}
 
+	/// Set the given block as the last canonicalized block, without inserting it first.
+	/// Used when the state of the block is imported as a whole, e.g. by state sync.
+	/// Fails if there are non-canonical blocks in the overlay.
+	pub fn set_canonical<E: fmt::Debug>(
+		&mut self,
+		hash: &BlockHash,
+		number: u64,
+		commit: &mut CommitSet<Key>,
+	) -> Result<(), Error<E>> {
+		if !self.levels.is_empty() || !self.pending_canonicalizations.is_empty() {
+			return Err(Error::InvalidBlock);
+		}
+		if self.last_canonicalized.as_ref().map_or(false, |&(_, n)| number <= n) {
+			return Err(Error::InvalidBlockNumber);
+		}
+		trace!(target: "state-db", "Setting canonical {:?} ({})", hash, number);
+		let canonicalized = (hash.clone(), number);
+		commit.meta.inserted.push((to_meta_key(LAST_CANONICAL, &()), canonicalized.encode()));
+		self.pending_canonical_reset = Some(canonicalized);
+		Ok(())
+	}
+
 	/// Revert a single level. Returns commit set that deletes the journal or `None` if not possible.
 	pub fn revert_one(&mut self) -> Option<CommitSet<Key>> {
 		self.levels.pop_back().map(|level| {
@@ -452,12 +479,16 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	/// Apply all pending changes
 	pub fn apply_pending(&mut self) {
 		self.apply_canonicalizations();
+		if let Some(canonicalized) = self.pending_canonical_reset.take() {
+			self.last_canonicalized = Some(canonicalized);
+		}
 		self.pending_insertions.clear();
 	}
 
 	/// Revert all pending changes
 	pub fn revert_pending(&mut self) {
 		self.pending_canonicalizations.clear();
+		self.pending_canonical_reset = None;
 		self.revert_insertions();
 	}
 
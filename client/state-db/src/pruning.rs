@@ -71,6 +71,26 @@ fn to_journal_key(block: u64) -> Vec<u8> {
 	to_meta_key(PRUNING_JOURNAL, &block)
 }
 
+/// Start a pruning window with the given canonical block.
+///
+/// Nothing is deleted when the block leaves the window. Used when pruning is enabled for a
+/// database that was created in one of the archive modes, which do not maintain a window.
+pub fn start_window<BlockHash: Hash, Key: Hash>(
+	hash: &BlockHash,
+	number: u64,
+	commit: &mut CommitSet<Key>,
+) {
+	if number > 0 {
+		commit.meta.inserted.push((to_meta_key(LAST_PRUNED, &()), (number - 1).encode()));
+	}
+	let journal_record = JournalRecord::<BlockHash, Key> {
+		hash: hash.clone(),
+		inserted: Vec::new(),
+		deleted: Vec::new(),
+	};
+	commit.meta.inserted.push((to_journal_key(number), journal_record.encode()));
+}
+
 impl<BlockHash: Hash, Key: Hash> RefWindow<BlockHash, Key> {
 	pub fn new<D: MetaDb>(db: &D, count_insertions: bool) -> Result<RefWindow<BlockHash, Key>, Error<D::Error>> {
 		let last_pruned = db.get_meta(&to_meta_key(LAST_PRUNED, &()))
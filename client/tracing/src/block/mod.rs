@@ -0,0 +1,438 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Utilities for tracing block execution
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, atomic::{AtomicU32, AtomicU64, Ordering}},
+	time::Instant,
+};
+
+use parking_lot::Mutex;
+use tracing::{
+	dispatcher, Dispatch, Level, Metadata, Subscriber,
+	span::{Attributes, Id, Record},
+};
+use tracing_subscriber::CurrentSpan;
+
+use sc_client_api::BlockBackend;
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_rpc::tracing::{BlockTrace, Data, Event, Span};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header},
+};
+use sp_tracing::{WASM_NAME_KEY, WASM_TARGET_KEY, WASM_TRACE_IDENTIFIER};
+
+use crate::{parse_target, SpanDatum, TraceEvent, Values, ZERO_DURATION};
+
+// Default to only pallet, frame support and state related traces
+const DEFAULT_TARGETS: &str = "pallet,frame,state";
+// Target of the logs emitted by this module.
+const LOG_TARGET: &str = "state_tracing";
+// The name of a field required for all events.
+const REQUIRED_EVENT_FIELD: &str = "method";
+// The name of the span that `frame-executive` enters for every applied extrinsic.
+const APPLY_EXTRINSIC_SPAN: &str = "apply_extrinsic";
+// The name of the value that holds the storage key of a storage access.
+const STORAGE_KEY_FIELD: &str = "key";
+
+/// Tracing Block Result type alias
+pub type TraceBlockResult<T> = Result<T, Error>;
+
+/// Tracing Block error
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub enum Error {
+	#[error("Invalid block Id: {0}")]
+	InvalidBlockId(#[from] sp_blockchain::Error),
+	#[error("Missing block component: {0}")]
+	MissingBlockComponent(String),
+	#[error("Dispatch error: {0}")]
+	Dispatch(String),
+}
+
+/// A span recorded while executing the block.
+struct BlockSpan {
+	datum: SpanDatum,
+	extrinsic_index: Option<u32>,
+}
+
+/// An event recorded while executing the block.
+struct BlockEvent {
+	event: TraceEvent,
+	extrinsic_index: Option<u32>,
+}
+
+/// Subscriber that records the spans and events of a single block execution.
+///
+/// Spans are assigned to the extrinsic whose `apply_extrinsic` span encloses them. Only
+/// events that carry a `method` field are recorded which are the storage accesses of the
+/// state machine.
+struct BlockSubscriber {
+	targets: Vec<(String, Level)>,
+	next_id: AtomicU64,
+	next_extrinsic: AtomicU32,
+	current_span: CurrentSpan,
+	spans: Mutex<HashMap<Id, BlockSpan>>,
+	events: Mutex<Vec<BlockEvent>>,
+}
+
+impl BlockSubscriber {
+	fn new(targets: &str) -> Self {
+		Self {
+			targets: targets.split(',').map(parse_target).collect(),
+			// `Id`s must not be zero.
+			next_id: AtomicU64::new(1),
+			next_extrinsic: AtomicU32::new(0),
+			current_span: CurrentSpan::default(),
+			spans: Mutex::new(HashMap::new()),
+			events: Mutex::new(Vec::new()),
+		}
+	}
+
+	fn check_target(&self, target: &str, level: &Level) -> bool {
+		self.targets.iter().any(|(t, l)| target.starts_with(t.as_str()) && level <= l)
+	}
+
+	fn extrinsic_index(&self, span: Option<&Id>) -> Option<u32> {
+		span.and_then(|id| self.spans.lock().get(id).and_then(|s| s.extrinsic_index))
+	}
+
+	/// Replace the placeholders of spans that originate from wasm and drop all spans
+	/// whose target was not requested.
+	fn into_span(&self, span: BlockSpan) -> Option<Span> {
+		let BlockSpan { mut datum, extrinsic_index } = span;
+		let wasm = datum.name == WASM_TRACE_IDENTIFIER;
+		if wasm {
+			if let Some(name) = datum.values.string_values.remove(WASM_NAME_KEY) {
+				datum.name = name;
+			}
+			if let Some(target) = datum.values.string_values.remove(WASM_TARGET_KEY) {
+				datum.target = target;
+			}
+		}
+		if !self.check_target(&datum.target, &datum.level) {
+			return None;
+		}
+		Some(Span {
+			id: datum.id.into_u64(),
+			parent_id: datum.parent_id.map(|id| id.into_u64()),
+			name: datum.name,
+			target: datum.target,
+			wasm,
+			data: datum.values.into(),
+			extrinsic_index,
+		})
+	}
+}
+
+impl Subscriber for BlockSubscriber {
+	fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+		if metadata.is_span() {
+			// Spans of wasm are checked against the targets once their real target is known.
+			// Extrinsic spans are always recorded in order to attribute the traces to them.
+			if metadata.name() == WASM_TRACE_IDENTIFIER || metadata.name() == APPLY_EXTRINSIC_SPAN {
+				return true;
+			}
+		} else if metadata.fields().field(REQUIRED_EVENT_FIELD).is_none() {
+			return false;
+		}
+		self.check_target(metadata.target(), metadata.level())
+	}
+
+	fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+		let id = Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed));
+		let mut values = Values::default();
+		attrs.record(&mut values);
+		let parent_id = attrs.parent().cloned().or_else(|| self.current_span.id());
+		let name = if attrs.metadata().name() == WASM_TRACE_IDENTIFIER {
+			values.string_values.get(WASM_NAME_KEY).map(String::as_str)
+		} else {
+			Some(attrs.metadata().name())
+		};
+		let extrinsic_index = if name == Some(APPLY_EXTRINSIC_SPAN) {
+			Some(self.next_extrinsic.fetch_add(1, Ordering::Relaxed))
+		} else {
+			self.extrinsic_index(parent_id.as_ref())
+		};
+		let datum = SpanDatum {
+			id: id.clone(),
+			parent_id,
+			name: attrs.metadata().name().to_owned(),
+			target: attrs.metadata().target().to_owned(),
+			level: attrs.metadata().level().clone(),
+			line: attrs.metadata().line().unwrap_or(0),
+			start_time: Instant::now(),
+			overall_time: ZERO_DURATION,
+			values,
+		};
+		self.spans.lock().insert(id.clone(), BlockSpan { datum, extrinsic_index });
+		id
+	}
+
+	fn record(&self, span: &Id, values: &Record<'_>) {
+		if let Some(s) = self.spans.lock().get_mut(span) {
+			values.record(&mut s.datum.values);
+		}
+	}
+
+	fn record_follows_from(&self, _span: &Id, _follows: &Id) {
+		// Not recorded
+	}
+
+	fn event(&self, event: &tracing::Event<'_>) {
+		let parent_id = event.parent().cloned().or_else(|| self.current_span.id());
+		let extrinsic_index = self.extrinsic_index(parent_id.as_ref());
+		let mut values = Values::default();
+		event.record(&mut values);
+		let event = TraceEvent {
+			name: event.metadata().name(),
+			target: event.metadata().target().to_owned(),
+			level: event.metadata().level().clone(),
+			values,
+			parent_id,
+		};
+		self.events.lock().push(BlockEvent { event, extrinsic_index });
+	}
+
+	fn enter(&self, span: &Id) {
+		self.current_span.enter(span.clone());
+		if let Some(s) = self.spans.lock().get_mut(span) {
+			s.datum.start_time = Instant::now();
+		}
+	}
+
+	fn exit(&self, span: &Id) {
+		self.current_span.exit();
+		let end_time = Instant::now();
+		if let Some(s) = self.spans.lock().get_mut(span) {
+			s.datum.overall_time += end_time - s.datum.start_time;
+		}
+	}
+}
+
+impl From<Values> for Data {
+	fn from(values: Values) -> Self {
+		let bool_values = values.bool_values.into_iter().map(|(k, v)| (k, v.to_string()));
+		let i64_values = values.i64_values.into_iter().map(|(k, v)| (k, v.to_string()));
+		let u64_values = values.u64_values.into_iter().map(|(k, v)| (k, v.to_string()));
+		Self {
+			string_values: bool_values
+				.chain(i64_values)
+				.chain(u64_values)
+				.chain(values.string_values)
+				.collect(),
+		}
+	}
+}
+
+/// Re-executes a block in order to collect the spans and events of its execution.
+///
+/// Spans and events are only recorded for the supplied comma separated `targets`
+/// (eg. "pallet,frame,state") which default to [`DEFAULT_TARGETS`]. Events are further
+/// filtered by the comma separated list of hex encoded storage key prefixes in
+/// `storage_keys`. An empty string disables the respective filter.
+pub struct BlockExecutor<Block: BlockT, Client> {
+	client: Arc<Client>,
+	block: Block::Hash,
+	targets: Option<String>,
+	storage_keys: Option<String>,
+}
+
+impl<Block, Client> BlockExecutor<Block, Client>
+	where
+		Block: BlockT + 'static,
+		Client: HeaderBackend<Block> + BlockBackend<Block> + ProvideRuntimeApi<Block>
+			+ Send + Sync + 'static,
+		Client::Api: Core<Block>,
+{
+	/// Create a new `BlockExecutor`
+	pub fn new(
+		client: Arc<Client>,
+		block: Block::Hash,
+		targets: Option<String>,
+		storage_keys: Option<String>,
+	) -> Self {
+		Self { client, block, targets, storage_keys }
+	}
+
+	/// Execute block, record all spans and events belonging to `Self::targets`
+	/// and filter out events which do not have keys starting with one of the
+	/// prefixes in `Self::storage_keys`.
+	pub fn trace_block(&self) -> TraceBlockResult<BlockTrace> {
+		tracing::debug!(target: LOG_TARGET, "Tracing block: {}", self.block);
+		// Prepare the block
+		let id = BlockId::Hash(self.block);
+		let mut header = self.client.header(id)?
+			.ok_or_else(|| Error::MissingBlockComponent("Header not found".to_string()))?;
+		let extrinsics = self.client.block_body(&id)?
+			.ok_or_else(|| Error::MissingBlockComponent("Extrinsics not found".to_string()))?;
+		let parent_hash = *header.parent_hash();
+		let parent_id = BlockId::Hash(parent_hash);
+		// Remove all `Seal`s as they are added by the consensus engines after building the block.
+		// On import they are normally removed by the consensus engine.
+		header.digest_mut().logs.retain(|d| d.as_seal().is_none());
+		let block = Block::new(header, extrinsics);
+
+		let targets = self.targets.as_deref().unwrap_or(DEFAULT_TARGETS);
+		let dispatch = Dispatch::new(BlockSubscriber::new(targets));
+		dispatcher::with_default(&dispatch, || {
+			self.client.runtime_api().execute_block(&parent_id, block)
+		}).map_err(|e| Error::Dispatch(
+			format!("Failed to collect traces and execute block: {:?}", e)
+		))?;
+
+		let block_subscriber = dispatch.downcast_ref::<BlockSubscriber>()
+			.ok_or_else(|| Error::Dispatch(
+				"Cannot downcast Dispatch to BlockSubscriber after tracing block".to_string()
+			))?;
+		let mut spans: Vec<_> = block_subscriber.spans.lock()
+			.drain()
+			.filter_map(|(_, span)| block_subscriber.into_span(span))
+			.collect();
+		spans.sort_by_key(|span| span.id);
+		let storage_keys = self.storage_keys.as_deref().unwrap_or_default();
+		let key_prefixes = parse_storage_keys(storage_keys);
+		let events: Vec<_> = block_subscriber.events.lock()
+			.drain(..)
+			.filter(|e| event_key_filter(&e.event, &key_prefixes))
+			.map(|e| Event {
+				target: e.event.target,
+				data: e.event.values.into(),
+				parent_id: e.event.parent_id.map(|id| id.into_u64()),
+				extrinsic_index: e.extrinsic_index,
+			})
+			.collect();
+		tracing::debug!(
+			target: LOG_TARGET,
+			"Captured {} spans and {} events",
+			spans.len(),
+			events.len(),
+		);
+
+		Ok(BlockTrace {
+			// `Debug` prints the full hash whereas `Display` abbreviates it.
+			block_hash: format!("{:?}", self.block),
+			parent_hash: format!("{:?}", parent_hash),
+			tracing_targets: targets.to_string(),
+			storage_keys: storage_keys.to_string(),
+			spans,
+			events,
+		})
+	}
+}
+
+/// Parse a comma separated list of hex encoded storage key prefixes.
+fn parse_storage_keys(storage_keys: &str) -> Vec<String> {
+	storage_keys
+		.split(',')
+		.map(|key| key.trim().trim_start_matches("0x").to_lowercase())
+		.filter(|key| !key.is_empty())
+		.collect()
+}
+
+/// Filter out events whose storage key does not start with one of the supplied prefixes.
+///
+/// No event is filtered out when no prefixes are supplied.
+fn event_key_filter(event: &TraceEvent, key_prefixes: &[String]) -> bool {
+	if key_prefixes.is_empty() {
+		return true;
+	}
+	event.values.string_values.get(STORAGE_KEY_FIELD)
+		.map(|key| key_prefixes.iter().any(|prefix| key.starts_with(prefix.as_str())))
+		.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn storage_event(key: &str) -> TraceEvent {
+		let mut values = Values::default();
+		values.string_values.insert(REQUIRED_EVENT_FIELD.to_owned(), "Put".to_owned());
+		values.string_values.insert(STORAGE_KEY_FIELD.to_owned(), key.to_owned());
+		TraceEvent {
+			name: "event",
+			target: "state".to_owned(),
+			level: Level::TRACE,
+			values,
+			parent_id: None,
+		}
+	}
+
+	#[test]
+	fn storage_keys_are_parsed() {
+		assert_eq!(parse_storage_keys(""), Vec::<String>::new());
+		assert_eq!(parse_storage_keys("0xAB, cd"), vec!["ab".to_owned(), "cd".to_owned()]);
+	}
+
+	#[test]
+	fn events_are_filtered_by_key_prefix() {
+		let event = storage_event("26aa394eea5630e07c48ae0c9558cef7");
+		assert!(event_key_filter(&event, &[]));
+		assert!(event_key_filter(&event, &parse_storage_keys("0x26aa")));
+		assert!(!event_key_filter(&event, &parse_storage_keys("0x3a63")));
+	}
+
+	#[test]
+	fn spans_and_events_are_attributed_to_extrinsics() {
+		let dispatch = Dispatch::new(BlockSubscriber::new(DEFAULT_TARGETS));
+		dispatcher::with_default(&dispatch, || {
+			let init = tracing::info_span!(target: "frame_executive", "init_block");
+			init.in_scope(|| {
+				tracing::trace!(target: "state", method = "Get", key = "00");
+			});
+			for _ in 0..2 {
+				let span = tracing::info_span!(target: "frame_executive", APPLY_EXTRINSIC_SPAN);
+				span.in_scope(|| {
+					let nested = tracing::info_span!(target: "pallet_balances", "transfer");
+					nested.in_scope(|| {
+						tracing::trace!(target: "state", method = "Put", key = "01");
+					});
+				});
+			}
+			// Events without the required field and spans of other targets are ignored.
+			tracing::trace!(target: "state", "not a storage access");
+			tracing::info_span!(target: "other", "other").in_scope(|| ());
+		});
+
+		let subscriber = dispatch.downcast_ref::<BlockSubscriber>().unwrap();
+		let mut spans: Vec<_> = subscriber.spans.lock()
+			.drain()
+			.filter_map(|(_, span)| subscriber.into_span(span))
+			.map(|span| (span.name, span.extrinsic_index))
+			.collect();
+		spans.sort();
+		assert_eq!(spans, vec![
+			(APPLY_EXTRINSIC_SPAN.to_owned(), Some(0)),
+			(APPLY_EXTRINSIC_SPAN.to_owned(), Some(1)),
+			("init_block".to_owned(), None),
+			("transfer".to_owned(), Some(0)),
+			("transfer".to_owned(), Some(1)),
+		]);
+		let events: Vec<_> = subscriber.events.lock()
+			.iter()
+			.map(|e| e.extrinsic_index)
+			.collect();
+		assert_eq!(events, vec![None, Some(0), Some(1)]);
+	}
+}
@@ -25,9 +25,13 @@
 //! See `sp-tracing` for examples on how to use tracing.
 //!
 //! Currently we only provide `Log` (default).
+//!
+//! The [`block`] module allows to re-execute a block in order to collect the spans and events
+//! of its execution.
 
 #![warn(missing_docs)]
 
+pub mod block;
 pub mod logging;
 
 use rustc_hash::FxHashMap;
@@ -21,7 +21,7 @@
 //! For a more full-featured pool, have a look at the `pool` module.
 
 use std::{
-	collections::HashSet,
+	collections::{HashMap, HashSet},
 	fmt,
 	hash,
 	sync::Arc,
@@ -218,6 +218,10 @@ pub struct BasePool<Hash: hash::Hash + Eq, Ex> {
 	/// transactions to future in case they were just stuck in verification.
 	recently_pruned: [HashSet<Tag>; RECENTLY_PRUNED_TAGS],
 	recently_pruned_index: usize,
+	/// Hashes of the transactions in the pool by the key of their sender.
+	///
+	/// Transactions that left the pool are removed lazily, see [`BasePool::sender_count`].
+	senders: HashMap<Vec<u8>, HashSet<Hash>>,
 }
 
 impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> Default for BasePool<Hash, Ex> {
@@ -235,6 +239,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			ready: Default::default(),
 			recently_pruned: Default::default(),
 			recently_pruned_index: 0,
+			senders: Default::default(),
 		}
 	}
 
@@ -392,7 +397,8 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 	///
 	/// Removes and returns worst transactions from the queues and all transactions that depend on them.
 	/// Technically the worst transaction should be evaluated by computing the entire pending set.
-	/// We use a simplified approach to remove the transaction that occupies the pool for the longest time.
+	/// We use a simplified approach to remove the transaction with the lowest priority and,
+	/// among those, the one that occupies the pool for the longest time.
 	pub fn enforce_limits(&mut self, ready: &Limit, future: &Limit) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		let mut removed = vec![];
 
@@ -403,7 +409,9 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 					let transaction = &current.transaction;
 					match minimal {
 						None => Some(transaction.clone()),
-						Some(ref tx) if tx.insertion_id > transaction.insertion_id => {
+						Some(ref tx) if (tx.transaction.priority, tx.insertion_id)
+							> (transaction.transaction.priority, transaction.insertion_id) =>
+						{
 							Some(transaction.clone())
 						},
 						other => other,
@@ -423,7 +431,9 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 				.fold(|minimal, current| {
 					match minimal {
 						None => Some(current.clone()),
-						Some(ref tx) if tx.imported_at > current.imported_at => {
+						Some(ref tx) if (tx.transaction.priority, tx.imported_at)
+							> (current.transaction.priority, current.imported_at) =>
+						{
 							Some(current.clone())
 						},
 						other => other,
@@ -440,6 +450,34 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		removed
 	}
 
+	/// Returns the number of transactions of the sender with the given key in the pool.
+	///
+	/// Only transactions that were attributed to the sender with [`BasePool::track_sender`]
+	/// are counted.
+	pub fn sender_count(&mut self, sender: &[u8]) -> usize {
+		let (ready, future) = (&self.ready, &self.future);
+		match self.senders.get_mut(sender) {
+			Some(hashes) => {
+				hashes.retain(|hash| ready.contains(hash) || future.contains(hash));
+				hashes.len()
+			},
+			None => 0,
+		}
+	}
+
+	/// Returns `true` if importing `tx` replaces transactions in the ready queue.
+	///
+	/// Ready transactions providing the same tags as `tx` are replaced if `tx` has a higher
+	/// priority, otherwise its import fails. The future queue never replaces transactions.
+	pub fn replaces_ready(&self, tx: &Transaction<Hash, Ex>) -> bool {
+		tx.provides.iter().any(|tag| self.ready.provided_tags().contains_key(tag))
+	}
+
+	/// Attribute the transaction with the given hash to the sender with the given key.
+	pub fn track_sender(&mut self, sender: Vec<u8>, hash: Hash) {
+		self.senders.entry(sender).or_default().insert(hash);
+	}
+
 	/// Removes all transactions represented by the hashes and all other transactions
 	/// that depend on them.
 	///
@@ -481,6 +519,13 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 			recently_pruned.insert(tag);
 		}
 
+		// forget the senders of transactions that left the pool
+		let (ready, future) = (&self.ready, &self.future);
+		self.senders.retain(|_, hashes| {
+			hashes.retain(|hash| ready.contains(hash) || future.contains(hash));
+			!hashes.is_empty()
+		});
+
 		let mut promoted = vec![];
 		let mut failed = vec![];
 		for tx in to_import {
@@ -1186,4 +1231,87 @@ source: TransactionSource::External, requires: [03, 02], provides: [04], data: [
 		assert_eq!(pool.reject_future_transactions, true);
 		assert_eq!(pool.future.len(), 1);
 	}
+
+	fn transaction(
+		hash: Hash,
+		priority: Priority,
+		requires: Vec<Tag>,
+		provides: Vec<Tag>,
+	) -> Transaction<Hash, Vec<u8>> {
+		Transaction {
+			data: vec![hash as u8],
+			bytes: 1,
+			hash,
+			priority,
+			valid_till: 64u64,
+			requires,
+			provides,
+			propagate: true,
+			source: Source::External,
+		}
+	}
+
+	#[test]
+	fn should_enforce_limits_by_evicting_lowest_priority_first() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 2, total_bytes: 1000 };
+		pool.import(transaction(1, 5, vec![], vec![vec![1]])).unwrap();
+		pool.import(transaction(2, 1, vec![], vec![vec![2]])).unwrap();
+		pool.import(transaction(3, 9, vec![vec![2]], vec![vec![3]])).unwrap();
+		pool.import(transaction(4, 3, vec![], vec![vec![4]])).unwrap();
+		assert_eq!(pool.ready().count(), 4);
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit);
+
+		// then
+		// the lowest priority transaction is removed together with its dependent transaction
+		let mut removed = removed.iter().map(|tx| tx.hash).collect::<Vec<_>>();
+		removed.sort();
+		assert_eq!(removed, vec![2, 3]);
+		let mut ready = pool.ready().map(|tx| tx.hash).collect::<Vec<_>>();
+		ready.sort();
+		assert_eq!(ready, vec![1, 4]);
+	}
+
+	#[test]
+	fn should_enforce_future_limits_by_evicting_lowest_priority_first() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 1, total_bytes: 1000 };
+		pool.import(transaction(1, 9, vec![vec![0]], vec![vec![1]])).unwrap();
+		pool.import(transaction(2, 1, vec![vec![0]], vec![vec![2]])).unwrap();
+		assert_eq!(pool.futures().count(), 2);
+
+		// when
+		let removed = pool.enforce_limits(&limit, &limit);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+		assert_eq!(pool.futures().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1]);
+	}
+
+	#[test]
+	fn should_count_transactions_of_sender() {
+		// given
+		let mut pool = pool();
+		pool.import(transaction(1, 5, vec![], vec![vec![1]])).unwrap();
+		pool.track_sender(vec![1], 1);
+		pool.import(transaction(2, 5, vec![vec![9]], vec![vec![2]])).unwrap();
+		pool.track_sender(vec![1], 2);
+		pool.import(transaction(3, 5, vec![], vec![vec![3]])).unwrap();
+		pool.track_sender(vec![2], 3);
+		assert_eq!(pool.sender_count(&[1]), 2);
+		assert_eq!(pool.sender_count(&[2]), 1);
+		assert_eq!(pool.sender_count(&[3]), 0);
+
+		// when
+		pool.remove_subtree(&[2]);
+		pool.prune_tags(vec![vec![3]]);
+
+		// then
+		assert_eq!(pool.sender_count(&[1]), 1);
+		assert!(!pool.senders.contains_key(&vec![2]));
+	}
 }
@@ -40,5 +40,5 @@ pub mod watcher;
 pub use self::base_pool::Transaction;
 pub use self::pool::{
 	BlockHash, ChainApi, EventStream, ExtrinsicFor, ExtrinsicHash, IsValidator, NumberFor, Options,
-	Pool, TransactionFor, ValidatedTransaction,
+	PersistenceOptions, Pool, TransactionFor, ValidatedTransaction,
 };
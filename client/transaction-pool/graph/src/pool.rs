@@ -18,7 +18,9 @@
 
 use std::{
 	collections::HashMap,
+	path::PathBuf,
 	sync::Arc,
+	time::Duration,
 };
 
 use futures::Future;
@@ -106,6 +108,30 @@ pub struct Options {
 	pub future: base::Limit,
 	/// Reject future transactions.
 	pub reject_future_transactions: bool,
+	/// Maximal number of signed transactions of a single sender in the pool.
+	///
+	/// Unlimited if `None`.
+	pub max_per_sender: Option<usize>,
+	/// Length of the key that identifies the sender of a transaction.
+	///
+	/// Transactions are attributed to the same sender if the first tags they provide start
+	/// with the same key. This is the size of the account id for signed FRAME transactions.
+	pub sender_key_len: usize,
+	/// Persist pending transactions across restarts.
+	///
+	/// Disabled if `None`.
+	pub persistence: Option<PersistenceOptions>,
+}
+
+/// Where and how often pending transactions are persisted.
+#[derive(Debug, Clone)]
+pub struct PersistenceOptions {
+	/// File that the pending transactions are written to.
+	pub path: PathBuf,
+	/// How often the pending transactions are written.
+	///
+	/// They are always written when the pool shuts down.
+	pub interval: Duration,
 }
 
 impl Default for Options {
@@ -120,6 +146,9 @@ impl Default for Options {
 				total_bytes: 1 * 1024 * 1024,
 			},
 			reject_future_transactions: false,
+			max_per_sender: None,
+			sender_key_len: 32,
+			persistence: None,
 		}
 	}
 }
@@ -476,6 +505,7 @@ mod tests {
 		invalidate: Arc<Mutex<HashSet<H256>>>,
 		clear_requirements: Arc<Mutex<HashSet<H256>>>,
 		add_requirements: Arc<Mutex<HashSet<H256>>>,
+		priorities: Arc<Mutex<HashMap<H256, u64>>>,
 	}
 
 	impl ChainApi for TestApi {
@@ -514,7 +544,7 @@ mod tests {
 						InvalidTransaction::Stale.into()
 					} else {
 						let mut transaction = ValidTransaction {
-							priority: 4,
+							priority: self.priorities.lock().get(&hash).cloned().unwrap_or(4),
 							requires: if nonce > block_number { vec![vec![nonce as u8 - 1]] } else { vec![] },
 							provides: if nonce == INVALID_NONCE { vec![] } else { vec![vec![nonce as u8]] },
 							longevity: 3,
@@ -832,6 +862,176 @@ mod tests {
 		assert_matches!(err, error::Error::NoTagsProvided);
 	}
 
+	#[test]
+	fn should_evict_lowest_priority_transaction_if_limit_is_reached() {
+		// given
+		let limit = Limit {
+			count: 1,
+			total_bytes: 1000,
+		};
+		let options = Options {
+			ready: limit.clone(),
+			future: limit.clone(),
+			..Default::default()
+		};
+		let pool = Pool::new(options, true.into(), TestApi::default().into());
+		let xt = uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce: 0,
+		});
+		let watcher = block_on(pool.submit_and_watch(&BlockId::Number(0), SOURCE, xt.clone()))
+			.unwrap();
+
+		// when
+		let hash = block_on(
+			pool.submit_one(&BlockId::Number(0), SOURCE, Extrinsic::IncludeData(vec![42])),
+		).unwrap();
+
+		// then
+		let ready = pool.validated_pool().ready().map(|tx| tx.hash).collect::<Vec<_>>();
+		assert_eq!(ready, vec![hash]);
+		let mut stream = futures::executor::block_on_stream(watcher.into_stream());
+		assert_eq!(stream.next(), Some(TransactionStatus::Ready));
+		assert_eq!(stream.next(), Some(TransactionStatus::Dropped));
+
+		// and a transaction with lower priority than everything in the pool does not get in
+		let err = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, xt)).unwrap_err();
+		assert_matches!(err, error::Error::TemporarilyBanned);
+		let xt = uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(2)),
+			to: AccountId::from_h256(H256::from_low_u64_be(1)),
+			amount: 5,
+			nonce: 0,
+		});
+		let err = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, xt)).unwrap_err();
+		assert_matches!(err, error::Error::ImmediatelyDropped);
+		assert_eq!(pool.validated_pool().status().ready, 1);
+	}
+
+	#[test]
+	fn should_limit_transactions_per_sender() {
+		// given
+		// The tags of the `TestApi` consist of the nonce only, so all transactions are
+		// attributed to the same sender.
+		let options = Options {
+			max_per_sender: Some(2),
+			sender_key_len: 0,
+			..Default::default()
+		};
+		let pool = Pool::new(options, true.into(), TestApi::default().into());
+		let transfer = |nonce| uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce,
+		});
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(0))).unwrap();
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(1))).unwrap();
+
+		// when
+		let err = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(2))).unwrap_err();
+
+		// then
+		assert_matches!(err, error::Error::TooManyFromSender);
+		assert_eq!(pool.validated_pool().status().ready, 2);
+		assert_eq!(pool.validated_pool().status().future, 0);
+	}
+
+	#[test]
+	fn should_not_limit_unsigned_transactions() {
+		// given
+		let options = Options {
+			max_per_sender: Some(1),
+			sender_key_len: 0,
+			..Default::default()
+		};
+		let pool = Pool::new(options, true.into(), TestApi::default().into());
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce: 0,
+		}))).unwrap();
+
+		// when
+		let xt = Extrinsic::IncludeData(vec![42]);
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, xt)).unwrap();
+
+		// then
+		assert_eq!(pool.validated_pool().status().ready, 2);
+	}
+
+	#[test]
+	fn should_allow_replacing_transactions_of_sender_at_limit() {
+		// given
+		let options = Options {
+			max_per_sender: Some(2),
+			sender_key_len: 0,
+			..Default::default()
+		};
+		let api = Arc::new(TestApi::default());
+		let pool = Pool::new(options, true.into(), api.clone());
+		let transfer = |nonce, amount| uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount,
+			nonce,
+		});
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(0, 5))).unwrap();
+		let replaced = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(1, 5)))
+			.unwrap();
+		let replacement = transfer(1, 6);
+		api.priorities.lock().insert(pool.hash_of(&replacement), 5);
+
+		// when
+		let hash = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, replacement)).unwrap();
+
+		// then
+		let ready = pool.validated_pool().ready().map(|tx| tx.hash).collect::<Vec<_>>();
+		assert_eq!(ready.len(), 2);
+		assert!(ready.contains(&hash));
+		assert!(!ready.contains(&replaced));
+		let err = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(2, 5)))
+			.unwrap_err();
+		assert_matches!(err, error::Error::TooManyFromSender);
+	}
+
+	#[test]
+	fn should_limit_transactions_per_sender_with_nonce_gaps() {
+		// given
+		let options = Options {
+			max_per_sender: Some(2),
+			sender_key_len: 0,
+			..Default::default()
+		};
+		let pool = Pool::new(options, true.into(), TestApi::default().into());
+		let transfer = |nonce| uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce,
+		});
+		// none of these depend on a transaction in the pool
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(3))).unwrap();
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(6))).unwrap();
+
+		// when
+		let err = block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(9))).unwrap_err();
+
+		// then
+		assert_matches!(err, error::Error::TooManyFromSender);
+		assert_eq!(pool.validated_pool().status().ready, 0);
+		assert_eq!(pool.validated_pool().status().future, 2);
+
+		// a transaction that leaves the pool frees its slot
+		let hash = pool.hash_of(&transfer(3));
+		pool.validated_pool().remove_invalid(&[hash]);
+		block_on(pool.submit_one(&BlockId::Number(0), SOURCE, transfer(9))).unwrap();
+		assert_eq!(pool.validated_pool().status().future, 2);
+	}
+
 	mod listener {
 		use super::*;
 
@@ -26,7 +26,7 @@ use serde::Serialize;
 use parking_lot::{Mutex, RwLock};
 use sp_runtime::{
 	generic::BlockId,
-	traits::{self, SaturatedConversion},
+	traits::{self, Extrinsic, SaturatedConversion},
 	transaction_validity::{TransactionTag as Tag, ValidTransaction, TransactionSource},
 };
 use sp_transaction_pool::{error, PoolStatus};
@@ -103,6 +103,14 @@ impl From<Box<dyn Fn() -> bool + Send + Sync>> for IsValidator {
 	}
 }
 
+/// Returns the key of the sender of `tx`.
+///
+/// This is the prefix of the first tag that the transaction provides. Signed FRAME transactions
+/// provide the encoded `(account, nonce)`, so their key is the account if `len` is its size.
+fn sender_key<Hash, Ex>(tx: &base::Transaction<Hash, Ex>, len: usize) -> Option<Vec<u8>> {
+	tx.provides.first().map(|tag| tag[..len.min(tag.len())].to_vec())
+}
+
 /// Pool that deals with validated transactions.
 pub struct ValidatedPool<B: ChainApi> {
 	api: Arc<B>,
@@ -202,7 +210,30 @@ impl<B: ChainApi> ValidatedPool<B> {
 					return Err(error::Error::Unactionable.into());
 				}
 
-				let imported = self.pool.write().import(tx)?;
+				let imported = {
+					let mut pool = self.pool.write();
+					let key_len = self.options.sender_key_len;
+					let sender = match self.options.max_per_sender {
+						// Unsigned transactions have no sender. Extrinsics that don't know whether
+						// they are signed are treated as signed.
+						Some(max) if tx.data.is_signed().unwrap_or(true) =>
+							sender_key(&tx, key_len).map(|key| (max, key)),
+						_ => None,
+					};
+					match sender {
+						Some((max_per_sender, key)) => {
+							// A replacement doesn't add a transaction of the sender to the pool.
+							let at_limit = pool.sender_count(&key) >= max_per_sender;
+							if at_limit && !pool.replaces_ready(&tx) {
+								return Err(error::Error::TooManyFromSender.into());
+							}
+							let imported = pool.import(tx)?;
+							pool.track_sender(key, *imported.hash());
+							imported
+						},
+						None => pool.import(tx)?,
+					}
+				};
 
 				if let base::Imported::Ready { ref hash, .. } = imported {
 					self.import_notification_sinks.lock()
@@ -617,6 +648,13 @@ impl<B: ChainApi> ValidatedPool<B> {
 			.collect()
 	}
 
+	/// Returns the source and data of the transactions in the future queue.
+	pub fn futures_with_source(&self) -> Vec<(TransactionSource, ExtrinsicFor<B>)> {
+		self.pool.read().futures()
+			.map(|tx| (tx.source, tx.data.clone()))
+			.collect()
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> PoolStatus {
 		self.pool.read().status()
@@ -25,6 +25,7 @@
 mod api;
 mod revalidation;
 mod metrics;
+mod persistence;
 
 pub mod error;
 
@@ -81,6 +82,8 @@ pub struct BasicPool<PoolApi, Block>
 	revalidation_queue: Arc<revalidation::RevalidationQueue<PoolApi>>,
 	ready_poll: Arc<Mutex<ReadyPoll<ReadyIteratorFor<PoolApi>, Block>>>,
 	metrics: PrometheusMetrics,
+	/// Writes the pending transactions when the pool is dropped.
+	persistence: Option<persistence::Handle>,
 }
 
 struct ReadyPoll<T, Block: BlockT> {
@@ -174,6 +177,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 				revalidation_strategy: Arc::new(Mutex::new(RevalidationStrategy::Always)),
 				ready_poll: Default::default(),
 				metrics: Default::default(),
+				persistence: None,
 			},
 			background_task,
 			notifier,
@@ -215,6 +219,7 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 			)),
 			ready_poll: Default::default(),
 			metrics: PrometheusMetrics::new(prometheus),
+			persistence: None,
 		}
 	}
 
@@ -224,6 +229,18 @@ impl<PoolApi, Block> BasicPool<PoolApi, Block>
 	}
 }
 
+impl<PoolApi, Block> Drop for BasicPool<PoolApi, Block>
+	where
+		Block: BlockT,
+		PoolApi: ChainApi<Block=Block>,
+{
+	fn drop(&mut self) {
+		if let Some(persistence) = self.persistence.take() {
+			persistence.persist_on_shutdown(&self.pool);
+		}
+	}
+}
+
 impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 	where
 		Block: BlockT,
@@ -358,21 +375,37 @@ where
 	Client: sp_api::ProvideRuntimeApi<Block>
 		+ sc_client_api::BlockBackend<Block>
 		+ sp_runtime::traits::BlockIdTo<Block>,
-	Client: sc_client_api::ExecutorProvider<Block> + Send + Sync + 'static,
+	Client: sc_client_api::ExecutorProvider<Block> + sp_blockchain::HeaderBackend<Block>,
+	Client: Send + Sync + 'static,
 	Client::Api: sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
 {
 	/// Create new basic transaction pool for a full node with the provided api.
+	///
+	/// If persistence is enabled in `options`, the persisted transactions are revalidated
+	/// against the best block and imported again.
 	pub fn new_full(
 		options: sc_transaction_graph::Options,
 		is_validator: txpool::IsValidator,
 		prometheus: Option<&PrometheusRegistry>,
-		spawner: impl SpawnNamed,
+		spawner: impl SpawnNamed + Clone,
 		client: Arc<Client>,
 	) -> Arc<Self> {
+		let persistence = options.persistence.clone();
 		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus));
-		let pool = Arc::new(Self::with_revalidation_type(
-			options, is_validator, pool_api, prometheus, RevalidationType::Full, spawner
-		));
+		let mut pool = Self::with_revalidation_type(
+			options, is_validator, pool_api, prometheus, RevalidationType::Full, spawner.clone(),
+		);
+
+		if let Some(persistence) = persistence {
+			let handle = persistence::Handle::new(persistence.path.clone());
+			let at = BlockId::Hash(client.info().best_hash);
+			spawner.spawn(
+				"txpool-persistence",
+				persistence::run(pool.pool.clone(), persistence, at, handle.clone()).boxed(),
+			);
+			pool.persistence = Some(handle);
+		}
+		let pool = Arc::new(pool);
 
 		// make transaction pool available for off-chain runtime calls.
 		client.execution_extensions().register_transaction_pool(&pool);
@@ -0,0 +1,192 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Persisting pending transactions across restarts.
+//!
+//! The extrinsics of all ready and future transactions are written to a file as a SCALE
+//! encoded list together with their source. On startup they are submitted to the pool again
+//! from the same source, which revalidates them against the best block and only imports the
+//! valid ones.
+
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+	sync::{Arc, atomic::{AtomicBool, Ordering}},
+};
+
+use codec::{Decode, Encode};
+use futures::prelude::*;
+use intervalier::IntoStream;
+use sc_transaction_graph::{ChainApi, ExtrinsicFor, PersistenceOptions, Pool};
+use sp_runtime::{generic::BlockId, transaction_validity::TransactionSource};
+
+/// Write the extrinsics of all ready and future transactions in `pool` to `path`.
+///
+/// The file is replaced atomically. Returns the number of written transactions.
+pub fn save<Api: ChainApi>(path: &Path, pool: &Pool<Api>) -> io::Result<usize> {
+	let validated_pool = pool.validated_pool();
+	let extrinsics = validated_pool.ready()
+		.map(|tx| (tx.source, tx.data.clone()))
+		.chain(validated_pool.futures_with_source())
+		.collect::<Vec<_>>();
+
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	let tmp_path = path.with_extension("tmp");
+	fs::write(&tmp_path, extrinsics.encode())?;
+	fs::rename(&tmp_path, path)?;
+
+	Ok(extrinsics.len())
+}
+
+/// Read the extrinsics that were written by [`save`].
+///
+/// Returns an empty list if nothing was persisted yet.
+pub fn load<Api: ChainApi>(
+	path: &Path,
+) -> io::Result<Vec<(TransactionSource, ExtrinsicFor<Api>)>> {
+	let data = match fs::read(path) {
+		Ok(data) => data,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+		Err(e) => return Err(e),
+	};
+
+	Decode::decode(&mut &data[..])
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+/// Submit the persisted transactions to `pool` at the given block.
+///
+/// Every transaction is validated again from the source it was originally submitted from
+/// and only the valid ones are imported. Returns the number of imported transactions.
+pub async fn restore<Api: ChainApi>(
+	path: &Path,
+	pool: &Pool<Api>,
+	at: &BlockId<Api::Block>,
+) -> io::Result<usize> {
+	let extrinsics = load::<Api>(path)?;
+
+	let mut imported = 0;
+	let sources = [
+		TransactionSource::Local,
+		TransactionSource::InBlock,
+		TransactionSource::External,
+	];
+	for source in &sources {
+		let extrinsics = extrinsics.iter()
+			.filter(|(s, _)| s == source)
+			.map(|(_, xt)| xt.clone())
+			.collect::<Vec<_>>();
+		if extrinsics.is_empty() {
+			continue;
+		}
+
+		imported += pool.submit_at(at, *source, extrinsics).await
+			.map(|results| results.into_iter().filter(Result::is_ok).count())
+			.unwrap_or(0);
+	}
+
+	Ok(imported)
+}
+
+/// Persists the pool when it shuts down.
+#[derive(Clone)]
+pub struct Handle {
+	path: PathBuf,
+	/// Set by [`run`] once the persisted transactions were restored.
+	restored: Arc<AtomicBool>,
+}
+
+impl Handle {
+	/// Create a handle for the transactions persisted at `path`.
+	pub fn new(path: PathBuf) -> Self {
+		Self { path, restored: Default::default() }
+	}
+
+	/// Mark the persisted transactions as restored.
+	pub fn set_restored(&self) {
+		self.restored.store(true, Ordering::Release);
+	}
+
+	/// Write the pending transactions of `pool` unless they are still being restored.
+	///
+	/// Writing before the restore completed would replace the persisted transactions with the
+	/// ones that were restored so far.
+	pub fn persist_on_shutdown<Api: ChainApi>(&self, pool: &Pool<Api>) {
+		if self.restored.load(Ordering::Acquire) {
+			persist(&self.path, pool);
+		} else {
+			log::warn!(
+				target: "txpool",
+				"Not persisting transactions to {}, they are still being restored",
+				self.path.display(),
+			);
+		}
+	}
+}
+
+/// Restore the persisted transactions and then persist the pool periodically.
+///
+/// `handle` is notified once the transactions were restored. Runs until the node shuts down.
+pub async fn run<Api: ChainApi>(
+	pool: Arc<Pool<Api>>,
+	options: PersistenceOptions,
+	at: BlockId<Api::Block>,
+	handle: Handle,
+) {
+	match restore(&options.path, &pool, &at).await {
+		Ok(imported) => log::info!(
+			target: "txpool",
+			"Restored {} persisted transactions from {}",
+			imported,
+			options.path.display(),
+		),
+		Err(e) => log::warn!(
+			target: "txpool",
+			"Failed to restore persisted transactions from {}: {:?}",
+			options.path.display(),
+			e,
+		),
+	}
+
+	handle.set_restored();
+
+	let mut interval = intervalier::Interval::new(options.interval).into_stream();
+	while interval.next().await.is_some() {
+		persist(&options.path, &pool);
+	}
+}
+
+/// Write the pending transactions of `pool` to `path` and log the outcome.
+pub fn persist<Api: ChainApi>(path: &Path, pool: &Pool<Api>) {
+	match save(path, pool) {
+		Ok(count) => log::debug!(
+			target: "txpool",
+			"Persisted {} transactions to {}",
+			count,
+			path.display(),
+		),
+		Err(e) => log::warn!(
+			target: "txpool",
+			"Failed to persist transactions to {}: {:?}",
+			path.display(),
+			e,
+		),
+	}
+}
@@ -1038,3 +1038,111 @@ fn only_revalidate_on_best_block() {
 
 	assert_eq!(pool.status().ready, 1);
 }
+
+#[test]
+fn should_restore_persisted_transactions() {
+	let tmp = tempfile::tempdir().unwrap();
+	let path = tmp.path().join("txpool");
+
+	let pool = pool();
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 209))).unwrap();
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 211))).unwrap();
+	assert_eq!(crate::persistence::save(&path, &pool).unwrap(), 2);
+
+	let restored = self::pool();
+	let imported = block_on(crate::persistence::restore(&path, &restored, &BlockId::number(0)));
+	assert_eq!(imported.unwrap(), 2);
+
+	let ready: Vec<_> = restored.validated_pool().ready()
+		.map(|a| a.data.transfer().nonce)
+		.collect();
+	assert_eq!(ready, vec![209]);
+	let future: Vec<_> = restored.validated_pool().futures().into_iter()
+		.map(|(_, xt)| xt.transfer().nonce)
+		.collect();
+	assert_eq!(future, vec![211]);
+}
+
+#[test]
+fn should_restore_persisted_transactions_from_their_source() {
+	let tmp = tempfile::tempdir().unwrap();
+	let path = tmp.path().join("txpool");
+
+	let pool = pool();
+	let local = TransactionSource::Local;
+	block_on(pool.submit_one(&BlockId::number(0), local, uxt(Alice, 209))).unwrap();
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 210))).unwrap();
+	crate::persistence::save(&path, &pool).unwrap();
+
+	let restored = self::pool();
+	let imported = block_on(crate::persistence::restore(&path, &restored, &BlockId::number(0)));
+	assert_eq!(imported.unwrap(), 2);
+
+	let ready: Vec<_> = restored.validated_pool().ready()
+		.map(|a| (a.data.transfer().nonce, a.source))
+		.collect();
+	assert_eq!(ready, vec![(209, local), (210, SOURCE)]);
+}
+
+#[test]
+fn should_not_restore_persisted_transactions_that_became_invalid() {
+	let tmp = tempfile::tempdir().unwrap();
+	let path = tmp.path().join("txpool");
+
+	let pool = pool();
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 209))).unwrap();
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 210))).unwrap();
+	crate::persistence::save(&path, &pool).unwrap();
+
+	let restored = self::pool();
+	restored.validated_pool().api().increment_nonce(Alice.into());
+	let imported = block_on(crate::persistence::restore(&path, &restored, &BlockId::number(0)));
+	assert_eq!(imported.unwrap(), 1);
+
+	let ready: Vec<_> = restored.validated_pool().ready()
+		.map(|a| a.data.transfer().nonce)
+		.collect();
+	assert_eq!(ready, vec![210]);
+}
+
+#[test]
+fn should_restore_nothing_if_nothing_was_persisted() {
+	let tmp = tempfile::tempdir().unwrap();
+
+	let pool = pool();
+	let imported = block_on(
+		crate::persistence::restore(&tmp.path().join("txpool"), &pool, &BlockId::number(0)),
+	);
+
+	assert_eq!(imported.unwrap(), 0);
+	assert_eq!(pool.validated_pool().status().ready, 0);
+}
+
+#[test]
+fn should_not_persist_on_drop_before_restore_completed() {
+	let tmp = tempfile::tempdir().unwrap();
+	let path = tmp.path().join("txpool");
+
+	let pool = pool();
+	block_on(pool.submit_one(&BlockId::number(0), SOURCE, uxt(Alice, 209))).unwrap();
+	crate::persistence::save(&path, &pool).unwrap();
+	let persisted = std::fs::read(&path).unwrap();
+
+	// the persisted transactions are not restored yet
+	let (mut pool, _background, _notifier) = BasicPool::new_test(
+		Arc::new(TestApi::with_alice_nonce(209)),
+	);
+	let handle = crate::persistence::Handle::new(path.clone());
+	pool.persistence = Some(handle.clone());
+	drop(pool);
+	assert_eq!(std::fs::read(&path).unwrap(), persisted);
+
+	// once they are, the pool is persisted
+	let (mut pool, _background, _notifier) = BasicPool::new_test(
+		Arc::new(TestApi::with_alice_nonce(209)),
+	);
+	handle.set_restored();
+	pool.persistence = Some(handle);
+	drop(pool);
+	assert!(crate::persistence::load::<TestApi>(&path).unwrap().is_empty());
+}
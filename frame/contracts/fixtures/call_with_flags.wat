@@ -0,0 +1,50 @@
+;; Calls the supplied dest using the new version of `seal_call` with the supplied flags.
+;; The input is structured as: flags (4 bytes) + 32 byte dest + input of the callee.
+;; The return code of the call is copied to the output buffer. Returns right away
+;; when called with an empty input.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i32 i64 i32 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 8) zero balance
+	(data (i32.const 0) "\00\00\00\00\00\00\00\00")
+
+	;; [8, 12) here we store the return code of the call
+
+	;; [12, 16) size of the input data
+	(data (i32.const 12) "\00\01")
+
+	;; [16, 272) here we store the input data
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 16) (i32.const 12))
+
+		;; Nothing to do when there is no dest.
+		(if (i32.lt_u (i32.load (i32.const 12)) (i32.const 36))
+			(then (return))
+		)
+
+		(i32.store
+			(i32.const 8)
+			(call $seal_call
+				(i32.load (i32.const 16)) ;; Flags
+				(i32.const 20) ;; Pointer to "callee" address.
+				(i32.const 32) ;; Length of "callee" address.
+				(i64.const 0) ;; How much gas to devote for the execution. 0 = all.
+				(i32.const 0) ;; Pointer to the buffer with value to transfer
+				(i32.const 8) ;; Length of the buffer with value to transfer.
+				(i32.const 52) ;; Pointer to input data buffer address
+				(i32.sub (i32.load (i32.const 12)) (i32.const 36)) ;; Length of input data buffer
+				(i32.const 0xffffffff) ;; u32 max sentinel value: do not copy output
+				(i32.const 0) ;; Ptr to output buffer len
+			)
+		)
+
+		;; Not reached when the call was a successful tail call.
+		(call $seal_return (i32.const 0) (i32.const 8) (i32.const 4))
+	)
+)
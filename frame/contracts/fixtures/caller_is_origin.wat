@@ -0,0 +1,15 @@
+;; Returns the result of `seal_caller_is_origin` (4 bytes).
+(module
+	(import "seal0" "seal_caller_is_origin" (func $seal_caller_is_origin (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) result of `seal_caller_is_origin`
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(i32.store (i32.const 0) (call $seal_caller_is_origin))
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 4))
+	)
+)
@@ -0,0 +1,31 @@
+;; Emits the input as a debug message.
+;; Returns the return code of `seal_debug_message` (4 bytes).
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_debug_message" (func $seal_debug_message (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) return code of `seal_debug_message`
+
+	;; [4, 8) length of the input buffer
+	(data (i32.const 4) "\00\01")
+
+	;; [8, 264) buffer where the input is copied
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 8) (i32.const 4))
+
+		(i32.store
+			(i32.const 0)
+			(call $seal_debug_message
+				(i32.const 8)	;; Pointer to the message.
+				(i32.load (i32.const 4))	;; Length of the message.
+			)
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 4))
+	)
+)
@@ -0,0 +1,49 @@
+;; Executes the code identified by the code hash passed as input through `seal_delegate_call`.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_delegate_call" (func $seal_delegate_call (param i32 i32 i32 i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) length of the input buffer
+	(data (i32.const 0) "\20")
+
+	;; [4, 36) buffer where the code hash is copied
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		;; Copy the code hash into this contracts memory.
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		;; Assert that the input is exactly one code hash.
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 0))
+				(i32.const 32)
+			)
+		)
+
+		;; Execute the code and assert that it succeeded.
+		(call $assert
+			(i32.eqz
+				(call $seal_delegate_call
+					(i32.const 4)	;; Pointer to the code hash.
+					(i32.const 32)	;; Length of the code hash.
+					(i32.const 0)	;; Pointer to input data buffer.
+					(i32.const 0)	;; Length of input data buffer.
+					(i32.const 4294967295)	;; u32 max sentinel value: do not copy output
+					(i32.const 0)	;; Length is ignored in this case.
+				)
+			)
+		)
+	)
+)
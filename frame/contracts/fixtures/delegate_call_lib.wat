@@ -0,0 +1,29 @@
+;; Library code that is meant to be executed through `seal_delegate_call`.
+;; It stores the value transferred to the calling contract in its storage.
+(module
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_value_transferred" (func $seal_value_transferred (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) storage key
+	(data (i32.const 0)
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+	)
+
+	;; [32, 40) buffer where the transferred value is copied
+
+	;; [40, 44) length of the value buffer
+	(data (i32.const 40) "\08")
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_value_transferred (i32.const 32) (i32.const 40))
+		(call $seal_set_storage
+			(i32.const 0)	;; Pointer to storage key
+			(i32.const 32)	;; Pointer to value
+			(i32.load (i32.const 40))	;; Size of value
+		)
+	)
+)
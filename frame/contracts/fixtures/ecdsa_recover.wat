@@ -0,0 +1,52 @@
+;; Recovers the ECDSA public key from the signature and message hash passed as input.
+;; The input is the signature (65 bytes) followed by the message hash (32 bytes).
+;; Returns the return code of `seal_ecdsa_recover` (4 bytes) followed by the public key.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_ecdsa_recover" (func $seal_ecdsa_recover (param i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) return code of `seal_ecdsa_recover`
+
+	;; [4, 37) buffer for the recovered public key
+
+	;; [40, 44) length of the input buffer
+	(data (i32.const 40) "\61")
+
+	;; [44, 141) buffer where input is copied
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 44) (i32.const 40))
+
+		;; Assert that the input is a signature followed by a message hash.
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 40))
+				(i32.const 97)
+			)
+		)
+
+		(i32.store
+			(i32.const 0)
+			(call $seal_ecdsa_recover
+				(i32.const 44)	;; Pointer to the signature.
+				(i32.const 109)	;; Pointer to the message hash.
+				(i32.const 4)	;; Pointer to the output buffer.
+			)
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 37))
+	)
+)
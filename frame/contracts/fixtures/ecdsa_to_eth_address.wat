@@ -0,0 +1,50 @@
+;; Calculates the Ethereum address of the compressed ECDSA public key passed as input.
+;; Returns the return code of `seal_ecdsa_to_eth_address` (4 bytes) followed by the address.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_ecdsa_to_eth_address" (func $seal_ecdsa_to_eth_address (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) return code of `seal_ecdsa_to_eth_address`
+
+	;; [4, 24) buffer for the Ethereum address
+
+	;; [24, 28) length of the input buffer
+	(data (i32.const 24) "\21")
+
+	;; [28, 61) buffer where the public key is copied
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 28) (i32.const 24))
+
+		;; Assert that the input is a compressed public key.
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 24))
+				(i32.const 33)
+			)
+		)
+
+		(i32.store
+			(i32.const 0)
+			(call $seal_ecdsa_to_eth_address
+				(i32.const 28)	;; Pointer to the public key.
+				(i32.const 4)	;; Pointer to the output buffer.
+			)
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 24))
+	)
+)
@@ -0,0 +1,54 @@
+;; Verifies a ed25519 signature.
+;; The input is the signature (64 bytes) followed by the public key (32 bytes) and the message.
+;; Returns the return code of `seal_ed25519_verify` (4 bytes).
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_ed25519_verify" (func $seal_ed25519_verify (param i32 i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) return code of `seal_ed25519_verify`
+
+	;; [4, 8) length of the input buffer
+	(data (i32.const 4) "\00\01")
+
+	;; [8, 264) buffer where input is copied
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 8) (i32.const 4))
+
+		;; Assert that the input contains at least a signature and a public key.
+		(call $assert
+			(i32.ge_u
+				(i32.load (i32.const 4))
+				(i32.const 96)
+			)
+		)
+
+		(i32.store
+			(i32.const 0)
+			(call $seal_ed25519_verify
+				(i32.const 8)	;; Pointer to the signature.
+				(i32.const 72)	;; Pointer to the public key.
+				(i32.sub	;; Length of the message.
+					(i32.load (i32.const 4))
+					(i32.const 96)
+				)
+				(i32.const 104)	;; Pointer to the message.
+			)
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 4))
+	)
+)
@@ -0,0 +1,29 @@
+;; Checks whether the 32 byte address supplied as input belongs to a contract.
+;; Returns the result of `seal_is_contract` (4 bytes).
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_is_contract" (func $seal_is_contract (param i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) result of `seal_is_contract`
+
+	;; [4, 8) size of the input data
+	(data (i32.const 4) "\20")
+
+	;; [8, 40) address to check
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 8) (i32.const 4))
+		(i32.store
+			(i32.const 0)
+			(call $seal_is_contract
+				(i32.const 8) ;; Pointer to the address.
+				(i32.const 32) ;; Length of the address.
+			)
+		)
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 4))
+	)
+)
@@ -0,0 +1,43 @@
+;; Replaces its own code with the code hash passed as input and returns 1 as output.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_set_code_hash" (func $seal_set_code_hash (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) value to return
+	(data (i32.const 0) "\01")
+
+	;; [4, 8) length of the input buffer
+	(data (i32.const 4) "\20")
+
+	;; [8, 40) buffer where the code hash is copied
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 8) (i32.const 4))
+
+		;; Replace the code and assert that it succeeded.
+		(call $assert
+			(i32.eqz
+				(call $seal_set_code_hash
+					(i32.const 8)	;; Pointer to the new code hash.
+					(i32.const 32)	;; Length of the new code hash.
+				)
+			)
+		)
+
+		;; The old code keeps running until it returns.
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 4))
+	)
+)
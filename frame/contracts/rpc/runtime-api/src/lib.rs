@@ -26,7 +26,7 @@
 use codec::Codec;
 use sp_std::vec::Vec;
 use pallet_contracts_primitives::{
-	ContractExecResult, GetStorageResult, RentProjectionResult, Code, ContractInstantiateResult,
+	ContractExecResult, GetStorageResult, Code, ContractInstantiateResult, CodeUploadResult,
 };
 
 sp_api::decl_runtime_apis! {
@@ -45,8 +45,9 @@ sp_api::decl_runtime_apis! {
 			dest: AccountId,
 			value: Balance,
 			gas_limit: u64,
+			storage_deposit_limit: Option<Balance>,
 			input_data: Vec<u8>,
-		) -> ContractExecResult;
+		) -> ContractExecResult<Balance>;
 
 		/// Instantiate a new contract.
 		///
@@ -55,28 +56,29 @@ sp_api::decl_runtime_apis! {
 			origin: AccountId,
 			endowment: Balance,
 			gas_limit: u64,
+			storage_deposit_limit: Option<Balance>,
 			code: Code<Hash>,
 			data: Vec<u8>,
 			salt: Vec<u8>,
-		) -> ContractInstantiateResult<AccountId, BlockNumber>;
+		) -> ContractInstantiateResult<AccountId, Balance>;
+
+		/// Upload new code without instantiating a contract from it.
+		///
+		/// See [`pallet_contracts::Pallet::upload_code`].
+		fn upload_code(
+			origin: AccountId,
+			code: Vec<u8>,
+			storage_deposit_limit: Option<Balance>,
+		) -> CodeUploadResult<Hash, Balance>;
 
 		/// Query a given storage key in a given contract.
 		///
 		/// Returns `Ok(Some(Vec<u8>))` if the storage value exists under the given key in the
 		/// specified account and `Ok(None)` if it doesn't. If the account specified by the address
-		/// doesn't exist, or doesn't have a contract or if the contract is a tombstone, then `Err`
-		/// is returned.
+		/// doesn't exist, or doesn't have a contract then `Err` is returned.
 		fn get_storage(
 			address: AccountId,
 			key: [u8; 32],
 		) -> GetStorageResult;
-
-		/// Returns the projected time a given contract will be able to sustain paying its rent.
-		///
-		/// The returned projection is relevant for the current block, i.e. it is as if the contract
-		/// was accessed at the current block.
-		///
-		/// Returns `Err` if the contract is in a tombstone state or doesn't exist.
-		fn rent_projection(address: AccountId) -> RentProjectionResult<BlockNumber>;
 	}
 }
@@ -25,7 +25,6 @@ mod sandbox;
 use crate::{
 	*, Pallet as Contracts,
 	exec::StorageKey,
-	rent::Rent,
 	schedule::{API_BENCHMARK_BATCH_SIZE, INSTR_BENCHMARK_BATCH_SIZE},
 	storage::Storage,
 };
@@ -40,9 +39,8 @@ use codec::Encode;
 use frame_benchmarking::{benchmarks, account, whitelisted_caller, impl_benchmark_test_suite};
 use frame_system::{Pallet as System, RawOrigin};
 use parity_wasm::elements::{Instruction, ValueType, BlockType};
-use sp_runtime::traits::{Hash, Bounded, Zero};
+use sp_runtime::traits::{Hash, Bounded};
 use sp_std::{default::Default, convert::{TryInto}, vec::Vec, vec};
-use pallet_contracts_primitives::RentProjection;
 use frame_support::weights::Weight;
 
 /// How many batches we do per API benchmark.
@@ -57,7 +55,6 @@ struct Contract<T: Config> {
 	account_id: T::AccountId,
 	addr: <T::Lookup as StaticLookup>::Source,
 	endowment: BalanceOf<T>,
-	code_hash: <T::Hashing as Hash>::Output,
 }
 
 /// Describes how much balance should be transferred on instantiate from the caller.
@@ -65,17 +62,14 @@ enum Endow {
 	/// Endow the contract with a maximum amount of balance. This value is described by
 	/// `Contract::max_endowment`.
 	Max,
-	/// Endow so that the amount of balance that is transferred is big but not so big
-	/// to offset the rent payment. This is needed in order to test rent collection.
-	CollectRent,
 }
 
 impl Endow {
-	/// The maximum amount of balance a caller can transfer without being brought below
-	/// the existential deposit. This assumes that every caller is funded with the amount
-	/// returned by `caller_funding`.
+	/// The maximum amount of balance a caller can transfer while still being able to pay
+	/// for the storage deposits of the benchmarked calls. This assumes that every caller is
+	/// funded with the amount returned by `caller_funding`.
 	fn max<T:Config>() -> BalanceOf<T> {
-		caller_funding::<T>().saturating_sub(T::Currency::minimum_balance())
+		caller_funding::<T>().saturating_sub(T::Currency::minimum_balance()) / 2u32.into()
 	}
 }
 
@@ -111,22 +105,8 @@ where
 		endowment: Endow,
 	) -> Result<Contract<T>, &'static str>
 	{
-		let (storage_size, endowment) = match endowment {
-			Endow::CollectRent => {
-				// storage_size cannot be zero because otherwise a contract that is just above
-				// the subsistence threshold does not pay rent given a large enough subsistence
-				// threshold. But we need rent payments to occur in order to benchmark for worst cases.
-				let storage_size = u32::max_value() / 10;
-
-				// Endowment should be large but not as large to inhibit rent payments.
-				// Balance will only cover half the storage
-				let endowment = T::DepositPerStorageByte::get()
-					.saturating_mul(<BalanceOf<T>>::from(storage_size) / 2u32.into())
-					.saturating_add(T::DepositPerContract::get());
-
-				(storage_size, endowment)
-			},
-			Endow::Max => (0u32.into(), Endow::max::<T>()),
+		let endowment = match endowment {
+			Endow::Max => Endow::max::<T>(),
 		};
 		T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
 		let salt = vec![0xff];
@@ -134,7 +114,7 @@ where
 
 		// The default block number is zero. The benchmarking system bumps the block number
 		// to one for the benchmarking closure when it is set to zero. In order to prevent this
-		// undesired implicit bump (which messes with rent collection), we do the bump ourselves
+		// undesired implicit bump, we do the bump ourselves
 		// in the setup closure so that both the instantiate and subsequent call are run with the
 		// same block number.
 		System::<T>::set_block_number(1u32.into());
@@ -144,6 +124,7 @@ where
 			RawOrigin::Signed(caller.clone()).into(),
 			endowment,
 			Weight::max_value(),
+			None,
 			module.hash,
 			data,
 			salt,
@@ -154,70 +135,47 @@ where
 			account_id: addr.clone(),
 			addr: T::Lookup::unlookup(addr),
 			endowment,
-			code_hash: module.hash.clone(),
 		};
 
-		let mut contract = result.alive_info()?;
-		contract.storage_size = storage_size;
-		ContractInfoOf::<T>::insert(&result.account_id, ContractInfo::Alive(contract));
+		result.info()?;
 
 		Ok(result)
 	}
 
 	/// Store the supplied storage items into this contracts storage.
 	fn store(&self, items: &Vec<(StorageKey, Vec<u8>)>) -> Result<(), &'static str> {
-		let info = self.alive_info()?;
+		let info = self.info()?;
 		for item in items {
 			Storage::<T>::write(
 				&self.account_id,
 				&info.trie_id,
 				&item.0,
 				Some(item.1.clone()),
+				None,
 			)
-			.map_err(|_| "Failed to write storage to restoration dest")?;
+			.map_err(|_| "Failed to write storage to contract")?;
 		}
 		Ok(())
 	}
 
-	/// Get the `AliveContractInfo` of the `addr` or an error if it is no longer alive.
-	fn address_alive_info(addr: &T::AccountId) -> Result<AliveContractInfo<T>, &'static str> {
-		ContractInfoOf::<T>::get(addr).and_then(|c| c.get_alive())
-			.ok_or("Expected contract to be alive at this point.")
+	/// Get the `ContractInfo` of the `addr` or an error if it no longer exists.
+	fn address_info(addr: &T::AccountId) -> Result<ContractInfo<T>, &'static str> {
+		ContractInfoOf::<T>::get(addr).ok_or("Expected contract to exist at this point.")
 	}
 
-	/// Get the `AliveContractInfo` of this contract or an error if it is no longer alive.
-	fn alive_info(&self) -> Result<AliveContractInfo<T>, &'static str> {
-		Self::address_alive_info(&self.account_id)
-	}
-
-	/// Return an error if this contract is no tombstone.
-	fn ensure_tombstone(&self) -> Result<(), &'static str> {
-		ContractInfoOf::<T>::get(&self.account_id).and_then(|c| c.get_tombstone())
-			.ok_or("Expected contract to be a tombstone at this point.")
-			.map(|_| ())
-	}
-
-	/// Get the block number when this contract will be evicted. Returns an error when
-	/// the rent collection won't happen because the contract has to much endowment.
-	fn eviction_at(&self) -> Result<T::BlockNumber, &'static str> {
-		let projection = Rent::<T, PrefabWasmModule<T>>::compute_projection(&self.account_id)
-			.map_err(|_| "Invalid acc for rent")?;
-		match projection {
-			RentProjection::EvictionAt(at) => Ok(at),
-			_ => Err("Account does not pay rent.")?,
-		}
+	/// Get the `ContractInfo` of this contract or an error if it no longer exists.
+	fn info(&self) -> Result<ContractInfo<T>, &'static str> {
+		Self::address_info(&self.account_id)
 	}
 }
 
 /// A `Contract` that contains some storage items.
 ///
-/// This is used to benchmark contract destruction and resurection. Those operations'
-/// weight depend on the amount of storage accumulated.
+/// This is used to benchmark contract destruction. This operation's weight depends on the
+/// amount of storage accumulated.
 struct ContractWithStorage<T: Config> {
-	/// The contract that was evicted.
+	/// The contract that holds the storage.
 	contract: Contract<T>,
-	/// The storage the contract held when it was avicted.
-	storage: Vec<(StorageKey, Vec<u8>)>,
 }
 
 impl<T: Config> ContractWithStorage<T>
@@ -230,31 +188,15 @@ where
 		Self::with_code(WasmModule::dummy(), stor_num, stor_size)
 	}
 
-	/// Create and evict a new contract with the supplied storage item count and size each.
+	/// Create a new contract with the supplied storage item count and size each.
 	fn with_code(code: WasmModule<T>, stor_num: u32, stor_size: u32) -> Result<Self, &'static str> {
-		let contract = Contract::<T>::new(code, vec![], Endow::CollectRent)?;
+		let contract = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let storage_items = create_storage::<T>(stor_num, stor_size)?;
 		contract.store(&storage_items)?;
 		Ok(Self {
 			contract,
-			storage: storage_items,
 		})
 	}
-
-	/// Increase the system block number so that this contract is eligible for eviction.
-	fn set_block_num_for_eviction(&self) -> Result<(), &'static str>  {
-		System::<T>::set_block_number(
-			self.contract.eviction_at()? + T::SignedClaimHandicap::get() + 5u32.into()
-		);
-		Ok(())
-	}
-
-	/// Evict this contract.
-	fn evict(&mut self) -> Result<(), &'static str> {
-		self.set_block_num_for_eviction()?;
-		Rent::<T, PrefabWasmModule<T>>::try_eviction(&self.contract.account_id, Zero::zero())?;
-		self.contract.ensure_tombstone()
-	}
 }
 
 /// Generate `stor_num` storage items. Each has the size `stor_size`.
@@ -290,7 +232,7 @@ benchmarks! {
 	on_initialize_per_trie_key {
 		let k in 0..1024;
 		let instance = ContractWithStorage::<T>::new(k, T::MaxValueSize::get())?;
-		Storage::<T>::queue_trie_for_deletion(&instance.contract.alive_info()?)?;
+		Storage::<T>::queue_trie_for_deletion(&instance.contract.info()?)?;
 	}: {
 		Storage::<T>::process_deletion_queue_batch(Weight::max_value())
 	}
@@ -299,7 +241,7 @@ benchmarks! {
 		let q in 0..1024.min(T::DeletionQueueDepth::get());
 		for i in 0 .. q {
 			let instance = Contract::<T>::with_index(i, WasmModule::dummy(), vec![], Endow::Max)?;
-			Storage::<T>::queue_trie_for_deletion(&instance.alive_info()?)?;
+			Storage::<T>::queue_trie_for_deletion(&instance.info()?)?;
 			ContractInfoOf::<T>::remove(instance.account_id);
 		}
 	}: {
@@ -348,14 +290,18 @@ benchmarks! {
 		let WasmModule { code, hash, .. } = WasmModule::<T>::sized(c * 1024);
 		let origin = RawOrigin::Signed(caller.clone());
 		let addr = Contracts::<T>::contract_address(&caller, &hash, &salt);
-	}: _(origin, endowment, Weight::max_value(), code, vec![], salt)
+	}: _(origin, endowment, Weight::max_value(), None, code, vec![], salt)
 	verify {
-		// endowment was removed from the caller
-		assert_eq!(T::Currency::free_balance(&caller), caller_funding::<T>() - endowment);
-		// contract has the full endowment because no rent collection happended
+		// endowment and the contract deposit were removed from the caller
+		assert_eq!(
+			T::Currency::free_balance(&caller),
+			caller_funding::<T>() - endowment - T::DepositPerContract::get(),
+		);
+		// contract has the full endowment while its deposit is reserved
 		assert_eq!(T::Currency::free_balance(&addr), endowment);
-		// instantiate should leave a alive contract
-		Contract::<T>::address_alive_info(&addr)?;
+		assert_eq!(T::Currency::reserved_balance(&addr), T::DepositPerContract::get());
+		// instantiate should leave a contract
+		Contract::<T>::address_info(&addr)?;
 	}
 
 	// Instantiate uses a dummy contract constructor to measure the overhead of the instantiate.
@@ -372,14 +318,18 @@ benchmarks! {
 		let origin = RawOrigin::Signed(caller.clone());
 		let addr = Contracts::<T>::contract_address(&caller, &hash, &salt);
 		Contracts::<T>::store_code_raw(code)?;
-	}: _(origin, endowment, Weight::max_value(), hash, vec![], salt)
+	}: _(origin, endowment, Weight::max_value(), None, hash, vec![], salt)
 	verify {
-		// endowment was removed from the caller
-		assert_eq!(T::Currency::free_balance(&caller), caller_funding::<T>() - endowment);
-		// contract has the full endowment because no rent collection happended
+		// endowment and the contract deposit were removed from the caller
+		assert_eq!(
+			T::Currency::free_balance(&caller),
+			caller_funding::<T>() - endowment - T::DepositPerContract::get(),
+		);
+		// contract has the full endowment while its deposit is reserved
 		assert_eq!(T::Currency::free_balance(&addr), endowment);
-		// instantiate should leave a alive contract
-		Contract::<T>::address_alive_info(&addr)?;
+		assert_eq!(T::Currency::reserved_balance(&addr), T::DepositPerContract::get());
+		// instantiate should leave a contract
+		Contract::<T>::address_info(&addr)?;
 	}
 
 	// We just call a dummy contract to measure to overhead of the call extrinsic.
@@ -392,66 +342,57 @@ benchmarks! {
 		let c in 0 .. T::MaxCodeSize::get() / 1024;
 		let data = vec![42u8; 1024];
 		let instance = Contract::<T>::with_caller(
-			whitelisted_caller(), WasmModule::dummy_with_bytes(c * 1024), vec![], Endow::CollectRent
+			whitelisted_caller(), WasmModule::dummy_with_bytes(c * 1024), vec![], Endow::Max
 		)?;
 		let value = T::Currency::minimum_balance() * 100u32.into();
 		let origin = RawOrigin::Signed(instance.caller.clone());
 		let callee = instance.addr.clone();
-
-		// trigger rent collection for worst case performance of call
-		System::<T>::set_block_number(instance.eviction_at()? - 5u32.into());
 		let before = T::Currency::free_balance(&instance.account_id);
-	}: _(origin, callee, value, Weight::max_value(), data)
+	}: _(origin, callee, value, Weight::max_value(), None, data)
 	verify {
-		// endowment and value transfered via call should be removed from the caller
+		// endowment, contract deposit and value transfered via call should be removed
+		// from the caller
 		assert_eq!(
 			T::Currency::free_balance(&instance.caller),
-			caller_funding::<T>() - instance.endowment - value,
+			caller_funding::<T>() - instance.endowment - T::DepositPerContract::get() - value,
 		);
-		// rent should have lowered the amount of balance of the contract
-		assert!(T::Currency::free_balance(&instance.account_id) < before + value);
-		// but it should not have been evicted by the rent collection
-		instance.alive_info()?;
-	}
-
-	// We benchmark the costs for sucessfully evicting an empty contract.
-	// The actual costs are depending on how many storage items the evicted contract
-	// does have. However, those costs are not to be payed by the sender but
-	// will be distributed over multiple blocks using a scheduler. Otherwise there is
-	// no incentive to remove large contracts when the removal is more expensive than
-	// the reward for removing them.
-	// `c`: Size of the code of the contract that should be evicted.
-	claim_surcharge {
-		let c in 0 .. T::MaxCodeSize::get() / 1024;
-		let instance = Contract::<T>::with_caller(
-			whitelisted_caller(), WasmModule::dummy_with_bytes(c * 1024), vec![], Endow::CollectRent
-		)?;
-		let origin = RawOrigin::Signed(instance.caller.clone());
-		let account_id = instance.account_id.clone();
+		// the value should have been transferred to the contract
+		assert_eq!(T::Currency::free_balance(&instance.account_id), before + value);
+		// contract should still exist
+		instance.info()?;
+	}
 
-		// instantiate should leave us with an alive contract
-		instance.alive_info()?;
+	// This constructs a contract that is maximal expensive to instrument.
+	// It creates a maximum number of metering blocks per byte.
+	// `c`: Size of the code in kilobytes.
+	upload_code {
+		let c in 0 .. Perbill::from_percent(50).mul_ceil(T::MaxCodeSize::get() / 1024);
+		let caller = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
+		let WasmModule { code, hash, .. } = WasmModule::<T>::sized(c * 1024);
+		let origin = RawOrigin::Signed(caller.clone());
+	}: _(origin, code, None)
+	verify {
+		// uploading the code reserves some balance in the callers account
+		assert!(T::Currency::reserved_balance(&caller) > 0u32.into());
+		assert!(<CodeStorage<T>>::contains_key(&hash));
+	}
 
-		// generate enough rent so that the contract is evicted
-		System::<T>::set_block_number(
-			instance.eviction_at()? + T::SignedClaimHandicap::get() + 5u32.into()
-		);
-	}: _(origin, account_id, None)
+	// Removing code does not depend on the size of the contract because all the information
+	// needed to verify the removal claim (refcount, owner) is stored in separate storage items.
+	remove_code {
+		let caller = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
+		let WasmModule { code, hash, .. } = WasmModule::<T>::dummy();
+		let origin = RawOrigin::Signed(caller.clone());
+		let uploaded = Contracts::<T>::bare_upload_code(caller.clone(), code, None)?;
+		assert_eq!(uploaded.code_hash, hash);
+		assert_eq!(uploaded.deposit, T::Currency::reserved_balance(&caller));
+	}: _(origin, hash)
 	verify {
-		// the claim surcharge should have evicted the contract
-		instance.ensure_tombstone()?;
-
-		// the caller should get the reward for being a good snitch
-		// this is capped by the maximum amount of rent payed. So we only now that it should
-		// have increased by at most the surcharge reward.
-		assert!(
-			T::Currency::free_balance(&instance.caller) >
-			caller_funding::<T>() - instance.endowment
-		);
-		assert!(
-			T::Currency::free_balance(&instance.caller) <=
-			caller_funding::<T>() - instance.endowment + <T as Config>::SurchargeReward::get(),
-		);
+		// removing the code should have unreserved the deposit
+		assert_eq!(T::Currency::reserved_balance(&caller), 0u32.into());
+		assert!(!<CodeStorage<T>>::contains_key(&hash));
 	}
 
 	seal_caller {
@@ -460,7 +401,60 @@ benchmarks! {
 			"seal_caller", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	seal_caller_is_origin {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_caller_is_origin",
+				params: vec![],
+				return_type: Some(ValueType::I32),
+			}],
+			call_body: Some(body::repeated(r * API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	seal_is_contract {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let accounts = (0 .. r * API_BENCHMARK_BATCH_SIZE)
+			.map(|n| Contract::with_index(n + 1, WasmModule::dummy(), vec![], Endow::Max))
+			.collect::<Result<Vec<_>, _>>()?;
+		let account_len = accounts.get(0).map(|i| i.account_id.encode().len()).unwrap_or(0);
+		let accounts_bytes = accounts.iter()
+			.flat_map(|a| a.account_id.encode())
+			.collect::<Vec<_>>();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_is_contract",
+				params: vec![ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: accounts_bytes,
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(0, account_len as u32), // account_ptr
+				Regular(Instruction::I32Const(account_len as i32)), // account_len
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_address {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -468,7 +462,57 @@ benchmarks! {
 			"seal_address", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	seal_code_hash {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let accounts = (0 .. r * API_BENCHMARK_BATCH_SIZE)
+			.map(|n| Contract::with_index(n + 1, WasmModule::dummy(), vec![], Endow::Max))
+			.collect::<Result<Vec<_>, _>>()?;
+		let account_len = accounts.get(0).map(|i| i.account_id.encode().len()).unwrap_or(0);
+		let accounts_bytes = accounts.iter()
+			.flat_map(|a| a.account_id.encode())
+			.collect::<Vec<_>>();
+		let accounts_len = accounts_bytes.len();
+		let pages = code::max_pages::<T>();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_code_hash",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: (pages * 64 * 1024 - 4).to_le_bytes().to_vec(), // output length
+				},
+				DataSegment {
+					offset: 4,
+					value: accounts_bytes,
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(4, account_len as u32), // account_ptr
+				Regular(Instruction::I32Const(account_len as i32)), // account_len
+				Regular(Instruction::I32Const((accounts_len + 4) as i32)), // out_ptr
+				Regular(Instruction::I32Const(0)), // out_len_ptr
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	seal_own_code_hash {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let instance = Contract::<T>::new(WasmModule::getter(
+			"seal_own_code_hash", r * API_BENCHMARK_BATCH_SIZE
+		), vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_gas_left {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -476,7 +520,7 @@ benchmarks! {
 			"seal_gas_left", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_balance {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -484,7 +528,7 @@ benchmarks! {
 			"seal_balance", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_value_transferred {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -492,7 +536,7 @@ benchmarks! {
 			"seal_value_transferred", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_minimum_balance {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -500,7 +544,7 @@ benchmarks! {
 			"seal_minimum_balance", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_tombstone_deposit {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -508,7 +552,7 @@ benchmarks! {
 			"seal_tombstone_deposit", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_rent_allowance {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -516,7 +560,7 @@ benchmarks! {
 			"seal_rent_allowance", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_block_number {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -524,7 +568,7 @@ benchmarks! {
 			"seal_block_number", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_now {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -532,15 +576,7 @@ benchmarks! {
 			"seal_now", r * API_BENCHMARK_BATCH_SIZE
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
-
-	seal_rent_params {
-		let r in 0 .. API_BENCHMARK_BATCHES;
-		let instance = Contract::<T>::new(WasmModule::getter(
-			"seal_rent_params", r * API_BENCHMARK_BATCH_SIZE
-		), vec![], Endow::Max)?;
-		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_weight_to_fee {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -566,7 +602,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_gas {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -585,7 +621,7 @@ benchmarks! {
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
 
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We cannot call seal_input multiple times. Therefore our weight determination is not
 	// as precise as with other APIs. Because this function can only be called once per
@@ -614,7 +650,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_input_per_kb {
 		let n in 0 .. code::max_pages::<T>() * 64;
@@ -666,7 +702,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_return_per_kb {
 		let n in 0 .. code::max_pages::<T>() * 64;
@@ -688,7 +724,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// The same argument as for `seal_input` is true here.
 	seal_terminate {
@@ -719,8 +755,8 @@ benchmarks! {
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
 		assert_eq!(T::Currency::total_balance(&beneficiary), 0u32.into());
-		assert_eq!(T::Currency::total_balance(&instance.account_id), Endow::max::<T>());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+		assert_eq!(T::Currency::free_balance(&instance.account_id), Endow::max::<T>());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		if r > 0 {
 			assert_eq!(T::Currency::total_balance(&instance.account_id), 0u32.into());
@@ -757,180 +793,55 @@ benchmarks! {
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
 		assert_eq!(T::Currency::total_balance(&beneficiary), 0u32.into());
-		assert_eq!(T::Currency::total_balance(&instance.account_id), Endow::max::<T>());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+		assert_eq!(T::Currency::free_balance(&instance.account_id), Endow::max::<T>());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		assert_eq!(T::Currency::total_balance(&instance.account_id), 0u32.into());
 		assert_eq!(T::Currency::total_balance(&beneficiary), Endow::max::<T>());
 	}
 
-	seal_restore_to {
-		let r in 0 .. 1;
-
-		// Restore just moves the trie id from origin to destination and therefore
-		// does not depend on the size of the destination contract. However, to not
-		// trigger any edge case we won't use an empty contract as destination.
-		let mut tombstone = ContractWithStorage::<T>::new(10, T::MaxValueSize::get())?;
-		tombstone.evict()?;
-
-		let dest = tombstone.contract.account_id.encode();
-		let dest_len = dest.len();
-		let code_hash = tombstone.contract.code_hash.encode();
-		let code_hash_len = code_hash.len();
-		let rent_allowance = BalanceOf::<T>::max_value().encode();
-		let rent_allowance_len = rent_allowance.len();
-
-		let dest_offset = 0;
-		let code_hash_offset = dest_offset + dest_len;
-		let rent_allowance_offset = code_hash_offset + code_hash_len;
-
+	// Every call replaces the code with a different code hash.
+	seal_set_code_hash {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let hashes = (0..r * API_BENCHMARK_BATCH_SIZE)
+			.map(|i| {
+				let code = WasmModule::<T>::dummy_with_bytes(i);
+				Contracts::<T>::store_code_raw(code.code)?;
+				Ok(code.hash)
+			})
+			.collect::<Result<Vec<_>, &'static str>>()?;
+		let hash_len = hashes.get(0).map(|x| x.encode().len()).unwrap_or(0);
+		let hashes_bytes = hashes.iter().flat_map(|x| x.encode()).collect::<Vec<_>>();
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
-				name: "seal_restore_to",
-				params: vec![
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-				],
-				return_type: None,
+				name: "seal_set_code_hash",
+				params: vec![ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
 			}],
 			data_segments: vec![
 				DataSegment {
-					offset: dest_offset as u32,
-					value: dest,
-				},
-				DataSegment {
-					offset: code_hash_offset as u32,
-					value: code_hash,
-				},
-				DataSegment {
-					offset: rent_allowance_offset as u32,
-					value: rent_allowance,
+					offset: 0,
+					value: hashes_bytes,
 				},
 			],
-			call_body: Some(body::repeated(r, &[
-				Instruction::I32Const(dest_offset as i32),
-				Instruction::I32Const(dest_len as i32),
-				Instruction::I32Const(code_hash_offset as i32),
-				Instruction::I32Const(code_hash_len as i32),
-				Instruction::I32Const(rent_allowance_offset as i32),
-				Instruction::I32Const(rent_allowance_len as i32),
-				Instruction::I32Const(0), // delta_ptr
-				Instruction::I32Const(0), // delta_count
-				Instruction::Call(0),
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(0, hash_len as u32), // code_hash_ptr
+				Regular(Instruction::I32Const(hash_len as i32)), // code_hash_len
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
 			])),
 			.. Default::default()
 		});
-
-		let instance = Contract::<T>::with_caller(
-			account("origin", 0, 0), code, vec![], Endow::Max
-		)?;
-		instance.store(&tombstone.storage)?;
-		System::<T>::set_block_number(System::<T>::block_number() + 1u32.into());
-
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
-		if r > 0 {
-			tombstone.contract.alive_info()?;
+		if let Some(hash) = hashes.last() {
+			assert_eq!(instance.info()?.code_hash, *hash);
 		}
 	}
 
-	// `c`: Code size of caller contract
-	// `t`: Code size of tombstone contract
-	// `d`: Number of supplied delta keys
-	seal_restore_to_per_code_kb_delta {
-		let c in 0 .. T::MaxCodeSize::get() / 1024;
-		let t in 0 .. T::MaxCodeSize::get() / 1024;
-		let d in 0 .. API_BENCHMARK_BATCHES;
-		let mut tombstone = ContractWithStorage::<T>::with_code(
-			WasmModule::<T>::dummy_with_bytes(t * 1024), 0, 0
-		)?;
-		tombstone.evict()?;
-		let delta = create_storage::<T>(d * API_BENCHMARK_BATCH_SIZE, T::MaxValueSize::get())?;
-
-		let dest = tombstone.contract.account_id.encode();
-		let dest_len = dest.len();
-		let code_hash = tombstone.contract.code_hash.encode();
-		let code_hash_len = code_hash.len();
-		let rent_allowance = BalanceOf::<T>::max_value().encode();
-		let rent_allowance_len = rent_allowance.len();
-		let delta_keys = delta.iter().flat_map(|(key, _)| key).cloned().collect::<Vec<_>>();
-
-		let dest_offset = 0;
-		let code_hash_offset = dest_offset + dest_len;
-		let rent_allowance_offset = code_hash_offset + code_hash_len;
-		let delta_keys_offset = rent_allowance_offset + rent_allowance_len;
-
-		let code = WasmModule::<T>::from(ModuleDefinition {
-			memory: Some(ImportedMemory::max::<T>()),
-			imported_functions: vec![ImportedFunction {
-				name: "seal_restore_to",
-				params: vec![
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-					ValueType::I32,
-				],
-				return_type: None,
-			}],
-			data_segments: vec![
-				DataSegment {
-					offset: dest_offset as u32,
-					value: dest,
-				},
-				DataSegment {
-					offset: code_hash_offset as u32,
-					value: code_hash,
-				},
-				DataSegment {
-					offset: rent_allowance_offset as u32,
-					value: rent_allowance,
-				},
-				DataSegment {
-					offset: delta_keys_offset as u32,
-					value: delta_keys,
-				},
-			],
-			call_body: Some(body::plain(vec![
-				Instruction::I32Const(dest_offset as i32),
-				Instruction::I32Const(dest_len as i32),
-				Instruction::I32Const(code_hash_offset as i32),
-				Instruction::I32Const(code_hash_len as i32),
-				Instruction::I32Const(rent_allowance_offset as i32),
-				Instruction::I32Const(rent_allowance_len as i32),
-				Instruction::I32Const(delta_keys_offset as i32), // delta_ptr
-				Instruction::I32Const(delta.len() as i32), // delta_count
-				Instruction::Call(0),
-				Instruction::End,
-			])),
-			dummy_section: c * 1024,
-			.. Default::default()
-		});
-
-		let instance = Contract::<T>::with_caller(
-			account("origin", 0, 0), code, vec![], Endow::Max
-		)?;
-		instance.store(&tombstone.storage)?;
-		instance.store(&delta)?;
-		System::<T>::set_block_number(System::<T>::block_number() + 1u32.into());
-
-		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
-	verify {
-		tombstone.contract.alive_info()?;
-	}
-
 	// We benchmark only for the maximum subject length. We assume that this is some lowish
 	// number (< 1 KB). Therefore we are not overcharging too much in case a smaller subject is
 	// used.
@@ -963,7 +874,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Overhead of calling the function without any topic.
 	// We benchmark for the worst case (largest event).
@@ -987,7 +898,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Benchmark the overhead that topics generate.
 	// `t`: Number of topics
@@ -1024,7 +935,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_set_rent_allowance {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -1052,7 +963,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	// The contract is a bit more complex because I needs to use different keys in order
@@ -1087,7 +998,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_set_storage_per_kb {
 		let n in 0 .. T::MaxValueSize::get() / 1024;
@@ -1116,7 +1027,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Similar to seal_set_storage. However, we store all the keys that we are about to
 	// delete beforehand in order to prevent any optimizations that could occur when
@@ -1148,18 +1059,19 @@ benchmarks! {
 			.. Default::default()
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
-		let trie_id = instance.alive_info()?.trie_id;
+		let trie_id = instance.info()?.trie_id;
 		for key in keys {
 			Storage::<T>::write(
 				&instance.account_id,
 				&trie_id,
 				key.as_slice().try_into().map_err(|e| "Key has wrong length")?,
-				Some(vec![42; T::MaxValueSize::get() as usize])
+				Some(vec![42; T::MaxValueSize::get() as usize]),
+				None,
 			)
 			.map_err(|_| "Failed to write to storage during setup.")?;
 		}
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We make sure that all storage accesses are to unique keys.
 	seal_get_storage {
@@ -1193,18 +1105,19 @@ benchmarks! {
 			.. Default::default()
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
-		let trie_id = instance.alive_info()?.trie_id;
+		let trie_id = instance.info()?.trie_id;
 		for key in keys {
 			Storage::<T>::write(
 				&instance.account_id,
 				&trie_id,
 				key.as_slice().try_into().map_err(|e| "Key has wrong length")?,
-				Some(vec![])
+				Some(vec![]),
+				None,
 			)
 			.map_err(|_| "Failed to write to storage during setup.")?;
 		}
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_get_storage_per_kb {
 		let n in 0 .. T::MaxValueSize::get() / 1024;
@@ -1238,16 +1151,17 @@ benchmarks! {
 			.. Default::default()
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
-		let trie_id = instance.alive_info()?.trie_id;
+		let trie_id = instance.info()?.trie_id;
 		Storage::<T>::write(
 			&instance.account_id,
 			&trie_id,
 			key.as_slice().try_into().map_err(|e| "Key has wrong length")?,
-			Some(vec![42u8; (n * 1024) as usize])
+			Some(vec![42u8; (n * 1024) as usize]),
+			None,
 		)
 		.map_err(|_| "Failed to write to storage during setup.")?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We transfer to unique accounts.
 	seal_transfer {
@@ -1257,7 +1171,7 @@ benchmarks! {
 			.collect::<Vec<_>>();
 		let account_len = accounts.get(0).map(|i| i.encode().len()).unwrap_or(0);
 		let account_bytes = accounts.iter().flat_map(|x| x.encode()).collect();
-		let value = Contracts::<T>::subsistence_threshold();
+		let value = T::Currency::minimum_balance();
 		assert!(value > 0u32.into());
 		let value_bytes = value.encode();
 		let value_len = value_bytes.len();
@@ -1293,7 +1207,7 @@ benchmarks! {
 		for account in &accounts {
 			assert_eq!(T::Currency::total_balance(account), 0u32.into());
 		}
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		for account in &accounts {
 			assert_eq!(T::Currency::total_balance(account), value);
@@ -1356,7 +1270,55 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	// We delegate call into unique code hashes.
+	seal_delegate_call {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let hashes = (0..r * API_BENCHMARK_BATCH_SIZE)
+			.map(|i| {
+				let code = WasmModule::<T>::dummy_with_bytes(i);
+				Contracts::<T>::store_code_raw(code.code)?;
+				Ok(code.hash)
+			})
+			.collect::<Result<Vec<_>, &'static str>>()?;
+		let hash_len = hashes.get(0).map(|x| x.encode().len()).unwrap_or(0);
+		let hashes_bytes = hashes.iter().flat_map(|x| x.encode()).collect::<Vec<_>>();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_delegate_call",
+				params: vec![
+					ValueType::I32,
+					ValueType::I32,
+					ValueType::I32,
+					ValueType::I32,
+					ValueType::I32,
+					ValueType::I32,
+				],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: hashes_bytes,
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(0, hash_len as u32), // code_hash_ptr
+				Regular(Instruction::I32Const(hash_len as i32)), // code_hash_len
+				Regular(Instruction::I32Const(0)), // input_data_ptr
+				Regular(Instruction::I32Const(0)), // input_data_len
+				Regular(Instruction::I32Const(u32::max_value() as i32)), // output_ptr
+				Regular(Instruction::I32Const(0)), // output_len_ptr
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	seal_call_per_code_transfer_input_output_kb {
 		let c in 0 .. T::MaxCodeSize::get() / 1024;
@@ -1441,9 +1403,9 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
-	// We assume that every instantiate sends at least the subsistence amount.
+	// We assume that every instantiate sends at least the minimum balance.
 	seal_instantiate {
 		let r in 0 .. API_BENCHMARK_BATCHES;
 		let hashes = (0..r * API_BENCHMARK_BATCH_SIZE)
@@ -1547,10 +1509,10 @@ benchmarks! {
 				return Err("Expected that contract does not exist at this point.");
 			}
 		}
-	}: call(origin, callee, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, callee, 0u32.into(), Weight::max_value(), None, vec![])
 	verify {
 		for addr in &addresses {
-			ContractInfoOf::<T>::get(&addr).and_then(|c| c.get_alive())
+			ContractInfoOf::<T>::get(&addr)
 				.ok_or_else(|| "Contract should have been instantiated")?;
 		}
 	}
@@ -1672,7 +1634,7 @@ benchmarks! {
 		});
 		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	seal_hash_sha2_256 {
@@ -1681,7 +1643,7 @@ benchmarks! {
 			"seal_hash_sha2_256", r * API_BENCHMARK_BATCH_SIZE, 0,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// `n`: Input to hash in kilobytes
 	seal_hash_sha2_256_per_kb {
@@ -1690,7 +1652,7 @@ benchmarks! {
 			"seal_hash_sha2_256", API_BENCHMARK_BATCH_SIZE, n * 1024,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	seal_hash_keccak_256 {
@@ -1699,7 +1661,7 @@ benchmarks! {
 			"seal_hash_keccak_256", r * API_BENCHMARK_BATCH_SIZE, 0,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// `n`: Input to hash in kilobytes
 	seal_hash_keccak_256_per_kb {
@@ -1708,7 +1670,7 @@ benchmarks! {
 			"seal_hash_keccak_256", API_BENCHMARK_BATCH_SIZE, n * 1024,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	seal_hash_blake2_256 {
@@ -1717,7 +1679,7 @@ benchmarks! {
 			"seal_hash_blake2_256", r * API_BENCHMARK_BATCH_SIZE, 0,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// `n`: Input to hash in kilobytes
 	seal_hash_blake2_256_per_kb {
@@ -1726,7 +1688,7 @@ benchmarks! {
 			"seal_hash_blake2_256", API_BENCHMARK_BATCH_SIZE, n * 1024,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// Only the overhead of calling the function itself with minimal arguments.
 	seal_hash_blake2_128 {
@@ -1735,7 +1697,7 @@ benchmarks! {
 			"seal_hash_blake2_128", r * API_BENCHMARK_BATCH_SIZE, 0,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// `n`: Input to hash in kilobytes
 	seal_hash_blake2_128_per_kb {
@@ -1744,7 +1706,276 @@ benchmarks! {
 			"seal_hash_blake2_128", API_BENCHMARK_BATCH_SIZE, n * 1024,
 		), vec![], Endow::Max)?;
 		let origin = RawOrigin::Signed(instance.caller.clone());
-	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), vec![])
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	// Only calling the function itself with valid arguments.
+	// It generates different private keys and signatures for the message "Hello world".
+	seal_ecdsa_recover {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let message = "Hello world".as_bytes();
+		let message_hash = sp_io::hashing::blake2_256(message);
+		let key_type = sp_core::crypto::KeyTypeId(*b"code");
+		let signatures = (0..r * API_BENCHMARK_BATCH_SIZE)
+			.flat_map(|_| {
+				let pub_key = sp_io::crypto::ecdsa_generate(key_type, None);
+				let sig = sp_io::crypto::ecdsa_sign(key_type, &pub_key, message)
+					.expect("Generates signature");
+				sig.0.to_vec()
+			})
+			.collect::<Vec<_>>();
+		let signatures_len = signatures.len();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_ecdsa_recover",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: message_hash.to_vec(),
+				},
+				DataSegment {
+					offset: 32,
+					value: signatures,
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(32, 65), // signature_ptr
+				Regular(Instruction::I32Const(0)), // message_hash_ptr
+				Regular(Instruction::I32Const((32 + signatures_len) as i32)), // output_ptr
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	// Only calling the function itself for the public keys of generated private keys.
+	seal_ecdsa_to_eth_address {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let key_type = sp_core::crypto::KeyTypeId(*b"code");
+		let pub_keys = (0..r * API_BENCHMARK_BATCH_SIZE)
+			.flat_map(|_| sp_io::crypto::ecdsa_generate(key_type, None).0.to_vec())
+			.collect::<Vec<_>>();
+		let pub_keys_len = pub_keys.len();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_ecdsa_to_eth_address",
+				params: vec![ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: pub_keys,
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(0, 33), // key_ptr
+				Regular(Instruction::I32Const(pub_keys_len as i32)), // output_ptr
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	// Only calling the function itself with a message of minimal size.
+	seal_sr25519_verify {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let message = b"Hello world".to_vec();
+		let message_len = message.len();
+		let key_type = sp_core::crypto::KeyTypeId(*b"code");
+		let sigs_and_keys = (0..r * API_BENCHMARK_BATCH_SIZE)
+			.flat_map(|_| {
+				let pub_key = sp_io::crypto::sr25519_generate(key_type, None);
+				let sig = sp_io::crypto::sr25519_sign(key_type, &pub_key, &message)
+					.expect("Generates signature");
+				sig.0.iter().chain(pub_key.0.iter()).cloned().collect::<Vec<_>>()
+			})
+			.collect::<Vec<_>>();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_sr25519_verify",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: message,
+				},
+				DataSegment {
+					offset: message_len as u32,
+					value: sigs_and_keys,
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(message_len as u32, 96), // signature_ptr
+				Counter(message_len as u32 + 64, 96), // pub_key_ptr
+				Regular(Instruction::I32Const(message_len as i32)), // message_len
+				Regular(Instruction::I32Const(0)), // message_ptr
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	// `n`: Message to verify in kilobytes
+	seal_sr25519_verify_per_kb {
+		let n in 0 .. code::max_pages::<T>() * 64 - 1;
+		let message = vec![42u8; (n * 1024) as usize];
+		let key_type = sp_core::crypto::KeyTypeId(*b"code");
+		let pub_key = sp_io::crypto::sr25519_generate(key_type, None);
+		let sig = sp_io::crypto::sr25519_sign(key_type, &pub_key, &message)
+			.expect("Generates signature");
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_sr25519_verify",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: sig.0.iter().chain(pub_key.0.iter()).cloned().collect(),
+				},
+				DataSegment {
+					offset: 96,
+					value: message,
+				},
+			],
+			call_body: Some(body::repeated(API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::I32Const(0), // signature_ptr
+				Instruction::I32Const(64), // pub_key_ptr
+				Instruction::I32Const((n * 1024) as i32), // message_len
+				Instruction::I32Const(96), // message_ptr
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	// Only calling the function itself with a message of minimal size.
+	seal_ed25519_verify {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let message = b"Hello world".to_vec();
+		let message_len = message.len();
+		let key_type = sp_core::crypto::KeyTypeId(*b"code");
+		let sigs_and_keys = (0..r * API_BENCHMARK_BATCH_SIZE)
+			.flat_map(|_| {
+				let pub_key = sp_io::crypto::ed25519_generate(key_type, None);
+				let sig = sp_io::crypto::ed25519_sign(key_type, &pub_key, &message)
+					.expect("Generates signature");
+				sig.0.iter().chain(pub_key.0.iter()).cloned().collect::<Vec<_>>()
+			})
+			.collect::<Vec<_>>();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_ed25519_verify",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: message,
+				},
+				DataSegment {
+					offset: message_len as u32,
+					value: sigs_and_keys,
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(message_len as u32, 96), // signature_ptr
+				Counter(message_len as u32 + 64, 96), // pub_key_ptr
+				Regular(Instruction::I32Const(message_len as i32)), // message_len
+				Regular(Instruction::I32Const(0)), // message_ptr
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	// `n`: Message to verify in kilobytes
+	seal_ed25519_verify_per_kb {
+		let n in 0 .. code::max_pages::<T>() * 64 - 1;
+		let message = vec![42u8; (n * 1024) as usize];
+		let key_type = sp_core::crypto::KeyTypeId(*b"code");
+		let pub_key = sp_io::crypto::ed25519_generate(key_type, None);
+		let sig = sp_io::crypto::ed25519_sign(key_type, &pub_key, &message)
+			.expect("Generates signature");
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_ed25519_verify",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: sig.0.iter().chain(pub_key.0.iter()).cloned().collect(),
+				},
+				DataSegment {
+					offset: 96,
+					value: message,
+				},
+			],
+			call_body: Some(body::repeated(API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::I32Const(0), // signature_ptr
+				Instruction::I32Const(64), // pub_key_ptr
+				Instruction::I32Const((n * 1024) as i32), // message_len
+				Instruction::I32Const(96), // message_ptr
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
+
+	// The debug buffer is disabled for on-chain execution which is what we benchmark here.
+	seal_debug_message {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				name: "seal_debug_message",
+				params: vec![ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			call_body: Some(body::repeated(r * API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::I32Const(0), // str_ptr
+				Instruction::I32Const(0), // str_len
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![], Endow::Max)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::max_value(), None, vec![])
 
 	// We make the assumption that pushing a constant and dropping a value takes roughly
 	// the same amount of time. We follow that `t.load` and `drop` both have the weight
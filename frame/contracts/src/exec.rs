@@ -17,20 +17,20 @@
 
 use crate::{
 	CodeHash, Event, Config, Pallet as Contracts,
-	TrieId, BalanceOf, ContractInfo, gas::GasMeter, rent::Rent, storage::{self, Storage},
-	Error, ContractInfoOf, Schedule, AliveContractInfo,
+	TrieId, BalanceOf, gas::GasMeter, storage::{Storage, meter::{DepositOf, Meter}},
+	Error, ContractInfoOf, Schedule,
 };
-use sp_core::crypto::UncheckedFrom;
+use sp_core::{crypto::UncheckedFrom, ed25519, sr25519};
+use sp_io::{crypto::secp256k1_ecdsa_recover_compressed, hashing::keccak_256};
 use sp_std::{
 	prelude::*,
 	marker::PhantomData,
 };
-use sp_runtime::{Perbill, traits::{Bounded, Zero, Convert, Saturating}};
+use sp_runtime::traits::{Zero, Convert};
 use frame_support::{
 	dispatch::{DispatchResult, DispatchError},
 	traits::{ExistenceRequirement, Currency, Time, Randomness, Get},
 	weights::Weight,
-	ensure,
 };
 use pallet_contracts_primitives::{ExecReturnValue, ReturnFlags};
 
@@ -78,84 +78,6 @@ impl<T: Into<DispatchError>> From<T> for ExecError {
 	}
 }
 
-/// Information needed for rent calculations that can be requested by a contract.
-#[derive(codec::Encode)]
-#[cfg_attr(test, derive(Debug, PartialEq))]
-pub struct RentParams<T: Config> {
-	/// The total balance of the contract. Includes the balance transferred from the caller.
-	total_balance: BalanceOf<T>,
-	/// The free balance of the contract. Includes the balance transferred from the caller.
-	free_balance: BalanceOf<T>,
-	/// See crate [`Contracts::subsistence_threshold()`].
-	subsistence_threshold: BalanceOf<T>,
-	/// See crate [`Config::DepositPerContract`].
-	deposit_per_contract: BalanceOf<T>,
-	/// See crate [`Config::DepositPerStorageByte`].
-	deposit_per_storage_byte: BalanceOf<T>,
-	/// See crate [`Config::DepositPerStorageItem`].
-	deposit_per_storage_item: BalanceOf<T>,
-	/// See crate [`Ext::rent_allowance()`].
-	rent_allowance: BalanceOf<T>,
-	/// See crate [`Config::RentFraction`].
-	rent_fraction: Perbill,
-	/// See crate [`AliveContractInfo::storage_size`].
-	storage_size: u32,
-	/// See crate [`Executable::aggregate_code_len()`].
-	code_size: u32,
-	/// See crate [`Executable::refcount()`].
-	code_refcount: u32,
-	/// Reserved for backwards compatible changes to this data structure.
-	_reserved: Option<()>,
-}
-
-impl<T> RentParams<T>
-where
-	T: Config,
-	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
-{
-	fn new<E: Executable<T>>(
-		account_id: &T::AccountId,
-		contract: &AliveContractInfo<T>,
-		executable: &E
-	) -> Self {
-		Self {
-			total_balance: T::Currency::total_balance(account_id),
-			free_balance: T::Currency::free_balance(account_id),
-			subsistence_threshold: <Contracts<T>>::subsistence_threshold(),
-			deposit_per_contract: T::DepositPerContract::get(),
-			deposit_per_storage_byte: T::DepositPerStorageByte::get(),
-			deposit_per_storage_item: T::DepositPerStorageItem::get(),
-			rent_allowance: contract.rent_allowance,
-			rent_fraction: T::RentFraction::get(),
-			storage_size: contract.storage_size,
-			code_size: executable.aggregate_code_len(),
-			code_refcount: executable.refcount(),
-			_reserved: None,
-		}
-	}
-}
-
-/// We cannot derive `Default` because `T` does not necessarily implement `Default`.
-#[cfg(test)]
-impl<T: Config> Default for RentParams<T> {
-	fn default() -> Self {
-		Self {
-			total_balance: Default::default(),
-			free_balance: Default::default(),
-			subsistence_threshold: Default::default(),
-			deposit_per_contract: Default::default(),
-			deposit_per_storage_byte: Default::default(),
-			deposit_per_storage_item: Default::default(),
-			rent_allowance: Default::default(),
-			rent_fraction: Default::default(),
-			storage_size: Default::default(),
-			code_size: Default::default(),
-			code_refcount: Default::default(),
-			_reserved: Default::default(),
-		}
-	}
-}
-
 /// An interface that provides access to the external environment in which the
 /// smart-contract is executed.
 ///
@@ -222,7 +144,10 @@ pub trait Ext: sealing::Sealed {
 
 	/// Call (possibly transferring some amount of funds) into the specified account.
 	///
-	/// Returns the original code size of the called contract.
+	/// Returns the original code size of the called contract. When `allows_reentry` is
+	/// `false` the current contract cannot be re-entered by the callee or any contract it
+	/// calls. The call fails if `to` is a contract on the call stack that is protected
+	/// against re-entry.
 	///
 	/// # Return Value
 	///
@@ -233,30 +158,55 @@ pub trait Ext: sealing::Sealed {
 		value: BalanceOf<Self::T>,
 		gas_meter: &mut GasMeter<Self::T>,
 		input_data: Vec<u8>,
+		allows_reentry: bool,
 	) -> Result<(ExecReturnValue, u32), (ExecError, u32)>;
 
-	/// Restores the given destination contract sacrificing the current one.
+	/// Execute the code identified by `code` in the context of the current contract.
 	///
-	/// Since this function removes the self contract eagerly, if succeeded, no further actions should
-	/// be performed on this `Ext` instance.
+	/// The executed code has access to the storage and balance of the current contract
+	/// and sees the same caller and value transferred.
 	///
-	/// This function will fail if the same contract is present
-	/// on the contract call stack.
+	/// Returns the original code size of the executed code.
 	///
 	/// # Return Value
 	///
-	/// Result<(CallerCodeSize, DestCodeSize), (DispatchError, CallerCodeSize, DestCodesize)>
-	fn restore_to(
+	/// Result<(ExecReturnValue, CodeSize), (ExecError, CodeSize)>
+	fn delegate_call(
 		&mut self,
-		dest: AccountIdOf<Self::T>,
-		code_hash: CodeHash<Self::T>,
-		rent_allowance: BalanceOf<Self::T>,
-		delta: Vec<StorageKey>,
-	) -> Result<(u32, u32), (DispatchError, u32, u32)>;
+		code: CodeHash<Self::T>,
+		gas_meter: &mut GasMeter<Self::T>,
+		input_data: Vec<u8>,
+	) -> Result<(ExecReturnValue, u32), (ExecError, u32)>;
+
+	/// Replace the code of the current contract with the code identified by `hash`.
+	///
+	/// The new code is executed starting with the next call into the contract. The code
+	/// that is currently executing is not affected.
+	fn set_code_hash(&mut self, hash: CodeHash<Self::T>) -> DispatchResult;
+
+	/// Returns the code hash of the contract at `address`.
+	///
+	/// Returns `None` if `address` does not belong to a contract.
+	fn code_hash(&self, address: &AccountIdOf<Self::T>) -> Option<CodeHash<Self::T>>;
+
+	/// Returns the code hash of the current contract.
+	///
+	/// Within a delegate call this is the code hash of the contract that the code is
+	/// executed for and not the hash of the executed code.
+	fn own_code_hash(&self) -> CodeHash<Self::T>;
 
 	/// Returns a reference to the account id of the caller.
 	fn caller(&self) -> &AccountIdOf<Self::T>;
 
+	/// Returns `true` if the caller of the current contract is the origin of the call stack.
+	///
+	/// This means that the current contract was called directly by a plain account and
+	/// not by another contract.
+	fn caller_is_origin(&self) -> bool;
+
+	/// Returns `true` if `address` belongs to a contract.
+	fn is_contract(&self, address: &AccountIdOf<Self::T>) -> bool;
+
 	/// Returns a reference to the account id of the current contract.
 	fn address(&self) -> &AccountIdOf<Self::T>;
 
@@ -274,8 +224,8 @@ pub trait Ext: sealing::Sealed {
 	/// Returns the minimum balance that is required for creating an account.
 	fn minimum_balance(&self) -> BalanceOf<Self::T>;
 
-	/// Returns the deposit required to create a tombstone upon contract eviction.
-	fn tombstone_deposit(&self) -> BalanceOf<Self::T>;
+	/// Returns the base deposit that is required to create a contract.
+	fn contract_deposit(&self) -> BalanceOf<Self::T>;
 
 	/// Returns a random number for the current block with the given subject.
 	fn random(&self, subject: &[u8]) -> (SeedOf<Self::T>, BlockNumberOf<Self::T>);
@@ -285,12 +235,6 @@ pub trait Ext: sealing::Sealed {
 	/// There should not be any duplicates in `topics`.
 	fn deposit_event(&mut self, topics: Vec<TopicOf<Self::T>>, data: Vec<u8>);
 
-	/// Set rent allowance of the contract
-	fn set_rent_allowance(&mut self, rent_allowance: BalanceOf<Self::T>);
-
-	/// Rent allowance of the contract
-	fn rent_allowance(&self) -> BalanceOf<Self::T>;
-
 	/// Returns the current block number.
 	fn block_number(&self) -> BlockNumberOf<Self::T>;
 
@@ -303,8 +247,25 @@ pub trait Ext: sealing::Sealed {
 	/// Get a reference to the schedule used by the current call.
 	fn schedule(&self) -> &Schedule<Self::T>;
 
-	/// Information needed for rent calculations.
-	fn rent_params(&self) -> &RentParams<Self::T>;
+	/// Recovers the compressed ECDSA public key that was used to sign `message_hash`.
+	fn ecdsa_recover(&self, signature: &[u8; 65], message_hash: &[u8; 32]) -> Result<[u8; 33], ()>;
+
+	/// Returns the Ethereum address that belongs to the compressed ECDSA public key `pk`.
+	fn ecdsa_to_eth_address(&self, pk: &[u8; 33]) -> Result<[u8; 20], ()>;
+
+	/// Verify a sr25519 signature of `message` by `pub_key`.
+	fn sr25519_verify(&self, signature: &[u8; 64], message: &[u8], pub_key: &[u8; 32]) -> bool;
+
+	/// Verify an ed25519 signature of `message` by `pub_key`.
+	fn ed25519_verify(&self, signature: &[u8; 64], message: &[u8], pub_key: &[u8; 32]) -> bool;
+
+	/// Append a string to the debug buffer.
+	///
+	/// It is added as-is without any additional new line. Messages that would grow the buffer
+	/// beyond [`Config::MaxDebugBufferLen`] are dropped.
+	///
+	/// Returns `true` if debug message recording is enabled. Otherwise `false` is returned.
+	fn append_debug_buffer(&mut self, msg: &str) -> bool;
 }
 
 /// Describes the different functions that can be exported by an [`Executable`].
@@ -368,26 +329,8 @@ pub trait Executable<T: Config>: Sized {
 	/// The code hash of the executable.
 	fn code_hash(&self) -> &CodeHash<T>;
 
-	/// The storage that is occupied by the instrumented executable and its pristine source.
-	///
-	/// The returned size is already divided by the number of users who share the code.
-	/// This is essentially `aggregate_code_len() / refcount()`.
-	///
-	/// # Note
-	///
-	/// This works with the current in-memory value of refcount. When calling any contract
-	/// without refetching this from storage the result can be inaccurate as it might be
-	/// working with a stale value. Usually this inaccuracy is tolerable.
-	fn occupied_storage(&self) -> u32;
-
 	/// Size of the instrumented code in bytes.
 	fn code_len(&self) -> u32;
-
-	/// Sum of instrumented and pristine code len.
-	fn aggregate_code_len(&self) -> u32;
-
-	// The number of contracts using this executable.
-	fn refcount(&self) -> u32;
 }
 
 pub struct ExecutionContext<'a, T: Config + 'a, E> {
@@ -398,6 +341,12 @@ pub struct ExecutionContext<'a, T: Config + 'a, E> {
 	schedule: &'a Schedule<T>,
 	timestamp: MomentOf<T>,
 	block_number: T::BlockNumber,
+	storage_meter: Meter<T>,
+	storage_deposit_limit: Option<BalanceOf<T>>,
+	debug_message: Option<Vec<u8>>,
+	/// Set to `false` while the contract of this context makes a call that must not
+	/// re-enter it.
+	allows_reentry: bool,
 	_phantom: PhantomData<E>,
 }
 
@@ -411,7 +360,15 @@ where
 	///
 	/// The specified `origin` address will be used as `sender` for. The `origin` must be a regular
 	/// account (not a contract).
-	pub fn top_level(origin: T::AccountId, schedule: &'a Schedule<T>) -> Self {
+	///
+	/// The storage deposit that is charged from the `origin` for all storage that is added
+	/// during the execution cannot exceed `storage_deposit_limit`. `None` means that the
+	/// deposit is only limited by the free balance of the `origin`.
+	pub fn top_level(
+		origin: T::AccountId,
+		schedule: &'a Schedule<T>,
+		storage_deposit_limit: Option<BalanceOf<T>>,
+	) -> Self {
 		ExecutionContext {
 			caller: None,
 			self_trie_id: None,
@@ -420,10 +377,34 @@ where
 			schedule,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Pallet<T>>::block_number(),
+			storage_meter: Default::default(),
+			storage_deposit_limit,
+			debug_message: None,
+			allows_reentry: true,
 			_phantom: Default::default(),
 		}
 	}
 
+	/// Collect the messages that contracts emit through `seal_debug_message`.
+	///
+	/// This must only be enabled for dry-runs (RPC calls) and never for on-chain execution.
+	pub fn enable_debug_message(&mut self) {
+		self.debug_message = Some(Vec::new());
+	}
+
+	/// Take the debug messages that were collected by all the calls made through this context.
+	///
+	/// Returns an empty buffer if debug message recording is not enabled.
+	pub fn take_debug_message(&mut self) -> Vec<u8> {
+		self.debug_message.take().unwrap_or_default()
+	}
+
+	/// The storage deposit that was charged or refunded by all the calls made
+	/// through this context.
+	pub fn storage_deposit(&self) -> DepositOf<T> {
+		self.storage_meter.total_deposit()
+	}
+
 	fn nested<'b, 'c: 'b>(&'c self, dest: T::AccountId, trie_id: TrieId)
 		-> ExecutionContext<'b, T, E>
 	{
@@ -435,6 +416,10 @@ where
 			schedule: self.schedule,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
+			storage_meter: Default::default(),
+			storage_deposit_limit: self.storage_deposit_limit,
+			debug_message: self.debug_message.as_ref().map(|_| Vec::new()),
+			allows_reentry: true,
 			_phantom: Default::default(),
 		}
 	}
@@ -456,39 +441,67 @@ where
 		}
 
 		let contract = <ContractInfoOf<T>>::get(&dest)
-			.and_then(|contract| contract.get_alive())
 			.ok_or((Error::<T>::NotCallable.into(), 0))?;
 
 		let executable = E::from_storage(contract.code_hash, &self.schedule, gas_meter)
 			.map_err(|e| (e.into(), 0))?;
 		let code_len = executable.code_len();
 
-		// This charges the rent and denies access to a contract that is in need of
-		// eviction by returning `None`. We cannot evict eagerly here because those
-		// changes would be rolled back in case this contract is called by another
-		// contract.
-		// See: https://github.com/paritytech/substrate/issues/6439#issuecomment-648754324
-		let contract = Rent::<T, E>::charge(&dest, contract, executable.occupied_storage())
-			.map_err(|e| (e.into(), code_len))?
-			.ok_or((Error::<T>::NotCallable.into(), code_len))?;
-
-		let transactor_kind = self.transactor_kind();
 		let caller = self.self_account.clone();
 
 		let result = self.with_nested_context(dest.clone(), contract.trie_id.clone(), |nested| {
 			if value > BalanceOf::<T>::zero() {
 				transfer::<T>(
 					TransferCause::Call,
-					transactor_kind,
 					&caller,
 					&dest,
 					value,
 				)?
 			}
 
-			let call_context = nested.new_call_context(
-				caller, &dest, value, &contract, &executable,
-			);
+			let call_context = nested.new_call_context(caller, value);
+
+			let output = executable.execute(
+				call_context,
+				&ExportedFunction::Call,
+				input_data,
+				gas_meter,
+			).map_err(|e| ExecError { error: e.error, origin: ErrorOrigin::Callee })?;
+			Ok(output)
+		}).map_err(|e| (e, code_len))?;
+		Ok((result, code_len))
+	}
+
+	/// Execute the code identified by `code_hash` in the context of the current contract.
+	///
+	/// A new frame is pushed that shares the account, storage and balance with the frame of
+	/// the current contract. The `caller` and `value` of the current frame are passed on
+	/// unchanged. No funds are transferred.
+	///
+	/// # Return Value
+	///
+	/// Result<(ExecReturnValue, CodeSize), (ExecError, CodeSize)>
+	pub fn delegate_call(
+		&mut self,
+		caller: T::AccountId,
+		value: BalanceOf<T>,
+		code_hash: CodeHash<T>,
+		gas_meter: &mut GasMeter<T>,
+		input_data: Vec<u8>,
+	) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+		if self.depth == T::MaxDepth::get() as usize {
+			return Err((Error::<T>::MaxCallDepthReached.into(), 0));
+		}
+
+		let trie_id = self.self_trie_id.clone()
+			.ok_or((Error::<T>::NotCallable.into(), 0))?;
+		let executable = E::from_storage(code_hash, &self.schedule, gas_meter)
+			.map_err(|e| (e.into(), 0))?;
+		let code_len = executable.code_len();
+
+		let dest = self.self_account.clone();
+		let result = self.with_nested_context(dest, trie_id, |nested| {
+			let call_context = nested.new_call_context(caller, value);
 
 			let output = executable.execute(
 				call_context,
@@ -513,7 +526,6 @@ where
 			Err(Error::<T>::MaxCallDepthReached)?
 		}
 
-		let transactor_kind = self.transactor_kind();
 		let caller = self.self_account.clone();
 		let dest = Contracts::<T>::contract_address(&caller, executable.code_hash(), salt);
 
@@ -522,7 +534,7 @@ where
 			let dest_trie_id = Storage::<T>::generate_trie_id(&dest);
 
 			let output = self.with_nested_context(dest.clone(), dest_trie_id, |nested| {
-				let contract = Storage::<T>::place_contract(
+				Storage::<T>::place_contract(
 					&dest,
 					nested
 						.self_trie_id
@@ -535,25 +547,16 @@ where
 				// then error will be returned here.
 				transfer::<T>(
 					TransferCause::Instantiate,
-					transactor_kind,
 					&caller,
 					&dest,
 					endowment,
 				)?;
 
-				// Cache the value before calling into the constructor because that
-				// consumes the value. If the constructor creates additional contracts using
-				// the same code hash we still charge the "1 block rent" as if they weren't
-				// spawned. This is OK as overcharging is always safe.
-				let occupied_storage = executable.occupied_storage();
+				// The base deposit for the contract itself is charged together with
+				// the deposit for the storage that the constructor creates.
+				nested.storage_meter.charge_instantiate();
 
-				let call_context = nested.new_call_context(
-					caller.clone(),
-					&dest,
-					endowment,
-					&contract,
-					&executable,
-				);
+				let call_context = nested.new_call_context(caller.clone(), endowment);
 
 				let output = executable.execute(
 					call_context,
@@ -562,19 +565,10 @@ where
 					gas_meter,
 				).map_err(|e| ExecError { error: e.error, origin: ErrorOrigin::Callee })?;
 
-				// We need to re-fetch the contract because changes are written to storage
-				// eagerly during execution.
-				let contract = <ContractInfoOf<T>>::get(&dest)
-					.and_then(|contract| contract.get_alive())
-					.ok_or(Error::<T>::NotCallable)?;
-
-				// Collect the rent for the first block to prevent the creation of very large
-				// contracts that never intended to pay for even one block.
-				// This also makes sure that it is above the subsistence threshold
-				// in order to keep up the guarantuee that we always leave a tombstone behind
-				// with the exception of a contract that called `seal_terminate`.
-				Rent::<T, E>::charge(&dest, contract, occupied_storage)?
-					.ok_or(Error::<T>::NewContractNotFunded)?;
+				// The constructor must not remove the contract it is constructing.
+				if !<ContractInfoOf<T>>::contains_key(&dest) {
+					Err(Error::<T>::NotCallable)?
+				}
 
 				// Deposit an instantiation event.
 				deposit_event::<T>(vec![], Event::Instantiated(caller.clone(), dest.clone()));
@@ -595,10 +589,7 @@ where
 	fn new_call_context<'b>(
 		&'b mut self,
 		caller: T::AccountId,
-		dest: &T::AccountId,
 		value: BalanceOf<T>,
-		contract: &AliveContractInfo<T>,
-		executable: &E,
 	) -> CallContext<'b, 'a, T, E> {
 		let timestamp = self.timestamp.clone();
 		let block_number = self.block_number.clone();
@@ -608,25 +599,49 @@ where
 			value_transferred: value,
 			timestamp,
 			block_number,
-			rent_params: RentParams::new(dest, contract, executable),
 			_phantom: Default::default(),
 		}
 	}
 
 	/// Execute the given closure within a nested execution context.
+	///
+	/// When the closure succeeds the storage deposit of the nested context is finalized.
+	/// The deposit is settled with the origin as part of the outermost contract call so
+	/// that failing to pay for it reverts all changes made by the call.
 	fn with_nested_context<F>(&mut self, dest: T::AccountId, trie_id: TrieId, func: F)
 		-> ExecResult
 		where F: FnOnce(&mut ExecutionContext<T, E>) -> ExecResult
 	{
 		use frame_support::storage::TransactionOutcome::*;
+		let is_top = self.depth == 0;
+		let origin = self.self_account.clone();
 		let mut nested = self.nested(dest, trie_id);
-		frame_support::storage::with_transaction(|| {
-			let output = func(&mut nested);
+		let output = frame_support::storage::with_transaction(|| {
+			let output = func(&mut nested).and_then(|output| {
+				if output.flags.contains(ReturnFlags::REVERT) {
+					return Ok(output);
+				}
+				nested.storage_meter.finish(&nested.self_account);
+				if is_top {
+					nested.storage_meter.settle(&origin, nested.storage_deposit_limit)?;
+				}
+				Ok(output)
+			});
 			match output {
 				Ok(ref rv) if !rv.flags.contains(ReturnFlags::REVERT) => Commit(output),
 				_ => Rollback(output),
 			}
-		})
+		});
+		let nested_meter = sp_std::mem::take(&mut nested.storage_meter);
+		let nested_message = nested.debug_message.take();
+		if matches!(output, Ok(ref rv) if !rv.flags.contains(ReturnFlags::REVERT)) {
+			self.storage_meter.absorb(nested_meter);
+		}
+		// Debug messages are kept even when the nested call is reverted.
+		if let (Some(message), Some(nested_message)) = (&mut self.debug_message, nested_message) {
+			message.extend(nested_message);
+		}
+		output
 	}
 
 	/// Returns whether a contract, identified by address, is currently live in the execution
@@ -636,25 +651,25 @@ where
 			self.caller.map_or(false, |caller| caller.is_live(account))
 	}
 
-	fn transactor_kind(&self) -> TransactorKind {
-		if self.depth == 0 {
-			debug_assert!(self.self_trie_id.is_none());
-			debug_assert!(self.caller.is_none());
-			debug_assert!(ContractInfoOf::<T>::get(&self.self_account).is_none());
-			TransactorKind::PlainAccount
-		} else {
-			TransactorKind::Contract
-		}
+	/// Returns the account that signed the transaction which started the execution.
+	fn origin(&self) -> &T::AccountId {
+		self.caller.map_or(&self.self_account, |caller| caller.origin())
+	}
+
+	/// Returns whether `account` may be entered by a new call.
+	///
+	/// This is not the case if `account` is live in the execution stack and one of its
+	/// contexts is protected against re-entry.
+	fn allows_reentry(&self, account: &T::AccountId) -> bool {
+		(&self.self_account != account || self.allows_reentry) &&
+			self.caller.map_or(true, |caller| caller.allows_reentry(account))
 	}
-}
 
-/// Describes whether we deal with a contract or a plain account.
-enum TransactorKind {
-	/// Transaction was initiated from a plain account. That can be either be through a
-	/// signed transaction or through RPC.
-	PlainAccount,
-	/// The call was initiated by a contract account.
-	Contract,
+	/// The size of all debug messages collected in this and all calling contexts.
+	fn debug_message_len(&self) -> usize {
+		self.debug_message.as_ref().map_or(0, |message| message.len()) +
+			self.caller.map_or(0, |caller| caller.debug_message_len())
+	}
 }
 
 /// Describes possible transfer causes.
@@ -668,11 +683,9 @@ enum TransferCause {
 ///
 /// We only allow allow for draining all funds of the sender if `cause` is
 /// is specified as `Terminate`. Otherwise, any transfer that would bring the sender below the
-/// subsistence threshold (for contracts) or the existential deposit (for plain accounts)
-/// results in an error.
+/// existential deposit results in an error.
 fn transfer<T: Config>(
 	cause: TransferCause,
-	origin: TransactorKind,
 	transactor: &T::AccountId,
 	dest: &T::AccountId,
 	value: BalanceOf<T>,
@@ -681,21 +694,11 @@ where
 	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
 {
 	use self::TransferCause::*;
-	use self::TransactorKind::*;
-
-	// Only seal_terminate is allowed to bring the sender below the subsistence
-	// threshold or even existential deposit.
-	let existence_requirement = match (cause, origin) {
-		(Terminate, _) => ExistenceRequirement::AllowDeath,
-		(_, Contract) => {
-			ensure!(
-				T::Currency::total_balance(transactor).saturating_sub(value) >=
-					Contracts::<T>::subsistence_threshold(),
-				Error::<T>::BelowSubsistenceThreshold,
-			);
-			ExistenceRequirement::KeepAlive
-		},
-		(_, PlainAccount) => ExistenceRequirement::KeepAlive,
+
+	// Only seal_terminate is allowed to bring the sender below the existential deposit.
+	let existence_requirement = match cause {
+		Terminate => ExistenceRequirement::AllowDeath,
+		Call | Instantiate => ExistenceRequirement::KeepAlive,
 	};
 
 	T::Currency::transfer(transactor, dest, value, existence_requirement)
@@ -721,7 +724,6 @@ struct CallContext<'a, 'b: 'a, T: Config + 'b, E> {
 	value_transferred: BalanceOf<T>,
 	timestamp: MomentOf<T>,
 	block_number: T::BlockNumber,
-	rent_params: RentParams<T>,
 	_phantom: PhantomData<E>,
 }
 
@@ -755,7 +757,13 @@ where
 		// the contract cannot be absent in storage;
 		// write cannot return `None`;
 		// qed
-		Storage::<T>::write(&self.ctx.self_account, trie_id, &key, value)
+		Storage::<T>::write(
+			&self.ctx.self_account,
+			trie_id,
+			&key,
+			value,
+			Some(&mut self.ctx.storage_meter),
+		)
 	}
 
 	fn instantiate(
@@ -781,7 +789,6 @@ where
 	) -> DispatchResult {
 		transfer::<T>(
 			TransferCause::Call,
-			TransactorKind::Contract,
 			&self.ctx.self_account.clone(),
 			to,
 			value,
@@ -793,21 +800,21 @@ where
 		beneficiary: &AccountIdOf<Self::T>,
 	) -> Result<u32, (DispatchError, u32)> {
 		let self_id = self.ctx.self_account.clone();
-		let value = T::Currency::free_balance(&self_id);
 		if let Some(caller_ctx) = self.ctx.caller {
 			if caller_ctx.is_live(&self_id) {
 				return Err((Error::<T>::ReentranceDenied.into(), 0));
 			}
 		}
-		transfer::<T>(
-			TransferCause::Terminate,
-			TransactorKind::Contract,
-			&self_id,
-			beneficiary,
-			value,
-		).map_err(|e| (e, 0))?;
-		if let Some(ContractInfo::Alive(info)) = ContractInfoOf::<T>::take(&self_id) {
+		if let Some(info) = ContractInfoOf::<T>::take(&self_id) {
 			Storage::<T>::queue_trie_for_deletion(&info).map_err(|e| (e, 0))?;
+			let origin = self.ctx.origin().clone();
+			self.ctx.storage_meter.terminate(&origin, &self_id, &info);
+			transfer::<T>(
+				TransferCause::Terminate,
+				&self_id,
+				beneficiary,
+				T::Currency::free_balance(&self_id),
+			).map_err(|e| (e, 0))?;
 			let code_len = E::remove_user(info.code_hash);
 			Contracts::<T>::deposit_event(Event::Terminated(self_id, beneficiary.clone()));
 			Ok(code_len)
@@ -826,42 +833,54 @@ where
 		value: BalanceOf<T>,
 		gas_meter: &mut GasMeter<T>,
 		input_data: Vec<u8>,
+		allows_reentry: bool,
 	) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
-		self.ctx.call(to.clone(), value, gas_meter, input_data)
+		// The protection of the current contract must be in place before the check so that
+		// a direct recursion is caught, too.
+		self.ctx.allows_reentry = allows_reentry;
+		let result = if self.ctx.allows_reentry(to) {
+			self.ctx.call(to.clone(), value, gas_meter, input_data)
+		} else {
+			Err((Error::<T>::ReentranceDenied.into(), 0))
+		};
+		// The protection only applies for the duration of this call.
+		self.ctx.allows_reentry = true;
+		result
 	}
 
-	fn restore_to(
+	fn delegate_call(
 		&mut self,
-		dest: AccountIdOf<Self::T>,
-		code_hash: CodeHash<Self::T>,
-		rent_allowance: BalanceOf<Self::T>,
-		delta: Vec<StorageKey>,
-	) -> Result<(u32, u32), (DispatchError, u32, u32)> {
-		if let Some(caller_ctx) = self.ctx.caller {
-			if caller_ctx.is_live(&self.ctx.self_account) {
-				return Err((Error::<T>::ReentranceDenied.into(), 0, 0));
-			}
-		}
+		code: CodeHash<T>,
+		gas_meter: &mut GasMeter<T>,
+		input_data: Vec<u8>,
+	) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+		let caller = self.caller.clone();
+		self.ctx.delegate_call(caller, self.value_transferred, code, gas_meter, input_data)
+	}
 
-		let result = Rent::<T, E>::restore_to(
-			self.ctx.self_account.clone(),
-			dest.clone(),
-			code_hash.clone(),
-			rent_allowance,
-			delta,
-		);
-		if let Ok(_) = result {
-			deposit_event::<Self::T>(
-				vec![],
-				Event::Restored(
-					self.ctx.self_account.clone(),
-					dest,
-					code_hash,
-					rent_allowance,
-				),
-			);
-		}
-		result
+	fn set_code_hash(&mut self, hash: CodeHash<T>) -> DispatchResult {
+		let account = self.ctx.self_account.clone();
+		let mut info = <ContractInfoOf<T>>::get(&account).ok_or(Error::<T>::NotCallable)?;
+		E::add_user(hash)?;
+		let prev_hash = sp_std::mem::replace(&mut info.code_hash, hash);
+		<ContractInfoOf<T>>::insert(&account, info);
+		E::remove_user(prev_hash);
+		deposit_event::<T>(vec![], Event::ContractCodeUpdated(account, hash, prev_hash));
+		Ok(())
+	}
+
+	fn code_hash(&self, address: &T::AccountId) -> Option<CodeHash<T>> {
+		<ContractInfoOf<T>>::get(address).map(|info| info.code_hash)
+	}
+
+	fn own_code_hash(&self) -> CodeHash<T> {
+		<ContractInfoOf<T>>::get(&self.ctx.self_account)
+			.map(|info| info.code_hash)
+			.expect(
+				"this function is only invoked by in the context of a contract;\
+				this contract is therefore alive;\
+				qed"
+			)
 	}
 
 	fn address(&self) -> &T::AccountId {
@@ -872,6 +891,14 @@ where
 		&self.caller
 	}
 
+	fn caller_is_origin(&self) -> bool {
+		&self.caller == self.ctx.origin()
+	}
+
+	fn is_contract(&self, address: &T::AccountId) -> bool {
+		<ContractInfoOf<T>>::contains_key(address)
+	}
+
 	fn balance(&self) -> BalanceOf<T> {
 		T::Currency::free_balance(&self.ctx.self_account)
 	}
@@ -892,8 +919,8 @@ where
 		T::Currency::minimum_balance()
 	}
 
-	fn tombstone_deposit(&self) -> BalanceOf<T> {
-		T::TombstoneDeposit::get()
+	fn contract_deposit(&self) -> BalanceOf<T> {
+		T::DepositPerContract::get()
 	}
 
 	fn deposit_event(&mut self, topics: Vec<T::Hash>, data: Vec<u8>) {
@@ -903,22 +930,6 @@ where
 		);
 	}
 
-	fn set_rent_allowance(&mut self, rent_allowance: BalanceOf<T>) {
-		if let Err(storage::ContractAbsentError) =
-			Storage::<T>::set_rent_allowance(&self.ctx.self_account, rent_allowance)
-		{
-			panic!(
-				"`self_account` points to an alive contract within the `CallContext`;
-					set_rent_allowance cannot return `Err`; qed"
-			);
-		}
-	}
-
-	fn rent_allowance(&self) -> BalanceOf<T> {
-		Storage::<T>::rent_allowance(&self.ctx.self_account)
-			.unwrap_or_else(|_| <BalanceOf<T>>::max_value()) // Must never be triggered actually
-	}
-
 	fn block_number(&self) -> T::BlockNumber { self.block_number }
 
 	fn max_value_size(&self) -> u32 {
@@ -933,8 +944,46 @@ where
 		&self.ctx.schedule
 	}
 
-	fn rent_params(&self) -> &RentParams<Self::T> {
-		&self.rent_params
+	fn ecdsa_recover(&self, signature: &[u8; 65], message_hash: &[u8; 32]) -> Result<[u8; 33], ()> {
+		secp256k1_ecdsa_recover_compressed(signature, message_hash).map_err(|_| ())
+	}
+
+	fn ecdsa_to_eth_address(&self, pk: &[u8; 33]) -> Result<[u8; 20], ()> {
+		let pk = libsecp256k1::PublicKey::parse_compressed(pk).map_err(|_| ())?;
+		// The address consists of the last 20 bytes of the hashed uncompressed key
+		// without its one byte prefix.
+		let hash = keccak_256(&pk.serialize()[1..]);
+		let mut address = [0u8; 20];
+		address.copy_from_slice(&hash[12..]);
+		Ok(address)
+	}
+
+	fn sr25519_verify(&self, signature: &[u8; 64], message: &[u8], pub_key: &[u8; 32]) -> bool {
+		sp_io::crypto::sr25519_verify(
+			&sr25519::Signature::from_raw(*signature),
+			message,
+			&sr25519::Public::from_raw(*pub_key),
+		)
+	}
+
+	fn ed25519_verify(&self, signature: &[u8; 64], message: &[u8], pub_key: &[u8; 32]) -> bool {
+		sp_io::crypto::ed25519_verify(
+			&ed25519::Signature::from_raw(*signature),
+			message,
+			&ed25519::Public::from_raw(*pub_key),
+		)
+	}
+
+	fn append_debug_buffer(&mut self, msg: &str) -> bool {
+		let len = self.ctx.debug_message_len();
+		if let Some(buffer) = &mut self.ctx.debug_message {
+			if len.saturating_add(msg.len()) <= T::MaxDebugBufferLen::get() as usize {
+				buffer.extend(msg.as_bytes());
+			}
+			true
+		} else {
+			false
+		}
 	}
 }
 
@@ -974,18 +1023,19 @@ mod tests {
 		gas::GasMeter, tests::{ExtBuilder, Test, Event as MetaEvent},
 		storage::{Storage, ContractAbsentError},
 		tests::{
-			ALICE, BOB, CHARLIE,
+			ALICE, BOB, CHARLIE, DJANGO,
 			test_utils::{place_contract, set_balance, get_balance},
 		},
 		exec::ExportedFunction::*,
 		Error, Weight, CurrentSchedule,
 	};
 	use sp_core::Bytes;
-	use frame_support::assert_noop;
+	use frame_support::{assert_noop, assert_ok};
+	use pallet_contracts_primitives::StorageDeposit;
 	use sp_runtime::DispatchError;
 	use assert_matches::assert_matches;
 	use std::{cell::RefCell, collections::HashMap, rc::Rc};
-	use pretty_assertions::{assert_eq, assert_ne};
+	use pretty_assertions::assert_eq;
 
 	type MockContext<'a> = ExecutionContext<'a, Test, MockExecutable>;
 
@@ -1070,17 +1120,6 @@ mod tests {
 				}
 			});
 		}
-
-		fn refcount(code_hash: &CodeHash<Test>) -> u32 {
-			LOADER.with(|loader| {
-				loader
-					.borrow()
-					.map
-					.get(code_hash)
-					.expect("code_hash does not exist")
-					.refcount()
-			})
-		}
 	}
 
 	impl Executable<Test> for MockExecutable {
@@ -1141,21 +1180,9 @@ mod tests {
 			&self.code_hash
 		}
 
-		fn occupied_storage(&self) -> u32 {
-			0
-		}
-
 		fn code_len(&self) -> u32 {
 			0
 		}
-
-		fn aggregate_code_len(&self) -> u32 {
-			0
-		}
-
-		fn refcount(&self) -> u32 {
-			self.refcount as u32
-		}
 	}
 
 	fn exec_success() -> ExecResult {
@@ -1177,7 +1204,7 @@ mod tests {
 
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			place_contract(&BOB, exec_ch);
 
 			assert_matches!(
@@ -1202,7 +1229,6 @@ mod tests {
 
 			super::transfer::<Test>(
 				super::TransferCause::Call,
-				super::TransactorKind::PlainAccount,
 				&origin,
 				&dest,
 				55,
@@ -1227,7 +1253,7 @@ mod tests {
 
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(origin.clone(), &schedule);
+			let mut ctx = MockContext::top_level(origin.clone(), &schedule, None);
 			place_contract(&BOB, return_ch);
 			set_balance(&origin, 100);
 			let balance = get_balance(&dest);
@@ -1241,9 +1267,7 @@ mod tests {
 
 			assert!(!output.0.is_success());
 			assert_eq!(get_balance(&origin), 100);
-
-			// the rent is still charged
-			assert!(get_balance(&dest) < balance);
+			assert_eq!(get_balance(&dest), balance);
 		});
 	}
 
@@ -1259,7 +1283,6 @@ mod tests {
 
 			let result = super::transfer::<Test>(
 				super::TransferCause::Call,
-				super::TransactorKind::PlainAccount,
 				&origin,
 				&dest,
 				100,
@@ -1287,7 +1310,7 @@ mod tests {
 
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(origin, &schedule);
+			let mut ctx = MockContext::top_level(origin, &schedule, None);
 			place_contract(&BOB, return_ch);
 
 			let result = ctx.call(
@@ -1316,7 +1339,7 @@ mod tests {
 
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(origin, &schedule);
+			let mut ctx = MockContext::top_level(origin, &schedule, None);
 			place_contract(&BOB, return_ch);
 
 			let result = ctx.call(
@@ -1342,7 +1365,7 @@ mod tests {
 		// This one tests passing the input data into a contract via call.
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			place_contract(&BOB, input_data_ch);
 
 			let result = ctx.call(
@@ -1365,17 +1388,17 @@ mod tests {
 		// This one tests passing the input data into a contract via instantiate.
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let subsistence = Contracts::<Test>::subsistence_threshold();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let min_balance = <Test as Config>::Currency::minimum_balance();
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
 			let executable = MockExecutable::from_storage(
 				input_data_ch, &schedule, &mut gas_meter
 			).unwrap();
 
-			set_balance(&ALICE, subsistence * 10);
+			set_balance(&ALICE, min_balance * 10);
 
 			let result = ctx.instantiate(
-				subsistence * 3,
+				min_balance * 3,
 				&mut gas_meter,
 				executable,
 				vec![1, 2, 3, 4],
@@ -1395,7 +1418,7 @@ mod tests {
 		let value = Default::default();
 		let recurse_ch = MockLoader::insert(Call, |ctx, _| {
 			// Try to call into yourself.
-			let r = ctx.ext.call(&BOB, 0, ctx.gas_meter, vec![]);
+			let r = ctx.ext.call(&BOB, 0, ctx.gas_meter, vec![], true);
 
 			REACHED_BOTTOM.with(|reached_bottom| {
 				let mut reached_bottom = reached_bottom.borrow_mut();
@@ -1418,7 +1441,7 @@ mod tests {
 
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			set_balance(&BOB, 1);
 			place_contract(&BOB, recurse_ch);
 
@@ -1451,7 +1474,7 @@ mod tests {
 
 			// Call into CHARLIE contract.
 			assert_matches!(
-				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![]),
+				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], true),
 				Ok(_)
 			);
 			exec_success()
@@ -1466,7 +1489,7 @@ mod tests {
 
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(origin.clone(), &schedule);
+			let mut ctx = MockContext::top_level(origin.clone(), &schedule, None);
 			place_contract(&dest, bob_ch);
 			place_contract(&CHARLIE, charlie_ch);
 
@@ -1492,7 +1515,7 @@ mod tests {
 
 			// Call into charlie contract.
 			assert_matches!(
-				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![]),
+				ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], true),
 				Ok(_)
 			);
 			exec_success()
@@ -1504,7 +1527,7 @@ mod tests {
 
 		ExtBuilder::default().build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			place_contract(&BOB, bob_ch);
 			place_contract(&CHARLIE, charlie_ch);
 
@@ -1525,7 +1548,7 @@ mod tests {
 
 		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
 			let executable = MockExecutable::from_storage(
 				dummy_ch, &schedule, &mut gas_meter
@@ -1553,7 +1576,7 @@ mod tests {
 
 		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
 			let executable = MockExecutable::from_storage(
 				dummy_ch, &schedule, &mut gas_meter
@@ -1589,7 +1612,7 @@ mod tests {
 
 		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
 			let executable = MockExecutable::from_storage(
 				dummy_ch, &schedule, &mut gas_meter
@@ -1627,7 +1650,7 @@ mod tests {
 				// Instantiate a contract and save it's address in `instantiated_contract_address`.
 				let (address, output, _) = ctx.ext.instantiate(
 					dummy_ch,
-					Contracts::<Test>::subsistence_threshold() * 3,
+					<Test as Config>::Currency::minimum_balance() * 3,
 					ctx.gas_meter,
 					vec![],
 					&[48, 49, 50],
@@ -1640,8 +1663,8 @@ mod tests {
 
 		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
-			set_balance(&ALICE, Contracts::<Test>::subsistence_threshold() * 100);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			set_balance(&ALICE, <Test as Config>::Currency::minimum_balance() * 100);
 			place_contract(&BOB, instantiator_ch);
 
 			assert_matches!(
@@ -1689,7 +1712,7 @@ mod tests {
 
 		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 			set_balance(&ALICE, 1000);
 			set_balance(&BOB, 100);
 			place_contract(&BOB, instantiator_ch);
@@ -1717,7 +1740,7 @@ mod tests {
 			.build()
 			.execute_with(|| {
 				let schedule = <CurrentSchedule<Test>>::get();
-				let mut ctx = MockContext::top_level(ALICE, &schedule);
+				let mut ctx = MockContext::top_level(ALICE, &schedule, None);
 				let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
 				let executable = MockExecutable::from_storage(
 					terminate_ch, &schedule, &mut gas_meter
@@ -1743,109 +1766,338 @@ mod tests {
 	}
 
 	#[test]
-	fn rent_allowance() {
-		let rent_allowance_ch = MockLoader::insert(Constructor, |ctx, _| {
-			let subsistence = Contracts::<Test>::subsistence_threshold();
-			let allowance = subsistence * 3;
-			assert_eq!(ctx.ext.rent_allowance(), <BalanceOf<Test>>::max_value());
-			ctx.ext.set_rent_allowance(allowance);
-			assert_eq!(ctx.ext.rent_allowance(), allowance);
+	fn storage_deposit_is_charged_and_refunded() {
+		let code_hash = MockLoader::insert(Call, |ctx, _| {
+			let value = if ctx.input_data.is_empty() { None } else { Some(ctx.input_data) };
+			ctx.ext.set_storage([1; 32], value).unwrap();
+			exec_success()
+		});
+
+		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
+			let schedule = <CurrentSchedule<Test>>::get();
+			let per_byte = <Test as Config>::DepositPerStorageByte::get();
+			let per_item = <Test as Config>::DepositPerStorageItem::get();
+			let deposit = 4 * per_byte + per_item;
+			set_balance(&ALICE, 1000);
+			place_contract(&BOB, code_hash);
+
+			// Adding storage charges the origin.
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![1, 2, 3, 4]),
+				Ok(_)
+			);
+			assert_eq!(ctx.storage_deposit(), StorageDeposit::Charge(deposit));
+			assert_eq!(get_balance(&ALICE), 1000 - deposit);
+			assert_eq!(<Test as Config>::Currency::reserved_balance(&BOB), deposit);
+			assert_eq!(<ContractInfoOf<Test>>::get(&BOB).unwrap().storage_deposit, deposit);
+
+			// Removing it refunds the deposit.
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+			assert_eq!(ctx.storage_deposit(), StorageDeposit::Refund(deposit));
+			assert_eq!(get_balance(&ALICE), 1000);
+			assert_eq!(<Test as Config>::Currency::reserved_balance(&BOB), 0);
+			assert_eq!(<ContractInfoOf<Test>>::get(&BOB).unwrap().storage_deposit, 0);
+		});
+	}
+
+	#[test]
+	fn storage_deposit_limit_is_enforced() {
+		let set_ch = MockLoader::insert(Call, |ctx, _| {
+			ctx.ext.set_storage([1; 32], Some(vec![1, 2, 3, 4])).unwrap();
+			exec_success()
+		});
+
+		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
+			let schedule = <CurrentSchedule<Test>>::get();
+			set_balance(&ALICE, 1000);
+			set_balance(&BOB, 100);
+			place_contract(&BOB, set_ch);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, Some(1));
+			assert_eq!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]).map_err(|e| e.0),
+				Err(Error::<Test>::StorageDepositLimitExhausted.into()),
+			);
+			assert_eq!(get_balance(&ALICE), 1000);
+			assert_eq!(Storage::<Test>::read(
+				&<ContractInfoOf<Test>>::get(&BOB).unwrap().trie_id,
+				&[1; 32],
+			), None);
+		});
+	}
+
+	#[test]
+	fn storage_deposit_refunds_are_settled_before_charges() {
+		let store_ch = MockLoader::insert(Call, |ctx, _| {
+			let value = if ctx.input_data.is_empty() { None } else { Some(ctx.input_data) };
+			ctx.ext.set_storage([1; 32], value).unwrap();
+			exec_success()
+		});
+		let bob_ch = MockLoader::insert(Call, |ctx, _| {
+			// Charges more than the limit ...
+			assert_matches!(ctx.ext.call(&DJANGO, 0, ctx.gas_meter, vec![0; 16], true), Ok(_));
+			// ... which is brought back below it by a later refund.
+			assert_matches!(ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], true), Ok(_));
+			exec_success()
+		});
+
+		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
+			let schedule = <CurrentSchedule<Test>>::get();
+			let per_byte = <Test as Config>::DepositPerStorageByte::get();
+			let per_item = <Test as Config>::DepositPerStorageItem::get();
+			place_contract(&BOB, bob_ch);
+			place_contract(&CHARLIE, store_ch);
+			place_contract(&DJANGO, store_ch);
+
+			let mut ctx = MockContext::top_level(BOB, &schedule, None);
+			assert_matches!(
+				ctx.call(CHARLIE, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![0; 8]),
+				Ok(_)
+			);
+
+			let charge = 16 * per_byte + per_item;
+			let refund = 8 * per_byte + per_item;
+			let limit = charge - refund;
+			// Alice can pay for the total deposit but not for the charge alone.
+			let balance = 15 + limit + 5;
+			assert!(balance - charge < 15);
+			set_balance(&ALICE, balance);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, Some(limit));
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+			assert_eq!(ctx.storage_deposit(), StorageDeposit::Charge(limit));
+			assert_eq!(get_balance(&ALICE), balance - limit);
+			assert_eq!(<Test as Config>::Currency::reserved_balance(&CHARLIE), 0);
+			assert_eq!(<Test as Config>::Currency::reserved_balance(&DJANGO), charge);
+		});
+	}
+
+	#[test]
+	fn delegate_call_runs_in_callers_context() {
+		let lib_ch = MockLoader::insert(Call, |ctx, _| {
+			// The library code sees the context of the contract that delegated to it.
+			assert_eq!(*ctx.ext.address(), BOB);
+			assert_eq!(*ctx.ext.caller(), ALICE);
+			assert_eq!(ctx.ext.value_transferred(), 55);
+			ctx.ext.set_storage([1; 32], Some(ctx.input_data)).unwrap();
+			exec_success()
+		});
+		let bob_ch = MockLoader::insert(Call, move |ctx, _| {
+			assert_matches!(
+				ctx.ext.delegate_call(lib_ch, ctx.gas_meter, vec![1, 2, 3]),
+				Ok(_)
+			);
+			exec_success()
+		});
+
+		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
+			let schedule = <CurrentSchedule<Test>>::get();
+			set_balance(&ALICE, 1000);
+			place_contract(&BOB, bob_ch);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 55, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+
+			// The storage was written to the contract that made the delegate call.
+			assert_eq!(Storage::<Test>::read(
+				&<ContractInfoOf<Test>>::get(&BOB).unwrap().trie_id,
+				&[1; 32],
+			), Some(vec![1, 2, 3]));
+			// The library code was not transformed into a contract.
+			assert_eq!(<ContractInfoOf<Test>>::get(&BOB).unwrap().code_hash, bob_ch);
+		});
+	}
+
+	#[test]
+	fn delegate_call_to_missing_code_fails() {
+		let bob_ch = MockLoader::insert(Call, |ctx, _| {
+			let missing = <Test as frame_system::Config>::Hash::repeat_byte(0xff);
+			assert_eq!(
+				ctx.ext.delegate_call(missing, ctx.gas_meter, vec![]).map_err(|e| e.0.error),
+				Err(Error::<Test>::CodeNotFound.into()),
+			);
+			exec_success()
+		});
+
+		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
+			let schedule = <CurrentSchedule<Test>>::get();
+			place_contract(&BOB, bob_ch);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+		});
+	}
+
+	#[test]
+	fn set_code_hash_works() {
+		fn refcount(code_hash: CodeHash<Test>) -> Option<u64> {
+			LOADER.with(|loader| loader.borrow().map.get(&code_hash).map(|e| e.refcount))
+		}
+
+		let new_ch = MockLoader::insert(Call, |ctx, _| {
+			assert_eq!(ctx.ext.own_code_hash(), ctx.ext.code_hash(&BOB).unwrap());
 			exec_success()
 		});
+		let bob_ch = MockLoader::insert(Call, move |ctx, _| {
+			assert_eq!(ctx.ext.code_hash(&CHARLIE), None);
+			let old_ch = ctx.ext.own_code_hash();
+			assert_ok!(ctx.ext.set_code_hash(new_ch));
+			// The hash is changed right away but the running code is not replaced.
+			assert_eq!(ctx.ext.own_code_hash(), new_ch);
+			assert_eq!(ctx.ext.code_hash(&BOB), Some(new_ch));
+			assert_ne!(old_ch, new_ch);
+			exec_success()
+		});
+
+		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
+			let schedule = <CurrentSchedule<Test>>::get();
+			place_contract(&BOB, bob_ch);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+			assert_eq!(<ContractInfoOf<Test>>::get(&BOB).unwrap().code_hash, new_ch);
+			assert_eq!(refcount(new_ch), Some(2));
+			assert_eq!(refcount(bob_ch), None);
+			assert_eq!(
+				&events(),
+				&[Event::ContractCodeUpdated(BOB, new_ch, bob_ch)],
+			);
+
+			// The next call executes the new code.
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+		});
+	}
+
+	#[test]
+	fn call_deny_reentry() {
+		// BOB calls CHARLIE which calls back into BOB. The input of BOB decides whether
+		// it protects itself against re-entry.
+		let bob_ch = MockLoader::insert(Call, |ctx, _| {
+			if ctx.input_data.is_empty() {
+				return exec_success();
+			}
+			let allows_reentry = ctx.input_data[0] == 1;
+			ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], allows_reentry)
+				.map(|(output, _)| output)
+				.map_err(|(error, _)| error)
+		});
+		let charlie_ch = MockLoader::insert(Call, |ctx, _| {
+			ctx.ext.call(&BOB, 0, ctx.gas_meter, vec![], true)
+				.map(|(output, _)| output)
+				.map_err(|(error, _)| error)
+		});
 
 		ExtBuilder::default().build().execute_with(|| {
-			let subsistence = Contracts::<Test>::subsistence_threshold();
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
-			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
-			let executable = MockExecutable::from_storage(
-				rent_allowance_ch, &schedule, &mut gas_meter
-			).unwrap();
-			set_balance(&ALICE, subsistence * 10);
+			place_contract(&BOB, bob_ch);
+			place_contract(&CHARLIE, charlie_ch);
 
-			let result = ctx.instantiate(
-				subsistence * 5,
-				&mut gas_meter,
-				executable,
-				vec![],
-				&[],
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![1]),
+				Ok(_)
+			);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_eq!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![0])
+					.map_err(|(error, _)| error.error),
+				Err(<Error<Test>>::ReentranceDenied.into()),
 			);
-			assert_matches!(result, Ok(_));
 		});
 	}
 
 	#[test]
-	fn rent_params_works() {
-		let code_hash = MockLoader::insert(Call, |ctx, executable| {
-			let address = ctx.ext.address();
-			let contract = <ContractInfoOf<Test>>::get(address)
-				.and_then(|c| c.get_alive())
-				.unwrap();
-			assert_eq!(ctx.ext.rent_params(), &RentParams::new(address, &contract, executable));
+	fn call_deny_direct_recursion() {
+		let bob_ch = MockLoader::insert(Call, |ctx, _| {
+			assert_eq!(
+				ctx.ext.call(&BOB, 0, ctx.gas_meter, vec![], false),
+				Err((<Error<Test>>::ReentranceDenied.into(), 0)),
+			);
 			exec_success()
 		});
 
 		ExtBuilder::default().build().execute_with(|| {
-			let subsistence = Contracts::<Test>::subsistence_threshold();
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
-			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
-			set_balance(&ALICE, subsistence * 10);
-			place_contract(&BOB, code_hash);
-			ctx.call(
-				BOB,
-				0,
-				&mut gas_meter,
-				vec![],
-			).unwrap();
+			place_contract(&BOB, bob_ch);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
 		});
 	}
 
 	#[test]
-	fn rent_params_snapshotted() {
-		let code_hash = MockLoader::insert(Call, |ctx, executable| {
-			let subsistence = Contracts::<Test>::subsistence_threshold();
-			let address = ctx.ext.address();
-			let contract = <ContractInfoOf<Test>>::get(address)
-				.and_then(|c| c.get_alive())
-				.unwrap();
-			let rent_params = RentParams::new(address, &contract, executable);
-
-			// Changing the allowance during the call: rent params stay unchanged.
-			let allowance = 42;
-			assert_ne!(allowance, rent_params.rent_allowance);
-			ctx.ext.set_rent_allowance(allowance);
-			assert_eq!(ctx.ext.rent_params(), &rent_params);
-
-			// Creating another instance from the same code_hash increases the refcount.
-			// This is also not reflected in the rent params.
-			assert_eq!(MockLoader::refcount(&executable.code_hash), 1);
-			ctx.ext.instantiate(
-				executable.code_hash,
-				subsistence * 25,
-				&mut GasMeter::<Test>::new(GAS_LIMIT),
-				vec![],
-				&[],
-			).unwrap();
-			assert_eq!(MockLoader::refcount(&executable.code_hash), 2);
-			assert_eq!(ctx.ext.rent_params(), &rent_params);
+	fn caller_is_origin_returns_proper_values() {
+		let charlie_ch = MockLoader::insert(Call, |ctx, _| {
+			// BOB is not the origin of the stack
+			assert!(!ctx.ext.caller_is_origin());
+			exec_success()
+		});
+		let bob_ch = MockLoader::insert(Call, |ctx, _| {
+			// ALICE is the origin of the call stack
+			assert!(ctx.ext.caller_is_origin());
+			// BOB calls CHARLIE
+			ctx.ext.call(&CHARLIE, 0, ctx.gas_meter, vec![], true)
+				.map(|(output, _)| output)
+				.map_err(|(error, _)| error)
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let schedule = <CurrentSchedule<Test>>::get();
+			place_contract(&BOB, bob_ch);
+			place_contract(&CHARLIE, charlie_ch);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
+		});
+	}
 
+	#[test]
+	fn is_contract_returns_proper_values() {
+		let bob_ch = MockLoader::insert(Call, |ctx, _| {
+			// Verify that BOB is a contract
+			assert!(ctx.ext.is_contract(&BOB));
+			// Verify that ALICE is not a contract
+			assert!(!ctx.ext.is_contract(&ALICE));
 			exec_success()
 		});
 
 		ExtBuilder::default().build().execute_with(|| {
-			let subsistence = Contracts::<Test>::subsistence_threshold();
 			let schedule = <CurrentSchedule<Test>>::get();
-			let mut ctx = MockContext::top_level(ALICE, &schedule);
-			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
-			set_balance(&ALICE, subsistence * 100);
-			place_contract(&BOB, code_hash);
-			ctx.call(
-				BOB,
-				subsistence * 50,
-				&mut gas_meter,
-				vec![],
-			).unwrap();
+			place_contract(&BOB, bob_ch);
+
+			let mut ctx = MockContext::top_level(ALICE, &schedule, None);
+			assert_matches!(
+				ctx.call(BOB, 0, &mut GasMeter::<Test>::new(GAS_LIMIT), vec![]),
+				Ok(_)
+			);
 		});
 	}
 }
@@ -15,7 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{Config, Weight, CurrentSchedule, Pallet, Schedule};
+use crate::{Config, Weight, CurrentSchedule, ContractInfoOf, Pallet, Schedule};
 use frame_support::traits::{GetPalletVersion, PalletVersion, Get};
 
 pub fn migrate<T: Config>() -> Weight {
@@ -33,9 +33,141 @@ pub fn migrate<T: Config>() -> Weight {
 						.. Default::default()
 					})
 			});
+			weight = weight.saturating_add(v3::migrate_contract_info::<T>());
 		}
 		_ => (),
 	}
 
 	weight
 }
+
+/// Migrate away from state rent: Tombstones are dropped and the rent related fields
+/// are removed from all alive contracts.
+///
+/// Alive contracts start paying a storage deposit for the storage they already use. It is
+/// reserved from the balance of the contract itself since there is no origin that could be
+/// charged. A contract that cannot afford the whole deposit reserves what it has, and the
+/// rest of its existing storage stays without a deposit.
+mod v3 {
+	use super::*;
+	use crate::{BalanceOf, CodeHash, TrieId, storage::ContractInfo};
+	use codec::{Encode, Decode};
+	use frame_support::traits::{Currency, ReservableCurrency};
+	use sp_runtime::traits::{Saturating, Zero};
+
+	#[derive(Encode, Decode)]
+	struct OldAliveContractInfo<T: Config> {
+		trie_id: TrieId,
+		storage_size: u32,
+		pair_count: u32,
+		code_hash: CodeHash<T>,
+		rent_allowance: BalanceOf<T>,
+		rent_payed: BalanceOf<T>,
+		deduct_block: T::BlockNumber,
+		last_write: Option<T::BlockNumber>,
+		_reserved: Option<()>,
+	}
+
+	#[derive(Encode, Decode)]
+	enum OldContractInfo<T: Config> {
+		Alive(OldAliveContractInfo<T>),
+		Tombstone(T::Hash),
+	}
+
+	pub fn migrate_contract_info<T: Config>() -> Weight {
+		let mut count: Weight = 0;
+		<ContractInfoOf<T>>::translate::<OldContractInfo<T>, _>(|account, old| {
+			count = count.saturating_add(1);
+			match old {
+				OldContractInfo::Alive(old) => Some(ContractInfo::<T> {
+					trie_id: old.trie_id,
+					storage_size: old.storage_size,
+					pair_count: old.pair_count,
+					code_hash: old.code_hash,
+					storage_deposit: reserve_deposit::<T>(
+						&account,
+						old.storage_size,
+						old.pair_count,
+					),
+					_reserved: old._reserved,
+				}),
+				OldContractInfo::Tombstone(_) => None,
+			}
+		});
+		// One read and write for the contract info and its account each.
+		T::DbWeight::get().reads_writes(count.saturating_mul(2), count.saturating_mul(2))
+	}
+
+	/// Reserve the deposit for the existing storage of a contract from its own balance.
+	///
+	/// Returns the reserved amount, which is less than the deposit if the contract cannot
+	/// afford it while staying alive.
+	fn reserve_deposit<T: Config>(
+		account: &T::AccountId,
+		storage_size: u32,
+		pair_count: u32,
+	) -> BalanceOf<T> {
+		let deposit = T::DepositPerContract::get()
+			.saturating_add(T::DepositPerStorageByte::get().saturating_mul(storage_size.into()))
+			.saturating_add(T::DepositPerStorageItem::get().saturating_mul(pair_count.into()));
+		let affordable = T::Currency::free_balance(account)
+			.saturating_sub(T::Currency::minimum_balance());
+		let amount = deposit.min(affordable);
+		match T::Currency::reserve(account, amount) {
+			Ok(()) => amount,
+			Err(_) => Zero::zero(),
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::tests::{ExtBuilder, Test, BOB, CHARLIE, test_utils::{get_balance, set_balance}};
+		use frame_support::storage::unhashed;
+
+		fn put_old_contract(account: &<Test as frame_system::Config>::AccountId, size: u32) {
+			let info = OldContractInfo::<Test>::Alive(OldAliveContractInfo {
+				trie_id: account.encode(),
+				storage_size: size,
+				pair_count: 2,
+				code_hash: Default::default(),
+				rent_allowance: 0,
+				rent_payed: 0,
+				deduct_block: 0,
+				last_write: None,
+				_reserved: None,
+			});
+			unhashed::put(&<ContractInfoOf<Test>>::hashed_key_for(account), &info);
+		}
+
+		#[test]
+		fn existing_storage_is_backed_by_a_deposit() {
+			ExtBuilder::default().existential_deposit(1).build().execute_with(|| {
+				let per_byte = <Test as Config>::DepositPerStorageByte::get();
+				let per_item = <Test as Config>::DepositPerStorageItem::get();
+				let per_contract = <Test as Config>::DepositPerContract::get();
+				let deposit = per_contract + 100 * per_byte + 2 * per_item;
+				set_balance(&BOB, 1000);
+				set_balance(&CHARLIE, 50);
+				put_old_contract(&BOB, 100);
+				put_old_contract(&CHARLIE, 100);
+				PalletVersion::new(3, 0, 0).put_into_storage::<
+					<Test as frame_system::Config>::PalletInfo,
+					Pallet<Test>,
+				>();
+
+				migrate::<Test>();
+
+				// The whole deposit is reserved from a contract that can afford it.
+				assert_eq!(<ContractInfoOf<Test>>::get(&BOB).unwrap().storage_deposit, deposit);
+				assert_eq!(<Test as Config>::Currency::reserved_balance(&BOB), deposit);
+				assert_eq!(get_balance(&BOB), 1000 - deposit);
+
+				// Others reserve what they have and keep the existential deposit.
+				assert_eq!(<ContractInfoOf<Test>>::get(&CHARLIE).unwrap().storage_deposit, 49);
+				assert_eq!(<Test as Config>::Currency::reserved_balance(&CHARLIE), 49);
+				assert_eq!(get_balance(&CHARLIE), 1);
+			});
+		}
+	}
+}
@@ -216,9 +216,21 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight of calling `seal_caller`.
 	pub caller: Weight,
 
+	/// Weight of calling `seal_caller_is_origin`.
+	pub caller_is_origin: Weight,
+
+	/// Weight of calling `seal_is_contract`.
+	pub is_contract: Weight,
+
 	/// Weight of calling `seal_address`.
 	pub address: Weight,
 
+	/// Weight of calling `seal_code_hash`.
+	pub code_hash: Weight,
+
+	/// Weight of calling `seal_own_code_hash`.
+	pub own_code_hash: Weight,
+
 	/// Weight of calling `seal_gas_left`.
 	pub gas_left: Weight,
 
@@ -267,18 +279,6 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight per byte of the terminated contract.
 	pub terminate_per_code_byte: Weight,
 
-	/// Weight of calling `seal_restore_to`.
-	pub restore_to: Weight,
-
-	/// Weight per byte of the restoring contract.
-	pub restore_to_per_caller_code_byte: Weight,
-
-	/// Weight per byte of the restored contract.
-	pub restore_to_per_tombstone_code_byte: Weight,
-
-	/// Weight per delta key supplied to `seal_restore_to`.
-	pub restore_to_per_delta: Weight,
-
 	/// Weight of calling `seal_random`.
 	pub random: Weight,
 
@@ -327,6 +327,12 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight per output byte received through `seal_call`.
 	pub call_per_output_byte: Weight,
 
+	/// Weight of calling `seal_delegate_call`.
+	pub delegate_call: Weight,
+
+	/// Weight of calling `seal_set_code_hash`.
+	pub set_code_hash: Weight,
+
 	/// Weight of calling `seal_instantiate`.
 	pub instantiate: Weight,
 
@@ -366,8 +372,26 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight per byte hashed by `seal_hash_blake2_128`.
 	pub hash_blake2_128_per_byte: Weight,
 
-	/// Weight of calling `seal_rent_params`.
-	pub rent_params: Weight,
+	/// Weight of calling `seal_ecdsa_recover`.
+	pub ecdsa_recover: Weight,
+
+	/// Weight of calling `seal_ecdsa_to_eth_address`.
+	pub ecdsa_to_eth_address: Weight,
+
+	/// Weight of calling `seal_sr25519_verify`.
+	pub sr25519_verify: Weight,
+
+	/// Weight per byte of the message verified by `seal_sr25519_verify`.
+	pub sr25519_verify_per_byte: Weight,
+
+	/// Weight of calling `seal_ed25519_verify`.
+	pub ed25519_verify: Weight,
+
+	/// Weight per byte of the message verified by `seal_ed25519_verify`.
+	pub ed25519_verify_per_byte: Weight,
+
+	/// Weight of calling `seal_debug_message`.
+	pub debug_message: Weight,
 
 	/// The type parameter is used in the default implementation.
 	#[codec(skip)]
@@ -544,7 +568,11 @@ impl<T: Config> Default for HostFnWeights<T> {
 	fn default() -> Self {
 		Self {
 			caller: cost_batched!(seal_caller),
+			caller_is_origin: cost_batched!(seal_caller_is_origin),
+			is_contract: cost_batched!(seal_is_contract),
 			address: cost_batched!(seal_address),
+			code_hash: cost_batched!(seal_code_hash),
+			own_code_hash: cost_batched!(seal_own_code_hash),
 			gas_left: cost_batched!(seal_gas_left),
 			balance: cost_batched!(seal_balance),
 			value_transferred: cost_batched!(seal_value_transferred),
@@ -561,10 +589,6 @@ impl<T: Config> Default for HostFnWeights<T> {
 			return_per_byte: cost_byte!(seal_return_per_kb),
 			terminate: cost!(seal_terminate),
 			terminate_per_code_byte: cost_byte!(seal_terminate_per_code_kb),
-			restore_to: cost!(seal_restore_to),
-			restore_to_per_caller_code_byte: cost_byte_args!(seal_restore_to_per_code_kb_delta, 1, 0, 0),
-			restore_to_per_tombstone_code_byte: cost_byte_args!(seal_restore_to_per_code_kb_delta, 0, 1, 0),
-			restore_to_per_delta: cost_batched_args!(seal_restore_to_per_code_kb_delta, 0, 0, 1),
 			random: cost_batched!(seal_random),
 			deposit_event: cost_batched!(seal_deposit_event),
 			deposit_event_per_topic: cost_batched_args!(seal_deposit_event_per_topic_and_kb, 1, 0),
@@ -581,6 +605,8 @@ impl<T: Config> Default for HostFnWeights<T> {
 			call_transfer_surcharge: cost_batched_args!(seal_call_per_code_transfer_input_output_kb, 0, 1, 0, 0),
 			call_per_input_byte: cost_byte_batched_args!(seal_call_per_code_transfer_input_output_kb, 0, 0, 1, 0),
 			call_per_output_byte: cost_byte_batched_args!(seal_call_per_code_transfer_input_output_kb, 0, 0, 0, 1),
+			delegate_call: cost_batched!(seal_delegate_call),
+			set_code_hash: cost_batched!(seal_set_code_hash),
 			instantiate: cost_batched!(seal_instantiate),
 			instantiate_per_code_byte: cost_byte_batched_args!(seal_instantiate_per_code_input_output_salt_kb, 1, 0, 0, 0),
 			instantiate_per_input_byte: cost_byte_batched_args!(seal_instantiate_per_code_input_output_salt_kb, 0, 1, 0, 0),
@@ -594,7 +620,13 @@ impl<T: Config> Default for HostFnWeights<T> {
 			hash_blake2_256_per_byte: cost_byte_batched!(seal_hash_blake2_256_per_kb),
 			hash_blake2_128: cost_batched!(seal_hash_blake2_128),
 			hash_blake2_128_per_byte: cost_byte_batched!(seal_hash_blake2_128_per_kb),
-			rent_params: cost_batched!(seal_rent_params),
+			ecdsa_recover: cost_batched!(seal_ecdsa_recover),
+			ecdsa_to_eth_address: cost_batched!(seal_ecdsa_to_eth_address),
+			sr25519_verify: cost_batched!(seal_sr25519_verify),
+			sr25519_verify_per_byte: cost_byte_batched!(seal_sr25519_verify_per_kb),
+			ed25519_verify: cost_batched!(seal_ed25519_verify),
+			ed25519_verify_per_byte: cost_byte_batched!(seal_ed25519_verify_per_kb),
+			debug_message: cost_batched!(seal_debug_message),
 			_phantom: PhantomData,
 		}
 	}
@@ -0,0 +1,232 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module contains functions to meter the storage deposit.
+
+use crate::{
+	storage::ContractInfo, BalanceOf, Config, ContractInfoOf, Error,
+};
+use pallet_contracts_primitives::StorageDeposit;
+use sp_std::prelude::*;
+use sp_runtime::traits::{Saturating, Zero};
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
+};
+
+/// Deposit that uses the native currency's balance type.
+pub type DepositOf<T> = StorageDeposit<BalanceOf<T>>;
+
+/// Changes to a contract's storage that were made by a single storage write.
+///
+/// Multiple diffs are accumulated into one by [`Meter::record`] so that bytes or items
+/// that are added and removed within the same call frame cancel each other out.
+#[derive(Default)]
+pub struct Diff {
+	/// How many bytes were added to storage.
+	pub bytes_added: u32,
+	/// How many bytes were removed from storage.
+	pub bytes_removed: u32,
+	/// How many storage items were added to storage.
+	pub items_added: u32,
+	/// How many storage items were removed from storage.
+	pub items_removed: u32,
+}
+
+impl Diff {
+	/// Calculate how much of a charge or refund results from applying the diff.
+	pub fn to_deposit<T: Config>(&self) -> DepositOf<T> {
+		let per_byte = T::DepositPerStorageByte::get();
+		let per_item = T::DepositPerStorageItem::get();
+		let added = per_byte.saturating_mul(self.bytes_added.into())
+			.saturating_add(per_item.saturating_mul(self.items_added.into()));
+		let removed = per_byte.saturating_mul(self.bytes_removed.into())
+			.saturating_add(per_item.saturating_mul(self.items_removed.into()));
+		StorageDeposit::Charge(added).saturating_sub(&StorageDeposit::Charge(removed))
+	}
+
+	fn saturating_add(&self, rhs: &Self) -> Self {
+		Self {
+			bytes_added: self.bytes_added.saturating_add(rhs.bytes_added),
+			bytes_removed: self.bytes_removed.saturating_add(rhs.bytes_removed),
+			items_added: self.items_added.saturating_add(rhs.items_added),
+			items_removed: self.items_removed.saturating_add(rhs.items_removed),
+		}
+	}
+}
+
+/// A deposit that needs to be charged from or refunded to the origin for a specific contract.
+struct Charge<T: Config> {
+	contract: T::AccountId,
+	amount: DepositOf<T>,
+}
+
+/// Keeps track of the storage deposit of a single call frame.
+///
+/// Every execution context owns a meter. Storage changes of the executing contract are
+/// recorded as a [`Diff`] and converted into a deposit once the frame finishes. When a
+/// frame commits, its meter is absorbed by the meter of the calling frame. This means that
+/// reverted frames are never charged for. The accumulated deposits are then settled with the
+/// origin when the outermost contract call finishes.
+pub struct Meter<T: Config> {
+	/// The storage changes of the contract executing in this frame.
+	own_diff: Diff,
+	/// The deposit that is charged for creating the contract of this frame (if any).
+	contract_deposit: BalanceOf<T>,
+	/// Set when the contract of this frame terminated itself.
+	terminated: bool,
+	/// The deposit that was already refunded to the origin by terminated contracts.
+	refunded: BalanceOf<T>,
+	/// The deposits of this and all committed nested frames in chronological order.
+	charges: Vec<Charge<T>>,
+}
+
+impl<T: Config> Default for Meter<T> {
+	fn default() -> Self {
+		Self {
+			own_diff: Default::default(),
+			contract_deposit: Zero::zero(),
+			terminated: false,
+			refunded: Zero::zero(),
+			charges: Vec::new(),
+		}
+	}
+}
+
+impl<T: Config> Meter<T> {
+	/// Record a storage change of the contract executing in this frame.
+	pub fn record(&mut self, diff: &Diff) {
+		self.own_diff = self.own_diff.saturating_add(diff);
+	}
+
+	/// Charge the base deposit for the contract that is instantiated in this frame.
+	pub fn charge_instantiate(&mut self) {
+		self.contract_deposit = T::DepositPerContract::get();
+	}
+
+	/// The contract of this frame terminated itself.
+	///
+	/// All of its storage deposit is refunded to `origin` right away: The contract account
+	/// is about to be removed and could not pay out the refund when the call is settled.
+	/// No further changes are recorded for this frame because all of its storage is removed.
+	pub fn terminate(
+		&mut self,
+		origin: &T::AccountId,
+		contract: &T::AccountId,
+		info: &ContractInfo<T>,
+	) {
+		let amount = info.storage_deposit
+			.saturating_sub(T::Currency::unreserve(contract, info.storage_deposit));
+		// The deposit was reserved on the contract and therefore this transfer cannot fail.
+		let _ = T::Currency::transfer(contract, origin, amount, ExistenceRequirement::AllowDeath);
+		self.refunded = self.refunded.saturating_add(amount);
+		self.terminated = true;
+	}
+
+	/// Convert the recorded storage changes of `contract` into a deposit.
+	///
+	/// This is called when the frame of `contract` returns successfully. The storage deposit
+	/// that is recorded on the contract is updated accordingly. A refund can never be larger
+	/// than what the contract has deposited.
+	pub fn finish(&mut self, contract: &T::AccountId) {
+		let diff = sp_std::mem::take(&mut self.own_diff);
+		let contract_deposit = sp_std::mem::replace(&mut self.contract_deposit, Zero::zero());
+		if self.terminated {
+			return;
+		}
+		let mut deposit = diff.to_deposit::<T>()
+			.saturating_add(&StorageDeposit::Charge(contract_deposit));
+		<ContractInfoOf<T>>::mutate(contract, |info| if let Some(info) = info {
+			match deposit {
+				StorageDeposit::Charge(amount) => {
+					info.storage_deposit = info.storage_deposit.saturating_add(amount);
+				},
+				StorageDeposit::Refund(amount) => {
+					let amount = amount.min(info.storage_deposit);
+					info.storage_deposit = info.storage_deposit.saturating_sub(amount);
+					deposit = StorageDeposit::Refund(amount);
+				},
+			}
+		});
+		if !deposit.is_zero() {
+			self.charges.push(Charge { contract: contract.clone(), amount: deposit });
+		}
+	}
+
+	/// Take over the charges of a nested frame that was committed.
+	pub fn absorb(&mut self, other: Self) {
+		self.refunded = self.refunded.saturating_add(other.refunded);
+		self.charges.extend(other.charges);
+	}
+
+	/// The sum of all charges and refunds collected by this meter.
+	pub fn total_deposit(&self) -> DepositOf<T> {
+		self.charges.iter()
+			.fold(DepositOf::<T>::default(), |sum, charge| sum.saturating_add(&charge.amount))
+			.saturating_sub(&StorageDeposit::Charge(self.refunded))
+	}
+
+	/// Transfer the collected deposits between `origin` and the affected contracts.
+	///
+	/// Fails if the total deposit exceeds `limit` or if `origin` has not enough funds
+	/// to pay for it. The deposit is reserved on the contract account that it pays for.
+	///
+	/// All refunds are paid out before any charge is transferred. This way the amount taken
+	/// from `origin` only grows while the charges are transferred and never exceeds the total
+	/// deposit, which is checked against `limit` up front.
+	pub fn settle(&self, origin: &T::AccountId, limit: Option<BalanceOf<T>>) -> DispatchResult {
+		if let Some(limit) = limit {
+			if self.total_deposit().charge_or_zero() > limit {
+				return Err(<Error<T>>::StorageDepositLimitExhausted.into());
+			}
+		}
+		for charge in &self.charges {
+			if let StorageDeposit::Refund(amount) = charge.amount {
+				if amount.is_zero() {
+					continue;
+				}
+				let amount = amount.saturating_sub(
+					T::Currency::unreserve(&charge.contract, amount)
+				);
+				// The deposit was reserved on the contract and therefore this transfer
+				// cannot fail. Still, we do not want to fail the whole call if it does.
+				let _ = T::Currency::transfer(
+					&charge.contract,
+					origin,
+					amount,
+					ExistenceRequirement::AllowDeath,
+				);
+			}
+		}
+		for charge in &self.charges {
+			if let StorageDeposit::Charge(amount) = charge.amount {
+				if amount.is_zero() {
+					continue;
+				}
+				T::Currency::transfer(
+					origin,
+					&charge.contract,
+					amount,
+					ExistenceRequirement::KeepAlive,
+				)
+				.and_then(|_| T::Currency::reserve(&charge.contract, amount))
+				.map_err(|_| <Error<T>>::StorageDepositNotEnoughFunds)?;
+			}
+		}
+		Ok(())
+	}
+}
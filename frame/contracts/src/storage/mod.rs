@@ -17,19 +17,21 @@
 
 //! This module contains routines for accessing and altering a contract related state.
 
+pub mod meter;
+
 use crate::{
 	exec::{AccountIdOf, StorageKey},
 	BalanceOf, CodeHash, ContractInfoOf, Config, TrieId,
 	AccountCounter, DeletionQueue, Error,
 	weights::WeightInfo,
 };
-use codec::{Codec, Encode, Decode};
+use codec::{Encode, Decode};
 use sp_std::prelude::*;
-use sp_std::{marker::PhantomData, fmt::Debug};
+use sp_std::marker::PhantomData;
 use sp_io::hashing::blake2_256;
 use sp_runtime::{
 	RuntimeDebug,
-	traits::{Bounded, Saturating, Zero, Hash, Member, MaybeSerializeDeserialize},
+	traits::{Zero, Hash},
 };
 use sp_core::crypto::UncheckedFrom;
 use frame_support::{
@@ -38,52 +40,14 @@ use frame_support::{
 	traits::Get,
 	weights::Weight,
 };
+use self::meter::{Diff, Meter};
 
-pub type AliveContractInfo<T> =
-	RawAliveContractInfo<CodeHash<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
-pub type TombstoneContractInfo<T> =
-	RawTombstoneContractInfo<<T as frame_system::Config>::Hash, <T as frame_system::Config>::Hashing>;
-
-/// Information for managing an account and its sub trie abstraction.
-/// This is the required info to cache for an account
-#[derive(Encode, Decode, RuntimeDebug)]
-pub enum ContractInfo<T: Config> {
-	Alive(AliveContractInfo<T>),
-	Tombstone(TombstoneContractInfo<T>),
-}
-
-impl<T: Config> ContractInfo<T> {
-	/// If contract is alive then return some alive info
-	pub fn get_alive(self) -> Option<AliveContractInfo<T>> {
-		if let ContractInfo::Alive(alive) = self {
-			Some(alive)
-		} else {
-			None
-		}
-	}
-	/// If contract is alive then return some reference to alive info
-	pub fn as_alive(&self) -> Option<&AliveContractInfo<T>> {
-		if let ContractInfo::Alive(ref alive) = self {
-			Some(alive)
-		} else {
-			None
-		}
-	}
-
-	/// If contract is tombstone then return some tombstone info
-	pub fn get_tombstone(self) -> Option<TombstoneContractInfo<T>> {
-		if let ContractInfo::Tombstone(tombstone) = self {
-			Some(tombstone)
-		} else {
-			None
-		}
-	}
-}
+pub type ContractInfo<T> = RawContractInfo<CodeHash<T>, BalanceOf<T>>;
 
 /// Information for managing an account and its sub trie abstraction.
 /// This is the required info to cache for an account.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
-pub struct RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
+pub struct RawContractInfo<CodeHash, Balance> {
 	/// Unique ID for the subtree encoded as a bytes vector.
 	pub trie_id: TrieId,
 	/// The total number of bytes used by this contract.
@@ -94,21 +58,17 @@ pub struct RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
 	pub pair_count: u32,
 	/// The code associated with a given account.
 	pub code_hash: CodeHash,
-	/// Pay rent at most up to this value.
-	pub rent_allowance: Balance,
-	/// The amount of rent that was payed by the contract over its whole lifetime.
+	/// The amount of balance that is currently deposited to pay for the storage of this
+	/// contract.
 	///
-	/// A restored contract starts with a value of zero just like a new contract.
-	pub rent_payed: Balance,
-	/// Last block rent has been payed.
-	pub deduct_block: BlockNumber,
-	/// Last block child storage has been written.
-	pub last_write: Option<BlockNumber>,
+	/// This balance is reserved on the contract account and refunded to whoever removes
+	/// the storage it pays for.
+	pub storage_deposit: Balance,
 	/// This field is reserved for future evolution of format.
 	pub _reserved: Option<()>,
 }
 
-impl<CodeHash, Balance, BlockNumber> RawAliveContractInfo<CodeHash, Balance, BlockNumber> {
+impl<CodeHash, Balance> RawContractInfo<CodeHash, Balance> {
 	/// Associated child trie unique id is built from the hash part of the trie id.
 	pub fn child_trie_info(&self) -> ChildInfo {
 		child_trie_info(&self.trie_id[..])
@@ -120,32 +80,7 @@ fn child_trie_info(trie_id: &[u8]) -> ChildInfo {
 	ChildInfo::new_default(trie_id)
 }
 
-#[derive(Encode, Decode, PartialEq, Eq, RuntimeDebug)]
-pub struct RawTombstoneContractInfo<H, Hasher>(H, PhantomData<Hasher>);
-
-impl<H, Hasher> RawTombstoneContractInfo<H, Hasher>
-where
-	H: Member + MaybeSerializeDeserialize+ Debug
-		+ AsRef<[u8]> + AsMut<[u8]> + Copy + Default
-		+ sp_std::hash::Hash + Codec,
-	Hasher: Hash<Output=H>,
-{
-	pub fn new(storage_root: &[u8], code_hash: H) -> Self {
-		let mut buf = Vec::new();
-		storage_root.using_encoded(|encoded| buf.extend_from_slice(encoded));
-		buf.extend_from_slice(code_hash.as_ref());
-		RawTombstoneContractInfo(<Hasher as Hash>::hash(&buf[..]), PhantomData)
-	}
-}
-
-impl<T: Config> From<AliveContractInfo<T>> for ContractInfo<T> {
-	fn from(alive_info: AliveContractInfo<T>) -> Self {
-		Self::Alive(alive_info)
-	}
-}
-
-/// An error that means that the account requested either doesn't exist or represents a tombstone
-/// account.
+/// An error that means that the account requested doesn't exist.
 #[cfg_attr(test, derive(PartialEq, Eq, Debug))]
 pub struct ContractAbsentError;
 
@@ -175,23 +110,25 @@ where
 	/// If the `opt_new_value` is `None` then the kv pair is removed.
 	///
 	/// This function also updates the bookkeeping info such as: number of total non-empty pairs a
-	/// contract owns, the last block the storage was written to, etc. That's why, in contrast to
+	/// contract owns, the total number of bytes it stores, etc. That's why, in contrast to
 	/// `read`, this function also requires the `account` ID.
 	///
-	/// If the contract specified by the id `account` doesn't exist `Err` is returned.`
+	/// The change in storage is recorded in the passed `storage_meter` so that a deposit
+	/// can be charged or refunded for it once the current call frame finishes.
 	///
 	/// # Panics
 	///
-	/// Panics iff the `account` specified is not alive and in storage.
+	/// Panics iff the `account` specified is not a contract in storage.
 	pub fn write(
 		account: &AccountIdOf<T>,
 		trie_id: &TrieId,
 		key: &StorageKey,
 		opt_new_value: Option<Vec<u8>>,
+		storage_meter: Option<&mut Meter<T>>,
 	) -> DispatchResult {
 		let mut new_info = match <ContractInfoOf<T>>::get(account) {
-			Some(ContractInfo::Alive(alive)) => alive,
-			None | Some(ContractInfo::Tombstone(_)) => panic!("Contract not found"),
+			Some(info) => info,
+			None => panic!("Contract not found"),
 		};
 
 		let hashed_key = blake2_256(key);
@@ -199,15 +136,19 @@ where
 
 		let opt_prev_len = child::len(&child_trie_info, &hashed_key);
 
+		let mut diff = Diff::default();
+
 		// Update the total number of KV pairs and the number of empty pairs.
 		match (&opt_prev_len, &opt_new_value) {
 			(Some(_), None) => {
 				new_info.pair_count = new_info.pair_count.checked_sub(1)
 					.ok_or_else(|| Error::<T>::StorageExhausted)?;
+				diff.items_removed = 1;
 			},
 			(None, Some(_)) => {
 				new_info.pair_count = new_info.pair_count.checked_add(1)
 					.ok_or_else(|| Error::<T>::StorageExhausted)?;
+				diff.items_added = 1;
 			},
 			(Some(_), Some(_)) => {},
 			(None, None) => {},
@@ -225,8 +166,16 @@ where
 			.and_then(|val| val.checked_add(new_value_len))
 			.ok_or_else(|| Error::<T>::StorageExhausted)?;
 
-		new_info.last_write = Some(<frame_system::Pallet<T>>::block_number());
-		<ContractInfoOf<T>>::insert(&account, ContractInfo::Alive(new_info));
+		if new_value_len > prev_value_len {
+			diff.bytes_added = new_value_len - prev_value_len;
+		} else {
+			diff.bytes_removed = prev_value_len - new_value_len;
+		}
+		if let Some(storage_meter) = storage_meter {
+			storage_meter.record(&diff);
+		}
+
+		<ContractInfoOf<T>>::insert(&account, new_info);
 
 		// Finally, perform the change on the storage.
 		match opt_new_value {
@@ -237,62 +186,29 @@ where
 		Ok(())
 	}
 
-	/// Returns the rent allowance set for the contract give by the account id.
-	pub fn rent_allowance(
-		account: &AccountIdOf<T>,
-	) -> Result<BalanceOf<T>, ContractAbsentError>
-	{
-		<ContractInfoOf<T>>::get(account)
-			.and_then(|i| i.as_alive().map(|i| i.rent_allowance))
-			.ok_or(ContractAbsentError)
-	}
-
-	/// Set the rent allowance for the contract given by the account id.
-	///
-	/// Returns `Err` if the contract doesn't exist or is a tombstone.
-	pub fn set_rent_allowance(
-		account: &AccountIdOf<T>,
-		rent_allowance: BalanceOf<T>,
-	) -> Result<(), ContractAbsentError> {
-		<ContractInfoOf<T>>::mutate(account, |maybe_contract_info| match maybe_contract_info {
-			Some(ContractInfo::Alive(ref mut alive_info)) => {
-				alive_info.rent_allowance = rent_allowance;
-				Ok(())
-			}
-			_ => Err(ContractAbsentError),
-		})
-	}
-
 	/// Creates a new contract descriptor in the storage with the given code hash at the given address.
 	///
-	/// Returns `Err` if there is already a contract (or a tombstone) exists at the given address.
+	/// Returns `Err` if there is already a contract at the given address.
 	pub fn place_contract(
 		account: &AccountIdOf<T>,
 		trie_id: TrieId,
 		ch: CodeHash<T>,
-	) -> Result<AliveContractInfo<T>, DispatchError> {
+	) -> Result<ContractInfo<T>, DispatchError> {
 		<ContractInfoOf<T>>::try_mutate(account, |existing| {
 			if existing.is_some() {
 				return Err(Error::<T>::DuplicateContract.into());
 			}
 
-			let contract = AliveContractInfo::<T> {
+			let contract = ContractInfo::<T> {
 				code_hash: ch,
 				storage_size: 0,
 				trie_id,
-				deduct_block:
-					// We want to charge rent for the first block in advance. Therefore we
-					// treat the contract as if it was created in the last block and then
-					// charge rent for it during instantiation.
-					<frame_system::Pallet<T>>::block_number().saturating_sub(1u32.into()),
-				rent_allowance: <BalanceOf<T>>::max_value(),
-				rent_payed: <BalanceOf<T>>::zero(),
+				storage_deposit: <BalanceOf<T>>::zero(),
 				pair_count: 0,
-				last_write: None,
 				_reserved: None,
 			};
 
-			*existing = Some(contract.clone().into());
+			*existing = Some(contract.clone());
 
 			Ok(contract)
 		})
@@ -300,9 +216,8 @@ where
 
 	/// Push a contract's trie to the deletion queue for lazy removal.
 	///
-	/// You must make sure that the contract is also removed or converted into a tombstone
-	/// when queuing the trie for deletion.
-	pub fn queue_trie_for_deletion(contract: &AliveContractInfo<T>) -> DispatchResult {
+	/// You must make sure that the contract is also removed when queuing the trie for deletion.
+	pub fn queue_trie_for_deletion(contract: &ContractInfo<T>) -> DispatchResult {
 		if <DeletionQueue<T>>::decode_len().unwrap_or(0) >= T::DeletionQueueDepth::get() as usize {
 			Err(Error::<T>::DeletionQueueFull.into())
 		} else {
@@ -417,7 +332,7 @@ where
 	pub fn code_hash(account: &AccountIdOf<T>) -> Result<CodeHash<T>, ContractAbsentError>
 	{
 		<ContractInfoOf<T>>::get(account)
-			.and_then(|i| i.as_alive().map(|i| i.code_hash))
+			.map(|i| i.code_hash)
 			.ok_or(ContractAbsentError)
 	}
 
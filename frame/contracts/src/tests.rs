@@ -16,7 +16,7 @@
 // limitations under the License.
 
 use crate::{
-	BalanceOf, ContractInfo, ContractInfoOf, Pallet,
+	BalanceOf, ContractInfoOf, CodeStorage, OwnerInfoOf, PristineCode, Pallet,
 	Config, Schedule,
 	Error, storage::Storage,
 	chain_extension::{
@@ -25,8 +25,8 @@ use crate::{
 	},
 	exec::{AccountIdOf, Executable}, wasm::PrefabWasmModule,
 	weights::WeightInfo,
-	wasm::ReturnCode as RuntimeReturnCode,
-	storage::RawAliveContractInfo,
+	wasm::{CallFlags, ReturnCode as RuntimeReturnCode},
+	storage::RawContractInfo,
 };
 use assert_matches::assert_matches;
 use codec::Encode;
@@ -34,19 +34,22 @@ use sp_core::Bytes;
 use sp_runtime::{
 	traits::{BlakeTwo256, Hash, IdentityLookup, Convert},
 	testing::{Header, H256},
-	AccountId32, Perbill,
+	AccountId32,
 };
 use sp_io::hashing::blake2_256;
+use hex_literal::hex;
 use frame_support::{
-	assert_ok, assert_err, assert_err_ignore_postinfo,
-	parameter_types, assert_storage_noop,
+	assert_ok, assert_noop, assert_err_ignore_postinfo, parameter_types,
 	traits::{Currency, ReservableCurrency, OnInitialize, GenesisBuild},
 	weights::{Weight, PostDispatchInfo, DispatchClass, constants::WEIGHT_PER_SECOND},
 	dispatch::DispatchErrorWithPostInfo,
 	storage::child,
 };
 use frame_system::{self as system, EventRecord, Phase};
+use pallet_contracts_primitives::StorageDeposit;
 use pretty_assertions::assert_eq;
+use sp_keystore::{testing::KeyStore, KeystoreExt};
+use std::sync::Arc;
 
 use crate as pallet_contracts;
 
@@ -71,24 +74,23 @@ frame_support::construct_runtime!(
 pub mod test_utils {
 	use super::{Test, Balances};
 	use crate::{
-		ContractInfoOf, CodeHash,
+		ContractInfoOf, CodeHash, Config,
 		storage::Storage,
 		exec::{StorageKey, AccountIdOf},
-		Pallet as Contracts,
 	};
 	use frame_support::traits::Currency;
 
 	pub fn set_storage(addr: &AccountIdOf<Test>, key: &StorageKey, value: Option<Vec<u8>>) {
-		let contract_info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
-		Storage::<Test>::write(addr, &contract_info.trie_id, key, value).unwrap();
+		let contract_info = <ContractInfoOf::<Test>>::get(&addr).unwrap();
+		Storage::<Test>::write(addr, &contract_info.trie_id, key, value, None).unwrap();
 	}
 	pub fn get_storage(addr: &AccountIdOf<Test>, key: &StorageKey) -> Option<Vec<u8>> {
-		let contract_info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
+		let contract_info = <ContractInfoOf::<Test>>::get(&addr).unwrap();
 		Storage::<Test>::read(&contract_info.trie_id, key)
 	}
 	pub fn place_contract(address: &AccountIdOf<Test>, code_hash: CodeHash<Test>) {
 		let trie_id = Storage::<Test>::generate_trie_id(address);
-		set_balance(address, Contracts::<Test>::subsistence_threshold() * 10);
+		set_balance(address, <Test as Config>::Currency::minimum_balance() * 10);
 		Storage::<Test>::place_contract(&address, trie_id, code_hash).unwrap();
 	}
 	pub fn set_balance(who: &AccountIdOf<Test>, amount: u64) {
@@ -244,18 +246,15 @@ impl pallet_timestamp::Config for Test {
 	type WeightInfo = ();
 }
 parameter_types! {
-	pub const SignedClaimHandicap: u64 = 2;
-	pub const TombstoneDeposit: u64 = 16;
-	pub const DepositPerContract: u64 = 8 * DepositPerStorageByte::get();
-	pub const DepositPerStorageByte: u64 = 10_000;
-	pub const DepositPerStorageItem: u64 = 10_000;
-	pub RentFraction: Perbill = Perbill::from_rational(4u32, 10_000u32);
-	pub const SurchargeReward: u64 = 500_000;
+	pub const DepositPerContract: u64 = 16;
+	pub const DepositPerStorageByte: u64 = 1;
+	pub const DepositPerStorageItem: u64 = 2;
 	pub const MaxDepth: u32 = 100;
 	pub const MaxValueSize: u32 = 16_384;
 	pub const DeletionQueueDepth: u32 = 1024;
 	pub const DeletionWeightLimit: Weight = 500_000_000_000;
 	pub const MaxCodeSize: u32 = 2 * 1024;
+	pub const MaxDebugBufferLen: u32 = 128;
 }
 
 parameter_types! {
@@ -273,14 +272,9 @@ impl Config for Test {
 	type Randomness = Randomness;
 	type Currency = Balances;
 	type Event = Event;
-	type RentPayment = ();
-	type SignedClaimHandicap = SignedClaimHandicap;
-	type TombstoneDeposit = TombstoneDeposit;
 	type DepositPerContract = DepositPerContract;
 	type DepositPerStorageByte = DepositPerStorageByte;
 	type DepositPerStorageItem = DepositPerStorageItem;
-	type RentFraction = RentFraction;
-	type SurchargeReward = SurchargeReward;
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
 	type WeightPrice = Self;
@@ -289,6 +283,7 @@ impl Config for Test {
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
 	type MaxCodeSize = MaxCodeSize;
+	type MaxDebugBufferLen = MaxDebugBufferLen;
 }
 
 pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
@@ -329,6 +324,7 @@ impl ExtBuilder {
 			},
 		}.assimilate_storage(&mut t).unwrap();
 		let mut ext = sp_io::TestExternalities::new(t);
+		ext.register_extension(KeystoreExt(Arc::new(KeyStore::new())));
 		ext.execute_with(|| System::set_block_number(1));
 		ext
 	}
@@ -360,7 +356,7 @@ fn calling_plain_account_fails() {
 		let base_cost = <<Test as Config>::WeightInfo as WeightInfo>::call(0);
 
 		assert_eq!(
-			Contracts::call(Origin::signed(ALICE), BOB, 0, GAS_LIMIT, Vec::new()),
+			Contracts::call(Origin::signed(ALICE), BOB, 0, GAS_LIMIT, None, Vec::new()),
 			Err(
 				DispatchErrorWithPostInfo {
 					error: Error::<Test>::NotCallable.into(),
@@ -386,33 +382,27 @@ fn account_removal_does_not_remove_storage() {
 
 		// Set up two accounts with free balance above the existential threshold.
 		{
-			let alice_contract_info = ContractInfo::Alive(RawAliveContractInfo {
+			let alice_contract_info = RawContractInfo {
 				trie_id: trie_id1.clone(),
 				storage_size: 0,
 				pair_count: 0,
-				deduct_block: System::block_number(),
 				code_hash: H256::repeat_byte(1),
-				rent_allowance: 40,
-				rent_payed: 0,
-				last_write: None,
+				storage_deposit: 0,
 				_reserved: None,
-			});
+			};
 			let _ = Balances::deposit_creating(&ALICE, 110);
 			ContractInfoOf::<Test>::insert(ALICE, &alice_contract_info);
 			set_storage(&ALICE, &key1, Some(b"1".to_vec()));
 			set_storage(&ALICE, &key2, Some(b"2".to_vec()));
 
-			let bob_contract_info = ContractInfo::Alive(RawAliveContractInfo {
+			let bob_contract_info = RawContractInfo {
 				trie_id: trie_id2.clone(),
 				storage_size: 0,
 				pair_count: 0,
-				deduct_block: System::block_number(),
 				code_hash: H256::repeat_byte(2),
-				rent_allowance: 40,
-				rent_payed: 0,
-				last_write: None,
+				storage_deposit: 0,
 				_reserved: None,
-			});
+			};
 			let _ = Balances::deposit_creating(&BOB, 110);
 			ContractInfoOf::<Test>::insert(BOB, &bob_contract_info);
 			set_storage(&BOB, &key1, Some(b"3".to_vec()));
@@ -460,13 +450,14 @@ fn instantiate_and_call_and_deposit_event() {
 		.build()
 		.execute_with(|| {
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			let subsistence = Pallet::<Test>::subsistence_threshold();
+			let min_balance = <Test as Config>::Currency::minimum_balance();
 
 			// Check at the end to get hash on error easily
 			let creation = Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -494,14 +485,14 @@ fn instantiate_and_call_and_deposit_event() {
 				EventRecord {
 					phase: Phase::Initialization,
 					event: Event::pallet_balances(
-						pallet_balances::Event::Endowed(addr.clone(), subsistence * 100)
+						pallet_balances::Event::Endowed(addr.clone(), min_balance * 100)
 					),
 					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Initialization,
 					event: Event::pallet_balances(
-						pallet_balances::Event::Transfer(ALICE, addr.clone(), subsistence * 100)
+						pallet_balances::Event::Transfer(ALICE, addr.clone(), min_balance * 100)
 					),
 					topics: vec![],
 				},
@@ -522,6 +513,27 @@ fn instantiate_and_call_and_deposit_event() {
 					event: Event::pallet_contracts(crate::Event::Instantiated(ALICE, addr.clone())),
 					topics: vec![],
 				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: Event::pallet_balances(
+						pallet_balances::Event::Transfer(
+							ALICE,
+							addr.clone(),
+							<Test as Config>::DepositPerContract::get(),
+						)
+					),
+					topics: vec![],
+				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: Event::pallet_balances(
+						pallet_balances::Event::Reserved(
+							addr.clone(),
+							<Test as Config>::DepositPerContract::get(),
+						)
+					),
+					topics: vec![],
+				},
 			]);
 
 			assert_ok!(creation);
@@ -543,6 +555,7 @@ fn deposit_event_max_value_limit() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -555,6 +568,7 @@ fn deposit_event_max_value_limit() {
 				addr.clone(),
 				0,
 				GAS_LIMIT * 2, // we are copying a huge buffer,
+				None,
 				<Test as Config>::MaxValueSize::get().encode(),
 			));
 
@@ -565,6 +579,7 @@ fn deposit_event_max_value_limit() {
 					addr,
 					0,
 					GAS_LIMIT,
+					None,
 					(<Test as Config>::MaxValueSize::get() + 1).encode(),
 				),
 				Error::<Test>::ValueTooLarge,
@@ -575,7 +590,7 @@ fn deposit_event_max_value_limit() {
 #[test]
 fn run_out_of_gas() {
 	let (wasm, code_hash) = compile_module::<Test>("run_out_of_gas").unwrap();
-	let subsistence = Pallet::<Test>::subsistence_threshold();
+	let min_balance = <Test as Config>::Currency::minimum_balance();
 
 	ExtBuilder::default()
 		.existential_deposit(50)
@@ -585,8 +600,9 @@ fn run_out_of_gas() {
 
 			assert_ok!(Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				100 * subsistence,
+				100 * min_balance,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -601,6 +617,7 @@ fn run_out_of_gas() {
 					addr, // newly created account
 					0,
 					67_500_000,
+					None,
 					vec![],
 				),
 				Error::<Test>::OutOfGas,
@@ -635,16 +652,13 @@ fn storage_size() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
-				// rent_allowance
 				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
 				vec![],
 			));
 			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-			let bob_contract = ContractInfoOf::<Test>::get(&addr)
-				.unwrap()
-				.get_alive()
-				.unwrap();
+			let bob_contract = ContractInfoOf::<Test>::get(&addr).unwrap();
 			assert_eq!(
 				bob_contract.storage_size,
 				4
@@ -659,12 +673,10 @@ fn storage_size() {
 				addr.clone(),
 				0,
 				GAS_LIMIT,
+				None,
 				call::set_storage_4_byte()
 			));
-			let bob_contract = ContractInfoOf::<Test>::get(&addr)
-				.unwrap()
-				.get_alive()
-				.unwrap();
+			let bob_contract = ContractInfoOf::<Test>::get(&addr).unwrap();
 			assert_eq!(
 				bob_contract.storage_size,
 				4 + 4
@@ -679,12 +691,10 @@ fn storage_size() {
 				addr.clone(),
 				0,
 				GAS_LIMIT,
+				None,
 				call::remove_storage_4_byte()
 			));
-			let bob_contract = ContractInfoOf::<Test>::get(&addr)
-				.unwrap()
-				.get_alive()
-				.unwrap();
+			let bob_contract = ContractInfoOf::<Test>::get(&addr).unwrap();
 			assert_eq!(
 				bob_contract.storage_size,
 				4
@@ -708,15 +718,13 @@ fn empty_kv_pairs() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
 			));
 			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-			let bob_contract = ContractInfoOf::<Test>::get(&addr)
-				.unwrap()
-				.get_alive()
-				.unwrap();
+			let bob_contract = ContractInfoOf::<Test>::get(&addr).unwrap();
 
 			assert_eq!(
 				bob_contract.storage_size,
@@ -729,382 +737,234 @@ fn empty_kv_pairs() {
 		});
 }
 
-fn initialize_block(number: u64) {
-	System::initialize(
-		&number,
-		&[0u8; 32].into(),
-		&Default::default(),
-		Default::default(),
-	);
-}
-
 #[test]
-fn deduct_blocks() {
+fn storage_deposit_works() {
 	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
-	let endowment: BalanceOf<Test> = 100_000;
-	let allowance: BalanceOf<Test> = 70_000;
 
 	ExtBuilder::default()
 		.existential_deposit(50)
 		.build()
 		.execute_with(|| {
-			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 			assert_ok!(Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				endowment,
+				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
-				allowance.encode(),
+				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
 				vec![],
 			));
 			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
-			let code_len: BalanceOf<Test> =
-				PrefabWasmModule::<Test>::from_storage_noinstr(contract.code_hash)
-					.unwrap()
-					.occupied_storage()
-					.into();
-
-			// The instantiation deducted the rent for one block immediately
-			let rent0 = <Test as Config>::RentFraction::get()
-				// (base_deposit(8) + bytes in storage(4) + size of code) * byte_price
-				// + 1 storage item (10_000) - free_balance
-				.mul_ceil((8 + 4 + code_len) * 10_000 + 10_000 - endowment)
-				// blocks to rent
-				* 1;
-			assert!(rent0 > 0);
-			assert_eq!(contract.rent_allowance, allowance - rent0);
-			assert_eq!(contract.deduct_block, 1);
-			assert_eq!(Balances::free_balance(&addr), endowment - rent0);
-
-			// Advance 4 blocks
-			initialize_block(5);
-
-			// Trigger rent through call
-			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
-			);
 
-			// Check result
-			let rent = <Test as Config>::RentFraction::get()
-				.mul_ceil((8 + 4 + code_len) * 10_000 + 10_000 - (endowment - rent0))
-				* 4;
-			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
-			assert_eq!(contract.rent_allowance, allowance - rent0 - rent);
-			assert_eq!(contract.deduct_block, 5);
-			assert_eq!(Balances::free_balance(&addr), endowment - rent0 - rent);
-
-			// Advance 2 blocks more
-			initialize_block(7);
-
-			// Trigger rent through call
-			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+			// The constructor stores 4 bytes in a single item.
+			let item_deposit = 4 * DepositPerStorageByte::get() + DepositPerStorageItem::get();
+			let base_deposit = DepositPerContract::get() + item_deposit;
+			let alice_balance = 1_000_000 - 30_000 - base_deposit;
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance);
+			assert_eq!(Balances::reserved_balance(&addr), base_deposit);
+			assert_eq!(
+				ContractInfoOf::<Test>::get(&addr).unwrap().storage_deposit,
+				base_deposit,
 			);
 
-			// Check result
-			let rent_2 = <Test as Config>::RentFraction::get()
-				.mul_ceil((8 + 4 + code_len) * 10_000 + 10_000 - (endowment - rent0 - rent))
-				* 2;
-			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
-			assert_eq!(contract.rent_allowance, allowance - rent0 - rent - rent_2);
-			assert_eq!(contract.deduct_block, 7);
-			assert_eq!(Balances::free_balance(&addr), endowment - rent0 - rent - rent_2);
-
-			// Second call on same block should have no effect on rent
-			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+			// Adding another item charges the origin.
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::set_storage_4_byte(),
+			));
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance - item_deposit);
+			assert_eq!(Balances::reserved_balance(&addr), base_deposit + item_deposit);
+			assert_eq!(
+				ContractInfoOf::<Test>::get(&addr).unwrap().storage_deposit,
+				base_deposit + item_deposit,
 			);
-			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
-			assert_eq!(contract.rent_allowance, allowance - rent0 - rent - rent_2);
-			assert_eq!(contract.deduct_block, 7);
-			assert_eq!(Balances::free_balance(&addr), endowment - rent0 - rent - rent_2)
-		});
-}
-
-#[test]
-fn inherent_claim_surcharge_contract_removals() {
-	removals(|addr| Contracts::claim_surcharge(Origin::none(), addr, Some(ALICE)).is_ok());
-}
-
-#[test]
-fn signed_claim_surcharge_contract_removals() {
-	removals(|addr| Contracts::claim_surcharge(Origin::signed(ALICE), addr, None).is_ok());
-}
-
-#[test]
-fn claim_surcharge_malus() {
-	// Test surcharge malus for inherent
-	claim_surcharge(8, |addr| Contracts::claim_surcharge(Origin::none(), addr, Some(ALICE)).is_ok(), true);
-	claim_surcharge(7, |addr| Contracts::claim_surcharge(Origin::none(), addr, Some(ALICE)).is_ok(), true);
-	claim_surcharge(6, |addr| Contracts::claim_surcharge(Origin::none(), addr, Some(ALICE)).is_ok(), true);
-	claim_surcharge(5, |addr| Contracts::claim_surcharge(Origin::none(), addr, Some(ALICE)).is_ok(), false);
-
-	// Test surcharge malus for signed
-	claim_surcharge(8, |addr| Contracts::claim_surcharge(Origin::signed(ALICE), addr, None).is_ok(), true);
-	claim_surcharge(7, |addr| Contracts::claim_surcharge(Origin::signed(ALICE), addr, None).is_ok(), false);
-	claim_surcharge(6, |addr| Contracts::claim_surcharge(Origin::signed(ALICE), addr, None).is_ok(), false);
-	claim_surcharge(5, |addr| Contracts::claim_surcharge(Origin::signed(ALICE), addr, None).is_ok(), false);
-}
-
-/// Claim surcharge with the given trigger_call at the given blocks.
-/// If `removes` is true then assert that the contract is a tombstone.
-fn claim_surcharge(blocks: u64, trigger_call: impl Fn(AccountIdOf<Test>) -> bool, removes: bool) {
-	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
 
-	ExtBuilder::default()
-		.existential_deposit(50)
-		.build()
-		.execute_with(|| {
-			// Create
-			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::instantiate_with_code(
+			// Removing it again refunds the origin.
+			assert_ok!(Contracts::call(
 				Origin::signed(ALICE),
-				100_000,
+				addr.clone(),
+				0,
 				GAS_LIMIT,
-				wasm,
-				<Test as pallet_balances::Config>::Balance::from(30_000u32).encode(), // rent allowance
-				vec![],
+				None,
+				call::remove_storage_4_byte(),
 			));
-			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-
-			// Advance blocks
-			initialize_block(blocks);
-
-			// Trigger rent through call
-			assert_eq!(trigger_call(addr.clone()), removes);
-
-			if removes {
-				assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_tombstone().is_some());
-			} else {
-				assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().is_some());
-			}
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance);
+			assert_eq!(Balances::reserved_balance(&addr), base_deposit);
+			assert_eq!(
+				ContractInfoOf::<Test>::get(&addr).unwrap().storage_deposit,
+				base_deposit,
+			);
 		});
 }
 
-/// Test for all kind of removals for the given trigger:
-/// * if balance is reached and balance > subsistence threshold
-/// * if allowance is exceeded
-/// * if balance is reached and balance < subsistence threshold
-///	    * this case cannot be triggered by a contract: we check whether a tombstone is left
-fn removals(trigger_call: impl Fn(AccountIdOf<Test>) -> bool) {
+#[test]
+fn storage_deposit_limit_is_enforced() {
 	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
 
-	// Balance reached and superior to subsistence threshold
 	ExtBuilder::default()
 		.existential_deposit(50)
 		.build()
 		.execute_with(|| {
-			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let item_deposit = 4 * DepositPerStorageByte::get() + DepositPerStorageItem::get();
+
+			// The limit is too low to pay for the contract and its initial storage.
+			assert_err_ignore_postinfo!(
+				Contracts::instantiate_with_code(
+					Origin::signed(ALICE),
+					30_000,
+					GAS_LIMIT,
+					Some(DepositPerContract::get().into()),
+					wasm.clone(),
+					<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
+					vec![],
+				),
+				Error::<Test>::StorageDepositLimitExhausted,
+			);
 			assert_ok!(Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				70_000,
+				30_000,
 				GAS_LIMIT,
-				wasm.clone(),
-				<Test as pallet_balances::Config>::Balance::from(100_000u32).encode(), // rent allowance
+				Some((DepositPerContract::get() + item_deposit).into()),
+				wasm,
+				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
 				vec![],
 			));
 			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-			let allowance = ContractInfoOf::<Test>::get(&addr)
-				.unwrap().get_alive().unwrap().rent_allowance;
-			let balance = Balances::free_balance(&addr);
-
-			let subsistence_threshold = Pallet::<Test>::subsistence_threshold();
+			let alice_balance = Balances::free_balance(&ALICE);
 
-			// Trigger rent must have no effect
-			assert!(!trigger_call(addr.clone()));
-			assert_eq!(
-				ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap().rent_allowance,
-				allowance,
+			// Adding an item fails when the limit does not cover its deposit.
+			assert_err_ignore_postinfo!(
+				Contracts::call(
+					Origin::signed(ALICE),
+					addr.clone(),
+					0,
+					GAS_LIMIT,
+					Some((item_deposit - 1).into()),
+					call::set_storage_4_byte(),
+				),
+				Error::<Test>::StorageDepositLimitExhausted,
 			);
-			assert_eq!(Balances::free_balance(&addr), balance);
-
-			// Advance blocks
-			initialize_block(27);
-
-			// Trigger rent through call (should remove the contract)
-			assert!(trigger_call(addr.clone()));
-			assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_tombstone().is_some());
-			assert_eq!(Balances::free_balance(&addr), subsistence_threshold);
+			let contract = ContractInfoOf::<Test>::get(&addr).unwrap();
+			assert_eq!(contract.storage_size, 4);
+			assert_eq!(contract.pair_count, 1);
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance);
 
-			// Advance blocks
-			initialize_block(30);
-
-			// Trigger rent must have no effect
-			assert!(!trigger_call(addr.clone()));
-			assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_tombstone().is_some());
-			assert_eq!(Balances::free_balance(&addr), subsistence_threshold);
-		});
-
-	// Allowance exceeded
-	ExtBuilder::default()
-		.existential_deposit(50)
-		.build()
-		.execute_with(|| {
-			// Create
-			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-			assert_ok!(Contracts::instantiate_with_code(
+			// A refund is never limited.
+			assert_ok!(Contracts::call(
 				Origin::signed(ALICE),
-				100_000,
+				addr.clone(),
+				0,
 				GAS_LIMIT,
-				wasm.clone(),
-				<Test as pallet_balances::Config>::Balance::from(70_000u32).encode(), // rent allowance
-				vec![],
+				Some(0u64.into()),
+				call::remove_storage_4_byte(),
 			));
-			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-			let allowance = ContractInfoOf::<Test>::get(&addr)
-				.unwrap().get_alive().unwrap().rent_allowance;
-			let balance = Balances::free_balance(&addr);
-
-			// Trigger rent must have no effect
-			assert!(!trigger_call(addr.clone()));
-			assert_eq!(
-				ContractInfoOf::<Test>::get(&addr)
-					.unwrap()
-					.get_alive()
-					.unwrap()
-					.rent_allowance,
-				allowance,
-			);
-			assert_eq!(Balances::free_balance(&addr), balance);
-
-			// Advance blocks
-			initialize_block(27);
-
-			// Trigger rent through call
-			assert!(trigger_call(addr.clone()));
-			assert!(ContractInfoOf::<Test>::get(&addr)
-				.unwrap()
-				.get_tombstone()
-				.is_some());
-			// Balance should be initial balance - initial rent_allowance
-			assert_eq!(Balances::free_balance(&addr), 30_000);
-
-			// Advance blocks
-			initialize_block(20);
-
-			// Trigger rent must have no effect
-			assert!(!trigger_call(addr.clone()));
-			assert!(ContractInfoOf::<Test>::get(&addr)
-				.unwrap()
-				.get_tombstone()
-				.is_some());
-			assert_eq!(Balances::free_balance(&addr), 30_000);
+			assert_eq!(Balances::free_balance(&ALICE), alice_balance + item_deposit);
 		});
+}
+
+#[test]
+fn storage_deposit_not_enough_funds() {
+	let (wasm, _) = compile_module::<Test>("set_rent").unwrap();
 
-	// Balance reached and inferior to subsistence threshold
 	ExtBuilder::default()
 		.existential_deposit(50)
 		.build()
 		.execute_with(|| {
-			// Create
-			let subsistence_threshold = Pallet::<Test>::subsistence_threshold();
-			let _ = Balances::deposit_creating(&ALICE, subsistence_threshold * 1000);
-			assert_ok!(Contracts::instantiate_with_code(
-				Origin::signed(ALICE),
-				subsistence_threshold * 100,
-				GAS_LIMIT,
-				wasm,
-				(subsistence_threshold * 100).encode(), // rent allowance
-				vec![],
-			));
-			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-			let allowance = ContractInfoOf::<Test>::get(&addr)
-				.unwrap().get_alive().unwrap().rent_allowance;
-			let balance = Balances::free_balance(&addr);
-
-			// Trigger rent must have no effect
-			assert!(!trigger_call(addr.clone()));
-			assert_eq!(
-				ContractInfoOf::<Test>::get(&addr)
-					.unwrap()
-					.get_alive()
-					.unwrap()
-					.rent_allowance,
-				allowance,
-			);
-			assert_eq!(
-				Balances::free_balance(&addr),
-				balance,
+			// ALICE can pay for the endowment but not for the storage deposit on top.
+			let _ = Balances::deposit_creating(&ALICE, 30_000 + 50);
+			assert_err_ignore_postinfo!(
+				Contracts::instantiate_with_code(
+					Origin::signed(ALICE),
+					30_000,
+					GAS_LIMIT,
+					None,
+					wasm,
+					<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
+					vec![],
+				),
+				Error::<Test>::StorageDepositNotEnoughFunds,
 			);
-
-			// Make contract have exactly the subsistence threshold
-			Balances::make_free_balance_be(&addr, subsistence_threshold);
-			assert_eq!(Balances::free_balance(&addr), subsistence_threshold);
-
-			// Advance blocks (should remove as balance is exactly subsistence)
-			initialize_block(10);
-
-			// Trigger rent through call
-			assert!(trigger_call(addr.clone()));
-			assert_matches!(ContractInfoOf::<Test>::get(&addr), Some(ContractInfo::Tombstone(_)));
-			assert_eq!(Balances::free_balance(&addr), subsistence_threshold);
-
-			// Advance blocks
-			initialize_block(20);
-
-			// Trigger rent must have no effect
-			assert!(!trigger_call(addr.clone()));
-			assert_matches!(ContractInfoOf::<Test>::get(&addr), Some(ContractInfo::Tombstone(_)));
-			assert_eq!(Balances::free_balance(&addr), subsistence_threshold);
+			assert_eq!(Balances::free_balance(&ALICE), 30_000 + 50);
 		});
 }
 
 #[test]
-fn call_removed_contract() {
+fn bare_call_reports_storage_deposit() {
 	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
 
-	// Balance reached and superior to subsistence threshold
 	ExtBuilder::default()
 		.existential_deposit(50)
 		.build()
 		.execute_with(|| {
-			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 			assert_ok!(Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
-				// rent allowance
 				<Test as pallet_balances::Config>::Balance::from(10_000u32).encode(),
 				vec![],
 			));
 			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+			let item_deposit = 4 * DepositPerStorageByte::get() + DepositPerStorageItem::get();
 
-			// Calling contract should succeed.
-			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::set_storage_4_byte(),
+				false,
 			);
+			assert!(result.result.unwrap().is_success());
+			assert_eq!(result.storage_deposit, StorageDeposit::Charge(item_deposit));
 
-			// Advance blocks
-			initialize_block(27);
-
-			// Calling contract should deny access because rent cannot be paid.
-			assert_err_ignore_postinfo!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null()),
-				Error::<Test>::NotCallable
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				call::null(),
+				false,
 			);
-			// No event is generated because the contract is not actually removed.
-			assert_eq!(System::events(), vec![]);
+			assert!(result.result.unwrap().is_success());
+			assert_eq!(result.storage_deposit, StorageDeposit::Charge(0));
 
-			// Subsequent contract calls should also fail.
-			assert_err_ignore_postinfo!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null()),
-				Error::<Test>::NotCallable
+			let result = Contracts::bare_call(
+				ALICE,
+				addr,
+				0,
+				GAS_LIMIT,
+				None,
+				call::remove_storage_4_byte(),
+				false,
 			);
+			assert!(result.result.unwrap().is_success());
+			assert_eq!(result.storage_deposit, StorageDeposit::Refund(item_deposit));
+		});
+}
 
-			// A snitch can now remove the contract
-			assert_ok!(Contracts::claim_surcharge(Origin::none(), addr.clone(), Some(ALICE)));
-			assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_tombstone().is_some());
-		})
+fn initialize_block(number: u64) {
+	System::initialize(
+		&number,
+		&[0u8; 32].into(),
+		&Default::default(),
+		Default::default(),
+	);
 }
 
 #[test]
-fn default_rent_allowance_on_instantiate() {
-	let (wasm, code_hash) = compile_module::<Test>("check_default_rent_allowance").unwrap();
+fn storage_max_value_limit() {
+	let (wasm, code_hash) = compile_module::<Test>("storage_size").unwrap();
 
 	ExtBuilder::default()
 		.existential_deposit(50)
@@ -1116,479 +976,214 @@ fn default_rent_allowance_on_instantiate() {
 				Origin::signed(ALICE),
 				30_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
 			));
 			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
-			let code_len: BalanceOf<Test> =
-			PrefabWasmModule::<Test>::from_storage_noinstr(contract.code_hash)
-				.unwrap()
-				.occupied_storage()
-				.into();
-
-			// The instantiation deducted the rent for one block immediately
-			let first_rent = <Test as Config>::RentFraction::get()
-				// (base_deposit(8) + code_len) * byte_price - free_balance
-				.mul_ceil((8 + code_len) * 10_000 - 30_000)
-				// blocks to rent
-				* 1;
-			assert_eq!(contract.rent_allowance, <BalanceOf<Test>>::max_value() - first_rent);
+			ContractInfoOf::<Test>::get(&addr).unwrap();
 
-			// Advance blocks
-			initialize_block(5);
+			// Call contract with allowed storage value.
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT * 2, // we are copying a huge buffer
+				None,
+				<Test as Config>::MaxValueSize::get().encode(),
+			));
 
-			// Trigger rent through call
-			assert_ok!(
-				Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, call::null())
+			// Call contract with too large a storage value.
+			assert_err_ignore_postinfo!(
+				Contracts::call(
+					Origin::signed(ALICE),
+					addr,
+					0,
+					GAS_LIMIT,
+					None,
+					(<Test as Config>::MaxValueSize::get() + 1).encode(),
+				),
+				Error::<Test>::ValueTooLarge,
 			);
-
-			// Check contract is still alive
-			let contract = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive();
-			assert!(contract.is_some())
 		});
 }
 
 #[test]
-fn restorations_dirty_storage_and_different_storage() {
-	restoration(true, true, false);
-}
-
-#[test]
-fn restorations_dirty_storage() {
-	restoration(false, true, false);
-}
-
-#[test]
-fn restoration_different_storage() {
-	restoration(true, false, false);
-}
-
-#[test]
-fn restoration_code_evicted() {
-	restoration(false, false, true);
-}
-
-#[test]
-fn restoration_success() {
-	restoration(false, false, false);
-}
-
-fn restoration(
-	test_different_storage: bool,
-	test_restore_to_with_dirty_storage: bool,
-	test_code_evicted: bool
-) {
-	let (set_rent_wasm, set_rent_code_hash) = compile_module::<Test>("set_rent").unwrap();
-	let (restoration_wasm, restoration_code_hash) = compile_module::<Test>("restoration").unwrap();
-	let allowance: <Test as pallet_balances::Config>::Balance = 10_000;
+fn deploy_and_call_other_contract() {
+	let (callee_wasm, callee_code_hash) = compile_module::<Test>("return_with_data").unwrap();
+	let (caller_wasm, caller_code_hash) = compile_module::<Test>("caller_contract").unwrap();
 
 	ExtBuilder::default()
 		.existential_deposit(50)
 		.build()
 		.execute_with(|| {
+			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-
-			// Create an account with address `BOB` with code `CODE_SET_RENT`.
-			// The input parameter sets the rent allowance to 0.
 			assert_ok!(Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				30_000,
+				100_000,
 				GAS_LIMIT,
-				set_rent_wasm.clone(),
-				allowance.encode(),
+				None,
+				caller_wasm,
+				vec![],
 				vec![],
 			));
-			let addr_bob = Contracts::contract_address(&ALICE, &set_rent_code_hash, &[]);
-
-			let mut events = vec![
-				EventRecord {
-					phase: Phase::Initialization,
-					event: Event::frame_system(frame_system::Event::NewAccount(ALICE)),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: Event::pallet_balances(
-						pallet_balances::Event::Endowed(ALICE, 1_000_000)
-					),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: Event::frame_system(frame_system::Event::NewAccount(addr_bob.clone())),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: Event::pallet_balances(
-						pallet_balances::Event::Endowed(addr_bob.clone(), 30_000)
-					),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: Event::pallet_balances(
-						pallet_balances::Event::Transfer(ALICE, addr_bob.clone(), 30_000)
-					),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: Event::pallet_contracts(
-						crate::Event::CodeStored(set_rent_code_hash.into())
-					),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: Event::pallet_contracts(
-						crate::Event::Instantiated(ALICE, addr_bob.clone())
-					),
-					topics: vec![],
-				},
-			];
-
-			// Create another contract from the same code in order to increment the codes
-			// refcounter so that it stays on chain.
-			if !test_code_evicted {
-				assert_ok!(Contracts::instantiate_with_code(
-					Origin::signed(ALICE),
-					20_000,
-					GAS_LIMIT,
-					set_rent_wasm,
-					allowance.encode(),
-					vec![1],
-				));
-				assert_refcount!(set_rent_code_hash, 2);
-				let addr_dummy = Contracts::contract_address(&ALICE, &set_rent_code_hash, &[1]);
-				events.extend([
-					EventRecord {
-						phase: Phase::Initialization,
-						event: Event::frame_system(frame_system::Event::NewAccount(addr_dummy.clone())),
-						topics: vec![],
-					},
-					EventRecord {
-						phase: Phase::Initialization,
-						event: Event::pallet_balances(
-							pallet_balances::Event::Endowed(addr_dummy.clone(), 20_000)
-						),
-						topics: vec![],
-					},
-					EventRecord {
-						phase: Phase::Initialization,
-						event: Event::pallet_balances(
-							pallet_balances::Event::Transfer(ALICE, addr_dummy.clone(), 20_000)
-						),
-						topics: vec![],
-					},
-					EventRecord {
-						phase: Phase::Initialization,
-						event: Event::pallet_contracts(
-							crate::Event::Instantiated(ALICE, addr_dummy.clone())
-						),
-						topics: vec![],
-					},
-				].iter().cloned());
-			}
-
-			assert_eq!(System::events(), events);
-
-			// Check if `BOB` was created successfully and that the rent allowance is below what
-			// we specified as the first rent was already collected.
-			let bob_contract = ContractInfoOf::<Test>::get(&addr_bob).unwrap().get_alive().unwrap();
-			assert!(bob_contract.rent_allowance < allowance);
-
-			if test_different_storage {
-				assert_ok!(Contracts::call(
-					Origin::signed(ALICE),
-					addr_bob.clone(), 0, GAS_LIMIT,
-					call::set_storage_4_byte())
-				);
-			}
-
-			// Advance blocks in order to make the contract run out of money for rent.
-			initialize_block(27);
-
-			// Call `BOB`, which makes it pay rent. Since the rent allowance is set to 20_000
-			// we expect that it is no longer callable but keeps existing until someone
-			// calls `claim_surcharge`.
-			assert_err_ignore_postinfo!(
-				Contracts::call(
-					Origin::signed(ALICE), addr_bob.clone(), 0, GAS_LIMIT, call::null()
-				),
-				Error::<Test>::NotCallable
-			);
-			assert!(System::events().is_empty());
-			assert!(ContractInfoOf::<Test>::get(&addr_bob).unwrap().get_alive().is_some());
-			assert_ok!(Contracts::claim_surcharge(Origin::none(), addr_bob.clone(), Some(ALICE)));
-			assert!(ContractInfoOf::<Test>::get(&addr_bob).unwrap().get_tombstone().is_some());
-			if test_code_evicted {
-				assert_refcount!(set_rent_code_hash, 0);
-			} else {
-				assert_refcount!(set_rent_code_hash, 1);
-			}
-
-			// Create another account with the address `DJANGO` with `CODE_RESTORATION`.
-			//
-			// Note that we can't use `ALICE` for creating `DJANGO` so we create yet another
-			// account `CHARLIE` and create `DJANGO` with it.
-			let _ = Balances::deposit_creating(&CHARLIE, 1_000_000);
 			assert_ok!(Contracts::instantiate_with_code(
-				Origin::signed(CHARLIE),
-				30_000,
+				Origin::signed(ALICE),
+				100_000,
 				GAS_LIMIT,
-				restoration_wasm,
-				vec![],
-				vec![],
+				None,
+				callee_wasm,
+				0u32.to_le_bytes().encode(),
+				vec![42],
 			));
-			let addr_django = Contracts::contract_address(&CHARLIE, &restoration_code_hash, &[]);
-
-			// Before performing a call to `DJANGO` save its original trie id.
-			let django_trie_id = ContractInfoOf::<Test>::get(&addr_django).unwrap()
-				.get_alive().unwrap().trie_id;
-
-			// The trie is regarded as 'dirty' when it was written to in the current block.
-			if !test_restore_to_with_dirty_storage {
-				// Advance 1 block.
-				initialize_block(28);
-			}
-
-			// Perform a call to `DJANGO`. This should either perform restoration successfully or
-			// fail depending on the test parameters.
-			let perform_the_restoration = || {
-				Contracts::call(
-					Origin::signed(ALICE),
-					addr_django.clone(),
-					0,
-					GAS_LIMIT,
-					set_rent_code_hash
-						.as_ref()
-						.iter()
-						.chain(AsRef::<[u8]>::as_ref(&addr_bob))
-						.cloned()
-						.collect(),
-				)
-			};
-
-			// The key that is used in the restorer contract but is not in the target contract.
-			// Is supplied as delta to the restoration. We need it to check whether the key
-			// is properly removed on success but still there on failure.
-			let delta_key = {
-				let mut key = [0u8; 32];
-				key[0] = 1;
-				key
-			};
 
-			if test_different_storage || test_restore_to_with_dirty_storage || test_code_evicted {
-				// Parametrization of the test imply restoration failure. Check that `DJANGO` aka
-				// restoration contract is still in place and also that `BOB` doesn't exist.
-				let result = perform_the_restoration();
-				assert!(ContractInfoOf::<Test>::get(&addr_bob).unwrap().get_tombstone().is_some());
-				let django_contract = ContractInfoOf::<Test>::get(&addr_django).unwrap()
-					.get_alive().unwrap();
-				assert_eq!(django_contract.storage_size, 8);
-				assert_eq!(django_contract.trie_id, django_trie_id);
-				assert_eq!(django_contract.deduct_block, System::block_number());
-				assert_eq!(
-					Storage::<Test>::read(&django_trie_id, &delta_key),
-					Some(vec![40, 0, 0, 0]),
-				);
-				match (
-					test_different_storage,
-					test_restore_to_with_dirty_storage,
-					test_code_evicted
-				) {
-					(true, false, false) => {
-						assert_err_ignore_postinfo!(
-							result, Error::<Test>::InvalidTombstone,
-						);
-						assert_eq!(System::events(), vec![]);
-					}
-					(_, true, false) => {
-						assert_err_ignore_postinfo!(
-							result, Error::<Test>::InvalidContractOrigin,
-						);
-						assert_eq!(System::events(), vec![
-							EventRecord {
-								phase: Phase::Initialization,
-								event: Event::pallet_contracts(crate::Event::Evicted(addr_bob)),
-								topics: vec![],
-							},
-							EventRecord {
-								phase: Phase::Initialization,
-								event: Event::frame_system(frame_system::Event::NewAccount(CHARLIE)),
-								topics: vec![],
-							},
-							EventRecord {
-								phase: Phase::Initialization,
-								event: Event::pallet_balances(pallet_balances::Event::Endowed(CHARLIE, 1_000_000)),
-								topics: vec![],
-							},
-							EventRecord {
-								phase: Phase::Initialization,
-								event: Event::frame_system(frame_system::Event::NewAccount(addr_django.clone())),
-								topics: vec![],
-							},
-							EventRecord {
-								phase: Phase::Initialization,
-								event: Event::pallet_balances(pallet_balances::Event::Endowed(addr_django.clone(), 30_000)),
-								topics: vec![],
-							},
-							EventRecord {
-								phase: Phase::Initialization,
-								event: Event::pallet_balances(
-									pallet_balances::Event::Transfer(CHARLIE, addr_django.clone(), 30_000)
-								),
-								topics: vec![],
-							},
-							EventRecord {
-								phase: Phase::Initialization,
-								event: Event::pallet_contracts(
-									crate::Event::CodeStored(restoration_code_hash)
-								),
-								topics: vec![],
-							},
-							EventRecord {
-								phase: Phase::Initialization,
-								event: Event::pallet_contracts(
-									crate::Event::Instantiated(CHARLIE, addr_django.clone())
-								),
-								topics: vec![],
-							},
-
-						]);
-					},
-					(false, false, true) => {
-						assert_err_ignore_postinfo!(
-							result, Error::<Test>::CodeNotFound,
-						);
-						assert_refcount!(set_rent_code_hash, 0);
-						assert_eq!(System::events(), vec![]);
-					},
-					_ => unreachable!(),
-				}
-			} else {
-				assert_ok!(perform_the_restoration());
-				assert_refcount!(set_rent_code_hash, 2);
-
-				// Here we expect that the restoration is succeeded. Check that the restoration
-				// contract `DJANGO` ceased to exist and that `BOB` returned back.
-				let bob_contract = ContractInfoOf::<Test>::get(&addr_bob).unwrap()
-					.get_alive().unwrap();
-				assert_eq!(bob_contract.rent_allowance, 50);
-				assert_eq!(bob_contract.storage_size, 4);
-				assert_eq!(bob_contract.trie_id, django_trie_id);
-				assert_eq!(bob_contract.deduct_block, System::block_number());
-				assert!(ContractInfoOf::<Test>::get(&addr_django).is_none());
-				assert_matches!(Storage::<Test>::read(&django_trie_id, &delta_key), None);
-				assert_eq!(System::events(), vec![
-					EventRecord {
-						phase: Phase::Initialization,
-						event: Event::pallet_contracts(crate::Event::CodeRemoved(restoration_code_hash)),
-						topics: vec![],
-					},
-					EventRecord {
-						phase: Phase::Initialization,
-						event: Event::frame_system(system::Event::KilledAccount(addr_django.clone())),
-						topics: vec![],
-					},
-					EventRecord {
-						phase: Phase::Initialization,
-						event: Event::pallet_contracts(
-							crate::Event::Restored(
-								addr_django, addr_bob, bob_contract.code_hash, 50
-							)
-						),
-						topics: vec![],
-					},
-				]);
-			}
+			// Call BOB contract, which attempts to instantiate and call the callee contract and
+			// makes various assertions on the results from those calls.
+			assert_ok!(Contracts::call(
+				Origin::signed(ALICE),
+				Contracts::contract_address(&ALICE, &caller_code_hash, &[]),
+				0,
+				GAS_LIMIT,
+				None,
+				callee_code_hash.as_ref().to_vec(),
+			));
 		});
 }
 
 #[test]
-fn storage_max_value_limit() {
-	let (wasm, code_hash) = compile_module::<Test>("storage_size").unwrap();
+fn delegate_call_executes_in_callers_context() {
+	let (caller_wasm, caller_code_hash) = compile_module::<Test>("delegate_call").unwrap();
+	let (lib_wasm, lib_code_hash) = compile_module::<Test>("delegate_call_lib").unwrap();
 
 	ExtBuilder::default()
 		.existential_deposit(50)
 		.build()
 		.execute_with(|| {
-			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 			assert_ok!(Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				30_000,
+				100_000,
 				GAS_LIMIT,
-				wasm,
+				None,
+				caller_wasm,
 				vec![],
 				vec![],
 			));
-			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-			ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+			// The library code needs to be on-chain in order to be delegated to.
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				None,
+				lib_wasm,
+				vec![],
+				vec![],
+			));
+			let caller_addr = Contracts::contract_address(&ALICE, &caller_code_hash, &[]);
+			let lib_addr = Contracts::contract_address(&ALICE, &lib_code_hash, &[]);
 
-			// Call contract with allowed storage value.
 			assert_ok!(Contracts::call(
 				Origin::signed(ALICE),
-				addr.clone(),
-				0,
-				GAS_LIMIT * 2, // we are copying a huge buffer
-				<Test as Config>::MaxValueSize::get().encode(),
+				caller_addr.clone(),
+				1_000,
+				GAS_LIMIT,
+				None,
+				lib_code_hash.as_ref().to_vec(),
 			));
 
-			// Call contract with too large a storage value.
+			// The library code wrote to the storage of the calling contract.
+			assert_eq!(
+				Contracts::get_storage(caller_addr.clone(), [1; 32]),
+				Ok(Some(1_000u64.encode())),
+			);
+			assert_eq!(Contracts::get_storage(lib_addr, [1; 32]), Ok(None));
+			// The calling contract still uses its own code.
+			assert_eq!(
+				ContractInfoOf::<Test>::get(&caller_addr).unwrap().code_hash,
+				caller_code_hash,
+			);
+
+			// Delegating to code that does not exist makes the caller trap.
 			assert_err_ignore_postinfo!(
 				Contracts::call(
 					Origin::signed(ALICE),
-					addr,
+					caller_addr,
 					0,
 					GAS_LIMIT,
-					(<Test as Config>::MaxValueSize::get() + 1).encode(),
+					None,
+					H256::repeat_byte(0xff).as_ref().to_vec(),
 				),
-				Error::<Test>::ValueTooLarge,
+				Error::<Test>::ContractTrapped,
 			);
 		});
 }
 
 #[test]
-fn deploy_and_call_other_contract() {
-	let (callee_wasm, callee_code_hash) = compile_module::<Test>("return_with_data").unwrap();
-	let (caller_wasm, caller_code_hash) = compile_module::<Test>("caller_contract").unwrap();
+fn set_code_hash_works() {
+	let (wasm, code_hash) = compile_module::<Test>("set_code_hash").unwrap();
+	let (new_wasm, new_code_hash) = compile_module::<Test>("new_set_code_hash_contract").unwrap();
 
 	ExtBuilder::default()
 		.existential_deposit(50)
 		.build()
 		.execute_with(|| {
-			// Create
 			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 			assert_ok!(Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
-				caller_wasm,
+				None,
+				wasm,
 				vec![],
 				vec![],
 			));
+			// Make the new code available on-chain.
 			assert_ok!(Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
-				callee_wasm,
-				0u32.to_le_bytes().encode(),
-				vec![42],
+				None,
+				new_wasm,
+				vec![],
+				vec![],
 			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
 
-			// Call BOB contract, which attempts to instantiate and call the callee contract and
-			// makes various assertions on the results from those calls.
-			assert_ok!(Contracts::call(
-				Origin::signed(ALICE),
-				Contracts::contract_address(&ALICE, &caller_code_hash, &[]),
+			// The first call replaces the code but still executes the old one.
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
 				0,
 				GAS_LIMIT,
-				callee_code_hash.as_ref().to_vec(),
-			));
+				None,
+				new_code_hash.as_ref().to_vec(),
+				false,
+			).result.unwrap();
+			assert_return_code!(result, 1);
+			assert_eq!(ContractInfoOf::<Test>::get(&addr).unwrap().code_hash, new_code_hash);
+			assert_eq!(
+				System::events().last().map(|record| record.event.clone()),
+				Some(Event::pallet_contracts(
+					crate::Event::ContractCodeUpdated(addr.clone(), new_code_hash, code_hash)
+				)),
+			);
+
+			// The old code is no longer used by any contract and was removed.
+			assert!(!crate::CodeStorage::<Test>::contains_key(&code_hash));
+
+			// The next call executes the new code.
+			let result = Contracts::bare_call(
+				ALICE,
+				addr,
+				0,
+				GAS_LIMIT,
+				None,
+				vec![],
+				false,
+			).result.unwrap();
+			assert_return_code!(result, 2);
 		});
 }
 
@@ -1606,6 +1201,7 @@ fn cannot_self_destruct_through_draning() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1615,7 +1211,7 @@ fn cannot_self_destruct_through_draning() {
 			// Check that the BOB contract has been instantiated.
 			assert_matches!(
 				ContractInfoOf::<Test>::get(&addr),
-				Some(ContractInfo::Alive(_))
+				Some(_)
 			);
 
 			// Call BOB which makes it send all funds to the zero address
@@ -1626,6 +1222,7 @@ fn cannot_self_destruct_through_draning() {
 					addr,
 					0,
 					GAS_LIMIT,
+					None,
 					vec![],
 				)
 			);
@@ -1646,6 +1243,7 @@ fn cannot_self_destruct_while_live() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1655,7 +1253,7 @@ fn cannot_self_destruct_while_live() {
 			// Check that the BOB contract has been instantiated.
 			assert_matches!(
 				ContractInfoOf::<Test>::get(&addr),
-				Some(ContractInfo::Alive(_))
+				Some(_)
 			);
 
 			// Call BOB with input data, forcing it make a recursive call to itself to
@@ -1666,6 +1264,7 @@ fn cannot_self_destruct_while_live() {
 					addr.clone(),
 					0,
 					GAS_LIMIT,
+					None,
 					vec![0],
 				),
 				Error::<Test>::ContractTrapped,
@@ -1674,7 +1273,7 @@ fn cannot_self_destruct_while_live() {
 			// Check that BOB is still alive.
 			assert_matches!(
 				ContractInfoOf::<Test>::get(&addr),
-				Some(ContractInfo::Alive(_))
+				Some(_)
 			);
 		});
 }
@@ -1694,6 +1293,7 @@ fn self_destruct_works() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1703,7 +1303,7 @@ fn self_destruct_works() {
 			// Check that the BOB contract has been instantiated.
 			assert_matches!(
 				ContractInfoOf::<Test>::get(&addr),
-				Some(ContractInfo::Alive(_))
+				Some(_)
 			);
 
 			// Drop all previous events
@@ -1716,12 +1316,28 @@ fn self_destruct_works() {
 					addr.clone(),
 					0,
 					GAS_LIMIT,
+					None,
 					vec![],
 				),
 				Ok(_)
 			);
 
+			let deposit = <Test as Config>::DepositPerContract::get();
 			pretty_assertions::assert_eq!(System::events(), vec![
+				EventRecord {
+					phase: Phase::Initialization,
+					event: Event::pallet_balances(
+						pallet_balances::Event::Unreserved(addr.clone(), deposit)
+					),
+					topics: vec![],
+				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: Event::pallet_balances(
+						pallet_balances::Event::Transfer(addr.clone(), ALICE, deposit)
+					),
+					topics: vec![],
+				},
 				EventRecord {
 					phase: Phase::Initialization,
 					event: Event::frame_system(
@@ -1732,7 +1348,7 @@ fn self_destruct_works() {
 				EventRecord {
 					phase: Phase::Initialization,
 					event: Event::pallet_balances(
-						pallet_balances::Event::Transfer(addr.clone(), DJANGO, 93_086)
+						pallet_balances::Event::Transfer(addr.clone(), DJANGO, 100_000)
 					),
 					topics: vec![],
 				},
@@ -1754,8 +1370,7 @@ fn self_destruct_works() {
 			assert!(ContractInfoOf::<Test>::get(&addr).is_none());
 
 			// check that the beneficiary (django) got remaining balance
-			// some rent was deducted before termination
-			assert_eq!(Balances::free_balance(DJANGO), 1_093_086);
+			assert_eq!(Balances::free_balance(DJANGO), 1_100_000);
 		});
 }
 
@@ -1776,6 +1391,7 @@ fn destroy_contract_and_transfer_funds() {
 				Origin::signed(ALICE),
 				200_000,
 				GAS_LIMIT,
+				None,
 				callee_wasm,
 				vec![],
 				vec![42]
@@ -1787,6 +1403,7 @@ fn destroy_contract_and_transfer_funds() {
 				Origin::signed(ALICE),
 				200_000,
 				GAS_LIMIT,
+				None,
 				caller_wasm,
 				callee_code_hash.as_ref().to_vec(),
 				vec![],
@@ -1799,7 +1416,7 @@ fn destroy_contract_and_transfer_funds() {
 			// Check that the CHARLIE contract has been instantiated.
 			assert_matches!(
 				ContractInfoOf::<Test>::get(&addr_charlie),
-				Some(ContractInfo::Alive(_))
+				Some(_)
 			);
 
 			// Call BOB, which calls CHARLIE, forcing CHARLIE to self-destruct.
@@ -1808,6 +1425,7 @@ fn destroy_contract_and_transfer_funds() {
 				addr_bob,
 				0,
 				GAS_LIMIT,
+				None,
 				addr_charlie.encode(),
 			));
 
@@ -1831,6 +1449,7 @@ fn cannot_self_destruct_in_constructor() {
 					Origin::signed(ALICE),
 					100_000,
 					GAS_LIMIT,
+					None,
 					wasm,
 					vec![],
 					vec![],
@@ -1855,6 +1474,7 @@ fn crypto_hashes() {
 				Origin::signed(ALICE),
 				100_000,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1886,7 +1506,9 @@ fn crypto_hashes() {
 					addr.clone(),
 					0,
 					GAS_LIMIT,
+					None,
 					params,
+					false,
 				).result.unwrap();
 				assert!(result.is_success());
 				let expected = hash_fn(input.as_ref());
@@ -1895,18 +1517,210 @@ fn crypto_hashes() {
 		})
 }
 
+#[test]
+fn ecdsa_recover() {
+	let (wasm, code_hash) = compile_module::<Test>("ecdsa_recover").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			use sp_core::Pair;
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				None,
+				wasm,
+				vec![],
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+			let message = b"Hello world";
+			let message_hash = blake2_256(message);
+			let pair = sp_core::ecdsa::Pair::from_seed(&[1; 32]);
+			let mut signature = pair.sign(message).0;
+			let call = |signature: &[u8; 65]| {
+				let mut params = signature.to_vec();
+				params.extend_from_slice(&message_hash);
+				<Pallet<Test>>::bare_call(
+					ALICE,
+					addr.clone(),
+					0,
+					GAS_LIMIT,
+					None,
+					params,
+					false,
+				).result.unwrap()
+			};
+
+			// A valid signature recovers the public key of the signer.
+			let result = call(&signature);
+			assert!(result.is_success());
+			assert_eq!(&result.data[..4], &(RuntimeReturnCode::Success as u32).to_le_bytes());
+			assert_eq!(&result.data[4..], &pair.public().0[..]);
+
+			// An invalid recovery id makes the recovery fail.
+			signature[64] = 0xff;
+			let result = call(&signature);
+			assert!(result.is_success());
+			assert_eq!(
+				&result.data[..4],
+				&(RuntimeReturnCode::EcdsaRecoverFailed as u32).to_le_bytes(),
+			);
+		})
+}
+
+#[test]
+fn ecdsa_to_eth_address() {
+	let (wasm, code_hash) = compile_module::<Test>("ecdsa_to_eth_address").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				None,
+				wasm,
+				vec![],
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+			let call = |pub_key: [u8; 33]| {
+				<Pallet<Test>>::bare_call(
+					ALICE,
+					addr.clone(),
+					0,
+					GAS_LIMIT,
+					None,
+					pub_key.to_vec(),
+					false,
+				).result.unwrap()
+			};
+
+			// The public key that belongs to the private key `1`.
+			let result = call(
+				hex!("0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798")
+			);
+			assert!(result.is_success());
+			assert_eq!(&result.data[..4], &(RuntimeReturnCode::Success as u32).to_le_bytes());
+			assert_eq!(&result.data[4..], &hex!("7E5F4552091A69125d5DfCb7b8C2659029395Bdf"));
+
+			// Not a valid public key.
+			let result = call([0; 33]);
+			assert!(result.is_success());
+			assert_eq!(
+				&result.data[..4],
+				&(RuntimeReturnCode::EcdsaRecoverFailed as u32).to_le_bytes(),
+			);
+		})
+}
+
+#[test]
+fn sr25519_verify() {
+	let (wasm, code_hash) = compile_module::<Test>("sr25519_verify").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			use sp_core::Pair;
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				None,
+				wasm,
+				vec![],
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+			let pair = sp_core::sr25519::Pair::from_seed(&[1; 32]);
+			let signature = pair.sign(b"Hello world");
+			let call = |message: &[u8]| {
+				let mut params = signature.0.to_vec();
+				params.extend_from_slice(&pair.public().0);
+				params.extend_from_slice(message);
+				<Pallet<Test>>::bare_call(
+					ALICE,
+					addr.clone(),
+					0,
+					GAS_LIMIT,
+					None,
+					params,
+					false,
+				).result.unwrap()
+			};
+
+			assert_return_code!(call(b"Hello world"), RuntimeReturnCode::Success);
+			assert_return_code!(call(b"Hello world!"), RuntimeReturnCode::Sr25519VerifyFailed);
+		})
+}
+
+#[test]
+fn ed25519_verify() {
+	let (wasm, code_hash) = compile_module::<Test>("ed25519_verify").unwrap();
+
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.build()
+		.execute_with(|| {
+			use sp_core::Pair;
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				100_000,
+				GAS_LIMIT,
+				None,
+				wasm,
+				vec![],
+				vec![],
+			));
+			let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+			let pair = sp_core::ed25519::Pair::from_seed(&[1; 32]);
+			let signature = pair.sign(b"Hello world");
+			let call = |message: &[u8]| {
+				let mut params = signature.0.to_vec();
+				params.extend_from_slice(&pair.public().0);
+				params.extend_from_slice(message);
+				<Pallet<Test>>::bare_call(
+					ALICE,
+					addr.clone(),
+					0,
+					GAS_LIMIT,
+					None,
+					params,
+					false,
+				).result.unwrap()
+			};
+
+			assert_return_code!(call(b"Hello world"), RuntimeReturnCode::Success);
+			assert_return_code!(call(b"Hello world!"), RuntimeReturnCode::Ed25519VerifyFailed);
+		})
+}
+
 #[test]
 fn transfer_return_code() {
 	let (wasm, code_hash) = compile_module::<Test>("transfer_return_code").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				wasm,
 				vec![],
 				vec![],
@@ -1914,28 +1728,32 @@ fn transfer_return_code() {
 		);
 		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
 
-		// Contract has only the minimal balance so any transfer will return BelowSubsistence.
-		Balances::make_free_balance_be(&addr, subsistence);
+		// Contract has only the minimal balance so any transfer will fail.
+		Balances::make_free_balance_be(&addr, min_balance);
 		let result = Contracts::bare_call(
 			ALICE,
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
+			false,
 		).result.unwrap();
-		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
+		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
-		// Contract has enough total balance in order to not go below the subsistence
-		// threshold when transfering 100 balance but this balance is reserved so
-		// the transfer still fails but with another return code.
-		Balances::make_free_balance_be(&addr, subsistence + 100);
-		Balances::reserve(&addr, subsistence + 100).unwrap();
+		// Contract has enough total balance in order to not go below the minimum balance
+		// when transfering 100 balance but this balance is reserved so the transfer
+		// still fails.
+		Balances::make_free_balance_be(&addr, min_balance + 100);
+		Balances::reserve(&addr, min_balance + 100).unwrap();
 		let result = Contracts::bare_call(
 			ALICE,
 			addr,
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 	});
@@ -1946,22 +1764,23 @@ fn call_return_code() {
 	let (caller_code, caller_hash) = compile_module::<Test>("call_return_code").unwrap();
 	let (callee_code, callee_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
-		let _ = Balances::deposit_creating(&CHARLIE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+		let _ = Balances::deposit_creating(&CHARLIE, 1000 * min_balance);
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				caller_code,
 				vec![0],
 				vec![],
 			),
 		);
 		let addr_bob = Contracts::contract_address(&ALICE, &caller_hash, &[]);
-		Balances::make_free_balance_be(&addr_bob, subsistence);
+		Balances::make_free_balance_be(&addr_bob, min_balance);
 
 		// Contract calls into Django which is no valid contract
 		let result = Contracts::bare_call(
@@ -1969,55 +1788,64 @@ fn call_return_code() {
 			addr_bob.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			AsRef::<[u8]>::as_ref(&DJANGO).to_vec(),
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::NotCallable);
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(CHARLIE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				callee_code,
 				vec![0],
 				vec![],
 			),
 		);
 		let addr_django = Contracts::contract_address(&CHARLIE, &callee_hash, &[]);
-		Balances::make_free_balance_be(&addr_django, subsistence);
+		Balances::make_free_balance_be(&addr_django, min_balance);
 
-		// Contract has only the minimal balance so any transfer will return BelowSubsistence.
+		// Contract has only the minimal balance so any transfer will fail.
 		let result = Contracts::bare_call(
 			ALICE,
 			addr_bob.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&0u32.to_le_bytes()).cloned().collect(),
+			false,
 		).result.unwrap();
-		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
+		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
-		// Contract has enough total balance in order to not go below the subsistence
-		// threshold when transfering 100 balance but this balance is reserved so
-		// the transfer still fails but with another return code.
-		Balances::make_free_balance_be(&addr_bob, subsistence + 100);
-		Balances::reserve(&addr_bob, subsistence + 100).unwrap();
+		// Contract has enough total balance in order to not go below the minimum balance
+		// when transfering 100 balance but this balance is reserved so the transfer
+		// still fails.
+		Balances::make_free_balance_be(&addr_bob, min_balance + 100);
+		Balances::reserve(&addr_bob, min_balance + 100).unwrap();
 		let result = Contracts::bare_call(
 			ALICE,
 			addr_bob.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&0u32.to_le_bytes()).cloned().collect(),
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
 		// Contract has enough balance but callee reverts because "1" is passed.
-		Balances::make_free_balance_be(&addr_bob, subsistence + 1000);
+		Balances::make_free_balance_be(&addr_bob, min_balance + 1000);
 		let result = Contracts::bare_call(
 			ALICE,
 			addr_bob.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&1u32.to_le_bytes()).cloned().collect(),
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
 
@@ -2027,7 +1855,9 @@ fn call_return_code() {
 			addr_bob,
 			0,
 			GAS_LIMIT,
+			None,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&2u32.to_le_bytes()).cloned().collect(),
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
 
@@ -2039,16 +1869,17 @@ fn instantiate_return_code() {
 	let (caller_code, caller_hash) = compile_module::<Test>("instantiate_return_code").unwrap();
 	let (callee_code, callee_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
-		let _ = Balances::deposit_creating(&CHARLIE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+		let _ = Balances::deposit_creating(&CHARLIE, 1000 * min_balance);
 		let callee_hash = callee_hash.as_ref().to_vec();
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				callee_code,
 				vec![],
 				vec![],
@@ -2058,8 +1889,9 @@ fn instantiate_return_code() {
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				caller_code,
 				vec![],
 				vec![],
@@ -2067,39 +1899,45 @@ fn instantiate_return_code() {
 		);
 		let addr = Contracts::contract_address(&ALICE, &caller_hash, &[]);
 
-		// Contract has only the minimal balance so any transfer will return BelowSubsistence.
-		Balances::make_free_balance_be(&addr, subsistence);
+		// Contract has only the minimal balance so any transfer will fail.
+		Balances::make_free_balance_be(&addr, min_balance);
 		let result = Contracts::bare_call(
 			ALICE,
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			callee_hash.clone(),
+			false,
 		).result.unwrap();
-		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
+		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
-		// Contract has enough total balance in order to not go below the subsistence
-		// threshold when transfering the balance but this balance is reserved so
-		// the transfer still fails but with another return code.
-		Balances::make_free_balance_be(&addr, subsistence + 10_000);
-		Balances::reserve(&addr, subsistence + 10_000).unwrap();
+		// Contract has enough total balance in order to not go below the minimum balance
+		// when transfering the balance but this balance is reserved so the transfer
+		// still fails.
+		Balances::make_free_balance_be(&addr, min_balance + 10_000);
+		Balances::reserve(&addr, min_balance + 10_000).unwrap();
 		let result = Contracts::bare_call(
 			ALICE,
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			callee_hash.clone(),
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
 		// Contract has enough balance but the passed code hash is invalid
-		Balances::make_free_balance_be(&addr, subsistence + 10_000);
+		Balances::make_free_balance_be(&addr, min_balance + 10_000);
 		let result = Contracts::bare_call(
 			ALICE,
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![0; 33],
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CodeNotFound);
 
@@ -2109,7 +1947,9 @@ fn instantiate_return_code() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			callee_hash.iter().chain(&1u32.to_le_bytes()).cloned().collect(),
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
 
@@ -2119,7 +1959,9 @@ fn instantiate_return_code() {
 			addr,
 			0,
 			GAS_LIMIT,
+			None,
 			callee_hash.iter().chain(&2u32.to_le_bytes()).cloned().collect(),
+			false,
 		).result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
 
@@ -2130,14 +1972,15 @@ fn instantiate_return_code() {
 fn disabled_chain_extension_wont_deploy() {
 	let (code, _hash) = compile_module::<Test>("chain_extension").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 		TestExtension::disable();
 		assert_err_ignore_postinfo!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				3 * subsistence,
+				3 * min_balance,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2151,13 +1994,14 @@ fn disabled_chain_extension_wont_deploy() {
 fn disabled_chain_extension_errors_on_call() {
 	let (code, hash) = compile_module::<Test>("chain_extension").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2171,6 +2015,7 @@ fn disabled_chain_extension_errors_on_call() {
 				addr.clone(),
 				0,
 				GAS_LIMIT,
+				None,
 				vec![],
 			),
 			Error::<Test>::NoChainExtension,
@@ -2182,13 +2027,14 @@ fn disabled_chain_extension_errors_on_call() {
 fn chain_extension_works() {
 	let (code, hash) = compile_module::<Test>("chain_extension").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2206,7 +2052,9 @@ fn chain_extension_works() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![0, 99],
+			false,
 		);
 		let gas_consumed = result.gas_consumed;
 		assert_eq!(TestExtension::last_seen_buffer(), vec![0, 99]);
@@ -2218,7 +2066,9 @@ fn chain_extension_works() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![1],
+			false,
 		).result.unwrap();
 		// those values passed in the fixture
 		assert_eq!(TestExtension::last_seen_inputs(), (4, 1, 16, 12));
@@ -2229,7 +2079,9 @@ fn chain_extension_works() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![2, 42],
+			false,
 		);
 		assert_ok!(result.result);
 		assert_eq!(result.gas_consumed, gas_consumed + 42);
@@ -2240,7 +2092,9 @@ fn chain_extension_works() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![3],
+			false,
 		).result.unwrap();
 		assert_eq!(result.flags, ReturnFlags::REVERT);
 		assert_eq!(result.data, Bytes(vec![42, 99]));
@@ -2251,14 +2105,15 @@ fn chain_extension_works() {
 fn lazy_removal_works() {
 	let (code, hash) = compile_module::<Test>("self_destruct").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2266,7 +2121,7 @@ fn lazy_removal_works() {
 		);
 
 		let addr = Contracts::contract_address(&ALICE, &hash, &[]);
-		let info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
+		let info = <ContractInfoOf::<Test>>::get(&addr).unwrap();
 		let trie = &info.child_trie_info();
 
 		// Put value into the contracts child trie
@@ -2278,6 +2133,7 @@ fn lazy_removal_works() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 
@@ -2311,14 +2167,15 @@ fn lazy_removal_partial_remove_works() {
 	let mut ext = ExtBuilder::default().existential_deposit(50).build();
 
 	let trie = ext.execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2326,7 +2183,7 @@ fn lazy_removal_partial_remove_works() {
 		);
 
 		let addr = Contracts::contract_address(&ALICE, &hash, &[]);
-		let info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
+		let info = <ContractInfoOf::<Test>>::get(&addr).unwrap();
 		let trie = &info.child_trie_info();
 
 		// Put value into the contracts child trie
@@ -2336,6 +2193,7 @@ fn lazy_removal_partial_remove_works() {
 				&info.trie_id,
 				&val.0,
 				Some(val.2.clone()),
+				None,
 			).unwrap();
 		}
 
@@ -2345,6 +2203,7 @@ fn lazy_removal_partial_remove_works() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 
@@ -2392,14 +2251,15 @@ fn lazy_removal_partial_remove_works() {
 fn lazy_removal_does_no_run_on_full_block() {
 	let (code, hash) = compile_module::<Test>("self_destruct").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2407,7 +2267,7 @@ fn lazy_removal_does_no_run_on_full_block() {
 		);
 
 		let addr = Contracts::contract_address(&ALICE, &hash, &[]);
-		let info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
+		let info = <ContractInfoOf::<Test>>::get(&addr).unwrap();
 		let trie = &info.child_trie_info();
 		let max_keys = 30;
 
@@ -2424,6 +2284,7 @@ fn lazy_removal_does_no_run_on_full_block() {
 				&info.trie_id,
 				&val.0,
 				Some(val.2.clone()),
+				None,
 			).unwrap();
 		}
 
@@ -2433,6 +2294,7 @@ fn lazy_removal_does_no_run_on_full_block() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 
@@ -2476,14 +2338,15 @@ fn lazy_removal_does_no_run_on_full_block() {
 fn lazy_removal_does_not_use_all_weight() {
 	let (code, hash) = compile_module::<Test>("self_destruct").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2491,7 +2354,7 @@ fn lazy_removal_does_not_use_all_weight() {
 		);
 
 		let addr = Contracts::contract_address(&ALICE, &hash, &[]);
-		let info = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
+		let info = <ContractInfoOf::<Test>>::get(&addr).unwrap();
 		let trie = &info.child_trie_info();
 		let weight_limit = 5_000_000_000;
 		let (weight_per_key, max_keys) = Storage::<Test>::deletion_budget(1, weight_limit);
@@ -2509,6 +2372,7 @@ fn lazy_removal_does_not_use_all_weight() {
 				&info.trie_id,
 				&val.0,
 				Some(val.2.clone()),
+				None,
 			).unwrap();
 		}
 
@@ -2518,6 +2382,7 @@ fn lazy_removal_does_not_use_all_weight() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 
@@ -2546,14 +2411,15 @@ fn lazy_removal_does_not_use_all_weight() {
 fn deletion_queue_full() {
 	let (code, hash) = compile_module::<Test>("self_destruct").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let subsistence = Pallet::<Test>::subsistence_threshold();
-		let _ = Balances::deposit_creating(&ALICE, 1000 * subsistence);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
 
 		assert_ok!(
 			Contracts::instantiate_with_code(
 				Origin::signed(ALICE),
-				subsistence * 100,
+				min_balance * 100,
 				GAS_LIMIT,
+				None,
 				code,
 				vec![],
 				vec![],
@@ -2572,98 +2438,14 @@ fn deletion_queue_full() {
 				addr.clone(),
 				0,
 				GAS_LIMIT,
+				None,
 				vec![],
 			),
 			Error::<Test>::DeletionQueueFull,
 		);
 
 		// Contract should be alive because removal failed
-		<ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
-
-		// make the contract ripe for eviction
-		initialize_block(5);
-
-		// eviction should fail for the same reason as termination
-		assert_err!(
-			Contracts::claim_surcharge(Origin::none(), addr.clone(), Some(ALICE)),
-			Error::<Test>::DeletionQueueFull,
-		);
-
-		// Contract should be alive because removal failed
-		<ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
-	});
-}
-
-#[test]
-fn not_deployed_if_endowment_too_low_for_first_rent() {
-	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
-
-	// The instantiation deducted the rent for one block immediately
-	let first_rent = <Test as Config>::RentFraction::get()
-		// base_deposit + deploy_set_storage (4 bytes in 1 item) - free_balance
-		.mul_ceil(80_000u32 + 40_000 + 10_000 - 30_000)
-		// blocks to rent
-		* 1;
-
-	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		// Create
-		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-		assert_storage_noop!(assert_err_ignore_postinfo!(
-			Contracts::instantiate_with_code(
-				Origin::signed(ALICE),
-				30_000,
-				GAS_LIMIT,
-				wasm,
-				(BalanceOf::<Test>::from(first_rent) - BalanceOf::<Test>::from(1u32))
-					.encode(), // rent allowance
-				vec![],
-			),
-			Error::<Test>::NewContractNotFunded,
-		));
-		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-		assert_matches!(ContractInfoOf::<Test>::get(&addr), None);
-	});
-}
-
-#[test]
-fn surcharge_reward_is_capped() {
-	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
-	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
-		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-		assert_ok!(Contracts::instantiate_with_code(
-			Origin::signed(ALICE),
-			30_000,
-			GAS_LIMIT,
-			wasm,
-			<BalanceOf<Test>>::from(10_000u32).encode(), // rent allowance
-			vec![],
-		));
-		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
-		let contract = <ContractInfoOf::<Test>>::get(&addr).unwrap().get_alive().unwrap();
-		let balance = Balances::free_balance(&ALICE);
-		let reward = <Test as Config>::SurchargeReward::get();
-
-		// some rent should have payed due to instantiation
-		assert_ne!(contract.rent_payed, 0);
-
-		// the reward should be parameterized sufficiently high to make this test useful
-		assert!(reward > contract.rent_payed);
-
-		// make contract eligible for eviction
-		initialize_block(40);
-
-		// this should have removed the contract
-		assert_ok!(Contracts::claim_surcharge(Origin::none(), addr.clone(), Some(ALICE)));
-
-		// this reward does not take into account the last rent payment collected during eviction
-		let capped_reward = reward.min(contract.rent_payed);
-
-		// this is smaller than the actual reward because it does not take into account the
-		// rent collected during eviction
-		assert!(Balances::free_balance(&ALICE) > balance + capped_reward);
-
-		// the full reward is not payed out because of the cap introduced by rent_payed
-		assert!(Balances::free_balance(&ALICE) < balance + reward);
+		<ContractInfoOf::<Test>>::get(&addr).unwrap();
 	});
 }
 
@@ -2672,21 +2454,23 @@ fn refcounter() {
 	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-		let subsistence = Pallet::<Test>::subsistence_threshold();
+		let min_balance = <Test as Config>::Currency::minimum_balance();
 
 		// Create two contracts with the same code and check that they do in fact share it.
 		assert_ok!(Contracts::instantiate_with_code(
 			Origin::signed(ALICE),
-			subsistence * 100,
+			min_balance * 100,
 			GAS_LIMIT,
+			None,
 			wasm.clone(),
 			vec![],
 			vec![0],
 		));
 		assert_ok!(Contracts::instantiate_with_code(
 			Origin::signed(ALICE),
-			subsistence * 100,
+			min_balance * 100,
 			GAS_LIMIT,
+			None,
 			wasm.clone(),
 			vec![],
 			vec![1],
@@ -2696,8 +2480,9 @@ fn refcounter() {
 		// Sharing should also work with the usual instantiate call
 		assert_ok!(Contracts::instantiate(
 			Origin::signed(ALICE),
-			subsistence * 100,
+			min_balance * 100,
 			GAS_LIMIT,
+			None,
 			code_hash,
 			vec![],
 			vec![2],
@@ -2715,22 +2500,34 @@ fn refcounter() {
 			addr0,
 			0,
 			GAS_LIMIT,
+			None,
 			vec![],
 		));
 		assert_refcount!(code_hash, 2);
 
-		// make remaining contracts eligible for eviction
-		initialize_block(40);
-
 		// remove one of them
-		assert_ok!(Contracts::claim_surcharge(Origin::none(), addr1, Some(ALICE)));
+		assert_ok!(Contracts::call(
+			Origin::signed(ALICE),
+			addr1,
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+		));
 		assert_refcount!(code_hash, 1);
 
 		// Pristine code should still be there
 		crate::PristineCode::<Test>::get(code_hash).unwrap();
 
 		// remove the last contract
-		assert_ok!(Contracts::claim_surcharge(Origin::none(), addr2, Some(ALICE)));
+		assert_ok!(Contracts::call(
+			Origin::signed(ALICE),
+			addr2,
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+		));
 		assert_refcount!(code_hash, 0);
 
 		// all code should be gone
@@ -2744,14 +2541,15 @@ fn reinstrument_does_charge() {
 	let (wasm, code_hash) = compile_module::<Test>("return_with_data").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-		let subsistence = Pallet::<Test>::subsistence_threshold();
+		let min_balance = <Test as Config>::Currency::minimum_balance();
 		let zero = 0u32.to_le_bytes().encode();
 		let code_len = wasm.len() as u32;
 
 		assert_ok!(Contracts::instantiate_with_code(
 			Origin::signed(ALICE),
-			subsistence * 100,
+			min_balance * 100,
 			GAS_LIMIT,
+			None,
 			wasm,
 			zero.clone(),
 			vec![],
@@ -2766,7 +2564,9 @@ fn reinstrument_does_charge() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			zero.clone(),
+			false,
 		);
 		assert!(result0.result.unwrap().is_success());
 
@@ -2775,7 +2575,9 @@ fn reinstrument_does_charge() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			zero.clone(),
+			false,
 		);
 		assert!(result1.result.unwrap().is_success());
 
@@ -2793,7 +2595,9 @@ fn reinstrument_does_charge() {
 			addr.clone(),
 			0,
 			GAS_LIMIT,
+			None,
 			zero.clone(),
+			false,
 		);
 		assert!(result2.result.unwrap().is_success());
 		assert!(result2.gas_consumed > result1.gas_consumed);
@@ -2803,3 +2607,361 @@ fn reinstrument_does_charge() {
 		);
 	});
 }
+
+#[test]
+fn upload_code_works() {
+	let (wasm, code_hash) = compile_module::<Test>("return_with_data").unwrap();
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		// Drop previous events
+		initialize_block(2);
+
+		assert!(!<CodeStorage<Test>>::contains_key(code_hash));
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm, None));
+		let deposit = Balances::reserved_balance(&ALICE);
+		assert!(deposit > 0);
+		assert_refcount!(code_hash, 0);
+		assert_eq!(<OwnerInfoOf<Test>>::get(code_hash).unwrap().owner, ALICE);
+
+		assert_eq!(System::events(), vec![
+			EventRecord {
+				phase: Phase::Initialization,
+				event: Event::pallet_balances(pallet_balances::Event::Reserved(ALICE, deposit)),
+				topics: vec![],
+			},
+			EventRecord {
+				phase: Phase::Initialization,
+				event: Event::pallet_contracts(crate::Event::CodeStored(code_hash)),
+				topics: vec![],
+			},
+		]);
+	});
+}
+
+#[test]
+fn upload_code_twice_reserves_once() {
+	let (wasm, code_hash) = compile_module::<Test>("return_with_data").unwrap();
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let _ = Balances::deposit_creating(&BOB, 1_000_000);
+
+		let result = Contracts::bare_upload_code(ALICE, wasm.clone(), None).unwrap();
+		assert_eq!(result.code_hash, code_hash);
+		assert_eq!(Balances::reserved_balance(&ALICE), result.deposit);
+
+		// The code already has an owner: nothing is reserved from the second uploader.
+		let result = Contracts::bare_upload_code(BOB, wasm, None).unwrap();
+		assert_eq!(result.deposit, 0);
+		assert_eq!(Balances::reserved_balance(&BOB), 0);
+		assert_eq!(<OwnerInfoOf<Test>>::get(code_hash).unwrap().owner, ALICE);
+	});
+}
+
+#[test]
+fn upload_code_limit_too_low() {
+	let (wasm, code_hash) = compile_module::<Test>("return_with_data").unwrap();
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		assert_noop!(
+			Contracts::upload_code(Origin::signed(ALICE), wasm, Some(100)),
+			<Error<Test>>::StorageDepositLimitExhausted,
+		);
+		assert!(!<CodeStorage<Test>>::contains_key(code_hash));
+	});
+}
+
+#[test]
+fn upload_code_not_enough_balance() {
+	let (wasm, code_hash) = compile_module::<Test>("return_with_data").unwrap();
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 150);
+
+		assert_noop!(
+			Contracts::upload_code(Origin::signed(ALICE), wasm, None),
+			<Error<Test>>::StorageDepositNotEnoughFunds,
+		);
+		assert!(!<CodeStorage<Test>>::contains_key(code_hash));
+	});
+}
+
+#[test]
+fn remove_code_works() {
+	let (wasm, code_hash) = compile_module::<Test>("return_with_data").unwrap();
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm, None));
+
+		// Drop previous events
+		initialize_block(2);
+
+		let deposit = Balances::reserved_balance(&ALICE);
+		assert_ok!(Contracts::remove_code(Origin::signed(ALICE), code_hash));
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		assert!(!<CodeStorage<Test>>::contains_key(code_hash));
+		assert!(!<PristineCode<Test>>::contains_key(code_hash));
+		assert!(!<OwnerInfoOf<Test>>::contains_key(code_hash));
+
+		assert_eq!(System::events(), vec![
+			EventRecord {
+				phase: Phase::Initialization,
+				event: Event::pallet_balances(pallet_balances::Event::Unreserved(ALICE, deposit)),
+				topics: vec![],
+			},
+			EventRecord {
+				phase: Phase::Initialization,
+				event: Event::pallet_contracts(crate::Event::CodeRemoved(code_hash)),
+				topics: vec![],
+			},
+		]);
+	});
+}
+
+#[test]
+fn remove_code_wrong_origin() {
+	let (wasm, code_hash) = compile_module::<Test>("return_with_data").unwrap();
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm, None));
+
+		assert_noop!(
+			Contracts::remove_code(Origin::signed(BOB), code_hash),
+			sp_runtime::traits::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn remove_code_not_found() {
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		assert_noop!(
+			Contracts::remove_code(Origin::signed(ALICE), Default::default()),
+			<Error<Test>>::CodeNotFound,
+		);
+	});
+}
+
+#[test]
+fn remove_code_in_use() {
+	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+
+		assert_ok!(Contracts::upload_code(Origin::signed(ALICE), wasm, None));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			code_hash,
+			vec![],
+			vec![],
+		));
+		assert_refcount!(code_hash, 1);
+
+		assert_noop!(
+			Contracts::remove_code(Origin::signed(ALICE), code_hash),
+			<Error<Test>>::CodeInUse,
+		);
+
+		// Terminating the contract does not remove the uploaded code.
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		assert_ok!(Contracts::call(Origin::signed(ALICE), addr, 0, GAS_LIMIT, None, vec![]));
+		assert_refcount!(code_hash, 0);
+		assert!(<CodeStorage<Test>>::contains_key(code_hash));
+		assert!(<PristineCode<Test>>::contains_key(code_hash));
+
+		assert_ok!(Contracts::remove_code(Origin::signed(ALICE), code_hash));
+		assert!(!<CodeStorage<Test>>::contains_key(code_hash));
+	});
+}
+
+#[test]
+fn debug_message_works() {
+	let (wasm, code_hash) = compile_module::<Test>("debug_message").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			30_000,
+			GAS_LIMIT,
+			None,
+			wasm,
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let call = |input: &[u8], debug: bool| {
+			<Pallet<Test>>::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				input.to_vec(),
+				debug,
+			)
+		};
+
+		// Messages are collected when debugging is enabled.
+		let result = call(b"Hello World!", true);
+		assert_return_code!(result.result.unwrap(), RuntimeReturnCode::Success);
+		assert_eq!(std::str::from_utf8(&result.debug_message).unwrap(), "Hello World!");
+
+		// On-chain execution does not record any messages.
+		let result = call(b"Hello World!", false);
+		assert_return_code!(result.result.unwrap(), RuntimeReturnCode::LoggingDisabled);
+		assert!(result.debug_message.is_empty());
+
+		// Messages that would exceed `MaxDebugBufferLen` are dropped.
+		let result = call(&[b'a'; 200], true);
+		assert_return_code!(result.result.unwrap(), RuntimeReturnCode::Success);
+		assert!(result.debug_message.is_empty());
+
+		// Invalid UTF-8 traps the contract.
+		let result = call(&[0xfc], true);
+		assert_eq!(result.result, Err(<Error<Test>>::DebugMessageInvalidUTF8.into()));
+	});
+}
+
+#[test]
+fn reentrance_is_denied_without_flag() {
+	let (wasm, code_hash) = compile_module::<Test>("call_with_flags").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			30_000,
+			GAS_LIMIT,
+			None,
+			wasm,
+			vec![],
+			vec![],
+		));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			30_000,
+			GAS_LIMIT,
+			None,
+			code_hash,
+			vec![],
+			vec![1],
+		));
+		let addr_bob = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let addr_charlie = Contracts::contract_address(&ALICE, &code_hash, &[1]);
+
+		// Bob calls Charlie which calls back into Bob.
+		let input = |flags: CallFlags| {
+			let mut input = flags.bits().encode();
+			input.extend(addr_charlie.encode());
+			input.extend(CallFlags::ALLOW_REENTRY.bits().encode());
+			input.extend(addr_bob.encode());
+			input
+		};
+		let call = |input: Vec<u8>| {
+			Contracts::bare_call(ALICE, addr_bob.clone(), 0, GAS_LIMIT, None, input, false)
+				.result
+				.unwrap()
+		};
+
+		let result = call(input(CallFlags::ALLOW_REENTRY));
+		assert_return_code!(result, RuntimeReturnCode::Success);
+
+		// Charlie traps because it is not allowed to re-enter Bob.
+		let result = call(input(CallFlags::empty()));
+		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
+	});
+}
+
+#[test]
+fn caller_is_origin_works() {
+	let (wasm, code_hash) = compile_module::<Test>("caller_is_origin").unwrap();
+	let (caller_wasm, caller_hash) = compile_module::<Test>("call_with_flags").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			30_000,
+			GAS_LIMIT,
+			None,
+			wasm,
+			vec![],
+			vec![],
+		));
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			30_000,
+			GAS_LIMIT,
+			None,
+			caller_wasm,
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let caller_addr = Contracts::contract_address(&ALICE, &caller_hash, &[]);
+
+		// Called directly by the origin.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+			false,
+		).result.unwrap();
+		assert_eq!(result.data.0, 1u32.encode());
+
+		// Called by another contract that hands the output back to us with a tail call.
+		let mut input = CallFlags::TAIL_CALL.bits().encode();
+		input.extend(addr.encode());
+		let result = Contracts::bare_call(
+			ALICE,
+			caller_addr,
+			0,
+			GAS_LIMIT,
+			None,
+			input,
+			false,
+		).result.unwrap();
+		assert_eq!(result.data.0, 0u32.encode());
+	});
+}
+
+#[test]
+fn is_contract_works() {
+	let (wasm, code_hash) = compile_module::<Test>("is_contract").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			30_000,
+			GAS_LIMIT,
+			None,
+			wasm,
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let call = |input: Vec<u8>| {
+			Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, None, input, false)
+				.result
+				.unwrap()
+				.data
+				.0
+		};
+
+		assert_eq!(call(addr.encode()), 1u32.encode());
+		assert_eq!(call(ALICE.encode()), 0u32.encode());
+	});
+}
@@ -28,21 +28,27 @@
 //! Thus, before executing a contract it should be reinstrument with new schedule.
 
 use crate::{
-	CodeHash, CodeStorage, PristineCode, Schedule, Config, Error, Weight,
-	wasm::{prepare, PrefabWasmModule}, Pallet as Contracts, Event,
+	BalanceOf, CodeHash, CodeStorage, PristineCode, OwnerInfoOf, Schedule, Config, Error, Weight,
+	wasm::{prepare, OwnerInfo, PrefabWasmModule}, Pallet as Contracts, Event,
 	gas::{GasMeter, Token},
 	weights::WeightInfo,
 };
 use sp_core::crypto::UncheckedFrom;
-use frame_support::dispatch::DispatchError;
+use sp_runtime::traits::{Saturating, Zero};
+use frame_support::{
+	dispatch::{DispatchError, DispatchResult},
+	ensure,
+	traits::{Get, ReservableCurrency},
+};
 #[cfg(feature = "runtime-benchmarks")]
 pub use self::private::reinstrument as reinstrument;
 
 /// Put the instrumented module in storage.
 ///
 /// Increments the refcount of the in-storage `prefab_module` if it already exists in storage
-/// under the specified `code_hash`.
-pub fn store<T: Config>(mut prefab_module: PrefabWasmModule<T>)
+/// under the specified `code_hash`. When `instantiated` is `false` the refcount is left
+/// untouched and a newly stored module starts out with a refcount of zero.
+pub fn store<T: Config>(mut prefab_module: PrefabWasmModule<T>, instantiated: bool)
 where
 	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
 {
@@ -55,8 +61,13 @@ where
 	}
 	<CodeStorage<T>>::mutate(&code_hash, |existing| {
 		match existing {
-			Some(module) => increment_64(&mut module.refcount),
+			Some(module) => if instantiated {
+				increment_64(&mut module.refcount)
+			},
 			None => {
+				if !instantiated {
+					prefab_module.refcount = 0;
+				}
 				*existing = Some(prefab_module);
 				Contracts::<T>::deposit_event(Event::CodeStored(code_hash))
 			}
@@ -64,15 +75,67 @@ where
 	});
 }
 
+/// Store the module on behalf of `owner` and reserve a deposit for it.
+///
+/// The code is kept in storage until the owner removes it with [`try_remove`], regardless
+/// of its refcount. Returns the reserved deposit which is zero when the code already
+/// has an owner.
+pub fn upload<T: Config>(
+	prefab_module: PrefabWasmModule<T>,
+	owner: &T::AccountId,
+	storage_deposit_limit: Option<BalanceOf<T>>,
+) -> Result<BalanceOf<T>, DispatchError>
+where
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
+{
+	if <OwnerInfoOf<T>>::contains_key(&prefab_module.code_hash) {
+		return Ok(Zero::zero());
+	}
+	let bytes = prefab_module.original_code_len.saturating_add(prefab_module.code.len() as u32);
+	// One item for the pristine and one for the instrumented code.
+	let deposit = T::DepositPerStorageByte::get().saturating_mul(bytes.into())
+		.saturating_add(T::DepositPerStorageItem::get().saturating_mul(2u32.into()));
+	if let Some(limit) = storage_deposit_limit {
+		ensure!(deposit <= limit, Error::<T>::StorageDepositLimitExhausted);
+	}
+	T::Currency::reserve(owner, deposit)
+		.map_err(|_| Error::<T>::StorageDepositNotEnoughFunds)?;
+	<OwnerInfoOf<T>>::insert(
+		&prefab_module.code_hash,
+		OwnerInfo { owner: owner.clone(), deposit },
+	);
+	store(prefab_module, false);
+	Ok(deposit)
+}
+
+/// Remove the code uploaded by `origin` and refund its deposit.
+///
+/// Fails if `origin` is not the owner of the code or if the code is still in use.
+pub fn try_remove<T: Config>(origin: &T::AccountId, code_hash: CodeHash<T>) -> DispatchResult
+where
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
+{
+	let owner_info = <OwnerInfoOf<T>>::get(&code_hash).ok_or(Error::<T>::CodeNotFound)?;
+	ensure!(owner_info.owner == *origin, DispatchError::BadOrigin);
+	let refcount = <CodeStorage<T>>::get(&code_hash).map(|module| module.refcount).unwrap_or(0);
+	ensure!(refcount == 0, Error::<T>::CodeInUse);
+	T::Currency::unreserve(&owner_info.owner, owner_info.deposit);
+	<OwnerInfoOf<T>>::remove(&code_hash);
+	<CodeStorage<T>>::remove(&code_hash);
+	finish_removal::<T>(code_hash);
+	Ok(())
+}
+
 /// Decrement the refcount and store.
 ///
-/// Removes the code instead of storing it when the refcount drops to zero.
+/// Removes the code instead of storing it when the refcount drops to zero and
+/// the code has no owner.
 pub fn store_decremented<T: Config>(mut prefab_module: PrefabWasmModule<T>)
 where
 	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
 {
 	prefab_module.refcount = prefab_module.refcount.saturating_sub(1);
-	if prefab_module.refcount > 0 {
+	if prefab_module.refcount > 0 || <OwnerInfoOf<T>>::contains_key(&prefab_module.code_hash) {
 		<CodeStorage<T>>::insert(prefab_module.code_hash, prefab_module);
 	} else {
 		<CodeStorage<T>>::remove(prefab_module.code_hash);
@@ -95,7 +158,9 @@ where
 	})
 }
 
-/// Decrement the refcount of a code in-storage by one and remove the code when it drops to zero.
+/// Decrement the refcount of a code in-storage by one.
+///
+/// The code is removed when the refcount drops to zero unless it has an owner.
 pub fn decrement_refcount<T: Config>(code_hash: CodeHash<T>) -> u32
 where
 	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
@@ -104,7 +169,7 @@ where
 		if let Some(module) = existing {
 			let code_len = module.original_code_len;
 			module.refcount = module.refcount.saturating_sub(1);
-			if module.refcount == 0 {
+			if module.refcount == 0 && !<OwnerInfoOf<T>>::contains_key(&code_hash) {
 				*existing = None;
 				finish_removal::<T>(code_hash);
 			}
@@ -25,7 +25,7 @@ mod prepare;
 mod runtime;
 
 use crate::{
-	CodeHash, Schedule, Config,
+	BalanceOf, CodeHash, Schedule, Config,
 	wasm::env_def::FunctionImplProvider,
 	exec::{Ext, Executable, ExportedFunction, ExecResult},
 	gas::GasMeter,
@@ -33,8 +33,8 @@ use crate::{
 use sp_std::prelude::*;
 use sp_core::crypto::UncheckedFrom;
 use codec::{Encode, Decode};
-use frame_support::dispatch::DispatchError;
-pub use self::runtime::{ReturnCode, Runtime, RuntimeToken};
+use frame_support::dispatch::{DispatchError, DispatchResult};
+pub use self::runtime::{CallFlags, ReturnCode, Runtime, RuntimeToken};
 #[cfg(feature = "runtime-benchmarks")]
 pub use self::code_cache::reinstrument;
 #[cfg(test)]
@@ -79,7 +79,7 @@ pub struct PrefabWasmModule<T: Config> {
 	/// The size of the uninstrumented code.
 	///
 	/// We cache this value here in order to avoid the need to pull the pristine code
-	/// from storage when we only need its length for rent calculations.
+	/// from storage when we only need its length for weight calculations.
 	original_code_len: u32,
 	/// The uninstrumented, pristine version of the code.
 	///
@@ -96,6 +96,18 @@ pub struct PrefabWasmModule<T: Config> {
 	code_hash: CodeHash<T>,
 }
 
+/// Information about the account that uploaded a code with `upload_code`.
+///
+/// A code with an owner is never removed implicitly when its refcount drops to zero.
+/// Only the owner can remove it which refunds the deposit.
+#[derive(Clone, Encode, Decode)]
+pub struct OwnerInfo<T: Config> {
+	/// The account that uploaded the code and paid the deposit.
+	pub owner: T::AccountId,
+	/// The balance that is reserved from `owner` for storing the code.
+	pub deposit: BalanceOf<T>,
+}
+
 impl ExportedFunction {
 	/// The wasm export name for the function.
 	fn identifier(&self) -> &str {
@@ -118,6 +130,22 @@ where
 		prepare::prepare_contract(original_code, schedule).map_err(Into::into)
 	}
 
+	/// Store the module without instantiating it and reserve a deposit from `owner`.
+	///
+	/// Returns the reserved deposit which is zero when the code already has an owner.
+	pub fn upload(
+		self,
+		owner: &T::AccountId,
+		storage_deposit_limit: Option<BalanceOf<T>>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		code_cache::upload(self, owner, storage_deposit_limit)
+	}
+
+	/// Remove the code uploaded by `origin` from storage and refund its deposit.
+	pub fn remove(origin: &T::AccountId, code_hash: CodeHash<T>) -> DispatchResult {
+		code_cache::try_remove::<T>(origin, code_hash)
+	}
+
 	/// Create and store the module without checking nor instrumenting the passed code.
 	///
 	/// # Note
@@ -131,7 +159,7 @@ where
 	) -> Result<(), DispatchError> {
 		let executable = prepare::benchmarking::prepare_contract(original_code, schedule)
 			.map_err::<DispatchError, _>(Into::into)?;
-		code_cache::store(executable);
+		code_cache::store(executable, true);
 		Ok(())
 	}
 
@@ -205,7 +233,7 @@ where
 		// We store before executing so that the code hash is available in the constructor.
 		let code = self.code.clone();
 		if let &ExportedFunction::Constructor = function {
-			code_cache::store(self)
+			code_cache::store(self, true)
 		}
 
 		// Instantiate the instance from the instrumented module code and invoke the contract
@@ -220,24 +248,9 @@ where
 		&self.code_hash
 	}
 
-	fn occupied_storage(&self) -> u32 {
-		// We disregard the size of the struct itself as the size is completely
-		// dominated by the code size.
-		let len = self.aggregate_code_len();
-		len.checked_div(self.refcount as u32).unwrap_or(len)
-	}
-
 	fn code_len(&self) -> u32 {
 		self.code.len() as u32
 	}
-
-	fn aggregate_code_len(&self) -> u32 {
-		self.original_code_len.saturating_add(self.code_len())
-	}
-
-	fn refcount(&self) -> u32 {
-		self.refcount as u32
-	}
 }
 
 #[cfg(test)]
@@ -247,12 +260,12 @@ mod tests {
 		CodeHash, BalanceOf, Error, Pallet as Contracts,
 		exec::{
 			Ext, StorageKey, AccountIdOf, Executable, SeedOf, BlockNumberOf,
-			RentParams, ExecError, ErrorOrigin,
+			ExecError, ErrorOrigin,
 		},
 		gas::GasMeter,
 		tests::{Test, Call, ALICE, BOB},
 	};
-	use std::collections::HashMap;
+	use std::{cell::RefCell, collections::HashMap};
 	use sp_core::{Bytes, H256};
 	use hex_literal::hex;
 	use sp_runtime::DispatchError;
@@ -266,14 +279,6 @@ mod tests {
 	#[derive(Debug, PartialEq, Eq)]
 	struct DispatchEntry(Call);
 
-	#[derive(Debug, PartialEq, Eq)]
-	struct RestoreEntry {
-		dest: AccountIdOf<Test>,
-		code_hash: H256,
-		rent_allowance: u64,
-		delta: Vec<StorageKey>,
-	}
-
 	#[derive(Debug, PartialEq, Eq)]
 	struct InstantiateEntry {
 		code_hash: H256,
@@ -295,18 +300,35 @@ mod tests {
 		data: Vec<u8>,
 	}
 
+	#[derive(Debug, PartialEq, Eq)]
+	struct CallEntry {
+		to: AccountIdOf<Test>,
+		value: u64,
+		data: Vec<u8>,
+		allows_reentry: bool,
+	}
+
+	#[derive(Debug, PartialEq, Eq)]
+	struct DelegateCallEntry {
+		code_hash: H256,
+		data: Vec<u8>,
+	}
+
 	#[derive(Default)]
 	pub struct MockExt {
 		storage: HashMap<StorageKey, Vec<u8>>,
-		rent_allowance: u64,
 		instantiates: Vec<InstantiateEntry>,
 		terminations: Vec<TerminationEntry>,
 		transfers: Vec<TransferEntry>,
-		restores: Vec<RestoreEntry>,
+		calls: Vec<CallEntry>,
+		delegate_calls: Vec<DelegateCallEntry>,
+		code_hashes: Vec<H256>,
 		// (topics, data)
 		events: Vec<(Vec<H256>, Vec<u8>)>,
 		schedule: Schedule<Test>,
-		rent_params: RentParams<Test>,
+		// (signature, message_hash)
+		ecdsa_recover: RefCell<Vec<([u8; 65], [u8; 32])>>,
+		debug_buffer: Vec<u8>,
 	}
 
 	impl Ext for MockExt {
@@ -361,16 +383,43 @@ mod tests {
 			value: u64,
 			_gas_meter: &mut GasMeter<Test>,
 			data: Vec<u8>,
+			allows_reentry: bool,
 		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
-			self.transfers.push(TransferEntry {
+			self.calls.push(CallEntry {
 				to: to.clone(),
 				value,
-				data: data,
+				data: data.clone(),
+				allows_reentry,
+			});
+			// The callee echoes its input so that forwarded input can be observed.
+			Ok((ExecReturnValue { flags: ReturnFlags::empty(), data: Bytes(data) }, 0))
+		}
+		fn delegate_call(
+			&mut self,
+			code_hash: CodeHash<Test>,
+			_gas_meter: &mut GasMeter<Test>,
+			data: Vec<u8>,
+		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+			self.delegate_calls.push(DelegateCallEntry {
+				code_hash,
+				data,
 			});
-			// Assume for now that it was just a plain transfer.
-			// TODO: Add tests for different call outcomes.
 			Ok((ExecReturnValue { flags: ReturnFlags::empty(), data: Bytes(Vec::new()) }, 0))
 		}
+		fn set_code_hash(&mut self, hash: CodeHash<Test>) -> DispatchResult {
+			self.code_hashes.push(hash);
+			Ok(())
+		}
+		fn code_hash(&self, address: &AccountIdOf<Self::T>) -> Option<CodeHash<Test>> {
+			if address == &BOB {
+				Some(H256::repeat_byte(0x11))
+			} else {
+				None
+			}
+		}
+		fn own_code_hash(&self) -> CodeHash<Test> {
+			H256::repeat_byte(0x11)
+		}
 		fn terminate(
 			&mut self,
 			beneficiary: &AccountIdOf<Self::T>,
@@ -380,24 +429,15 @@ mod tests {
 			});
 			Ok(0)
 		}
-		fn restore_to(
-			&mut self,
-			dest: AccountIdOf<Self::T>,
-			code_hash: H256,
-			rent_allowance: u64,
-			delta: Vec<StorageKey>,
-		) -> Result<(u32, u32), (DispatchError, u32, u32)> {
-			self.restores.push(RestoreEntry {
-				dest,
-				code_hash,
-				rent_allowance,
-				delta,
-			});
-			Ok((0, 0))
-		}
 		fn caller(&self) -> &AccountIdOf<Self::T> {
 			&ALICE
 		}
+		fn caller_is_origin(&self) -> bool {
+			false
+		}
+		fn is_contract(&self, _address: &AccountIdOf<Self::T>) -> bool {
+			true
+		}
 		fn address(&self) -> &AccountIdOf<Self::T> {
 			&BOB
 		}
@@ -413,7 +453,7 @@ mod tests {
 		fn minimum_balance(&self) -> u64 {
 			666
 		}
-		fn tombstone_deposit(&self) -> u64 {
+		fn contract_deposit(&self) -> u64 {
 			16
 		}
 		fn random(&self, subject: &[u8]) -> (SeedOf<Self::T>, BlockNumberOf<Self::T>) {
@@ -422,12 +462,6 @@ mod tests {
 		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) {
 			self.events.push((topics, data))
 		}
-		fn set_rent_allowance(&mut self, rent_allowance: u64) {
-			self.rent_allowance = rent_allowance;
-		}
-		fn rent_allowance(&self) -> u64 {
-			self.rent_allowance
-		}
 		fn block_number(&self) -> u64 { 121 }
 		fn max_value_size(&self) -> u32 { 16_384 }
 		fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
@@ -436,8 +470,26 @@ mod tests {
 		fn schedule(&self) -> &Schedule<Self::T> {
 			&self.schedule
 		}
-		fn rent_params(&self) -> &RentParams<Self::T> {
-			&self.rent_params
+		fn ecdsa_recover(
+			&self,
+			signature: &[u8; 65],
+			message_hash: &[u8; 32],
+		) -> Result<[u8; 33], ()> {
+			self.ecdsa_recover.borrow_mut().push((*signature, *message_hash));
+			Ok([3; 33])
+		}
+		fn ecdsa_to_eth_address(&self, _pk: &[u8; 33]) -> Result<[u8; 20], ()> {
+			Ok([2; 20])
+		}
+		fn sr25519_verify(&self, _sig: &[u8; 64], _msg: &[u8], _pub_key: &[u8; 32]) -> bool {
+			true
+		}
+		fn ed25519_verify(&self, _sig: &[u8; 64], _msg: &[u8], _pub_key: &[u8; 32]) -> bool {
+			true
+		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			self.debug_buffer.extend(msg.as_bytes());
+			true
 		}
 	}
 
@@ -479,26 +531,36 @@ mod tests {
 			value: u64,
 			gas_meter: &mut GasMeter<Test>,
 			input_data: Vec<u8>,
+			allows_reentry: bool,
 		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
-			(**self).call(to, value, gas_meter, input_data)
+			(**self).call(to, value, gas_meter, input_data, allows_reentry)
 		}
-		fn restore_to(
+		fn delegate_call(
 			&mut self,
-			dest: AccountIdOf<Self::T>,
-			code_hash: H256,
-			rent_allowance: u64,
-			delta: Vec<StorageKey>,
-		) -> Result<(u32, u32), (DispatchError, u32, u32)> {
-			(**self).restore_to(
-				dest,
-				code_hash,
-				rent_allowance,
-				delta,
-			)
+			code_hash: CodeHash<Test>,
+			gas_meter: &mut GasMeter<Test>,
+			input_data: Vec<u8>,
+		) -> Result<(ExecReturnValue, u32), (ExecError, u32)> {
+			(**self).delegate_call(code_hash, gas_meter, input_data)
+		}
+		fn set_code_hash(&mut self, hash: CodeHash<Test>) -> DispatchResult {
+			(**self).set_code_hash(hash)
+		}
+		fn code_hash(&self, address: &AccountIdOf<Self::T>) -> Option<CodeHash<Test>> {
+			(**self).code_hash(address)
+		}
+		fn own_code_hash(&self) -> CodeHash<Test> {
+			(**self).own_code_hash()
 		}
 		fn caller(&self) -> &AccountIdOf<Self::T> {
 			(**self).caller()
 		}
+		fn caller_is_origin(&self) -> bool {
+			(**self).caller_is_origin()
+		}
+		fn is_contract(&self, address: &AccountIdOf<Self::T>) -> bool {
+			(**self).is_contract(address)
+		}
 		fn address(&self) -> &AccountIdOf<Self::T> {
 			(**self).address()
 		}
@@ -514,8 +576,8 @@ mod tests {
 		fn minimum_balance(&self) -> u64 {
 			(**self).minimum_balance()
 		}
-		fn tombstone_deposit(&self) -> u64 {
-			(**self).tombstone_deposit()
+		fn contract_deposit(&self) -> u64 {
+			(**self).contract_deposit()
 		}
 		fn random(&self, subject: &[u8]) -> (SeedOf<Self::T>, BlockNumberOf<Self::T>) {
 			(**self).random(subject)
@@ -523,12 +585,6 @@ mod tests {
 		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) {
 			(**self).deposit_event(topics, data)
 		}
-		fn set_rent_allowance(&mut self, rent_allowance: u64) {
-			(**self).set_rent_allowance(rent_allowance)
-		}
-		fn rent_allowance(&self) -> u64 {
-			(**self).rent_allowance()
-		}
 		fn block_number(&self) -> u64 {
 			(**self).block_number()
 		}
@@ -541,8 +597,24 @@ mod tests {
 		fn schedule(&self) -> &Schedule<Self::T> {
 			(**self).schedule()
 		}
-		fn rent_params(&self) -> &RentParams<Self::T> {
-			(**self).rent_params()
+		fn ecdsa_recover(
+			&self,
+			signature: &[u8; 65],
+			message_hash: &[u8; 32],
+		) -> Result<[u8; 33], ()> {
+			(**self).ecdsa_recover(signature, message_hash)
+		}
+		fn ecdsa_to_eth_address(&self, pk: &[u8; 33]) -> Result<[u8; 20], ()> {
+			(**self).ecdsa_to_eth_address(pk)
+		}
+		fn sr25519_verify(&self, sig: &[u8; 64], msg: &[u8], pub_key: &[u8; 32]) -> bool {
+			(**self).sr25519_verify(sig, msg, pub_key)
+		}
+		fn ed25519_verify(&self, sig: &[u8; 64], msg: &[u8], pub_key: &[u8; 32]) -> bool {
+			(**self).ed25519_verify(sig, msg, pub_key)
+		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			(**self).append_debug_buffer(msg)
 		}
 	}
 
@@ -673,15 +745,281 @@ mod tests {
 		));
 
 		assert_eq!(
-			&mock_ext.transfers,
-			&[TransferEntry {
+			&mock_ext.calls,
+			&[CallEntry {
+				to: ALICE,
+				value: 6,
+				data: vec![1, 2, 3, 4],
+				allows_reentry: true,
+			}]
+		);
+	}
+
+	/// Calls `ALICE` with the new version of `seal_call` using the supplied `flags`.
+	///
+	/// Afterwards the input is read with `seal_input` and returned followed by the
+	/// output of the call.
+	fn code_call_flags(flags: u32) -> String {
+		format!(r#"
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i32 i64 i32 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) size of the input buffer
+	(data (i32.const 0) "\04")
+
+	;; [4, 8) input buffer
+
+	;; [8, 12) output buffer
+
+	;; [12, 16) size of the output buffer
+	(data (i32.const 12) "\04")
+
+	;; [16, 48) callee (ALICE)
+	(data (i32.const 16)
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+	)
+
+	;; [48, 56) value to transfer
+	(data (i32.const 48) "\06\00\00\00\00\00\00\00")
+
+	;; [56, 60) input data of the call when the input is not forwarded
+	(data (i32.const 56) "\01\02\03\04")
+
+	(func (export "call")
+		(drop
+			(call $seal_call
+				(i32.const {}) ;; Flags
+				(i32.const 16) ;; Pointer to "callee" address.
+				(i32.const 32) ;; Length of "callee" address.
+				(i64.const 0) ;; How much gas to devote for the execution. 0 = all.
+				(i32.const 48) ;; Pointer to the buffer with value to transfer
+				(i32.const 8) ;; Length of the buffer with value to transfer.
+				(i32.const 56) ;; Pointer to input data buffer address
+				(i32.const 4) ;; Length of input data buffer
+				(i32.const 8) ;; Pointer to output buffer
+				(i32.const 12) ;; Pointer to output buffer length
+			)
+		)
+
+		;; Traps when the input was consumed by the call.
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		;; Return the input followed by the output of the call.
+		(call $seal_return (i32.const 0) (i32.const 4) (i32.const 8))
+	)
+	(func (export "deploy"))
+)
+"#, flags)
+	}
+
+	#[test]
+	fn contract_call_deny_reentry() {
+		let mut mock_ext = MockExt::default();
+		let result = execute(
+			&code_call_flags(0),
+			vec![5, 6, 7, 8],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(result.data.0, vec![5, 6, 7, 8, 1, 2, 3, 4]);
+		assert_eq!(
+			&mock_ext.calls,
+			&[CallEntry {
 				to: ALICE,
 				value: 6,
 				data: vec![1, 2, 3, 4],
+				allows_reentry: false,
 			}]
 		);
 	}
 
+	#[test]
+	fn contract_call_clone_input() {
+		let mut mock_ext = MockExt::default();
+		let flags = CallFlags::CLONE_INPUT | CallFlags::ALLOW_REENTRY;
+		let result = execute(
+			&code_call_flags(flags.bits()),
+			vec![5, 6, 7, 8],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(result.data.0, vec![5, 6, 7, 8, 5, 6, 7, 8]);
+		assert_eq!(
+			&mock_ext.calls,
+			&[CallEntry {
+				to: ALICE,
+				value: 6,
+				data: vec![5, 6, 7, 8],
+				allows_reentry: true,
+			}]
+		);
+	}
+
+	#[test]
+	fn contract_call_forward_input_consumes_input() {
+		let mut mock_ext = MockExt::default();
+		let result = execute(
+			&code_call_flags(CallFlags::FORWARD_INPUT.bits()),
+			vec![5, 6, 7, 8],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		);
+
+		assert_eq!(
+			result,
+			Err(ExecError {
+				error: Error::<Test>::InputAlreadyRead.into(),
+				origin: ErrorOrigin::Caller,
+			})
+		);
+		assert_eq!(mock_ext.calls[0].data, vec![5, 6, 7, 8]);
+	}
+
+	#[test]
+	fn contract_call_tail_call() {
+		let mut mock_ext = MockExt::default();
+		let flags = CallFlags::FORWARD_INPUT | CallFlags::TAIL_CALL;
+		let result = execute(
+			&code_call_flags(flags.bits()),
+			vec![5, 6, 7, 8],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		// The output of the callee is returned and the code after the call is never reached.
+		assert_eq!(result.data.0, vec![5, 6, 7, 8]);
+		assert_eq!(mock_ext.calls.len(), 1);
+	}
+
+	#[test]
+	fn contract_call_invalid_flags() {
+		let mut mock_ext = MockExt::default();
+		let result = execute(
+			&code_call_flags(0b1_0000),
+			vec![5, 6, 7, 8],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		);
+
+		assert_eq!(
+			result,
+			Err(ExecError {
+				error: Error::<Test>::InvalidCallFlags.into(),
+				origin: ErrorOrigin::Caller,
+			})
+		);
+		assert!(mock_ext.calls.is_empty());
+	}
+
+	const CODE_DELEGATE_CALL: &str = r#"
+(module
+	;; seal_delegate_call(
+	;;    code_hash_ptr: u32,
+	;;    code_hash_len: u32,
+	;;    input_data_ptr: u32,
+	;;    input_data_len: u32,
+	;;    output_ptr: u32,
+	;;    output_len_ptr: u32
+	;;) -> u32
+	(import "seal0" "seal_delegate_call" (func $seal_delegate_call (param i32 i32 i32 i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+	(func (export "call")
+		(drop
+			(call $seal_delegate_call
+				(i32.const 4)  ;; Pointer to "code_hash".
+				(i32.const 32)  ;; Length of "code_hash".
+				(i32.const 36) ;; Pointer to input data buffer address
+				(i32.const 4)  ;; Length of input data buffer
+				(i32.const 4294967295) ;; u32 max value is the sentinel value: do not copy output
+				(i32.const 0) ;; Length is ignored in this case
+			)
+		)
+	)
+	(func (export "deploy"))
+
+	;; Code hash to execute
+	(data (i32.const 4)
+		"\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11"
+		"\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11\11"
+	)
+
+	(data (i32.const 36) "\01\02\03\04")
+)
+"#;
+
+	#[test]
+	fn contract_delegate_call() {
+		let mut mock_ext = MockExt::default();
+		assert_ok!(execute(
+			CODE_DELEGATE_CALL,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		));
+
+		assert_eq!(
+			&mock_ext.delegate_calls,
+			&[DelegateCallEntry {
+				code_hash: H256::repeat_byte(0x11),
+				data: vec![1, 2, 3, 4],
+			}]
+		);
+	}
+
+	const CODE_SET_CODE_HASH: &str = r#"
+(module
+	(import "seal0" "seal_set_code_hash" (func $seal_set_code_hash (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		;; assert that the call succeeded
+		(call $assert
+			(i32.eqz
+				(call $seal_set_code_hash
+					(i32.const 0)  ;; Pointer to the new code hash
+					(i32.const 32) ;; Length of the new code hash
+				)
+			)
+		)
+	)
+	(func (export "deploy"))
+
+	;; New code hash
+	(data (i32.const 0)
+		"\22\22\22\22\22\22\22\22\22\22\22\22\22\22\22\22"
+		"\22\22\22\22\22\22\22\22\22\22\22\22\22\22\22\22"
+	)
+)
+"#;
+
+	#[test]
+	fn set_code_hash() {
+		let mut mock_ext = MockExt::default();
+		assert_ok!(execute(
+			CODE_SET_CODE_HASH,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		));
+
+		assert_eq!(&mock_ext.code_hashes, &[H256::repeat_byte(0x22)]);
+	}
+
 	const CODE_INSTANTIATE: &str = r#"
 (module
 	;; seal_instantiate(
@@ -861,11 +1199,12 @@ mod tests {
 		));
 
 		assert_eq!(
-			&mock_ext.transfers,
-			&[TransferEntry {
+			&mock_ext.calls,
+			&[CallEntry {
 				to: ALICE,
 				value: 6,
 				data: vec![1, 2, 3, 4],
+				allows_reentry: true,
 			}]
 		);
 	}
@@ -1007,6 +1346,47 @@ mod tests {
 		));
 	}
 
+	/// calls `seal_caller_is_origin` and `seal_is_contract` and returns both results.
+	const CODE_CALLER_IS_ORIGIN_IS_CONTRACT: &str = r#"
+(module
+	(import "seal0" "seal_caller_is_origin" (func $seal_caller_is_origin (result i32)))
+	(import "seal0" "seal_is_contract" (func $seal_is_contract (param i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) result of seal_caller_is_origin
+	;; [4, 8) result of seal_is_contract
+
+	;; [8, 40) address to check (BOB)
+	(data (i32.const 8)
+		"\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02"
+		"\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02"
+	)
+
+	(func (export "call")
+		(i32.store (i32.const 0) (call $seal_caller_is_origin))
+		(i32.store (i32.const 4)
+			(call $seal_is_contract (i32.const 8) (i32.const 32))
+		)
+		(call $seal_return (i32.const 0) (i32.const 0) (i32.const 8))
+	)
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn caller_is_origin_and_is_contract() {
+		let output = execute(
+			CODE_CALLER_IS_ORIGIN_IS_CONTRACT,
+			vec![],
+			MockExt::default(),
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		// The mock reports that the caller is not the origin but that `BOB` is a contract.
+		assert_eq!(output.data.0, [0u32.to_le_bytes(), 1u32.to_le_bytes()].concat());
+	}
+
 	/// calls `seal_address` and compares the result with the constant 69.
 	const CODE_ADDRESS: &str = r#"
 (module
@@ -1060,6 +1440,128 @@ mod tests {
 		));
 	}
 
+	const CODE_CODE_HASH: &str = r#"
+(module
+	(import "seal0" "seal_code_hash" (func $seal_code_hash (param i32 i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; size of our buffer is 32 bytes
+	(data (i32.const 64) "\20")
+
+	;; Address of "BOB"
+	(data (i32.const 68)
+		"\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02"
+		"\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02\02"
+	)
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		;; fill the buffer with the code hash of "BOB" and assert success
+		(call $assert
+			(i32.eqz
+				(call $seal_code_hash (i32.const 68) (i32.const 32) (i32.const 0) (i32.const 64))
+			)
+		)
+
+		;; assert size == 32
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 64))
+				(i32.const 32)
+			)
+		)
+
+		;; assert that the first 8 bytes are the beginning of the code hash
+		(call $assert
+			(i64.eq
+				(i64.load (i32.const 0))
+				(i64.const 0x1111111111111111)
+			)
+		)
+
+		;; assert that there is no code hash for a non contract address
+		(call $assert
+			(i32.eq
+				(call $seal_code_hash (i32.const 0) (i32.const 32) (i32.const 0) (i32.const 64))
+				(i32.const 3) ;; ReturnCode::KeyNotFound
+			)
+		)
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn code_hash() {
+		assert_ok!(execute(
+			CODE_CODE_HASH,
+			vec![],
+			MockExt::default(),
+			&mut GasMeter::new(GAS_LIMIT),
+		));
+	}
+
+	const CODE_OWN_CODE_HASH: &str = r#"
+(module
+	(import "seal0" "seal_own_code_hash" (func $seal_own_code_hash (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; size of our buffer is 32 bytes
+	(data (i32.const 32) "\20")
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		;; fill the buffer with the code hash of the current contract.
+		(call $seal_own_code_hash (i32.const 0) (i32.const 32))
+
+		;; assert size == 32
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 32))
+				(i32.const 32)
+			)
+		)
+
+		;; assert that the first 8 bytes are the beginning of the code hash
+		(call $assert
+			(i64.eq
+				(i64.load (i32.const 0))
+				(i64.const 0x1111111111111111)
+			)
+		)
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn own_code_hash() {
+		assert_ok!(execute(
+			CODE_OWN_CODE_HASH,
+			vec![],
+			MockExt::default(),
+			&mut GasMeter::new(GAS_LIMIT),
+		));
+	}
+
 	const CODE_BALANCE: &str = r#"
 (module
 	(import "seal0" "seal_balance" (func $seal_balance (param i32 i32)))
@@ -1164,6 +1666,123 @@ mod tests {
 		));
 	}
 
+	const CODE_ECDSA_RECOVER: &str = r#"
+(module
+	;; seal_ecdsa_recover(
+	;;    signature_ptr: u32,
+	;;    message_hash_ptr: u32,
+	;;    output_ptr: u32
+	;; ) -> u32
+	(import "seal0" "seal_ecdsa_recover" (func $seal_ecdsa_recover (param i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+	(func (export "call")
+		(drop
+			(call $seal_ecdsa_recover
+				(i32.const 36) ;; Pointer to signature.
+				(i32.const 4)  ;; Pointer to message hash.
+				(i32.const 36) ;; Pointer for output - public key.
+			)
+		)
+	)
+	(func (export "deploy"))
+
+	;; Hash of message.
+	(data (i32.const 4)
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+	)
+	;; Signature
+	(data (i32.const 36)
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+		"\01"
+	)
+)
+"#;
+
+	#[test]
+	fn contract_ecdsa_recover() {
+		let mut mock_ext = MockExt::default();
+		assert_ok!(execute(
+			CODE_ECDSA_RECOVER,
+			vec![],
+			&mut mock_ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		));
+		assert_eq!(mock_ext.ecdsa_recover.into_inner(), [([1; 65], [1; 32])]);
+	}
+
+	const CODE_DEBUG_MESSAGE: &str = r#"
+(module
+	(import "seal0" "seal_debug_message" (func $seal_debug_message (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(data (i32.const 0) "Hello World!")
+
+	(func (export "call")
+		(call $seal_debug_message
+			(i32.const 0)	;; Pointer to the text buffer
+			(i32.const 12)	;; The size of the buffer
+		)
+		drop
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn debug_message_works() {
+		let mut ext = MockExt::default();
+		execute(
+			CODE_DEBUG_MESSAGE,
+			vec![],
+			&mut ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(std::str::from_utf8(&ext.debug_buffer).unwrap(), "Hello World!");
+	}
+
+	const CODE_DEBUG_MESSAGE_FAIL: &str = r#"
+(module
+	(import "seal0" "seal_debug_message" (func $seal_debug_message (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(data (i32.const 0) "\fc")
+
+	(func (export "call")
+		(call $seal_debug_message
+			(i32.const 0)	;; Pointer to the text buffer
+			(i32.const 1)	;; The size of the buffer
+		)
+		drop
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn debug_message_invalid_utf8_fails() {
+		let mut ext = MockExt::default();
+		let result = execute(
+			CODE_DEBUG_MESSAGE_FAIL,
+			vec![],
+			&mut ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		);
+		assert_eq!(
+			result,
+			Err(ExecError {
+				error: Error::<Test>::DebugMessageInvalidUTF8.into(),
+				origin: ErrorOrigin::Caller,
+			})
+		);
+	}
+
 	const CODE_GAS_LEFT: &str = r#"
 (module
 	(import "seal0" "seal_gas_left" (func $seal_gas_left (param i32 i32)))
@@ -1944,28 +2563,38 @@ mod tests {
 		);
 	}
 
-	const CODE_RENT_PARAMS: &str = r#"
+	const CODE_RENT_ALLOWANCE: &str = r#"
 (module
-	(import "seal0" "seal_rent_params" (func $seal_rent_params (param i32 i32)))
+	(import "seal0" "seal_set_rent_allowance" (func $seal_set_rent_allowance (param i32 i32)))
+	(import "seal0" "seal_rent_allowance" (func $seal_rent_allowance (param i32 i32)))
 	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
 	(import "env" "memory" (memory 1 1))
 
 	;; [0, 4) buffer size = 128 bytes
 	(data (i32.const 0) "\80")
 
-	;; [4; inf) buffer where the result is copied
+	;; [4, 12) rent allowance to set
+	(data (i32.const 4) "\2A")
+
+	;; [12; inf) buffer where the result is copied
 
 	(func (export "call")
-		;; Load the rent params into memory
-		(call $seal_rent_params
-			(i32.const 4)		;; Pointer to the output buffer
+		;; Setting the rent allowance is a no-op
+		(call $seal_set_rent_allowance
+			(i32.const 4)		;; Pointer to the value
+			(i32.const 8)		;; Length of the value
+		)
+
+		;; Load the rent allowance into memory
+		(call $seal_rent_allowance
+			(i32.const 12)		;; Pointer to the output buffer
 			(i32.const 0)		;; Pointer to the size of the buffer
 		)
 
 		;; Return the contents of the buffer
 		(call $seal_return
 			(i32.const 0)				;; return flags
-			(i32.const 4)				;; buffer pointer
+			(i32.const 12)				;; buffer pointer
 			(i32.load (i32.const 0))	;; buffer size
 		)
 	)
@@ -1975,14 +2604,14 @@ mod tests {
 "#;
 
 	#[test]
-	fn rent_params_work() {
+	fn rent_allowance_is_max_value() {
 		let output = execute(
-			CODE_RENT_PARAMS,
+			CODE_RENT_ALLOWANCE,
 			vec![],
 			MockExt::default(),
 			&mut GasMeter::new(GAS_LIMIT),
 		).unwrap();
-		let rent_params = Bytes(<RentParams<Test>>::default().encode());
-		assert_eq!(output, ExecReturnValue { flags: ReturnFlags::empty(), data: rent_params });
+		let rent_allowance = Bytes(<BalanceOf<Test>>::max_value().encode());
+		assert_eq!(output, ExecReturnValue { flags: ReturnFlags::empty(), data: rent_allowance });
 	}
 }
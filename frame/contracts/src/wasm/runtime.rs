@@ -28,7 +28,7 @@ use parity_wasm::elements::ValueType;
 use frame_support::{dispatch::DispatchError, ensure, traits::Get, weights::Weight};
 use sp_std::prelude::*;
 use codec::{Decode, DecodeAll, Encode};
-use sp_runtime::traits::SaturatedConversion;
+use sp_runtime::traits::{Bounded, SaturatedConversion};
 use sp_core::{Bytes, crypto::UncheckedFrom};
 use sp_io::hashing::{
 	keccak_256,
@@ -37,6 +37,7 @@ use sp_io::hashing::{
 	sha2_256,
 };
 use pallet_contracts_primitives::{ExecReturnValue, ReturnFlags};
+use bitflags::bitflags;
 
 /// Every error that can be returned to a contract when it calls any of the host functions.
 ///
@@ -58,20 +59,26 @@ pub enum ReturnCode {
 	CalleeReverted = 2,
 	/// The passed key does not exist in storage.
 	KeyNotFound = 3,
-	/// Transfer failed because it would have brought the sender's total balance below the
-	/// subsistence threshold.
+	/// Deprecated and no longer returned: There is only the minimum balance.
 	BelowSubsistenceThreshold = 4,
 	/// Transfer failed for other reasons. Most probably reserved or locked balance of the
 	/// sender prevents the transfer.
 	TransferFailed = 5,
-	/// The newly created contract is below the subsistence threshold after executing
-	/// its constructor.
+	/// Deprecated and no longer returned: Endowment is no longer required.
 	NewContractNotFunded = 6,
 	/// No code could be found at the supplied code hash.
 	CodeNotFound = 7,
-	/// The contract that was called is either no contract at all (a plain account)
-	/// or is a tombstone.
+	/// The contract that was called is no contract (a plain account).
 	NotCallable = 8,
+	/// ECDSA public key recovery failed. Most probably wrong recovery id or signature.
+	EcdsaRecoverFailed = 9,
+	/// The supplied sr25519 signature is not valid for the supplied message and public key.
+	Sr25519VerifyFailed = 10,
+	/// The supplied ed25519 signature is not valid for the supplied message and public key.
+	Ed25519VerifyFailed = 11,
+	/// The call to `seal_debug_message` had no effect because debug message
+	/// recording was disabled.
+	LoggingDisabled = 12,
 }
 
 impl ConvertibleToWasm for ReturnCode {
@@ -96,6 +103,47 @@ impl From<ExecReturnValue> for ReturnCode {
 	}
 }
 
+bitflags! {
+	/// Flags used to change the behaviour of `seal_call`.
+	pub struct CallFlags: u32 {
+		/// Forward the input of current function to the callee.
+		///
+		/// Supplied input pointers are ignored when set.
+		///
+		/// # Note
+		///
+		/// A forwarding call will consume the current contracts input. Any attempt to
+		/// access the input after this call returns will lead to [`Error::InputAlreadyRead`].
+		/// It does not matter if this is due to calling `seal_input` or trying another
+		/// forwarding call. Consider using [`Self::CLONE_INPUT`] in order to preserve
+		/// the input.
+		const FORWARD_INPUT = 0b0000_0001;
+		/// Identical to [`Self::FORWARD_INPUT`] but without consuming the input.
+		///
+		/// This adds some additional weight costs to the call.
+		///
+		/// # Note
+		///
+		/// This implies [`Self::FORWARD_INPUT`] and takes precedence when both are set.
+		const CLONE_INPUT = 0b0000_0010;
+		/// Do not return from the call but rather return the result of the callee to the
+		/// callers caller.
+		///
+		/// # Note
+		///
+		/// This makes the current contract completely transparent to its caller by replacing
+		/// this contracts potential output by the callee ones. Any code after `seal_call`
+		/// can be safely considered unreachable.
+		const TAIL_CALL = 0b0000_0100;
+		/// Allow the callee to reenter into the current contract.
+		///
+		/// Without this flag any reentrancy into the current contract that originates from
+		/// the callee (or any of its callees) is denied. This includes the first callee:
+		/// You cannot call into yourself with this flag set.
+		const ALLOW_REENTRY = 0b0000_1000;
+	}
+}
+
 /// The data passed through when a contract uses `seal_return`.
 pub struct ReturnData {
 	/// The flags as passed through by the contract. They are still unchecked and
@@ -120,8 +168,6 @@ pub enum TrapReason {
 	/// Signals that a trap was generated in response to a successful call to the
 	/// `seal_terminate` host function.
 	Termination,
-	/// Signals that a trap was generated because of a successful restoration.
-	Restoration,
 }
 
 impl<T: Into<DispatchError>> From<T> for TrapReason {
@@ -138,8 +184,16 @@ pub enum RuntimeToken {
 	MeteringBlock(u32),
 	/// Weight of calling `seal_caller`.
 	Caller,
+	/// Weight of calling `seal_caller_is_origin`.
+	CallerIsOrigin,
+	/// Weight of calling `seal_is_contract`.
+	IsContract,
 	/// Weight of calling `seal_address`.
 	Address,
+	/// Weight of calling `seal_code_hash`.
+	CodeHash,
+	/// Weight of calling `seal_own_code_hash`.
+	OwnCodeHash,
 	/// Weight of calling `seal_gas_left`.
 	GasLeft,
 	/// Weight of calling `seal_balance`.
@@ -168,15 +222,11 @@ pub enum RuntimeToken {
 	Terminate,
 	/// Weight that is added to `seal_terminate` for every byte of the terminated contract.
 	TerminateSurchargeCodeSize(u32),
-	/// Weight of calling `seal_restore_to` per number of supplied delta entries.
-	RestoreTo(u32),
-	/// Weight that is added to `seal_restore_to` for the involved code sizes.
-	RestoreToSurchargeCodeSize{caller_code: u32, tombstone_code: u32},
 	/// Weight of calling `seal_random`. It includes the weight for copying the subject.
 	Random,
 	/// Weight of calling `seal_deposit_event` with the given number of topics and event size.
 	DepositEvent{num_topic: u32, len: u32},
-	/// Weight of calling `seal_set_rent_allowance`.
+	/// Weight of calling `seal_set_rent_allowance` or `seal_restore_to`.
 	SetRentAllowance,
 	/// Weight of calling `seal_set_storage` for the given storage item size.
 	SetStorage(u32),
@@ -196,6 +246,13 @@ pub enum RuntimeToken {
 	CallSurchargeTransfer,
 	/// Weight of output received through `seal_call` for the given size.
 	CallCopyOut(u32),
+	/// Weight of cloning the input of the calling contract for a `seal_call` with the
+	/// `CLONE_INPUT` flag.
+	CallInputCloned(u32),
+	/// Weight of calling `seal_delegate_call` for the given input size.
+	DelegateCallBase(u32),
+	/// Weight of calling `seal_set_code_hash`.
+	SetCodeHash,
 	/// Weight of calling `seal_instantiate` for the given input and salt without output weight.
 	/// This includes the transfer as an instantiate without a value will always be below
 	/// the existential deposit and is disregarded as corner case.
@@ -212,12 +269,20 @@ pub enum RuntimeToken {
 	HashBlake256(u32),
 	/// Weight of calling `seal_hash_blake2_128` for the given input size.
 	HashBlake128(u32),
+	/// Weight of calling `seal_ecdsa_recover`.
+	EcdsaRecovery,
+	/// Weight of calling `seal_ecdsa_to_eth_address`.
+	EcdsaToEthAddress,
+	/// Weight of calling `seal_sr25519_verify` for the given message size.
+	Sr25519Verify(u32),
+	/// Weight of calling `seal_ed25519_verify` for the given message size.
+	Ed25519Verify(u32),
+	/// Weight of calling `seal_debug_message`.
+	DebugMessage,
 	/// Weight charged by a chain extension through `seal_call_chain_extension`.
 	ChainExtension(u64),
 	/// Weight charged for copying data from the sandbox.
 	CopyIn(u32),
-	/// Weight of calling `seal_rent_params`.
-	RentParams,
 }
 
 impl<T: Config> Token<T> for RuntimeToken
@@ -231,7 +296,11 @@ where
 		match *self {
 			MeteringBlock(amount) => s.gas.saturating_add(amount.into()),
 			Caller => s.caller,
+			CallerIsOrigin => s.caller_is_origin,
+			IsContract => s.is_contract,
 			Address => s.address,
+			CodeHash => s.code_hash,
+			OwnCodeHash => s.own_code_hash,
 			GasLeft => s.gas_left,
 			Balance => s.balance,
 			ValueTransferred => s.value_transferred,
@@ -247,12 +316,6 @@ where
 				.saturating_add(s.return_per_byte.saturating_mul(len.into())),
 			Terminate => s.terminate,
 			TerminateSurchargeCodeSize(len) => s.terminate_per_code_byte.saturating_mul(len.into()),
-			RestoreTo(delta) => s.restore_to
-				.saturating_add(s.restore_to_per_delta.saturating_mul(delta.into())),
-			RestoreToSurchargeCodeSize{caller_code, tombstone_code} =>
-				s.restore_to_per_caller_code_byte.saturating_mul(caller_code.into()).saturating_add(
-					s.restore_to_per_tombstone_code_byte.saturating_mul(tombstone_code.into())
-				),
 			Random => s.random,
 			DepositEvent{num_topic, len} => s.deposit_event
 				.saturating_add(s.deposit_event_per_topic.saturating_mul(num_topic.into()))
@@ -269,6 +332,10 @@ where
 			CallSurchargeCodeSize(len) => s.call_per_code_byte.saturating_mul(len.into()),
 			CallSurchargeTransfer => s.call_transfer_surcharge,
 			CallCopyOut(len) => s.call_per_output_byte.saturating_mul(len.into()),
+			CallInputCloned(len) => s.call_per_input_byte.saturating_mul(len.into()),
+			DelegateCallBase(len) => s.delegate_call
+				.saturating_add(s.call_per_input_byte.saturating_mul(len.into())),
+			SetCodeHash => s.set_code_hash,
 			InstantiateBase{input_data_len, salt_len} => s.instantiate
 				.saturating_add(s.instantiate_per_input_byte.saturating_mul(input_data_len.into()))
 				.saturating_add(s.instantiate_per_salt_byte.saturating_mul(salt_len.into())),
@@ -284,9 +351,15 @@ where
 				.saturating_add(s.hash_blake2_256_per_byte.saturating_mul(len.into())),
 			HashBlake128(len) => s.hash_blake2_128
 				.saturating_add(s.hash_blake2_128_per_byte.saturating_mul(len.into())),
+			EcdsaRecovery => s.ecdsa_recover,
+			EcdsaToEthAddress => s.ecdsa_to_eth_address,
+			Sr25519Verify(len) => s.sr25519_verify
+				.saturating_add(s.sr25519_verify_per_byte.saturating_mul(len.into())),
+			Ed25519Verify(len) => s.ed25519_verify
+				.saturating_add(s.ed25519_verify_per_byte.saturating_mul(len.into())),
+			DebugMessage => s.debug_message,
 			ChainExtension(amount) => amount,
 			CopyIn(len) => s.return_per_byte.saturating_mul(len.into()),
-			RentParams => s.rent_params,
 		}
 	}
 }
@@ -356,12 +429,6 @@ where
 						data: Bytes(Vec::new()),
 					})
 				},
-				TrapReason::Restoration => {
-					Ok(ExecReturnValue {
-						flags: ReturnFlags::empty(),
-						data: Bytes(Vec::new()),
-					})
-				},
 				TrapReason::SupervisorError(error) => Err(error)?,
 			}
 		}
@@ -574,20 +641,95 @@ where
 		Ok(())
 	}
 
+	/// Make a call to another contract. This is shared by all versions of `seal_call`.
+	fn call(
+		&mut self,
+		flags: CallFlags,
+		callee_ptr: u32,
+		callee_len: u32,
+		gas: u64,
+		value_ptr: u32,
+		value_len: u32,
+		input_data_ptr: u32,
+		input_data_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32
+	) -> Result<ReturnCode, TrapReason> {
+		self.charge_gas(RuntimeToken::CallBase(input_data_len))?;
+		let callee: <<E as Ext>::T as frame_system::Config>::AccountId =
+			self.read_sandbox_memory_as(callee_ptr, callee_len)?;
+		let value: BalanceOf<<E as Ext>::T> = self.read_sandbox_memory_as(value_ptr, value_len)?;
+		let input_data = if flags.contains(CallFlags::CLONE_INPUT) {
+			let input = self.input_data.as_ref().ok_or(Error::<E::T>::InputAlreadyRead)?;
+			self.charge_gas(RuntimeToken::CallInputCloned(input.len() as u32))?;
+			input.clone()
+		} else if flags.contains(CallFlags::FORWARD_INPUT) {
+			self.input_data.take().ok_or(Error::<E::T>::InputAlreadyRead)?
+		} else {
+			self.read_sandbox_memory(input_data_ptr, input_data_len)?
+		};
+		if value > 0u32.into() {
+			self.charge_gas(RuntimeToken::CallSurchargeTransfer)?;
+		}
+		let charged = self.charge_gas(
+			RuntimeToken::CallSurchargeCodeSize(<E::T as Config>::MaxCodeSize::get())
+		)?;
+		let nested_gas_limit = if gas == 0 {
+			self.gas_meter.gas_left()
+		} else {
+			gas.saturated_into()
+		};
+		let ext = &mut self.ext;
+		let call_outcome = self.gas_meter.with_nested(nested_gas_limit, |nested_meter| {
+			match nested_meter {
+				Some(nested_meter) => {
+					ext.call(
+						&callee,
+						value,
+						nested_meter,
+						input_data,
+						flags.contains(CallFlags::ALLOW_REENTRY),
+					)
+				}
+				// there is not enough gas to allocate for the nested call.
+				None => Err((Error::<<E as Ext>::T>::OutOfGas.into(), 0)),
+			}
+		});
+		let code_len = match &call_outcome {
+			Ok((_, len)) => len,
+			Err((_, len)) => len,
+		};
+		self.adjust_gas(charged, RuntimeToken::CallSurchargeCodeSize(*code_len));
+
+		// `TAIL_CALL` only matters on an `OK` result. Otherwise the call stack comes to
+		// a halt anyways without anymore code being executed.
+		if flags.contains(CallFlags::TAIL_CALL) {
+			if let Ok((return_value, _)) = call_outcome {
+				return Err(TrapReason::Return(ReturnData {
+					flags: return_value.flags.bits(),
+					data: return_value.data.0,
+				}));
+			}
+		}
+
+		if let Ok((output, _)) = &call_outcome {
+			self.write_sandbox_output(output_ptr, output_len_ptr, &output.data, true, |len| {
+				Some(RuntimeToken::CallCopyOut(len))
+			})?;
+		}
+		Ok(Runtime::<E>::exec_into_return_code(call_outcome.map(|r| r.0).map_err(|r| r.0))?)
+	}
+
 	/// Fallible conversion of `DispatchError` to `ReturnCode`.
 	fn err_into_return_code(from: DispatchError) -> Result<ReturnCode, DispatchError> {
 		use ReturnCode::*;
 
-		let below_sub = Error::<E::T>::BelowSubsistenceThreshold.into();
 		let transfer_failed = Error::<E::T>::TransferFailed.into();
-		let not_funded = Error::<E::T>::NewContractNotFunded.into();
 		let no_code = Error::<E::T>::CodeNotFound.into();
 		let invalid_contract = Error::<E::T>::NotCallable.into();
 
 		match from {
-			x if x == below_sub => Ok(BelowSubsistenceThreshold),
 			x if x == transfer_failed => Ok(TransferFailed),
-			x if x == not_funded => Ok(NewContractNotFunded),
 			x if x == no_code => Ok(CodeNotFound),
 			x if x == invalid_contract => Ok(NotCallable),
 			err => Err(err)
@@ -712,7 +854,6 @@ define_env!(Env, <E: Ext>,
 	//
 	// # Errors
 	//
-	// `ReturnCode::BelowSubsistenceThreshold`
 	// `ReturnCode::TransferFailed`
 	[seal0] seal_transfer(
 		ctx,
@@ -739,6 +880,9 @@ define_env!(Env, <E: Ext>,
 
 	// Make a call to another contract.
 	//
+	// This is equivalent to calling the newer version of this function with
+	// `flags` set to `ALLOW_REENTRY`. See the newer version for documentation.
+	//
 	// The callees output buffer is copied to `output_ptr` and its length to `output_len_ptr`.
 	// The copy of the output buffer can be skipped by supplying the sentinel value
 	// of `u32::max_value()` to `output_ptr`.
@@ -765,7 +909,6 @@ define_env!(Env, <E: Ext>,
 	//
 	// `ReturnCode::CalleeReverted`: Output buffer is returned.
 	// `ReturnCode::CalleeTrapped`
-	// `ReturnCode::BelowSubsistenceThreshold`
 	// `ReturnCode::TransferFailed`
 	// `ReturnCode::NotCallable`
 	[seal0] seal_call(
@@ -780,33 +923,137 @@ define_env!(Env, <E: Ext>,
 		output_ptr: u32,
 		output_len_ptr: u32
 	) -> ReturnCode => {
-		ctx.charge_gas(RuntimeToken::CallBase(input_data_len))?;
-		let callee: <<E as Ext>::T as frame_system::Config>::AccountId =
-			ctx.read_sandbox_memory_as(callee_ptr, callee_len)?;
-		let value: BalanceOf<<E as Ext>::T> = ctx.read_sandbox_memory_as(value_ptr, value_len)?;
+		ctx.call(
+			CallFlags::ALLOW_REENTRY,
+			callee_ptr,
+			callee_len,
+			gas,
+			value_ptr,
+			value_len,
+			input_data_ptr,
+			input_data_len,
+			output_ptr,
+			output_len_ptr,
+		)
+	},
+
+	// Make a call to another contract.
+	//
+	// The callees output buffer is copied to `output_ptr` and its length to `output_len_ptr`.
+	// The copy of the output buffer can be skipped by supplying the sentinel value
+	// of `u32::max_value()` to `output_ptr`.
+	//
+	// # Parameters
+	//
+	// - flags: See [`CallFlags`] for a documentation of the supported flags.
+	// - callee_ptr: a pointer to the address of the callee contract.
+	//   Should be decodable as an `T::AccountId`. Traps otherwise.
+	// - callee_len: length of the address buffer.
+	// - gas: how much gas to devote to the execution.
+	// - value_ptr: a pointer to the buffer with value, how much value to send.
+	//   Should be decodable as a `T::Balance`. Traps otherwise.
+	// - value_len: length of the value buffer.
+	// - input_data_ptr: a pointer to a buffer to be used as input data to the callee.
+	//   Ignored when `FORWARD_INPUT` or `CLONE_INPUT` is set.
+	// - input_data_len: length of the input data buffer.
+	// - output_ptr: a pointer where the output buffer is copied to.
+	// - output_len_ptr: in-out pointer to where the length of the buffer is read from
+	//   and the actual length is written to.
+	//
+	// # Errors
+	//
+	// An error means that the call wasn't successful output buffer is returned unless
+	// stated otherwise.
+	//
+	// `ReturnCode::CalleeReverted`: Output buffer is returned.
+	// `ReturnCode::CalleeTrapped`
+	// `ReturnCode::TransferFailed`
+	// `ReturnCode::NotCallable`
+	//
+	// # Traps
+	//
+	// - `flags` contains unknown bits.
+	// - The input was already consumed by `seal_input` or a forwarding call and
+	//   `FORWARD_INPUT` or `CLONE_INPUT` is set.
+	// - The callee would re-enter a contract that did not set `ALLOW_REENTRY`.
+	[seal1] seal_call(
+		ctx,
+		flags: u32,
+		callee_ptr: u32,
+		callee_len: u32,
+		gas: u64,
+		value_ptr: u32,
+		value_len: u32,
+		input_data_ptr: u32,
+		input_data_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32
+	) -> ReturnCode => {
+		ctx.call(
+			CallFlags::from_bits(flags).ok_or(Error::<E::T>::InvalidCallFlags)?,
+			callee_ptr,
+			callee_len,
+			gas,
+			value_ptr,
+			value_len,
+			input_data_ptr,
+			input_data_len,
+			output_ptr,
+			output_len_ptr,
+		)
+	},
+
+	// Execute code in the context of the current contract.
+	//
+	// The code identified by the supplied code hash is executed with the storage, balance
+	// and address of the calling contract. `seal_caller` and `seal_value_transferred` return
+	// the same values as they do for the calling contract. No value is transferred.
+	// All of the remaining gas is made available to the executed code.
+	//
+	// The output buffer is copied to `output_ptr` and its length to `output_len_ptr`.
+	// The copy of the output buffer can be skipped by supplying the sentinel value
+	// of `u32::max_value()` to `output_ptr`.
+	//
+	// # Parameters
+	//
+	// - code_hash_ptr: a pointer to the hash of the code that should be executed.
+	//   Should be decodable as a `T::Hash`. Traps otherwise.
+	// - code_hash_len: length of the code hash buffer.
+	// - input_data_ptr: a pointer to a buffer to be used as input data to the executed code.
+	// - input_data_len: length of the input data buffer.
+	// - output_ptr: a pointer where the output buffer is copied to.
+	// - output_len_ptr: in-out pointer to where the length of the buffer is read from
+	//   and the actual length is written to.
+	//
+	// # Errors
+	//
+	// An error means that the call wasn't successful and no output buffer is returned unless
+	// stated otherwise.
+	//
+	// `ReturnCode::CalleeReverted`: Output buffer is returned.
+	// `ReturnCode::CalleeTrapped`
+	// `ReturnCode::CodeNotFound`
+	[seal0] seal_delegate_call(
+		ctx,
+		code_hash_ptr: u32,
+		code_hash_len: u32,
+		input_data_ptr: u32,
+		input_data_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32
+	) -> ReturnCode => {
+		ctx.charge_gas(RuntimeToken::DelegateCallBase(input_data_len))?;
+		let code_hash: CodeHash<<E as Ext>::T> =
+			ctx.read_sandbox_memory_as(code_hash_ptr, code_hash_len)?;
 		let input_data = ctx.read_sandbox_memory(input_data_ptr, input_data_len)?;
-		if value > 0u32.into() {
-			ctx.charge_gas(RuntimeToken::CallSurchargeTransfer)?;
-		}
 		let charged = ctx.charge_gas(
 			RuntimeToken::CallSurchargeCodeSize(<E::T as Config>::MaxCodeSize::get())
 		)?;
-		let nested_gas_limit = if gas == 0 {
-			ctx.gas_meter.gas_left()
-		} else {
-			gas.saturated_into()
-		};
+		let nested_gas_limit = ctx.gas_meter.gas_left();
 		let ext = &mut ctx.ext;
 		let call_outcome = ctx.gas_meter.with_nested(nested_gas_limit, |nested_meter| {
 			match nested_meter {
-				Some(nested_meter) => {
-					ext.call(
-						&callee,
-						value,
-						nested_meter,
-						input_data,
-					)
-				}
+				Some(nested_meter) => ext.delegate_call(code_hash, nested_meter, input_data),
 				// there is not enough gas to allocate for the nested call.
 				None => Err((Error::<<E as Ext>::T>::OutOfGas.into(), 0)),
 			}
@@ -832,10 +1079,6 @@ define_env!(Env, <E: Ext>,
 	// length to `output_len_ptr`. The copy of the output buffer and address can be skipped by
 	// supplying the sentinel value of `u32::max_value()` to `output_ptr` or `address_ptr`.
 	//
-	// After running the constructor it is verified that the contract account holds at
-	// least the subsistence threshold. If that is not the case the instantiation fails and
-	// the contract is not created.
-	//
 	// # Parameters
 	//
 	// - code_hash_ptr: a pointer to the buffer that contains the initializer code.
@@ -865,9 +1108,7 @@ define_env!(Env, <E: Ext>,
 	//
 	// `ReturnCode::CalleeReverted`: Output buffer is returned.
 	// `ReturnCode::CalleeTrapped`
-	// `ReturnCode::BelowSubsistenceThreshold`
 	// `ReturnCode::TransferFailed`
-	// `ReturnCode::NewContractNotFunded`
 	// `ReturnCode::CodeNotFound`
 	[seal0] seal_instantiate(
 		ctx,
@@ -1036,6 +1277,34 @@ define_env!(Env, <E: Ext>,
 		)?)
 	},
 
+	// Checks whether the caller of the current contract is the origin of the whole call stack.
+	//
+	// Prefer this over `seal_is_contract` when checking whether your contract is being called
+	// by a contract or a plain account. The reason is that it performs better since it does not
+	// need to do any storage lookups.
+	//
+	// A return value of `1` means that the caller is the origin and `0` otherwise.
+	[seal0] seal_caller_is_origin(ctx) -> u32 => {
+		ctx.charge_gas(RuntimeToken::CallerIsOrigin)?;
+		Ok(ctx.ext.caller_is_origin() as u32)
+	},
+
+	// Checks whether a specified address belongs to a contract.
+	//
+	// # Parameters
+	//
+	// - account_ptr: a pointer to the address of the account to check.
+	//   Should be decodable as an `T::AccountId`. Traps otherwise.
+	// - account_len: length of the address buffer.
+	//
+	// A return value of `1` means that the address belongs to a contract and `0` otherwise.
+	[seal0] seal_is_contract(ctx, account_ptr: u32, account_len: u32) -> u32 => {
+		ctx.charge_gas(RuntimeToken::IsContract)?;
+		let address: <<E as Ext>::T as frame_system::Config>::AccountId =
+			ctx.read_sandbox_memory_as(account_ptr, account_len)?;
+		Ok(ctx.ext.is_contract(&address) as u32)
+	},
+
 	// Stores the address of the current contract into the supplied buffer.
 	//
 	// The value is stored to linear memory at the address pointed to by `out_ptr`.
@@ -1049,6 +1318,84 @@ define_env!(Env, <E: Ext>,
 		)?)
 	},
 
+	// Stores the code hash of the contract at the supplied address into the supplied buffer.
+	//
+	// The value is stored to linear memory at the address pointed to by `out_ptr`.
+	// `out_len_ptr` must point to a u32 value that describes the available space at
+	// `out_ptr`. This call overwrites it with the size of the value. If the available
+	// space at `out_ptr` is less than the size of the value a trap is triggered.
+	//
+	// # Parameters
+	//
+	// - account_ptr: a pointer to the address of the contract.
+	//   Should be decodable as an `T::AccountId`. Traps otherwise.
+	// - account_len: length of the address buffer.
+	//
+	// # Errors
+	//
+	// `ReturnCode::KeyNotFound`: The supplied address does not belong to a contract.
+	[seal0] seal_code_hash(
+		ctx,
+		account_ptr: u32,
+		account_len: u32,
+		out_ptr: u32,
+		out_len_ptr: u32
+	) -> ReturnCode => {
+		ctx.charge_gas(RuntimeToken::CodeHash)?;
+		let address: <<E as Ext>::T as frame_system::Config>::AccountId =
+			ctx.read_sandbox_memory_as(account_ptr, account_len)?;
+		if let Some(value) = ctx.ext.code_hash(&address) {
+			ctx.write_sandbox_output(
+				out_ptr, out_len_ptr, &value.encode(), false, already_charged
+			)?;
+			Ok(ReturnCode::Success)
+		} else {
+			Ok(ReturnCode::KeyNotFound)
+		}
+	},
+
+	// Stores the code hash of the current contract into the supplied buffer.
+	//
+	// The value is stored to linear memory at the address pointed to by `out_ptr`.
+	// `out_len_ptr` must point to a u32 value that describes the available space at
+	// `out_ptr`. This call overwrites it with the size of the value. If the available
+	// space at `out_ptr` is less than the size of the value a trap is triggered.
+	//
+	// When called from code that is executed through `seal_delegate_call` the code hash
+	// of the contract that made the delegate call is returned.
+	[seal0] seal_own_code_hash(ctx, out_ptr: u32, out_len_ptr: u32) => {
+		ctx.charge_gas(RuntimeToken::OwnCodeHash)?;
+		let code_hash_encoded = &ctx.ext.own_code_hash().encode();
+		Ok(ctx.write_sandbox_output(
+			out_ptr, out_len_ptr, code_hash_encoded, false, already_charged
+		)?)
+	},
+
+	// Replace the code of the current contract.
+	//
+	// The new code is used starting with the next call into the contract. The code that
+	// is currently executing keeps running until it returns. The storage and balance of
+	// the contract are not touched. A `ContractCodeUpdated` event is emitted.
+	//
+	// # Parameters
+	//
+	// - code_hash_ptr: a pointer to the hash of the new code.
+	//   Should be decodable as a `T::Hash`. Traps otherwise.
+	// - code_hash_len: length of the code hash buffer.
+	//
+	// # Errors
+	//
+	// `ReturnCode::CodeNotFound`
+	[seal0] seal_set_code_hash(ctx, code_hash_ptr: u32, code_hash_len: u32) -> ReturnCode => {
+		ctx.charge_gas(RuntimeToken::SetCodeHash)?;
+		let code_hash: CodeHash<<E as Ext>::T> =
+			ctx.read_sandbox_memory_as(code_hash_ptr, code_hash_len)?;
+		match ctx.ext.set_code_hash(code_hash) {
+			Ok(()) => Ok(ReturnCode::Success),
+			Err(err) => Ok(Runtime::<E>::err_into_return_code(err)?),
+		}
+	},
+
 	// Stores the price for the specified amount of gas into the supplied buffer.
 	//
 	// The value is stored to linear memory at the address pointed to by `out_ptr`.
@@ -1192,7 +1539,7 @@ define_env!(Env, <E: Ext>,
 		)?)
 	},
 
-	// Stores the tombstone deposit into the supplied buffer.
+	// Stores the contract deposit into the supplied buffer.
 	//
 	// The value is stored to linear memory at the address pointed to by `out_ptr`.
 	// `out_len_ptr` must point to a u32 value that describes the available space at
@@ -1203,109 +1550,35 @@ define_env!(Env, <E: Ext>,
 	//
 	// # Note
 	//
-	// The tombstone deposit is on top of the existential deposit. So in order for
-	// a contract to leave a tombstone the balance of the contract must not go
-	// below the sum of existential deposit and the tombstone deposit. The sum
-	// is commonly referred as subsistence threshold in code.
+	// State rent and tombstones were removed. The value returned is the deposit that
+	// is charged from the origin for every new contract. This function only exists for
+	// backwards compatibility with already deployed contracts.
 	[seal0] seal_tombstone_deposit(ctx, out_ptr: u32, out_len_ptr: u32) => {
 		ctx.charge_gas(RuntimeToken::TombstoneDeposit)?;
 		Ok(ctx.write_sandbox_output(
-			out_ptr, out_len_ptr, &ctx.ext.tombstone_deposit().encode(), false, already_charged
+			out_ptr, out_len_ptr, &ctx.ext.contract_deposit().encode(), false, already_charged
 		)?)
 	},
 
-	// Try to restore the given destination contract sacrificing the caller.
-	//
-	// This function will compute a tombstone hash from the caller's storage and the given code hash
-	// and if the hash matches the hash found in the tombstone at the specified address - kill
-	// the caller contract and restore the destination contract and set the specified `rent_allowance`.
-	// All caller's funds are transferred to the destination.
-	//
-	// The tombstone hash is derived as `hash(code_hash, storage_root_hash)`. In order to match
-	// this hash to its own hash the restorer must make its storage equal to the one of the
-	// evicted destination contract. In order to allow for additional storage items in the
-	// restoring contract a delta can be specified to this function. All keys specified as
-	// delta are disregarded when calculating the storage root hash.
-	//
-	// On success, the destination contract is restored. This function is diverging and
-	// stops execution even on success.
-	//
-	// - `dest_ptr`, `dest_len` - the pointer and the length of a buffer that encodes `T::AccountId`
-	//    with the address of the to be restored contract.
-	// - `code_hash_ptr`, `code_hash_len` - the pointer and the length of a buffer that encodes
-	//    a code hash of the to be restored contract.
-	// - `rent_allowance_ptr`, `rent_allowance_len` - the pointer and the length of a buffer that
-	//    encodes the rent allowance that must be set in the case of successful restoration.
-	// - `delta_ptr` is the pointer to the start of a buffer that has `delta_count` storage keys
-	//    laid out sequentially.
+	// Was used to restore the given destination contract sacrificing the caller.
 	//
-	// # Traps
+	// # Note
 	//
-	// - There is no tombstone at the destination address.
-	// - Tombstone hashes do not match.
-	// - The calling contract is already present on the call stack.
-	// - The supplied code_hash does not exist on-chain.
+	// State rent and tombstones were removed. This function is a no-op that only exists
+	// for backwards compatibility with already deployed contracts.
 	[seal0] seal_restore_to(
 		ctx,
-		dest_ptr: u32,
-		dest_len: u32,
-		code_hash_ptr: u32,
-		code_hash_len: u32,
-		rent_allowance_ptr: u32,
-		rent_allowance_len: u32,
-		delta_ptr: u32,
-		delta_count: u32
+		_dest_ptr: u32,
+		_dest_len: u32,
+		_code_hash_ptr: u32,
+		_code_hash_len: u32,
+		_rent_allowance_ptr: u32,
+		_rent_allowance_len: u32,
+		_delta_ptr: u32,
+		_delta_count: u32
 	) => {
-		ctx.charge_gas(RuntimeToken::RestoreTo(delta_count))?;
-		let dest: <<E as Ext>::T as frame_system::Config>::AccountId =
-			ctx.read_sandbox_memory_as(dest_ptr, dest_len)?;
-		let code_hash: CodeHash<<E as Ext>::T> =
-			ctx.read_sandbox_memory_as(code_hash_ptr, code_hash_len)?;
-		let rent_allowance: BalanceOf<<E as Ext>::T> =
-			ctx.read_sandbox_memory_as(rent_allowance_ptr, rent_allowance_len)?;
-		let delta = {
-			const KEY_SIZE: usize = 32;
-
-			// We can eagerly allocate because we charged for the complete delta count already
-			// We still need to make sure that the allocation isn't larger than the memory
-			// allocator can handle.
-			ensure!(
-				delta_count
-					.saturating_mul(KEY_SIZE as u32) <= ctx.ext.schedule().limits.max_memory_size(),
-				Error::<E::T>::OutOfBounds,
-			);
-			let mut delta = vec![[0; KEY_SIZE]; delta_count as usize];
-			let mut key_ptr = delta_ptr;
-
-			for i in 0..delta_count {
-				// Read the delta into the provided buffer
-				// This cannot panic because of the loop condition
-				ctx.read_sandbox_memory_into_buf(key_ptr, &mut delta[i as usize])?;
-
-				// Offset key_ptr to the next element.
-				key_ptr = key_ptr.checked_add(KEY_SIZE as u32).ok_or(Error::<E::T>::OutOfBounds)?;
-			}
-
-			delta
-		};
-
-		let max_len = <E::T as Config>::MaxCodeSize::get();
-		let charged = ctx.charge_gas(RuntimeToken::RestoreToSurchargeCodeSize {
-			caller_code: max_len,
-			tombstone_code: max_len,
-		})?;
-		let (result, caller_code, tombstone_code) = match ctx.ext.restore_to(
-			dest, code_hash, rent_allowance, delta
-		) {
-			Ok((code, tomb)) => (Ok(()), code, tomb),
-			Err((err, code, tomb)) => (Err(err), code, tomb),
-		};
-		ctx.adjust_gas(charged, RuntimeToken::RestoreToSurchargeCodeSize {
-			caller_code,
-			tombstone_code,
-		});
-		result?;
-		Err(TrapReason::Restoration)
+		ctx.charge_gas(RuntimeToken::SetRentAllowance)?;
+		Ok(())
 	},
 
 	// Deposit a contract event with the data buffer and optional list of topics. There is a limit
@@ -1373,17 +1646,14 @@ define_env!(Env, <E: Ext>,
 		Ok(())
 	},
 
-	// Set rent allowance of the contract
+	// Was used to set the rent allowance of the contract.
 	//
-	// - value_ptr: a pointer to the buffer with value, how much to allow for rent
-	//   Should be decodable as a `T::Balance`. Traps otherwise.
-	// - value_len: length of the value buffer.
-	[seal0] seal_set_rent_allowance(ctx, value_ptr: u32, value_len: u32) => {
+	// # Note
+	//
+	// State rent and tombstones were removed. This function is a no-op that only exists
+	// for backwards compatibility with already deployed contracts.
+	[seal0] seal_set_rent_allowance(ctx, _value_ptr: u32, _value_len: u32) => {
 		ctx.charge_gas(RuntimeToken::SetRentAllowance)?;
-		let value: BalanceOf<<E as Ext>::T> =
-			ctx.read_sandbox_memory_as(value_ptr, value_len)?;
-		ctx.ext.set_rent_allowance(value);
-
 		Ok(())
 	},
 
@@ -1395,16 +1665,23 @@ define_env!(Env, <E: Ext>,
 	// space at `out_ptr` is less than the size of the value a trap is triggered.
 	//
 	// The data is encoded as T::Balance.
+	//
+	// # Note
+	//
+	// State rent and tombstones were removed. This function always returns the maximum
+	// value of the balance type and only exists for backwards compatibility with already
+	// deployed contracts.
 	[seal0] seal_rent_allowance(ctx, out_ptr: u32, out_len_ptr: u32) => {
 		ctx.charge_gas(RuntimeToken::RentAllowance)?;
+		let rent_allowance = <BalanceOf<<E as Ext>::T>>::max_value().encode();
 		Ok(ctx.write_sandbox_output(
-			out_ptr, out_len_ptr, &ctx.ext.rent_allowance().encode(), false, already_charged
+			out_ptr, out_len_ptr, &rent_allowance, false, already_charged
 		)?)
 	},
 
 	// Prints utf8 encoded string from the data buffer.
 	// Only available on `--dev` chains.
-	// This function may be removed at any time, superseded by a more general contract debugging feature.
+	// This function may be removed at any time, superseded by `seal_debug_message`.
 	[seal0] seal_println(ctx, str_ptr: u32, str_len: u32) => {
 		let data = ctx.read_sandbox_memory(str_ptr, str_len)?;
 		if let Ok(utf8) = core::str::from_utf8(&data) {
@@ -1413,6 +1690,34 @@ define_env!(Env, <E: Ext>,
 		Ok(())
 	},
 
+	// Emit a custom debug message.
+	//
+	// The message is appended to the debug buffer which is then supplied to the calling RPC
+	// client. No newline is added to the message. Debug messages are only collected when
+	// executing as a dry-run through the RPC. When executing on-chain this function is a
+	// no-op which does not even read the message from the sandbox memory.
+	//
+	// Messages that would grow the debug buffer beyond `MaxDebugBufferLen` are dropped.
+	//
+	// # Errors
+	//
+	// `ReturnCode::LoggingDisabled`
+	//
+	// # Traps
+	//
+	// - The message is not valid UTF-8.
+	[seal0] seal_debug_message(ctx, str_ptr: u32, str_len: u32) -> ReturnCode => {
+		ctx.charge_gas(RuntimeToken::DebugMessage)?;
+		if ctx.ext.append_debug_buffer("") {
+			let data = ctx.read_sandbox_memory(str_ptr, str_len)?;
+			let msg = core::str::from_utf8(&data)
+				.map_err(|_| Error::<E::T>::DebugMessageInvalidUTF8)?;
+			ctx.ext.append_debug_buffer(msg);
+			return Ok(ReturnCode::Success);
+		}
+		Ok(ReturnCode::LoggingDisabled)
+	},
+
 	// Stores the current block number of the current contract into the supplied buffer.
 	//
 	// The value is stored to linear memory at the address pointed to by `out_ptr`.
@@ -1526,6 +1831,153 @@ define_env!(Env, <E: Ext>,
 		Ok(ctx.compute_hash_on_intermediate_buffer(blake2_128, input_ptr, input_len, output_ptr)?)
 	},
 
+	// Recover the ECDSA public key from the given message hash and signature.
+	//
+	// Writes the public key into the given output buffer.
+	// Assumes the secp256k1 curve.
+	//
+	// # Parameters
+	//
+	// - `signature_ptr`: the pointer into the linear memory where the signature
+	//                    is placed. Should be decodable as a 65 bytes. Traps otherwise.
+	// - `message_hash_ptr`: the pointer into the linear memory where the message
+	//                       hash is placed. Should be decodable as a 32 bytes. Traps otherwise.
+	// - `output_ptr`: the pointer into the linear memory where the output
+	//                 data is placed. The buffer should be 33 bytes. The function
+	//                 will write the result directly into this buffer.
+	//
+	// # Errors
+	//
+	// `ReturnCode::EcdsaRecoverFailed`
+	[seal0] seal_ecdsa_recover(
+		ctx,
+		signature_ptr: u32,
+		message_hash_ptr: u32,
+		output_ptr: u32
+	) -> ReturnCode => {
+		ctx.charge_gas(RuntimeToken::EcdsaRecovery)?;
+
+		let mut signature: [u8; 65] = [0; 65];
+		ctx.read_sandbox_memory_into_buf(signature_ptr, &mut signature)?;
+		let mut message_hash: [u8; 32] = [0; 32];
+		ctx.read_sandbox_memory_into_buf(message_hash_ptr, &mut message_hash)?;
+
+		match ctx.ext.ecdsa_recover(&signature, &message_hash) {
+			Ok(pub_key) => {
+				// Write the recovered compressed ecdsa public key back into the sandboxed output
+				// buffer.
+				ctx.write_sandbox_memory(output_ptr, pub_key.as_ref())?;
+				Ok(ReturnCode::Success)
+			},
+			Err(_) => Ok(ReturnCode::EcdsaRecoverFailed),
+		}
+	},
+
+	// Calculate an Ethereum address from a compressed ECDSA public key.
+	//
+	// The address consists of the last 20 bytes of the keccak 256-bit hash of the
+	// uncompressed public key.
+	//
+	// # Parameters
+	//
+	// - `key_ptr`: the pointer into the linear memory where the compressed public key
+	//              is placed. Should be decodable as a 33 bytes. Traps otherwise.
+	// - `output_ptr`: the pointer into the linear memory where the output
+	//                 data is placed. The buffer should be 20 bytes. The function
+	//                 will write the result directly into this buffer.
+	//
+	// # Errors
+	//
+	// `ReturnCode::EcdsaRecoverFailed`: The supplied key is no valid public key.
+	[seal0] seal_ecdsa_to_eth_address(ctx, key_ptr: u32, output_ptr: u32) -> ReturnCode => {
+		ctx.charge_gas(RuntimeToken::EcdsaToEthAddress)?;
+
+		let mut compressed_key: [u8; 33] = [0; 33];
+		ctx.read_sandbox_memory_into_buf(key_ptr, &mut compressed_key)?;
+
+		match ctx.ext.ecdsa_to_eth_address(&compressed_key) {
+			Ok(address) => {
+				ctx.write_sandbox_memory(output_ptr, address.as_ref())?;
+				Ok(ReturnCode::Success)
+			},
+			Err(_) => Ok(ReturnCode::EcdsaRecoverFailed),
+		}
+	},
+
+	// Verify a sr25519 signature.
+	//
+	// # Parameters
+	//
+	// - `signature_ptr`: the pointer into the linear memory where the signature
+	//                    is placed. Should be a value of 64 bytes.
+	// - `pub_key_ptr`: the pointer into the linear memory where the public key
+	//                  is placed. Should be a value of 32 bytes.
+	// - `message_len`: the length of the message payload.
+	// - `message_ptr`: the pointer into the linear memory where the message
+	//                  is placed.
+	//
+	// # Errors
+	//
+	// `ReturnCode::Sr25519VerifyFailed`
+	[seal0] seal_sr25519_verify(
+		ctx,
+		signature_ptr: u32,
+		pub_key_ptr: u32,
+		message_len: u32,
+		message_ptr: u32
+	) -> ReturnCode => {
+		ctx.charge_gas(RuntimeToken::Sr25519Verify(message_len))?;
+
+		let mut signature: [u8; 64] = [0; 64];
+		ctx.read_sandbox_memory_into_buf(signature_ptr, &mut signature)?;
+		let mut pub_key: [u8; 32] = [0; 32];
+		ctx.read_sandbox_memory_into_buf(pub_key_ptr, &mut pub_key)?;
+		let message = ctx.read_sandbox_memory(message_ptr, message_len)?;
+
+		if ctx.ext.sr25519_verify(&signature, &message, &pub_key) {
+			Ok(ReturnCode::Success)
+		} else {
+			Ok(ReturnCode::Sr25519VerifyFailed)
+		}
+	},
+
+	// Verify an ed25519 signature.
+	//
+	// # Parameters
+	//
+	// - `signature_ptr`: the pointer into the linear memory where the signature
+	//                    is placed. Should be a value of 64 bytes.
+	// - `pub_key_ptr`: the pointer into the linear memory where the public key
+	//                  is placed. Should be a value of 32 bytes.
+	// - `message_len`: the length of the message payload.
+	// - `message_ptr`: the pointer into the linear memory where the message
+	//                  is placed.
+	//
+	// # Errors
+	//
+	// `ReturnCode::Ed25519VerifyFailed`
+	[seal0] seal_ed25519_verify(
+		ctx,
+		signature_ptr: u32,
+		pub_key_ptr: u32,
+		message_len: u32,
+		message_ptr: u32
+	) -> ReturnCode => {
+		ctx.charge_gas(RuntimeToken::Ed25519Verify(message_len))?;
+
+		let mut signature: [u8; 64] = [0; 64];
+		ctx.read_sandbox_memory_into_buf(signature_ptr, &mut signature)?;
+		let mut pub_key: [u8; 32] = [0; 32];
+		ctx.read_sandbox_memory_into_buf(pub_key_ptr, &mut pub_key)?;
+		let message = ctx.read_sandbox_memory(message_ptr, message_len)?;
+
+		if ctx.ext.ed25519_verify(&signature, &message, &pub_key) {
+			Ok(ReturnCode::Success)
+		} else {
+			Ok(ReturnCode::Ed25519VerifyFailed)
+		}
+	},
+
 	// Call into the chain extension provided by the chain if any.
 	//
 	// Handling of the input values is up to the specific chain extension and so is the
@@ -1558,25 +2010,4 @@ define_env!(Env, <E: Ext>,
 			})),
 		}
 	},
-
-	// Stores the rent params into the supplied buffer.
-	//
-	// The value is stored to linear memory at the address pointed to by `out_ptr`.
-	// `out_len_ptr` must point to a u32 value that describes the available space at
-	// `out_ptr`. This call overwrites it with the size of the value. If the available
-	// space at `out_ptr` is less than the size of the value a trap is triggered.
-	//
-	// The data is encoded as [`crate::exec::RentParams`].
-	//
-	// # Note
-	//
-	// The returned information was collected and cached when the current contract call
-	// started execution. Any change to those values that happens due to actions of the
-	// current call or contracts that are called by this contract are not considered.
-	[seal0] seal_rent_params(ctx, out_ptr: u32, out_len_ptr: u32) => {
-		ctx.charge_gas(RuntimeToken::RentParams)?;
-		Ok(ctx.write_sandbox_output(
-			out_ptr, out_len_ptr, &ctx.ext.rent_params().encode(), false, already_charged
-		)?)
-	},
 );
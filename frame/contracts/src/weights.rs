@@ -52,9 +52,14 @@ pub trait WeightInfo {
 	fn instantiate_with_code(c: u32, s: u32, ) -> Weight;
 	fn instantiate(c: u32, s: u32, ) -> Weight;
 	fn call(c: u32, ) -> Weight;
-	fn claim_surcharge(c: u32, ) -> Weight;
+	fn upload_code(c: u32, ) -> Weight;
+	fn remove_code() -> Weight;
 	fn seal_caller(r: u32, ) -> Weight;
+	fn seal_caller_is_origin(r: u32, ) -> Weight;
+	fn seal_is_contract(r: u32, ) -> Weight;
 	fn seal_address(r: u32, ) -> Weight;
+	fn seal_code_hash(r: u32, ) -> Weight;
+	fn seal_own_code_hash(r: u32, ) -> Weight;
 	fn seal_gas_left(r: u32, ) -> Weight;
 	fn seal_balance(r: u32, ) -> Weight;
 	fn seal_value_transferred(r: u32, ) -> Weight;
@@ -63,7 +68,6 @@ pub trait WeightInfo {
 	fn seal_rent_allowance(r: u32, ) -> Weight;
 	fn seal_block_number(r: u32, ) -> Weight;
 	fn seal_now(r: u32, ) -> Weight;
-	fn seal_rent_params(r: u32, ) -> Weight;
 	fn seal_weight_to_fee(r: u32, ) -> Weight;
 	fn seal_gas(r: u32, ) -> Weight;
 	fn seal_input(r: u32, ) -> Weight;
@@ -72,8 +76,7 @@ pub trait WeightInfo {
 	fn seal_return_per_kb(n: u32, ) -> Weight;
 	fn seal_terminate(r: u32, ) -> Weight;
 	fn seal_terminate_per_code_kb(c: u32, ) -> Weight;
-	fn seal_restore_to(r: u32, ) -> Weight;
-	fn seal_restore_to_per_code_kb_delta(c: u32, t: u32, d: u32, ) -> Weight;
+	fn seal_set_code_hash(r: u32, ) -> Weight;
 	fn seal_random(r: u32, ) -> Weight;
 	fn seal_deposit_event(r: u32, ) -> Weight;
 	fn seal_deposit_event_per_topic_and_kb(t: u32, n: u32, ) -> Weight;
@@ -85,6 +88,7 @@ pub trait WeightInfo {
 	fn seal_get_storage_per_kb(n: u32, ) -> Weight;
 	fn seal_transfer(r: u32, ) -> Weight;
 	fn seal_call(r: u32, ) -> Weight;
+	fn seal_delegate_call(r: u32, ) -> Weight;
 	fn seal_call_per_code_transfer_input_output_kb(c: u32, t: u32, i: u32, o: u32, ) -> Weight;
 	fn seal_instantiate(r: u32, ) -> Weight;
 	fn seal_instantiate_per_code_input_output_salt_kb(c: u32, i: u32, o: u32, s: u32, ) -> Weight;
@@ -96,6 +100,13 @@ pub trait WeightInfo {
 	fn seal_hash_blake2_256_per_kb(n: u32, ) -> Weight;
 	fn seal_hash_blake2_128(r: u32, ) -> Weight;
 	fn seal_hash_blake2_128_per_kb(n: u32, ) -> Weight;
+	fn seal_ecdsa_recover(r: u32, ) -> Weight;
+	fn seal_ecdsa_to_eth_address(r: u32, ) -> Weight;
+	fn seal_sr25519_verify(r: u32, ) -> Weight;
+	fn seal_sr25519_verify_per_kb(n: u32, ) -> Weight;
+	fn seal_ed25519_verify(r: u32, ) -> Weight;
+	fn seal_ed25519_verify_per_kb(n: u32, ) -> Weight;
+	fn seal_debug_message(r: u32, ) -> Weight;
 	fn instr_i64const(r: u32, ) -> Weight;
 	fn instr_i64load(r: u32, ) -> Weight;
 	fn instr_i64store(r: u32, ) -> Weight;
@@ -208,11 +219,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
-	fn claim_surcharge(c: u32, ) -> Weight {
-		(336_867_000 as Weight)
-			// Standard Error: 10_000
-			.saturating_add((5_262_000 as Weight).saturating_mul(c as Weight))
-			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	fn upload_code(c: u32, ) -> Weight {
+		(45_218_000 as Weight)
+			// Standard Error: 49_000
+			.saturating_add((93_587_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn remove_code() -> Weight {
+		(39_704_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
 	fn seal_caller(r: u32, ) -> Weight {
@@ -221,12 +237,38 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((266_876_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
 	}
+	fn seal_caller_is_origin(r: u32, ) -> Weight {
+		(139_815_000 as Weight)
+			// Standard Error: 98_000
+			.saturating_add((187_524_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
+	fn seal_is_contract(r: u32, ) -> Weight {
+		(128_402_000 as Weight)
+			// Standard Error: 1_386_000
+			.saturating_add((372_918_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((100 as Weight).saturating_mul(r as Weight)))
+	}
 	fn seal_address(r: u32, ) -> Weight {
 		(150_342_000 as Weight)
 			// Standard Error: 127_000
 			.saturating_add((266_051_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
 	}
+	fn seal_code_hash(r: u32, ) -> Weight {
+		(133_427_000 as Weight)
+			// Standard Error: 1_541_000
+			.saturating_add((377_203_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((100 as Weight).saturating_mul(r as Weight)))
+	}
+	fn seal_own_code_hash(r: u32, ) -> Weight {
+		(146_215_000 as Weight)
+			// Standard Error: 156_000
+			.saturating_add((305_390_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
 	fn seal_gas_left(r: u32, ) -> Weight {
 		(144_833_000 as Weight)
 			// Standard Error: 124_000
@@ -275,12 +317,6 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((258_627_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
 	}
-	fn seal_rent_params(r: u32, ) -> Weight {
-		(168_575_000 as Weight)
-			// Standard Error: 394_000
-			.saturating_add((397_754_000 as Weight).saturating_mul(r as Weight))
-			.saturating_add(T::DbWeight::get().reads(5 as Weight))
-	}
 	fn seal_weight_to_fee(r: u32, ) -> Weight {
 		(148_102_000 as Weight)
 			// Standard Error: 201_000
@@ -332,26 +368,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(7 as Weight))
 			.saturating_add(T::DbWeight::get().writes(5 as Weight))
 	}
-	fn seal_restore_to(r: u32, ) -> Weight {
-		(232_291_000 as Weight)
-			// Standard Error: 301_000
-			.saturating_add((136_379_000 as Weight).saturating_mul(r as Weight))
-			.saturating_add(T::DbWeight::get().reads(5 as Weight))
-			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(r as Weight)))
-			.saturating_add(T::DbWeight::get().writes((6 as Weight).saturating_mul(r as Weight)))
-	}
-	fn seal_restore_to_per_code_kb_delta(c: u32, t: u32, d: u32, ) -> Weight {
+	fn seal_set_code_hash(r: u32, ) -> Weight {
 		(0 as Weight)
-			// Standard Error: 162_000
-			.saturating_add((8_619_000 as Weight).saturating_mul(c as Weight))
-			// Standard Error: 162_000
-			.saturating_add((4_877_000 as Weight).saturating_mul(t as Weight))
-			// Standard Error: 1_433_000
-			.saturating_add((3_762_810_000 as Weight).saturating_mul(d as Weight))
-			.saturating_add(T::DbWeight::get().reads(8 as Weight))
-			.saturating_add(T::DbWeight::get().reads((100 as Weight).saturating_mul(d as Weight)))
-			.saturating_add(T::DbWeight::get().writes(7 as Weight))
-			.saturating_add(T::DbWeight::get().writes((100 as Weight).saturating_mul(d as Weight)))
+			// Standard Error: 4_632_000
+			.saturating_add((2_864_471_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((300 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((300 as Weight).saturating_mul(r as Weight)))
 	}
 	fn seal_random(r: u32, ) -> Weight {
 		(153_634_000 as Weight)
@@ -436,6 +460,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(6 as Weight))
 			.saturating_add(T::DbWeight::get().reads((200 as Weight).saturating_mul(r as Weight)))
 	}
+	fn seal_delegate_call(r: u32, ) -> Weight {
+		(0 as Weight)
+			// Standard Error: 6_124_000
+			.saturating_add((10_842_917_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((100 as Weight).saturating_mul(r as Weight)))
+	}
 	fn seal_call_per_code_transfer_input_output_kb(c: u32, t: u32, i: u32, o: u32, ) -> Weight {
 		(9_916_288_000 as Weight)
 			// Standard Error: 552_000
@@ -519,6 +550,48 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add((159_996_000 as Weight).saturating_mul(n as Weight))
 			.saturating_add(T::DbWeight::get().reads(5 as Weight))
 	}
+	fn seal_ecdsa_recover(r: u32, ) -> Weight {
+		(161_471_000 as Weight)
+			// Standard Error: 1_057_000
+			.saturating_add((3_000_591_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
+	fn seal_ecdsa_to_eth_address(r: u32, ) -> Weight {
+		(149_803_000 as Weight)
+			// Standard Error: 664_000
+			.saturating_add((1_552_147_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
+	fn seal_sr25519_verify(r: u32, ) -> Weight {
+		(157_082_000 as Weight)
+			// Standard Error: 1_244_000
+			.saturating_add((4_619_325_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
+	fn seal_sr25519_verify_per_kb(n: u32, ) -> Weight {
+		(4_766_910_000 as Weight)
+			// Standard Error: 58_000
+			.saturating_add((62_813_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
+	fn seal_ed25519_verify(r: u32, ) -> Weight {
+		(152_440_000 as Weight)
+			// Standard Error: 981_000
+			.saturating_add((4_137_952_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
+	fn seal_ed25519_verify_per_kb(n: u32, ) -> Weight {
+		(4_291_518_000 as Weight)
+			// Standard Error: 47_000
+			.saturating_add((11_204_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
+	fn seal_debug_message(r: u32, ) -> Weight {
+		(138_275_000 as Weight)
+			// Standard Error: 31_000
+			.saturating_add((6_934_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+	}
 	fn instr_i64const(r: u32, ) -> Weight {
 		(24_250_000 as Weight)
 			// Standard Error: 14_000
@@ -834,11 +907,16 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
-	fn claim_surcharge(c: u32, ) -> Weight {
-		(336_867_000 as Weight)
-			// Standard Error: 10_000
-			.saturating_add((5_262_000 as Weight).saturating_mul(c as Weight))
-			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	fn upload_code(c: u32, ) -> Weight {
+		(45_218_000 as Weight)
+			// Standard Error: 49_000
+			.saturating_add((93_587_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn remove_code() -> Weight {
+		(39_704_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
 	fn seal_caller(r: u32, ) -> Weight {
@@ -847,12 +925,38 @@ impl WeightInfo for () {
 			.saturating_add((266_876_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
 	}
+	fn seal_caller_is_origin(r: u32, ) -> Weight {
+		(139_815_000 as Weight)
+			// Standard Error: 98_000
+			.saturating_add((187_524_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
+	fn seal_is_contract(r: u32, ) -> Weight {
+		(128_402_000 as Weight)
+			// Standard Error: 1_386_000
+			.saturating_add((372_918_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((100 as Weight).saturating_mul(r as Weight)))
+	}
 	fn seal_address(r: u32, ) -> Weight {
 		(150_342_000 as Weight)
 			// Standard Error: 127_000
 			.saturating_add((266_051_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
 	}
+	fn seal_code_hash(r: u32, ) -> Weight {
+		(133_427_000 as Weight)
+			// Standard Error: 1_541_000
+			.saturating_add((377_203_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((100 as Weight).saturating_mul(r as Weight)))
+	}
+	fn seal_own_code_hash(r: u32, ) -> Weight {
+		(146_215_000 as Weight)
+			// Standard Error: 156_000
+			.saturating_add((305_390_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
 	fn seal_gas_left(r: u32, ) -> Weight {
 		(144_833_000 as Weight)
 			// Standard Error: 124_000
@@ -901,12 +1005,6 @@ impl WeightInfo for () {
 			.saturating_add((258_627_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
 	}
-	fn seal_rent_params(r: u32, ) -> Weight {
-		(168_575_000 as Weight)
-			// Standard Error: 394_000
-			.saturating_add((397_754_000 as Weight).saturating_mul(r as Weight))
-			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
-	}
 	fn seal_weight_to_fee(r: u32, ) -> Weight {
 		(148_102_000 as Weight)
 			// Standard Error: 201_000
@@ -958,26 +1056,14 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
 	}
-	fn seal_restore_to(r: u32, ) -> Weight {
-		(232_291_000 as Weight)
-			// Standard Error: 301_000
-			.saturating_add((136_379_000 as Weight).saturating_mul(r as Weight))
-			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
-			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(r as Weight)))
-			.saturating_add(RocksDbWeight::get().writes((6 as Weight).saturating_mul(r as Weight)))
-	}
-	fn seal_restore_to_per_code_kb_delta(c: u32, t: u32, d: u32, ) -> Weight {
+	fn seal_set_code_hash(r: u32, ) -> Weight {
 		(0 as Weight)
-			// Standard Error: 162_000
-			.saturating_add((8_619_000 as Weight).saturating_mul(c as Weight))
-			// Standard Error: 162_000
-			.saturating_add((4_877_000 as Weight).saturating_mul(t as Weight))
-			// Standard Error: 1_433_000
-			.saturating_add((3_762_810_000 as Weight).saturating_mul(d as Weight))
-			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
-			.saturating_add(RocksDbWeight::get().reads((100 as Weight).saturating_mul(d as Weight)))
-			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
-			.saturating_add(RocksDbWeight::get().writes((100 as Weight).saturating_mul(d as Weight)))
+			// Standard Error: 4_632_000
+			.saturating_add((2_864_471_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((300 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((300 as Weight).saturating_mul(r as Weight)))
 	}
 	fn seal_random(r: u32, ) -> Weight {
 		(153_634_000 as Weight)
@@ -1062,6 +1148,13 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
 			.saturating_add(RocksDbWeight::get().reads((200 as Weight).saturating_mul(r as Weight)))
 	}
+	fn seal_delegate_call(r: u32, ) -> Weight {
+		(0 as Weight)
+			// Standard Error: 6_124_000
+			.saturating_add((10_842_917_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((100 as Weight).saturating_mul(r as Weight)))
+	}
 	fn seal_call_per_code_transfer_input_output_kb(c: u32, t: u32, i: u32, o: u32, ) -> Weight {
 		(9_916_288_000 as Weight)
 			// Standard Error: 552_000
@@ -1145,6 +1238,48 @@ impl WeightInfo for () {
 			.saturating_add((159_996_000 as Weight).saturating_mul(n as Weight))
 			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
 	}
+	fn seal_ecdsa_recover(r: u32, ) -> Weight {
+		(161_471_000 as Weight)
+			// Standard Error: 1_057_000
+			.saturating_add((3_000_591_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
+	fn seal_ecdsa_to_eth_address(r: u32, ) -> Weight {
+		(149_803_000 as Weight)
+			// Standard Error: 664_000
+			.saturating_add((1_552_147_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
+	fn seal_sr25519_verify(r: u32, ) -> Weight {
+		(157_082_000 as Weight)
+			// Standard Error: 1_244_000
+			.saturating_add((4_619_325_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
+	fn seal_sr25519_verify_per_kb(n: u32, ) -> Weight {
+		(4_766_910_000 as Weight)
+			// Standard Error: 58_000
+			.saturating_add((62_813_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
+	fn seal_ed25519_verify(r: u32, ) -> Weight {
+		(152_440_000 as Weight)
+			// Standard Error: 981_000
+			.saturating_add((4_137_952_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
+	fn seal_ed25519_verify_per_kb(n: u32, ) -> Weight {
+		(4_291_518_000 as Weight)
+			// Standard Error: 47_000
+			.saturating_add((11_204_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
+	fn seal_debug_message(r: u32, ) -> Weight {
+		(138_275_000 as Weight)
+			// Standard Error: 31_000
+			.saturating_add((6_934_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+	}
 	fn instr_i64const(r: u32, ) -> Weight {
 		(24_250_000 as Weight)
 			// Standard Error: 14_000
@@ -187,6 +187,39 @@ frame_benchmarking::benchmarks! {
 		assert!(<MultiPhase<T>>::current_phase().is_unsigned());
 	}
 
+	finalize_signed_phase_accept_solution {
+		let receiver = frame_benchmarking::account("receiver", 0, SEED);
+		let initial_balance = T::Currency::minimum_balance().max(One::one()) * 10u32.into();
+		T::Currency::make_free_balance_be(&receiver, initial_balance);
+		let ready: ReadySolution<T::AccountId> = Default::default();
+		let deposit: BalanceOf<T> = 10u32.into();
+		let reward: BalanceOf<T> = 20u32.into();
+
+		assert_ok!(T::Currency::reserve(&receiver, deposit));
+		assert_eq!(T::Currency::free_balance(&receiver), initial_balance - 10u32.into());
+	}: {
+		<MultiPhase<T>>::finalize_signed_phase_accept_solution(ready, &receiver, deposit, reward)
+	} verify {
+		assert_eq!(T::Currency::free_balance(&receiver), initial_balance + 20u32.into());
+		assert_eq!(T::Currency::reserved_balance(&receiver), 0u32.into());
+	}
+
+	finalize_signed_phase_reject_solution {
+		let receiver = frame_benchmarking::account("receiver", 0, SEED);
+		let initial_balance = T::Currency::minimum_balance().max(One::one()) * 10u32.into();
+		let deposit: BalanceOf<T> = 10u32.into();
+		T::Currency::make_free_balance_be(&receiver, initial_balance);
+		assert_ok!(T::Currency::reserve(&receiver, deposit));
+
+		assert_eq!(T::Currency::free_balance(&receiver), initial_balance - 10u32.into());
+		assert_eq!(T::Currency::reserved_balance(&receiver), 10u32.into());
+	}: {
+		<MultiPhase<T>>::finalize_signed_phase_reject_solution(&receiver, deposit)
+	} verify {
+		assert_eq!(T::Currency::free_balance(&receiver), initial_balance - 10u32.into());
+		assert_eq!(T::Currency::reserved_balance(&receiver), 0u32.into());
+	}
+
 	// a call to `<Pallet as ElectionProvider>::elect` where we only return the queued solution.
 	elect_queued {
 		// assume largest values for the election status. These will merely affect the decoding.
@@ -226,6 +259,42 @@ frame_benchmarking::benchmarks! {
 		assert!(<MultiPhase<T>>::snapshot().is_some());
 	}
 
+	submit {
+		let c in 1 .. (T::SignedMaxSubmissions::get() - 1);
+
+		// the solution will be worse than all of them meaning the score need to be checked against
+		// all of the queued ones.
+		let solution = RawSolution {
+			score: [(10_000_000u128 - 1).into(), 0, 0],
+			..Default::default()
+		};
+
+		<MultiPhase<T>>::on_initialize_open_signed().expect("should be ok to start signed phase");
+		<Round<T>>::put(1);
+
+		let signed_submissions = (0..c)
+			.map(|i| {
+				let solution = RawSolution {
+					score: [(10_000_000 + i).into(), 0, 0],
+					..Default::default()
+				};
+				SignedSubmission { solution, ..Default::default() }
+			})
+			.collect::<Vec<SignedSubmissionOf<T>>>();
+		<SignedSubmissions<T>>::put(signed_submissions);
+
+		let size = <MultiPhase<T>>::snapshot_metadata().unwrap();
+		let deposit = <MultiPhase<T>>::deposit_for(&solution, size);
+		let caller = frame_benchmarking::whitelisted_caller();
+		T::Currency::make_free_balance_be(
+			&caller,
+			T::Currency::minimum_balance() * 10u32.into() + deposit,
+		);
+	}: _(RawOrigin::Signed(caller), solution, c)
+	verify {
+		assert!(<MultiPhase<T>>::signed_submissions().len() as u32 == c + 1);
+	}
+
 	submit_unsigned {
 		// number of votes in snapshot.
 		let v in (T::BenchmarkingConfig::VOTERS[0]) .. T::BenchmarkingConfig::VOTERS[1];
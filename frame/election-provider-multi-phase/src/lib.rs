@@ -47,10 +47,10 @@
 //! [`Config::Fallback`].
 //! ### Signed Phase
 //!
-//!	In the signed phase, solutions (of type [`RawSolution`]) are submitted and queued on chain. A
+//! In the signed phase, solutions (of type [`RawSolution`]) are submitted and queued on chain. A
 //! deposit is reserved, based on the size of the solution, for the cost of keeping this solution
 //! on-chain for a number of blocks, and the potential weight of the solution upon being checked. A
-//! maximum of `pallet::Config::MaxSignedSubmissions` solutions are stored. The queue is always
+//! maximum of [`pallet::Config::SignedMaxSubmissions`] solutions are stored. The queue is always
 //! sorted based on score (worse to best).
 //!
 //! Upon arrival of a new solution:
@@ -65,6 +65,9 @@
 //! A signed solution cannot be reversed, taken back, updated, or retracted. In other words, the
 //! origin can not bail out in any way, if their solution is queued.
 //!
+//! The submitter must also provide the current length of the queue as a witness, which is used to
+//! bound the weight of the [`Pallet::submit`] call.
+//!
 //! Upon the end of the signed phase, the solutions are examined from best to worse (i.e. `pop()`ed
 //! until drained). Each solution undergoes an expensive `Pallet::feasibility_check`, which
 //! ensures the score claimed by this score was correct, and it is valid based on the election data
@@ -92,6 +95,13 @@
 //! Note that both of the bottom solutions end up being discarded and get their deposit back,
 //! despite one of them being *invalid*.
 //!
+//! The reward of the winner is [`pallet::Config::SignedRewardBase`], minted via
+//! [`pallet::Config::RewardHandler`]. The deposits of invalid solutions are slashed and handed to
+//! [`pallet::Config::SlashHandler`].
+//!
+//! If [`ElectionProvider::elect`] is called while the signed phase is still open (i.e. the unsigned
+//! phase has length zero), the signed phase is finalized at that point.
+//!
 //! ## Unsigned Phase
 //!
 //! The unsigned phase will always follow the signed phase, with the specified duration. In this
@@ -218,10 +228,10 @@ use codec::{Decode, Encode};
 use frame_support::{
 	dispatch::DispatchResultWithPostInfo,
 	ensure,
-	traits::{Currency, Get, ReservableCurrency},
+	traits::{Currency, Get, OnUnbalanced, ReservableCurrency},
 	weights::Weight,
 };
-use frame_system::{ensure_none, offchain::SendTransactionTypes};
+use frame_system::{ensure_none, ensure_signed, offchain::SendTransactionTypes};
 use frame_election_provider_support::{ElectionDataProvider, ElectionProvider, onchain};
 use sp_npos_elections::{
 	assignment_ratio_to_staked_normalized, is_score_better, CompactSolution, ElectionScore,
@@ -250,9 +260,14 @@ pub mod helpers;
 
 const LOG_TARGET: &'static str = "runtime::election-provider";
 
+pub mod signed;
 pub mod unsigned;
 pub mod weights;
 
+pub use signed::{
+	BalanceOf, NegativeImbalanceOf, PositiveImbalanceOf, SignedSubmission, SignedSubmissionOf,
+};
+
 /// The weight declaration of the pallet.
 pub use weights::WeightInfo;
 
@@ -547,6 +562,34 @@ pub mod pallet {
 		/// this value.
 		type MinerMaxLength: Get<u32>;
 
+		/// Maximum number of signed submissions that can be queued.
+		#[pallet::constant]
+		type SignedMaxSubmissions: Get<u32>;
+		/// Maximum weight of a signed solution.
+		///
+		/// This should probably be similar to [`Config::MinerMaxWeight`].
+		#[pallet::constant]
+		type SignedMaxWeight: Get<Weight>;
+
+		/// Base reward for a signed solution
+		#[pallet::constant]
+		type SignedRewardBase: Get<BalanceOf<Self>>;
+
+		/// Base deposit for a signed solution.
+		#[pallet::constant]
+		type SignedDepositBase: Get<BalanceOf<Self>>;
+		/// Per-byte deposit for a signed solution.
+		#[pallet::constant]
+		type SignedDepositByte: Get<BalanceOf<Self>>;
+		/// Per-weight deposit for a signed solution.
+		#[pallet::constant]
+		type SignedDepositWeight: Get<BalanceOf<Self>>;
+
+		/// Handler for the slashed deposits.
+		type SlashHandler: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// Handler for the rewards.
+		type RewardHandler: OnUnbalanced<PositiveImbalanceOf<Self>>;
+
 		/// Something that will provide the election data.
 		type DataProvider: ElectionDataProvider<Self::AccountId, Self::BlockNumber>;
 
@@ -606,8 +649,11 @@ pub mod pallet {
 					// determine if followed by signed or not.
 					let (need_snapshot, enabled, signed_weight) = if current_phase == Phase::Signed {
 						// followed by a signed phase: close the signed phase, no need for snapshot.
-						// TODO: proper weight https://github.com/paritytech/substrate/pull/7910.
-						(false, true, Weight::zero())
+						// NOTE: we could disable the unsigned phase if the signed phase produced a
+						// good solution, but for now it is unconditionally enabled as a defensive
+						// measure.
+						let (_success, signed_weight) = Self::finalize_signed_phase();
+						(false, true, signed_weight)
 					} else {
 						// no signed phase: create a new snapshot, definitely `enable` the unsigned
 						// phase.
@@ -702,7 +748,7 @@ pub mod pallet {
 	impl<T: Config> Pallet<T> {
 		/// Submit a solution for the unsigned phase.
 		///
-		/// The dispatch origin fo this call must be __none__.
+		/// The dispatch origin of this call must be __none__.
 		///
 		/// This submission is checked on the fly. Moreover, this unsigned solution is only
 		/// validated when submitted to the pool from the **local** node. Effectively, this means
@@ -751,6 +797,70 @@ pub mod pallet {
 
 			Ok(None.into())
 		}
+
+		/// Submit a solution for the signed phase.
+		///
+		/// The dispatch origin of this call must be __signed__.
+		///
+		/// The solution is potentially queued, based on the claimed score and processed at the end
+		/// of the signed phase.
+		///
+		/// A deposit is reserved and recorded for the solution. Based on the outcome, the solution
+		/// might be rewarded, slashed, or get all or a part of the deposit back.
+		///
+		/// `num_signed_submissions` must be the current length of the signed submission queue. It
+		/// is used as weight witness data.
+		///
+		/// # <weight>
+		/// Queue size must be provided as witness data.
+		/// # </weight>
+		#[pallet::weight(T::WeightInfo::submit(*num_signed_submissions))]
+		pub fn submit(
+			origin: OriginFor<T>,
+			solution: RawSolution<CompactOf<T>>,
+			num_signed_submissions: u32,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			// ensure witness data is correct.
+			ensure!(
+				num_signed_submissions >=
+					<SignedSubmissions<T>>::decode_len().unwrap_or_default() as u32,
+				Error::<T>::SignedInvalidWitness,
+			);
+
+			// ensure solution is timely.
+			ensure!(Self::current_phase().is_signed(), Error::<T>::PreDispatchEarlySubmission);
+
+			// build size. Note: this is not needed for weight calc, thus not input.
+			// unlikely to ever return an error: if phase is signed, snapshot will exist.
+			let size = Self::snapshot_metadata().ok_or(Error::<T>::MissingSnapshotMetadata)?;
+
+			ensure!(
+				Self::feasibility_weight_of(&solution, size) < T::SignedMaxWeight::get(),
+				Error::<T>::SignedTooMuchWeight,
+			);
+
+			// ensure solution claims is better.
+			let mut signed_submissions = Self::signed_submissions();
+			let index = Self::insert_submission(&who, &mut signed_submissions, solution, size)
+				.ok_or(Error::<T>::SignedQueueFull)?;
+
+			// collect deposit. Thereafter, the function cannot fail.
+			let deposit = signed_submissions
+				.get(index)
+				.map(|s| s.deposit)
+				.ok_or(Error::<T>::InvalidSubmissionIndex)?;
+			T::Currency::reserve(&who, deposit).map_err(|_| Error::<T>::SignedCannotPayDeposit)?;
+
+			// if the queue overflowed, the weakest submission is dropped and refunded.
+			let _ejected = Self::eject_weakest_submission(&mut signed_submissions);
+
+			// store the new signed submission.
+			<SignedSubmissions<T>>::put(signed_submissions);
+			Self::deposit_event(Event::SolutionStored(ElectionCompute::Signed));
+			Ok(())
+		}
 	}
 
 	#[pallet::event]
@@ -784,6 +894,18 @@ pub mod pallet {
 		PreDispatchWrongWinnerCount,
 		/// Submission was too weak, score-wise.
 		PreDispatchWeakSubmission,
+		/// The queue was full, and the solution was not better than any of the existing ones.
+		SignedQueueFull,
+		/// The origin failed to pay the deposit.
+		SignedCannotPayDeposit,
+		/// Witness data to dispatchable is invalid.
+		SignedInvalidWitness,
+		/// The signed submission consumes too much weight
+		SignedTooMuchWeight,
+		/// Snapshot metadata should exist but didn't.
+		MissingSnapshotMetadata,
+		/// `Self::insert_submission` returned an invalid index.
+		InvalidSubmissionIndex,
 	}
 
 	#[pallet::origin]
@@ -885,6 +1007,15 @@ pub mod pallet {
 	#[pallet::getter(fn snapshot_metadata)]
 	pub type SnapshotMetadata<T: Config> = StorageValue<_, SolutionOrSnapshotSize>;
 
+	/// Sorted (worse -> best) list of unchecked, signed solutions.
+	///
+	/// Holds at most [`Config::SignedMaxSubmissions`] items. Drained at the end of the signed
+	/// phase, or upon a call to `elect`.
+	#[pallet::storage]
+	#[pallet::getter(fn signed_submissions)]
+	pub type SignedSubmissions<T: Config> =
+		StorageValue<_, Vec<SignedSubmissionOf<T>>, ValueQuery>;
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
 	pub struct Pallet<T>(PhantomData<T>);
@@ -1063,6 +1194,7 @@ impl<T: Config> Pallet<T> {
 	/// 1. Increment round.
 	/// 2. Change phase to [`Phase::Off`]
 	/// 3. Clear all snapshot data.
+	/// 4. Clear any leftover signed submissions.
 	fn post_elect() {
 		// inc round
 		<Round<T>>::mutate(|r| *r = *r + 1);
@@ -1072,6 +1204,10 @@ impl<T: Config> Pallet<T> {
 
 		// kill snapshots
 		Self::kill_snapshot();
+
+		// defensive-only: the signed submissions are drained in `do_elect` already.
+		debug_assert!(<SignedSubmissions<T>>::decode_len().unwrap_or_default() == 0);
+		<SignedSubmissions<T>>::kill();
 	}
 
 	/// On-chain fallback of election.
@@ -1084,6 +1220,14 @@ impl<T: Config> Pallet<T> {
 	}
 
 	fn do_elect() -> Result<(Supports<T::AccountId>, Weight), ElectionError> {
+		// We have to unconditionally try finalizing the signed phase here. There are only two
+		// possibilities:
+		//
+		// - signed phase was open, in which case this is essential for correct functioning of the
+		//   system.
+		// - signed phase was complete or not started, in which case finalization is idempotent and
+		//   inexpensive (1 read of an empty vector).
+		let (_, signed_finalize_weight) = Self::finalize_signed_phase();
 		<QueuedSolution<T>>::take()
 			.map_or_else(
 				|| match T::Fallback::get() {
@@ -1103,7 +1247,7 @@ impl<T: Config> Pallet<T> {
 				if Self::round() != 1 {
 					log!(info, "Finalized election round with compute {:?}.", compute);
 				}
-				(supports, weight)
+				(supports, weight.saturating_add(signed_finalize_weight))
 			})
 			.map_err(|err| {
 				Self::deposit_event(Event::ElectionFinalized(None));
@@ -0,0 +1,668 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The signed phase implementation.
+
+use crate::*;
+use frame_support::traits::OnUnbalanced;
+use sp_arithmetic::traits::Saturating;
+
+/// A raw, unchecked signed submission.
+///
+/// This is just a wrapper around [`RawSolution`] and some additional info.
+#[derive(PartialEq, Eq, Encode, Decode, Clone, RuntimeDebug, Default)]
+pub struct SignedSubmission<AccountId, Balance, CompactSolution> {
+	/// Who submitted this solution.
+	pub who: AccountId,
+	/// The deposit reserved for storing this solution.
+	pub deposit: Balance,
+	/// The reward that should be given to this solution, if chosen as the final one.
+	pub reward: Balance,
+	/// The raw solution itself.
+	pub solution: RawSolution<CompactSolution>,
+}
+
+/// The balance type of the currency used by this pallet.
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+/// The positive imbalance type of the currency used by this pallet.
+pub type PositiveImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::PositiveImbalance;
+/// The negative imbalance type of the currency used by this pallet.
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// A signed submission, as stored in [`SignedSubmissions`].
+pub type SignedSubmissionOf<T> =
+	SignedSubmission<<T as frame_system::Config>::AccountId, BalanceOf<T>, CompactOf<T>>;
+
+impl<T: Config> Pallet<T> {
+	/// Finish the signed phase. Process the signed submissions from best to worse until a valid one
+	/// is found, rewarding the best one and slashing the invalid ones along the way.
+	///
+	/// Returns true if we have a good solution in the signed phase.
+	///
+	/// This drains the [`SignedSubmissions`], potentially storing the best valid one in
+	/// [`QueuedSolution`].
+	pub fn finalize_signed_phase() -> (bool, Weight) {
+		let mut all_submissions: Vec<SignedSubmissionOf<T>> = <SignedSubmissions<T>>::take();
+		let mut found_solution = false;
+		let mut weight = T::DbWeight::get().reads_writes(1, 1);
+
+		while let Some(best) = all_submissions.pop() {
+			let SignedSubmission { solution, who, deposit, reward } = best;
+			let active_voters = solution.compact.voter_count() as u32;
+			let feasibility_weight = {
+				// defensive only: at the end of signed phase, snapshot will exits.
+				let SolutionOrSnapshotSize { voters, targets } =
+					Self::snapshot_metadata().unwrap_or_default();
+				let desired_targets = Self::desired_targets().unwrap_or_default();
+				T::WeightInfo::feasibility_check(voters, targets, active_voters, desired_targets)
+			};
+			// the feasibility check itself has some weight.
+			weight = weight.saturating_add(feasibility_weight);
+			match Self::feasibility_check(solution, ElectionCompute::Signed) {
+				Ok(ready_solution) => {
+					Self::finalize_signed_phase_accept_solution(ready_solution, &who, deposit, reward);
+					found_solution = true;
+
+					weight = weight
+						.saturating_add(T::WeightInfo::finalize_signed_phase_accept_solution());
+					break;
+				}
+				Err(why) => {
+					log!(warn, "signed submission of {:?} was infeasible due to {:?}", who, why);
+					Self::finalize_signed_phase_reject_solution(&who, deposit);
+					weight = weight
+						.saturating_add(T::WeightInfo::finalize_signed_phase_reject_solution());
+				}
+			}
+		}
+
+		// Any unprocessed solution is pointless to even consider. Feasible or malicious, they
+		// didn't end up being used. Unreserve the bonds.
+		for SignedSubmission { who, deposit, .. } in all_submissions {
+			let _remaining = T::Currency::unreserve(&who, deposit);
+			weight = weight.saturating_add(T::DbWeight::get().writes(1));
+			debug_assert!(_remaining.is_zero());
+		}
+
+		(found_solution, weight)
+	}
+
+	/// Helper function for the case where a solution is accepted in the signed phase.
+	///
+	/// Extracted to facilitate with weight calculation.
+	///
+	/// Infallible
+	pub fn finalize_signed_phase_accept_solution(
+		ready_solution: ReadySolution<T::AccountId>,
+		who: &T::AccountId,
+		deposit: BalanceOf<T>,
+		reward: BalanceOf<T>,
+	) {
+		// write this ready solution.
+		<QueuedSolution<T>>::put(ready_solution);
+
+		// emit reward event.
+		Self::deposit_event(Event::Rewarded(who.clone()));
+
+		// unreserve deposit.
+		let _remaining = T::Currency::unreserve(who, deposit);
+		debug_assert!(_remaining.is_zero());
+
+		// reward.
+		let positive_imbalance = T::Currency::deposit_creating(who, reward);
+		T::RewardHandler::on_unbalanced(positive_imbalance);
+	}
+
+	/// Helper function for the case where a solution is rejected in the signed phase.
+	///
+	/// Extracted to facilitate with weight calculation.
+	///
+	/// Infallible
+	pub fn finalize_signed_phase_reject_solution(who: &T::AccountId, deposit: BalanceOf<T>) {
+		Self::deposit_event(Event::Slashed(who.clone()));
+		let (negative_imbalance, _remaining) = T::Currency::slash_reserved(who, deposit);
+		debug_assert!(_remaining.is_zero());
+		T::SlashHandler::on_unbalanced(negative_imbalance);
+	}
+
+	/// Insert a solution into the queue while maintaining an ordering by solution quality.
+	///
+	/// The queue is sorted from worse to best, thus the best solution is always at the end and can
+	/// be `pop()`ed.
+	///
+	/// Returns `Some(index)` of the new submission if it was inserted, `None` if the queue is full
+	/// and the solution is not better than any of the queued ones. Note that the queue might
+	/// temporarily hold [`Config::SignedMaxSubmissions`] + 1 items after this call; it is up to
+	/// the caller to eject the weakest one via [`Pallet::eject_weakest_submission`].
+	pub fn insert_submission(
+		who: &T::AccountId,
+		queue: &mut Vec<SignedSubmissionOf<T>>,
+		solution: RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> Option<usize> {
+		// from the last score, compare and see if the current one is better. If none, then the
+		// awarded index is 0.
+		let at = queue
+			.iter()
+			.enumerate()
+			.rev()
+			.find_map(|(i, s)| {
+				if is_score_better::<Perbill>(
+					solution.score,
+					s.solution.score,
+					T::SolutionImprovementThreshold::get(),
+				) {
+					Some(i + 1)
+				} else {
+					None
+				}
+			})
+			.unwrap_or(0);
+
+		if at == 0 && queue.len() as u32 >= T::SignedMaxSubmissions::get() {
+			// if this is worse than all, and the queue is full, don't bother.
+			return None;
+		}
+
+		let reward = T::SignedRewardBase::get();
+		let deposit = Self::deposit_for(&solution, size);
+		let submission = SignedSubmission { who: who.clone(), deposit, reward, solution };
+		// Proof: `at` must always be less than or equal to `queue.len()` for this not to panic. It
+		// is either 0 (in which case `0 <= queue.len()`) or one of the queue indices + 1. The
+		// biggest queue index is `queue.len() - 1`, thus `at <= queue.len()`.
+		queue.insert(at, submission);
+		Some(at)
+	}
+
+	/// Remove the weakest submission from the queue if it holds more than
+	/// [`Config::SignedMaxSubmissions`] items, returning the deposit of its submitter.
+	///
+	/// Returns `true` if a submission was ejected.
+	pub fn eject_weakest_submission(queue: &mut Vec<SignedSubmissionOf<T>>) -> bool {
+		if queue.len() as u32 > T::SignedMaxSubmissions::get() {
+			let SignedSubmission { who, deposit, .. } = queue.remove(0);
+			let _remaining = T::Currency::unreserve(&who, deposit);
+			debug_assert!(_remaining.is_zero());
+			true
+		} else {
+			false
+		}
+	}
+
+	/// The feasibility weight of the given raw solution.
+	pub fn feasibility_weight_of(
+		solution: &RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> Weight {
+		T::WeightInfo::feasibility_check(
+			size.voters,
+			size.targets,
+			solution.compact.voter_count() as u32,
+			solution.compact.unique_targets().len() as u32,
+		)
+	}
+
+	/// Collect sufficient deposit to store this solution in this chain.
+	///
+	/// The deposit is composed of 3 main elements:
+	///
+	/// 1. base deposit, fixed for all submissions.
+	/// 2. a per-byte deposit, for renting the state usage.
+	/// 3. a per-weight deposit, for the potential weight usage in an upcoming on_initialize
+	pub fn deposit_for(
+		solution: &RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> BalanceOf<T> {
+		let encoded_len: u32 = solution.encoded_size().saturated_into();
+		let encoded_len: BalanceOf<T> = encoded_len.into();
+		let feasibility_weight = Self::feasibility_weight_of(solution, size);
+
+		let len_deposit = T::SignedDepositByte::get().saturating_mul(encoded_len);
+		let weight_deposit =
+			T::SignedDepositWeight::get().saturating_mul(feasibility_weight.saturated_into());
+
+		T::SignedDepositBase::get().saturating_add(len_deposit).saturating_add(weight_deposit)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		mock::{Origin, *},
+		Error, Phase, *,
+	};
+	use frame_support::dispatch::DispatchResult;
+
+	fn submit_with_witness(
+		origin: Origin,
+		solution: RawSolution<CompactOf<Runtime>>,
+	) -> DispatchResult {
+		MultiPhase::submit(origin, solution, MultiPhase::signed_submissions().len() as u32)
+	}
+
+	#[test]
+	fn cannot_submit_too_early() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(2);
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+
+			// create a temp snapshot only for this test.
+			MultiPhase::create_snapshot().unwrap();
+			let solution = raw_solution();
+
+			assert_noop!(
+				submit_with_witness(Origin::signed(10), solution),
+				Error::<Runtime>::PreDispatchEarlySubmission,
+			);
+		})
+	}
+
+	#[test]
+	fn wrong_witness_fails() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			// submit this once correctly
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+			assert_eq!(MultiPhase::signed_submissions().len(), 1);
+
+			// now try and cheat by passing a lower queue length
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(99), solution, 0),
+				Error::<Runtime>::SignedInvalidWitness,
+			);
+		})
+	}
+
+	#[test]
+	fn should_pay_deposit() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+
+			assert_eq!(balances(&99), (95, 5));
+			assert_eq!(MultiPhase::signed_submissions().first().unwrap().deposit, 5);
+			assert_eq!(
+				multi_phase_events(),
+				vec![Event::SignedPhaseStarted(1), Event::SolutionStored(ElectionCompute::Signed)],
+			);
+		})
+	}
+
+	#[test]
+	fn good_solution_is_rewarded() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			assert_eq!(balances(&99), (95, 5));
+
+			assert!(MultiPhase::finalize_signed_phase().0);
+			assert_eq!(balances(&99), (100 + 7, 0));
+			assert!(MultiPhase::queued_solution().is_some());
+		})
+	}
+
+	#[test]
+	fn bad_solution_is_slashed() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			// make the solution invalid.
+			solution.score[0] += 1;
+
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			assert_eq!(balances(&99), (95, 5));
+
+			// no good solution was stored.
+			assert!(!MultiPhase::finalize_signed_phase().0);
+			// and the bond is gone.
+			assert_eq!(balances(&99), (95, 0));
+			assert!(MultiPhase::queued_solution().is_none());
+		})
+	}
+
+	#[test]
+	fn suppressed_solution_gets_bond_back() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+			assert_eq!(balances(&999), (100, 0));
+
+			// submit as correct.
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+
+			// make the solution invalid and weaker.
+			solution.score[0] -= 1;
+			assert_ok!(submit_with_witness(Origin::signed(999), solution));
+			assert_eq!(balances(&99), (95, 5));
+			assert_eq!(balances(&999), (95, 5));
+
+			assert!(MultiPhase::finalize_signed_phase().0);
+
+			// 99 is rewarded.
+			assert_eq!(balances(&99), (100 + 7, 0));
+			// 999 gets everything back.
+			assert_eq!(balances(&999), (100, 0));
+		})
+	}
+
+	#[test]
+	fn cannot_submit_worse_with_full_queue() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 0..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+
+			// weaker.
+			let solution = RawSolution { score: [4, 0, 0], ..Default::default() };
+
+			assert_noop!(
+				submit_with_witness(Origin::signed(99), solution),
+				Error::<Runtime>::SignedQueueFull,
+			);
+		})
+	}
+
+	#[test]
+	fn weakest_is_removed_if_better_provided() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 0..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7, 8, 9]
+			);
+
+			// better.
+			let solution = RawSolution { score: [20, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+
+			// the one with score 5 was rejected, the new one inserted.
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![6, 7, 8, 9, 20]
+			);
+		})
+	}
+
+	#[test]
+	fn ejected_submission_gets_deposit_back() {
+		ExtBuilder::default().signed_max_submission(1).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = RawSolution { score: [5, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			assert_eq!(balances(&99), (95, 5));
+
+			let solution = RawSolution { score: [6, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(999), solution));
+
+			assert_eq!(balances(&99), (100, 0));
+			assert_eq!(balances(&999), (95, 5));
+			assert_eq!(MultiPhase::signed_submissions().len(), 1);
+		})
+	}
+
+	#[test]
+	fn replace_weakest_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 1..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+
+			let solution = RawSolution { score: [4, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![4, 6, 7, 8, 9],
+			);
+
+			// better.
+			let solution = RawSolution { score: [5, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+
+			// the one with score 4 was rejected, the new one inserted.
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7, 8, 9],
+			);
+		})
+	}
+
+	#[test]
+	fn early_ejected_solution_gets_bond_back() {
+		ExtBuilder::default().signed_deposit(2, 0, 0).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 0..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+
+			assert_eq!(balances(&99).1, 2 * 5);
+			assert_eq!(balances(&999).1, 0);
+
+			// better.
+			let solution = RawSolution { score: [20, 0, 0], ..Default::default() };
+			assert_ok!(submit_with_witness(Origin::signed(999), solution));
+
+			// got one bond back.
+			assert_eq!(balances(&99).1, 2 * 4);
+			assert_eq!(balances(&999).1, 2);
+		})
+	}
+
+	#[test]
+	fn equally_good_solution_is_not_accepted() {
+		ExtBuilder::default().signed_max_submission(3).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for i in 0..SignedMaxSubmissions::get() {
+				let solution = RawSolution { score: [(5 + i).into(), 0, 0], ..Default::default() };
+				assert_ok!(submit_with_witness(Origin::signed(99), solution));
+			}
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7]
+			);
+
+			// 5 is not accepted. This will only cause processing with no benefit.
+			let solution = RawSolution { score: [5, 0, 0], ..Default::default() };
+			assert_noop!(
+				submit_with_witness(Origin::signed(99), solution),
+				Error::<Runtime>::SignedQueueFull,
+			);
+		})
+	}
+
+	#[test]
+	fn all_in_one_signed_submission_scenario() {
+		// a combination of:
+		// - good_solution_is_rewarded
+		// - bad_solution_is_slashed
+		// - suppressed_solution_gets_bond_back
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			assert_eq!(balances(&99), (100, 0));
+			assert_eq!(balances(&999), (100, 0));
+			assert_eq!(balances(&9999), (100, 0));
+			let solution = raw_solution();
+
+			// submit a correct one.
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+
+			// make the solution invalidly better and submit. This ought to be slashed.
+			let mut solution_999 = solution.clone();
+			solution_999.score[0] += 1;
+			assert_ok!(submit_with_witness(Origin::signed(999), solution_999));
+
+			// make the solution invalidly worse and submit. This ought to be suppressed and
+			// returned.
+			let mut solution_9999 = solution.clone();
+			solution_9999.score[0] -= 1;
+			assert_ok!(submit_with_witness(Origin::signed(9999), solution_9999));
+
+			assert_eq!(
+				MultiPhase::signed_submissions().iter().map(|x| x.who).collect::<Vec<_>>(),
+				vec![9999, 99, 999]
+			);
+
+			// _some_ good solution was stored.
+			assert!(MultiPhase::finalize_signed_phase().0);
+
+			// 99 is rewarded.
+			assert_eq!(balances(&99), (100 + 7, 0));
+			// 999 is slashed.
+			assert_eq!(balances(&999), (95, 0));
+			// 9999 gets everything back.
+			assert_eq!(balances(&9999), (100, 0));
+		})
+	}
+
+	#[test]
+	fn cannot_consume_too_much_future_weight() {
+		ExtBuilder::default().signed_weight(40).mock_weight_info(true).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let (solution, witness) = MultiPhase::mine_solution(2).unwrap();
+			let solution_weight = <Runtime as Config>::WeightInfo::feasibility_check(
+				witness.voters,
+				witness.targets,
+				solution.compact.voter_count() as u32,
+				solution.compact.unique_targets().len() as u32,
+			);
+			// default solution will have 5 edges (5 * 5 + 10)
+			assert_eq!(solution_weight, 35);
+			assert_eq!(solution.compact.voter_count(), 5);
+			assert_eq!(<Runtime as Config>::SignedMaxWeight::get(), 40);
+
+			assert_ok!(submit_with_witness(Origin::signed(99), solution.clone()));
+
+			<SignedMaxWeight>::set(30);
+
+			// note: resubmitting the same solution is technically okay as long as the queue has
+			// space.
+			assert_noop!(
+				submit_with_witness(Origin::signed(99), solution),
+				Error::<Runtime>::SignedTooMuchWeight,
+			);
+		})
+	}
+
+	#[test]
+	fn insufficient_deposit_does_not_store_submission() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+
+			assert_eq!(balances(&123), (0, 0));
+			assert_noop!(
+				submit_with_witness(Origin::signed(123), solution),
+				Error::<Runtime>::SignedCannotPayDeposit,
+			);
+
+			assert_eq!(balances(&123), (0, 0));
+		})
+	}
+
+	#[test]
+	fn elect_finalizes_an_open_signed_phase() {
+		ExtBuilder::default().phases(10, 0).build_and_execute(|| {
+			roll_to(20);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_ok!(submit_with_witness(Origin::signed(99), solution));
+
+			// the unsigned phase is void, thus the signed phase is still open upon election.
+			roll_to(30);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			assert_ok!(MultiPhase::elect());
+			assert_eq!(balances(&99), (100 + 7, 0));
+			assert!(multi_phase_events()
+				.contains(&Event::ElectionFinalized(Some(ElectionCompute::Signed))));
+			assert!(MultiPhase::signed_submissions().is_empty());
+		})
+	}
+}
@@ -31,7 +31,10 @@ use sp_runtime::MultiAddress;
 use sp_runtime::traits::{
 	StaticLookup, LookupError, Zero, Saturating, AtLeast32Bit
 };
-use frame_support::traits::{Currency, ReservableCurrency, BalanceStatus::Reserved};
+use frame_support::{
+	metadata::TypeInfo,
+	traits::{Currency, ReservableCurrency, BalanceStatus::Reserved},
+};
 pub use weights::WeightInfo;
 
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -49,7 +52,8 @@ pub mod pallet {
 	pub trait Config: frame_system::Config {
 		/// Type used for storing an account's index; implies the maximum number of accounts the system
 		/// can hold.
-		type AccountIndex: Parameter + Member + MaybeSerializeDeserialize + Codec + Default + AtLeast32Bit + Copy;
+		type AccountIndex: Parameter + Member + MaybeSerializeDeserialize + Codec + Default
+			+ AtLeast32Bit + Copy + TypeInfo;
 
 		/// The currency trait.
 		type Currency: ReservableCurrency<Self::AccountId>;
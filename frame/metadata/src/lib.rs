@@ -28,9 +28,13 @@ use serde::Serialize;
 #[cfg(feature = "std")]
 use codec::{Decode, Input, Error};
 use codec::{Encode, Output};
-use sp_std::vec::Vec;
+use sp_std::{vec, vec::Vec};
 use sp_core::RuntimeDebug;
 
+pub mod types;
+
+use types::{PortableRegistry, Registry, TypeIndex};
+
 #[cfg(feature = "std")]
 type StringBuf = String;
 
@@ -300,6 +304,11 @@ pub enum StorageEntryType {
 		value: DecodeDifferentStr,
 		key2_hasher: StorageHasher,
 	},
+	NMap {
+		keys: DecodeDifferentArray<&'static str, StringBuf>,
+		hashers: DecodeDifferentArray<StorageHasher>,
+		value: DecodeDifferentStr,
+	},
 }
 
 /// A storage entry modifier.
@@ -366,6 +375,11 @@ pub enum RuntimeMetadata {
 	V11(RuntimeMetadataDeprecated),
 	/// Version 12 for runtime metadata.
 	V12(RuntimeMetadataV12),
+	/// Version 13 for runtime metadata. Not supported, the version is used by other
+	/// implementations of the metadata.
+	V13(RuntimeMetadataDeprecated),
+	/// Version 14 for runtime metadata, with type information.
+	V14(RuntimeMetadataV14),
 }
 
 /// Enum that should fail.
@@ -423,8 +437,221 @@ impl Into<sp_core::OpaqueMetadata> for RuntimeMetadataPrefixed {
 	}
 }
 
-impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataLastVersion {
+impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataV12 {
 	fn into(self) -> RuntimeMetadataPrefixed {
 		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V12(self))
 	}
 }
+
+impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataV14 {
+	fn into(self) -> RuntimeMetadataPrefixed {
+		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V14(self))
+	}
+}
+
+/// The metadata of a runtime, with type information.
+///
+/// Types are described in the type registry, and referred to by their index in the registry.
+#[derive(Eq, Encode, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct RuntimeMetadataV14 {
+	/// The type registry.
+	pub types: PortableRegistry,
+	/// Metadata of all the modules.
+	pub modules: Vec<ModuleMetadataV14>,
+	/// Metadata of the extrinsic.
+	pub extrinsic: ExtrinsicMetadataV14,
+}
+
+/// Metadata of the extrinsic used by the runtime, with type information.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ExtrinsicMetadataV14 {
+	/// The type of the extrinsic.
+	pub ty: TypeIndex,
+	/// Extrinsic version.
+	pub version: u8,
+	/// The signed extensions in the order they appear in the extrinsic.
+	pub signed_extensions: Vec<DecodeDifferentStr>,
+}
+
+/// All metadata about a runtime module, with type information.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ModuleMetadataV14 {
+	pub name: DecodeDifferentStr,
+	pub storage: Option<StorageMetadataV14>,
+	pub calls: Option<CallsMetadataV14>,
+	pub event: Option<EventsMetadataV14>,
+	pub constants: Vec<ModuleConstantMetadataV14>,
+	pub errors: Vec<ErrorMetadata>,
+	/// Define the index of the module, this index will be used for the encoding of module event,
+	/// call and origin variants.
+	pub index: u8,
+}
+
+/// The calls of a module.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct CallsMetadataV14 {
+	/// The type of the call enum of the module. Each variant is a call, whose fields are the
+	/// arguments of the call.
+	pub ty: TypeIndex,
+}
+
+/// The events of a module.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct EventsMetadataV14 {
+	/// The type of the event enum of the module.
+	pub ty: TypeIndex,
+}
+
+/// All the metadata about one module constant, with type information.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ModuleConstantMetadataV14 {
+	pub name: DecodeDifferentStr,
+	pub ty: TypeIndex,
+	pub value: ByteGetter,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+impl ModuleConstantMetadataV14 {
+	/// Build the constant metadata from the metadata only describing its type with its name, and
+	/// the actual type of the constant.
+	pub fn with_type(constant: &ModuleConstantMetadata, ty: TypeIndex) -> Self {
+		Self {
+			name: constant.name.clone(),
+			ty,
+			value: constant.value.clone(),
+			documentation: constant.documentation.clone(),
+		}
+	}
+
+	/// Build the constants metadata from metadata only describing types with their names.
+	pub fn from_v12(registry: &mut Registry, constants: &[ModuleConstantMetadata]) -> Vec<Self> {
+		constants.iter()
+			.map(|constant| {
+				let ty = registry.register_named(string_buf(&constant.ty));
+				Self::with_type(constant, ty)
+			})
+			.collect()
+	}
+}
+
+/// All metadata of the storage, with type information.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageMetadataV14 {
+	/// The common prefix used by all storage entries.
+	pub prefix: DecodeDifferentStr,
+	pub entries: Vec<StorageEntryMetadataV14>,
+}
+
+impl StorageMetadataV14 {
+	/// Build the storage metadata from the metadata only describing types with their names, and
+	/// the actual types of the entries, given in the same order as the entries.
+	pub fn with_types(storage: &StorageMetadata, types: Vec<StorageEntryTypeV14>) -> Self {
+		Self {
+			prefix: storage.prefix.clone(),
+			entries: slice(&storage.entries).iter()
+				.zip(types)
+				.map(|(entry, ty)| StorageEntryMetadataV14 {
+					name: entry.name.clone(),
+					modifier: entry.modifier.clone(),
+					ty,
+					default: entry.default.clone(),
+					documentation: entry.documentation.clone(),
+				})
+				.collect(),
+		}
+	}
+
+	/// Build the storage metadata from metadata only describing types with their names.
+	pub fn from_v12(registry: &mut Registry, storage: &StorageMetadata) -> Self {
+		let types = slice(&storage.entries).iter()
+			.map(|entry| match &entry.ty {
+				StorageEntryType::Plain(value) =>
+					StorageEntryTypeV14::Plain(registry.register_named(string_buf(value))),
+				StorageEntryType::Map { hasher, key, value, .. } => StorageEntryTypeV14::Map {
+					hashers: vec![hasher.clone()],
+					key: registry.register_named(string_buf(key)),
+					value: registry.register_named(string_buf(value)),
+				},
+				StorageEntryType::DoubleMap { hasher, key1, key2, value, key2_hasher } => {
+					let keys = vec![
+						registry.register_named(string_buf(key1)),
+						registry.register_named(string_buf(key2)),
+					];
+					StorageEntryTypeV14::Map {
+						hashers: vec![hasher.clone(), key2_hasher.clone()],
+						key: registry.register_tuple(keys),
+						value: registry.register_named(string_buf(value)),
+					}
+				},
+				StorageEntryType::NMap { keys, hashers, value } => {
+					let keys = string_bufs(keys).into_iter()
+						.map(|key| registry.register_named(key))
+						.collect::<Vec<_>>();
+					StorageEntryTypeV14::Map {
+						hashers: slice(hashers).to_vec(),
+						key: if keys.len() == 1 { keys[0] } else { registry.register_tuple(keys) },
+						value: registry.register_named(string_buf(value)),
+					}
+				},
+			})
+			.collect();
+
+		Self::with_types(storage, types)
+	}
+}
+
+/// All the metadata about one storage entry, with type information.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageEntryMetadataV14 {
+	pub name: DecodeDifferentStr,
+	pub modifier: StorageEntryModifier,
+	pub ty: StorageEntryTypeV14,
+	pub default: ByteGetter,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+/// A storage entry type, with type information.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum StorageEntryTypeV14 {
+	Plain(TypeIndex),
+	/// A map, with one hasher per key. Maps with multiple keys have a tuple as key.
+	Map {
+		hashers: Vec<StorageHasher>,
+		key: TypeIndex,
+		value: TypeIndex,
+	},
+}
+
+/// The elements of a decode different array whose encoded and decoded elements are the same.
+fn slice<T>(array: &DecodeDifferentArray<T>) -> &[T] {
+	match array {
+		DecodeDifferent::Encode(encode) => encode,
+		DecodeDifferent::Decoded(decoded) => &decoded[..],
+	}
+}
+
+/// The string of a decode different string.
+fn string_buf(string: &DecodeDifferentStr) -> StringBuf {
+	match string {
+		DecodeDifferent::Encode(encode) => (*encode).into(),
+		DecodeDifferent::Decoded(decoded) => decoded.clone(),
+	}
+}
+
+/// The strings of a decode different array of strings.
+fn string_bufs(strings: &DecodeDifferentArray<&'static str, StringBuf>) -> Vec<StringBuf> {
+	match strings {
+		DecodeDifferent::Encode(encode) => encode.iter().map(|s| (*s).into()).collect(),
+		DecodeDifferent::Decoded(decoded) => decoded.clone(),
+	}
+}
+
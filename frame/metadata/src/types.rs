@@ -0,0 +1,748 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Type registry of the runtime metadata.
+//!
+//! Types describe their structure through the [`TypeInfo`] trait. The structure of a type refers
+//! to other types by their index in a [`Registry`], which interns every type it is given. The
+//! metadata then refers to types by their index in the final [`PortableRegistry`].
+//!
+//! Types not implementing [`TypeInfo`] can still be registered, as opaque types only described
+//! by their name, see [`MetaType::opaque`] and [`meta_type!`](crate::meta_type).
+
+#[cfg(feature = "std")]
+use serde::Serialize;
+#[cfg(feature = "std")]
+use codec::Decode;
+use codec::{Encode, Compact};
+use sp_std::{
+	any::TypeId,
+	boxed::Box,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	marker::PhantomData,
+	vec::Vec,
+	vec,
+};
+use sp_core::RuntimeDebug;
+use super::StringBuf;
+
+/// The index of a type in the registry.
+pub type TypeIndex = u32;
+
+/// Describes the structure of a type, registering the types it is made of into the given
+/// registry.
+pub trait TypeInfo {
+	/// The type actually described, e.g. `T` for `&T` or `Box<T>`.
+	///
+	/// Types with the same identity are registered only once.
+	type Identity: ?Sized + 'static;
+
+	/// Return the description of the type, registering the types it refers to into `registry`.
+	fn type_info(registry: &mut Registry) -> Type;
+}
+
+/// A type whose description can be registered into a [`Registry`].
+#[derive(Clone, Copy)]
+pub struct MetaType {
+	type_id: TypeId,
+	type_info: fn(&mut Registry) -> Type,
+	opaque: bool,
+}
+
+impl MetaType {
+	/// The meta type of a type implementing [`TypeInfo`].
+	pub fn new<T: TypeInfo + ?Sized>() -> Self {
+		Self { type_id: TypeId::of::<T::Identity>(), type_info: T::type_info, opaque: false }
+	}
+
+	/// The meta type of a type not implementing [`TypeInfo`].
+	///
+	/// The type is registered as [`TypeDef::Opaque`], only described by its name. If the same
+	/// type is later registered with its structure, the opaque description is replaced.
+	pub fn opaque<T: ?Sized + 'static>() -> Self {
+		Self { type_id: TypeId::of::<T>(), type_info: opaque_type_info::<T>, opaque: true }
+	}
+}
+
+fn opaque_type_info<T: ?Sized>(_: &mut Registry) -> Type {
+	Type::new(vec![sp_std::any::type_name::<T>().into()], TypeDef::Opaque)
+}
+
+/// Helper to build the [`MetaType`] of a type which may or may not implement [`TypeInfo`].
+///
+/// Method resolution picks [`MetaTypeWithInfo`] when the type implements [`TypeInfo`], and falls
+/// back to [`MetaTypeOpaque`] otherwise. Note that in a generic context the resolution happens
+/// with the bounds known at that point, e.g. an associated type of a pallet's `Config` is opaque
+/// unless bounded by `TypeInfo`. Use [`meta_type!`](crate::meta_type) rather than this type
+/// directly.
+pub struct MetaTypeProbe<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> MetaTypeProbe<T> {
+	/// Create a new probe.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+/// Resolution of [`MetaTypeProbe`] for types implementing [`TypeInfo`].
+pub trait MetaTypeWithInfo {
+	/// The meta type of the probed type.
+	fn meta_type(&self) -> MetaType;
+}
+
+impl<T: TypeInfo + ?Sized> MetaTypeWithInfo for &MetaTypeProbe<T> {
+	fn meta_type(&self) -> MetaType {
+		MetaType::new::<T>()
+	}
+}
+
+/// Resolution of [`MetaTypeProbe`] for types not implementing [`TypeInfo`].
+pub trait MetaTypeOpaque {
+	/// The meta type of the probed type.
+	fn meta_type(&self) -> MetaType;
+}
+
+impl<T: ?Sized + 'static> MetaTypeOpaque for MetaTypeProbe<T> {
+	fn meta_type(&self) -> MetaType {
+		MetaType::opaque::<T>()
+	}
+}
+
+/// Return the [`MetaType`] of the given type, whether or not it implements [`TypeInfo`].
+///
+/// E.g. `meta_type!(T::Balance)`.
+#[macro_export]
+macro_rules! meta_type {
+	($ty:ty) => {{
+		#[allow(unused_imports)]
+		use $crate::types::{MetaTypeWithInfo as _, MetaTypeOpaque as _};
+		(&&$crate::types::MetaTypeProbe::<$ty>::new()).meta_type()
+	}};
+}
+
+/// Interns types and assigns them an index.
+#[derive(Default)]
+pub struct Registry {
+	ids: BTreeMap<TypeId, TypeIndex>,
+	names: BTreeMap<StringBuf, TypeIndex>,
+	tuples: BTreeMap<Vec<TypeIndex>, TypeIndex>,
+	compacts: BTreeMap<TypeIndex, TypeIndex>,
+	types: Vec<RegistryEntry>,
+}
+
+struct RegistryEntry {
+	ty: Option<Type>,
+	opaque: bool,
+}
+
+impl Registry {
+	/// Create an empty registry.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register the type and return its index.
+	///
+	/// The index of a type is reserved before its description is computed, so recursive types
+	/// refer to themselves by this index.
+	pub fn register(&mut self, meta: MetaType) -> TypeIndex {
+		if let Some(&index) = self.ids.get(&meta.type_id) {
+			let entry = &self.types[index as usize];
+			// The structure of the type replaces its opaque description.
+			if entry.opaque && !meta.opaque && entry.ty.is_some() {
+				self.types[index as usize].opaque = false;
+				self.types[index as usize].ty = None;
+				let ty = (meta.type_info)(self);
+				self.types[index as usize].ty = Some(ty);
+			}
+			return index
+		}
+
+		let index = self.types.len() as TypeIndex;
+		self.ids.insert(meta.type_id, index);
+		self.types.push(RegistryEntry { ty: None, opaque: meta.opaque });
+		let ty = (meta.type_info)(self);
+		self.types[index as usize].ty = Some(ty);
+		index
+	}
+
+	/// Register the type implementing [`TypeInfo`] and return its index.
+	pub fn register_type<T: TypeInfo + ?Sized>(&mut self) -> TypeIndex {
+		self.register(MetaType::new::<T>())
+	}
+
+	/// Register an opaque type only known by its name, and return its index.
+	///
+	/// This is used for metadata only describing types with their names.
+	pub fn register_named(&mut self, name: StringBuf) -> TypeIndex {
+		if let Some(&index) = self.names.get(&name) {
+			return index
+		}
+
+		let index = self.types.len() as TypeIndex;
+		self.names.insert(name.clone(), index);
+		self.types.push(RegistryEntry {
+			ty: Some(Type::new(vec![name], TypeDef::Opaque)),
+			opaque: true,
+		});
+		index
+	}
+
+	/// Register the tuple of the given registered types, and return its index.
+	pub fn register_tuple(&mut self, fields: Vec<TypeIndex>) -> TypeIndex {
+		if let Some(&index) = self.tuples.get(&fields) {
+			return index
+		}
+
+		let index = self.register_description(Type::new(Vec::new(), TypeDef::Tuple(fields.clone())));
+		self.tuples.insert(fields, index);
+		index
+	}
+
+	/// Register the compact encoding of the given registered type, and return its index.
+	pub fn register_compact(&mut self, inner: TypeIndex) -> TypeIndex {
+		if let Some(&index) = self.compacts.get(&inner) {
+			return index
+		}
+
+		let index = self.register_description(Type::new(Vec::new(), TypeDef::Compact(inner)));
+		self.compacts.insert(inner, index);
+		index
+	}
+
+	/// Register a type given its full description, and return its index.
+	///
+	/// The type is not interned, each call registers a new type.
+	pub fn register_description(&mut self, ty: Type) -> TypeIndex {
+		let index = self.types.len() as TypeIndex;
+		self.types.push(RegistryEntry { ty: Some(ty), opaque: false });
+		index
+	}
+
+	/// Finalize the registry.
+	pub fn into_portable(self) -> PortableRegistry {
+		PortableRegistry {
+			types: self.types.into_iter()
+				.map(|entry| entry.ty.expect("Description is computed after index reservation; qed"))
+				.collect(),
+		}
+	}
+}
+
+/// All the types of the metadata, the index of a type being its position.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct PortableRegistry {
+	pub types: Vec<Type>,
+}
+
+impl PortableRegistry {
+	/// Return the type at the given index.
+	pub fn resolve(&self, index: TypeIndex) -> Option<&Type> {
+		self.types.get(index as usize)
+	}
+}
+
+/// The description of a type.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct Type {
+	/// The path of the type, i.e. its module path followed by its name. Empty for types which
+	/// have no name, e.g. tuples, arrays or primitives.
+	pub path: Vec<StringBuf>,
+	/// The generic parameters of the type.
+	pub type_params: Vec<TypeIndex>,
+	/// The structure of the type.
+	pub def: TypeDef,
+	/// The documentation of the type.
+	pub docs: Vec<StringBuf>,
+}
+
+impl Type {
+	/// Create a type with the given path and structure.
+	pub fn new(path: Vec<StringBuf>, def: TypeDef) -> Self {
+		Self { path, type_params: Vec::new(), def, docs: Vec::new() }
+	}
+
+	/// Set the generic parameters of the type.
+	pub fn type_params(mut self, type_params: Vec<TypeIndex>) -> Self {
+		self.type_params = type_params;
+		self
+	}
+
+	/// Set the documentation of the type.
+	pub fn docs(mut self, docs: Vec<StringBuf>) -> Self {
+		self.docs = docs;
+		self
+	}
+}
+
+/// The structure of a type.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum TypeDef {
+	/// A struct, with named or unnamed fields.
+	Composite(Vec<Field>),
+	/// An enum, each variant having named or unnamed fields.
+	Variant(Vec<Variant>),
+	/// A sequence of values of the given type, encoded with its length, e.g. `Vec<T>`.
+	Sequence(TypeIndex),
+	/// A fixed size array, e.g. `[T; 32]`.
+	Array {
+		len: u32,
+		ty: TypeIndex,
+	},
+	/// A tuple.
+	Tuple(Vec<TypeIndex>),
+	/// A primitive type.
+	Primitive(TypeDefPrimitive),
+	/// The compact encoding of the given type.
+	Compact(TypeIndex),
+	/// A type whose structure is unknown, only described by its path.
+	Opaque,
+}
+
+/// A field of a struct or of an enum variant.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct Field {
+	/// The name of the field, `None` for unnamed fields.
+	pub name: Option<StringBuf>,
+	/// The type of the field.
+	pub ty: TypeIndex,
+	/// The name of the type of the field as written in the source code.
+	pub type_name: Option<StringBuf>,
+	/// The documentation of the field.
+	pub docs: Vec<StringBuf>,
+}
+
+/// A variant of an enum.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct Variant {
+	/// The name of the variant.
+	pub name: StringBuf,
+	/// The fields of the variant.
+	pub fields: Vec<Field>,
+	/// The index of the variant in the encoding.
+	pub index: u8,
+	/// The documentation of the variant.
+	pub docs: Vec<StringBuf>,
+}
+
+/// A primitive type.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum TypeDefPrimitive {
+	Bool,
+	Char,
+	Str,
+	U8,
+	U16,
+	U32,
+	U64,
+	U128,
+	U256,
+	I8,
+	I16,
+	I32,
+	I64,
+	I128,
+	I256,
+}
+
+macro_rules! impl_primitives {
+	( $( $ty:ty => $primitive:ident, )* ) => {
+		$(
+			impl TypeInfo for $ty {
+				type Identity = Self;
+
+				fn type_info(_: &mut Registry) -> Type {
+					Type::new(Vec::new(), TypeDef::Primitive(TypeDefPrimitive::$primitive))
+				}
+			}
+		)*
+	}
+}
+
+impl_primitives! {
+	bool => Bool,
+	char => Char,
+	str => Str,
+	u8 => U8,
+	u16 => U16,
+	u32 => U32,
+	u64 => U64,
+	u128 => U128,
+	i8 => I8,
+	i16 => I16,
+	i32 => I32,
+	i64 => I64,
+	i128 => I128,
+}
+
+impl TypeInfo for sp_core::U256 {
+	type Identity = Self;
+
+	fn type_info(_: &mut Registry) -> Type {
+		Type::new(Vec::new(), TypeDef::Primitive(TypeDefPrimitive::U256))
+	}
+}
+
+#[cfg(feature = "std")]
+impl TypeInfo for String {
+	type Identity = str;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		<str as TypeInfo>::type_info(registry)
+	}
+}
+
+impl<T: TypeInfo + ?Sized> TypeInfo for &T {
+	type Identity = T::Identity;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		T::type_info(registry)
+	}
+}
+
+impl<T: TypeInfo + ?Sized> TypeInfo for &mut T {
+	type Identity = T::Identity;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		T::type_info(registry)
+	}
+}
+
+impl<T: TypeInfo + ?Sized> TypeInfo for Box<T> {
+	type Identity = T::Identity;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		T::type_info(registry)
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for [T] {
+	type Identity = Self;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::new(Vec::new(), TypeDef::Sequence(registry.register_type::<T>()))
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Vec<T> {
+	type Identity = [T];
+
+	fn type_info(registry: &mut Registry) -> Type {
+		<[T]>::type_info(registry)
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for BTreeSet<T> {
+	type Identity = Self;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		let param = registry.register_type::<T>();
+		Type::new(
+			vec!["BTreeSet".into()],
+			TypeDef::Composite(vec![Field {
+				name: None,
+				ty: registry.register_type::<[T]>(),
+				type_name: None,
+				docs: Vec::new(),
+			}]),
+		).type_params(vec![param])
+	}
+}
+
+impl<K: TypeInfo + 'static, V: TypeInfo + 'static> TypeInfo for BTreeMap<K, V> {
+	type Identity = Self;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		let params = vec![registry.register_type::<K>(), registry.register_type::<V>()];
+		Type::new(
+			vec!["BTreeMap".into()],
+			TypeDef::Composite(vec![Field {
+				name: None,
+				ty: registry.register_type::<[(K, V)]>(),
+				type_name: None,
+				docs: Vec::new(),
+			}]),
+		).type_params(params)
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Option<T> {
+	type Identity = Self;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		let param = registry.register_type::<T>();
+		Type::new(
+			vec!["Option".into()],
+			TypeDef::Variant(vec![
+				Variant { name: "None".into(), fields: Vec::new(), index: 0, docs: Vec::new() },
+				Variant {
+					name: "Some".into(),
+					fields: vec![Field { name: None, ty: param, type_name: None, docs: Vec::new() }],
+					index: 1,
+					docs: Vec::new(),
+				},
+			]),
+		).type_params(vec![param])
+	}
+}
+
+impl<T: TypeInfo + 'static, E: TypeInfo + 'static> TypeInfo for Result<T, E> {
+	type Identity = Self;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		let ok = registry.register_type::<T>();
+		let err = registry.register_type::<E>();
+		Type::new(
+			vec!["Result".into()],
+			TypeDef::Variant(vec![
+				Variant {
+					name: "Ok".into(),
+					fields: vec![Field { name: None, ty: ok, type_name: None, docs: Vec::new() }],
+					index: 0,
+					docs: Vec::new(),
+				},
+				Variant {
+					name: "Err".into(),
+					fields: vec![Field { name: None, ty: err, type_name: None, docs: Vec::new() }],
+					index: 1,
+					docs: Vec::new(),
+				},
+			]),
+		).type_params(vec![ok, err])
+	}
+}
+
+impl<T: TypeInfo + 'static> TypeInfo for Compact<T> {
+	type Identity = Self;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::new(Vec::new(), TypeDef::Compact(registry.register_type::<T>()))
+	}
+}
+
+impl<T: ?Sized + 'static> TypeInfo for PhantomData<T> {
+	type Identity = Self;
+
+	fn type_info(_: &mut Registry) -> Type {
+		Type::new(Vec::new(), TypeDef::Tuple(Vec::new()))
+	}
+}
+
+macro_rules! impl_tuples {
+	( $( ( $( $param:ident ),* ) )* ) => {
+		$(
+			impl<$( $param: TypeInfo + 'static ),*> TypeInfo for ( $( $param, )* ) {
+				type Identity = Self;
+
+				#[allow(unused_variables)]
+				fn type_info(registry: &mut Registry) -> Type {
+					Type::new(
+						Vec::new(),
+						TypeDef::Tuple(vec![ $( registry.register_type::<$param>() ),* ]),
+					)
+				}
+			}
+		)*
+	}
+}
+
+impl_tuples! {
+	()
+	(A)
+	(A, B)
+	(A, B, C)
+	(A, B, C, D)
+	(A, B, C, D, E)
+	(A, B, C, D, E, F)
+	(A, B, C, D, E, F, G)
+	(A, B, C, D, E, F, G, H)
+	(A, B, C, D, E, F, G, H, I)
+	(A, B, C, D, E, F, G, H, I, J)
+	(A, B, C, D, E, F, G, H, I, J, K)
+	(A, B, C, D, E, F, G, H, I, J, K, L)
+}
+
+macro_rules! impl_arrays {
+	( $( $len:expr ),* ) => {
+		$(
+			impl<T: TypeInfo + 'static> TypeInfo for [T; $len] {
+				type Identity = Self;
+
+				fn type_info(registry: &mut Registry) -> Type {
+					Type::new(
+						Vec::new(),
+						TypeDef::Array { len: $len, ty: registry.register_type::<T>() },
+					)
+				}
+			}
+		)*
+	}
+}
+
+impl_arrays!(
+	0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+	26, 27, 28, 29, 30, 31, 32, 33, 40, 48, 56, 64, 65, 72, 96, 128, 160, 192, 224, 256, 512,
+	1024, 2048
+);
+
+macro_rules! impl_fixed_hashes {
+	( $( $ty:ident => $len:expr, )* ) => {
+		$(
+			impl TypeInfo for sp_core::$ty {
+				type Identity = Self;
+
+				fn type_info(registry: &mut Registry) -> Type {
+					Type::new(
+						vec!["primitive_types".into(), stringify!($ty).into()],
+						TypeDef::Composite(vec![Field {
+							name: None,
+							ty: registry.register_type::<[u8; $len]>(),
+							type_name: Some(concat!("[u8; ", stringify!($len), "]").into()),
+							docs: Vec::new(),
+						}]),
+					)
+				}
+			}
+		)*
+	}
+}
+
+impl_fixed_hashes! {
+	H160 => 20,
+	H256 => 32,
+	H512 => 64,
+}
+
+impl TypeInfo for sp_core::crypto::AccountId32 {
+	type Identity = Self;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		Type::new(
+			vec!["sp_core".into(), "crypto".into(), "AccountId32".into()],
+			TypeDef::Composite(vec![Field {
+				name: None,
+				ty: registry.register_type::<[u8; 32]>(),
+				type_name: Some("[u8; 32]".into()),
+				docs: Vec::new(),
+			}]),
+		)
+	}
+}
+
+macro_rules! impl_public_keys {
+	( $( $module:ident => $len:expr, )* ) => {
+		$(
+			impl TypeInfo for sp_core::$module::Public {
+				type Identity = Self;
+
+				fn type_info(registry: &mut Registry) -> Type {
+					Type::new(
+						vec!["sp_core".into(), stringify!($module).into(), "Public".into()],
+						TypeDef::Composite(vec![Field {
+							name: None,
+							ty: registry.register_type::<[u8; $len]>(),
+							type_name: Some(concat!("[u8; ", stringify!($len), "]").into()),
+							docs: Vec::new(),
+						}]),
+					)
+				}
+			}
+		)*
+	}
+}
+
+impl_public_keys! {
+	ed25519 => 32,
+	sr25519 => 32,
+	ecdsa => 33,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Recursive(Vec<Recursive>);
+
+	impl TypeInfo for Recursive {
+		type Identity = Self;
+
+		fn type_info(registry: &mut Registry) -> Type {
+			Type::new(
+				vec!["Recursive".into()],
+				TypeDef::Composite(vec![Field {
+					name: None,
+					ty: registry.register_type::<Vec<Recursive>>(),
+					type_name: Some("Vec<Recursive>".into()),
+					docs: Vec::new(),
+				}]),
+			)
+		}
+	}
+
+	struct NoInfo;
+
+	#[test]
+	fn types_are_interned() {
+		let mut registry = Registry::new();
+		assert_eq!(registry.register_type::<u32>(), 0);
+		assert_eq!(registry.register_type::<Vec<u32>>(), 1);
+		assert_eq!(registry.register_type::<&[u32]>(), 1);
+		assert_eq!(registry.register_type::<Box<u32>>(), 0);
+		assert_eq!(registry.register_named("Foo".into()), 2);
+		assert_eq!(registry.register_named("Foo".into()), 2);
+
+		let registry = registry.into_portable();
+		assert_eq!(
+			registry.resolve(0).unwrap().def,
+			TypeDef::Primitive(TypeDefPrimitive::U32),
+		);
+		assert_eq!(registry.resolve(1).unwrap().def, TypeDef::Sequence(0));
+		assert_eq!(registry.resolve(2).unwrap().def, TypeDef::Opaque);
+	}
+
+	#[test]
+	fn recursive_types_are_registered() {
+		let mut registry = Registry::new();
+		assert_eq!(registry.register_type::<Recursive>(), 0);
+
+		let registry = registry.into_portable();
+		assert_eq!(registry.types.len(), 2);
+		assert_eq!(registry.resolve(1).unwrap().def, TypeDef::Sequence(0));
+	}
+
+	#[test]
+	fn opaque_types_are_upgraded() {
+		let mut registry = Registry::new();
+		assert_eq!(registry.register(meta_type!(NoInfo)), 0);
+		assert_eq!(registry.register(MetaType::opaque::<u8>()), 1);
+		assert_eq!(registry.register(meta_type!(u8)), 1);
+
+		let registry = registry.into_portable();
+		assert_eq!(registry.resolve(0).unwrap().def, TypeDef::Opaque);
+		assert!(registry.resolve(0).unwrap().path[0].ends_with("NoInfo"));
+		assert_eq!(
+			registry.resolve(1).unwrap().def,
+			TypeDef::Primitive(TypeDefPrimitive::U8),
+		);
+	}
+}
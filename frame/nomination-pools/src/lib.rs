@@ -0,0 +1,2041 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Nomination Pools for Staking Delegation
+//!
+//! A pallet that allows members to delegate their stake to nominating pools. A nomination pool
+//! acts as nominator and nominates validators on the members behalf.
+//!
+//! ## Key terms
+//!
+//! * bonded pool: Tracks the distribution of actively staked funds. See [`BondedPool`] and
+//!   [`BondedPoolInner`].
+//! * reward pool: Tracks rewards earned by actively staked funds. See [`RewardPool`] and
+//!   [`RewardPools`].
+//! * unbonding sub pools: Collection of pools at different phases of the unbonding lifecycle. See
+//!   [`SubPools`] and [`SubPoolsStorage`].
+//! * members: Accounts that are members of pools. See [`PoolMember`] and [`PoolMembers`].
+//! * roles: Administrative roles of each pool, capable of controlling nomination, and the state of
+//!   the pool.
+//! * point: A unit of measure for a members portion of a pool's funds. Points initially have a
+//!   ratio of 1 (as set by `POINTS_TO_BALANCE_INIT_RATIO`) to balance, but as slashing happens,
+//!   this can change.
+//! * kick: The act of a pool administrator forcibly ejecting a member.
+//!
+//! ## Usage
+//!
+//! ### Join
+//!
+//! An account can stake funds with a nomination pool by calling [`Call::join`].
+//!
+//! ### Claim rewards
+//!
+//! After joining a pool, a member can claim rewards by calling [`Call::claim_payout`].
+//!
+//! For design docs see the [reward pool](#reward-pool) section.
+//!
+//! ### Leave
+//!
+//! In order to leave, a member must take two steps.
+//!
+//! First, they must call [`Call::unbond`]. The unbond extrinsic will start the unbonding process
+//! by unbonding all or a portion of the members funds.
+//!
+//! Second, once [`sp_staking::StakingInterface::bonding_duration`] eras have passed, the member
+//! can call [`Call::withdraw_unbonded`] to withdraw any funds that are free.
+//!
+//! For design docs see the [bonded pool](#bonded-pool) and [unbonding sub
+//! pools](#unbonding-sub-pools) sections.
+//!
+//! ### Slashes
+//!
+//! Slashes are distributed evenly across the bonded pool and the unbonding pools from slash era+1
+//! through the slash apply era. Thus, any member who either
+//!
+//! 1. unbonded, or
+//! 2. was actively bonded
+//!
+//! in the aforementioned range of eras will be affected by the slash. A member is slashed pro-rata
+//! based on its stake relative to the total slash amount.
+//!
+//! For design docs see the [slashing](#slashing) section.
+//!
+//! ### Administration
+//!
+//! A pool can be created with the [`Call::create`] call. Once created, the pools nominator or root
+//! user must call [`Call::nominate`] to start nominating. [`Call::nominate`] can be called at
+//! anytime to update validator selection.
+//!
+//! To help facilitate pool administration the pool has one of three states (see [`PoolState`]):
+//!
+//! * Open: Anyone can join the pool and no members can be permissionlessly removed.
+//! * Blocked: No members can join and some admin roles can kick members.
+//! * Destroying: No members can join and all members can be permissionlessly removed with
+//!   [`Call::unbond`] and [`Call::withdraw_unbonded`]. Once a pool is in destroying state, it
+//!   cannot be reverted to another state.
+//!
+//! A pool has 3 administrative roles (see [`PoolRoles`]):
+//!
+//! * Depositor: creates the pool and is the initial member. They can only leave the pool once all
+//!   other members have left. Once they fully leave the pool is destroyed.
+//! * Nominator: can select which validators the pool nominates.
+//! * State-Toggler: can change the pools state and kick members if the pool is blocked.
+//! * Root: can change the nominator, state-toggler, or itself and can perform any of the actions
+//!   the nominator or state-toggler can.
+//!
+//! ## Design
+//!
+//! _Notes_: this section uses pseudo code to explain general design and does not necessarily
+//! reflect the exact implementation. Additionally, a working knowledge of `pallet-staking`'s api
+//! is assumed.
+//!
+//! ### Goals
+//!
+//! * Maintain network security by upholding integrity of slashing events, sufficiently penalizing
+//!   members that where in the pool while it was backing a validator that got slashed.
+//! * Maximize scalability in terms of member count.
+//!
+//! ### Bonded pool
+//!
+//! A bonded pool nominates with its total balance, excluding that which has been withdrawn for
+//! unbonding. The total points of a bonded pool are always equal to the sum of points of the
+//! delegation members. A bonded pool tracks its points and reads its bonded balance.
+//!
+//! When a member joins a pool, `amount_transferred` is transferred from the members account to the
+//! bonded pools account. Then the pool calls `staking::bond_extra(amount_transferred)` and issues
+//! new points which are tracked by the member and added to the bonded pool's points.
+//!
+//! When the pool already has some balance, we want the value of a point before the transfer to
+//! equal the value of a point after the transfer. So, when a member joins a bonded pool with a
+//! given `amount_transferred`, we maintain the ratio of bonded balance to points such that:
+//!
+//! ```text
+//! balance_after / points_after == balance_before / points_before;
+//! ```
+//!
+//! To achieve this, we issue points based on the following:
+//!
+//! ```text
+//! points_issued = (points_before_transfer / balance_before_transfer) * amount_transferred;
+//! ```
+//!
+//! For new bonded pools we can set the points issued per balance arbitrarily. In this
+//! implementation we use a 1 points to 1 balance ratio for pool creation (see
+//! `POINTS_TO_BALANCE_INIT_RATIO`).
+//!
+//! **Relevant extrinsics:**
+//!
+//! * [`Call::create`]
+//! * [`Call::join`]
+//!
+//! ### Reward pool
+//!
+//! When a pool is first bonded it sets up a deterministic, inaccessible account as its reward
+//! destination. This reward account holds the existential deposit, which is provided by the
+//! depositor upon creation and returned to them once the pool is dissolved.
+//!
+//! The reward pool keeps a _reward counter_: the amount of rewards that a single point of the
+//! bonded pool has earned since the creation of the pool. Every time the number of points in the
+//! bonded pool changes, the counter is brought up to date first, such that all rewards that came
+//! in before the change are distributed among the points that existed at that time:
+//!
+//! ```text
+//! reward_counter += rewards_since_last_record / bonded_pool_points;
+//! ```
+//!
+//! Each member records the value of the reward counter at the time they last claimed. Their
+//! pending rewards are then simply:
+//!
+//! ```text
+//! pending_rewards = (current_reward_counter - member_last_recorded_counter) * member_points;
+//! ```
+//!
+//! **Relevant extrinsics:**
+//!
+//! * [`Call::claim_payout`]
+//!
+//! ### Unbonding sub pools
+//!
+//! When a member unbonds, its balance is unbonded in the bonded pool's account and tracked in an
+//! unbonding pool associated with the era in which the funds will be unlocked. An unbonding pool
+//! issues points in the same manner as the bonded pool, which allows slashes to be shared
+//! pro-rata between all members that unbonded in the same era.
+//!
+//! Unbonding pools whose unlock era lies more than [`Config::PostUnbondingPoolsWindow`] eras in
+//! the past are merged into a single, era-less pool. Such funds can no longer be slashed, so
+//! merging them does not affect fairness, and it bounds the number of unbonding pools per bonded
+//! pool.
+//!
+//! **Relevant extrinsics:**
+//!
+//! * [`Call::unbond`]
+//! * [`Call::withdraw_unbonded`]
+//!
+//! ### Slashing
+//!
+//! This section assumes that the slash computation is executed by
+//! `pallet_staking::StakingLedger::slash`, which passes the information to this pallet via
+//! [`sp_staking::OnStakerSlash::on_slash`].
+//!
+//! Unbonding pools need to be slashed to ensure all nominators whom where in the bonded pool while
+//! it was backing a validator that equivocated are punished. Without these measures a member
+//! could unbond right after a validator equivocated with no consequences.
+//!
+//! The bonded pool's balance is read from the staking system, so it needs no extra bookkeeping.
+//! For every unbonding era touched by a slash, the staking system reports the balance that is left
+//! to unlock, and the matching unbonding pool adopts it as its new balance.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	ensure,
+	traits::{Currency, ExistenceRequirement, Get},
+	transactional, BoundedVec, CloneNoBound, DebugNoBound, DefaultNoBound, PalletId,
+	PartialEqNoBound,
+};
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational,
+	traits::{
+		AccountIdConversion, Bounded, CheckedAdd, CheckedSub, Saturating, SaturatedConversion, Zero,
+	},
+	FixedPointNumber, FixedU128,
+};
+use sp_staking::{EraIndex, OnStakerSlash, StakingInterface};
+use sp_std::{
+	collections::btree_map::BTreeMap,
+	convert::TryInto,
+	ops::{Deref, DerefMut},
+	prelude::*,
+};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub(crate) const LOG_TARGET: &'static str = "runtime::nomination-pools";
+
+// syntactic sugar for logging.
+#[macro_export]
+macro_rules! log {
+	($level:tt, $patter:expr $(, $values:expr)* $(,)?) => {
+		log::$level!(
+			target: crate::LOG_TARGET,
+			concat!("[{:?}] 🏊 ", $patter), <frame_system::Pallet<T>>::block_number() $(, $values)*
+		)
+	};
+}
+
+/// The balance type used by the currency system.
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Type used to track the points of a reward pool.
+pub type RewardCounter = FixedU128;
+
+/// Type used for unique identifier of each pool.
+pub type PoolId = u32;
+
+/// The initial ratio of points to balance when a pool is created.
+pub const POINTS_TO_BALANCE_INIT_RATIO: u32 = 1;
+
+/// Possible operations on the configuration values of this pallet.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub enum ConfigOp<T> {
+	/// Don't change.
+	Noop,
+	/// Set the given value.
+	Set(T),
+	/// Remove from storage.
+	Remove,
+}
+
+/// The type of bonding that can happen to a pool.
+enum BondType {
+	/// Someone is bonding into the pool upon creation.
+	Create,
+	/// Someone is adding more funds later to this pool.
+	Later,
+}
+
+/// How to increase the bond of a member.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, sp_runtime::RuntimeDebug)]
+pub enum BondExtra<Balance> {
+	/// Take from the free balance.
+	FreeBalance(Balance),
+	/// Take the entire amount from the accumulated rewards.
+	Rewards,
+}
+
+/// The type of account being created.
+#[derive(Encode, Decode)]
+enum AccountType {
+	Bonded,
+	Reward,
+}
+
+/// A member in a pool.
+#[derive(Encode, Decode, CloneNoBound, DebugNoBound, PartialEqNoBound)]
+pub struct PoolMember<T: Config> {
+	/// The identifier of the pool to which `who` belongs.
+	pub pool_id: PoolId,
+	/// The quantity of points this member has in the bonded pool or in a sub pool if
+	/// `Self::unbonding_era` is some.
+	pub points: BalanceOf<T>,
+	/// The reward counter at the time of this member's last payout claim.
+	pub last_recorded_reward_counter: RewardCounter,
+	/// The eras in which this member is unbonding, mapped from era index to the number of
+	/// points scheduled to unbond in the given era.
+	pub unbonding_eras: BTreeMap<EraIndex, BalanceOf<T>>,
+}
+
+impl<T: Config> PoolMember<T> {
+	/// The pending rewards of this member.
+	fn pending_rewards(
+		&self,
+		current_reward_counter: RewardCounter,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		// accuracy note: `current_reward_counter` is the total reward a point of the bonded pool
+		// has earned since the creation of the pool, so it can only ever grow.
+		current_reward_counter
+			.saturating_sub(self.last_recorded_reward_counter)
+			.checked_mul_int(self.active_points().saturated_into::<u128>())
+			.map(|pending| pending.saturated_into())
+			.ok_or(Error::<T>::OverflowRisk)
+	}
+
+	/// Active balance of the member.
+	///
+	/// This is derived from the ratio of points in the pool to which the member belongs to.
+	/// Might return different values based on the pool state for the same member and points.
+	pub fn active_balance(&self) -> BalanceOf<T> {
+		if let Some(pool) = BondedPool::<T>::get(self.pool_id) {
+			pool.points_to_balance(self.points)
+		} else {
+			Zero::zero()
+		}
+	}
+
+	/// Total points of this member, both active and unbonding.
+	pub fn total_points(&self) -> BalanceOf<T> {
+		self.active_points().saturating_add(self.unbonding_points())
+	}
+
+	/// Active points of the member.
+	pub fn active_points(&self) -> BalanceOf<T> {
+		self.points
+	}
+
+	/// Inactive points of the member, waiting to be withdrawn.
+	pub fn unbonding_points(&self) -> BalanceOf<T> {
+		self.unbonding_eras
+			.values()
+			.fold(Zero::zero(), |acc: BalanceOf<T>, v| acc.saturating_add(*v))
+	}
+
+	/// Try and unbond `points_dissolved` from self, and in return mint `points_issued` into the
+	/// corresponding `era`'s unlock schedule.
+	///
+	/// In the absence of slashing, these two points are always the same. In the presence of
+	/// slashing, the value of points in different pools varies.
+	///
+	/// Returns `Ok(())` and updates `unbonding_eras` and `points` if success, `Err(_)` otherwise.
+	fn try_unbond(
+		&mut self,
+		points_dissolved: BalanceOf<T>,
+		points_issued: BalanceOf<T>,
+		unbonding_era: EraIndex,
+	) -> Result<(), Error<T>> {
+		let new_points =
+			self.points.checked_sub(&points_dissolved).ok_or(Error::<T>::MinimumBondNotMet)?;
+		match self.unbonding_eras.get_mut(&unbonding_era) {
+			Some(already_unbonding) =>
+				*already_unbonding = already_unbonding.saturating_add(points_issued),
+			None => {
+				ensure!(
+					(self.unbonding_eras.len() as u32) < T::MaxUnbonding::get(),
+					Error::<T>::MaxUnbondingLimit,
+				);
+				self.unbonding_eras.insert(unbonding_era, points_issued);
+			},
+		}
+		self.points = new_points;
+		Ok(())
+	}
+
+	/// Withdraw any funds in [`Self::unbonding_eras`] who's deadline in reached and is fully
+	/// unlocked.
+	///
+	/// Returns a a subset of [`Self::unbonding_eras`] that got withdrawn.
+	fn withdraw_unlocked(&mut self, current_era: EraIndex) -> BTreeMap<EraIndex, BalanceOf<T>> {
+		let still_locked = self.unbonding_eras.split_off(&current_era.saturating_add(1));
+		sp_std::mem::replace(&mut self.unbonding_eras, still_locked)
+	}
+}
+
+/// A pool's possible states.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, sp_runtime::RuntimeDebug)]
+pub enum PoolState {
+	/// The pool is open to be joined, and is working normally.
+	Open,
+	/// The pool is blocked. No one else can join.
+	Blocked,
+	/// The pool is in the process of being destroyed.
+	///
+	/// All members can now be permissionlessly unbonded, and the pool can never go back to any
+	/// other state other than being dissolved.
+	Destroying,
+}
+
+/// Pool administration roles.
+///
+/// Any pool has a depositor, which can never change. But, all the other roles are optional, and
+/// cannot exist. Note that if `root` is set to `None`, it basically means that the roles of this
+/// pool can never change again (except via governance).
+#[derive(Encode, Decode, PartialEq, Clone, sp_runtime::RuntimeDebug)]
+pub struct PoolRoles<AccountId> {
+	/// Creates the pool and is the initial member. They can only leave the pool once all other
+	/// members have left. Once they fully leave, the pool is destroyed.
+	pub depositor: AccountId,
+	/// Can change the nominator, state-toggler, or itself and can perform any of the actions the
+	/// nominator or state-toggler can.
+	pub root: Option<AccountId>,
+	/// Can select which validators the pool nominates.
+	pub nominator: Option<AccountId>,
+	/// Can change the pools state and kick members if the pool is blocked.
+	pub state_toggler: Option<AccountId>,
+}
+
+/// Pool permissions and state.
+#[derive(Encode, Decode, CloneNoBound, DebugNoBound, PartialEqNoBound)]
+pub struct BondedPoolInner<T: Config> {
+	/// Total points of all the members in the pool who are actively bonded.
+	pub points: BalanceOf<T>,
+	/// The current state of the pool.
+	pub state: PoolState,
+	/// Count of members that belong to the pool.
+	pub member_counter: u32,
+	/// See [`PoolRoles`].
+	pub roles: PoolRoles<T::AccountId>,
+}
+
+/// A wrapper for bonded pools, with utility functions.
+///
+/// The main purpose of this is to wrap a [`BondedPoolInner`], with the account + id of the pool,
+/// for easier access.
+#[derive(DebugNoBound)]
+#[cfg_attr(feature = "std", derive(CloneNoBound, PartialEqNoBound))]
+pub struct BondedPool<T: Config> {
+	/// The identifier of the pool.
+	id: PoolId,
+	/// The inner fields.
+	inner: BondedPoolInner<T>,
+}
+
+impl<T: Config> Deref for BondedPool<T> {
+	type Target = BondedPoolInner<T>;
+	fn deref(&self) -> &Self::Target {
+		&self.inner
+	}
+}
+
+impl<T: Config> DerefMut for BondedPool<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.inner
+	}
+}
+
+impl<T: Config> BondedPool<T> {
+	/// Create a new bonded pool with the given roles and identifier.
+	fn new(id: PoolId, roles: PoolRoles<T::AccountId>) -> Self {
+		Self {
+			id,
+			inner: BondedPoolInner {
+				roles,
+				state: PoolState::Open,
+				points: Zero::zero(),
+				member_counter: Zero::zero(),
+			},
+		}
+	}
+
+	/// Get [`Self`] from storage. Returns `None` if no pool with the given `id` exists.
+	pub fn get(id: PoolId) -> Option<Self> {
+		BondedPools::<T>::get(id).map(|inner| Self { id, inner })
+	}
+
+	/// Get the bonded account id of this pool.
+	fn bonded_account(&self) -> T::AccountId {
+		Pallet::<T>::create_bonded_account(self.id)
+	}
+
+	/// Get the reward account id of this pool.
+	fn reward_account(&self) -> T::AccountId {
+		Pallet::<T>::create_reward_account(self.id)
+	}
+
+	/// Consume self and put into storage.
+	fn put(self) {
+		BondedPools::<T>::insert(self.id, self.inner);
+	}
+
+	/// Consume self and remove from storage.
+	fn remove(self) {
+		BondedPools::<T>::remove(self.id);
+	}
+
+	/// Convert the given amount of balance to points given the current pool state.
+	///
+	/// This is often used for bonding and issuing new funds into the pool.
+	fn balance_to_point(&self, new_funds: BalanceOf<T>) -> BalanceOf<T> {
+		let bonded_balance =
+			T::StakingInterface::active_stake(&self.bonded_account()).unwrap_or(Zero::zero());
+		Pallet::<T>::balance_to_point(bonded_balance, self.points, new_funds)
+	}
+
+	/// Convert the given number of points to balance given the current pool state.
+	///
+	/// This is often used for unbonding.
+	fn points_to_balance(&self, points: BalanceOf<T>) -> BalanceOf<T> {
+		let bonded_balance =
+			T::StakingInterface::active_stake(&self.bonded_account()).unwrap_or(Zero::zero());
+		Pallet::<T>::point_to_balance(bonded_balance, self.points, points)
+	}
+
+	/// Issue points to [`Self`] for `new_funds`.
+	fn issue(&mut self, new_funds: BalanceOf<T>) -> BalanceOf<T> {
+		let points_to_issue = self.balance_to_point(new_funds);
+		self.points = self.points.saturating_add(points_to_issue);
+		points_to_issue
+	}
+
+	/// Dissolve some points from the pool i.e. unbond the given amount of points from this pool.
+	/// This is the opposite of issuing some funds into the pool.
+	///
+	/// Mutates self in place, but does not write anything to storage.
+	///
+	/// Returns the equivalent balance amount that actually needs to get unbonded.
+	fn dissolve(&mut self, points: BalanceOf<T>) -> BalanceOf<T> {
+		// NOTE: do not optimize by removing `balance`. it must be computed before mutating
+		// `self.point`.
+		let balance = self.points_to_balance(points);
+		self.points = self.points.saturating_sub(points);
+		balance
+	}
+
+	/// Increment the member counter. Ensures that the pool and system member limits are
+	/// respected.
+	fn try_inc_members(&mut self) -> Result<(), Error<T>> {
+		ensure!(
+			MaxPoolMembersPerPool::<T>::get()
+				.map_or(true, |max_per_pool| self.member_counter < max_per_pool),
+			Error::<T>::MaxPoolMembers
+		);
+		ensure!(
+			MaxPoolMembers::<T>::get().map_or(true, |max| PoolMembers::<T>::count() < max),
+			Error::<T>::MaxPoolMembers
+		);
+		self.member_counter = self.member_counter.checked_add(1).ok_or(Error::<T>::OverflowRisk)?;
+		Ok(())
+	}
+
+	/// Decrement the member counter.
+	fn dec_members(mut self) -> Self {
+		self.member_counter = self.member_counter.saturating_sub(1);
+		self
+	}
+
+	fn is_root(&self, who: &T::AccountId) -> bool {
+		self.roles.root.as_ref().map_or(false, |root| root == who)
+	}
+
+	fn is_state_toggler(&self, who: &T::AccountId) -> bool {
+		self.roles.state_toggler.as_ref().map_or(false, |state_toggler| state_toggler == who)
+	}
+
+	fn can_nominate(&self, who: &T::AccountId) -> bool {
+		self.is_root(who) ||
+			self.roles.nominator.as_ref().map_or(false, |nominator| nominator == who)
+	}
+
+	fn can_kick(&self, who: &T::AccountId) -> bool {
+		self.state == PoolState::Blocked && (self.is_root(who) || self.is_state_toggler(who))
+	}
+
+	fn can_toggle_state(&self, who: &T::AccountId) -> bool {
+		(self.is_root(who) || self.is_state_toggler(who)) && !self.is_destroying()
+	}
+
+	fn can_set_metadata(&self, who: &T::AccountId) -> bool {
+		self.is_root(who) || self.is_state_toggler(who)
+	}
+
+	fn is_destroying(&self) -> bool {
+		matches!(self.state, PoolState::Destroying)
+	}
+
+	fn is_destroying_and_only_depositor(&self, alleged_depositor_points: BalanceOf<T>) -> bool {
+		// we need to ensure that `self.member_counter == 1` as well, because the depositor's
+		// initial `MinCreateBond` (or more) is what guarantees that the ledger of the pool does
+		// not get killed in the staking system, and that it does not fall below
+		// `MinimumNominatorBond`, which could prevent other non-depositor members from fully
+		// leaving. Thus, all members must withdraw, then depositor can unbond, and finally
+		// withdraw after waiting another cycle.
+		self.is_destroying() &&
+			self.points == alleged_depositor_points &&
+			self.member_counter == 1
+	}
+
+	/// Whether or not the pool is ok to be in `PoolSate::Open`. If this returns an `Err`, then
+	/// the pool is unrecoverable and should be in the destroying state.
+	fn ok_to_be_open(&self) -> Result<(), Error<T>> {
+		ensure!(!self.is_destroying(), Error::<T>::CanNotChangeState);
+
+		let bonded_balance =
+			T::StakingInterface::active_stake(&self.bonded_account()).unwrap_or(Zero::zero());
+		ensure!(!bonded_balance.is_zero(), Error::<T>::OverflowRisk);
+
+		let points_to_balance_ratio_floor = self.points / bonded_balance;
+		let max_points_to_balance = T::MaxPointsToBalance::get();
+
+		// Pool points can inflate relative to balance, but only if the pool is slashed.
+		// If we cap the ratio of points:balance so one cannot join a pool that has been slashed
+		// by `max_points_to_balance`%, if not zero.
+		ensure!(
+			points_to_balance_ratio_floor < max_points_to_balance.into(),
+			Error::<T>::OverflowRisk
+		);
+
+		// then we can be decently sure that the pool has not been slashed in a way that overflows
+		// the points.
+		Ok(())
+	}
+
+	/// Check that the pool can accept a member with `new_funds`.
+	fn ok_to_join(&self) -> Result<(), Error<T>> {
+		ensure!(self.state == PoolState::Open, Error::<T>::NotOpen);
+		self.ok_to_be_open()?;
+		Ok(())
+	}
+
+	fn ok_to_unbond_with(
+		&self,
+		caller: &T::AccountId,
+		target_account: &T::AccountId,
+		target_member: &PoolMember<T>,
+		unbonding_points: BalanceOf<T>,
+	) -> Result<(), Error<T>> {
+		let is_permissioned = caller == target_account;
+		let is_depositor = *target_account == self.roles.depositor;
+		let is_full_unbond = unbonding_points == target_member.active_points();
+
+		let balance_after_unbond = {
+			let new_depositor_points =
+				target_member.active_points().saturating_sub(unbonding_points);
+			let mut target_member_after_unbond = (*target_member).clone();
+			target_member_after_unbond.points = new_depositor_points;
+			target_member_after_unbond.active_balance()
+		};
+
+		// any partial unbonding is only ever allowed if this unbond is permissioned.
+		ensure!(
+			is_permissioned || is_full_unbond,
+			Error::<T>::PartialUnbondNotAllowedPermissionlessly
+		);
+
+		// any unbond must comply with the balance condition:
+		ensure!(
+			is_full_unbond ||
+				balance_after_unbond >=
+					if is_depositor {
+						Pallet::<T>::depositor_min_bond()
+					} else {
+						MinJoinBond::<T>::get()
+					},
+			Error::<T>::MinimumBondNotMet
+		);
+
+		// additional checks:
+		match (is_permissioned, is_depositor) {
+			(true, false) => (),
+			(true, true) => {
+				// permission depositor unbond: if destroying and pool is empty, always allowed,
+				// with no additional limits.
+				if self.is_destroying_and_only_depositor(target_member.active_points()) {
+					// everything good, let them unbond anything.
+				} else {
+					// depositor cannot fully unbond yet.
+					ensure!(!is_full_unbond, Error::<T>::MinimumBondNotMet);
+				}
+			},
+			(false, false) => {
+				// If the pool is blocked, then an admin with kicking permissions can remove a
+				// member. If the pool is being destroyed, anyone can remove a member
+				debug_assert!(is_full_unbond);
+				ensure!(
+					self.can_kick(caller) || self.is_destroying(),
+					Error::<T>::NotKickerOrDestroying
+				)
+			},
+			(false, true) => {
+				// the depositor can simply not be unbonded permissionlessly, period.
+				return Err(Error::<T>::DoesNotHavePermission)
+			},
+		};
+
+		Ok(())
+	}
+
+	/// # Returns
+	///
+	/// * Ok(()) if [`Call::withdraw_unbonded`] can be called, `Err(DispatchError)` otherwise.
+	fn ok_to_withdraw_unbonded_with(
+		&self,
+		caller: &T::AccountId,
+		target_account: &T::AccountId,
+		target_member: &PoolMember<T>,
+	) -> Result<(), Error<T>> {
+		let is_permissioned = caller == target_account;
+		let is_depositor = *target_account == self.roles.depositor;
+
+		match (is_permissioned, is_depositor) {
+			(true, false) => (),
+			(false, false) => {
+				ensure!(
+					self.can_kick(caller) || self.is_destroying(),
+					Error::<T>::NotKickerOrDestroying
+				)
+			},
+			(_, true) => {
+				if target_member.active_points().is_zero() {
+					// the depositor's last funds keep the pool alive, they can only leave once
+					// they are the only member left.
+					ensure!(self.member_counter == 1, Error::<T>::NotOnlyPoolMember);
+				} else {
+					// partially unbonded funds of the depositor are theirs to withdraw only.
+					ensure!(is_permissioned, Error::<T>::DoesNotHavePermission);
+				}
+			},
+		}
+
+		Ok(())
+	}
+
+	/// Bond exactly `amount` from `who`'s funds into this pool.
+	///
+	/// If the bond type is `Create`, `StakingInterface::bond` is called, and `who`
+	/// is allowed to be killed. Otherwise, `StakingInterface::bond_extra` is called and `who`
+	/// cannot be killed.
+	///
+	/// Returns `Ok(points_issues)`, `Err` otherwise.
+	fn try_bond_funds(
+		&mut self,
+		who: &T::AccountId,
+		amount: BalanceOf<T>,
+		ty: BondType,
+	) -> Result<BalanceOf<T>, sp_runtime::DispatchError> {
+		// Cache the value
+		let bonded_account = self.bonded_account();
+		T::Currency::transfer(
+			who,
+			&bonded_account,
+			amount,
+			match ty {
+				BondType::Create => ExistenceRequirement::AllowDeath,
+				BondType::Later => ExistenceRequirement::KeepAlive,
+			},
+		)?;
+		// We must calculate the points issued *before* we bond who's funds, else points:balance
+		// ratio will be wrong.
+		let points_issued = self.issue(amount);
+
+		match ty {
+			BondType::Create => T::StakingInterface::bond(
+				bonded_account.clone(),
+				bonded_account,
+				amount,
+				self.reward_account(),
+			)?,
+			// The pool should always be created in such a way its in a state to bond extra, but
+			// if the active balance is slashed below the minimum bonded or the account cannot be
+			// found, we exit early.
+			BondType::Later => T::StakingInterface::bond_extra(bonded_account, amount)?,
+		}
+
+		Ok(points_issued)
+	}
+
+	/// Set the state of `self`, and deposit an event if the state changed. State should never be
+	/// set directly in in order to ensure a state change event is always correctly deposited.
+	fn set_state(&mut self, state: PoolState) {
+		if self.state != state {
+			self.state = state;
+			Pallet::<T>::deposit_event(Event::<T>::StateChanged(self.id, state));
+		};
+	}
+
+	/// The funds of the bonded account that are not locked in the staking system.
+	fn transferable_balance(&self) -> BalanceOf<T> {
+		let account = self.bonded_account();
+		T::Currency::free_balance(&account)
+			.saturating_sub(T::StakingInterface::total_stake(&account).unwrap_or(Zero::zero()))
+	}
+}
+
+/// A reward pool.
+///
+/// A reward pool is not so much a pool anymore, since it does not contain any shares or points.
+/// Rather, simply to fit nicely next to bonded pool and unbonding pools in terms of terminology.
+/// In reality, a reward pool is just a container for a few pool-dependent data related to the
+/// rewards.
+#[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, DebugNoBound, DefaultNoBound)]
+pub struct RewardPool<T: Config> {
+	/// The last recorded value of the reward counter.
+	///
+	/// This is updated ONLY when the points in the bonded pool change, which means `join`,
+	/// `bond_extra` and `unbond`, all of which is done through `update_recorded`.
+	pub last_recorded_reward_counter: RewardCounter,
+	/// The last recorded total payouts of the reward pool.
+	///
+	/// Payouts is essentially income of the pool.
+	///
+	/// Update criteria is same as that of `last_recorded_reward_counter`.
+	pub last_recorded_total_payouts: BalanceOf<T>,
+	/// Total amount that this pool has paid out so far to the members.
+	pub total_rewards_claimed: BalanceOf<T>,
+}
+
+impl<T: Config> RewardPool<T> {
+	/// Getter for [`RewardPool::last_recorded_reward_counter`].
+	pub fn last_recorded_reward_counter(&self) -> RewardCounter {
+		self.last_recorded_reward_counter
+	}
+
+	/// Register some rewards that are claimed from the pool by the members.
+	fn register_claimed_reward(&mut self, reward: BalanceOf<T>) {
+		self.total_rewards_claimed = self.total_rewards_claimed.saturating_add(reward);
+	}
+
+	/// Update the recorded values of the pool.
+	///
+	/// This function MUST be called whenever the points in the bonded pool change, AND whenever
+	/// the the pools commission is updated. The reason for the former is that a change in pool
+	/// points will alter the share of the reward balance among pool members.
+	fn update_records(&mut self, id: PoolId, bonded_points: BalanceOf<T>) -> Result<(), Error<T>> {
+		let balance = Self::current_balance(id);
+		self.last_recorded_reward_counter = self.current_reward_counter(id, bonded_points)?;
+		self.last_recorded_total_payouts = balance.saturating_add(self.total_rewards_claimed);
+		Ok(())
+	}
+
+	/// Get the current reward counter, based on the given `bonded_points` being the state of the
+	/// bonded pool at this time.
+	fn current_reward_counter(
+		&self,
+		id: PoolId,
+		bonded_points: BalanceOf<T>,
+	) -> Result<RewardCounter, Error<T>> {
+		// nothing can be distributed to a pool without points. Whatever comes in until the pool
+		// has points again is kept, and goes to the depositor once the pool is dissolved.
+		if bonded_points.is_zero() {
+			return Ok(self.last_recorded_reward_counter)
+		}
+
+		let balance = Self::current_balance(id);
+		let payouts_since_last_record = balance
+			.saturating_add(self.total_rewards_claimed)
+			.saturating_sub(self.last_recorded_total_payouts);
+
+		RewardCounter::checked_from_rational(
+			payouts_since_last_record.saturated_into::<u128>(),
+			bonded_points.saturated_into::<u128>(),
+		)
+		.and_then(|ref r| self.last_recorded_reward_counter.checked_add(r))
+		.ok_or(Error::<T>::OverflowRisk)
+	}
+
+	/// Current free balance of the reward pool.
+	///
+	/// This is sum of all the rewards that are claimable by pool members.
+	fn current_balance(id: PoolId) -> BalanceOf<T> {
+		T::Currency::free_balance(&Pallet::<T>::create_reward_account(id))
+			.saturating_sub(T::Currency::minimum_balance())
+	}
+}
+
+/// An unbonding pool. This is always mapped with an era.
+#[derive(Encode, Decode, DefaultNoBound, CloneNoBound, PartialEqNoBound, DebugNoBound)]
+pub struct UnbondPool<T: Config> {
+	/// The points in this pool.
+	pub points: BalanceOf<T>,
+	/// The funds in the pool.
+	pub balance: BalanceOf<T>,
+}
+
+impl<T: Config> UnbondPool<T> {
+	fn balance_to_point(&self, new_funds: BalanceOf<T>) -> BalanceOf<T> {
+		Pallet::<T>::balance_to_point(self.balance, self.points, new_funds)
+	}
+
+	fn point_to_balance(&self, points: BalanceOf<T>) -> BalanceOf<T> {
+		Pallet::<T>::point_to_balance(self.balance, self.points, points)
+	}
+
+	/// Issue the equivalent points of `new_funds` into self.
+	///
+	/// Returns the actual amounts of points issued.
+	fn issue(&mut self, new_funds: BalanceOf<T>) -> BalanceOf<T> {
+		let new_points = self.balance_to_point(new_funds);
+		self.points = self.points.saturating_add(new_points);
+		self.balance = self.balance.saturating_add(new_funds);
+		new_points
+	}
+
+	/// Dissolve some points from the unbonding pool, reducing the balance of the pool
+	/// proportionally.
+	///
+	/// This is the opposite of `issue`.
+	///
+	/// Returns the actual amount of `Balance` that was removed from the pool.
+	fn dissolve(&mut self, points: BalanceOf<T>) -> BalanceOf<T> {
+		let balance_to_unbond = self.point_to_balance(points);
+		self.points = self.points.saturating_sub(points);
+		self.balance = self.balance.saturating_sub(balance_to_unbond);
+
+		balance_to_unbond
+	}
+}
+
+/// The unbonding pools of a bonded pool.
+#[derive(Encode, Decode, DefaultNoBound, CloneNoBound, PartialEqNoBound, DebugNoBound)]
+pub struct SubPools<T: Config> {
+	/// A general, era agnostic pool of funds that have fully unbonded. The pools
+	/// of `Self::with_era` will lazily be merged into into this pool if they are
+	/// older then `current_era - PostUnbondingPoolsWindow`.
+	pub no_era: UnbondPool<T>,
+	/// Map of era in which a pool becomes unbonded in => unbond pools.
+	pub with_era: BTreeMap<EraIndex, UnbondPool<T>>,
+}
+
+impl<T: Config> SubPools<T> {
+	/// Merge the oldest `with_era` unbond pools into the `no_era` unbond pool.
+	///
+	/// This is often used whilst getting the sub-pool from storage, thus it consumes and returns
+	/// `Self` for ergonomic purposes.
+	fn maybe_merge_pools(mut self, current_era: EraIndex) -> Self {
+		// Ideally when a new `UnbondPool` is added to `with_era` we would only ever need to merge
+		// the oldest pool, but merging everything that unlocked more than
+		// `PostUnbondingPoolsWindow` eras ago keeps this robust to eras where nothing happened.
+		if let Some(newest_era_to_remove) =
+			current_era.checked_sub(T::PostUnbondingPoolsWindow::get())
+		{
+			let still_recent = self.with_era.split_off(&newest_era_to_remove.saturating_add(1));
+			let to_merge = sp_std::mem::replace(&mut self.with_era, still_recent);
+			for (_, pool) in to_merge {
+				self.no_era.points = self.no_era.points.saturating_add(pool.points);
+				self.no_era.balance = self.no_era.balance.saturating_add(pool.balance);
+			}
+		}
+
+		self
+	}
+
+	/// The sum of all unbonding balance, regardless of whether they are actually unlocked or not.
+	#[cfg(test)]
+	fn sum_unbonding_balance(&self) -> BalanceOf<T> {
+		self.no_era.balance.saturating_add(
+			self.with_era
+				.values()
+				.fold(BalanceOf::<T>::zero(), |acc, pool| acc.saturating_add(pool.balance)),
+		)
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(crate) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: weights::WeightInfo;
+
+		/// The nominating balance.
+		type Currency: Currency<Self::AccountId>;
+
+		/// The interface for nominating.
+		type StakingInterface: StakingInterface<
+			Balance = BalanceOf<Self>,
+			AccountId = Self::AccountId,
+		>;
+
+		/// The nomination pool's pallet id.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The maximum pool points-to-balance ratio that an `open` pool can have.
+		///
+		/// This is important in the event slashing takes place and the pool's points-to-balance
+		/// ratio becomes disproportional.
+		///
+		/// Moreover, this relates to the `RewardCounter` type as well, as the arithmetic
+		/// operations are a function of number of points, and by setting this value to e.g. 10,
+		/// you ensure that the total number of points in the system are at most 10 times the
+		/// total issuance of the chain, in the absolute worse case.
+		///
+		/// For a value of 10, the threshold would be a pool points-to-balance ratio of 10:1.
+		/// Such a scenario would also be the equivalent of the pool being 90% slashed.
+		#[pallet::constant]
+		type MaxPointsToBalance: Get<u8>;
+
+		/// The amount of eras a `SubPools::with_era` pool can exist before it gets merged into
+		/// the `SubPools::no_era` pool. In other words, this is the amount of eras a member will
+		/// be able to withdraw from an unbonding pool which is guaranteed to have the correct
+		/// ratio of points to balance; once the `with_era` pool is merged into the `no_era` pool,
+		/// the ratio can become skewed due to some slashed ratio getting merged in at some point.
+		type PostUnbondingPoolsWindow: Get<u32>;
+
+		/// The maximum length, in bytes, that a pools metadata maybe.
+		type MaxMetadataLen: Get<u32>;
+
+		/// The maximum number of simultaneous unbonding chunks that can exist per member.
+		type MaxUnbonding: Get<u32>;
+	}
+
+	/// Minimum amount to bond to join a pool.
+	#[pallet::storage]
+	pub type MinJoinBond<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Minimum bond required to create a pool.
+	///
+	/// This is the amount that the depositor must put as their initial stake in the pool, as an
+	/// indication of "skin in the game".
+	///
+	/// This is the value that will always exist in the staking ledger of the pool bonded account
+	/// while all other accounts leave.
+	#[pallet::storage]
+	pub type MinCreateBond<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Maximum number of nomination pools that can exist. If `None`, then an unbounded number of
+	/// pools can exist.
+	#[pallet::storage]
+	pub type MaxPools<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+	/// Maximum number of members that can exist in the system. If `None`, then the count
+	/// members are not bound on a system wide basis.
+	#[pallet::storage]
+	pub type MaxPoolMembers<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+	/// Maximum number of members that may belong to pool. If `None`, then the count of
+	/// members is not bound on a per pool basis.
+	#[pallet::storage]
+	pub type MaxPoolMembersPerPool<T: Config> = StorageValue<_, u32, OptionQuery>;
+
+	/// Active members.
+	#[pallet::storage]
+	pub type PoolMembers<T: Config> =
+		CountedStorageMap<_, Twox64Concat, T::AccountId, PoolMember<T>>;
+
+	/// Storage for bonded pools.
+	// To get or insert a pool see [`BondedPool::get`] and [`BondedPool::put`]
+	#[pallet::storage]
+	pub type BondedPools<T: Config> =
+		CountedStorageMap<_, Twox64Concat, PoolId, BondedPoolInner<T>>;
+
+	/// Reward pools. This is where there rewards for each pool accumulate. When a members payout
+	/// is claimed, the balance comes out fo the reward pool. Keyed by the bonded pools account.
+	#[pallet::storage]
+	pub type RewardPools<T: Config> = CountedStorageMap<_, Twox64Concat, PoolId, RewardPool<T>>;
+
+	/// Groups of unbonding pools. Each group of unbonding pools belongs to a bonded pool,
+	/// hence the name sub-pools. Keyed by the bonded pools account.
+	#[pallet::storage]
+	pub type SubPoolsStorage<T: Config> = CountedStorageMap<_, Twox64Concat, PoolId, SubPools<T>>;
+
+	/// Metadata for the pool.
+	#[pallet::storage]
+	pub type Metadata<T: Config> =
+		StorageMap<_, Twox64Concat, PoolId, BoundedVec<u8, T::MaxMetadataLen>, ValueQuery>;
+
+	/// Ever increasing number of all pools created so far.
+	#[pallet::storage]
+	pub type LastPoolId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// A reverse lookup from the pool's account id to its id.
+	///
+	/// This is only used for slashing. In all other instances, the pool id is used, and the
+	/// accounts are deterministically derived from it.
+	#[pallet::storage]
+	pub type ReversePoolIdLookup<T: Config> =
+		CountedStorageMap<_, Twox64Concat, T::AccountId, PoolId, OptionQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub min_join_bond: BalanceOf<T>,
+		pub min_create_bond: BalanceOf<T>,
+		pub max_pools: Option<u32>,
+		pub max_members_per_pool: Option<u32>,
+		pub max_members: Option<u32>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self {
+				min_join_bond: Zero::zero(),
+				min_create_bond: Zero::zero(),
+				max_pools: Some(16),
+				max_members_per_pool: Some(32),
+				max_members: Some(16 * 32),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			MinJoinBond::<T>::put(self.min_join_bond);
+			MinCreateBond::<T>::put(self.min_create_bond);
+			if let Some(max_pools) = self.max_pools {
+				MaxPools::<T>::put(max_pools);
+			}
+			if let Some(max_members_per_pool) = self.max_members_per_pool {
+				MaxPoolMembersPerPool::<T>::put(max_members_per_pool);
+			}
+			if let Some(max_members) = self.max_members {
+				MaxPoolMembers::<T>::put(max_members);
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	pub enum Event<T: Config> {
+		/// A pool has been created. \[depositor, pool_id\]
+		Created(T::AccountId, PoolId),
+		/// A member has became bonded in a pool. \[member, pool_id, bonded, joined\]
+		Bonded(T::AccountId, PoolId, BalanceOf<T>, bool),
+		/// A payout has been made to a member. \[member, pool_id, payout\]
+		PaidOut(T::AccountId, PoolId, BalanceOf<T>),
+		/// A member has unbonded from their pool. \[member, pool_id, amount, era\]
+		///
+		/// `amount` is the balance that was unbonded, `era` is the era in which it becomes
+		/// withdrawable.
+		Unbonded(T::AccountId, PoolId, BalanceOf<T>, EraIndex),
+		/// A member has withdrawn from their pool. \[member, pool_id, amount\]
+		Withdrawn(T::AccountId, PoolId, BalanceOf<T>),
+		/// A pool has been destroyed. \[pool_id\]
+		Destroyed(PoolId),
+		/// The state of a pool has changed. \[pool_id, new_state\]
+		StateChanged(PoolId, PoolState),
+		/// A member has been removed from a pool. \[pool_id, member\]
+		///
+		/// The removal can be voluntary (withdrawn all unbonded funds) or involuntary (kicked).
+		MemberRemoved(PoolId, T::AccountId),
+		/// The roles of a pool have been updated to the given new roles. Note that the depositor
+		/// can never change. \[root, nominator, state_toggler\]
+		RolesUpdated(Option<T::AccountId>, Option<T::AccountId>, Option<T::AccountId>),
+		/// The active balance of a pool has been slashed to the given amount. \[pool_id, balance\]
+		PoolSlashed(PoolId, BalanceOf<T>),
+		/// The unbond pool at `era` of a pool has been slashed to the given amount.
+		/// \[pool_id, era, balance\]
+		UnbondingPoolSlashed(PoolId, EraIndex, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// A (bonded) pool id does not exist.
+		PoolNotFound,
+		/// An account is not a member.
+		PoolMemberNotFound,
+		/// A reward pool does not exist. In all cases this is a system logic error.
+		RewardPoolNotFound,
+		/// A sub pool does not exist.
+		SubPoolsNotFound,
+		/// An account is already delegating in another pool. An account may only belong to one
+		/// pool at a time.
+		AccountBelongsToOtherPool,
+		/// The member is fully unbonded (and thus cannot access the bonded and reward pool
+		/// anymore to, for example, collect rewards).
+		FullyUnbonding,
+		/// The member cannot unbond further chunks due to reaching the limit.
+		MaxUnbondingLimit,
+		/// None of the funds can be withdrawn yet because the bonding duration has not passed.
+		CannotWithdrawAny,
+		/// The amount does not meet the minimum bond to either join or create a pool.
+		///
+		/// The depositor can never unbond to a value less than
+		/// `Pallet::depositor_min_bond`. The caller does not have nominating
+		/// permissions for the pool. Members can never unbond to a value below `MinJoinBond`.
+		MinimumBondNotMet,
+		/// The transaction could not be executed due to overflow risk for the pool.
+		OverflowRisk,
+		/// A pool must be in [`PoolState::Destroying`] in order for the depositor to unbond or for
+		/// other members to be permissionlessly unbonded.
+		NotDestroying,
+		/// The caller does not have nominating permissions for the pool.
+		NotNominator,
+		/// Either a) the caller cannot make a valid kick or b) the pool is not destroying.
+		NotKickerOrDestroying,
+		/// The pool is not open to join
+		NotOpen,
+		/// The system is maxed out on pools.
+		MaxPools,
+		/// Too many members in the pool or system.
+		MaxPoolMembers,
+		/// The pools state cannot be changed.
+		CanNotChangeState,
+		/// The caller does not have adequate permissions.
+		DoesNotHavePermission,
+		/// Metadata exceeds [`Config::MaxMetadataLen`]
+		MetadataExceedsMaxLen,
+		/// Partial unbonding now allowed permissionlessly.
+		PartialUnbondNotAllowedPermissionlessly,
+		/// The depositor can only withdraw all of their funds once all other members have left.
+		NotOnlyPoolMember,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn integrity_test() {
+			assert!(
+				T::MaxPointsToBalance::get() > 0,
+				"Minimum points to balance ratio must be greater than 0"
+			);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Stake funds with a pool. The amount to bond is transferred from the member to the
+		/// pools account and immediately increases the pools bond.
+		///
+		/// # Note
+		///
+		/// * An account can only be a member of a single pool.
+		/// * An account cannot join the same pool multiple times.
+		/// * This call will *not* dust the member account, so the member must have at least
+		///   `existential deposit + amount` in their account.
+		/// * Only a pool with [`PoolState::Open`] can be joined
+		#[pallet::weight(T::WeightInfo::join())]
+		#[transactional]
+		pub fn join(
+			origin: OriginFor<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+			pool_id: PoolId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(amount >= MinJoinBond::<T>::get(), Error::<T>::MinimumBondNotMet);
+			// If a member already exists that means they already belong to a pool
+			ensure!(!PoolMembers::<T>::contains_key(&who), Error::<T>::AccountBelongsToOtherPool);
+
+			let mut bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			bonded_pool.ok_to_join()?;
+
+			let mut reward_pool =
+				RewardPools::<T>::get(pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+			// IMPORTANT: reward pool records must be updated with the old points.
+			reward_pool.update_records(pool_id, bonded_pool.points)?;
+
+			bonded_pool.try_inc_members()?;
+			let points_issued = bonded_pool.try_bond_funds(&who, amount, BondType::Later)?;
+
+			PoolMembers::<T>::insert(
+				who.clone(),
+				PoolMember::<T> {
+					pool_id,
+					points: points_issued,
+					// we just updated `last_known_reward_counter` to the current one in
+					// `update_recorded`.
+					last_recorded_reward_counter: reward_pool.last_recorded_reward_counter(),
+					unbonding_eras: Default::default(),
+				},
+			);
+
+			Self::deposit_event(Event::<T>::Bonded(who, pool_id, amount, true));
+
+			bonded_pool.put();
+			RewardPools::<T>::insert(pool_id, reward_pool);
+
+			Ok(())
+		}
+
+		/// Bond `extra` more funds from `origin` into the pool to which they already belong.
+		///
+		/// Additional funds can come from either the free balance of the account, of from the
+		/// accumulated rewards, see [`BondExtra`].
+		///
+		/// Bonding extra funds implies an automatic payout of all pending rewards as well.
+		#[pallet::weight(
+			T::WeightInfo::bond_extra_transfer().max(T::WeightInfo::bond_extra_reward())
+		)]
+		#[transactional]
+		pub fn bond_extra(origin: OriginFor<T>, extra: BondExtra<BalanceOf<T>>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let (mut member, mut bonded_pool, mut reward_pool) =
+				Self::get_member_with_pools(&who)?;
+
+			// payout related stuff: we must claim the payouts, and updated recorded payout data
+			// before updating the bonded pool points, similar to that of `join` transaction.
+			reward_pool.update_records(bonded_pool.id, bonded_pool.points)?;
+			let claimed =
+				Self::do_reward_payout(&who, &mut member, &mut bonded_pool, &mut reward_pool)?;
+
+			let (points_issued, bonded) = match extra {
+				BondExtra::FreeBalance(amount) =>
+					(bonded_pool.try_bond_funds(&who, amount, BondType::Later)?, amount),
+				BondExtra::Rewards =>
+					(bonded_pool.try_bond_funds(&who, claimed, BondType::Later)?, claimed),
+			};
+
+			bonded_pool.ok_to_be_open()?;
+			member.points = member.points.saturating_add(points_issued);
+
+			Self::deposit_event(Event::<T>::Bonded(who.clone(), member.pool_id, bonded, false));
+			Self::put_member_with_pools(&who, member, bonded_pool, reward_pool);
+
+			Ok(())
+		}
+
+		/// A bonded member can use this to claim their payout based on the rewards that the pool
+		/// has accumulated since their last claimed payout (OR since joining if this is there
+		/// first time claiming rewards). The payout will be transferred to the member's account.
+		///
+		/// The member will earn rewards pro rata based on the members stake vs the sum of the
+		/// members in the pools stake. Rewards do not "expire".
+		#[pallet::weight(T::WeightInfo::claim_payout())]
+		pub fn claim_payout(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let (mut member, mut bonded_pool, mut reward_pool) =
+				Self::get_member_with_pools(&who)?;
+
+			let _ = Self::do_reward_payout(&who, &mut member, &mut bonded_pool, &mut reward_pool)?;
+
+			Self::put_member_with_pools(&who, member, bonded_pool, reward_pool);
+			Ok(())
+		}
+
+		/// Unbond up to `unbonding_points` of the `member_account`'s funds from the pool. It
+		/// implicitly collects the rewards one last time, since not doing so would mean some
+		/// rewards would be forfeited.
+		///
+		/// Under certain conditions, this call can be dispatched permissionlessly (i.e. by any
+		/// account).
+		///
+		/// # Conditions for a permissionless dispatch.
+		///
+		/// * The pool is blocked and the caller is either the root or state-toggler. This is
+		///   refereed to as a kick.
+		/// * The pool is destroying and the member is not the depositor.
+		/// * The pool is destroying, the member is the depositor and no other members are in the
+		///   pool.
+		///
+		/// ## Conditions for permissioned dispatch (i.e. the caller is also the
+		/// `member_account`):
+		///
+		/// * The caller is not the depositor.
+		/// * The caller is the depositor, the pool is destroying and no other members are in the
+		///   pool.
+		///
+		/// # Note
+		///
+		/// If there are too many unlocking chunks to unbond with the pool account,
+		/// [`Call::pool_withdraw_unbonded`] can be called to try and minimize unlocking chunks.
+		/// The [`StakingInterface::unbond`] will implicitly call [`Call::pool_withdraw_unbonded`]
+		/// to try to free chunks if necessary (ie. if unbound was called and no unlocking chunks
+		/// are available). However, it may not be possible to release the current unlocking
+		/// chunks, in which case, the result of this call will likely be the `NoMoreChunks` error
+		/// from the staking system.
+		#[pallet::weight(T::WeightInfo::unbond())]
+		#[transactional]
+		pub fn unbond(
+			origin: OriginFor<T>,
+			member_account: T::AccountId,
+			#[pallet::compact] unbonding_points: BalanceOf<T>,
+		) -> DispatchResult {
+			let caller = ensure_signed(origin)?;
+			let (mut member, mut bonded_pool, mut reward_pool) =
+				Self::get_member_with_pools(&member_account)?;
+
+			bonded_pool.ok_to_unbond_with(&caller, &member_account, &member, unbonding_points)?;
+
+			// Claim the the payout prior to unbonding. Once the user is unbonding their points no
+			// longer exist in the bonded pool and thus they can no longer claim their payouts. It
+			// is not strictly necessary to claim the rewards, but we do it here for UX.
+			reward_pool.update_records(bonded_pool.id, bonded_pool.points)?;
+			let _ = Self::do_reward_payout(
+				&member_account,
+				&mut member,
+				&mut bonded_pool,
+				&mut reward_pool,
+			)?;
+
+			let current_era = T::StakingInterface::current_era();
+			let unbond_era = T::StakingInterface::bonding_duration().saturating_add(current_era);
+
+			// Unbond in the actual underlying nominator.
+			let unbonding_balance = bonded_pool.dissolve(unbonding_points);
+			T::StakingInterface::unbond(bonded_pool.bonded_account(), unbonding_balance)?;
+
+			// Note that we lazily create the unbonding pools here if they don't already exist
+			let mut sub_pools = SubPoolsStorage::<T>::get(member.pool_id)
+				.unwrap_or_default()
+				.maybe_merge_pools(current_era);
+
+			// Update the unbond pool associated with the current era with the unbonded funds. Note
+			// that we lazily create the unbond pool if it does not yet exist.
+			let points_unbonded = sub_pools
+				.with_era
+				.entry(unbond_era)
+				.or_insert_with(Default::default)
+				.issue(unbonding_balance);
+
+			// Try and unbond in the member map.
+			member.try_unbond(unbonding_points, points_unbonded, unbond_era)?;
+
+			Self::deposit_event(Event::<T>::Unbonded(
+				member_account.clone(),
+				member.pool_id,
+				unbonding_balance,
+				unbond_era,
+			));
+
+			// Now that we know everything has worked write the items to storage.
+			SubPoolsStorage::<T>::insert(&member.pool_id, sub_pools);
+			Self::put_member_with_pools(&member_account, member, bonded_pool, reward_pool);
+			Ok(())
+		}
+
+		/// Call `withdraw_unbonded` for the pools account. This call can be made by any account.
+		///
+		/// This is useful if their are too many unlocking chunks to call `unbond`, and some
+		/// can be cleared by withdrawing. In the case there are too many unlocking chunks, the
+		/// user would probably see an error like `NoMoreChunks` emitted from the staking system
+		/// when they attempt to unbond.
+		#[pallet::weight(T::WeightInfo::pool_withdraw_unbonded(*num_slashing_spans))]
+		pub fn pool_withdraw_unbonded(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			num_slashing_spans: u32,
+		) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+			let pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			// For now we only allow a pool to withdraw unbonded if its not destroying. If the pool
+			// is destroying then `withdraw_unbonded` can be used.
+			ensure!(pool.state != PoolState::Destroying, Error::<T>::NotDestroying);
+			T::StakingInterface::withdraw_unbonded(pool.bonded_account(), num_slashing_spans)?;
+			Ok(())
+		}
+
+		/// Withdraw unbonded funds from `member_account`. If no bonded funds can be unbonded, an
+		/// error is returned.
+		///
+		/// Under certain conditions, this call can be dispatched permissionlessly (i.e. by any
+		/// account).
+		///
+		/// # Conditions for a permissionless dispatch
+		///
+		/// * The pool is in destroy mode and the target is not the depositor.
+		/// * The target is the depositor and they are the only member in the sub pools.
+		/// * The pool is blocked and the caller is either the root or state-toggler.
+		///
+		/// # Conditions for permissioned dispatch
+		///
+		/// * The caller is the target and they are not the depositor.
+		///
+		/// # Note
+		///
+		/// If the target is the depositor, the pool will be destroyed.
+		#[pallet::weight(T::WeightInfo::withdraw_unbonded_kill(*num_slashing_spans))]
+		#[transactional]
+		pub fn withdraw_unbonded(
+			origin: OriginFor<T>,
+			member_account: T::AccountId,
+			num_slashing_spans: u32,
+		) -> DispatchResultWithPostInfo {
+			let caller = ensure_signed(origin)?;
+			let mut member =
+				PoolMembers::<T>::get(&member_account).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let current_era = T::StakingInterface::current_era();
+
+			let bonded_pool =
+				BondedPool::<T>::get(member.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut sub_pools =
+				SubPoolsStorage::<T>::get(member.pool_id).ok_or(Error::<T>::SubPoolsNotFound)?;
+
+			bonded_pool.ok_to_withdraw_unbonded_with(&caller, &member_account, &member)?;
+
+			// NOTE: must do this after we have done the `ok_to_withdraw_unbonded_other_with` check.
+			let withdrawn_points = member.withdraw_unlocked(current_era);
+			ensure!(!withdrawn_points.is_empty(), Error::<T>::CannotWithdrawAny);
+
+			// Before calculate the `balance_to_unbond`, with call withdraw unbonded to ensure the
+			// `transferrable_balance` is correct.
+			let _stash_killed = T::StakingInterface::withdraw_unbonded(
+				bonded_pool.bonded_account(),
+				num_slashing_spans,
+			)?;
+
+			let balance_to_unbond = withdrawn_points
+				.iter()
+				.fold(BalanceOf::<T>::zero(), |accumulator, (era, unlocked_points)| {
+					if let Some(era_pool) = sub_pools.with_era.get_mut(era) {
+						let balance_to_unbond = era_pool.dissolve(*unlocked_points);
+						if era_pool.points.is_zero() {
+							sub_pools.with_era.remove(era);
+						}
+						accumulator.saturating_add(balance_to_unbond)
+					} else {
+						// A pool does not belong to this era, so it must have been merged to the
+						// era-less pool.
+						accumulator.saturating_add(sub_pools.no_era.dissolve(*unlocked_points))
+					}
+				})
+				// A call to this transaction may cause the pool's stash to get dusted. If this
+				// happens before the last member has withdrawn, then all subsequent withdraws will
+				// be 0. However the unbond pools do no get updated to reflect this. In the
+				// aforementioned scenario, this check ensures we don't try to withdraw funds that
+				// don't exist. This check is also defensive in cases where the unbond pool does not
+				// update its balance (e.g. a bug in the slashing hook.) We gracefully proceed in
+				// order to ensure members can leave the pool and it can be destroyed.
+				.min(bonded_pool.transferable_balance());
+
+			T::Currency::transfer(
+				&bonded_pool.bonded_account(),
+				&member_account,
+				balance_to_unbond,
+				ExistenceRequirement::AllowDeath,
+			)?;
+
+			Self::deposit_event(Event::<T>::Withdrawn(
+				member_account.clone(),
+				member.pool_id,
+				balance_to_unbond,
+			));
+
+			let post_info_weight = if member.total_points().is_zero() {
+				// member being reaped.
+				PoolMembers::<T>::remove(&member_account);
+				Self::deposit_event(Event::<T>::MemberRemoved(
+					member.pool_id,
+					member_account.clone(),
+				));
+
+				if member_account == bonded_pool.roles.depositor {
+					Pallet::<T>::dissolve_pool(bonded_pool);
+					None
+				} else {
+					bonded_pool.dec_members().put();
+					SubPoolsStorage::<T>::insert(&member.pool_id, sub_pools);
+					Some(T::WeightInfo::withdraw_unbonded_update(num_slashing_spans))
+				}
+			} else {
+				// we certainly don't need to delete any pools, because no one is being removed.
+				SubPoolsStorage::<T>::insert(&member.pool_id, sub_pools);
+				PoolMembers::<T>::insert(&member_account, member);
+				Some(T::WeightInfo::withdraw_unbonded_update(num_slashing_spans))
+			};
+
+			Ok(post_info_weight.into())
+		}
+
+		/// Create a new delegation pool.
+		///
+		/// # Arguments
+		///
+		/// * `amount` - The amount of funds to delegate to the pool. This also acts of a sort of
+		///   deposit since the pools creator cannot fully unbond funds until the pool is being
+		///   destroyed.
+		/// * `root` - The account to set as [`PoolRoles::root`].
+		/// * `nominator` - The account to set as the [`PoolRoles::nominator`].
+		/// * `state_toggler` - The account to set as the [`PoolRoles::state_toggler`].
+		///
+		/// # Note
+		///
+		/// In addition to `amount`, the caller will transfer the existential deposit; so the caller
+		/// needs at have at least `amount + existential_deposit` transferrable.
+		#[pallet::weight(T::WeightInfo::create())]
+		#[transactional]
+		pub fn create(
+			origin: OriginFor<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+			root: T::AccountId,
+			nominator: T::AccountId,
+			state_toggler: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(amount >= Pallet::<T>::depositor_min_bond(), Error::<T>::MinimumBondNotMet);
+			ensure!(
+				MaxPools::<T>::get()
+					.map_or(true, |max_pools| BondedPools::<T>::count() < max_pools),
+				Error::<T>::MaxPools
+			);
+			ensure!(!PoolMembers::<T>::contains_key(&who), Error::<T>::AccountBelongsToOtherPool);
+
+			let pool_id = LastPoolId::<T>::get().checked_add(1).ok_or(Error::<T>::OverflowRisk)?;
+			let mut bonded_pool = BondedPool::<T>::new(
+				pool_id,
+				PoolRoles {
+					root: Some(root),
+					nominator: Some(nominator),
+					state_toggler: Some(state_toggler),
+					depositor: who.clone(),
+				},
+			);
+
+			bonded_pool.try_inc_members()?;
+			let points = bonded_pool.try_bond_funds(&who, amount, BondType::Create)?;
+
+			T::Currency::transfer(
+				&who,
+				&bonded_pool.reward_account(),
+				T::Currency::minimum_balance(),
+				ExistenceRequirement::AllowDeath,
+			)?;
+
+			PoolMembers::<T>::insert(
+				who.clone(),
+				PoolMember::<T> {
+					pool_id,
+					points,
+					last_recorded_reward_counter: Zero::zero(),
+					unbonding_eras: Default::default(),
+				},
+			);
+			RewardPools::<T>::insert(pool_id, RewardPool::<T>::default());
+			ReversePoolIdLookup::<T>::insert(bonded_pool.bonded_account(), pool_id);
+			LastPoolId::<T>::put(pool_id);
+
+			Self::deposit_event(Event::<T>::Created(who.clone(), pool_id));
+			Self::deposit_event(Event::<T>::Bonded(who, pool_id, amount, true));
+			bonded_pool.put();
+
+			Ok(())
+		}
+
+		/// Nominate on behalf of the pool.
+		///
+		/// The dispatch origin of this call must be signed by the pool nominator or the pool
+		/// root role.
+		///
+		/// This directly forward the call to the staking pallet, on behalf of the pool bonded
+		/// account.
+		#[pallet::weight(T::WeightInfo::nominate(validators.len() as u32))]
+		pub fn nominate(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			validators: Vec<T::AccountId>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.can_nominate(&who), Error::<T>::NotNominator);
+			T::StakingInterface::nominate(bonded_pool.bonded_account(), validators)
+		}
+
+		/// Set a new state for the pool.
+		///
+		/// If a pool is already in the `Destroying` state, then under no condition can its state
+		/// change again.
+		///
+		/// The dispatch origin of this call must be either:
+		///
+		/// 1. signed by the state toggler, or the root role of the pool,
+		/// 2. if the pool conditions to be open are NOT met (as described by `ok_to_be_open`), and
+		///    then the state of the pool can be permissionlessly changed to `Destroying`.
+		#[pallet::weight(T::WeightInfo::set_state())]
+		pub fn set_state(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			state: PoolState,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let mut bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.state != PoolState::Destroying, Error::<T>::CanNotChangeState);
+
+			if bonded_pool.can_toggle_state(&who) {
+				bonded_pool.set_state(state);
+			} else if bonded_pool.ok_to_be_open().is_err() && state == PoolState::Destroying {
+				// If the pool has bad properties, then anyone can set it as destroying
+				bonded_pool.set_state(PoolState::Destroying);
+			} else {
+				Err(Error::<T>::CanNotChangeState)?;
+			}
+
+			bonded_pool.put();
+
+			Ok(())
+		}
+
+		/// Set a new metadata for the pool.
+		///
+		/// The dispatch origin of this call must be signed by the state toggler, or the root role
+		/// of the pool.
+		#[pallet::weight(T::WeightInfo::set_metadata(metadata.len() as u32))]
+		pub fn set_metadata(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			metadata: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let metadata: BoundedVec<_, _> =
+				metadata.try_into().map_err(|_| Error::<T>::MetadataExceedsMaxLen)?;
+			ensure!(
+				BondedPool::<T>::get(pool_id)
+					.ok_or(Error::<T>::PoolNotFound)?
+					.can_set_metadata(&who),
+				Error::<T>::DoesNotHavePermission
+			);
+
+			Metadata::<T>::mutate(pool_id, |pool_meta| *pool_meta = metadata);
+
+			Ok(())
+		}
+
+		/// Update configurations for the nomination pools. The origin for this call must be
+		/// Root.
+		///
+		/// # Arguments
+		///
+		/// * `min_join_bond` - Set [`MinJoinBond`].
+		/// * `min_create_bond` - Set [`MinCreateBond`].
+		/// * `max_pools` - Set [`MaxPools`].
+		/// * `max_members` - Set [`MaxPoolMembers`].
+		/// * `max_members_per_pool` - Set [`MaxPoolMembersPerPool`].
+		#[pallet::weight(T::WeightInfo::set_configs())]
+		pub fn set_configs(
+			origin: OriginFor<T>,
+			min_join_bond: ConfigOp<BalanceOf<T>>,
+			min_create_bond: ConfigOp<BalanceOf<T>>,
+			max_pools: ConfigOp<u32>,
+			max_members: ConfigOp<u32>,
+			max_members_per_pool: ConfigOp<u32>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			macro_rules! config_op_exp {
+				($storage:ty, $op:ident) => {
+					match $op {
+						ConfigOp::Noop => (),
+						ConfigOp::Set(v) => <$storage>::put(v),
+						ConfigOp::Remove => <$storage>::kill(),
+					}
+				};
+			}
+
+			config_op_exp!(MinJoinBond::<T>, min_join_bond);
+			config_op_exp!(MinCreateBond::<T>, min_create_bond);
+			config_op_exp!(MaxPools::<T>, max_pools);
+			config_op_exp!(MaxPoolMembers::<T>, max_members);
+			config_op_exp!(MaxPoolMembersPerPool::<T>, max_members_per_pool);
+			Ok(())
+		}
+
+		/// Update the roles of the pool.
+		///
+		/// The root is the only entity that can change any of the roles, including itself,
+		/// excluding the depositor, who can never change.
+		///
+		/// It emits an event, notifying UIs of the role change. This event is quite relevant to
+		/// most pool members and they should be informed of changes to pool roles.
+		#[pallet::weight(T::WeightInfo::update_roles())]
+		pub fn update_roles(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			new_root: ConfigOp<T::AccountId>,
+			new_nominator: ConfigOp<T::AccountId>,
+			new_state_toggler: ConfigOp<T::AccountId>,
+		) -> DispatchResult {
+			let mut bonded_pool = match ensure_root(origin.clone()) {
+				Ok(()) => BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?,
+				Err(_) => {
+					let who = ensure_signed(origin)?;
+					let bonded_pool =
+						BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+					ensure!(bonded_pool.is_root(&who), Error::<T>::DoesNotHavePermission);
+					bonded_pool
+				},
+			};
+
+			match new_root {
+				ConfigOp::Noop => (),
+				ConfigOp::Remove => bonded_pool.roles.root = None,
+				ConfigOp::Set(v) => bonded_pool.roles.root = Some(v),
+			};
+			match new_nominator {
+				ConfigOp::Noop => (),
+				ConfigOp::Remove => bonded_pool.roles.nominator = None,
+				ConfigOp::Set(v) => bonded_pool.roles.nominator = Some(v),
+			};
+			match new_state_toggler {
+				ConfigOp::Noop => (),
+				ConfigOp::Remove => bonded_pool.roles.state_toggler = None,
+				ConfigOp::Set(v) => bonded_pool.roles.state_toggler = Some(v),
+			};
+
+			Self::deposit_event(Event::<T>::RolesUpdated(
+				bonded_pool.roles.root.clone(),
+				bonded_pool.roles.nominator.clone(),
+				bonded_pool.roles.state_toggler.clone(),
+			));
+
+			bonded_pool.put();
+			Ok(())
+		}
+
+		/// Chill on behalf of the pool.
+		///
+		/// The dispatch origin of this call must be signed by the pool nominator or the pool
+		/// root role, same as [`Call::nominate`].
+		///
+		/// This directly forward the call to the staking pallet, on behalf of the pool bonded
+		/// account.
+		#[pallet::weight(T::WeightInfo::chill())]
+		pub fn chill(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.can_nominate(&who), Error::<T>::NotNominator);
+			T::StakingInterface::chill(bonded_pool.bonded_account())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The amount of bond that MUST REMAIN IN BONDED in ALL POOLS.
+	///
+	/// It is the responsibility of the depositor to put these funds into the pool initially. Upon
+	/// unbond, they can never unbond to a value below this amount.
+	///
+	/// It is essentially `max { MinNominatorBond, MinCreateBond, MinJoinBond }`, where the former
+	/// is coming from the staking pallet and the latter two are configured in this pallet.
+	pub fn depositor_min_bond() -> BalanceOf<T> {
+		T::StakingInterface::minimum_bond()
+			.max(MinCreateBond::<T>::get())
+			.max(MinJoinBond::<T>::get())
+			.max(T::Currency::minimum_balance())
+	}
+
+	/// Remove everything related to the given bonded pool.
+	///
+	/// All sub-pools are also deleted. All accounts are dusted and the leftover of the reward
+	/// account is returned to the depositor.
+	pub fn dissolve_pool(bonded_pool: BondedPool<T>) {
+		let reward_account = bonded_pool.reward_account();
+		let bonded_account = bonded_pool.bonded_account();
+
+		ReversePoolIdLookup::<T>::remove(&bonded_account);
+		RewardPools::<T>::remove(bonded_pool.id);
+		SubPoolsStorage::<T>::remove(bonded_pool.id);
+
+		// Kill accounts from storage by making their balance go below ED. We assume that the
+		// accounts have no references that would prevent destruction once we get to this point.
+		// We don't work with the system pallet directly, but in the end, if the account has no
+		// references, the balances pallet will kill it.
+		let reward_pool_remaining = T::Currency::free_balance(&reward_account);
+		if T::Currency::transfer(
+			&reward_account,
+			&bonded_pool.roles.depositor,
+			reward_pool_remaining,
+			ExistenceRequirement::AllowDeath,
+		)
+		.is_err()
+		{
+			log!(warn, "failed to return the reward account funds of pool {}", bonded_pool.id);
+		}
+
+		// NOTE: this is purely defensive.
+		let _ = T::Currency::make_free_balance_be(&bonded_account, Zero::zero());
+
+		Self::deposit_event(Event::<T>::Destroyed(bonded_pool.id));
+		// Remove bonded pool metadata.
+		Metadata::<T>::remove(bonded_pool.id);
+
+		bonded_pool.remove();
+	}
+
+	/// Create the main, bonded account of a pool with the given id.
+	pub fn create_bonded_account(id: PoolId) -> T::AccountId {
+		T::PalletId::get().into_sub_account((AccountType::Bonded, id))
+	}
+
+	/// Create the reward account of a pool with the given id.
+	pub fn create_reward_account(id: PoolId) -> T::AccountId {
+		// NOTE: in order to have a distinction in the test account id type (u128), we put
+		// account_type first so it does not get truncated out.
+		T::PalletId::get().into_sub_account((AccountType::Reward, id))
+	}
+
+	/// Get the member with their associated bonded and reward pool.
+	fn get_member_with_pools(
+		who: &T::AccountId,
+	) -> Result<(PoolMember<T>, BondedPool<T>, RewardPool<T>), Error<T>> {
+		let member = PoolMembers::<T>::get(&who).ok_or(Error::<T>::PoolMemberNotFound)?;
+		let bonded_pool = BondedPool::<T>::get(member.pool_id).ok_or(Error::<T>::PoolNotFound)?;
+		let reward_pool =
+			RewardPools::<T>::get(member.pool_id).ok_or(Error::<T>::RewardPoolNotFound)?;
+		Ok((member, bonded_pool, reward_pool))
+	}
+
+	/// Persist the member with their associated bonded and reward pool into storage, consuming
+	/// all of them.
+	fn put_member_with_pools(
+		member_account: &T::AccountId,
+		member: PoolMember<T>,
+		bonded_pool: BondedPool<T>,
+		reward_pool: RewardPool<T>,
+	) {
+		bonded_pool.put();
+		RewardPools::<T>::insert(member.pool_id, reward_pool);
+		PoolMembers::<T>::insert(member_account, member);
+	}
+
+	/// Calculate the equivalent point of `new_funds` in a pool with `current_balance` and
+	/// `current_points`.
+	fn balance_to_point(
+		current_balance: BalanceOf<T>,
+		current_points: BalanceOf<T>,
+		new_funds: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		match (current_balance.is_zero(), current_points.is_zero()) {
+			(_, true) => new_funds.saturating_mul(POINTS_TO_BALANCE_INIT_RATIO.into()),
+			(true, false) => {
+				// The pool was totally slashed.
+				// This is the equivalent of `(current_points / 1) * new_funds`.
+				new_funds.saturating_mul(current_points)
+			},
+			(false, false) => {
+				// Equivalent to (current_points / current_balance) * new_funds
+				Self::mul_div(current_points, new_funds, current_balance)
+			},
+		}
+	}
+
+	/// Calculate the equivalent balance of `points` in a pool with `current_balance` and
+	/// `current_points`.
+	fn point_to_balance(
+		current_balance: BalanceOf<T>,
+		current_points: BalanceOf<T>,
+		points: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		if current_balance.is_zero() || current_points.is_zero() || points.is_zero() {
+			// There is nothing to unbond
+			return Zero::zero()
+		}
+
+		// Equivalent of (current_balance / current_points) * points
+		Self::mul_div(current_balance, points, current_points)
+	}
+
+	/// Compute `a * b / c`, without overflowing in the intermediate multiplication, saturating
+	/// the result.
+	fn mul_div(a: BalanceOf<T>, b: BalanceOf<T>, c: BalanceOf<T>) -> BalanceOf<T> {
+		multiply_by_rational(a.saturated_into(), b.saturated_into(), c.saturated_into())
+			.map(|result| result.saturated_into())
+			.unwrap_or_else(|_| Bounded::max_value())
+	}
+
+	/// If the member has some rewards, transfer a payout from the reward pool to the member.
+	///
+	/// The reward pool records must be up to date with `bonded_pool` when this is called.
+	// Emits events and potentially modifies pool state if any arithmetic saturates, but does
+	// not persist any of the mutable inputs to storage.
+	fn do_reward_payout(
+		member_account: &T::AccountId,
+		member: &mut PoolMember<T>,
+		bonded_pool: &mut BondedPool<T>,
+		reward_pool: &mut RewardPool<T>,
+	) -> Result<BalanceOf<T>, sp_runtime::DispatchError> {
+		debug_assert_eq!(member.pool_id, bonded_pool.id);
+
+		// a member who has no skin in the game anymore cannot claim any rewards.
+		ensure!(!member.active_points().is_zero(), Error::<T>::FullyUnbonding);
+
+		let current_reward_counter =
+			reward_pool.current_reward_counter(bonded_pool.id, bonded_pool.points)?;
+		let pending_rewards = member.pending_rewards(current_reward_counter)?;
+
+		if pending_rewards.is_zero() {
+			return Ok(pending_rewards)
+		}
+
+		// IFF the reward is non-zero alter the member and reward pool info.
+		member.last_recorded_reward_counter = current_reward_counter;
+		reward_pool.register_claimed_reward(pending_rewards);
+
+		// Transfer payout to the member.
+		T::Currency::transfer(
+			&bonded_pool.reward_account(),
+			&member_account,
+			pending_rewards,
+			ExistenceRequirement::KeepAlive,
+		)?;
+
+		Self::deposit_event(Event::<T>::PaidOut(
+			member_account.clone(),
+			member.pool_id,
+			pending_rewards,
+		));
+
+		Ok(pending_rewards)
+	}
+}
+
+impl<T: Config> OnStakerSlash<T::AccountId, BalanceOf<T>> for Pallet<T> {
+	fn on_slash(
+		pool_account: &T::AccountId,
+		// Bonded balance is always read directly from staking, therefore we need not update
+		// anything here.
+		slashed_bonded: BalanceOf<T>,
+		slashed_unlocking: &BTreeMap<EraIndex, BalanceOf<T>>,
+	) {
+		if let Some(pool_id) = ReversePoolIdLookup::<T>::get(pool_account) {
+			let mut sub_pools = match SubPoolsStorage::<T>::get(pool_id) {
+				Some(sub_pools) => sub_pools,
+				None => Default::default(),
+			};
+			for (era, slashed_balance) in slashed_unlocking.iter() {
+				if let Some(pool) = sub_pools.with_era.get_mut(era) {
+					pool.balance = *slashed_balance;
+					Self::deposit_event(Event::<T>::UnbondingPoolSlashed(
+						pool_id,
+						*era,
+						*slashed_balance,
+					));
+				}
+			}
+
+			Self::deposit_event(Event::<T>::PoolSlashed(pool_id, slashed_bonded));
+			SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+		}
+	}
+}
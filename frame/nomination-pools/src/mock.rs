@@ -0,0 +1,390 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test environment for the nomination pools pallet.
+
+use super::*;
+use crate as pools;
+use frame_support::{assert_ok, parameter_types, traits::GenesisBuild, PalletId};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	DispatchError, DispatchResult,
+};
+
+pub type AccountId = u128;
+pub type Balance = u128;
+
+/// Pool 1's bonded account id.
+pub const DEFAULT_BONDED_ACCOUNT: AccountId = 20318131474730217858575332831085;
+/// Pool 1's reward account id.
+pub const DEFAULT_REWARD_ACCOUNT: AccountId = 20397359637244482196168876781421;
+/// The roles of pool 1, as set by `ExtBuilder::build`.
+pub const DEFAULT_ROLES: PoolRoles<AccountId> =
+	PoolRoles { depositor: 10, root: Some(900), nominator: Some(901), state_toggler: Some(902) };
+
+parameter_types! {
+	pub static CurrentEra: EraIndex = 0;
+	pub static BondingDuration: EraIndex = 3;
+	pub static BondedBalanceMap: BTreeMap<AccountId, Balance> = Default::default();
+	pub static UnbondingBalanceMap: BTreeMap<AccountId, Balance> = Default::default();
+	pub static Nominations: Vec<AccountId> = vec![];
+}
+
+pub struct StakingMock;
+impl StakingMock {
+	pub(crate) fn set_bonded_balance(who: AccountId, bonded: Balance) {
+		let mut x = BondedBalanceMap::get();
+		x.insert(who, bonded);
+		BondedBalanceMap::set(x)
+	}
+}
+
+impl sp_staking::StakingInterface for StakingMock {
+	type Balance = Balance;
+	type AccountId = AccountId;
+
+	fn minimum_bond() -> Self::Balance {
+		10
+	}
+
+	fn current_era() -> EraIndex {
+		CurrentEra::get()
+	}
+
+	fn bonding_duration() -> EraIndex {
+		BondingDuration::get()
+	}
+
+	fn active_stake(who: &Self::AccountId) -> Option<Self::Balance> {
+		BondedBalanceMap::get().get(who).map(|v| *v)
+	}
+
+	fn total_stake(who: &Self::AccountId) -> Option<Self::Balance> {
+		match (UnbondingBalanceMap::get().get(who), BondedBalanceMap::get().get(who)) {
+			(None, None) => None,
+			(Some(v), None) | (None, Some(v)) => Some(*v),
+			(Some(a), Some(b)) => Some(a + b),
+		}
+	}
+
+	fn bond_extra(who: Self::AccountId, extra: Self::Balance) -> DispatchResult {
+		let mut x = BondedBalanceMap::get();
+		x.get_mut(&who).map(|v| *v += extra);
+		BondedBalanceMap::set(x);
+		Ok(())
+	}
+
+	fn unbond(who: Self::AccountId, amount: Self::Balance) -> DispatchResult {
+		let mut x = BondedBalanceMap::get();
+		let bonded = x.get_mut(&who).unwrap();
+		*bonded = bonded.saturating_sub(amount);
+		BondedBalanceMap::set(x);
+		let mut y = UnbondingBalanceMap::get();
+		*y.entry(who).or_insert(Zero::zero()) += amount;
+		UnbondingBalanceMap::set(y);
+		Ok(())
+	}
+
+	fn chill(_: Self::AccountId) -> DispatchResult {
+		Ok(())
+	}
+
+	fn withdraw_unbonded(who: Self::AccountId, _: u32) -> Result<bool, DispatchError> {
+		// Simulates removing unlocking chunks and only having the bonded balance locked
+		let mut x = UnbondingBalanceMap::get();
+		x.remove(&who);
+		UnbondingBalanceMap::set(x);
+
+		Ok(UnbondingBalanceMap::get().is_empty() && BondedBalanceMap::get().is_empty())
+	}
+
+	fn bond(
+		stash: Self::AccountId,
+		_: Self::AccountId,
+		value: Self::Balance,
+		_: Self::AccountId,
+	) -> DispatchResult {
+		StakingMock::set_bonded_balance(stash, value);
+		Ok(())
+	}
+
+	fn nominate(_: Self::AccountId, nominations: Vec<Self::AccountId>) -> DispatchResult {
+		Nominations::set(nominations);
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub static ExistentialDeposit: Balance = 5;
+}
+
+impl pallet_balances::Config for Runtime {
+	type MaxLocks = ();
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub static PostUnbondingPoolsWindow: u32 = 2;
+	pub static MaxMetadataLen: u32 = 2;
+	pub static CheckLevel: u8 = 255;
+	pub const PoolsPalletId: PalletId = PalletId(*b"py/nopls");
+	pub const MaxPointsToBalance: u8 = 10;
+	pub const MaxUnbonding: u32 = 8;
+}
+
+impl pools::Config for Runtime {
+	type Event = Event;
+	type WeightInfo = ();
+	type Currency = Balances;
+	type StakingInterface = StakingMock;
+	type PalletId = PoolsPalletId;
+	type MaxPointsToBalance = MaxPointsToBalance;
+	type PostUnbondingPoolsWindow = PostUnbondingPoolsWindow;
+	type MaxMetadataLen = MaxMetadataLen;
+	type MaxUnbonding = MaxUnbonding;
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: frame_system::{Pallet, Call, Storage, Event<T>, Config},
+		Balances: pallet_balances::{Pallet, Call, Storage, Event<T>, Config<T>},
+		Pools: pools::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+pub(crate) struct ExtBuilder {
+	members: Vec<(AccountId, Balance)>,
+	max_members: Option<u32>,
+	max_members_per_pool: Option<u32>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self { members: Default::default(), max_members: Some(4), max_members_per_pool: Some(3) }
+	}
+}
+
+impl ExtBuilder {
+	// Add members to pool 0.
+	pub(crate) fn add_members(mut self, members: Vec<(AccountId, Balance)>) -> Self {
+		self.members = members;
+		self
+	}
+
+	pub(crate) fn ed(self, ed: Balance) -> Self {
+		ExistentialDeposit::set(ed);
+		self
+	}
+
+	pub(crate) fn with_check(self, level: u8) -> Self {
+		CheckLevel::set(level);
+		self
+	}
+
+	pub(crate) fn max_members(mut self, max: Option<u32>) -> Self {
+		self.max_members = max;
+		self
+	}
+
+	pub(crate) fn max_members_per_pool(mut self, max: Option<u32>) -> Self {
+		self.max_members_per_pool = max;
+		self
+	}
+
+	pub(crate) fn build(self) -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+
+		let _ = pools::GenesisConfig::<Runtime> {
+			min_join_bond: 2,
+			min_create_bond: 2,
+			max_pools: Some(2),
+			max_members_per_pool: self.max_members_per_pool,
+			max_members: self.max_members,
+		}
+		.assimilate_storage(&mut storage);
+
+		let mut ext = sp_io::TestExternalities::from(storage);
+
+		ext.execute_with(|| {
+			// for events to be deposited.
+			frame_system::Pallet::<Runtime>::set_block_number(1);
+
+			// make a pool
+			let amount_to_bond = Pools::depositor_min_bond();
+			Balances::make_free_balance_be(&10, amount_to_bond * 5);
+			assert_ok!(Pools::create(RawOrigin::Signed(10).into(), amount_to_bond, 900, 901, 902));
+
+			for (account_id, bonded) in self.members {
+				Balances::make_free_balance_be(&account_id, bonded * 2);
+				assert_ok!(Pools::join(RawOrigin::Signed(account_id).into(), bonded, 1));
+			}
+		});
+
+		ext
+	}
+
+	pub(crate) fn build_and_execute(self, test: impl FnOnce() -> ()) {
+		self.build().execute_with(|| {
+			test();
+			post_checks();
+		})
+	}
+}
+
+/// Check the internal consistency of the pallet's storage after each test.
+pub(crate) fn post_checks() {
+	if CheckLevel::get() == 0 {
+		return
+	}
+
+	assert!(BondedPools::<Runtime>::count() > 0 || PoolMembers::<Runtime>::count() == 0);
+	assert_eq!(RewardPools::<Runtime>::count(), BondedPools::<Runtime>::count());
+	assert!(SubPoolsStorage::<Runtime>::count() <= BondedPools::<Runtime>::count());
+	assert_eq!(ReversePoolIdLookup::<Runtime>::count(), BondedPools::<Runtime>::count());
+
+	let mut pools_members = BTreeMap::<PoolId, u32>::new();
+	let mut all_members = 0u32;
+	PoolMembers::<Runtime>::iter().for_each(|(_, d)| {
+		assert!(BondedPools::<Runtime>::contains_key(d.pool_id));
+		assert!(!d.total_points().is_zero(), "no member should have zero points: {:?}", d);
+		*pools_members.entry(d.pool_id).or_default() += 1;
+		all_members += 1;
+	});
+
+	BondedPools::<Runtime>::iter().for_each(|(id, inner)| {
+		let bonded_pool = BondedPool { id, inner };
+		assert_eq!(
+			pools_members.get(&id).map(|x| *x).unwrap_or_default(),
+			bonded_pool.member_counter
+		);
+		assert!(MaxPoolMembersPerPool::<Runtime>::get()
+			.map_or(true, |max| bonded_pool.member_counter <= max));
+
+		let depositor = PoolMembers::<Runtime>::get(&bonded_pool.roles.depositor).unwrap();
+		assert!(
+			bonded_pool.is_destroying_and_only_depositor(depositor.active_points()) ||
+				depositor.active_points() >= MinCreateBond::<Runtime>::get(),
+			"depositor must always have MinCreateBond stake in the pool, except for when the \
+			pool is being destroyed and the depositor is the last member",
+		);
+	});
+	assert!(MaxPoolMembers::<Runtime>::get().map_or(true, |max| all_members <= max));
+}
+
+/// Return all the pool events that were deposited since the last time this function was called.
+pub(crate) fn pool_events_since_last_call() -> Vec<super::Event<Runtime>> {
+	let events = System::events()
+		.into_iter()
+		.map(|r| r.event)
+		.filter_map(|e| if let Event::pools(inner) = e { Some(inner) } else { None })
+		.collect::<Vec<_>>();
+	let already_seen = ObservedEvents::get();
+	ObservedEvents::set(events.len());
+	events.into_iter().skip(already_seen).collect()
+}
+
+/// Return all the balances events that were deposited since the last time this function was
+/// called.
+pub(crate) fn balances_events_since_last_call() -> Vec<pallet_balances::Event<Runtime>> {
+	let events = System::events()
+		.into_iter()
+		.map(|r| r.event)
+		.filter_map(|e| if let Event::pallet_balances(inner) = e { Some(inner) } else { None })
+		.collect::<Vec<_>>();
+	let already_seen = ObservedBalancesEvents::get();
+	ObservedBalancesEvents::set(events.len());
+	events.into_iter().skip(already_seen).collect()
+}
+
+parameter_types! {
+	static ObservedEvents: usize = 0;
+	static ObservedBalancesEvents: usize = 0;
+}
+
+/// Same as `fn balance_to_point`, but only for testing.
+pub(crate) fn balance_to_point(
+	current_balance: Balance,
+	current_points: Balance,
+	new_funds: Balance,
+) -> Balance {
+	Pools::balance_to_point(current_balance, current_points, new_funds)
+}
+
+/// Same as `fn point_to_balance`, but only for testing.
+pub(crate) fn point_to_balance(
+	current_balance: Balance,
+	current_points: Balance,
+	points: Balance,
+) -> Balance {
+	Pools::point_to_balance(current_balance, current_points, points)
+}
+
+pub(crate) use frame_system::RawOrigin;
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	#[test]
+	fn pool_accounts_are_derived_from_pallet_id() {
+		assert_eq!(Pools::create_bonded_account(1), DEFAULT_BONDED_ACCOUNT);
+		assert_eq!(Pools::create_reward_account(1), DEFAULT_REWARD_ACCOUNT);
+	}
+}
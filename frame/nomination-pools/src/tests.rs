@@ -0,0 +1,1591 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{mock::*, Event};
+use frame_support::{assert_noop, assert_ok, assert_storage_noop};
+use pallet_balances::Event as BEvent;
+use sp_runtime::traits::BadOrigin;
+
+macro_rules! unbonding_pools_with_era {
+	($($k:expr => $v:expr),* $(,)?) => {
+		vec![$(($k, $v)),*].into_iter().collect::<BTreeMap<EraIndex, UnbondPool<Runtime>>>()
+	};
+}
+
+macro_rules! member_unbonding_eras {
+	($($k:expr => $v:expr),* $(,)?) => {
+		vec![$(($k, $v)),*].into_iter().collect::<BTreeMap<EraIndex, Balance>>()
+	};
+}
+
+/// Forcefully set the state of a pool, without any checks or events.
+fn unsafe_set_state(pool_id: PoolId, state: PoolState) {
+	BondedPools::<Runtime>::mutate(pool_id, |maybe_pool| {
+		maybe_pool.as_mut().expect("pool must exist").state = state
+	});
+}
+
+/// Send `amount` of rewards to the reward account of pool 1.
+fn deposit_rewards(amount: Balance) {
+	let current = Balances::free_balance(&DEFAULT_REWARD_ACCOUNT);
+	Balances::make_free_balance_be(&DEFAULT_REWARD_ACCOUNT, current + amount);
+}
+
+#[test]
+fn test_setup_works() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_eq!(BondedPools::<Runtime>::count(), 1);
+		assert_eq!(RewardPools::<Runtime>::count(), 1);
+		assert_eq!(SubPoolsStorage::<Runtime>::count(), 0);
+		assert_eq!(PoolMembers::<Runtime>::count(), 1);
+		assert_eq!(StakingMock::bonding_duration(), 3);
+
+		let last_pool = LastPoolId::<Runtime>::get();
+		assert_eq!(
+			BondedPool::<Runtime>::get(last_pool).unwrap(),
+			BondedPool::<Runtime> {
+				id: last_pool,
+				inner: BondedPoolInner {
+					state: PoolState::Open,
+					points: 10,
+					member_counter: 1,
+					roles: DEFAULT_ROLES,
+				},
+			}
+		);
+		assert_eq!(
+			RewardPools::<Runtime>::get(last_pool).unwrap(),
+			RewardPool::<Runtime>::default()
+		);
+		assert_eq!(
+			PoolMembers::<Runtime>::get(10).unwrap(),
+			PoolMember::<Runtime> {
+				pool_id: last_pool,
+				points: 10,
+				last_recorded_reward_counter: Zero::zero(),
+				unbonding_eras: Default::default(),
+			}
+		);
+
+		let bonded_account = Pools::create_bonded_account(last_pool);
+		let reward_account = Pools::create_reward_account(last_pool);
+
+		// the bonded_account should be bonded by the depositor's funds.
+		assert_eq!(StakingMock::active_stake(&bonded_account).unwrap(), 10);
+		assert_eq!(StakingMock::total_stake(&bonded_account).unwrap(), 10);
+		assert_eq!(ReversePoolIdLookup::<Runtime>::get(&bonded_account), Some(last_pool));
+
+		// but not nominating yet.
+		assert!(Nominations::get().is_empty());
+
+		// reward account should have an initial ED in it.
+		assert_eq!(Balances::free_balance(&reward_account), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn depositor_min_bond_respects_existential_deposit() {
+	ExtBuilder::default().ed(20).build_and_execute(|| {
+		assert_eq!(StakingMock::minimum_bond(), 10);
+		assert_eq!(Pools::depositor_min_bond(), 20);
+		assert_eq!(PoolMembers::<Runtime>::get(10).unwrap().points, 20);
+	})
+}
+
+mod bonded_pool {
+	use super::*;
+
+	fn pool_with_points(points: Balance) -> BondedPool<Runtime> {
+		BondedPool::<Runtime> {
+			id: 123123,
+			inner: BondedPoolInner {
+				state: PoolState::Open,
+				points,
+				member_counter: 1,
+				roles: DEFAULT_ROLES,
+			},
+		}
+	}
+
+	#[test]
+	fn balance_to_point_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			let mut bonded_pool = pool_with_points(100);
+			let bonded_account = bonded_pool.bonded_account();
+
+			// 1 points : 1 balance ratio
+			StakingMock::set_bonded_balance(bonded_account, 100);
+			assert_eq!(bonded_pool.balance_to_point(10), 10);
+			assert_eq!(bonded_pool.balance_to_point(0), 0);
+
+			// 2 points : 1 balance ratio
+			StakingMock::set_bonded_balance(bonded_account, 50);
+			assert_eq!(bonded_pool.balance_to_point(10), 20);
+
+			// 1 points : 2 balance ratio
+			StakingMock::set_bonded_balance(bonded_account, 100);
+			bonded_pool.points = 50;
+			assert_eq!(bonded_pool.balance_to_point(10), 5);
+
+			// 100 points : 0 balance ratio
+			StakingMock::set_bonded_balance(bonded_account, 0);
+			bonded_pool.points = 100;
+			assert_eq!(bonded_pool.balance_to_point(10), 100 * 10);
+
+			// 0 points : 100 balance
+			StakingMock::set_bonded_balance(bonded_account, 100);
+			bonded_pool.points = 0;
+			assert_eq!(bonded_pool.balance_to_point(10), 10);
+
+			// 10 points : 3 balance ratio
+			StakingMock::set_bonded_balance(bonded_account, 30);
+			bonded_pool.points = 100;
+			assert_eq!(bonded_pool.balance_to_point(10), 33);
+
+			// 2 points : 3 balance ratio
+			StakingMock::set_bonded_balance(bonded_account, 300);
+			bonded_pool.points = 200;
+			assert_eq!(bonded_pool.balance_to_point(10), 6);
+
+			// 4 points : 9 balance ratio
+			StakingMock::set_bonded_balance(bonded_account, 900);
+			bonded_pool.points = 400;
+			assert_eq!(bonded_pool.balance_to_point(90), 40);
+		})
+	}
+
+	#[test]
+	fn points_to_balance_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// 1 balance : 1 points ratio
+			let mut bonded_pool = pool_with_points(100);
+			let bonded_account = bonded_pool.bonded_account();
+
+			StakingMock::set_bonded_balance(bonded_account, 100);
+			assert_eq!(bonded_pool.points_to_balance(10), 10);
+			assert_eq!(bonded_pool.points_to_balance(0), 0);
+
+			// 2 balance : 1 points ratio
+			bonded_pool.points = 50;
+			assert_eq!(bonded_pool.points_to_balance(10), 20);
+
+			// 100 balance : 0 points ratio
+			StakingMock::set_bonded_balance(bonded_account, 100);
+			bonded_pool.points = 0;
+			assert_eq!(bonded_pool.points_to_balance(10), 0);
+
+			// 0 balance : 100 points ratio
+			StakingMock::set_bonded_balance(bonded_account, 0);
+			bonded_pool.points = 100;
+			assert_eq!(bonded_pool.points_to_balance(10), 0);
+
+			// 10 balance : 3 points ratio
+			StakingMock::set_bonded_balance(bonded_account, 100);
+			bonded_pool.points = 30;
+			assert_eq!(bonded_pool.points_to_balance(10), 33);
+
+			// 2 balance : 3 points ratio
+			StakingMock::set_bonded_balance(bonded_account, 200);
+			bonded_pool.points = 300;
+			assert_eq!(bonded_pool.points_to_balance(10), 6);
+
+			// 4 balance : 9 points ratio
+			StakingMock::set_bonded_balance(bonded_account, 400);
+			bonded_pool.points = 900;
+			assert_eq!(bonded_pool.points_to_balance(90), 40);
+		})
+	}
+
+	#[test]
+	fn ok_to_join_with_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			let pool = pool_with_points(100);
+			let bonded_account = pool.bonded_account();
+			let max_points_to_balance: Balance =
+				<<Runtime as Config>::MaxPointsToBalance as Get<u8>>::get().into();
+
+			// Simulate a 100% slashed pool.
+			StakingMock::set_bonded_balance(bonded_account, 0);
+			assert!(matches!(pool.ok_to_join(), Err(Error::<Runtime>::OverflowRisk)));
+
+			// Simulate a pool slashed right down to the `MaxPointsToBalance` ratio.
+			StakingMock::set_bonded_balance(bonded_account, max_points_to_balance);
+			assert!(matches!(pool.ok_to_join(), Err(Error::<Runtime>::OverflowRisk)));
+
+			StakingMock::set_bonded_balance(bonded_account, max_points_to_balance + 1);
+			assert_ok!(pool.ok_to_join());
+		})
+	}
+}
+
+mod reward_pool {
+	use super::*;
+
+	#[test]
+	fn current_reward_counter_is_unchanged_without_points() {
+		ExtBuilder::default().build_and_execute(|| {
+			let reward_pool = RewardPool::<Runtime> {
+				last_recorded_reward_counter: RewardCounter::saturating_from_integer(2),
+				..Default::default()
+			};
+			deposit_rewards(100);
+
+			assert_eq!(
+				reward_pool.current_reward_counter(1, 0).unwrap(),
+				RewardCounter::saturating_from_integer(2),
+			);
+			assert_eq!(
+				reward_pool.current_reward_counter(1, 50).unwrap(),
+				RewardCounter::saturating_from_integer(4),
+			);
+		})
+	}
+}
+
+mod unbond_pool {
+	use super::*;
+
+	#[test]
+	fn points_to_balance_works() {
+		// 1 balance : 1 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 100, balance: 100 };
+		assert_eq!(unbond_pool.point_to_balance(10), 10);
+		assert_eq!(unbond_pool.point_to_balance(0), 0);
+
+		// 2 balance : 1 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 50, balance: 100 };
+		assert_eq!(unbond_pool.point_to_balance(10), 20);
+
+		// 100 balance : 0 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 0, balance: 100 };
+		assert_eq!(unbond_pool.point_to_balance(10), 0);
+
+		// 0 balance : 100 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 100, balance: 0 };
+		assert_eq!(unbond_pool.point_to_balance(10), 0);
+
+		// 10 balance : 3 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 30, balance: 100 };
+		assert_eq!(unbond_pool.point_to_balance(10), 33);
+
+		// 2 balance : 3 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 300, balance: 200 };
+		assert_eq!(unbond_pool.point_to_balance(10), 6);
+
+		// 4 balance : 9 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 900, balance: 400 };
+		assert_eq!(unbond_pool.point_to_balance(90), 40);
+	}
+
+	#[test]
+	fn balance_to_points_works() {
+		// 1 balance : 1 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 100, balance: 100 };
+		assert_eq!(unbond_pool.balance_to_point(10), 10);
+		assert_eq!(unbond_pool.balance_to_point(0), 0);
+
+		// 1 balance : 2 points ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 100, balance: 50 };
+		assert_eq!(unbond_pool.balance_to_point(10), 20);
+
+		// 100 points : 0 balance ratio, i.e. a fully slashed pool.
+		let unbond_pool = UnbondPool::<Runtime> { points: 100, balance: 0 };
+		assert_eq!(unbond_pool.balance_to_point(10), 100 * 10);
+
+		// 0 points : 100 balance ratio
+		let unbond_pool = UnbondPool::<Runtime> { points: 0, balance: 100 };
+		assert_eq!(unbond_pool.balance_to_point(10), 10);
+
+		// the module level functions agree with the pools.
+		assert_eq!(balance_to_point(50, 100, 10), 20);
+		assert_eq!(point_to_balance(50, 100, 20), 10);
+	}
+
+	#[test]
+	fn issue_and_dissolve_works() {
+		let mut unbond_pool = UnbondPool::<Runtime> { points: 0, balance: 0 };
+
+		assert_eq!(unbond_pool.issue(100), 100);
+		assert_eq!(unbond_pool, UnbondPool { points: 100, balance: 100 });
+
+		// the pool gets slashed by half.
+		unbond_pool.balance = 50;
+		assert_eq!(unbond_pool.issue(50), 100);
+		assert_eq!(unbond_pool, UnbondPool { points: 200, balance: 100 });
+
+		assert_eq!(unbond_pool.dissolve(50), 25);
+		assert_eq!(unbond_pool, UnbondPool { points: 150, balance: 75 });
+		assert_eq!(unbond_pool.dissolve(150), 75);
+		assert_eq!(unbond_pool, UnbondPool { points: 0, balance: 0 });
+	}
+}
+
+mod sub_pools {
+	use super::*;
+
+	#[test]
+	fn maybe_merge_pools_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_eq!(PostUnbondingPoolsWindow::get(), 2);
+
+			// Given
+			let sub_pool_0 = SubPools::<Runtime> {
+				no_era: UnbondPool::<Runtime>::default(),
+				with_era: unbonding_pools_with_era! {
+					3 => UnbondPool { points: 10, balance: 10 },
+					4 => UnbondPool { points: 10, balance: 10 },
+					5 => UnbondPool { points: 10, balance: 10 },
+				},
+			};
+
+			// When `current_era < PostUnbondingPoolsWindow`
+			let sub_pool_1 = sub_pool_0.clone().maybe_merge_pools(1);
+
+			// Then it exits early without modifications
+			assert_eq!(sub_pool_1, sub_pool_0);
+
+			// When no pool is older than `current_era - PostUnbondingPoolsWindow`
+			let sub_pool_1 = sub_pool_1.maybe_merge_pools(4);
+
+			// Then it exits early without modifications
+			assert_eq!(sub_pool_1, sub_pool_0);
+
+			// When `current_era - PostUnbondingPoolsWindow == 3`
+			let sub_pool_1 = sub_pool_1.maybe_merge_pools(5);
+
+			// Then era 3 is merged into the `no_era` pool
+			assert_eq!(
+				sub_pool_1,
+				SubPools {
+					no_era: UnbondPool { points: 10, balance: 10 },
+					with_era: unbonding_pools_with_era! {
+						4 => UnbondPool { points: 10, balance: 10 },
+						5 => UnbondPool { points: 10, balance: 10 },
+					},
+				}
+			);
+			assert_eq!(sub_pool_1.sum_unbonding_balance(), 30);
+
+			// When several eras are skipped, everything old enough gets merged at once.
+			let sub_pool_2 = sub_pool_1.maybe_merge_pools(7);
+
+			// Then
+			assert_eq!(
+				sub_pool_2,
+				SubPools {
+					no_era: UnbondPool { points: 30, balance: 30 },
+					with_era: Default::default(),
+				}
+			);
+			assert_eq!(sub_pool_2.sum_unbonding_balance(), 30);
+		});
+	}
+}
+
+mod join {
+	use super::*;
+
+	#[test]
+	fn join_works() {
+		let bonded = |points, member_counter| BondedPool::<Runtime> {
+			id: 1,
+			inner: BondedPoolInner {
+				state: PoolState::Open,
+				points,
+				member_counter,
+				roles: DEFAULT_ROLES,
+			},
+		};
+		ExtBuilder::default().build_and_execute(|| {
+			// Given
+			Balances::make_free_balance_be(&11, ExistentialDeposit::get() + 2);
+			assert!(!PoolMembers::<Runtime>::contains_key(&11));
+
+			// When
+			assert_ok!(Pools::join(Origin::signed(11), 2, 1));
+
+			// Then
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::Created(10, 1),
+					Event::Bonded(10, 1, 10, true),
+					Event::Bonded(11, 1, 2, true),
+				]
+			);
+			assert_eq!(
+				PoolMembers::<Runtime>::get(&11).unwrap(),
+				PoolMember::<Runtime> {
+					pool_id: 1,
+					points: 2,
+					last_recorded_reward_counter: Zero::zero(),
+					unbonding_eras: Default::default(),
+				}
+			);
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap(), bonded(12, 2));
+
+			// Given
+			// The bonded balance is slashed in half
+			StakingMock::set_bonded_balance(Pools::create_bonded_account(1), 6);
+
+			// And
+			Balances::make_free_balance_be(&12, ExistentialDeposit::get() + 12);
+			assert!(!PoolMembers::<Runtime>::contains_key(&12));
+
+			// When
+			assert_ok!(Pools::join(Origin::signed(12), 12, 1));
+
+			// Then
+			assert_eq!(pool_events_since_last_call(), vec![Event::Bonded(12, 1, 12, true)]);
+			assert_eq!(
+				PoolMembers::<Runtime>::get(&12).unwrap(),
+				PoolMember::<Runtime> {
+					pool_id: 1,
+					points: 24,
+					last_recorded_reward_counter: Zero::zero(),
+					unbonding_eras: Default::default(),
+				}
+			);
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap(), bonded(12 + 24, 3));
+		});
+	}
+
+	#[test]
+	fn join_errors_correctly() {
+		// the mocked pool below is not consistent with the rest of the storage.
+		ExtBuilder::default().with_check(0).build_and_execute(|| {
+			// 10 is already part of the default pool created.
+			assert_eq!(PoolMembers::<Runtime>::get(&10).unwrap().pool_id, 1);
+
+			assert_noop!(
+				Pools::join(Origin::signed(10), 420, 123),
+				Error::<Runtime>::AccountBelongsToOtherPool
+			);
+
+			assert_noop!(Pools::join(Origin::signed(11), 420, 123), Error::<Runtime>::PoolNotFound);
+
+			// Force the pools bonded balance to 0, simulating a 100% slash
+			StakingMock::set_bonded_balance(Pools::create_bonded_account(1), 0);
+			assert_noop!(Pools::join(Origin::signed(11), 420, 1), Error::<Runtime>::OverflowRisk);
+
+			// Given a mocked bonded pool
+			BondedPool::<Runtime> {
+				id: 123,
+				inner: BondedPoolInner {
+					member_counter: 1,
+					state: PoolState::Open,
+					points: 100,
+					roles: DEFAULT_ROLES,
+				},
+			}
+			.put();
+
+			// and reward pool
+			RewardPools::<Runtime>::insert(123, RewardPool::<Runtime>::default());
+
+			// Force the points:balance ratio to `MaxPointsToBalance` (100/10)
+			let max_points_to_balance: Balance =
+				<<Runtime as Config>::MaxPointsToBalance as Get<u8>>::get().into();
+			StakingMock::set_bonded_balance(
+				Pools::create_bonded_account(123),
+				max_points_to_balance,
+			);
+			assert_noop!(Pools::join(Origin::signed(11), 420, 123), Error::<Runtime>::OverflowRisk);
+
+			// Cannot join a pool that isn't open
+			StakingMock::set_bonded_balance(Pools::create_bonded_account(123), 100);
+			unsafe_set_state(123, PoolState::Blocked);
+			assert_noop!(Pools::join(Origin::signed(11), 420, 123), Error::<Runtime>::NotOpen);
+
+			unsafe_set_state(123, PoolState::Destroying);
+			assert_noop!(Pools::join(Origin::signed(11), 420, 123), Error::<Runtime>::NotOpen);
+
+			// Given
+			MinJoinBond::<Runtime>::put(100);
+
+			// Then
+			assert_noop!(
+				Pools::join(Origin::signed(11), 99, 123),
+				Error::<Runtime>::MinimumBondNotMet
+			);
+		});
+	}
+
+	#[test]
+	fn join_max_member_limits_are_respected() {
+		ExtBuilder::default()
+			.max_members_per_pool(Some(2))
+			.max_members(Some(3))
+			.build_and_execute(|| {
+				// Given the default pool with 1 member, one more can join it.
+				Balances::make_free_balance_be(&101, 100);
+				assert_ok!(Pools::join(Origin::signed(101), 10, 1));
+
+				// Then the pool is full.
+				Balances::make_free_balance_be(&102, 100);
+				assert_noop!(
+					Pools::join(Origin::signed(102), 10, 1),
+					Error::<Runtime>::MaxPoolMembers
+				);
+
+				// Given a new pool, which brings the system to its limit of members.
+				Balances::make_free_balance_be(&103, 100);
+				assert_ok!(Pools::create(Origin::signed(103), 10, 103, 103, 103));
+
+				// Then no one else can join any pool.
+				assert_noop!(
+					Pools::join(Origin::signed(102), 10, 2),
+					Error::<Runtime>::MaxPoolMembers
+				);
+			});
+	}
+}
+
+mod claim_payout {
+	use super::*;
+
+	#[test]
+	fn claim_payout_works() {
+		ExtBuilder::default()
+			.add_members(vec![(40, 40), (50, 50)])
+			.build_and_execute(|| {
+				// Given pool points 10 + 40 + 50
+				assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 100);
+				let _ = pool_events_since_last_call();
+				let _ = balances_events_since_last_call();
+				let (free_10, free_40, free_50) = (
+					Balances::free_balance(&10),
+					Balances::free_balance(&40),
+					Balances::free_balance(&50),
+				);
+
+				// and 100 in rewards.
+				deposit_rewards(100);
+
+				// When
+				assert_ok!(Pools::claim_payout(Origin::signed(10)));
+
+				// Then
+				assert_eq!(pool_events_since_last_call(), vec![Event::PaidOut(10, 1, 10)]);
+				assert_eq!(
+					balances_events_since_last_call(),
+					vec![BEvent::Transfer(DEFAULT_REWARD_ACCOUNT, 10, 10)]
+				);
+				assert_eq!(Balances::free_balance(&10), free_10 + 10);
+				assert_eq!(
+					PoolMembers::<Runtime>::get(10).unwrap().last_recorded_reward_counter,
+					RewardCounter::saturating_from_integer(1)
+				);
+				assert_eq!(RewardPools::<Runtime>::get(1).unwrap().total_rewards_claimed, 10);
+
+				// When
+				assert_ok!(Pools::claim_payout(Origin::signed(40)));
+				assert_ok!(Pools::claim_payout(Origin::signed(50)));
+
+				// Then
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![Event::PaidOut(40, 1, 40), Event::PaidOut(50, 1, 50)]
+				);
+				assert_eq!(Balances::free_balance(&40), free_40 + 40);
+				assert_eq!(Balances::free_balance(&50), free_50 + 50);
+				assert_eq!(
+					Balances::free_balance(&DEFAULT_REWARD_ACCOUNT),
+					Balances::minimum_balance()
+				);
+
+				// When claiming again, nothing is paid out.
+				assert_ok!(Pools::claim_payout(Origin::signed(50)));
+				assert!(pool_events_since_last_call().is_empty());
+
+				// Given more rewards come in
+				deposit_rewards(50);
+
+				// When
+				assert_ok!(Pools::claim_payout(Origin::signed(10)));
+
+				// Then
+				assert_eq!(pool_events_since_last_call(), vec![Event::PaidOut(10, 1, 5)]);
+
+				// Given more rewards come in before everyone claimed
+				deposit_rewards(30);
+
+				// When
+				assert_ok!(Pools::claim_payout(Origin::signed(40)));
+				assert_ok!(Pools::claim_payout(Origin::signed(50)));
+				assert_ok!(Pools::claim_payout(Origin::signed(10)));
+
+				// Then
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::PaidOut(40, 1, 32),
+						Event::PaidOut(50, 1, 40),
+						Event::PaidOut(10, 1, 3)
+					]
+				);
+				assert_eq!(
+					Balances::free_balance(&DEFAULT_REWARD_ACCOUNT),
+					Balances::minimum_balance()
+				);
+				assert_eq!(RewardPools::<Runtime>::get(1).unwrap().total_rewards_claimed, 180);
+			});
+	}
+
+	#[test]
+	fn rewards_are_not_shared_with_later_members() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given rewards that accumulated while 10 was the only member.
+			deposit_rewards(10);
+
+			// When 20 joins
+			Balances::make_free_balance_be(&20, 100);
+			assert_ok!(Pools::join(Origin::signed(20), 10, 1));
+
+			// Then 20 has nothing to claim
+			let _ = pool_events_since_last_call();
+			assert_ok!(Pools::claim_payout(Origin::signed(20)));
+			assert!(pool_events_since_last_call().is_empty());
+
+			// and 10 gets everything
+			assert_ok!(Pools::claim_payout(Origin::signed(10)));
+			assert_eq!(pool_events_since_last_call(), vec![Event::PaidOut(10, 1, 10)]);
+
+			// Given new rewards
+			deposit_rewards(20);
+
+			// Then they are shared
+			assert_ok!(Pools::claim_payout(Origin::signed(10)));
+			assert_ok!(Pools::claim_payout(Origin::signed(20)));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::PaidOut(10, 1, 10), Event::PaidOut(20, 1, 10)]
+			);
+		});
+	}
+
+	#[test]
+	fn claim_payout_errors_correctly() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			assert_noop!(
+				Pools::claim_payout(Origin::signed(123)),
+				Error::<Runtime>::PoolMemberNotFound
+			);
+
+			// a fully unbonding member cannot claim anymore.
+			assert_ok!(Pools::unbond(Origin::signed(20), 20, 20));
+			deposit_rewards(100);
+			assert_noop!(Pools::claim_payout(Origin::signed(20)), Error::<Runtime>::FullyUnbonding);
+		});
+	}
+}
+
+mod bond_extra {
+	use super::*;
+
+	#[test]
+	fn bond_extra_from_free_balance_and_rewards() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			let _ = pool_events_since_last_call();
+			Balances::make_free_balance_be(&10, 100);
+
+			// When
+			assert_ok!(Pools::bond_extra(Origin::signed(10), BondExtra::FreeBalance(10)));
+
+			// Then
+			assert_eq!(pool_events_since_last_call(), vec![Event::Bonded(10, 1, 10, false)]);
+			assert_eq!(Balances::free_balance(&10), 90);
+			assert_eq!(PoolMembers::<Runtime>::get(10).unwrap().points, 20);
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 40);
+			assert_eq!(StakingMock::active_stake(&DEFAULT_BONDED_ACCOUNT), Some(40));
+
+			// Given
+			deposit_rewards(40);
+
+			// When
+			assert_ok!(Pools::bond_extra(Origin::signed(10), BondExtra::Rewards));
+
+			// Then 10 re-bonded its share of the rewards.
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::PaidOut(10, 1, 20), Event::Bonded(10, 1, 20, false)]
+			);
+			assert_eq!(Balances::free_balance(&10), 90);
+			assert_eq!(PoolMembers::<Runtime>::get(10).unwrap().points, 40);
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 60);
+			assert_eq!(StakingMock::active_stake(&DEFAULT_BONDED_ACCOUNT), Some(60));
+
+			// and 20 can still claim its own share.
+			assert_ok!(Pools::claim_payout(Origin::signed(20)));
+			assert_eq!(pool_events_since_last_call(), vec![Event::PaidOut(20, 1, 20)]);
+		});
+	}
+}
+
+mod unbond {
+	use super::*;
+
+	#[test]
+	fn unbond_works() {
+		ExtBuilder::default().add_members(vec![(40, 40)]).build_and_execute(|| {
+			let _ = pool_events_since_last_call();
+
+			// When a partial unbond happens
+			assert_ok!(Pools::unbond(Origin::signed(40), 40, 10));
+
+			// Then
+			assert_eq!(pool_events_since_last_call(), vec![Event::Unbonded(40, 1, 10, 3)]);
+			assert_eq!(
+				SubPoolsStorage::<Runtime>::get(1).unwrap().with_era,
+				unbonding_pools_with_era! { 3 => UnbondPool { points: 10, balance: 10 } }
+			);
+			let member = PoolMembers::<Runtime>::get(40).unwrap();
+			assert_eq!(member.points, 30);
+			assert_eq!(member.unbonding_eras, member_unbonding_eras!(3 => 10));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 40);
+			assert_eq!(StakingMock::active_stake(&DEFAULT_BONDED_ACCOUNT), Some(40));
+
+			// Given the pool is slashed by half, in a later era
+			StakingMock::set_bonded_balance(DEFAULT_BONDED_ACCOUNT, 20);
+			CurrentEra::set(1);
+
+			// When the rest is unbonded
+			assert_ok!(Pools::unbond(Origin::signed(40), 40, 30));
+
+			// Then
+			assert_eq!(pool_events_since_last_call(), vec![Event::Unbonded(40, 1, 15, 4)]);
+			assert_eq!(
+				SubPoolsStorage::<Runtime>::get(1).unwrap().with_era,
+				unbonding_pools_with_era! {
+					3 => UnbondPool { points: 10, balance: 10 },
+					4 => UnbondPool { points: 15, balance: 15 },
+				}
+			);
+			let member = PoolMembers::<Runtime>::get(40).unwrap();
+			assert_eq!(member.points, 0);
+			assert_eq!(member.unbonding_eras, member_unbonding_eras!(3 => 10, 4 => 15));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 10);
+			assert_eq!(StakingMock::active_stake(&DEFAULT_BONDED_ACCOUNT), Some(5));
+		});
+	}
+
+	#[test]
+	fn depositor_unbond_is_restricted() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			// The depositor cannot fully unbond while there are other members
+			assert_noop!(
+				Pools::unbond(Origin::signed(10), 10, 10),
+				Error::<Runtime>::MinimumBondNotMet
+			);
+
+			// nor go below `depositor_min_bond`.
+			assert_noop!(
+				Pools::unbond(Origin::signed(10), 10, 5),
+				Error::<Runtime>::MinimumBondNotMet
+			);
+
+			// Given
+			assert_ok!(Pools::bond_extra(Origin::signed(10), BondExtra::FreeBalance(10)));
+
+			// Then the surplus can be unbonded
+			assert_ok!(Pools::unbond(Origin::signed(10), 10, 5));
+			assert_eq!(PoolMembers::<Runtime>::get(10).unwrap().points, 15);
+
+			// but no one else can unbond the depositor.
+			assert_noop!(
+				Pools::unbond(Origin::signed(20), 10, 15),
+				Error::<Runtime>::DoesNotHavePermission
+			);
+		});
+	}
+
+	#[test]
+	fn unbond_kick_works() {
+		ExtBuilder::default()
+			.add_members(vec![(100, 100), (200, 200)])
+			.build_and_execute(|| {
+				// Given
+				unsafe_set_state(1, PoolState::Blocked);
+				let _ = pool_events_since_last_call();
+
+				// When the nominator tries to kick, then its a noop
+				assert_noop!(
+					Pools::unbond(Origin::signed(901), 100, 100),
+					Error::<Runtime>::NotKickerOrDestroying
+				);
+
+				// A kick can only be a full unbond.
+				assert_noop!(
+					Pools::unbond(Origin::signed(902), 100, 50),
+					Error::<Runtime>::PartialUnbondNotAllowedPermissionlessly
+				);
+
+				// When the root kicks then its ok
+				assert_ok!(Pools::unbond(Origin::signed(900), 100, 100));
+
+				// When the state toggler kicks then its ok
+				assert_ok!(Pools::unbond(Origin::signed(902), 200, 200));
+
+				// Then
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![Event::Unbonded(100, 1, 100, 3), Event::Unbonded(200, 1, 200, 3)]
+				);
+				assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 10);
+				assert_eq!(
+					SubPoolsStorage::<Runtime>::get(1).unwrap().with_era,
+					unbonding_pools_with_era! { 3 => UnbondPool { points: 300, balance: 300 } }
+				);
+			});
+	}
+
+	#[test]
+	fn unbond_permissionless_works_when_destroying() {
+		ExtBuilder::default().add_members(vec![(100, 100)]).build_and_execute(|| {
+			// Given the pool is open, no one else can unbond a member.
+			assert_noop!(
+				Pools::unbond(Origin::signed(420), 100, 100),
+				Error::<Runtime>::NotKickerOrDestroying
+			);
+
+			// Given
+			unsafe_set_state(1, PoolState::Destroying);
+
+			// Then the depositor can never be unbonded permissionlessly
+			assert_noop!(
+				Pools::unbond(Origin::signed(420), 10, 10),
+				Error::<Runtime>::DoesNotHavePermission
+			);
+
+			// but anyone else can.
+			assert_ok!(Pools::unbond(Origin::signed(420), 100, 100));
+
+			// The depositor cannot unbond while 100 is still a member.
+			assert_noop!(
+				Pools::unbond(Origin::signed(10), 10, 10),
+				Error::<Runtime>::MinimumBondNotMet
+			);
+
+			// Given 100 leaves
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(Origin::signed(420), 100, 0));
+
+			// Then the depositor can unbond everything.
+			let _ = pool_events_since_last_call();
+			assert_ok!(Pools::unbond(Origin::signed(10), 10, 10));
+			assert_eq!(pool_events_since_last_call(), vec![Event::Unbonded(10, 1, 10, 6)]);
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 0);
+		});
+	}
+
+	#[test]
+	fn unbond_respects_max_unbonding_and_merges_sub_pools() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			// Given 20 unbonds a little bit in `MaxUnbonding` different eras.
+			for era in 0..MaxUnbonding::get() {
+				CurrentEra::set(era);
+				assert_ok!(Pools::unbond(Origin::signed(20), 20, 1));
+			}
+
+			// Then the oldest unbonding pools are merged into the era-less pool.
+			let sub_pools = SubPoolsStorage::<Runtime>::get(1).unwrap();
+			assert_eq!(sub_pools.no_era, UnbondPool { points: 3, balance: 3 });
+			assert_eq!(
+				sub_pools.with_era.keys().cloned().collect::<Vec<_>>(),
+				vec![6, 7, 8, 9, 10]
+			);
+			// but the member still knows the exact era of each chunk.
+			assert_eq!(
+				PoolMembers::<Runtime>::get(20).unwrap().unbonding_eras.len() as u32,
+				MaxUnbonding::get()
+			);
+
+			// Unbonding more in an era that is already used is fine.
+			assert_ok!(Pools::unbond(Origin::signed(20), 20, 1));
+			assert_eq!(PoolMembers::<Runtime>::get(20).unwrap().unbonding_eras.get(&10), Some(&2));
+
+			// When a new era is needed
+			CurrentEra::set(MaxUnbonding::get());
+
+			// Then
+			assert_noop!(
+				Pools::unbond(Origin::signed(20), 20, 1),
+				Error::<Runtime>::MaxUnbondingLimit
+			);
+		});
+	}
+
+	#[test]
+	fn unbond_errors_correctly() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_noop!(
+				Pools::unbond(Origin::signed(11), 11, 0),
+				Error::<Runtime>::PoolMemberNotFound
+			);
+
+			// Add the member
+			PoolMembers::<Runtime>::insert(
+				11,
+				PoolMember::<Runtime> {
+					pool_id: 2,
+					points: 10,
+					last_recorded_reward_counter: Zero::zero(),
+					unbonding_eras: Default::default(),
+				},
+			);
+
+			assert_noop!(Pools::unbond(Origin::signed(11), 11, 0), Error::<Runtime>::PoolNotFound);
+
+			PoolMembers::<Runtime>::remove(11);
+		});
+	}
+}
+
+mod pool_withdraw_unbonded {
+	use super::*;
+
+	#[test]
+	fn pool_withdraw_unbonded_works() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			// Given 20 unbonds
+			assert_ok!(Pools::unbond(Origin::signed(20), 20, 20));
+			assert_eq!(UnbondingBalanceMap::get().get(&DEFAULT_BONDED_ACCOUNT), Some(&20));
+
+			// When the pool withdraws once the bonding duration passed
+			CurrentEra::set(StakingMock::bonding_duration());
+			assert_ok!(Pools::pool_withdraw_unbonded(Origin::signed(10), 1, 0));
+
+			// Then the funds are no longer locked in staking
+			assert_eq!(UnbondingBalanceMap::get().get(&DEFAULT_BONDED_ACCOUNT), None);
+
+			// and the member can still withdraw them.
+			let free_before = Balances::free_balance(&20);
+			assert_ok!(Pools::withdraw_unbonded(Origin::signed(20), 20, 0));
+			assert_eq!(Balances::free_balance(&20), free_before + 20);
+		});
+	}
+
+	#[test]
+	fn pool_withdraw_unbonded_errors_correctly() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_noop!(
+				Pools::pool_withdraw_unbonded(Origin::signed(10), 123, 0),
+				Error::<Runtime>::PoolNotFound
+			);
+
+			unsafe_set_state(1, PoolState::Destroying);
+			assert_noop!(
+				Pools::pool_withdraw_unbonded(Origin::signed(10), 1, 0),
+				Error::<Runtime>::NotDestroying
+			);
+		});
+	}
+}
+
+mod withdraw_unbonded {
+	use super::*;
+
+	#[test]
+	fn withdraw_unbonded_works_with_slashed_unbonding_pool() {
+		ExtBuilder::default().add_members(vec![(40, 40), (50, 50)]).build_and_execute(|| {
+			// Given
+			assert_ok!(Pools::unbond(Origin::signed(40), 40, 40));
+			assert_ok!(Pools::unbond(Origin::signed(50), 50, 50));
+			assert_eq!(
+				SubPoolsStorage::<Runtime>::get(1).unwrap().with_era,
+				unbonding_pools_with_era! { 3 => UnbondPool { points: 90, balance: 90 } }
+			);
+			let (free_40, free_50) = (Balances::free_balance(&40), Balances::free_balance(&50));
+
+			// When the unbonding chunk is slashed in half
+			Balances::make_free_balance_be(&DEFAULT_BONDED_ACCOUNT, 100 - 45);
+			UnbondingBalanceMap::set(vec![(DEFAULT_BONDED_ACCOUNT, 45)].into_iter().collect());
+			let _ = pool_events_since_last_call();
+			Pools::on_slash(&DEFAULT_BONDED_ACCOUNT, 10, &vec![(3, 45)].into_iter().collect());
+
+			// Then
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::UnbondingPoolSlashed(1, 3, 45), Event::PoolSlashed(1, 10)]
+			);
+			assert_eq!(
+				SubPoolsStorage::<Runtime>::get(1).unwrap().with_era,
+				unbonding_pools_with_era! { 3 => UnbondPool { points: 90, balance: 45 } }
+			);
+
+			// When
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(Origin::signed(40), 40, 0));
+			assert_ok!(Pools::withdraw_unbonded(Origin::signed(50), 50, 0));
+
+			// Then each member gets their share of the slashed funds.
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::Withdrawn(40, 1, 20),
+					Event::MemberRemoved(1, 40),
+					Event::Withdrawn(50, 1, 25),
+					Event::MemberRemoved(1, 50),
+				]
+			);
+			assert_eq!(Balances::free_balance(&40), free_40 + 20);
+			assert_eq!(Balances::free_balance(&50), free_50 + 25);
+			assert!(SubPoolsStorage::<Runtime>::get(1).unwrap().with_era.is_empty());
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().member_counter, 1);
+		});
+	}
+
+	#[test]
+	fn withdraw_unbonded_dissolves_pool_with_depositor() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given
+			unsafe_set_state(1, PoolState::Destroying);
+			assert_ok!(Pools::unbond(Origin::signed(10), 10, 10));
+			let free_10 = Balances::free_balance(&10);
+			let _ = pool_events_since_last_call();
+
+			// When
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(Origin::signed(10), 10, 0));
+
+			// Then the bonded funds and the reward account's deposit are returned.
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::Withdrawn(10, 1, 10), Event::MemberRemoved(1, 10), Event::Destroyed(1)]
+			);
+			assert_eq!(Balances::free_balance(&10), free_10 + 10 + Balances::minimum_balance());
+			assert_eq!(Balances::free_balance(&DEFAULT_BONDED_ACCOUNT), 0);
+			assert_eq!(Balances::free_balance(&DEFAULT_REWARD_ACCOUNT), 0);
+
+			// and all the pool's storage is removed.
+			assert!(!BondedPools::<Runtime>::contains_key(1));
+			assert!(!RewardPools::<Runtime>::contains_key(1));
+			assert!(!SubPoolsStorage::<Runtime>::contains_key(1));
+			assert!(!PoolMembers::<Runtime>::contains_key(10));
+			assert_eq!(ReversePoolIdLookup::<Runtime>::get(DEFAULT_BONDED_ACCOUNT), None);
+		});
+	}
+
+	#[test]
+	fn withdraw_unbonded_errors_correctly() {
+		ExtBuilder::default().add_members(vec![(100, 100)]).build_and_execute(|| {
+			assert_noop!(
+				Pools::withdraw_unbonded(Origin::signed(11), 11, 0),
+				Error::<Runtime>::PoolMemberNotFound
+			);
+
+			// a member that never unbonded
+			assert_noop!(
+				Pools::withdraw_unbonded(Origin::signed(100), 100, 0),
+				Error::<Runtime>::SubPoolsNotFound
+			);
+
+			// Given
+			assert_ok!(Pools::unbond(Origin::signed(100), 100, 100));
+
+			// Then nothing can be withdrawn before the bonding duration passed
+			CurrentEra::set(2);
+			assert_noop!(
+				Pools::withdraw_unbonded(Origin::signed(100), 100, 0),
+				Error::<Runtime>::CannotWithdrawAny
+			);
+
+			// and no one else can withdraw on behalf of a member in an open pool
+			CurrentEra::set(3);
+			assert_noop!(
+				Pools::withdraw_unbonded(Origin::signed(420), 100, 0),
+				Error::<Runtime>::NotKickerOrDestroying
+			);
+
+			// nor on behalf of the depositor.
+			assert_noop!(
+				Pools::withdraw_unbonded(Origin::signed(420), 10, 0),
+				Error::<Runtime>::DoesNotHavePermission
+			);
+
+			assert_ok!(Pools::withdraw_unbonded(Origin::signed(100), 100, 0));
+		});
+	}
+}
+
+mod create {
+	use super::*;
+
+	#[test]
+	fn create_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// next pool id is 2.
+			let next_pool_stash = Pools::create_bonded_account(2);
+			let ed = Balances::minimum_balance();
+
+			assert!(!BondedPools::<Runtime>::contains_key(2));
+			assert!(!RewardPools::<Runtime>::contains_key(2));
+			assert!(!PoolMembers::<Runtime>::contains_key(11));
+			assert_eq!(StakingMock::active_stake(&next_pool_stash), None);
+
+			Balances::make_free_balance_be(&11, StakingMock::minimum_bond() + ed);
+			let _ = pool_events_since_last_call();
+			assert_ok!(Pools::create(
+				Origin::signed(11),
+				StakingMock::minimum_bond(),
+				123,
+				456,
+				789
+			));
+
+			assert_eq!(Balances::free_balance(&11), 0);
+			assert_eq!(
+				PoolMembers::<Runtime>::get(11).unwrap(),
+				PoolMember::<Runtime> {
+					pool_id: 2,
+					points: StakingMock::minimum_bond(),
+					last_recorded_reward_counter: Zero::zero(),
+					unbonding_eras: Default::default(),
+				}
+			);
+			assert_eq!(
+				BondedPool::<Runtime>::get(2).unwrap(),
+				BondedPool::<Runtime> {
+					id: 2,
+					inner: BondedPoolInner {
+						points: StakingMock::minimum_bond(),
+						member_counter: 1,
+						state: PoolState::Open,
+						roles: PoolRoles {
+							depositor: 11,
+							root: Some(123),
+							nominator: Some(456),
+							state_toggler: Some(789),
+						},
+					},
+				}
+			);
+			assert_eq!(
+				StakingMock::active_stake(&next_pool_stash).unwrap(),
+				StakingMock::minimum_bond()
+			);
+			assert_eq!(RewardPools::<Runtime>::get(2).unwrap(), RewardPool::<Runtime>::default());
+			assert_eq!(ReversePoolIdLookup::<Runtime>::get(&next_pool_stash), Some(2));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::Created(11, 2), Event::Bonded(11, 2, 10, true)]
+			);
+		});
+	}
+
+	#[test]
+	fn create_errors_correctly() {
+		ExtBuilder::default().with_check(0).build_and_execute(|| {
+			assert_noop!(
+				Pools::create(Origin::signed(10), 420, 123, 456, 789),
+				Error::<Runtime>::AccountBelongsToOtherPool
+			);
+
+			// Given
+			assert_eq!(MinCreateBond::<Runtime>::get(), 2);
+			assert_eq!(StakingMock::minimum_bond(), 10);
+
+			// Then
+			assert_noop!(
+				Pools::create(Origin::signed(11), 9, 123, 456, 789),
+				Error::<Runtime>::MinimumBondNotMet
+			);
+
+			// Given
+			MinCreateBond::<Runtime>::put(20);
+
+			// Then
+			assert_noop!(
+				Pools::create(Origin::signed(11), 19, 123, 456, 789),
+				Error::<Runtime>::MinimumBondNotMet
+			);
+
+			// Given
+			BondedPool::<Runtime> {
+				id: 2,
+				inner: BondedPoolInner {
+					state: PoolState::Open,
+					points: 10,
+					member_counter: 1,
+					roles: DEFAULT_ROLES,
+				},
+			}
+			.put();
+			assert_eq!(MaxPools::<Runtime>::get(), Some(2));
+			assert_eq!(BondedPools::<Runtime>::count(), 2);
+
+			// Then
+			assert_noop!(
+				Pools::create(Origin::signed(11), 20, 123, 456, 789),
+				Error::<Runtime>::MaxPools
+			);
+		});
+	}
+}
+
+mod nominate {
+	use super::*;
+
+	#[test]
+	fn nominate_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Depositor can't nominate
+			assert_noop!(
+				Pools::nominate(Origin::signed(10), 1, vec![21]),
+				Error::<Runtime>::NotNominator
+			);
+
+			// State toggler can't nominate
+			assert_noop!(
+				Pools::nominate(Origin::signed(902), 1, vec![21]),
+				Error::<Runtime>::NotNominator
+			);
+
+			// Root can nominate
+			assert_ok!(Pools::nominate(Origin::signed(900), 1, vec![21]));
+			assert_eq!(Nominations::get(), vec![21]);
+
+			// Nominator can nominate
+			assert_ok!(Pools::nominate(Origin::signed(901), 1, vec![31]));
+			assert_eq!(Nominations::get(), vec![31]);
+
+			// Can't nominate for a pool that doesn't exist
+			assert_noop!(
+				Pools::nominate(Origin::signed(902), 123, vec![21]),
+				Error::<Runtime>::PoolNotFound
+			);
+		});
+	}
+
+	#[test]
+	fn chill_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_noop!(Pools::chill(Origin::signed(902), 1), Error::<Runtime>::NotNominator);
+			assert_noop!(Pools::chill(Origin::signed(901), 123), Error::<Runtime>::PoolNotFound);
+
+			assert_ok!(Pools::chill(Origin::signed(900), 1));
+			assert_ok!(Pools::chill(Origin::signed(901), 1));
+		});
+	}
+}
+
+mod set_state {
+	use super::*;
+
+	#[test]
+	fn set_state_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given
+			assert!(BondedPool::<Runtime>::get(1).unwrap().ok_to_be_open().is_ok());
+			let _ = pool_events_since_last_call();
+
+			// Only the root and state toggler can change the state when the pool is ok to be open.
+			assert_noop!(
+				Pools::set_state(Origin::signed(10), 1, PoolState::Blocked),
+				Error::<Runtime>::CanNotChangeState
+			);
+			assert_noop!(
+				Pools::set_state(Origin::signed(901), 1, PoolState::Blocked),
+				Error::<Runtime>::CanNotChangeState
+			);
+
+			// Root can change state
+			assert_ok!(Pools::set_state(Origin::signed(900), 1, PoolState::Blocked));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().state, PoolState::Blocked);
+
+			// State toggler can change state
+			assert_ok!(Pools::set_state(Origin::signed(902), 1, PoolState::Destroying));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().state, PoolState::Destroying);
+
+			// If the pool is destroying, then no one can set state
+			assert_noop!(
+				Pools::set_state(Origin::signed(900), 1, PoolState::Blocked),
+				Error::<Runtime>::CanNotChangeState
+			);
+			assert_noop!(
+				Pools::set_state(Origin::signed(902), 1, PoolState::Blocked),
+				Error::<Runtime>::CanNotChangeState
+			);
+
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::StateChanged(1, PoolState::Blocked),
+					Event::StateChanged(1, PoolState::Destroying)
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn set_state_permissionless_when_not_ok_to_be_open() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given the pool was slashed to zero
+			StakingMock::set_bonded_balance(DEFAULT_BONDED_ACCOUNT, 0);
+			assert!(BondedPool::<Runtime>::get(1).unwrap().ok_to_be_open().is_err());
+			let _ = pool_events_since_last_call();
+
+			// Then anyone can only set it to destroying
+			assert_noop!(
+				Pools::set_state(Origin::signed(11), 1, PoolState::Blocked),
+				Error::<Runtime>::CanNotChangeState
+			);
+			assert_ok!(Pools::set_state(Origin::signed(11), 1, PoolState::Destroying));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::StateChanged(1, PoolState::Destroying)]
+			);
+
+			// Given the points to balance ratio is too high
+			unsafe_set_state(1, PoolState::Open);
+			StakingMock::set_bonded_balance(DEFAULT_BONDED_ACCOUNT, 1);
+
+			// Then anyone can set it to destroying
+			assert_ok!(Pools::set_state(Origin::signed(11), 1, PoolState::Destroying));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().state, PoolState::Destroying);
+		});
+	}
+}
+
+mod set_metadata {
+	use super::*;
+
+	#[test]
+	fn set_metadata_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Root can set metadata
+			assert_ok!(Pools::set_metadata(Origin::signed(900), 1, vec![1, 1]));
+			assert_eq!(Metadata::<Runtime>::get(1).into_inner(), vec![1, 1]);
+
+			// State toggler can set metadata
+			assert_ok!(Pools::set_metadata(Origin::signed(902), 1, vec![2, 2]));
+			assert_eq!(Metadata::<Runtime>::get(1).into_inner(), vec![2, 2]);
+
+			// Depositor can't set metadata
+			assert_noop!(
+				Pools::set_metadata(Origin::signed(10), 1, vec![3, 3]),
+				Error::<Runtime>::DoesNotHavePermission
+			);
+
+			// Nominator can't set metadata
+			assert_noop!(
+				Pools::set_metadata(Origin::signed(901), 1, vec![3, 3]),
+				Error::<Runtime>::DoesNotHavePermission
+			);
+
+			// Metadata cannot be longer than `MaxMetadataLen`
+			assert_noop!(
+				Pools::set_metadata(Origin::signed(900), 1, vec![1, 1, 1]),
+				Error::<Runtime>::MetadataExceedsMaxLen
+			);
+		});
+	}
+}
+
+mod set_configs {
+	use super::*;
+
+	#[test]
+	fn set_configs_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Setting works
+			assert_ok!(Pools::set_configs(
+				Origin::root(),
+				ConfigOp::Set(1 as Balance),
+				ConfigOp::Set(2 as Balance),
+				ConfigOp::Set(3u32),
+				ConfigOp::Set(4u32),
+				ConfigOp::Set(5u32),
+			));
+			assert_eq!(MinJoinBond::<Runtime>::get(), 1);
+			assert_eq!(MinCreateBond::<Runtime>::get(), 2);
+			assert_eq!(MaxPools::<Runtime>::get(), Some(3));
+			assert_eq!(MaxPoolMembers::<Runtime>::get(), Some(4));
+			assert_eq!(MaxPoolMembersPerPool::<Runtime>::get(), Some(5));
+
+			// Noop does nothing
+			assert_storage_noop!(Pools::set_configs(
+				Origin::root(),
+				ConfigOp::Noop,
+				ConfigOp::Noop,
+				ConfigOp::Noop,
+				ConfigOp::Noop,
+				ConfigOp::Noop,
+			)
+			.unwrap());
+
+			// Removing works
+			assert_ok!(Pools::set_configs(
+				Origin::root(),
+				ConfigOp::Remove,
+				ConfigOp::Remove,
+				ConfigOp::Remove,
+				ConfigOp::Remove,
+				ConfigOp::Remove,
+			));
+			assert_eq!(MinJoinBond::<Runtime>::get(), 0);
+			assert_eq!(MinCreateBond::<Runtime>::get(), 0);
+			assert_eq!(MaxPools::<Runtime>::get(), None);
+			assert_eq!(MaxPoolMembers::<Runtime>::get(), None);
+			assert_eq!(MaxPoolMembersPerPool::<Runtime>::get(), None);
+
+			// Only root can set the configs
+			assert_noop!(
+				Pools::set_configs(
+					Origin::signed(10),
+					ConfigOp::Noop,
+					ConfigOp::Noop,
+					ConfigOp::Noop,
+					ConfigOp::Noop,
+					ConfigOp::Noop,
+				),
+				BadOrigin
+			);
+		});
+	}
+}
+
+mod update_roles {
+	use super::*;
+
+	#[test]
+	fn update_roles_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_eq!(BondedPools::<Runtime>::get(1).unwrap().roles, DEFAULT_ROLES);
+
+			// non-existent pools
+			assert_noop!(
+				Pools::update_roles(
+					Origin::signed(1),
+					2,
+					ConfigOp::Set(5),
+					ConfigOp::Set(6),
+					ConfigOp::Set(7)
+				),
+				Error::<Runtime>::PoolNotFound,
+			);
+
+			// depositor, nominator and state toggler cannot change roles.
+			for who in [10, 901, 902].iter() {
+				assert_noop!(
+					Pools::update_roles(
+						Origin::signed(*who),
+						1,
+						ConfigOp::Set(5),
+						ConfigOp::Set(6),
+						ConfigOp::Set(7)
+					),
+					Error::<Runtime>::DoesNotHavePermission,
+				);
+			}
+
+			// but root can
+			let _ = pool_events_since_last_call();
+			assert_ok!(Pools::update_roles(
+				Origin::signed(900),
+				1,
+				ConfigOp::Set(5),
+				ConfigOp::Set(6),
+				ConfigOp::Set(7)
+			));
+
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::RolesUpdated(Some(5), Some(6), Some(7))]
+			);
+			assert_eq!(
+				BondedPools::<Runtime>::get(1).unwrap().roles,
+				PoolRoles {
+					depositor: 10,
+					root: Some(5),
+					nominator: Some(6),
+					state_toggler: Some(7)
+				},
+			);
+
+			// also root origin can
+			assert_ok!(Pools::update_roles(
+				Origin::root(),
+				1,
+				ConfigOp::Set(1),
+				ConfigOp::Set(2),
+				ConfigOp::Set(3)
+			));
+			assert_eq!(
+				BondedPools::<Runtime>::get(1).unwrap().roles,
+				PoolRoles {
+					depositor: 10,
+					root: Some(1),
+					nominator: Some(2),
+					state_toggler: Some(3)
+				},
+			);
+
+			// Noop works
+			assert_ok!(Pools::update_roles(
+				Origin::root(),
+				1,
+				ConfigOp::Set(11),
+				ConfigOp::Noop,
+				ConfigOp::Noop
+			));
+			assert_eq!(
+				BondedPools::<Runtime>::get(1).unwrap().roles,
+				PoolRoles {
+					depositor: 10,
+					root: Some(11),
+					nominator: Some(2),
+					state_toggler: Some(3)
+				},
+			);
+
+			// Remove works
+			assert_ok!(Pools::update_roles(
+				Origin::signed(11),
+				1,
+				ConfigOp::Remove,
+				ConfigOp::Remove,
+				ConfigOp::Remove
+			));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::RolesUpdated(Some(1), Some(2), Some(3)),
+					Event::RolesUpdated(Some(11), Some(2), Some(3)),
+					Event::RolesUpdated(None, None, None),
+				]
+			);
+			assert_eq!(
+				BondedPools::<Runtime>::get(1).unwrap().roles,
+				PoolRoles { depositor: 10, root: None, nominator: None, state_toggler: None },
+			);
+
+			// now only the root origin can change the roles.
+			assert_noop!(
+				Pools::update_roles(
+					Origin::signed(11),
+					1,
+					ConfigOp::Set(1),
+					ConfigOp::Noop,
+					ConfigOp::Noop
+				),
+				Error::<Runtime>::DoesNotHavePermission,
+			);
+		});
+	}
+}
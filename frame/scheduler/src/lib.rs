@@ -47,6 +47,9 @@
 //! * `schedule_named` - augments the `schedule` interface with an additional
 //!   `Vec<u8>` parameter that can be used for identification.
 //! * `cancel_named` - the named complement to the cancel function.
+//!
+//! Calls may be scheduled either by value or by the hash of their encoding. Calls given by hash
+//! are requested from `Config::PreimageProvider` when scheduled and looked up when they are due.
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -60,7 +63,10 @@ use sp_runtime::{RuntimeDebug, traits::{Zero, One, BadOrigin, Saturating}};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, IterableStorageMap,
 	dispatch::{Dispatchable, DispatchError, DispatchResult, Parameter},
-	traits::{Get, schedule::{self, DispatchTime}, OriginTrait, EnsureOrigin, IsType},
+	traits::{
+		Get, schedule::{self, DispatchTime, MaybeHashed, LookupError}, OriginTrait, EnsureOrigin,
+		IsType, PreimageProvider,
+	},
 	weights::{GetDispatchInfo, Weight},
 };
 use frame_system::{self as system, ensure_signed};
@@ -98,6 +104,13 @@ pub trait Config: system::Config {
 
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
+
+	/// The preimage provider with which we look up call hashes to get the call.
+	type PreimageProvider: PreimageProvider<Self::Hash>;
+
+	/// If `Some` then the number of blocks to postpone execution for when the item is delayed
+	/// because its preimage is not yet available. If `None`, such an item is dropped instead.
+	type NoPreimagePostponement: Get<Option<Self::BlockNumber>>;
 }
 
 /// Just a simple index for naming period tasks.
@@ -131,8 +144,33 @@ pub struct ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId> {
 	_phantom: PhantomData<AccountId>,
 }
 
+/// The hash of a call or the call itself.
+pub type CallOrHashOf<T> = MaybeHashed<<T as Config>::Call, <T as system::Config>::Hash>;
+
+/// Information regarding an item to be executed in the future, where the call may be given by
+/// the hash of its encoding.
+pub type ScheduledV3<Call, BlockNumber, PalletsOrigin, AccountId> =
+	ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId>;
+
+pub type ScheduledV2Of<T> = ScheduledV2<
+	<T as Config>::Call,
+	<T as system::Config>::BlockNumber,
+	<T as Config>::PalletsOrigin,
+	<T as system::Config>::AccountId,
+>;
+
+pub type ScheduledV3Of<T> = ScheduledV3<
+	CallOrHashOf<T>,
+	<T as system::Config>::BlockNumber,
+	<T as Config>::PalletsOrigin,
+	<T as system::Config>::AccountId,
+>;
+
+pub type ScheduledOf<T> = ScheduledV3Of<T>;
+
 /// The current version of Scheduled struct.
-pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Call, BlockNumber, PalletsOrigin, AccountId>;
+pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> =
+	ScheduledV3<Call, BlockNumber, PalletsOrigin, AccountId>;
 
 // A value placed in storage that represents the current version of the Scheduler storage.
 // This value is used by the `on_runtime_upgrade` logic to determine whether we run
@@ -141,6 +179,7 @@ pub type Scheduled<Call, BlockNumber, PalletsOrigin, AccountId> = ScheduledV2<Ca
 enum Releases {
 	V1,
 	V2,
+	V3,
 }
 
 impl Default for Releases {
@@ -152,8 +191,7 @@ impl Default for Releases {
 decl_storage! {
 	trait Store for Module<T: Config> as Scheduler {
 		/// Items to be executed, indexed by the block number that they should be executed on.
-		pub Agenda: map hasher(twox_64_concat) T::BlockNumber
-			=> Vec<Option<Scheduled<<T as Config>::Call, T::BlockNumber, T::PalletsOrigin, T::AccountId>>>;
+		pub Agenda: map hasher(twox_64_concat) T::BlockNumber => Vec<Option<ScheduledOf<T>>>;
 
 		/// Lookup from identity to the block number and index of the task.
 		Lookup: map hasher(twox_64_concat) Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
@@ -161,7 +199,7 @@ decl_storage! {
 		/// Storage version of the pallet.
 		///
 		/// New networks start with last version.
-		StorageVersion build(|_| Releases::V2): Releases;
+		StorageVersion build(|_| Releases::V3): Releases;
 	}
 }
 
@@ -173,6 +211,9 @@ decl_event!(
 		Canceled(BlockNumber, u32),
 		/// Dispatched some task. \[task, id, result\]
 		Dispatched(TaskAddress<BlockNumber>, Option<Vec<u8>>, DispatchResult),
+		/// The call for the provided hash was not found so the task has been aborted.
+		/// \[task, id, error\]
+		CallLookupFailed(TaskAddress<BlockNumber>, Option<Vec<u8>>, LookupError),
 	}
 );
 
@@ -195,6 +236,14 @@ decl_module! {
 		type Error = Error<T>;
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if StorageVersion::get() == Releases::V2 {
+				weight = weight.saturating_add(Self::migrate_v2_to_v3());
+			}
+			weight
+		}
+
 		/// Anonymously schedule a task.
 		///
 		/// # <weight>
@@ -210,7 +259,7 @@ decl_module! {
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -250,7 +299,7 @@ decl_module! {
 			when: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -286,7 +335,7 @@ decl_module! {
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -306,7 +355,7 @@ decl_module! {
 			after: T::BlockNumber,
 			maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 			priority: schedule::Priority,
-			call: Box<<T as Config>::Call>,
+			call: Box<CallOrHashOf<T>>,
 		) {
 			T::ScheduleOrigin::ensure_origin(origin.clone())?;
 			let origin = <T as Config>::Origin::from(origin);
@@ -321,9 +370,10 @@ decl_module! {
 		/// - S = Number of already scheduled calls
 		/// - N = Named scheduled calls
 		/// - P = Periodic Calls
+		/// - H = Calls given by hash
 		/// - Base Weight: 9.243 + 23.45 * S µs
 		/// - DB Weight:
-		///     - Read: Agenda + Lookup * N + Agenda(Future) * P
+		///     - Read: Agenda + Lookup * N + Agenda(Future) * P + Preimage * H
 		///     - Write: Agenda + Lookup * N  + Agenda(future) * P
 		/// # </weight>
 		fn on_initialize(now: T::BlockNumber) -> Weight {
@@ -345,8 +395,17 @@ decl_module! {
 			queued.into_iter()
 				.enumerate()
 				.scan(base_weight, |cumulative_weight, (order, (index, s))| {
-					*cumulative_weight = cumulative_weight
-						.saturating_add(s.call.get_dispatch_info().weight);
+					// Calls given by hash need to be looked up first.
+					let maybe_call = s.call.lookup::<T::PreimageProvider>();
+					if s.call.as_hash().is_some() {
+						// Read preimage
+						*cumulative_weight = cumulative_weight
+							.saturating_add(T::DbWeight::get().reads(1));
+					}
+					if let Ok(ref call) = maybe_call {
+						*cumulative_weight = cumulative_weight
+							.saturating_add(call.get_dispatch_info().weight);
+					}
 
 					let origin = <<T as Config>::Origin as From<T::PalletsOrigin>>::from(
 						s.origin.clone()
@@ -367,16 +426,24 @@ decl_module! {
 						*cumulative_weight = cumulative_weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
 					}
 
-					Some((order, index, *cumulative_weight, s))
+					Some((order, index, *cumulative_weight, s, maybe_call))
 				})
-				.filter_map(|(order, index, cumulative_weight, mut s)| {
+				.filter_map(|(order, index, cumulative_weight, mut s, maybe_call)| {
 					// We allow a scheduled call if any is true:
 					// - It's priority is `HARD_DEADLINE`
 					// - It does not push the weight past the limit.
 					// - It is the first item in the schedule
 					if s.priority <= schedule::HARD_DEADLINE || cumulative_weight <= limit || order == 0 {
-						let r = s.call.clone().dispatch(s.origin.clone().into());
 						let maybe_id = s.maybe_id.clone();
+						let call = match maybe_call {
+							Ok(call) => call,
+							Err(error) => {
+								Self::handle_lookup_failure(now, index, s, error);
+								total_weight = cumulative_weight;
+								return None
+							},
+						};
+						let r = call.dispatch(s.origin.clone().into());
 						if let &Some((period, count)) = &s.maybe_periodic {
 							if count > 1 {
 								s.maybe_periodic = Some((period, count - 1));
@@ -394,6 +461,7 @@ decl_module! {
 							if let Some(ref id) = s.maybe_id {
 								Lookup::<T>::remove(id);
 							}
+							s.call.ensure_unrequested::<T::PreimageProvider>();
 						}
 						Self::deposit_event(RawEvent::Dispatched(
 							(now, index),
@@ -417,21 +485,21 @@ decl_module! {
 }
 
 impl<T: Config> Module<T> {
-	/// Migrate storage format from V1 to V2.
+	/// Migrate storage format from V1 to V3.
 	/// Return true if migration is performed.
-	pub fn migrate_v1_to_t2() -> bool {
+	pub fn migrate_v1_to_v3() -> bool {
 		if StorageVersion::get() == Releases::V1 {
-			StorageVersion::put(Releases::V2);
+			StorageVersion::put(Releases::V3);
 
 			Agenda::<T>::translate::<
 				Vec<Option<ScheduledV1<<T as Config>::Call, T::BlockNumber>>>, _
 			>(|_, agenda| Some(
 				agenda
 					.into_iter()
-					.map(|schedule| schedule.map(|schedule| ScheduledV2 {
+					.map(|schedule| schedule.map(|schedule| ScheduledV3 {
 						maybe_id: schedule.maybe_id,
 						priority: schedule.priority,
-						call: schedule.call,
+						call: schedule.call.into(),
 						maybe_periodic: schedule.maybe_periodic,
 						origin: system::RawOrigin::Root.into(),
 						_phantom: Default::default(),
@@ -445,10 +513,43 @@ impl<T: Config> Module<T> {
 		}
 	}
 
+	/// Migrate storage format from V2 to V3, wrapping every scheduled call into
+	/// `MaybeHashed::Value`.
+	///
+	/// Returns the weight consumed by this migration.
+	pub fn migrate_v2_to_v3() -> Weight {
+		let mut weight = T::DbWeight::get().reads_writes(1, 1);
+		if StorageVersion::get() != Releases::V2 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		log::info!(target: "runtime::scheduler", "Migrating scheduler agenda to Releases::V3");
+
+		Agenda::<T>::translate::<Vec<Option<ScheduledV2Of<T>>>, _>(|_, agenda| {
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+			Some(
+				agenda
+					.into_iter()
+					.map(|schedule| schedule.map(|schedule| ScheduledV3 {
+						maybe_id: schedule.maybe_id,
+						priority: schedule.priority,
+						call: schedule.call.into(),
+						maybe_periodic: schedule.maybe_periodic,
+						origin: schedule.origin,
+						_phantom: Default::default(),
+					}))
+					.collect::<Vec<_>>()
+			)
+		});
+		StorageVersion::put(Releases::V3);
+
+		weight
+	}
+
 	/// Helper to migrate scheduler when the pallet origin type has changed.
 	pub fn migrate_origin<OldOrigin: Into<T::PalletsOrigin> + codec::Decode>() {
 		Agenda::<T>::translate::<
-			Vec<Option<Scheduled<<T as Config>::Call, T::BlockNumber, OldOrigin, T::AccountId>>>, _
+			Vec<Option<Scheduled<CallOrHashOf<T>, T::BlockNumber, OldOrigin, T::AccountId>>>, _
 		>(|_, agenda| Some(
 			agenda
 				.into_iter()
@@ -464,6 +565,35 @@ impl<T: Config> Module<T> {
 		));
 	}
 
+	/// Deal with a due task whose call could not be looked up by its hash.
+	///
+	/// If the preimage is merely unknown and `NoPreimagePostponement` is set, the task is moved to
+	/// a later block. Otherwise it is dropped along with its preimage request.
+	fn handle_lookup_failure(
+		now: T::BlockNumber,
+		index: u32,
+		s: ScheduledOf<T>,
+		error: LookupError,
+	) {
+		match (error, T::NoPreimagePostponement::get()) {
+			(LookupError::Unknown, Some(delay)) => {
+				let next = now.saturating_add(delay.max(One::one()));
+				if let Some(ref id) = s.maybe_id {
+					let next_index = Agenda::<T>::decode_len(next).unwrap_or(0);
+					Lookup::<T>::insert(id, (next, next_index as u32));
+				}
+				Agenda::<T>::append(next, Some(s));
+			},
+			_ => {
+				if let Some(ref id) = s.maybe_id {
+					Lookup::<T>::remove(id);
+				}
+				s.call.ensure_unrequested::<T::PreimageProvider>();
+				Self::deposit_event(RawEvent::CallLookupFailed((now, index), s.maybe_id, error));
+			},
+		}
+	}
+
 	fn resolve_time(when: DispatchTime<T::BlockNumber>) -> Result<T::BlockNumber, DispatchError> {
 		let now = frame_system::Pallet::<T>::block_number();
 
@@ -486,9 +616,10 @@ impl<T: Config> Module<T> {
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Config>::Call
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		let when = Self::resolve_time(when)?;
+		call.ensure_requested::<T::PreimageProvider>();
 
 		// sanitize maybe_periodic
 		let maybe_periodic = maybe_periodic
@@ -531,6 +662,7 @@ impl<T: Config> Module<T> {
 			},
 		)?;
 		if let Some(s) = scheduled {
+			s.call.ensure_unrequested::<T::PreimageProvider>();
 			if let Some(id) = s.maybe_id {
 				Lookup::<T>::remove(id);
 			}
@@ -571,7 +703,7 @@ impl<T: Config> Module<T> {
 		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
 		priority: schedule::Priority,
 		origin: T::PalletsOrigin,
-		call: <T as Config>::Call,
+		call: CallOrHashOf<T>,
 	) -> Result<TaskAddress<T::BlockNumber>, DispatchError> {
 		// ensure id it is unique
 		if Lookup::<T>::contains_key(&id) {
@@ -579,6 +711,7 @@ impl<T: Config> Module<T> {
 		}
 
 		let when = Self::resolve_time(when)?;
+		call.ensure_requested::<T::PreimageProvider>();
 
 		// sanitize maybe_periodic
 		let maybe_periodic = maybe_periodic
@@ -616,7 +749,9 @@ impl<T: Config> Module<T> {
 								return Err(BadOrigin.into());
 							}
 						}
-						*s = None;
+						if let Some(s) = s.take() {
+							s.call.ensure_unrequested::<T::PreimageProvider>();
+						}
 					}
 					Ok(())
 				})?;
@@ -660,7 +795,9 @@ impl<T: Config> Module<T> {
 	}
 }
 
-impl<T: Config> schedule::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOrigin> for Module<T> {
+impl<T: Config> schedule::v1::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOrigin>
+	for Module<T>
+{
 	type Address = TaskAddress<T::BlockNumber>;
 
 	fn schedule(
@@ -670,7 +807,7 @@ impl<T: Config> schedule::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOr
 		origin: T::PalletsOrigin,
 		call: <T as Config>::Call
 	) -> Result<Self::Address, DispatchError> {
-		Self::do_schedule(when, maybe_periodic, priority, origin, call)
+		Self::do_schedule(when, maybe_periodic, priority, origin, call.into())
 	}
 
 	fn cancel((when, index): Self::Address) -> Result<(), ()> {
@@ -689,7 +826,9 @@ impl<T: Config> schedule::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOr
 	}
 }
 
-impl<T: Config> schedule::Named<T::BlockNumber, <T as Config>::Call, T::PalletsOrigin> for Module<T> {
+impl<T: Config> schedule::v1::Named<T::BlockNumber, <T as Config>::Call, T::PalletsOrigin>
+	for Module<T>
+{
 	type Address = TaskAddress<T::BlockNumber>;
 
 	fn schedule_named(
@@ -700,7 +839,8 @@ impl<T: Config> schedule::Named<T::BlockNumber, <T as Config>::Call, T::PalletsO
 		origin: T::PalletsOrigin,
 		call: <T as Config>::Call,
 	) -> Result<Self::Address, ()> {
-		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call).map_err(|_| ())
+		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call.into())
+			.map_err(|_| ())
 	}
 
 	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
@@ -719,6 +859,73 @@ impl<T: Config> schedule::Named<T::BlockNumber, <T as Config>::Call, T::PalletsO
 	}
 }
 
+impl<T: Config> schedule::v2::Anon<T::BlockNumber, <T as Config>::Call, T::PalletsOrigin>
+	for Module<T>
+{
+	type Address = TaskAddress<T::BlockNumber>;
+	type Hash = T::Hash;
+
+	fn schedule(
+		when: DispatchTime<T::BlockNumber>,
+		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+		priority: schedule::Priority,
+		origin: T::PalletsOrigin,
+		call: CallOrHashOf<T>,
+	) -> Result<Self::Address, DispatchError> {
+		Self::do_schedule(when, maybe_periodic, priority, origin, call)
+	}
+
+	fn cancel((when, index): Self::Address) -> Result<(), ()> {
+		Self::do_cancel(None, (when, index)).map_err(|_| ())
+	}
+
+	fn reschedule(
+		address: Self::Address,
+		when: DispatchTime<T::BlockNumber>,
+	) -> Result<Self::Address, DispatchError> {
+		Self::do_reschedule(address, when)
+	}
+
+	fn next_dispatch_time((when, index): Self::Address) -> Result<T::BlockNumber, ()> {
+		Agenda::<T>::get(when).get(index as usize).ok_or(()).map(|_| when)
+	}
+}
+
+impl<T: Config> schedule::v2::Named<T::BlockNumber, <T as Config>::Call, T::PalletsOrigin>
+	for Module<T>
+{
+	type Address = TaskAddress<T::BlockNumber>;
+	type Hash = T::Hash;
+
+	fn schedule_named(
+		id: Vec<u8>,
+		when: DispatchTime<T::BlockNumber>,
+		maybe_periodic: Option<schedule::Period<T::BlockNumber>>,
+		priority: schedule::Priority,
+		origin: T::PalletsOrigin,
+		call: CallOrHashOf<T>,
+	) -> Result<Self::Address, ()> {
+		Self::do_schedule_named(id, when, maybe_periodic, priority, origin, call).map_err(|_| ())
+	}
+
+	fn cancel_named(id: Vec<u8>) -> Result<(), ()> {
+		Self::do_cancel_named(None, id).map_err(|_| ())
+	}
+
+	fn reschedule_named(
+		id: Vec<u8>,
+		when: DispatchTime<T::BlockNumber>,
+	) -> Result<Self::Address, DispatchError> {
+		Self::do_reschedule_named(id, when)
+	}
+
+	fn next_dispatch_time(id: Vec<u8>) -> Result<T::BlockNumber, ()> {
+		Lookup::<T>::get(id)
+			.and_then(|(when, index)| Agenda::<T>::get(when).get(index as usize).map(|_| when))
+			.ok_or(())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -726,14 +933,14 @@ mod tests {
 	use frame_support::{
 		parameter_types, assert_ok, ord_parameter_types,
 		assert_noop, assert_err, Hashable,
-		traits::{OnInitialize, OnFinalize, Filter},
+		traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, Filter},
 		weights::constants::RocksDbWeight,
 	};
 	use sp_core::H256;
 	use sp_runtime::{
 		Perbill,
 		testing::Header,
-		traits::{BlakeTwo256, IdentityLookup},
+		traits::{BlakeTwo256, Hash, IdentityLookup},
 	};
 	use frame_system::{EnsureOneOf, EnsureRoot, EnsureSignedBy};
 	use substrate_test_utils::assert_eq_uvec;
@@ -796,6 +1003,8 @@ mod tests {
 			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 			Logger: logger::{Pallet, Call, Event},
 			Scheduler: scheduler::{Pallet, Call, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Preimage: pallet_preimage::{Pallet, Call, Storage, Event<T>},
 		}
 	);
 
@@ -830,7 +1039,7 @@ mod tests {
 		type BlockHashCount = BlockHashCount;
 		type Version = ();
 		type PalletInfo = PalletInfo;
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 		type SystemWeightInfo = ();
@@ -840,9 +1049,36 @@ mod tests {
 	impl logger::Config for Test {
 		type Event = Event;
 	}
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type DustRemoval = ();
+		type Event = Event;
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type WeightInfo = ();
+		type MaxLocks = ();
+	}
+	parameter_types! {
+		pub const PreimageMaxSize: u32 = 4096;
+		pub const PreimageBaseDeposit: u64 = 2;
+		pub const PreimageByteDeposit: u64 = 1;
+	}
+	impl pallet_preimage::Config for Test {
+		type Event = Event;
+		type WeightInfo = ();
+		type Currency = Balances;
+		type ManagerOrigin = EnsureRoot<u64>;
+		type MaxSize = PreimageMaxSize;
+		type BaseDeposit = PreimageBaseDeposit;
+		type ByteDeposit = PreimageByteDeposit;
+	}
 	parameter_types! {
 		pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
 		pub const MaxScheduledPerBlock: u32 = 10;
+		pub static NoPreimagePostponement: Option<u64> = Some(2);
 	}
 	ord_parameter_types! {
 		pub const One: u64 = 1;
@@ -857,6 +1093,8 @@ mod tests {
 		type ScheduleOrigin = EnsureOneOf<u64, EnsureRoot<u64>, EnsureSignedBy<One, u64>>;
 		type MaxScheduledPerBlock = MaxScheduledPerBlock;
 		type WeightInfo = ();
+		type PreimageProvider = Preimage;
+		type NoPreimagePostponement = NoPreimagePostponement;
 	}
 
 	pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -881,7 +1119,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into()));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
 			run_to_block(4);
@@ -898,7 +1136,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			// This will schedule the call 3 blocks after the next block... so block 3 + 3 = 6
-			assert_ok!(Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::After(3), None, 127, root(), call.into()));
 			run_to_block(5);
 			assert!(logger::log().is_empty());
 			run_to_block(6);
@@ -914,7 +1152,7 @@ mod tests {
 			run_to_block(2);
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_ok!(Scheduler::do_schedule(DispatchTime::After(0), None, 127, root(), call));
+			assert_ok!(Scheduler::do_schedule(DispatchTime::After(0), None, 127, root(), call.into()));
 			// Will trigger on the next block.
 			run_to_block(3);
 			assert_eq!(logger::log(), vec![(root(), 42u32)]);
@@ -928,7 +1166,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4, every 3 blocks, 3 times.
 			assert_ok!(Scheduler::do_schedule(
-				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), Some((3, 3)), 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			));
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -952,7 +1190,7 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
-			assert_eq!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call).unwrap(), (4, 0));
+			assert_eq!(Scheduler::do_schedule(DispatchTime::At(4), None, 127, root(), call.into()).unwrap(), (4, 0));
 
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -978,7 +1216,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			assert_eq!(Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), None, 127, root(), call
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), call.into()
 			).unwrap(), (4, 0));
 
 			run_to_block(3);
@@ -1005,7 +1243,7 @@ mod tests {
 			let call = Call::Logger(logger::Call::log(42, 1000));
 			assert!(!<Test as frame_system::Config>::BaseCallFilter::filter(&call));
 			assert_eq!(Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), Some((3, 3)), 127, root(), call
+				1u32.encode(), DispatchTime::At(4), Some((3, 3)), 127, root(), call.into()
 			).unwrap(), (4, 0));
 
 			run_to_block(3);
@@ -1041,10 +1279,10 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			// at #4.
 			Scheduler::do_schedule_named(
-				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			let i = Scheduler::do_schedule(
-				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000))
+				DispatchTime::At(4), None, 127, root(), Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1065,7 +1303,7 @@ mod tests {
 				Some((3, 3)),
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, 1000))
+				Call::Logger(logger::Call::log(42, 1000)).into()
 			).unwrap();
 			// same id results in error.
 			assert!(Scheduler::do_schedule_named(
@@ -1074,11 +1312,11 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, 1000))
+				Call::Logger(logger::Call::log(69, 1000)).into()
 			).is_err());
 			// different id is ok.
 			Scheduler::do_schedule_named(
-				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000))
+				2u32.encode(), DispatchTime::At(8), None, 127, root(), Call::Logger(logger::Call::log(69, 1000)).into()
 			).unwrap();
 			run_to_block(3);
 			assert!(logger::log().is_empty());
@@ -1099,13 +1337,13 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// 69 and 42 do not fit together
 			run_to_block(4);
@@ -1123,14 +1361,14 @@ mod tests {
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// With base weights, 69 and 42 should not fit together, but do because of hard deadlines
 			run_to_block(4);
@@ -1146,14 +1384,14 @@ mod tests {
 				None,
 				1,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				0,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			run_to_block(4);
 			assert_eq!(logger::log(), vec![(root(), 69u32), (root(), 42u32)]);
@@ -1167,19 +1405,19 @@ mod tests {
 				DispatchTime::At(4),
 				None,
 				255,
-				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				root(), Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				127,
-				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			assert_ok!(Scheduler::do_schedule(
 				DispatchTime::At(4),
 				None,
 				126,
-				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2))
+				root(), Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into()
 			));
 
 			// 2600 does not fit with 69 or 42, but has higher priority, so will go through
@@ -1203,7 +1441,7 @@ mod tests {
 			assert_ok!(
 				Scheduler::do_schedule_named(
 					1u32.encode(), DispatchTime::At(1), None, 255, root(),
-					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3))
+					Call::Logger(logger::Call::log(3, MaximumSchedulerWeight::get() / 3)).into()
 				)
 			);
 			// Anon Periodic
@@ -1212,7 +1450,7 @@ mod tests {
 				Some((1000, 3)),
 				128,
 				root(),
-				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3))
+				Call::Logger(logger::Call::log(42, MaximumSchedulerWeight::get() / 3)).into()
 			));
 			// Anon
 			assert_ok!(Scheduler::do_schedule(
@@ -1220,12 +1458,12 @@ mod tests {
 				None,
 				127,
 				root(),
-				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2))
+				Call::Logger(logger::Call::log(69, MaximumSchedulerWeight::get() / 2)).into()
 			));
 			// Named Periodic
 			assert_ok!(Scheduler::do_schedule_named(
 				2u32.encode(), DispatchTime::At(1), Some((1000, 3)), 126, root(),
-				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)))
+				Call::Logger(logger::Call::log(2600, MaximumSchedulerWeight::get() / 2)).into())
 			);
 
 			// Will include the named periodic only
@@ -1257,8 +1495,8 @@ mod tests {
 	#[test]
 	fn root_calls_works() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_ok!(Scheduler::schedule_named(Origin::root(), 1u32.encode(), 4, None, 127, call));
 			assert_ok!(Scheduler::schedule(Origin::root(), 4, None, 127, call2));
 			run_to_block(3);
@@ -1278,8 +1516,8 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			run_to_block(3);
 
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 
 			assert_err!(
 				Scheduler::schedule_named(Origin::root(), 1u32.encode(), 2, None, 127, call),
@@ -1301,8 +1539,8 @@ mod tests {
 	#[test]
 	fn should_use_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1322,8 +1560,8 @@ mod tests {
 	#[test]
 	fn should_check_orign() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log(42, 1000)).into());
 			assert_noop!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(2).into(), 1u32.encode(), 4, None, 127, call),
 				BadOrigin
@@ -1335,8 +1573,8 @@ mod tests {
 	#[test]
 	fn should_check_orign_for_cancel() {
 		new_test_ext().execute_with(|| {
-			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)));
-			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)));
+			let call = Box::new(Call::Logger(logger::Call::log_without_filter(69, 1000)).into());
+			let call2 = Box::new(Call::Logger(logger::Call::log_without_filter(42, 1000)).into());
 			assert_ok!(
 				Scheduler::schedule_named(system::RawOrigin::Signed(1).into(), 1u32.encode(), 4, None, 127, call)
 			);
@@ -1358,7 +1596,7 @@ mod tests {
 	}
 
 	#[test]
-	fn migration_to_v2_works() {
+	fn migration_to_v3_works() {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
@@ -1387,25 +1625,25 @@ mod tests {
 
 			assert_eq!(StorageVersion::get(), Releases::V1);
 
-			assert!(Scheduler::migrate_v1_to_t2());
+			assert!(Scheduler::migrate_v1_to_v3());
 
 			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), vec![
 				(
 					0,
 					vec![
-					Some(ScheduledV2 {
+					Some(ScheduledV3Of::<Test> {
 						maybe_id: None,
 						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: root(),
 						_phantom: PhantomData::<u64>::default(),
 					}),
 					None,
-					Some(ScheduledV2 {
+					Some(ScheduledV3Of::<Test> {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: root(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1414,19 +1652,19 @@ mod tests {
 				(
 					1,
 					vec![
-						Some(ScheduledV2 {
+						Some(ScheduledV3Of::<Test> {
 							maybe_id: None,
 							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
 						}),
 						None,
-						Some(ScheduledV2 {
+						Some(ScheduledV3Of::<Test> {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1436,19 +1674,19 @@ mod tests {
 				(
 					2,
 					vec![
-						Some(ScheduledV2 {
+						Some(ScheduledV3Of::<Test> {
 							maybe_id: None,
 							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
 						}),
 						None,
-						Some(ScheduledV2 {
+						Some(ScheduledV3Of::<Test> {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: root(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1457,7 +1695,7 @@ mod tests {
 				)
 			]);
 
-			assert_eq!(StorageVersion::get(), Releases::V2);
+			assert_eq!(StorageVersion::get(), Releases::V3);
 		});
 	}
 
@@ -1466,11 +1704,11 @@ mod tests {
 		new_test_ext().execute_with(|| {
 			for i in 0..3u64 {
 				let k = i.twox_64_concat();
-				let old: Vec<Option<Scheduled<_, _, u32, u64>>> = vec![
+				let old: Vec<Option<Scheduled<CallOrHashOf<Test>, u64, u32, u64>>> = vec![
 					Some(Scheduled {
 						maybe_id: None,
 						priority: i as u8 + 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						origin: 3u32,
 						maybe_periodic: None,
 						_phantom: Default::default(),
@@ -1480,7 +1718,7 @@ mod tests {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
 						origin: 2u32,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						_phantom: Default::default(),
 					}),
@@ -1509,19 +1747,19 @@ mod tests {
 				(
 					0,
 					vec![
-					Some(ScheduledV2::<_, _, OriginCaller, u64> {
+					Some(ScheduledV3Of::<Test> {
 						maybe_id: None,
 						priority: 10,
-						call: Call::Logger(logger::Call::log(96, 100)),
+						call: Call::Logger(logger::Call::log(96, 100)).into(),
 						maybe_periodic: None,
 						origin: system::RawOrigin::Root.into(),
 						_phantom: PhantomData::<u64>::default(),
 					}),
 					None,
-					Some(ScheduledV2 {
+					Some(ScheduledV3Of::<Test> {
 						maybe_id: Some(b"test".to_vec()),
 						priority: 123,
-						call: Call::Logger(logger::Call::log(69, 1000)),
+						call: Call::Logger(logger::Call::log(69, 1000)).into(),
 						maybe_periodic: Some((456u64, 10)),
 						origin: system::RawOrigin::None.into(),
 						_phantom: PhantomData::<u64>::default(),
@@ -1530,19 +1768,19 @@ mod tests {
 				(
 					1,
 					vec![
-						Some(ScheduledV2 {
+						Some(ScheduledV3Of::<Test> {
 							maybe_id: None,
 							priority: 11,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: system::RawOrigin::Root.into(),
 							_phantom: PhantomData::<u64>::default(),
 						}),
 						None,
-						Some(ScheduledV2 {
+						Some(ScheduledV3Of::<Test> {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: system::RawOrigin::None.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1552,19 +1790,19 @@ mod tests {
 				(
 					2,
 					vec![
-						Some(ScheduledV2 {
+						Some(ScheduledV3Of::<Test> {
 							maybe_id: None,
 							priority: 12,
-							call: Call::Logger(logger::Call::log(96, 100)),
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
 							maybe_periodic: None,
 							origin: system::RawOrigin::Root.into(),
 							_phantom: PhantomData::<u64>::default(),
 						}),
 						None,
-						Some(ScheduledV2 {
+						Some(ScheduledV3Of::<Test> {
 							maybe_id: Some(b"test".to_vec()),
 							priority: 123,
-							call: Call::Logger(logger::Call::log(69, 1000)),
+							call: Call::Logger(logger::Call::log(69, 1000)).into(),
 							maybe_periodic: Some((456u64, 10)),
 							origin: system::RawOrigin::None.into(),
 							_phantom: PhantomData::<u64>::default(),
@@ -1574,4 +1812,191 @@ mod tests {
 			]);
 		});
 	}
+
+	#[test]
+	fn migration_v2_to_v3_works() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::put(Releases::V2);
+			for i in 0..2u64 {
+				let k = i.twox_64_concat();
+				let old: Vec<Option<ScheduledV2Of<Test>>> = vec![
+					Some(ScheduledV2 {
+						maybe_id: None,
+						priority: i as u8 + 10,
+						call: Call::Logger(logger::Call::log(96, 100)),
+						maybe_periodic: None,
+						origin: root(),
+						_phantom: PhantomData::<u64>::default(),
+					}),
+					None,
+				];
+				frame_support::migration::put_storage_value(
+					b"Scheduler",
+					b"Agenda",
+					&k,
+					old,
+				);
+			}
+
+			Scheduler::on_runtime_upgrade();
+
+			assert_eq_uvec!(Agenda::<Test>::iter().collect::<Vec<_>>(), vec![
+				(
+					0,
+					vec![
+						Some(ScheduledV3Of::<Test> {
+							maybe_id: None,
+							priority: 10,
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
+							maybe_periodic: None,
+							origin: root(),
+							_phantom: PhantomData::<u64>::default(),
+						}),
+						None,
+					]
+				),
+				(
+					1,
+					vec![
+						Some(ScheduledV3Of::<Test> {
+							maybe_id: None,
+							priority: 11,
+							call: Call::Logger(logger::Call::log(96, 100)).into(),
+							maybe_periodic: None,
+							origin: root(),
+							_phantom: PhantomData::<u64>::default(),
+						}),
+						None,
+					]
+				),
+			]);
+
+			assert_eq!(StorageVersion::get(), Releases::V3);
+		});
+	}
+
+	#[test]
+	fn scheduling_with_preimages_works() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Preimage::note_preimage(Origin::root(), call.encode()));
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			assert!(Preimage::preimage_requested(&hash));
+			run_to_block(3);
+			assert!(logger::log().is_empty());
+			run_to_block(4);
+			// The preimage is no longer needed once the call is dispatched.
+			assert!(!Preimage::have_preimage(&hash));
+			assert!(!Preimage::preimage_requested(&hash));
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+		});
+	}
+
+	#[test]
+	fn scheduling_with_preimage_postpones_correctly() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::do_schedule_named(
+				b"test".to_vec(), DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			assert!(Preimage::preimage_requested(&hash));
+
+			run_to_block(4);
+			// #4 empty due to no preimage
+			assert!(logger::log().is_empty());
+			assert_eq!(Lookup::<Test>::get(b"test".to_vec()), Some((6, 0)));
+
+			// Register the preimage.
+			assert_ok!(Preimage::note_preimage(Origin::root(), call.encode()));
+
+			run_to_block(5);
+			// #5 empty since postponement is 2 blocks.
+			assert!(logger::log().is_empty());
+
+			run_to_block(6);
+			// #6 is good.
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+			assert!(!Preimage::have_preimage(&hash));
+			assert!(!Preimage::preimage_requested(&hash));
+			assert_eq!(Lookup::<Test>::get(b"test".to_vec()), None);
+
+			run_to_block(100);
+			assert_eq!(logger::log(), vec![(root(), 42u32)]);
+		});
+	}
+
+	#[test]
+	fn missing_preimage_without_postponement_aborts_task() {
+		new_test_ext().execute_with(|| {
+			NoPreimagePostponement::set(None);
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::do_schedule_named(
+				b"test".to_vec(), DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+
+			run_to_block(4);
+			assert!(logger::log().is_empty());
+			assert!(!Preimage::preimage_requested(&hash));
+			assert_eq!(Lookup::<Test>::get(b"test".to_vec()), None);
+			assert_eq!(
+				System::events().last().unwrap().event,
+				Event::scheduler(RawEvent::CallLookupFailed(
+					(4, 0),
+					Some(b"test".to_vec()),
+					LookupError::Unknown,
+				)),
+			);
+
+			run_to_block(100);
+			assert!(logger::log().is_empty());
+		});
+	}
+
+	#[test]
+	fn badly_formatted_preimage_aborts_task() {
+		new_test_ext().execute_with(|| {
+			let data = vec![1, 2, 3];
+			let hash = BlakeTwo256::hash(&data[..]);
+			assert_ok!(Preimage::note_preimage(Origin::root(), data));
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+
+			run_to_block(4);
+			assert!(!Preimage::have_preimage(&hash));
+			assert_eq!(
+				System::events().last().unwrap().event,
+				Event::scheduler(RawEvent::CallLookupFailed((4, 0), None, LookupError::BadFormat)),
+			);
+			assert!(Agenda::<Test>::iter().all(|(_, agenda)| agenda.is_empty()));
+		});
+	}
+
+	#[test]
+	fn cancel_hashed_call_clears_preimage_request() {
+		new_test_ext().execute_with(|| {
+			let call = Call::Logger(logger::Call::log(42, 1000));
+			let hash = <Test as frame_system::Config>::Hashing::hash_of(&call);
+			assert_ok!(Scheduler::do_schedule(
+				DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			assert_ok!(Scheduler::do_schedule_named(
+				1u32.encode(), DispatchTime::At(4), None, 127, root(), MaybeHashed::Hash(hash)
+			));
+			assert!(Preimage::preimage_requested(&hash));
+
+			// The preimage stays requested until both tasks are cancelled.
+			assert_ok!(Scheduler::do_cancel(None, (4, 0)));
+			assert!(Preimage::preimage_requested(&hash));
+			assert_ok!(Scheduler::do_cancel_named(None, 1u32.encode()));
+			assert!(!Preimage::preimage_requested(&hash));
+		});
+	}
 }
@@ -197,4 +197,6 @@ impl pallet_staking::Config for Test {
 	type OffchainSolutionWeightLimit = ();
 	type WeightInfo = ();
 	type ElectionProvider = MockElectionProvider;
+	type SortedListProvider = pallet_staking::UseNominatorsMap<Self>;
+	type OnStakerSlash = ();
 }
@@ -577,7 +577,7 @@ benchmarks! {
 			add_slashing_spans::<T>(&validators[index as usize], 10);
 		});
 	}: {
-		let voters = <Staking<T>>::get_npos_voters();
+		let voters = <Staking<T>>::get_npos_voters(None);
 		assert_eq!(voters.len() as u32, v + n);
 	}
 
@@ -310,14 +310,16 @@ use sp_runtime::{
 	},
 };
 use sp_staking::{
-	SessionIndex,
+	SessionIndex, OnStakerSlash, StakingInterface,
 	offence::{OnOffenceHandler, OffenceDetails, Offence, ReportOffence, OffenceError},
 };
 use frame_system::{
 	self as system, ensure_signed, ensure_root,
 	offchain::SendTransactionTypes,
 };
-use frame_election_provider_support::{ElectionProvider, VoteWeight, Supports, data_provider};
+use frame_election_provider_support::{
+	ElectionProvider, VoteWeight, Supports, data_provider, SortedListProvider, VoteWeightProvider,
+};
 pub use weights::WeightInfo;
 
 const STAKING_ID: LockIdentifier = *b"staking ";
@@ -337,7 +339,7 @@ macro_rules! log {
 pub const MAX_UNLOCKING_CHUNKS: usize = 32;
 
 /// Counter for the number of eras that have passed.
-pub type EraIndex = u32;
+pub use sp_staking::EraIndex;
 
 /// Counter for the number of "reward" points earned by a given validator.
 pub type RewardPoint = u32;
@@ -523,11 +525,13 @@ impl<AccountId, Balance> StakingLedger<AccountId, Balance> where
 	/// active funds. Returns the amount of funds actually slashed.
 	///
 	/// Slashes from `active` funds first, and then `unlocking`, starting with the
-	/// chunks that are closest to unlocking.
+	/// chunks that are closest to unlocking. For every era whose unlocking funds were touched by
+	/// the slash, the amount left to unlock in that era is recorded in `slashed_unlocking`.
 	fn slash(
 		&mut self,
 		mut value: Balance,
 		minimum_balance: Balance,
+		slashed_unlocking: &mut BTreeMap<EraIndex, Balance>,
 	) -> Balance {
 		let pre_total = self.total;
 		let total = &mut self.total;
@@ -558,7 +562,11 @@ impl<AccountId, Balance> StakingLedger<AccountId, Balance> where
 
 		let i = self.unlocking.iter_mut()
 			.map(|chunk| {
+				let pre_value = chunk.value;
 				slash_out_of(total, &mut chunk.value, &mut value);
+				if chunk.value != pre_value {
+					slashed_unlocking.insert(chunk.era, chunk.value);
+				}
 				chunk.value
 			})
 			.take_while(|value| value.is_zero()) // take all fully-consumed chunks out.
@@ -567,6 +575,13 @@ impl<AccountId, Balance> StakingLedger<AccountId, Balance> where
 		// kill all drained chunks.
 		let _ = self.unlocking.drain(..i);
 
+		// several chunks can unlock in the same era; report what is left of all of them.
+		for (era, remaining) in slashed_unlocking.iter_mut() {
+			*remaining = self.unlocking.iter()
+				.filter(|chunk| chunk.era == *era)
+				.fold(Zero::zero(), |acc: Balance, chunk| acc.saturating_add(chunk.value));
+		}
+
 		pre_total.saturating_sub(*total)
 	}
 }
@@ -785,6 +800,15 @@ pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 	/// their reward. This used to limit the i/o cost for the nominator payout.
 	type MaxNominatorRewardedPerValidator: Get<u32>;
 
+	/// Something that can provide a list of nominators in a somewhat sorted way, from which the
+	/// voters of the election are taken. `pallet-bags-list` is designed for this purpose; if the
+	/// sorting is not desired, [`UseNominatorsMap`] reads the `Nominators` map directly.
+	type SortedListProvider: SortedListProvider<Self::AccountId>;
+
+	/// A hook called when any staker is slashed. Mostly likely this can be a no-op unless
+	/// other pallets exist that are affected by slashing per-staker.
+	type OnStakerSlash: OnStakerSlash<Self::AccountId, BalanceOf<Self>>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -820,11 +844,12 @@ enum Releases {
 	V4_0_0,
 	V5_0_0, // blockable validators.
 	V6_0_0, // removal of all storage associated with offchain phragmen.
+	V7_0_0, // keep track of number of nominators / validators in map, sorted voter list.
 }
 
 impl Default for Releases {
 	fn default() -> Self {
-		Releases::V6_0_0
+		Releases::V7_0_0
 	}
 }
 
@@ -865,10 +890,19 @@ decl_storage! {
 		pub Validators get(fn validators):
 			map hasher(twox_64_concat) T::AccountId => ValidatorPrefs;
 
+		/// A tracker to keep count of the number of items in the `Validators` map.
+		pub CounterForValidators: u32;
+
 		/// The map from nominator stash key to the set of stash keys of all validators to nominate.
+		///
+		/// Note that the nominators are also kept, sorted by their vote weight, in
+		/// `T::SortedListProvider`, which is where the voters of an election are read from.
 		pub Nominators get(fn nominators):
 			map hasher(twox_64_concat) T::AccountId => Option<Nominations<T::AccountId>>;
 
+		/// A tracker to keep count of the number of items in the `Nominators` map.
+		pub CounterForNominators: u32;
+
 		/// The current era index.
 		///
 		/// This is the latest planned era, depending on how the Session pallet queues the validator
@@ -992,8 +1026,8 @@ decl_storage! {
 		/// True if network has been upgraded to this version.
 		/// Storage version of the pallet.
 		///
-		/// This is set to v6.0.0 for new networks.
-		StorageVersion build(|_: &GenesisConfig<T>| Releases::V6_0_0): Releases;
+		/// This is set to v7.0.0 for new networks.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V7_0_0): Releases;
 	}
 	add_extra_genesis {
 		config(stakers):
@@ -1073,6 +1107,49 @@ pub mod migrations {
 			T::DbWeight::get().writes(6 + 1)
 		}
 	}
+
+	pub mod v7 {
+		use super::*;
+
+		/// check to execute prior to migration.
+		pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+			assert!(CounterForValidators::get().is_zero(), "CounterForValidators already set.");
+			assert!(CounterForNominators::get().is_zero(), "CounterForNominators already set.");
+			assert!(
+				T::SortedListProvider::count().is_zero(),
+				"voter list already exists.",
+			);
+			assert!(StorageVersion::get() == Releases::V6_0_0, "must upgrade linearly");
+			Ok(())
+		}
+
+		/// Migrate storage to v7: initialize the nominator and validator counters and populate
+		/// `T::SortedListProvider` with all the existing nominators.
+		pub fn migrate<T: Config>() -> Weight {
+			log!(info, "Migrating staking to Releases::V7_0_0");
+
+			let validator_count = Validators::<T>::iter().count() as u32;
+			let nominator_count = Nominators::<T>::iter().count() as u32;
+			CounterForValidators::put(validator_count);
+			CounterForNominators::put(nominator_count);
+
+			let migrated = T::SortedListProvider::regenerate(
+				Nominators::<T>::iter().map(|(id, _)| id),
+				Module::<T>::slashable_balance_of_fn(),
+			);
+			debug_assert_eq!(T::SortedListProvider::sanity_check(), Ok(()));
+
+			StorageVersion::put(Releases::V7_0_0);
+			log!(
+				info,
+				"Done. {} validators, {} nominators, {} voters put in the sorted list.",
+				validator_count,
+				nominator_count,
+				migrated,
+			);
+			T::BlockWeights::get().max_block
+		}
+	}
 }
 
 decl_event!(
@@ -1181,11 +1258,14 @@ decl_module! {
 		fn deposit_event() = default;
 
 		fn on_runtime_upgrade() -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
 			if StorageVersion::get() == Releases::V5_0_0 {
-				migrations::v6::migrate::<T>()
-			} else {
-				T::DbWeight::get().reads(1)
+				weight = weight.saturating_add(migrations::v6::migrate::<T>());
+			}
+			if StorageVersion::get() == Releases::V6_0_0 {
+				weight = weight.saturating_add(migrations::v7::migrate::<T>());
 			}
+			weight
 		}
 
 		fn on_initialize(_now: T::BlockNumber) -> Weight {
@@ -1322,8 +1402,12 @@ decl_module! {
 				// last check: the new active amount of ledger must be more than ED.
 				ensure!(ledger.active >= T::Currency::minimum_balance(), Error::<T>::InsufficientValue);
 
-				Self::deposit_event(RawEvent::Bonded(stash, extra));
+				Self::deposit_event(RawEvent::Bonded(stash.clone(), extra));
 				Self::update_ledger(&controller, &ledger);
+				// update this staker in the sorted list, if they exist in it.
+				if T::SortedListProvider::contains(&stash) {
+					T::SortedListProvider::on_update(&stash, Self::weight_of(&ledger.stash));
+				}
 			}
 		}
 
@@ -1383,6 +1467,12 @@ decl_module! {
 				let era = Self::current_era().unwrap_or(0) + T::BondingDuration::get();
 				ledger.unlocking.push(UnlockChunk { value, era });
 				Self::update_ledger(&controller, &ledger);
+
+				// update this staker in the sorted list, if they exist in it.
+				if T::SortedListProvider::contains(&ledger.stash) {
+					T::SortedListProvider::on_update(&ledger.stash, Self::weight_of(&ledger.stash));
+				}
+
 				Self::deposit_event(RawEvent::Unbonded(ledger.stash, value));
 			}
 		}
@@ -1477,8 +1567,8 @@ decl_module! {
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
 			let stash = &ledger.stash;
-			<Nominators<T>>::remove(stash);
-			<Validators<T>>::insert(stash, prefs);
+			Self::do_remove_nominator(stash);
+			Self::do_add_validator(stash, prefs);
 		}
 
 		/// Declare the desire to nominate `targets` for the origin controller.
@@ -1526,8 +1616,8 @@ decl_module! {
 				suppressed: false,
 			};
 
-			<Validators<T>>::remove(stash);
-			<Nominators<T>>::insert(stash, &nominations);
+			Self::do_remove_validator(stash);
+			Self::do_add_nominator(stash, nominations);
 		}
 
 		/// Declare no desire to either validate or nominate.
@@ -1821,6 +1911,11 @@ decl_module! {
 			ensure!(ledger.active >= T::Currency::minimum_balance(), Error::<T>::InsufficientValue);
 
 			Self::update_ledger(&controller, &ledger);
+			// update this staker in the sorted list, if they exist in it.
+			if T::SortedListProvider::contains(&ledger.stash) {
+				T::SortedListProvider::on_update(&ledger.stash, Self::weight_of(&ledger.stash));
+			}
+
 			Ok(Some(
 				35 * WEIGHT_PER_MICROS
 				+ 50 * WEIGHT_PER_NANOS * (ledger.unlocking.len() as Weight)
@@ -1955,6 +2050,13 @@ impl<T: Config> Module<T> {
 		})
 	}
 
+	/// The vote weight of `who` in the election, i.e. its slashable balance converted with
+	/// `T::CurrencyToVote`.
+	pub fn weight_of(who: &T::AccountId) -> VoteWeight {
+		let issuance = T::Currency::total_issuance();
+		Self::slashable_balance_of_vote_weight(who, issuance)
+	}
+
 	fn do_payout_stakers(validator_stash: T::AccountId, era: EraIndex) -> DispatchResultWithPostInfo {
 		// Validate input data
 		let current_era = CurrentEra::get().ok_or(
@@ -2085,8 +2187,8 @@ impl<T: Config> Module<T> {
 
 	/// Chill a stash account.
 	fn chill_stash(stash: &T::AccountId) {
-		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_validator(stash);
+		Self::do_remove_nominator(stash);
 	}
 
 	/// Actually make a payment to a staker. This uses the currency's reward function
@@ -2403,8 +2505,8 @@ impl<T: Config> Module<T> {
 		<Ledger<T>>::remove(&controller);
 
 		<Payee<T>>::remove(stash);
-		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_validator(stash);
+		Self::do_remove_nominator(stash);
 
 		system::Pallet::<T>::dec_consumers(stash);
 
@@ -2484,44 +2586,144 @@ impl<T: Config> Module<T> {
 		SlashRewardFraction::put(fraction);
 	}
 
+	/// Add a nominator to the `Nominators` storage map and to `T::SortedListProvider`, keeping
+	/// `CounterForNominators` up to date.
+	///
+	/// If the nominator already exists, their nominations are updated.
+	///
+	/// NOTE: any write to `Nominators` should go through this function, or the counter and the
+	/// sorted list will go out of sync.
+	pub fn do_add_nominator(who: &T::AccountId, nominations: Nominations<T::AccountId>) {
+		if !Nominators::<T>::contains_key(who) {
+			CounterForNominators::mutate(|x| *x = x.saturating_add(1));
+			// this should never fail, since the counter and the list are kept in sync.
+			if T::SortedListProvider::on_insert(who.clone(), Self::weight_of(who)).is_err() {
+				log!(warn, "attempt to insert duplicate nominator ({:?})", who);
+				debug_assert!(false, "attempt to insert duplicate nominator");
+			}
+			debug_assert_eq!(T::SortedListProvider::sanity_check(), Ok(()));
+		}
+		Nominators::<T>::insert(who, nominations);
+	}
+
+	/// Remove a nominator from the `Nominators` storage map and from `T::SortedListProvider`,
+	/// keeping `CounterForNominators` up to date.
+	///
+	/// Returns `true` if `who` was a nominator.
+	pub fn do_remove_nominator(who: &T::AccountId) -> bool {
+		if Nominators::<T>::contains_key(who) {
+			Nominators::<T>::remove(who);
+			CounterForNominators::mutate(|x| *x = x.saturating_sub(1));
+			T::SortedListProvider::on_remove(who);
+			debug_assert_eq!(T::SortedListProvider::sanity_check(), Ok(()));
+			debug_assert_eq!(CounterForNominators::get(), T::SortedListProvider::count());
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Add a validator to the `Validators` storage map, keeping `CounterForValidators` up to
+	/// date.
+	///
+	/// If the validator already exists, their preferences are updated.
+	pub fn do_add_validator(who: &T::AccountId, prefs: ValidatorPrefs) {
+		if !Validators::<T>::contains_key(who) {
+			CounterForValidators::mutate(|x| *x = x.saturating_add(1));
+		}
+		Validators::<T>::insert(who, prefs);
+	}
+
+	/// Remove a validator from the `Validators` storage map, keeping `CounterForValidators` up to
+	/// date.
+	///
+	/// Returns `true` if `who` was a validator.
+	pub fn do_remove_validator(who: &T::AccountId) -> bool {
+		if Validators::<T>::contains_key(who) {
+			Validators::<T>::remove(who);
+			CounterForValidators::mutate(|x| *x = x.saturating_sub(1));
+			true
+		} else {
+			false
+		}
+	}
+
 	/// Get all of the voters that are eligible for the npos election.
 	///
-	/// This will use all on-chain nominators, and all the validators will inject a self vote.
+	/// `maybe_max_len` can impose a cap on the number of voters returned; first all the validators
+	/// are included in no particular order, then the remainder is taken from the nominators, in
+	/// the order returned by [`Config::SortedListProvider`].
+	///
+	/// This will use nominators, and all the validators will inject a self vote.
 	///
 	/// ### Slashing
 	///
 	/// All nominations that have been submitted before the last non-zero slash of the validator are
-	/// auto-chilled.
-	///
-	/// Note that this is VERY expensive. Use with care.
-	pub fn get_npos_voters() -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
-		let weight_of = Self::slashable_balance_of_fn();
-		let mut all_voters = Vec::new();
-
-		for (validator, _) in <Validators<T>>::iter() {
-			// append self vote
-			let self_vote = (validator.clone(), weight_of(&validator), vec![validator.clone()]);
+	/// auto-chilled, but still count towards the limit imposed by `maybe_max_len`.
+	pub fn get_npos_voters(
+		maybe_max_len: Option<usize>,
+	) -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
+		let max_allowed_len = {
+			let nominator_count = CounterForNominators::get() as usize;
+			let validator_count = CounterForValidators::get() as usize;
+			let all_voter_count = validator_count.saturating_add(nominator_count);
+			maybe_max_len.unwrap_or(all_voter_count).min(all_voter_count)
+		};
+
+		let mut all_voters = Vec::<_>::with_capacity(max_allowed_len);
+
+		// first, grab all validators in no particular order, capped by the maximum allowed length.
+		let mut validators_taken = 0u32;
+		for (validator, _) in <Validators<T>>::iter().take(max_allowed_len) {
+			// append self vote.
+			let self_vote =
+				(validator.clone(), Self::weight_of(&validator), vec![validator.clone()]);
 			all_voters.push(self_vote);
+			validators_taken = validators_taken.saturating_add(1);
 		}
 
+		// .. and grab whatever we have left from nominators.
+		let nominators_quota = (max_allowed_len as u32).saturating_sub(validators_taken);
 		// collect all slashing spans into a BTreeMap for further queries.
 		let slashing_spans = <SlashingSpans<T>>::iter().collect::<BTreeMap<_, _>>();
 
-		for (nominator, nominations) in <Nominators<T>>::iter() {
-			let Nominations { submitted_in, mut targets, suppressed: _ } = nominations;
-
-			// Filter out nomination targets which were nominated before the most recent
-			// slashing span.
-			targets.retain(|stash| {
-				slashing_spans
-					.get(stash)
-					.map_or(true, |spans| submitted_in >= spans.last_nonzero_slash())
-			});
+		// track the count of nominators added to `all_voters`.
+		let mut nominators_taken = 0u32;
+		for nominator in T::SortedListProvider::iter() {
+			if nominators_taken >= nominators_quota {
+				break
+			}
 
-			let vote_weight = weight_of(&nominator);
-			all_voters.push((nominator, vote_weight, targets))
+			if let Some(Nominations { submitted_in, mut targets, suppressed: _ }) =
+				<Nominators<T>>::get(&nominator)
+			{
+				// Filter out nomination targets which were nominated before the most recent
+				// slashing span.
+				targets.retain(|stash| {
+					slashing_spans
+						.get(stash)
+						.map_or(true, |spans| submitted_in >= spans.last_nonzero_slash())
+				});
+				let vote_weight = Self::weight_of(&nominator);
+				all_voters.push((nominator, vote_weight, targets));
+				nominators_taken = nominators_taken.saturating_add(1);
+			} else {
+				// this can only happen if there is a bug in the sorted list logic and the state of
+				// the two pallets is no longer compatible.
+				log!(warn, "DEFENSIVE: invalid item in `SortedListProvider`: {:?}", nominator)
+			}
 		}
 
+		// all_voters should have not re-allocated.
+		debug_assert!(all_voters.capacity() == max_allowed_len);
+
+		log!(
+			debug,
+			"generated {} npos voters, {} from validators and {} nominators",
+			all_voters.len(),
+			validators_taken,
+			nominators_taken,
+		);
 		all_voters
 	}
 
@@ -2541,24 +2743,26 @@ impl<T: Config> frame_election_provider_support::ElectionDataProvider<T::Account
 	fn voters(
 		maybe_max_len: Option<usize>,
 	) -> data_provider::Result<(Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)>, Weight)> {
-		// NOTE: reading these counts already needs to iterate a lot of storage keys, but they get
-		// cached. This is okay for the case of `Ok(_)`, but bad for `Err(_)`, as the trait does not
-		// report weight in failures.
-		let nominator_count = <Nominators<T>>::iter().count();
-		let validator_count = <Validators<T>>::iter().count();
-		let voter_count = nominator_count.saturating_add(validator_count);
-
-		if maybe_max_len.map_or(false, |max_len| voter_count > max_len) {
+		// NOTE: if `maybe_max_len` is lower than the number of voters, only the top voters are
+		// returned: all the validators first, then the nominators in the order of
+		// `T::SortedListProvider`. The validators are not sorted, so they must all fit.
+		let validator_count = CounterForValidators::get() as usize;
+		if maybe_max_len.map_or(false, |max_len| validator_count > max_len) {
 			return Err("Voter snapshot too big");
 		}
 
+		let voters = Self::get_npos_voters(maybe_max_len);
+		debug_assert!(maybe_max_len.map_or(true, |max| voters.len() <= max));
+
+		let validators_taken = validator_count.min(voters.len());
+		let nominators_taken = voters.len().saturating_sub(validators_taken);
 		let slashing_span_count = <SlashingSpans<T>>::iter().count();
 		let weight = T::WeightInfo::get_npos_voters(
-			nominator_count as u32,
-			validator_count as u32,
+			validators_taken as u32,
+			nominators_taken as u32,
 			slashing_span_count as u32,
 		);
-		Ok((Self::get_npos_voters(), weight))
+		Ok((voters, weight))
 	}
 
 	fn targets(maybe_max_len: Option<usize>) -> data_provider::Result<(Vec<T::AccountId>, Weight)> {
@@ -2621,8 +2825,8 @@ impl<T: Config> frame_election_provider_support::ElectionDataProvider<T::Account
 					claimed_rewards: vec![],
 				},
 			);
-			<Validators<T>>::insert(
-				v,
+			Self::do_add_validator(
+				&v,
 				ValidatorPrefs { commission: Perbill::zero(), blocked: false },
 			);
 		});
@@ -2642,14 +2846,165 @@ impl<T: Config> frame_election_provider_support::ElectionDataProvider<T::Account
 					claimed_rewards: vec![],
 				},
 			);
-			<Nominators<T>>::insert(
-				v,
+			Self::do_add_nominator(
+				&v,
 				Nominations { targets: t, submitted_in: 0, suppressed: false },
 			);
 		});
 	}
 }
 
+impl<T: Config> VoteWeightProvider<T::AccountId> for Module<T> {
+	fn vote_weight(who: &T::AccountId) -> VoteWeight {
+		Self::weight_of(who)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_vote_weight_of(who: &T::AccountId, weight: VoteWeight) {
+		// this will clearly result in an inconsistent state, but it should not matter for a
+		// benchmark.
+		use sp_std::convert::TryInto;
+		let active: BalanceOf<T> = weight.try_into().map_err(|_| ()).unwrap();
+		let ledger = match Self::ledger(who) {
+			Some(mut ledger) => {
+				ledger.active = active;
+				ledger
+			},
+			None => StakingLedger {
+				stash: who.clone(),
+				active,
+				total: active,
+				unlocking: vec![],
+				claimed_rewards: vec![],
+			},
+		};
+		<Ledger<T>>::insert(who, ledger);
+		<Bonded<T>>::insert(who, who);
+
+		// also, we play a trick to make sure that a issuance based-`CurrencyToVote` behaves well:
+		// This will make sure that total issuance is zero, thus the currency to vote will be a 1-1
+		// conversion.
+		let imbalance = T::Currency::burn(T::Currency::total_issuance());
+		// kinda ugly, but gets the job done. The fact that this works here is a HUGE exception.
+		// Don't try this pattern in other places.
+		sp_std::mem::forget(imbalance);
+	}
+}
+
+impl<T: Config> StakingInterface for Module<T> {
+	type AccountId = T::AccountId;
+	type Balance = BalanceOf<T>;
+
+	fn minimum_bond() -> Self::Balance {
+		// staking rejects any bond that is considered to be dust.
+		T::Currency::minimum_balance()
+	}
+
+	fn bonding_duration() -> EraIndex {
+		T::BondingDuration::get()
+	}
+
+	fn current_era() -> EraIndex {
+		Self::current_era().unwrap_or(Zero::zero())
+	}
+
+	fn active_stake(stash: &Self::AccountId) -> Option<Self::Balance> {
+		Self::bonded(stash).and_then(|c| Self::ledger(c)).map(|l| l.active)
+	}
+
+	fn total_stake(stash: &Self::AccountId) -> Option<Self::Balance> {
+		Self::bonded(stash).and_then(|c| Self::ledger(c)).map(|l| l.total)
+	}
+
+	fn bond(
+		stash: Self::AccountId,
+		controller: Self::AccountId,
+		value: Self::Balance,
+		payee: Self::AccountId,
+	) -> DispatchResult {
+		Self::bond(
+			system::RawOrigin::Signed(stash).into(),
+			T::Lookup::unlookup(controller),
+			value,
+			RewardDestination::Account(payee),
+		)
+	}
+
+	fn nominate(stash: Self::AccountId, targets: Vec<Self::AccountId>) -> DispatchResult {
+		let controller = Self::bonded(&stash).ok_or(Error::<T>::NotStash)?;
+		let targets = targets.into_iter().map(T::Lookup::unlookup).collect::<Vec<_>>();
+		Self::nominate(system::RawOrigin::Signed(controller).into(), targets)
+	}
+
+	fn chill(stash: Self::AccountId) -> DispatchResult {
+		let controller = Self::bonded(&stash).ok_or(Error::<T>::NotStash)?;
+		Self::chill(system::RawOrigin::Signed(controller).into())
+	}
+
+	fn bond_extra(stash: Self::AccountId, extra: Self::Balance) -> DispatchResult {
+		Self::bond_extra(system::RawOrigin::Signed(stash).into(), extra)
+	}
+
+	fn unbond(stash: Self::AccountId, value: Self::Balance) -> DispatchResult {
+		let controller = Self::bonded(&stash).ok_or(Error::<T>::NotStash)?;
+		Self::unbond(system::RawOrigin::Signed(controller).into(), value)
+	}
+
+	fn withdraw_unbonded(
+		stash: Self::AccountId,
+		num_slashing_spans: u32,
+	) -> Result<bool, DispatchError> {
+		let controller = Self::bonded(&stash).ok_or(Error::<T>::NotStash)?;
+		let origin = system::RawOrigin::Signed(controller.clone()).into();
+		Self::withdraw_unbonded(origin, num_slashing_spans)
+			.map(|_| !<Ledger<T>>::contains_key(&controller))
+			.map_err(|with_post| with_post.error)
+	}
+}
+
+/// A simple voter list implementation that does not require any additional pallets. Note, this
+/// does not provide nominators in sorted order. If you desire nominators in a sorted order take
+/// a look at `pallet-bags-list`.
+pub struct UseNominatorsMap<T>(sp_std::marker::PhantomData<T>);
+impl<T: Config> SortedListProvider<T::AccountId> for UseNominatorsMap<T> {
+	type Error = ();
+
+	/// Returns iterator over voter list, which can have `take` called on it.
+	fn iter() -> Box<dyn Iterator<Item = T::AccountId>> {
+		Box::new(Nominators::<T>::iter().map(|(n, _)| n))
+	}
+	fn count() -> u32 {
+		CounterForNominators::get()
+	}
+	fn contains(id: &T::AccountId) -> bool {
+		Nominators::<T>::contains_key(id)
+	}
+	fn on_insert(_: T::AccountId, _weight: VoteWeight) -> Result<(), Self::Error> {
+		// nothing to do on insert.
+		Ok(())
+	}
+	fn on_update(_: &T::AccountId, _weight: VoteWeight) {
+		// nothing to do on update.
+	}
+	fn on_remove(_: &T::AccountId) {
+		// nothing to do on remove.
+	}
+	fn regenerate(
+		_: impl IntoIterator<Item = T::AccountId>,
+		_: Box<dyn Fn(&T::AccountId) -> VoteWeight>,
+	) -> u32 {
+		// nothing to do upon regenerate.
+		0
+	}
+	fn sanity_check() -> Result<(), &'static str> {
+		Ok(())
+	}
+	fn clear() {
+		Nominators::<T>::remove_all();
+		CounterForNominators::kill();
+	}
+}
+
 /// In this implementation `new_session(session)` must be called before `end_session(session-1)`
 /// i.e. the new session must be planned before the ending of the previous session.
 ///
@@ -30,7 +30,12 @@ const SEED: u32 = 0;
 /// This function removes all validators and nominators from storage.
 pub fn clear_validators_and_nominators<T: Config>() {
 	Validators::<T>::remove_all();
+	CounterForValidators::kill();
+
+	// whenever we touch nominators counter we should update `T::SortedListProvider` as well.
 	Nominators::<T>::remove_all();
+	CounterForNominators::kill();
+	T::SortedListProvider::clear();
 }
 
 /// Grab a funded user.
@@ -3931,9 +3931,60 @@ mod election_data_provider {
 		})
 	}
 
+	#[test]
+	fn respects_snapshot_len_limits() {
+		ExtBuilder::default().validator_pool(true).build_and_execute(|| {
+			// sum of all nominators who'd be voters (1), plus the self-votes (4).
+			assert_eq!(CounterForNominators::get() + CounterForValidators::get(), 5);
+
+			// if limits is less, only the validators are taken..
+			assert_eq!(Staking::voters(Some(4)).unwrap().0.len(), 4);
+
+			// if limit is equal..
+			assert_eq!(Staking::voters(Some(5)).unwrap().0.len(), 5);
+
+			// if limit is more.
+			assert_eq!(Staking::voters(Some(55)).unwrap().0.len(), 5);
+
+			// if target limit is more..
+			assert_eq!(Staking::targets(Some(6)).unwrap().0.len(), 4);
+			assert_eq!(Staking::targets(Some(4)).unwrap().0.len(), 4);
+
+			// if target limit is less, then we return an error.
+			assert_eq!(Staking::targets(Some(1)).unwrap_err(), "Target snapshot too big");
+		});
+	}
+
+	#[test]
+	fn voters_are_taken_from_the_sorted_list() {
+		ExtBuilder::default().build_and_execute(|| {
+			// given 3 validators and 101 nominating with 500, add a heavier and a lighter nominator.
+			bond_nominator(61, 60, 2_000, vec![11]);
+			bond_nominator(71, 70, 50, vec![21]);
+			assert_eq!(CounterForValidators::get(), 3);
+			assert_eq!(CounterForNominators::get(), 3);
+
+			// then all the validators come first, followed by the heaviest nominators.
+			let voters = Staking::voters(Some(5)).unwrap().0;
+			assert_eq!(voters.len(), 5);
+			assert_eq!(
+				voters.iter().skip(3).map(|(who, _, _)| *who).collect::<Vec<_>>(),
+				vec![61, 101],
+			);
+
+			// and without a limit everyone is a voter.
+			let voters = Staking::voters(None).unwrap().0;
+			assert_eq!(
+				voters.iter().skip(3).map(|(who, weight, _)| (*who, *weight)).collect::<Vec<_>>(),
+				vec![(61, 2_000), (101, 500), (71, 50)],
+			);
+		});
+	}
+
 	#[test]
 	fn respects_len_limits() {
-		ExtBuilder::default().build().execute_with(|| {
+		ExtBuilder::default().build_and_execute(|| {
+			// the limit can't fit the self votes of all validators.
 			assert_eq!(Staking::voters(Some(1)).unwrap_err(), "Voter snapshot too big");
 			assert_eq!(Staking::targets(Some(1)).unwrap_err(), "Target snapshot too big");
 		});
@@ -3973,3 +4024,118 @@ mod election_data_provider {
 		})
 	}
 }
+
+mod sorted_list_provider {
+	use super::*;
+	use frame_election_provider_support::SortedListProvider;
+
+	#[test]
+	fn re_nominate_does_not_change_counters_or_list() {
+		ExtBuilder::default().nominate(true).build_and_execute(|| {
+			// given
+			let pre_insert_nominator_count = Nominators::<Test>::iter().count() as u32;
+			assert_eq!(CounterForNominators::get(), pre_insert_nominator_count);
+			assert!(Nominators::<Test>::contains_key(101));
+			assert_eq!(<Test as Config>::SortedListProvider::iter().collect::<Vec<_>>(), vec![101]);
+
+			// when account 101 renominates
+			assert_ok!(Staking::nominate(Origin::signed(100), vec![41]));
+
+			// then counts don't change
+			assert_eq!(CounterForNominators::get(), pre_insert_nominator_count);
+			assert_eq!(Nominators::<Test>::iter().count() as u32, pre_insert_nominator_count);
+			// and the list is the same
+			assert_eq!(<Test as Config>::SortedListProvider::iter().collect::<Vec<_>>(), vec![101]);
+		});
+	}
+
+	#[test]
+	fn bonding_changes_update_the_list() {
+		ExtBuilder::default().build_and_execute(|| {
+			// given 101 nominating with 500, in the bag of 1_000.
+			assert!(BagsList::list_bags_get(1_000).is_some());
+
+			// when bonding extra
+			assert_ok!(Staking::bond_extra(Origin::signed(101), 1_000));
+
+			// then it moves to the bag of 2_000.
+			assert!(BagsList::list_bags_get(1_000).is_none());
+			assert!(BagsList::list_bags_get(2_000).is_some());
+
+			// when unbonding
+			assert_ok!(Staking::unbond(Origin::signed(100), 1_490));
+
+			// then it moves to the bag of 10.
+			assert!(BagsList::list_bags_get(2_000).is_none());
+			assert!(BagsList::list_bags_get(10).is_some());
+
+			// when rebonding
+			assert_ok!(Staking::rebond(Origin::signed(100), 990));
+
+			// then it moves back to the bag of 1_000.
+			assert!(BagsList::list_bags_get(10).is_none());
+			assert!(BagsList::list_bags_get(1_000).is_some());
+			assert_eq!(<Test as Config>::SortedListProvider::count(), 1);
+		});
+	}
+
+	#[test]
+	fn chill_and_validate_remove_from_the_list() {
+		ExtBuilder::default().build_and_execute(|| {
+			// given
+			bond_nominator(61, 60, 2_000, vec![11]);
+			assert_eq!(
+				<Test as Config>::SortedListProvider::iter().collect::<Vec<_>>(),
+				vec![61, 101],
+			);
+
+			// when 101 becomes a validator
+			assert_ok!(Staking::validate(Origin::signed(100), ValidatorPrefs::default()));
+
+			// then it is no longer in the list.
+			assert_eq!(<Test as Config>::SortedListProvider::iter().collect::<Vec<_>>(), vec![61]);
+			assert_eq!(CounterForNominators::get(), 1);
+			assert_eq!(CounterForValidators::get(), 4);
+
+			// when 61 chills
+			assert_ok!(Staking::chill(Origin::signed(60)));
+
+			// then the list is empty.
+			assert_eq!(<Test as Config>::SortedListProvider::count(), 0);
+			assert_eq!(CounterForNominators::get(), 0);
+		});
+	}
+}
+
+#[test]
+fn ledger_slash_reports_remaining_unlocking_per_era() {
+	let mut ledger = StakingLedger::<AccountId, Balance> {
+		stash: 123,
+		total: 100,
+		active: 10,
+		unlocking: vec![
+			UnlockChunk { value: 40, era: 4 },
+			UnlockChunk { value: 20, era: 5 },
+			UnlockChunk { value: 10, era: 5 },
+			UnlockChunk { value: 20, era: 6 },
+		],
+		claimed_rewards: vec![],
+	};
+	let mut slashed_unlocking = BTreeMap::new();
+
+	// slashes all of `active`, the first chunk and part of the second chunk.
+	assert_eq!(ledger.slash(55, 1, &mut slashed_unlocking), 55);
+
+	assert_eq!(ledger.total, 45);
+	assert_eq!(ledger.active, 0);
+	assert_eq!(
+		ledger.unlocking,
+		vec![
+			UnlockChunk { value: 15, era: 5 },
+			UnlockChunk { value: 10, era: 5 },
+			UnlockChunk { value: 20, era: 6 },
+		],
+	);
+	// era 4 is fully slashed, and era 5 reports the sum of both of its chunks.
+	assert_eq!(slashed_unlocking, vec![(4, 0), (5, 25)].into_iter().collect::<BTreeMap<_, _>>());
+}
@@ -88,6 +88,7 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 			#frame_support::PartialEqNoBound,
 			#frame_support::codec::Encode,
 			#frame_support::codec::Decode,
+			#frame_support::TypeInfo,
 		)]
 		#[allow(non_camel_case_types)]
 		pub enum #call_ident<#type_decl_bounded_gen> #where_clause {
@@ -211,6 +212,16 @@ pub fn expand_call(def: &mut Def) -> proc_macro2::TokenStream {
 					},
 				)* ]
 			}
+
+			#[doc(hidden)]
+			#[allow(dead_code)]
+			pub fn calls_metadata_v14(
+				registry: &mut #frame_support::metadata::Registry,
+			) -> #frame_support::metadata::CallsMetadataV14 {
+				#frame_support::metadata::CallsMetadataV14 {
+					ty: registry.register_type::<#call_ident<#type_use_gen>>(),
+				}
+			}
 		}
 	)
 }
@@ -71,7 +71,10 @@ pub fn expand_constants(def: &mut Def) -> proc_macro2::TokenStream {
 		}
 	});
 
-	let consts = config_consts.chain(extra_consts)
+	let consts = config_consts.chain(extra_consts).collect::<Vec<_>>();
+	let consts_type = consts.iter().map(|const_| &const_.type_);
+
+	let consts = consts.iter()
 		.map(|const_| {
 			let const_type = &const_.type_;
 			let const_type_str = clean_type_string(&const_type.to_token_stream().to_string());
@@ -133,6 +136,25 @@ pub fn expand_constants(def: &mut Def) -> proc_macro2::TokenStream {
 			{
 				&[ #( #consts ),* ]
 			}
+
+			#[doc(hidden)]
+			#[allow(unused_variables)]
+			pub fn module_constants_metadata_v14(
+				registry: &mut #frame_support::metadata::Registry,
+			) -> #frame_support::sp_std::vec::Vec<
+				#frame_support::metadata::ModuleConstantMetadataV14
+			> {
+				let types: #frame_support::sp_std::vec::Vec<#frame_support::metadata::TypeIndex> =
+					#frame_support::sp_std::vec![
+						#( registry.register(#frame_support::metadata::meta_type!(#consts_type)) ),*
+					];
+				Self::module_constants_metadata().iter()
+					.zip(types)
+					.map(|(constant, ty)| {
+						#frame_support::metadata::ModuleConstantMetadataV14::with_type(constant, ty)
+					})
+					.collect()
+			}
 		}
 	)
 }
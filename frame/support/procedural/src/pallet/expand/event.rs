@@ -99,6 +99,7 @@ pub fn expand_event(def: &mut Def) -> proc_macro2::TokenStream {
 			#frame_support::RuntimeDebugNoBound,
 			#frame_support::codec::Encode,
 			#frame_support::codec::Decode,
+			#frame_support::TypeInfo,
 		)]
 	));
 
@@ -142,6 +143,16 @@ pub fn expand_event(def: &mut Def) -> proc_macro2::TokenStream {
 			pub fn metadata() -> &'static [#frame_support::event::EventMetadata] {
 				&[ #( #metadata )* ]
 			}
+
+			#[allow(dead_code)]
+			#[doc(hidden)]
+			pub fn metadata_v14(
+				registry: &mut #frame_support::metadata::Registry,
+			) -> #frame_support::metadata::EventsMetadataV14 {
+				#frame_support::metadata::EventsMetadataV14 {
+					ty: registry.register_type::<#event_ident<#event_use_gen>>(),
+				}
+			}
 		}
 	)
 }
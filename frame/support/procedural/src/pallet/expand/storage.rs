@@ -25,8 +25,25 @@ fn prefix_ident(storage_ident: &syn::Ident) -> syn::Ident {
 	syn::Ident::new(&format!("_GeneratedPrefixForStorage{}", storage_ident), storage_ident.span())
 }
 
+/// Generate the counter_prefix_ident related to the storage.
+/// counter_prefix_ident is used for the prefix struct to be given to counted storage map.
+fn counter_prefix_ident(storage_ident: &syn::Ident) -> syn::Ident {
+	syn::Ident::new(
+		&format!("_GeneratedCounterPrefixForStorage{}", storage_ident),
+		storage_ident.span(),
+	)
+}
+
+/// Generate the counter_prefix related to the storage.
+/// counter_prefix is used by counted storage map.
+fn counter_prefix(prefix: &str) -> String {
+	format!("CounterFor{}", prefix)
+}
+
 /// * generate StoragePrefix structs (e.g. for a storage `MyStorage` a struct with the name
 ///   `_GeneratedPrefixForStorage$NameOfStorage` is generated) and implements StorageInstance trait.
+/// * for counted storage maps, also generate the counter prefix struct (named
+///   `_GeneratedCounterPrefixForStorage$NameOfStorage`) and implement CountedStorageMapInstance.
 /// * replace the first generic `_` by the generated prefix structure
 /// * generate metadatas
 pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
@@ -87,9 +104,15 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 				Metadata::Map { .. } => quote::quote_spanned!(storage.attr_span =>
 					#frame_support::storage::types::StorageMapMetadata
 				),
+				Metadata::CountedMap { .. } => quote::quote_spanned!(storage.attr_span =>
+					#frame_support::storage::types::CountedStorageMapMetadata
+				),
 				Metadata::DoubleMap { .. } => quote::quote_spanned!(storage.attr_span =>
 					#frame_support::storage::types::StorageDoubleMapMetadata
 				),
+				Metadata::NMap { .. } => quote::quote_spanned!(storage.attr_span =>
+					#frame_support::storage::types::StorageNMapMetadata
+				),
 			};
 
 			let ty = match &storage.metadata {
@@ -101,7 +124,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						)
 					)
 				},
-				Metadata::Map { key, value } => {
+				Metadata::Map { key, value } | Metadata::CountedMap { key, value } => {
 					let value = clean_type_string(&quote::quote!(#value).to_string());
 					let key = clean_type_string(&quote::quote!(#key).to_string());
 					quote::quote_spanned!(storage.attr_span =>
@@ -126,10 +149,28 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 							value: #frame_support::metadata::DecodeDifferent::Encode(#value),
 						}
 					)
+				},
+				Metadata::NMap { keys, value, .. } => {
+					let keys = keys
+						.iter()
+						.map(|key| clean_type_string(&quote::quote!(#key).to_string()))
+						.collect::<Vec<_>>();
+					let value = clean_type_string(&quote::quote!(#value).to_string());
+					quote::quote_spanned!(storage.attr_span =>
+						#frame_support::metadata::StorageEntryType::NMap {
+							keys: #frame_support::metadata::DecodeDifferent::Encode(&[
+								#( #keys, )*
+							]),
+							hashers: #frame_support::metadata::DecodeDifferent::Encode(
+								<#full_ident as #metadata_trait>::HASHERS,
+							),
+							value: #frame_support::metadata::DecodeDifferent::Encode(#value),
+						}
+					)
 				}
 			};
 
-			quote::quote_spanned!(storage.attr_span =>
+			let entry = quote::quote_spanned!(storage.attr_span =>
 				#(#cfg_attrs)* #frame_support::metadata::StorageEntryMetadata {
 					name: #frame_support::metadata::DecodeDifferent::Encode(
 						<#full_ident as #metadata_trait>::NAME
@@ -143,7 +184,129 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						#( #docs, )*
 					]),
 				}
-			)
+			);
+
+			if let Metadata::CountedMap { .. } = storage.metadata {
+				quote::quote_spanned!(storage.attr_span =>
+					#entry,
+					#(#cfg_attrs)* #frame_support::metadata::StorageEntryMetadata {
+						name: #frame_support::metadata::DecodeDifferent::Encode(
+							<#full_ident as #metadata_trait>::COUNTER_NAME
+						),
+						modifier: <#full_ident as #metadata_trait>::COUNTER_MODIFIER,
+						ty: #frame_support::metadata::StorageEntryType::Plain(
+							#frame_support::metadata::DecodeDifferent::Encode(
+								<#full_ident as #metadata_trait>::COUNTER_TY
+							)
+						),
+						default: #frame_support::metadata::DecodeDifferent::Encode(
+							<#full_ident as #metadata_trait>::COUNTER_DEFAULT
+						),
+						documentation: #frame_support::metadata::DecodeDifferent::Encode(&[
+							<#full_ident as #metadata_trait>::COUNTER_DOC,
+						]),
+					}
+				)
+			} else {
+				entry
+			}
+		});
+
+	let entries_types_v14 = def.storages.iter()
+		.map(|storage| {
+			let ident = &storage.ident;
+			let gen = &def.type_use_generics(storage.attr_span);
+			let full_ident = quote::quote_spanned!(storage.attr_span => #ident<#gen> );
+
+			let cfg_attrs = &storage.cfg_attrs;
+
+			let register = |ty: &dyn quote::ToTokens| quote::quote_spanned!(storage.attr_span =>
+				registry.register(#frame_support::metadata::meta_type!(#ty))
+			);
+
+			match &storage.metadata {
+				Metadata::Value { value } => {
+					let value = register(value);
+					quote::quote_spanned!(storage.attr_span =>
+						#(#cfg_attrs)* #frame_support::metadata::StorageEntryTypeV14::Plain(#value)
+					)
+				},
+				Metadata::Map { key, value } => {
+					let key = register(key);
+					let value = register(value);
+					quote::quote_spanned!(storage.attr_span =>
+						#(#cfg_attrs)* #frame_support::metadata::StorageEntryTypeV14::Map {
+							hashers: #frame_support::sp_std::vec![
+								<#full_ident as #frame_support::storage::types::StorageMapMetadata>
+									::HASHER
+							],
+							key: #key,
+							value: #value,
+						}
+					)
+				},
+				Metadata::CountedMap { key, value } => {
+					let key = register(key);
+					let value = register(value);
+					quote::quote_spanned!(storage.attr_span =>
+						#(#cfg_attrs)* #frame_support::metadata::StorageEntryTypeV14::Map {
+							hashers: #frame_support::sp_std::vec![
+								<#full_ident as
+									#frame_support::storage::types::CountedStorageMapMetadata
+								>::HASHER
+							],
+							key: #key,
+							value: #value,
+						},
+						#(#cfg_attrs)* #frame_support::metadata::StorageEntryTypeV14::Plain(
+							registry.register_type::<u32>()
+						)
+					)
+				},
+				Metadata::DoubleMap { key1, key2, value } => {
+					let key1 = register(key1);
+					let key2 = register(key2);
+					let value = register(value);
+					quote::quote_spanned!(storage.attr_span =>
+						#(#cfg_attrs)* #frame_support::metadata::StorageEntryTypeV14::Map {
+							hashers: #frame_support::sp_std::vec![
+								<#full_ident as
+									#frame_support::storage::types::StorageDoubleMapMetadata
+								>::HASHER1,
+								<#full_ident as
+									#frame_support::storage::types::StorageDoubleMapMetadata
+								>::HASHER2,
+							],
+							key: {
+								let keys = #frame_support::sp_std::vec![#key1, #key2];
+								registry.register_tuple(keys)
+							},
+							value: #value,
+						}
+					)
+				},
+				Metadata::NMap { keys, value, .. } => {
+					let keys = keys.iter().map(|key| register(key)).collect::<Vec<_>>();
+					let key = if keys.len() == 1 {
+						keys[0].clone()
+					} else {
+						quote::quote_spanned!(storage.attr_span => {
+							let keys = #frame_support::sp_std::vec![ #( #keys ),* ];
+							registry.register_tuple(keys)
+						})
+					};
+					let value = register(value);
+					quote::quote_spanned!(storage.attr_span =>
+						#(#cfg_attrs)* #frame_support::metadata::StorageEntryTypeV14::Map {
+							hashers: <#full_ident as
+								#frame_support::storage::types::StorageNMapMetadata
+							>::HASHERS.to_vec(),
+							key: #key,
+							value: #value,
+						}
+					)
+				},
+			}
 		});
 
 	let getters = def.storages.iter()
@@ -204,6 +367,25 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						}
 					)
 				},
+				Metadata::CountedMap { key, value } => {
+					let query = match storage.query_kind.as_ref().expect("Checked by def") {
+						QueryKind::OptionQuery => quote::quote_spanned!(storage.attr_span =>
+							Option<#value>
+						),
+						QueryKind::ValueQuery => quote::quote!(#value),
+					};
+					quote::quote_spanned!(storage.attr_span =>
+						#(#cfg_attrs)*
+						impl<#type_impl_gen> #pallet_ident<#type_use_gen> #completed_where_clause {
+							#( #docs )*
+							pub fn #getter<KArg>(k: KArg) -> #query where
+								KArg: #frame_support::codec::EncodeLike<#key>,
+							{
+								<#full_ident>::get(k)
+							}
+						}
+					)
+				},
 				Metadata::DoubleMap { key1, key2, value } => {
 					let query = match storage.query_kind.as_ref().expect("Checked by def") {
 						QueryKind::OptionQuery => quote::quote_spanned!(storage.attr_span =>
@@ -227,6 +409,31 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						}
 					)
 				},
+				Metadata::NMap { keygen, value, .. } => {
+					let query = match storage.query_kind.as_ref().expect("Checked by def") {
+						QueryKind::OptionQuery => quote::quote_spanned!(storage.attr_span =>
+							Option<#value>
+						),
+						QueryKind::ValueQuery => quote::quote!(#value),
+					};
+					quote::quote_spanned!(storage.attr_span =>
+						#(#cfg_attrs)*
+						impl<#type_impl_gen> #pallet_ident<#type_use_gen> #completed_where_clause {
+							#( #docs )*
+							pub fn #getter<KArg>(key: KArg) -> #query where
+								KArg: #frame_support::storage::types::EncodeLikeTuple<
+									<#keygen as #frame_support::storage::types::KeyGenerator>::KArg
+								>
+									+ #frame_support::storage::types::TupleToEncodedIter,
+							{
+								<
+									#full_ident as
+									#frame_support::storage::StorageNMap<#keygen, #value>
+								>::get(key)
+							}
+						}
+					)
+				},
 			}
 		} else {
 			Default::default()
@@ -242,7 +449,44 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 
 		let cfg_attrs = &storage_def.cfg_attrs;
 
+		let maybe_counter = if let Metadata::CountedMap { .. } = storage_def.metadata {
+			let counter_prefix_struct_ident = counter_prefix_ident(&storage_def.ident);
+			let counter_prefix_struct_const = counter_prefix(&prefix_struct_const);
+
+			quote::quote_spanned!(storage_def.attr_span =>
+				#(#cfg_attrs)*
+				#prefix_struct_vis struct #counter_prefix_struct_ident<#type_use_gen>(
+					core::marker::PhantomData<(#type_use_gen,)>
+				);
+				#(#cfg_attrs)*
+				impl<#type_impl_gen> #frame_support::traits::StorageInstance
+					for #counter_prefix_struct_ident<#type_use_gen>
+					#config_where_clause
+				{
+					fn pallet_prefix() -> &'static str {
+						<
+							<T as #frame_system::Config>::PalletInfo
+							as #frame_support::traits::PalletInfo
+						>::name::<Pallet<#type_use_gen>>()
+							.expect("Every active pallet has a name in the runtime; qed")
+					}
+					const STORAGE_PREFIX: &'static str = #counter_prefix_struct_const;
+				}
+				#(#cfg_attrs)*
+				impl<#type_impl_gen> #frame_support::storage::types::CountedStorageMapInstance
+					for #prefix_struct_ident<#type_use_gen>
+					#config_where_clause
+				{
+					type CounterPrefix = #counter_prefix_struct_ident<#type_use_gen>;
+				}
+			)
+		} else {
+			proc_macro2::TokenStream::default()
+		};
+
 		quote::quote_spanned!(storage_def.attr_span =>
+			#maybe_counter
+
 			#(#cfg_attrs)*
 			#prefix_struct_vis struct #prefix_struct_ident<#type_use_gen>(
 				core::marker::PhantomData<(#type_use_gen,)>
@@ -289,6 +533,20 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 					),
 				}
 			}
+
+			#[doc(hidden)]
+			#[allow(unused_variables)]
+			pub fn storage_metadata_v14(
+				registry: &mut #frame_support::metadata::Registry,
+			) -> #frame_support::metadata::StorageMetadataV14 {
+				let types: &[#frame_support::metadata::StorageEntryTypeV14] = &[
+					#( #entries_types_v14, )*
+				];
+				#frame_support::metadata::StorageMetadataV14::with_types(
+					&Self::storage_metadata(),
+					types.to_vec(),
+				)
+			}
 		}
 
 		#( #getters )*
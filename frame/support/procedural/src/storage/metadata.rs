@@ -66,6 +66,51 @@ fn storage_line_metadata_type(scrate: &TokenStream, line: &StorageLineDefExt) ->
 	}
 }
 
+fn storage_line_metadata_type_v14(scrate: &TokenStream, line: &StorageLineDefExt) -> TokenStream {
+	let value_type = &line.value_type;
+	let value = quote!( registry.register(#scrate::metadata::meta_type!(#value_type)) );
+	match &line.storage_type {
+		StorageLineTypeDef::Simple(_) => {
+			quote!{
+				#scrate::metadata::StorageEntryTypeV14::Plain(#value)
+			}
+		},
+		StorageLineTypeDef::Map(map) => {
+			let hasher = map.hasher.into_metadata();
+			let key = &map.key;
+			quote!{
+				#scrate::metadata::StorageEntryTypeV14::Map {
+					hashers: #scrate::sp_std::vec![ #scrate::metadata::#hasher ],
+					key: registry.register(#scrate::metadata::meta_type!(#key)),
+					value: #value,
+				}
+			}
+		},
+		StorageLineTypeDef::DoubleMap(map) => {
+			let hasher1 = map.hasher1.into_metadata();
+			let hasher2 = map.hasher2.into_metadata();
+			let key1 = &map.key1;
+			let key2 = &map.key2;
+			quote!{
+				#scrate::metadata::StorageEntryTypeV14::Map {
+					hashers: #scrate::sp_std::vec![
+						#scrate::metadata::#hasher1,
+						#scrate::metadata::#hasher2,
+					],
+					key: {
+						let keys = #scrate::sp_std::vec![
+							registry.register(#scrate::metadata::meta_type!(#key1)),
+							registry.register(#scrate::metadata::meta_type!(#key2)),
+						];
+						registry.register_tuple(keys)
+					},
+					value: #value,
+				}
+			}
+		},
+	}
+}
+
 fn default_byte_getter(
 	scrate: &TokenStream,
 	line: &StorageLineDefExt,
@@ -142,6 +187,7 @@ fn default_byte_getter(
 
 pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStream {
 	let mut entries = TokenStream::new();
+	let mut entries_types_v14 = TokenStream::new();
 	let mut default_byte_getter_struct_defs = TokenStream::new();
 
 	for line in def.storage_lines.iter() {
@@ -154,6 +200,7 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 		};
 
 		let ty = storage_line_metadata_type(scrate, line);
+		let ty_v14 = storage_line_metadata_type_v14(scrate, line);
 
 		let (
 			default_byte_getter_struct_def,
@@ -184,6 +231,7 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 
 		default_byte_getter_struct_defs.extend(default_byte_getter_struct_def);
 		entries.extend(entry);
+		entries_types_v14.extend(quote!(#ty_v14,));
 	}
 
 	let prefix = if let Some(instance) = &def.module_instance {
@@ -213,6 +261,15 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 			pub fn storage_metadata() -> #scrate::metadata::StorageMetadata {
 				#store_metadata
 			}
+
+			#[doc(hidden)]
+			#[allow(unused_variables)]
+			pub fn storage_metadata_v14(
+				registry: &mut #scrate::metadata::Registry,
+			) -> #scrate::metadata::StorageMetadataV14 {
+				let types = #scrate::sp_std::vec![ #entries_types_v14 ];
+				#scrate::metadata::StorageMetadataV14::with_types(&Self::storage_metadata(), types)
+			}
 		}
 	)
 }
@@ -0,0 +1,220 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support_procedural_tools::{clean_type_string, generate_crate_access_2018};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Result, spanned::Spanned};
+
+/// Derive `TypeInfo`, describing the structure of the type the way it is encoded by the codec
+/// derives: `#[codec(skip)]`, `#[codec(compact)]` and `#[codec(index = ..)]` are respected.
+pub fn derive_type_info(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input: syn::DeriveInput = match syn::parse(input) {
+		Ok(input) => input,
+		Err(e) => return e.to_compile_error().into(),
+	};
+
+	expand(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand(mut input: syn::DeriveInput) -> Result<TokenStream> {
+	let frame_support = generate_crate_access_2018("frame-support")?;
+
+	if let Some(lifetime) = input.generics.lifetimes().next() {
+		let msg = "Lifetime parameters are not supported by `derive(TypeInfo)`";
+		return Err(syn::Error::new(lifetime.span(), msg))
+	}
+
+	let type_params = input.generics.type_params()
+		.map(|param| param.ident.clone())
+		.collect::<Vec<_>>();
+
+	// The type is identified by its `TypeId`, thus all its parameters must be `'static`.
+	let where_clause = input.generics.make_where_clause();
+	for param in &type_params {
+		where_clause.predicates.push(syn::parse_quote!(#param: 'static));
+	}
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let def = match &input.data {
+		syn::Data::Struct(struct_) => {
+			let fields = expand_fields(&frame_support, &struct_.fields)?;
+			quote!( #frame_support::metadata::TypeDef::Composite(#fields) )
+		},
+		syn::Data::Enum(enum_) => {
+			let mut variants = Vec::new();
+			let mut index = 0u8;
+			for variant in &enum_.variants {
+				let attrs = CodecAttrs::parse(&variant.attrs)?;
+				if attrs.skip {
+					continue
+				}
+
+				let variant_index = attrs.index.unwrap_or(index);
+				index = index.checked_add(1)
+					.ok_or_else(|| syn::Error::new(variant.span(), "Too many variants"))?;
+
+				let variant_name = variant.ident.to_string();
+				let fields = expand_fields(&frame_support, &variant.fields)?;
+				let docs = get_docs(&variant.attrs);
+				variants.push(quote!(
+					#frame_support::metadata::Variant {
+						name: #variant_name.into(),
+						fields: #fields,
+						index: #variant_index,
+						docs: #frame_support::sp_std::vec![ #( #docs.into() ),* ],
+					}
+				));
+			}
+
+			quote!(
+				#frame_support::metadata::TypeDef::Variant(
+					#frame_support::sp_std::vec![ #( #variants ),* ]
+				)
+			)
+		},
+		syn::Data::Union(_) => {
+			let msg = "Union type not supported by `derive(TypeInfo)`";
+			return Err(syn::Error::new(input.span(), msg))
+		},
+	};
+
+	let name_string = name.to_string();
+	let docs = get_docs(&input.attrs);
+
+	Ok(quote!(
+		const _: () = {
+			impl #impl_generics #frame_support::metadata::TypeInfo for #name #ty_generics
+				#where_clause
+			{
+				type Identity = Self;
+
+				fn type_info(
+					registry: &mut #frame_support::metadata::Registry,
+				) -> #frame_support::metadata::Type {
+					let path = module_path!().split("::")
+						.chain(core::iter::once(#name_string))
+						.map(Into::into)
+						.collect();
+					let type_params = #frame_support::sp_std::vec![
+						#( registry.register(#frame_support::metadata::meta_type!(#type_params)) ),*
+					];
+
+					#frame_support::metadata::Type::new(path, #def)
+						.type_params(type_params)
+						.docs(#frame_support::sp_std::vec![ #( #docs.into() ),* ])
+				}
+			}
+		};
+	))
+}
+
+/// Expand to the `Vec<Field>` describing the given fields.
+fn expand_fields(frame_support: &syn::Ident, fields: &syn::Fields) -> Result<TokenStream> {
+	let mut expanded = Vec::new();
+	for field in fields.iter() {
+		let attrs = CodecAttrs::parse(&field.attrs)?;
+		if attrs.skip {
+			continue
+		}
+
+		let name = match &field.ident {
+			Some(ident) => {
+				let ident = ident.to_string();
+				quote!( Some(#ident.into()) )
+			},
+			None => quote!( None ),
+		};
+		let field_ty = &field.ty;
+		let ty = if attrs.compact {
+			quote!({
+				let inner = registry.register(#frame_support::metadata::meta_type!(#field_ty));
+				registry.register_compact(inner)
+			})
+		} else {
+			quote!( registry.register(#frame_support::metadata::meta_type!(#field_ty)) )
+		};
+		let type_name = clean_type_string(&quote!(#field_ty).to_string());
+		let docs = get_docs(&field.attrs);
+
+		expanded.push(quote!(
+			#frame_support::metadata::Field {
+				name: #name,
+				ty: #ty,
+				type_name: Some(#type_name.into()),
+				docs: #frame_support::sp_std::vec![ #( #docs.into() ),* ],
+			}
+		));
+	}
+
+	Ok(quote!( #frame_support::sp_std::vec![ #( #expanded ),* ] ))
+}
+
+/// The codec attributes relevant to the description of a type.
+#[derive(Default)]
+struct CodecAttrs {
+	skip: bool,
+	compact: bool,
+	index: Option<u8>,
+}
+
+impl CodecAttrs {
+	fn parse(attrs: &[syn::Attribute]) -> Result<Self> {
+		let mut codec_attrs = Self::default();
+		for attr in attrs.iter().filter(|attr| attr.path.is_ident("codec")) {
+			let list = match attr.parse_meta()? {
+				syn::Meta::List(list) => list,
+				_ => continue,
+			};
+
+			for nested in list.nested.iter() {
+				match nested {
+					syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") =>
+						codec_attrs.skip = true,
+					syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("compact") =>
+						codec_attrs.compact = true,
+					syn::NestedMeta::Meta(syn::Meta::NameValue(name_value))
+						if name_value.path.is_ident("index") =>
+					{
+						if let syn::Lit::Int(index) = &name_value.lit {
+							codec_attrs.index = Some(index.base10_parse()?);
+						}
+					},
+					_ => (),
+				}
+			}
+		}
+
+		Ok(codec_attrs)
+	}
+}
+
+/// The doc lines of the given attributes.
+fn get_docs(attrs: &[syn::Attribute]) -> Vec<syn::LitStr> {
+	attrs.iter()
+		.filter_map(|attr| match attr.parse_meta() {
+			Ok(syn::Meta::NameValue(name_value)) if name_value.path.is_ident("doc") =>
+				match name_value.lit {
+					syn::Lit::Str(doc) => Some(doc),
+					_ => None,
+				},
+			_ => None,
+		})
+		.collect()
+}
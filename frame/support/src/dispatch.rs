@@ -1807,7 +1807,7 @@ macro_rules! decl_module {
 		/// Dispatchable calls.
 		///
 		/// Each variant of this enum maps to a dispatchable function from the associated module.
-		#[derive($crate::codec::Encode, $crate::codec::Decode)]
+		#[derive($crate::codec::Encode, $crate::codec::Decode, $crate::TypeInfo)]
 		pub enum $call_type<$trait_instance: $trait_name$(<I>, $instance: $instantiable $( = $module_default_instance)?)?>
 			where $( $other_where_bounds )*
 		{
@@ -2320,6 +2320,7 @@ macro_rules! __dispatch_impl_metadata {
 	(
 		$mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>
 		{ $( $other_where_bounds:tt )* }
+		$call_type:ident
 		$($rest:tt)*
 	) => {
 		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $mod_type<$trait_instance $(, $instance)?>
@@ -2328,7 +2329,17 @@ macro_rules! __dispatch_impl_metadata {
 			#[doc(hidden)]
 			#[allow(dead_code)]
 			pub fn call_functions() -> &'static [$crate::dispatch::FunctionMetadata] {
-				$crate::__call_to_functions!($($rest)*)
+				$crate::__call_to_functions!($call_type $($rest)*)
+			}
+
+			#[doc(hidden)]
+			#[allow(dead_code)]
+			pub fn calls_metadata_v14(
+				registry: &mut $crate::metadata::Registry,
+			) -> $crate::metadata::CallsMetadataV14 {
+				$crate::metadata::CallsMetadataV14 {
+					ty: registry.register_type::<$call_type<$trait_instance $(, $instance)?>>(),
+				}
 			}
 		}
 	}
@@ -2453,6 +2464,22 @@ macro_rules! __impl_module_constants_metadata {
 					),*
 				]
 			}
+
+			#[doc(hidden)]
+			#[allow(dead_code, unused_variables)]
+			pub fn module_constants_metadata_v14(
+				registry: &mut $crate::metadata::Registry,
+			) -> $crate::dispatch::Vec<$crate::metadata::ModuleConstantMetadataV14> {
+				let types: $crate::dispatch::Vec<$crate::metadata::TypeIndex> = $crate::sp_std::vec![
+					$( registry.register($crate::metadata::meta_type!($type)) ),*
+				];
+				Self::module_constants_metadata().iter()
+					.zip(types)
+					.map(|(constant, ty)| {
+						$crate::metadata::ModuleConstantMetadataV14::with_type(constant, ty)
+					})
+					.collect()
+			}
 		}
 	}
 }
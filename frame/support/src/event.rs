@@ -130,6 +130,7 @@ macro_rules! decl_event {
 			$crate::codec::Encode,
 			$crate::codec::Decode,
 			$crate::RuntimeDebug,
+			$crate::TypeInfo,
 		)]
 		/// Events for this module.
 		///
@@ -148,6 +149,14 @@ macro_rules! decl_event {
 			pub fn metadata() -> &'static [ $crate::event::EventMetadata ] {
 				$crate::__events_to_metadata!(; $( $events )* )
 			}
+
+			#[allow(dead_code)]
+			#[doc(hidden)]
+			pub fn metadata_v14(
+				registry: &mut $crate::metadata::Registry,
+			) -> $crate::metadata::EventsMetadataV14 {
+				$crate::metadata::EventsMetadataV14 { ty: registry.register_type::<Self>() }
+			}
 		}
 	}
 }
@@ -273,6 +282,7 @@ macro_rules! __decl_generic_event {
 			$crate::codec::Encode,
 			$crate::codec::Decode,
 			$crate::RuntimeDebug,
+			$crate::TypeInfo,
 		)]
 		/// Events for this module.
 		///
@@ -296,6 +306,17 @@ macro_rules! __decl_generic_event {
 			pub fn metadata() -> &'static [$crate::event::EventMetadata] {
 				$crate::__events_to_metadata!(; $( $events )* )
 			}
+
+			#[allow(dead_code)]
+			#[doc(hidden)]
+			pub fn metadata_v14(
+				registry: &mut $crate::metadata::Registry,
+			) -> $crate::metadata::EventsMetadataV14 where
+				$( $generic_param: 'static, )*
+				$( $instance: 'static, )?
+			{
+				$crate::metadata::EventsMetadataV14 { ty: registry.register_type::<Self>() }
+			}
 		}
 	};
 	(@cannot_parse $ty:ty) => {
@@ -542,6 +563,13 @@ macro_rules! __impl_outer_event_json_metadata {
 				{
 					$module_name::Event ::< $( $generic_params ),* > ::metadata()
 				}
+
+				#[allow(dead_code)]
+				pub fn [< __module_events_v14_ $module_name $( _ $instance )? >] (
+					registry: &mut $crate::metadata::Registry,
+				) -> $crate::metadata::EventsMetadataV14 {
+					$module_name::Event ::< $( $generic_params ),* > ::metadata_v14(registry)
+				}
 			)*
 		}
 	}
@@ -74,8 +74,8 @@ pub use self::hash::{
 	StorageHasher, ReversibleStorageHasher
 };
 pub use self::storage::{
-	StorageValue, StorageMap, StorageDoubleMap, StoragePrefixedMap, IterableStorageMap,
-	IterableStorageDoubleMap, migration,
+	StorageValue, StorageMap, StorageDoubleMap, StorageNMap, StoragePrefixedMap,
+	IterableStorageMap, IterableStorageDoubleMap, IterableStorageNMap, migration,
 	bounded_vec::{self, BoundedVec},
 };
 pub use self::dispatch::{Parameter, Callable};
@@ -593,6 +593,22 @@ pub use frame_support_procedural::DebugNoBound;
 /// ```
 pub use frame_support_procedural::DefaultNoBound;
 
+/// Derive [`metadata::TypeInfo`], describing the structure of the type in the runtime metadata.
+///
+/// Fields and variants are described the way the codec derives encode them: `#[codec(skip)]`,
+/// `#[codec(compact)]` and `#[codec(index = ..)]` are taken into account. Types of fields which
+/// don't implement [`metadata::TypeInfo`] are described only by their name.
+/// ```
+/// # use frame_support::TypeInfo;
+/// #[derive(codec::Encode, TypeInfo)]
+/// enum Foo {
+///		A { #[codec(compact)] a: u32 },
+///		#[codec(index = 3)]
+///		B(Vec<u8>),
+/// }
+/// ```
+pub use frame_support_procedural::TypeInfo;
+
 /// Assert the annotated function is executed within a storage transaction.
 ///
 /// The assertion is enabled for native execution and when `debug_assertions` are enabled.
@@ -1237,7 +1253,10 @@ pub mod pallet_prelude {
 		traits::{Get, Hooks, IsType, GetPalletVersion, EnsureOrigin, PalletInfoAccess},
 		dispatch::{DispatchResultWithPostInfo, Parameter, DispatchError, DispatchResult},
 		weights::{DispatchClass, Pays, Weight},
-		storage::types::{StorageValue, StorageMap, StorageDoubleMap, ValueQuery, OptionQuery},
+		storage::types::{
+			StorageValue, StorageMap, CountedStorageMap, StorageDoubleMap, StorageNMap,
+			Key as NMapKey, ValueQuery, OptionQuery,
+		},
 	};
 	pub use codec::{Encode, Decode};
 	pub use sp_inherents::{InherentData, InherentIdentifier, ProvideInherent};
@@ -1564,7 +1583,8 @@ pub mod pallet_prelude {
 /// 	= $StorageType<_, $some_generics, ...>;
 /// ```
 /// I.e. it must be a type alias, with generics: `T` or `T: Config`, aliased type must be one
-/// of `StorageValue`, `StorageMap` or `StorageDoubleMap` (defined in frame_support).
+/// of `StorageValue`, `StorageMap`, `CountedStorageMap`, `StorageDoubleMap` or `StorageNMap`
+/// (defined in frame_support).
 /// Their first generic must be `_` as it is written by the macro itself.
 ///
 /// The Prefix generic written by the macro is generated using `PalletInfo::name::<Pallet<..>>()`
@@ -1608,14 +1628,20 @@ pub mod pallet_prelude {
 /// `_GeneratedPrefixForStorage$NameOfStorage`, and implements [`StorageInstance`](traits::StorageInstance)
 /// on it using the pallet and storage name. It then uses it as the first generic of the aliased
 /// type.
-///
+/// For `CountedStorageMap`, `CountedStorageMapInstance` is implemented, and another similar struct
+/// is generated for the counter, named `_GeneratedCounterPrefixForStorage$NameOfStorage` and using
+/// the storage name `CounterFor$NameOfStorage`.
 ///
 /// The macro implements the function `storage_metadata` on `Pallet` implementing the metadata for
 /// all storage items based on their kind:
 /// * for a storage value, the type of the value is copied into the metadata
 /// * for a storage map, the type of the values and the key's type is copied into the metadata
+/// * for a counted storage map, the metadata of the map is generated, followed by the metadata of
+///   its counter, a plain storage value of type `u32`.
 /// * for a storage double map, the type of the values, and the types of key1 and key2 are copied into
 ///   the metadata.
+/// * for a storage n map, the type of the values, and the types of each key are copied into the
+///   metadata, along with the hasher of each key.
 ///
 /// # Type value: `#[pallet::type_value]` optional
 ///
@@ -1876,14 +1902,18 @@ pub mod pallet_prelude {
 ///
 /// 	// Declare a storage item. Any amount of storage items can be declared (optional).
 /// 	//
-/// 	// Is expected either `StorageValue`, `StorageMap` or `StorageDoubleMap`.
+/// 	// Is expected either `StorageValue`, `StorageMap`, `CountedStorageMap`, `StorageDoubleMap` or
+/// 	// `StorageNMap`.
 /// 	// The macro generates the prefix type and replaces the first generic `_`.
 /// 	//
 /// 	// The macro expands the metadata for the storage item with the type used:
 /// 	// * for a storage value the type of the value is copied into the metadata
 /// 	// * for a storage map the type of the values and the type of the key is copied into the metadata
+/// 	// * for a counted storage map the metadata of the map is followed by the metadata of its
+/// 	//   counter.
 /// 	// * for a storage double map the types of the values and keys are copied into the
 /// 	//   metadata.
+/// 	// * for a storage n map the types of the values and keys are copied into the metadata.
 /// 	//
 /// 	// NOTE: The generic `Hasher` must implement the `StorageHasher` trait (or the type is not
 /// 	// usable at all). We use [`StorageHasher::METADATA`] for the metadata of the hasher of the
@@ -1897,6 +1927,18 @@ pub mod pallet_prelude {
 /// 	#[pallet::getter(fn my_storage)]
 /// 	pub(super) type MyStorage<T> = StorageMap<_, Blake2_128Concat, u32, u32>;
 ///
+/// 	// A storage map which also keeps track of the number of its items.
+/// 	#[pallet::storage]
+/// 	pub(super) type MyCountedStorage<T> = CountedStorageMap<_, Blake2_128Concat, u32, u32>;
+///
+/// 	// A storage with an arbitrary number of keys, each one with its own hasher.
+/// 	#[pallet::storage]
+/// 	pub(super) type MyNMapStorage<T> = StorageNMap<
+/// 		_,
+/// 		(NMapKey<Blake2_128Concat, u32>, NMapKey<Twox64Concat, u16>),
+/// 		u64,
+/// 	>;
+///
 /// 	// Declare the genesis config (optional).
 /// 	//
 /// 	// The macro accepts either a struct or an enum; it checks that generics are consistent.
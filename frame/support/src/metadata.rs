@@ -19,7 +19,13 @@ pub use frame_metadata::{
 	DecodeDifferent, FnEncode, RuntimeMetadata, ModuleMetadata, RuntimeMetadataLastVersion,
 	DefaultByteGetter, RuntimeMetadataPrefixed, StorageEntryMetadata, StorageMetadata,
 	StorageEntryType, StorageEntryModifier, DefaultByte, StorageHasher, ModuleErrorMetadata,
-	ExtrinsicMetadata,
+	ExtrinsicMetadata, RuntimeMetadataV12, RuntimeMetadataV14, ModuleMetadataV14,
+	CallsMetadataV14, EventsMetadataV14, ModuleConstantMetadataV14, StorageMetadataV14,
+	StorageEntryMetadataV14, StorageEntryTypeV14, ExtrinsicMetadataV14, meta_type,
+	types::{
+		Registry, PortableRegistry, TypeIndex, TypeInfo, MetaType, Type, TypeDef, TypeDefPrimitive,
+		Field, Variant,
+	},
 };
 
 /// Implements the metadata support for the given runtime and all its modules.
@@ -89,6 +95,33 @@ macro_rules! impl_runtime_metadata {
 						},
 				}.into()
 			}
+
+			/// The metadata of the runtime in version 14, with the type information of calls,
+			/// events, storage entries and constants.
+			pub fn metadata_v14() -> $crate::metadata::RuntimeMetadataPrefixed {
+				let mut registry = $crate::metadata::Registry::new();
+				let modules = $crate::__runtime_modules_to_metadata_v14!(
+					registry; $runtime;; $( $rest )*
+				);
+				let extrinsic = $crate::metadata::ExtrinsicMetadataV14 {
+					ty: registry.register($crate::metadata::meta_type!($ext)),
+					version: <$ext as $crate::sp_runtime::traits::ExtrinsicMetadata>::VERSION,
+					signed_extensions: <
+							<
+								$ext as $crate::sp_runtime::traits::ExtrinsicMetadata
+							>::SignedExtensions as $crate::sp_runtime::traits::SignedExtension
+						>::identifier()
+							.into_iter()
+							.map($crate::metadata::DecodeDifferent::Encode)
+							.collect(),
+				};
+
+				$crate::metadata::RuntimeMetadataV14 {
+					types: registry.into_portable(),
+					modules,
+					extrinsic,
+				}.into()
+			}
 		}
 	}
 }
@@ -251,6 +284,158 @@ macro_rules! __runtime_modules_to_metadata_calls_storage {
 }
 
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v14 {
+	(
+		$registry:ident;
+		$runtime: ident;
+		$( $metadata:expr ),*;
+		$mod:ident::$module:ident $( < $instance:ident > )? as $name:ident
+			{ index $index:tt }
+			$(with)+ $($kw:ident)*
+		,
+		$( $rest:tt )*
+	) => {
+		$crate::__runtime_modules_to_metadata_v14!(
+			$registry;
+			$runtime;
+			$( $metadata, )* $crate::metadata::ModuleMetadataV14 {
+				name: $crate::metadata::DecodeDifferent::Encode(stringify!($name)),
+				index: $index,
+				storage: $crate::__runtime_modules_to_metadata_v14_calls_storage!(
+					$registry, $mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+				),
+				calls: $crate::__runtime_modules_to_metadata_v14_calls_call!(
+					$registry, $mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+				),
+				event: $crate::__runtime_modules_to_metadata_v14_calls_event!(
+					$registry, $mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+				),
+				constants: $mod::$module::<$runtime $(, $mod::$instance )?>
+					::module_constants_metadata_v14(&mut $registry),
+				errors: <$mod::$module::<$runtime $(, $mod::$instance )?>
+					as $crate::metadata::ModuleErrorMetadata>::metadata().to_vec(),
+			};
+			$( $rest )*
+		)
+	};
+	(
+		$registry:ident;
+		$runtime:ident;
+		$( $metadata:expr ),*;
+	) => {
+		$crate::sp_std::vec![ $( $metadata ),* ]
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v14_calls_call {
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Call
+		$(with $kws:ident)*
+	) => {
+		Some($mod::$module::<$runtime $(, $mod::$instance )?>::calls_metadata_v14(&mut $registry))
+	};
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_v14_calls_call! {
+			$registry, $mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+		}
+	};
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v14_calls_event {
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Event
+		$(with $kws:ident)*
+	) => {
+		Some($crate::paste::expr!{
+			$runtime:: [< __module_events_v14_ $mod $(_ $instance)?>](&mut $registry)
+		})
+	};
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_v14_calls_event!(
+			$registry, $mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+		)
+	};
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_modules_to_metadata_v14_calls_storage {
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with Storage
+		$(with $kws:ident)*
+	) => {
+		Some($mod::$module::<$runtime $(, $mod::$instance )?>::storage_metadata_v14(&mut $registry))
+	};
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+		with $_:ident
+		$(with $kws:ident)*
+	) => {
+		$crate::__runtime_modules_to_metadata_v14_calls_storage! {
+			$registry, $mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+		}
+	};
+	(
+		$registry:ident,
+		$mod: ident,
+		$module: ident $( <$instance:ident> )?,
+		$runtime: ident,
+	) => {
+		None
+	};
+}
+
 #[cfg(test)]
 // Do not complain about unused `dispatch` and `dispatch_aux`.
 #[allow(dead_code)]
@@ -259,7 +444,7 @@ mod tests {
 	use frame_metadata::{
 		EventMetadata, StorageEntryModifier, StorageEntryType, FunctionMetadata, StorageEntryMetadata,
 		ModuleMetadata, RuntimeMetadataPrefixed, DefaultByte, ModuleConstantMetadata, DefaultByteGetter,
-		ErrorMetadata, ExtrinsicMetadata,
+		ErrorMetadata, ExtrinsicMetadata, FunctionArgumentMetadata,
 	};
 	use codec::{Encode, Decode};
 	use crate::traits::Get;
@@ -369,7 +554,7 @@ mod tests {
 				type Error = Error<T>;
 
 				#[weight = 0]
-				fn aux_0(_origin) -> DispatchResult { unreachable!() }
+				fn aux_0(_origin, #[compact] _data: u32) -> DispatchResult { unreachable!() }
 			}
 		}
 
@@ -521,7 +706,7 @@ mod tests {
 
 	#[test]
 	fn runtime_metadata() {
-		let expected_metadata: RuntimeMetadataLastVersion = RuntimeMetadataLastVersion {
+		let expected_metadata: RuntimeMetadataV12 = RuntimeMetadataV12 {
 			modules: DecodeDifferent::Encode(&[
 				ModuleMetadata {
 					name: DecodeDifferent::Encode("System"),
@@ -575,7 +760,12 @@ mod tests {
 						DecodeDifferent::Encode(FnEncode(|| &[
 							FunctionMetadata {
 								name: DecodeDifferent::Encode("aux_0"),
-								arguments: DecodeDifferent::Encode(&[]),
+								arguments: DecodeDifferent::Encode(&[
+									FunctionArgumentMetadata {
+										name: DecodeDifferent::Encode("_data"),
+										ty: DecodeDifferent::Encode("Compact<u32>"),
+									}
+								]),
 								documentation: DecodeDifferent::Encode(&[]),
 							}
 						]))),
@@ -657,4 +847,81 @@ mod tests {
 
 		pretty_assertions::assert_eq!(expected_metadata, metadata_decoded.unwrap());
 	}
+
+	#[test]
+	fn runtime_metadata_v14() {
+		let metadata_encoded = TestRuntime::metadata_v14().encode();
+		let metadata = match RuntimeMetadataPrefixed::decode(&mut &metadata_encoded[..]).unwrap().1 {
+			RuntimeMetadata::V14(metadata) => metadata,
+			_ => panic!("metadata has version 14"),
+		};
+		let resolve = |ty| metadata.types.resolve(ty).expect("type is in the registry");
+		let variant_names = |ty| match &resolve(ty).def {
+			TypeDef::Variant(variants) => variants.iter().map(|v| v.name.clone()).collect::<Vec<_>>(),
+			_ => panic!("type is a variant"),
+		};
+
+		let names = metadata.modules.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+		assert_eq!(
+			names,
+			vec![
+				DecodeDifferent::Decoded("System".to_string()),
+				DecodeDifferent::Decoded("Module".to_string()),
+				DecodeDifferent::Decoded("Module2".to_string()),
+			],
+		);
+
+		let system = &metadata.modules[0];
+		assert!(system.calls.is_none());
+		assert_eq!(variant_names(system.event.as_ref().unwrap().ty), vec!["SystemEvent"]);
+		let constant_types = system.constants.iter()
+			.map(|c| resolve(c.ty).def.clone())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			constant_types,
+			vec![
+				TypeDef::Opaque,
+				TypeDef::Opaque,
+				TypeDef::Primitive(TypeDefPrimitive::U64),
+			],
+		);
+
+		let module = &metadata.modules[1];
+		let calls = resolve(module.calls.as_ref().unwrap().ty);
+		assert_eq!(calls.path.last().map(String::as_str), Some("Call"));
+		let call = match &calls.def {
+			TypeDef::Variant(variants) => &variants[0],
+			_ => panic!("call type is a variant"),
+		};
+		assert_eq!((call.name.as_str(), call.index), ("aux_0", 0));
+		assert_eq!(call.fields.len(), 1);
+		assert_eq!(call.fields[0].name, Some("_data".to_string()));
+		match resolve(call.fields[0].ty).def {
+			TypeDef::Compact(inner) =>
+				assert_eq!(resolve(inner).def, TypeDef::Primitive(TypeDefPrimitive::U32)),
+			_ => panic!("`_data` is compact"),
+		}
+		assert_eq!(variant_names(module.event.as_ref().unwrap().ty), vec!["TestEvent"]);
+		let event = resolve(module.event.as_ref().unwrap().ty);
+		assert_eq!(event.path.last().map(String::as_str), Some("RawEvent"));
+
+		let module2 = &metadata.modules[2];
+		assert!(variant_names(module2.calls.as_ref().unwrap().ty).is_empty());
+		assert_eq!(variant_names(module2.event.as_ref().unwrap().ty), vec!["TestEvent"]);
+		let storage = module2.storage.as_ref().unwrap();
+		assert_eq!(storage.entries.len(), 1);
+		match &storage.entries[0].ty {
+			StorageEntryTypeV14::Plain(ty) =>
+				assert_eq!(resolve(*ty).def, TypeDef::Primitive(TypeDefPrimitive::U32)),
+			_ => panic!("storage entry is a plain value"),
+		}
+
+		assert_eq!(
+			metadata.extrinsic.signed_extensions,
+			vec![
+				DecodeDifferent::Decoded("testextension".to_string()),
+				DecodeDifferent::Decoded("testextension2".to_string()),
+			],
+		);
+	}
 }
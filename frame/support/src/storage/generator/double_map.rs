@@ -224,6 +224,7 @@ impl<K1, K2, V, G> storage::StorageDoubleMap<K1, K2, V> for G where
 			prefix: prefix.clone(),
 			previous_key: prefix,
 			drain: false,
+			phantom: Default::default(),
 			closure: |_raw_key, mut raw_value| V::decode(&mut raw_value),
 		}
 	}
@@ -348,6 +349,7 @@ impl<
 			prefix: prefix.clone(),
 			previous_key: prefix,
 			drain: false,
+			phantom: Default::default(),
 			closure: |raw_key_without_prefix, mut raw_value| {
 				let mut key_material = G::Hasher2::reverse(raw_key_without_prefix);
 				Ok((K2::decode(&mut key_material)?, V::decode(&mut raw_value)?))
@@ -367,6 +369,7 @@ impl<
 			prefix: prefix.clone(),
 			previous_key: prefix,
 			drain: false,
+			phantom: Default::default(),
 			closure: |raw_key_without_prefix, mut raw_value| {
 				let mut k1_k2_material = G::Hasher1::reverse(raw_key_without_prefix);
 				let k1 = K1::decode(&mut k1_k2_material)?;
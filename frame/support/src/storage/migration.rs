@@ -175,7 +175,7 @@ pub fn storage_iter_with_suffix<T: Decode + Sized>(
 		Ok((raw_key_without_prefix.to_vec(), value))
 	};
 
-	PrefixIterator { prefix, previous_key, drain: false, closure }
+	PrefixIterator::new(prefix, previous_key, closure)
 }
 
 /// Construct iterator to iterate over map items in `module` for the map called `item`.
@@ -203,7 +203,7 @@ pub fn storage_key_iter_with_suffix<K: Decode + Sized, T: Decode + Sized, H: Rev
 		let value = T::decode(&mut &raw_value[..])?;
 		Ok((key, value))
 	};
-	PrefixIterator { prefix, previous_key, drain: false, closure }
+	PrefixIterator::new(prefix, previous_key, closure)
 }
 
 /// Get a particular value in storage by the `module`, the map's `item` name and the key `hash`.
@@ -332,12 +332,11 @@ pub fn move_prefix(from_prefix: &[u8], to_prefix: &[u8]) {
 		return
 	}
 
-	let iter = PrefixIterator {
-		prefix: from_prefix.to_vec(),
-		previous_key: from_prefix.to_vec(),
-		drain: true,
-		closure: |key, value| Ok((key.to_vec(), value.to_vec())),
-	};
+	let iter = PrefixIterator::<_>::new(
+		from_prefix.to_vec(),
+		from_prefix.to_vec(),
+		|key, value| Ok((key.to_vec(), value.to_vec())),
+	).drain();
 
 	for (key, value) in iter {
 		let full_key = [to_prefix, &key].concat();
@@ -22,6 +22,8 @@
 pub mod tokens;
 pub use tokens::fungible;
 pub use tokens::fungibles;
+pub use tokens::nonfungible;
+pub use tokens::nonfungibles;
 pub use tokens::currency::{
 	Currency, LockIdentifier, LockableCurrency, ReservableCurrency, VestingSchedule,
 };
@@ -50,7 +52,7 @@ mod misc;
 pub use misc::{
 	Len, Get, GetDefault, HandleLifetime, TryDrop, Time, UnixTime, IsType, IsSubType, ExecuteBlock,
 	SameOrOther, OnNewAccount, OnKilledAccount, OffchainWorker, GetBacking, Backing, ExtrinsicCall,
-	EnsureInherentsAreFirst,
+	EnsureInherentsAreFirst, PreimageProvider, PreimageRecipient,
 };
 
 mod stored_map;
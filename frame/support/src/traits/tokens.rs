@@ -19,6 +19,8 @@
 
 pub mod fungible;
 pub mod fungibles;
+pub mod nonfungible;
+pub mod nonfungibles;
 pub mod currency;
 pub mod imbalance;
 mod misc;
@@ -20,6 +20,7 @@
 use sp_std::fmt::Debug;
 use sp_runtime::traits::MaybeSerializeDeserialize;
 use crate::dispatch::{DispatchResult, DispatchError};
+use crate::metadata::TypeInfo;
 use super::misc::{Balance, WithdrawReasons, ExistenceRequirement};
 use super::imbalance::{Imbalance, SignedImbalance};
 
@@ -32,7 +33,7 @@ pub use lockable::{LockableCurrency, VestingSchedule, LockIdentifier};
 /// Abstraction over a fungible assets system.
 pub trait Currency<AccountId> {
 	/// The balance of an account.
-	type Balance: Balance + MaybeSerializeDeserialize + Debug;
+	type Balance: Balance + MaybeSerializeDeserialize + Debug + TypeInfo;
 
 	/// The opaque token type for an imbalance. This is returned by unbalanced operations
 	/// and must be dealt with. It may be dropped but cannot be cloned.
@@ -284,7 +284,7 @@ fn call_codec() {
 #[test]
 fn test_metadata() {
 	use frame_metadata::*;
-	let expected_metadata: RuntimeMetadataLastVersion = RuntimeMetadataLastVersion {
+	let expected_metadata: RuntimeMetadataV12 = RuntimeMetadataV12 {
 		modules: DecodeDifferent::Encode(&[
 			ModuleMetadata {
 				name: DecodeDifferent::Encode("System"),
@@ -217,6 +217,25 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type DoubleMap2<T> = StorageDoubleMap<_, Twox64Concat, u16, Blake2_128Concat, u32, u64>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn nmap)]
+	pub type NMap<T> = StorageNMap<_, storage::types::Key<Blake2_128Concat, u8>, u32>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn nmap2)]
+	pub type NMap2<T> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, u16>,
+			NMapKey<Blake2_128Concat, u32>,
+		),
+		u64,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn counted_map)]
+	pub type CountedMap<T> = CountedStorageMap<_, Twox64Concat, u8, u32>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn conditional_value)]
 	#[cfg(feature = "conditional-storage")]
@@ -239,6 +258,18 @@ pub mod pallet {
 		u32,
 	>;
 
+	#[cfg(feature = "conditional-storage")]
+	#[pallet::storage]
+	#[pallet::getter(fn conditional_nmap)]
+	pub type ConditionalNMap<T> = StorageNMap<
+		_,
+		(
+			storage::types::Key<Blake2_128Concat, u8>,
+			storage::types::Key<Twox64Concat, u16>,
+		),
+		u32,
+	>;
+
 	#[pallet::genesis_config]
 	#[derive(Default)]
 	pub struct GenesisConfig {
@@ -569,11 +600,36 @@ fn storage_expand() {
 		assert_eq!(unhashed::get::<u64>(&k), Some(3u64));
 		assert_eq!(&k[..32], &<pallet::DoubleMap2<Runtime>>::final_prefix());
 
+		pallet::NMap::<Runtime>::insert((&1,), &3);
+		let mut k = [twox_128(b"Example"), twox_128(b"NMap")].concat();
+		k.extend(1u8.using_encoded(blake2_128_concat));
+		assert_eq!(unhashed::get::<u32>(&k), Some(3u32));
+		assert_eq!(&k[..32], &<pallet::NMap<Runtime>>::final_prefix());
+		assert_eq!(pallet::Pallet::<Runtime>::nmap((1,)), Some(3));
+
+		pallet::NMap2::<Runtime>::insert((&1, &2), &3);
+		let mut k = [twox_128(b"Example"), twox_128(b"NMap2")].concat();
+		k.extend(1u16.using_encoded(twox_64_concat));
+		k.extend(2u32.using_encoded(blake2_128_concat));
+		assert_eq!(unhashed::get::<u64>(&k), Some(3u64));
+		assert_eq!(&k[..32], &<pallet::NMap2<Runtime>>::final_prefix());
+		assert_eq!(pallet::Pallet::<Runtime>::nmap2((1, 2)), Some(3));
+
+		pallet::CountedMap::<Runtime>::insert(1, 2);
+		let mut k = [twox_128(b"Example"), twox_128(b"CountedMap")].concat();
+		k.extend(1u8.using_encoded(twox_64_concat));
+		assert_eq!(unhashed::get::<u32>(&k), Some(2u32));
+		assert_eq!(pallet::Pallet::<Runtime>::counted_map(1), Some(2));
+		let k = [twox_128(b"Example"), twox_128(b"CounterForCountedMap")].concat();
+		assert_eq!(unhashed::get::<u32>(&k), Some(1u32));
+		assert_eq!(pallet::CountedMap::<Runtime>::count(), 1);
+
 		#[cfg(feature = "conditional-storage")]
 		{
 			pallet::ConditionalValue::<Runtime>::put(1);
 			pallet::ConditionalMap::<Runtime>::insert(1, 2);
 			pallet::ConditionalDoubleMap::<Runtime>::insert(1, 2, 3);
+			pallet::ConditionalNMap::<Runtime>::insert((1, 2), 3);
 		}
 	})
 }
@@ -699,6 +755,57 @@ fn metadata() {
 					default: DecodeDifferent::Decoded(vec![0]),
 					documentation: DecodeDifferent::Decoded(vec![]),
 				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Decoded("NMap".to_string()),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::NMap {
+						keys: DecodeDifferent::Decoded(vec!["u8".to_string()]),
+						hashers: DecodeDifferent::Decoded(vec![
+							StorageHasher::Blake2_128Concat,
+						]),
+						value: DecodeDifferent::Decoded("u32".to_string()),
+					},
+					default: DecodeDifferent::Decoded(vec![0]),
+					documentation: DecodeDifferent::Decoded(vec![]),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Decoded("NMap2".to_string()),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::NMap {
+						keys: DecodeDifferent::Decoded(vec![
+							"u16".to_string(),
+							"u32".to_string(),
+						]),
+						hashers: DecodeDifferent::Decoded(vec![
+							StorageHasher::Twox64Concat,
+							StorageHasher::Blake2_128Concat,
+						]),
+						value: DecodeDifferent::Decoded("u64".to_string()),
+					},
+					default: DecodeDifferent::Decoded(vec![0]),
+					documentation: DecodeDifferent::Decoded(vec![]),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Decoded("CountedMap".to_string()),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::Map {
+						key: DecodeDifferent::Decoded("u8".to_string()),
+						value: DecodeDifferent::Decoded("u32".to_string()),
+						hasher: StorageHasher::Twox64Concat,
+						unused: false,
+					},
+					default: DecodeDifferent::Decoded(vec![0]),
+					documentation: DecodeDifferent::Decoded(vec![]),
+				},
+				StorageEntryMetadata {
+					name: DecodeDifferent::Decoded("CounterForCountedMap".to_string()),
+					modifier: StorageEntryModifier::Default,
+					ty: StorageEntryType::Plain(DecodeDifferent::Decoded("u32".to_string())),
+					default: DecodeDifferent::Decoded(vec![0, 0, 0, 0]),
+					documentation: DecodeDifferent::Decoded(vec![
+						"Counter for the related counted storage map".to_string(),
+					]),
+				},
 				#[cfg(feature = "conditional-storage")] StorageEntryMetadata {
 					name: DecodeDifferent::Decoded("ConditionalValue".to_string()),
 					modifier: StorageEntryModifier::Optional,
@@ -731,6 +838,20 @@ fn metadata() {
 					default: DecodeDifferent::Decoded(vec![0]),
 					documentation: DecodeDifferent::Decoded(vec![]),
 				},
+				#[cfg(feature = "conditional-storage")] StorageEntryMetadata {
+					name: DecodeDifferent::Decoded("ConditionalNMap".to_string()),
+					modifier: StorageEntryModifier::Optional,
+					ty: StorageEntryType::NMap {
+						keys: DecodeDifferent::Decoded(vec!["u8".to_string(), "u16".to_string()]),
+						hashers: DecodeDifferent::Decoded(vec![
+							StorageHasher::Blake2_128Concat,
+							StorageHasher::Twox64Concat,
+						]),
+						value: DecodeDifferent::Decoded("u32".to_string()),
+					},
+					default: DecodeDifferent::Decoded(vec![0]),
+					documentation: DecodeDifferent::Decoded(vec![]),
+				},
 			]),
 		})),
 		calls: Some(DecodeDifferent::Decoded(vec![
@@ -862,6 +983,93 @@ fn metadata() {
 	pretty_assertions::assert_eq!(pallet_metadata, expected_pallet_metadata);
 }
 
+#[test]
+fn metadata_v14() {
+	use frame_metadata::{*, types::*};
+	use codec::{Decode, Encode};
+
+	let metadata = match RuntimeMetadataPrefixed::decode(&mut &Runtime::metadata_v14().encode()[..])
+		.unwrap().1
+	{
+		RuntimeMetadata::V14(metadata) => metadata,
+		_ => panic!("metadata has been bump, test needs to be updated"),
+	};
+	let resolve = |ty| &metadata.types.resolve(ty).expect("type is in the registry").def;
+	let variants = |ty| match resolve(ty) {
+		TypeDef::Variant(variants) => variants.clone(),
+		_ => panic!("type is a variant"),
+	};
+	let name = |name: &DecodeDifferent<&'static str, String>| match name {
+		DecodeDifferent::Decoded(name) => name.clone(),
+		DecodeDifferent::Encode(name) => name.to_string(),
+	};
+	let u32_def = TypeDef::Primitive(TypeDefPrimitive::U32);
+
+	let pallet = &metadata.modules[1];
+	assert_eq!(name(&pallet.name), "Example");
+
+	let calls = variants(pallet.calls.as_ref().unwrap().ty);
+	assert_eq!(
+		calls.iter().map(|call| call.name.as_str()).collect::<Vec<_>>(),
+		vec!["foo", "foo_transactional", "foo_no_post_info"],
+	);
+	assert_eq!(calls[0].docs, vec![" Doc comment put in metadata"]);
+	assert_eq!(calls[0].fields[0].name, Some("_foo".to_string()));
+	match resolve(calls[0].fields[0].ty) {
+		TypeDef::Compact(inner) => assert_eq!(resolve(*inner), &u32_def),
+		_ => panic!("`_foo` is compact"),
+	}
+	assert_eq!(calls[0].fields[1].name, Some("_bar".to_string()));
+	assert_eq!(resolve(calls[0].fields[1].ty), &u32_def);
+
+	let events = variants(pallet.event.as_ref().unwrap().ty);
+	assert_eq!(
+		events.iter().map(|event| (event.name.as_str(), event.index)).collect::<Vec<_>>(),
+		vec![("Proposed", 0), ("Spending", 1), ("Something", 2), ("SomethingElse", 3)],
+	);
+	assert_eq!(resolve(events[2].fields[0].ty), &u32_def);
+
+	let constant = pallet.constants.iter()
+		.find(|constant| name(&constant.name) == "MyGetParam")
+		.unwrap();
+	assert_eq!(resolve(constant.ty), &u32_def);
+
+	let storage = pallet.storage.as_ref().unwrap();
+	let entry = |entry_name: &str| &storage.entries.iter()
+		.find(|entry| name(&entry.name) == entry_name)
+		.unwrap()
+		.ty;
+	match entry("CountedMap") {
+		StorageEntryTypeV14::Map { hashers, key, value } => {
+			assert_eq!(hashers, &vec![StorageHasher::Twox64Concat]);
+			assert_eq!(resolve(*key), &TypeDef::Primitive(TypeDefPrimitive::U8));
+			assert_eq!(resolve(*value), &u32_def);
+		},
+		_ => panic!("`CountedMap` is a map"),
+	}
+	match entry("CounterForCountedMap") {
+		StorageEntryTypeV14::Plain(value) => assert_eq!(resolve(*value), &u32_def),
+		_ => panic!("counter is a plain value"),
+	}
+	match entry("NMap2") {
+		StorageEntryTypeV14::Map { hashers, key, value } => {
+			assert_eq!(
+				hashers,
+				&vec![StorageHasher::Twox64Concat, StorageHasher::Blake2_128Concat],
+			);
+			match resolve(*key) {
+				TypeDef::Tuple(keys) => assert_eq!(
+					keys.iter().map(|key| resolve(*key).clone()).collect::<Vec<_>>(),
+					vec![TypeDef::Primitive(TypeDefPrimitive::U16), u32_def.clone()],
+				),
+				_ => panic!("`NMap2` key is a tuple"),
+			}
+			assert_eq!(resolve(*value), &TypeDef::Primitive(TypeDefPrimitive::U64));
+		},
+		_ => panic!("`NMap2` is a map"),
+	}
+}
+
 #[test]
 fn test_pallet_info_access() {
 	assert_eq!(<System as frame_support::traits::PalletInfoAccess>::name(), "System");
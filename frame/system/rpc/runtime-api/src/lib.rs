@@ -23,6 +23,29 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
+use sp_runtime::{ApplyExtrinsicResult, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// Storage items that are written to the state before an extrinsic is dry-run.
+///
+/// A value of `None` removes the item from storage. Overrides of keys that are only read when the
+/// runtime is loaded or a block is finalized, like `:code`, have no effect.
+pub type StorageOverrides = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+/// The outcome of dry-running an extrinsic with [`DryRunApi`].
+#[derive(Eq, PartialEq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct DryRunEffects<Event, FeeDetails> {
+	/// The result of applying the extrinsic.
+	pub result: ApplyExtrinsicResult,
+	/// The events that were deposited while applying the extrinsic.
+	pub events: Vec<Event>,
+	/// The fee that would be charged, computed from the post dispatch weight.
+	pub fee_details: FeeDetails,
+	/// The post dispatch weight of the extrinsic.
+	pub actual_weight: u64,
+}
+
 sp_api::decl_runtime_apis! {
 	/// The API to query account nonce (aka transaction index).
 	pub trait AccountNonceApi<AccountId, Index> where
@@ -32,4 +55,18 @@ sp_api::decl_runtime_apis! {
 		/// Get current account nonce of given `AccountId`.
 		fn account_nonce(account: AccountId) -> Index;
 	}
+
+	/// The API to dry-run an extrinsic on top of modified state.
+	pub trait DryRunApi<Event, FeeDetails> where
+		Event: codec::Codec,
+		FeeDetails: codec::Codec,
+	{
+		/// Apply `extrinsic` after writing `overrides` to the state and return its effects.
+		///
+		/// None of the changes are persisted.
+		fn dry_run(
+			extrinsic: Block::Extrinsic,
+			overrides: StorageOverrides,
+		) -> DryRunEffects<Event, FeeDetails>;
+	}
 }
@@ -111,6 +111,31 @@ pub struct BlockCheckParams<Block: BlockT> {
 	pub import_existing: bool,
 }
 
+/// Block import state, downloaded by state sync.
+#[derive(Clone)]
+pub struct ImportedState<B: BlockT> {
+	/// Target block hash.
+	pub block: B::Hash,
+	/// State keys and values.
+	pub state: sp_core::storage::Storage,
+}
+
+impl<B: BlockT> std::fmt::Debug for ImportedState<B> {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		fmt.debug_struct("ImportedState")
+			.field("block", &self.block)
+			.finish()
+	}
+}
+
+impl<B: BlockT> PartialEq for ImportedState<B> {
+	fn eq(&self, other: &Self) -> bool {
+		self.block == other.block
+	}
+}
+
+impl<B: BlockT> Eq for ImportedState<B> {}
+
 /// Data required to import a Block.
 #[non_exhaustive]
 pub struct BlockImportParams<Block: BlockT, Transaction> {
@@ -162,6 +187,9 @@ pub struct BlockImportParams<Block: BlockT, Transaction> {
 	pub allow_missing_state: bool,
 	/// Re-validate existing block.
 	pub import_existing: bool,
+	/// Imported state, downloaded by state sync. If this is `Some(_)`, the block is not
+	/// executed and the given state is set as the state of the block instead.
+	pub state: Option<ImportedState<Block>>,
 	/// Cached full header hash (with post-digests applied).
 	pub post_hash: Option<Block::Hash>,
 }
@@ -184,6 +212,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			fork_choice: None,
 			allow_missing_state: false,
 			import_existing: false,
+			state: None,
 			post_hash: None,
 		}
 	}
@@ -229,6 +258,7 @@ impl<Block: BlockT, Transaction> BlockImportParams<Block, Transaction> {
 			allow_missing_state: self.allow_missing_state,
 			fork_choice: self.fork_choice,
 			import_existing: self.import_existing,
+			state: self.state,
 			post_hash: self.post_hash,
 		}
 	}
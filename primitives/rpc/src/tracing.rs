@@ -0,0 +1,124 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types for working with tracing data
+
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+/// Container for all related spans and events for the block being traced.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTrace {
+	/// Hash of the block being traced
+	pub block_hash: String,
+	/// Parent hash
+	pub parent_hash: String,
+	/// Module targets that were recorded by the tracing subscriber.
+	/// Empty string means record all targets.
+	pub tracing_targets: String,
+	/// Storage key prefixes that events were filtered by.
+	/// Empty string means that no events were filtered out.
+	pub storage_keys: String,
+	/// Vec of tracing spans
+	pub spans: Vec<Span>,
+	/// Vec of tracing events
+	pub events: Vec<Event>,
+}
+
+/// Represents a tracing event, complete with recorded data.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+	/// Event target
+	pub target: String,
+	/// Associated data
+	pub data: Data,
+	/// Parent id, if it exists
+	pub parent_id: Option<u64>,
+	/// Index of the extrinsic that was executing when the event was emitted, if any
+	pub extrinsic_index: Option<u32>,
+}
+
+/// Represents a single instance of a tracing span.
+///
+/// Exiting a span does not imply that the span will not be re-entered.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+	/// id for this span
+	pub id: u64,
+	/// id of the parent span, if any
+	pub parent_id: Option<u64>,
+	/// Name of this span
+	pub name: String,
+	/// Target, typically module
+	pub target: String,
+	/// Indicates if the span is from wasm
+	pub wasm: bool,
+	/// Values recorded to this span
+	pub data: Data,
+	/// Index of the extrinsic that was executing when the span was created, if any
+	pub extrinsic_index: Option<u32>,
+}
+
+/// Holds associated values for a tracing span or event.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Data {
+	/// Recorded values converted to strings
+	pub string_values: BTreeMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::assert_deser;
+
+	#[test]
+	fn should_serialize_and_deserialize() {
+		let mut data = Data::default();
+		data.string_values.insert("method".into(), "Put".into());
+		assert_deser(
+			concat!(
+				r#"{"target":"state","data":{"stringValues":{"method":"Put"}},"#,
+				r#""parentId":2,"extrinsicIndex":0}"#,
+			),
+			Event {
+				target: "state".into(),
+				data,
+				parent_id: Some(2),
+				extrinsic_index: Some(0),
+			},
+		);
+		assert_deser(
+			concat!(
+				r#"{"id":1,"parentId":null,"name":"init_block","target":"frame_executive","#,
+				r#""wasm":true,"data":{"stringValues":{}},"extrinsicIndex":null}"#,
+			),
+			Span {
+				id: 1,
+				parent_id: None,
+				name: "init_block".into(),
+				target: "frame_executive".into(),
+				wasm: true,
+				data: Data::default(),
+				extrinsic_index: None,
+			},
+		);
+	}
+}
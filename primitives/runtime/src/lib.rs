@@ -546,6 +546,8 @@ pub enum TokenError {
 	Underflow,
 	/// An overflow would occur.
 	Overflow,
+	/// Operation is not supported by the asset.
+	Unsupported,
 }
 
 impl From<TokenError> for &'static str {
@@ -559,6 +561,7 @@ impl From<TokenError> for &'static str {
 			TokenError::Frozen => "Funds exist but are frozen",
 			TokenError::Underflow => "An underflow would occur",
 			TokenError::Overflow => "An overflow would occur",
+			TokenError::Unsupported => "Operation is not supported by the asset",
 		}
 	}
 }
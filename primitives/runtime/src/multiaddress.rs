@@ -18,7 +18,8 @@
 //! MultiAddress type is a wrapper for multiple downstream account formats.
 
 use codec::{Encode, Decode};
-use sp_std::vec::Vec;
+use frame_metadata::types::{Field, Registry, Type, TypeDef, TypeInfo, Variant};
+use sp_std::{vec, vec::Vec};
 
 /// A multi-format address wrapper for on-chain accounts.
 #[derive(Encode, Decode, PartialEq, Eq, Clone, crate::RuntimeDebug)]
@@ -53,6 +54,44 @@ where
 	}
 }
 
+impl<AccountId, AccountIndex> TypeInfo for MultiAddress<AccountId, AccountIndex>
+where
+	AccountId: TypeInfo + 'static,
+	AccountIndex: TypeInfo + 'static,
+{
+	type Identity = Self;
+
+	fn type_info(registry: &mut Registry) -> Type {
+		let account_id = registry.register_type::<AccountId>();
+		let account_index = registry.register_type::<AccountIndex>();
+		let field = |ty, type_name: &'static str| vec![Field {
+			name: None,
+			ty,
+			type_name: Some(type_name.into()),
+			docs: Vec::new(),
+		}];
+		let variants = vec![
+			("Id", field(account_id, "AccountId")),
+			("Index", field(registry.register_compact(account_index), "AccountIndex")),
+			("Raw", field(registry.register_type::<Vec<u8>>(), "Vec<u8>")),
+			("Address32", field(registry.register_type::<[u8; 32]>(), "[u8; 32]")),
+			("Address20", field(registry.register_type::<[u8; 20]>(), "[u8; 20]")),
+		];
+		Type::new(
+			vec!["sp_runtime".into(), "multiaddress".into(), "MultiAddress".into()],
+			TypeDef::Variant(variants.into_iter()
+				.enumerate()
+				.map(|(index, (name, fields))| Variant {
+					name: name.into(),
+					fields,
+					index: index as u8,
+					docs: Vec::new(),
+				})
+				.collect()),
+		).type_params(vec![account_id, account_index])
+	}
+}
+
 impl<AccountId, AccountIndex> From<AccountId> for MultiAddress<AccountId, AccountIndex> {
 	fn from(a: AccountId) -> Self {
 		Self::Id(a)
@@ -46,7 +46,9 @@ pub use std_reexport::*;
 #[cfg(feature = "std")]
 pub use execution::*;
 #[cfg(feature = "std")]
-pub use log::{debug, warn, trace, error as log_error};
+pub use log::{debug, warn, error as log_error};
+#[cfg(feature = "std")]
+pub use tracing::trace;
 
 /// In no_std we skip logs for state_machine, this macro
 /// is a noops.
@@ -777,6 +779,10 @@ mod execution {
 		I::Item: AsRef<[u8]>,
 	{
 		let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+		// Always prove the child trie root in the main trie, even if no keys are requested.
+		proving_backend
+			.storage(child_info.prefixed_storage_key().as_slice())
+			.map_err(|e| Box::new(e) as Box<dyn Error>)?;
 		for key in keys.into_iter() {
 			proving_backend
 				.child_storage(child_info, key.as_ref())
@@ -785,6 +791,137 @@ mod execution {
 		Ok(proving_backend.extract_proof())
 	}
 
+	/// Generate range storage read proof.
+	///
+	/// The proof covers the keys following `start_at` (exclusive) in lexicographic order, until
+	/// the accumulated size of keys and values exceeds `size_limit`. At least one key is always
+	/// included. Returns the proof and the number of keys it covers.
+	pub fn prove_range_read_with_size<B, H>(
+		mut backend: B,
+		child_info: Option<&ChildInfo>,
+		start_at: &[u8],
+		size_limit: usize,
+	) -> Result<(StorageProof, u32), Box<dyn Error>>
+	where
+		B: Backend<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let trie_backend = backend.as_trie_backend()
+			.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<dyn Error>)?;
+		prove_range_read_with_size_on_trie_backend(trie_backend, child_info, start_at, size_limit)
+	}
+
+	/// Generate range storage read proof on pre-created trie backend.
+	pub fn prove_range_read_with_size_on_trie_backend<S, H>(
+		trie_backend: &TrieBackend<S, H>,
+		child_info: Option<&ChildInfo>,
+		start_at: &[u8],
+		size_limit: usize,
+	) -> Result<(StorageProof, u32), Box<dyn Error>>
+	where
+		S: trie_backend_essence::TrieBackendStorage<H>,
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend = proving_backend::ProvingBackend::<_, H>::new(trie_backend);
+		let mut count = 0;
+		let mut size = 0;
+		let mut next_key = start_at.to_vec();
+		loop {
+			let key = match next_range_key(&proving_backend, child_info, &next_key)? {
+				Some(key) => key,
+				None => break,
+			};
+			let value = range_value(&proving_backend, child_info, &key)?;
+			count += 1;
+			size += key.len() + value.map_or(0, |v| v.len());
+			if size > size_limit {
+				break
+			}
+			next_key = key;
+		}
+		Ok((proving_backend.extract_proof(), count))
+	}
+
+	/// Check storage range proof, generated by `prove_range_read_with_size` call.
+	///
+	/// Returns the key-value pairs covered by the proof and whether the end of the range
+	/// (i.e. the end of the storage) was reached.
+	pub fn read_range_proof_check<H>(
+		root: H::Out,
+		proof: StorageProof,
+		child_info: Option<&ChildInfo>,
+		start_at: &[u8],
+	) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+	where
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+		read_range_proof_check_on_proving_backend(&proving_backend, child_info, start_at)
+	}
+
+	/// Check storage range proof on pre-created proving backend.
+	///
+	/// The range ends at the first key which is not covered by the proof.
+	pub fn read_range_proof_check_on_proving_backend<H>(
+		proving_backend: &TrieBackend<MemoryDB<H>, H>,
+		child_info: Option<&ChildInfo>,
+		start_at: &[u8],
+	) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool), Box<dyn Error>>
+	where
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let mut values = Vec::new();
+		let mut next_key = start_at.to_vec();
+		loop {
+			let key = match next_range_key(proving_backend, child_info, &next_key) {
+				Ok(Some(key)) => key,
+				Ok(None) => return Ok((values, true)),
+				// Incomplete proof, the range stops here.
+				Err(_) => return Ok((values, false)),
+			};
+			match range_value(proving_backend, child_info, &key) {
+				Ok(Some(value)) => values.push((key.clone(), value)),
+				Ok(None) => (),
+				Err(_) => return Ok((values, false)),
+			}
+			next_key = key;
+		}
+	}
+
+	fn next_range_key<B, H>(
+		backend: &B,
+		child_info: Option<&ChildInfo>,
+		key: &[u8],
+	) -> Result<Option<Vec<u8>>, Box<dyn Error>>
+	where
+		B: Backend<H>,
+		H: Hasher,
+	{
+		match child_info {
+			Some(child_info) => backend.next_child_storage_key(child_info, key),
+			None => backend.next_storage_key(key),
+		}.map_err(|e| Box::new(e) as Box<dyn Error>)
+	}
+
+	fn range_value<B, H>(
+		backend: &B,
+		child_info: Option<&ChildInfo>,
+		key: &[u8],
+	) -> Result<Option<Vec<u8>>, Box<dyn Error>>
+	where
+		B: Backend<H>,
+		H: Hasher,
+	{
+		match child_info {
+			Some(child_info) => backend.child_storage(child_info, key),
+			None => backend.storage(key),
+		}.map_err(|e| Box::new(e) as Box<dyn Error>)
+	}
+
 	/// Check storage read proof, generated by `prove_read` call.
 	pub fn read_proof_check<H, I>(
 		root: H::Out,
@@ -832,6 +969,26 @@ mod execution {
 		Ok(result)
 	}
 
+	/// Check the child trie root contained in a proof generated by `prove_child_read` call.
+	///
+	/// Returns the child trie root that is stored in the main trie under the prefixed storage
+	/// key of `child_info`, or `None` if the child trie does not exist.
+	pub fn read_child_root_proof_check<H>(
+		root: H::Out,
+		proof: StorageProof,
+		child_info: &ChildInfo,
+	) -> Result<Option<Vec<u8>>, Box<dyn Error>>
+	where
+		H: Hasher,
+		H::Out: Ord + Codec,
+	{
+		let proving_backend = create_proof_check_backend::<H>(root, proof)?;
+		read_proof_check_on_proving_backend(
+			&proving_backend,
+			child_info.prefixed_storage_key().as_slice(),
+		)
+	}
+
 	/// Check storage read proof on pre-created proving backend.
 	pub fn read_proof_check_on_proving_backend<H>(
 		proving_backend: &TrieBackend<MemoryDB<H>, H>,
@@ -1457,6 +1614,88 @@ mod tests {
 			local_result2.into_iter().collect::<Vec<_>>(),
 			vec![(b"value2".to_vec(), None)],
 		);
+		// the child trie root is part of the proof
+		let remote_backend = trie_backend::tests::test_trie();
+		let child_root = remote_backend
+			.storage(child_info.prefixed_storage_key().as_slice())
+			.unwrap();
+		assert!(child_root.is_some());
+		assert_eq!(
+			read_child_root_proof_check::<BlakeTwo256>(
+				remote_root,
+				remote_proof.clone(),
+				child_info,
+			).unwrap(),
+			child_root,
+		);
+		let remote_proof = prove_child_read(
+			remote_backend,
+			child_info,
+			std::iter::empty::<Vec<u8>>(),
+		).unwrap();
+		assert_eq!(
+			read_child_root_proof_check::<BlakeTwo256>(remote_root, remote_proof, child_info)
+				.unwrap(),
+			child_root,
+		);
+	}
+
+	#[test]
+	fn prove_range_read_and_proof_check_works() {
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let all = remote_backend.pairs();
+
+		let mut start_at = Vec::new();
+		let mut collected = Vec::new();
+		let mut completed = false;
+		while !completed {
+			let remote_backend = trie_backend::tests::test_trie();
+			let (proof, count) = prove_range_read_with_size(
+				remote_backend,
+				None,
+				&start_at,
+				16,
+			).unwrap();
+			let (values, complete) = read_range_proof_check::<BlakeTwo256>(
+				remote_root,
+				proof,
+				None,
+				&start_at,
+			).unwrap();
+			assert!(count > 0);
+			assert!(values.len() as u32 >= count - 1);
+			if let Some((key, _)) = values.last() {
+				start_at = key.clone();
+			}
+			collected.extend(values);
+			completed = complete;
+		}
+
+		let mut all = all;
+		all.sort();
+		assert_eq!(collected, all);
+
+		// on child trie
+		let child_info = ChildInfo::new_default(b"sub1");
+		let remote_backend = trie_backend::tests::test_trie();
+		let (proof, _) = prove_range_read_with_size(
+			remote_backend,
+			Some(&child_info),
+			&[],
+			usize::MAX,
+		).unwrap();
+		let (values, complete) = read_range_proof_check::<BlakeTwo256>(
+			remote_root,
+			proof,
+			Some(&child_info),
+			&[],
+		).unwrap();
+		assert!(complete);
+		assert_eq!(
+			values,
+			vec![(b"value3".to_vec(), vec![142]), (b"value4".to_vec(), vec![124])],
+		);
 	}
 
 	#[test]
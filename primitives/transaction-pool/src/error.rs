@@ -60,6 +60,9 @@ pub enum Error {
 	#[error("Transaction couldn't enter the pool because of the limit")]
 	ImmediatelyDropped,
 
+	#[error("Too many transactions of the same sender in the pool")]
+	TooManyFromSender,
+
 	#[error("Transaction cannot be propagated and the local node does not author blocks")]
 	Unactionable,
 
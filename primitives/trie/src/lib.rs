@@ -28,6 +28,7 @@ mod trie_stream;
 use sp_std::{boxed::Box, marker::PhantomData, vec::Vec, borrow::Borrow};
 use hash_db::{Hasher, Prefix};
 use trie_db::proof::{generate_proof, verify_proof};
+use trie_db::{NibbleVec, NodeCodec as NodeCodecT, node::{Node, NodeHandle}};
 pub use trie_db::proof::VerifyError;
 /// Our `NodeCodec`-specific error.
 pub use error::Error;
@@ -327,6 +328,63 @@ pub fn record_all_keys<L: TrieConfiguration, DB>(
 	Ok(())
 }
 
+/// Call `f` for the hash and prefix of all nodes of the trie with the given root.
+///
+/// Nodes are read with `get` and the children of a node are only visited when `f` returns
+/// `true`. Nodes that can't be found are skipped, so this also works on partially pruned tries.
+pub fn visit_nodes<L: TrieLayout, G, F>(
+	root: &TrieHash<L>,
+	get: G,
+	mut f: F,
+) -> Result<(), Box<TrieError<L>>> where
+	G: Fn(&TrieHash<L>, Prefix) -> Option<DBValue>,
+	F: FnMut(&TrieHash<L>, Prefix) -> bool,
+{
+	let mut stack = Vec::new();
+	stack.push((NibbleVec::new(), root.clone()));
+	while let Some((mut prefix, hash)) = stack.pop() {
+		let data = match get(&hash, prefix.as_prefix()) {
+			Some(data) => data,
+			None => continue,
+		};
+		if !f(&hash, prefix.as_prefix()) {
+			continue;
+		}
+
+		let node = <L::Codec as NodeCodecT<_>>::decode(&data)
+			.map_err(|e| Box::new(TrieError::<L>::DecoderError(hash, e)))?;
+		let (partial, children) = match node {
+			Node::Empty | Node::Leaf(..) => continue,
+			Node::Extension(partial, child) => {
+				prefix.append_partial(partial.right());
+				if let NodeHandle::Hash(child) = child {
+					let mut child_hash = TrieHash::<L>::default();
+					child_hash.as_mut().copy_from_slice(child);
+					stack.push((prefix, child_hash));
+				}
+				continue;
+			},
+			Node::Branch(children, _) => (None, children),
+			Node::NibbledBranch(partial, children, _) => (Some(partial), children),
+		};
+		if let Some(partial) = partial {
+			prefix.append_partial(partial.right());
+		}
+		// Inline children are part of their parent node.
+		for (i, child) in children.iter().enumerate() {
+			if let Some(NodeHandle::Hash(child)) = child {
+				let mut child_hash = TrieHash::<L>::default();
+				child_hash.as_mut().copy_from_slice(child);
+				let mut child_prefix = prefix.clone();
+				child_prefix.push(i as u8);
+				stack.push((child_prefix, child_hash));
+			}
+		}
+	}
+
+	Ok(())
+}
+
 /// Read a value from the child trie.
 pub fn read_child_trie_value<L: TrieConfiguration, DB>(
 	keyspace: &[u8],
@@ -890,4 +948,42 @@ mod tests {
 
 		assert_eq!(first_storage_root, second_storage_root);
 	}
+
+	#[test]
+	fn visit_nodes_works() {
+		let mut db = PrefixedMemoryDB::<Blake2Hasher>::default();
+		let mut root = Default::default();
+		{
+			let pairs = StandardMap {
+				alphabet: Alphabet::Low,
+				min_key: 5,
+				journal_key: 0,
+				value_mode: ValueMode::Random,
+				count: 100,
+			}.make();
+			let mut t = TrieDBMut::<Layout>::new(&mut db, &mut root);
+			for (key, value) in pairs {
+				t.insert(&key, &value).unwrap();
+			}
+		}
+		let get = |hash: &_, prefix| HashDB::get(&db, hash, prefix);
+
+		// Nodes are only found with the right prefix, so all of them are visited.
+		let mut visited = Vec::new();
+		visit_nodes::<Layout, _, _>(&root, get, |hash, prefix| {
+			visited.push(prefixed_key::<Blake2Hasher>(hash, prefix));
+			true
+		}).unwrap();
+		let mut keys = db.keys().into_iter().map(|(key, _)| key).collect::<Vec<_>>();
+		visited.sort();
+		keys.sort();
+		assert_eq!(visited, keys);
+
+		let mut visited = 0;
+		visit_nodes::<Layout, _, _>(&root, get, |_, _| {
+			visited += 1;
+			false
+		}).unwrap();
+		assert_eq!(visited, 1);
+	}
 }
@@ -39,6 +39,13 @@ pub trait BlockBuilderExt {
 		&mut self,
 		new_config: Option<ChangesTrieConfiguration>,
 	) -> Result<(), sp_blockchain::Error>;
+	/// Add child storage change extrinsic to the block.
+	fn push_child_storage_change(
+		&mut self,
+		storage_key: Vec<u8>,
+		key: Vec<u8>,
+		value: Option<Vec<u8>>,
+	) -> Result<(), sp_blockchain::Error>;
 }
 
 impl<'a, A, B> BlockBuilderExt for sc_block_builder::BlockBuilder<'a, substrate_test_runtime::Block, A, B> where
@@ -71,4 +78,13 @@ impl<'a, A, B> BlockBuilderExt for sc_block_builder::BlockBuilder<'a, substrate_
 	) -> Result<(), sp_blockchain::Error> {
 		self.push(substrate_test_runtime::Extrinsic::ChangesTrieConfigUpdate(new_config))
 	}
+
+	fn push_child_storage_change(
+		&mut self,
+		storage_key: Vec<u8>,
+		key: Vec<u8>,
+		value: Option<Vec<u8>>,
+	) -> Result<(), sp_blockchain::Error> {
+		self.push(substrate_test_runtime::Extrinsic::ChildStorageChange(storage_key, key, value))
+	}
 }
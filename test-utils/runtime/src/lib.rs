@@ -166,6 +166,8 @@ pub enum Extrinsic {
 	ChangesTrieConfigUpdate(Option<ChangesTrieConfiguration>),
 	OffchainIndexSet(Vec<u8>, Vec<u8>),
 	OffchainIndexClear(Vec<u8>),
+	/// Change a key of the default child trie with the given storage key.
+	ChildStorageChange(Vec<u8>, Vec<u8>, Option<Vec<u8>>),
 }
 
 parity_util_mem::malloc_size_of_is_0!(Extrinsic); // non-opaque extrinsic does not need this
@@ -198,6 +200,8 @@ impl BlindCheckable for Extrinsic {
 				Ok(Extrinsic::OffchainIndexSet(key, value)),
 			Extrinsic::OffchainIndexClear(key) =>
 				Ok(Extrinsic::OffchainIndexClear(key)),
+			Extrinsic::ChildStorageChange(storage_key, key, value) =>
+				Ok(Extrinsic::ChildStorageChange(storage_key, key, value)),
 		}
 	}
 }
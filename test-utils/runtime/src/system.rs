@@ -268,7 +268,9 @@ fn execute_transaction_backend(utx: &Extrinsic, extrinsic_index: u32) -> ApplyEx
 		Extrinsic::OffchainIndexClear(key) => {
 			sp_io::offchain_index::clear(&key);
 			Ok(Ok(()))
-		}
+		},
+		Extrinsic::ChildStorageChange(storage_key, key, value) =>
+			execute_child_storage_change(storage_key, key, value.as_ref().map(|v| &**v)),
 	}
 }
 
@@ -311,6 +313,18 @@ fn execute_storage_change(key: &[u8], value: Option<&[u8]>) -> ApplyExtrinsicRes
 	Ok(Ok(()))
 }
 
+fn execute_child_storage_change(
+	storage_key: &[u8],
+	key: &[u8],
+	value: Option<&[u8]>,
+) -> ApplyExtrinsicResult {
+	match value {
+		Some(value) => sp_io::default_child_storage::set(storage_key, key, value),
+		None => sp_io::default_child_storage::clear(storage_key, key),
+	}
+	Ok(Ok(()))
+}
+
 fn execute_changes_trie_config_update(new_config: Option<ChangesTrieConfiguration>) -> ApplyExtrinsicResult {
 	match new_config.clone() {
 		Some(new_config) => storage::unhashed::put_raw(
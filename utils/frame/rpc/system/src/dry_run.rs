@@ -0,0 +1,271 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2019-2021 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dry running extrinsics on top of overridden storage.
+
+use std::sync::Arc;
+
+use codec::{Codec, Decode, Encode};
+use jsonrpc_core::{
+	Error as RpcError, ErrorCode,
+	futures::future::{self as rpc_future, result},
+};
+use jsonrpc_derive::rpc;
+use sp_api::ApiExt;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	hexdisplay::HexDisplay,
+	storage::{well_known_keys, StorageData, StorageKey},
+	Bytes,
+};
+use sp_runtime::{generic::BlockId, traits};
+use sc_rpc_api::DenyUnsafe;
+
+use crate::{DryRunApi, Error, FutureResult};
+
+/// Dry run RPC methods that require the runtime to implement `DryRunApi`.
+#[rpc]
+pub trait DryRunWithOverridesApi<BlockHash> {
+	/// Dry run an extrinsic at a given block after writing `overrides` to its state.
+	///
+	/// A storage value of `None` removes the item. Overrides of well known keys that are only
+	/// read when the runtime is loaded or a block is finalized (e.g. `:code`) and of child storage
+	/// keys are rejected. Return SCALE encoded `DryRunEffects` which contain the
+	/// ApplyExtrinsicResult, the emitted events, the fee details and the post dispatch weight.
+	#[rpc(name = "system_dryRunWithOverrides")]
+	fn dry_run_with_overrides(
+		&self,
+		extrinsic: Bytes,
+		overrides: Vec<(StorageKey, Option<StorageData>)>,
+		at: Option<BlockHash>,
+	) -> FutureResult<Bytes>;
+}
+
+/// An implementation of `DryRunWithOverridesApi` on full client.
+///
+/// `Event` and `FeeDetails` are the types the runtime implements `DryRunApi` with.
+pub struct FullDryRun<C, B, Event, FeeDetails> {
+	client: Arc<C>,
+	deny_unsafe: DenyUnsafe,
+	_marker: std::marker::PhantomData<(B, Event, FeeDetails)>,
+}
+
+impl<C, B, Event, FeeDetails> FullDryRun<C, B, Event, FeeDetails> {
+	/// Create new `FullDryRun` given client.
+	pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+		FullDryRun {
+			client,
+			deny_unsafe,
+			_marker: Default::default(),
+		}
+	}
+}
+
+impl<C, Block, Event, FeeDetails> DryRunWithOverridesApi<<Block as traits::Block>::Hash>
+	for FullDryRun<C, Block, Event, FeeDetails>
+where
+	C: sp_api::ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block>,
+	C: Send + Sync + 'static,
+	C::Api: DryRunApi<Block, Event, FeeDetails>,
+	Block: traits::Block,
+	Event: Codec + Send + Sync + 'static,
+	FeeDetails: Codec + Send + Sync + 'static,
+{
+	fn dry_run_with_overrides(
+		&self,
+		extrinsic: Bytes,
+		overrides: Vec<(StorageKey, Option<StorageData>)>,
+		at: Option<<Block as traits::Block>::Hash>,
+	) -> FutureResult<Bytes> {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			return Box::new(rpc_future::err(err.into()));
+		}
+
+		let dry_run = || {
+			let api = self.client.runtime_api();
+			let at = BlockId::<Block>::hash(at.unwrap_or_else(||
+				// If the block hash is not supplied assume the best block.
+				self.client.info().best_hash
+			));
+
+			let uxt: <Block as traits::Block>::Extrinsic = Decode::decode(&mut &*extrinsic)
+				.map_err(|e| RpcError {
+					code: ErrorCode::ServerError(Error::DecodeError.into()),
+					message: "Unable to dry run extrinsic.".into(),
+					data: Some(format!("{:?}", e).into()),
+				})?;
+			let overrides = overrides.into_iter()
+				.map(|(key, value)| if ineffective_override(&key.0) {
+					Err(RpcError {
+						code: ErrorCode::InvalidParams,
+						message: "Storage override can not take effect.".into(),
+						data: Some(format!("{}", HexDisplay::from(&key.0)).into()),
+					})
+				} else {
+					Ok((key.0, value.map(|value| value.0)))
+				})
+				.collect::<Result<frame_system_rpc_runtime_api::StorageOverrides, _>>()?;
+
+			let has_api = api.has_api::<dyn DryRunApi<Block, Event, FeeDetails>>(&at)
+				.map_err(|e| RpcError {
+					code: ErrorCode::ServerError(Error::RuntimeError.into()),
+					message: "Unable to dry run extrinsic.".into(),
+					data: Some(format!("{:?}", e).into()),
+				})?;
+			if !has_api {
+				return Err(RpcError {
+					code: ErrorCode::ServerError(Error::RuntimeError.into()),
+					message: "The runtime does not support dry running with overrides.".into(),
+					data: None,
+				});
+			}
+
+			let effects = api.dry_run(&at, uxt, overrides)
+				.map_err(|e| RpcError {
+					code: ErrorCode::ServerError(Error::RuntimeError.into()),
+					message: "Unable to dry run extrinsic.".into(),
+					data: Some(format!("{:?}", e).into()),
+				})?;
+
+			Ok(Encode::encode(&effects).into())
+		};
+
+		Box::new(result(dry_run()))
+	}
+}
+
+/// Returns `true` if overriding `key` before dry running an extrinsic has no effect.
+///
+/// The runtime code and heap pages are only read when the runtime is instantiated and the
+/// changes trie configuration only when the block is finalized. Child storage can not be written
+/// through top level storage keys.
+fn ineffective_override(key: &[u8]) -> bool {
+	key == well_known_keys::CODE ||
+		key == well_known_keys::HEAP_PAGES ||
+		key == well_known_keys::CHANGES_TRIE_CONFIG ||
+		well_known_keys::starts_with_child_storage_key(key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use frame_system::EventRecord;
+	use jsonrpc_core::futures::Future;
+	use node_runtime::{BalancesCall, Call, CheckedExtrinsic, Event, VERSION};
+	use node_testing::{
+		client::{Client, TestClientBuilder, TestClientBuilderExt},
+		keyring::{alice, bob, sign, signed_extra},
+	};
+	use pallet_transaction_payment::FeeDetails;
+	use crate::DryRunEffects;
+
+	type TestDryRun = FullDryRun<Client, node_primitives::Block, Event, FeeDetails<u128>>;
+
+	fn transfer(tip: u128) -> node_runtime::UncheckedExtrinsic {
+		sign(
+			CheckedExtrinsic {
+				signed: Some((alice(), signed_extra(0, tip))),
+				function: Call::Balances(BalancesCall::transfer(bob().into(), 69)),
+			},
+			VERSION.spec_version,
+			VERSION.transaction_version,
+			// the genesis block hash as stored by `frame_system` at genesis
+			[69u8; 32],
+		)
+	}
+
+	#[test]
+	fn dry_run_with_overrides_should_deny_unsafe() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(TestClientBuilder::new().build());
+		let dry_run = TestDryRun::new(client, DenyUnsafe::Yes);
+
+		// when
+		let res = dry_run.dry_run_with_overrides(vec![].into(), vec![], None);
+
+		// then
+		assert_eq!(res.wait(), Err(RpcError::method_not_found()));
+	}
+
+	#[test]
+	fn dry_run_with_overrides_should_reject_ineffective_overrides() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(TestClientBuilder::new().build());
+		let dry_run = TestDryRun::new(client, DenyUnsafe::No);
+
+		// when
+		let res = dry_run.dry_run_with_overrides(
+			transfer(0).encode().into(),
+			vec![(StorageKey(well_known_keys::CODE.to_vec()), Some(StorageData(vec![1, 2, 3])))],
+			None,
+		);
+
+		// then
+		let err = res.wait().unwrap_err();
+		assert_eq!(err.code, ErrorCode::InvalidParams);
+	}
+
+	#[test]
+	fn dry_run_with_overrides_should_return_effects() {
+		sp_tracing::try_init_simple();
+
+		// given
+		let client = Arc::new(TestClientBuilder::new().build());
+		let dry_run = TestDryRun::new(client, DenyUnsafe::No);
+
+		let tip = 42;
+		// Events are not deposited at block number zero, so they only show up
+		// if the override of the block number is applied.
+		let block_number_key = [
+			sp_core::hashing::twox_128(b"System"),
+			sp_core::hashing::twox_128(b"Number"),
+		].concat();
+
+		// when
+		let res = dry_run.dry_run_with_overrides(
+			transfer(tip).encode().into(),
+			vec![(StorageKey(block_number_key), Some(StorageData(1u32.encode())))],
+			None,
+		);
+
+		// then
+		let bytes = res.wait().unwrap().0;
+		let effects: DryRunEffects<EventRecord<Event, node_primitives::Hash>, FeeDetails<u128>> =
+			Decode::decode(&mut bytes.as_slice()).unwrap();
+		assert_eq!(effects.result, Ok(Ok(())));
+		assert!(effects.events.iter().any(|record| record.event ==
+			Event::pallet_balances(pallet_balances::Event::Transfer(alice(), bob(), 69))
+		));
+		let info = effects.events.iter()
+			.find_map(|record| match record.event {
+				Event::frame_system(frame_system::Event::ExtrinsicSuccess(ref info)) =>
+					Some(info.clone()),
+				_ => None,
+			})
+			.unwrap();
+		assert!(effects.actual_weight > 0);
+		assert_eq!(effects.actual_weight, info.weight);
+		assert_eq!(effects.fee_details.tip, tip);
+		assert!(effects.fee_details.inclusion_fee.is_some());
+	}
+}
@@ -40,8 +40,13 @@ use sp_transaction_pool::{TransactionPool, InPoolTransaction};
 use sp_block_builder::BlockBuilder;
 use sc_rpc_api::DenyUnsafe;
 
-pub use frame_system_rpc_runtime_api::AccountNonceApi;
+pub use frame_system_rpc_runtime_api::{AccountNonceApi, DryRunApi, DryRunEffects};
 pub use self::gen_client::Client as SystemClient;
+pub use self::dry_run::{
+	FullDryRun, DryRunWithOverridesApi, gen_client::Client as DryRunWithOverridesClient,
+};
+
+mod dry_run;
 
 /// Future that resolves to account nonce.
 pub type FutureResult<T> = Box<dyn Future<Item = T, Error = RpcError> + Send>;